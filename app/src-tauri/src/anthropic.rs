@@ -0,0 +1,191 @@
+// Anthropic Messages API as a chat provider (`chat_provider: "anthropic"`). The chat loop keeps
+// its history in the Chat Completions format; each request converts it: system messages become
+// the top-level `system` prompt, assistant tool_calls become `tool_use` blocks and tool messages
+// become `tool_result` blocks in a user turn. MCP and built-in function tools map to Anthropic
+// tools with the same JSON Schema as `input_schema`, so tool names round-trip unchanged.
+
+use futures_util::future::BoxFuture;
+
+use crate::chat::{ChatProvider, Turn};
+use crate::error::AidcError;
+
+const MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+const API_VERSION: &str = "2023-06-01";
+// The Messages API requires an explicit output limit
+const MAX_OUTPUT_TOKENS: u32 = 8192;
+
+pub(crate) struct AnthropicChat {
+  pub key: String,
+}
+
+fn text_of(content: &serde_json::Value) -> String {
+  match content {
+    serde_json::Value::String(s) => s.clone(),
+    serde_json::Value::Array(parts) => parts.iter().filter_map(|p| p.get("text").and_then(|t| t.as_str())).collect::<Vec<_>>().join("\n"),
+    _ => String::new(),
+  }
+}
+
+// Chat Completions content (string or text/image_url parts) as Anthropic content blocks
+fn to_blocks(content: &serde_json::Value) -> Vec<serde_json::Value> {
+  match content {
+    serde_json::Value::String(s) if !s.is_empty() => vec![serde_json::json!({ "type": "text", "text": s })],
+    serde_json::Value::Array(parts) => parts
+      .iter()
+      .filter_map(|p| match p.get("type").and_then(|t| t.as_str()) {
+        Some("text") => p.get("text").and_then(|t| t.as_str()).filter(|t| !t.is_empty()).map(|t| serde_json::json!({ "type": "text", "text": t })),
+        Some("image_url") => {
+          // Images arrive as data URLs (chat::image_data_url)
+          let url = p.get("image_url").and_then(|u| u.get("url")).and_then(|u| u.as_str())?;
+          let (meta, data) = url.strip_prefix("data:")?.split_once(',')?;
+          let media_type = meta.strip_suffix(";base64")?;
+          Some(serde_json::json!({ "type": "image", "source": { "type": "base64", "media_type": media_type, "data": data } }))
+        }
+        _ => None,
+      })
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
+// Returns the system prompt and the alternating user/assistant messages
+fn to_messages(msgs: &[serde_json::Value]) -> (String, Vec<serde_json::Value>) {
+  let mut system: Vec<String> = Vec::new();
+  let mut out: Vec<(String, Vec<serde_json::Value>)> = Vec::new();
+  for m in msgs {
+    let role = m.get("role").and_then(|r| r.as_str()).unwrap_or("user");
+    let content = m.get("content").cloned().unwrap_or(serde_json::Value::Null);
+    let (role, blocks) = match role {
+      "system" => {
+        let t = text_of(&content);
+        if !t.trim().is_empty() { system.push(t); }
+        continue;
+      }
+      "assistant" => {
+        let mut blocks = to_blocks(&content);
+        for tc in m.get("tool_calls").and_then(|x| x.as_array()).into_iter().flatten() {
+          let f = tc.get("function");
+          let args = f.and_then(|f| f.get("arguments")).and_then(|a| a.as_str()).unwrap_or("{}");
+          let input = serde_json::from_str::<serde_json::Value>(args).ok().filter(|v| v.is_object()).unwrap_or_else(|| serde_json::json!({}));
+          blocks.push(serde_json::json!({
+            "type": "tool_use",
+            "id": tc.get("id").and_then(|x| x.as_str()).unwrap_or(""),
+            "name": f.and_then(|f| f.get("name")).and_then(|x| x.as_str()).unwrap_or(""),
+            "input": input,
+          }));
+        }
+        ("assistant", blocks)
+      }
+      "tool" => {
+        let id = m.get("tool_call_id").and_then(|x| x.as_str()).unwrap_or("");
+        ("user", vec![serde_json::json!({ "type": "tool_result", "tool_use_id": id, "content": text_of(&content) })])
+      }
+      _ => ("user", to_blocks(&content)),
+    };
+    if blocks.is_empty() {
+      continue;
+    }
+    // Consecutive turns of one role are merged (tool results for parallel calls share a user turn)
+    match out.last_mut() {
+      Some((r, b)) if r == role => b.extend(blocks),
+      _ => out.push((role.to_string(), blocks)),
+    }
+  }
+  // The conversation has to open with a user turn; history truncation can leave an assistant first
+  while out.first().is_some_and(|(r, _)| r == "assistant") {
+    out.remove(0);
+  }
+  let messages = out.into_iter().map(|(role, content)| serde_json::json!({ "role": role, "content": content })).collect();
+  (system.join("\n\n"), messages)
+}
+
+// Chat Completions function tools ({type, function: {...}}) as Anthropic tools
+fn to_tools(tools: &[serde_json::Value]) -> Vec<serde_json::Value> {
+  tools
+    .iter()
+    .filter_map(|t| {
+      let f = t.get("function")?;
+      let mut tool = serde_json::json!({
+        "name": f.get("name")?,
+        "input_schema": f.get("parameters").cloned().unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} })),
+      });
+      if let Some(d) = f.get("description").and_then(|d| d.as_str()).filter(|d| !d.is_empty()) {
+        tool["description"] = serde_json::json!(d);
+      }
+      Some(tool)
+    })
+    .collect()
+}
+
+// Response content blocks as text plus Chat Completions tool calls
+fn to_turn(v: &serde_json::Value) -> Turn {
+  let mut text = String::new();
+  let mut tool_calls = Vec::new();
+  for block in v.get("content").and_then(|c| c.as_array()).into_iter().flatten() {
+    match block.get("type").and_then(|t| t.as_str()) {
+      Some("text") => text.push_str(block.get("text").and_then(|t| t.as_str()).unwrap_or("")),
+      Some("tool_use") => tool_calls.push(serde_json::json!({
+        "id": block.get("id").and_then(|x| x.as_str()).unwrap_or(""),
+        "type": "function",
+        "function": {
+          "name": block.get("name").and_then(|x| x.as_str()).unwrap_or(""),
+          "arguments": block.get("input").map(|i| i.to_string()).unwrap_or_else(|| "{}".to_string()),
+        },
+      })),
+      _ => {}
+    }
+  }
+  Turn { text: Some(text).filter(|t| !t.is_empty()), tool_calls }
+}
+
+impl ChatProvider for AnthropicChat {
+  fn id(&self) -> &'static str {
+    "anthropic"
+  }
+
+  fn complete<'a>(
+    &'a self,
+    client: &'a reqwest::Client,
+    model: &'a str,
+    msgs: &'a [serde_json::Value],
+    tools: &'a [serde_json::Value],
+    temp: Option<f32>,
+  ) -> BoxFuture<'a, Result<Turn, AidcError>> {
+    Box::pin(async move {
+      let (system, messages) = to_messages(msgs);
+      if messages.is_empty() {
+        return Err(AidcError::InvalidInput("Nothing to send: the conversation has no user message".into()));
+      }
+      let mut body = serde_json::json!({ "model": model, "max_tokens": MAX_OUTPUT_TOKENS, "messages": messages });
+      if let serde_json::Value::Object(ref mut m) = body {
+        if !system.trim().is_empty() { m.insert("system".to_string(), serde_json::json!(system)); }
+        // Anthropic accepts 0..=1 (OpenAI allows up to 2)
+        if let Some(t) = temp { m.insert("temperature".to_string(), serde_json::json!(t.clamp(0.0, 1.0))); }
+        let tools = to_tools(tools);
+        if !tools.is_empty() {
+          m.insert("tools".to_string(), serde_json::Value::Array(tools));
+          m.insert("tool_choice".to_string(), serde_json::json!({ "type": "auto" }));
+        }
+      }
+
+      let resp = crate::rate_limit::send("anthropic", "chat", || {
+        client.post(MESSAGES_URL).header("x-api-key", &self.key).header("anthropic-version", API_VERSION).json(&body)
+      })
+      .await
+      .map_err(|e| AidcError::from_reqwest("anthropic", &e))?;
+
+      if !resp.status().is_success() {
+        let status = resp.status();
+        let body_text = resp.text().await.unwrap_or_default();
+        return Err(AidcError::from_status("anthropic", status, &body_text));
+      }
+
+      let v: serde_json::Value = resp.json().await.map_err(|e| AidcError::invalid_response("anthropic", format!("json error: {e}")))?;
+      crate::usage::record_response(crate::usage::CHAT, model, &v);
+      if v.get("stop_reason").and_then(|r| r.as_str()) == Some("max_tokens") {
+        tracing::warn!(model, "anthropic reply cut off at max_tokens");
+      }
+      Ok(to_turn(&v))
+    })
+  }
+}
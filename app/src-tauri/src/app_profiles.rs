@@ -0,0 +1,103 @@
+// Per-application capture/insertion strategies keyed by process name (settings: `app_profiles`).
+// Example:
+//   "app_profiles": {
+//     "WINWORD.EXE": { "capture_method": "uia", "paste_method": "shift_insert", "paste_delay_ms": 250 },
+//     "WindowsTerminal.exe": { "paste_method": "type", "typing_rate_cps": 120 },
+//     "KeePassXC.exe": { "safe_mode": true }
+//   }
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AppProfile {
+  /// "clipboard" (Ctrl+C copy-restore) or "uia" (UI Automation, falls back to clipboard)
+  pub capture_method: String,
+  /// "ctrl_v", "shift_insert" or "type"
+  pub paste_method: String,
+  /// Wait after refocusing the target window before sending keys
+  pub focus_delay_ms: u64,
+  /// Wait after Ctrl+C before reading the clipboard
+  pub copy_delay_ms: u64,
+  /// Wait after pasting before the previous clipboard is restored
+  pub paste_delay_ms: u64,
+  /// Typing rate for paste_method "type"; falls back to `insert_typing_rate_cps`
+  pub typing_rate_cps: Option<u32>,
+  /// Never send Ctrl+C to this app when capturing a selection (overrides `safe_mode_defaults`)
+  pub safe_mode: Option<bool>,
+}
+
+impl Default for AppProfile {
+  fn default() -> Self {
+    AppProfile {
+      capture_method: "clipboard".to_string(),
+      paste_method: "ctrl_v".to_string(),
+      focus_delay_ms: 80,
+      copy_delay_ms: 120,
+      paste_delay_ms: 120,
+      typing_rate_cps: None,
+      safe_mode: None,
+    }
+  }
+}
+
+impl AppProfile {
+  fn normalized(mut self) -> Self {
+    self.capture_method = match self.capture_method.trim().to_lowercase().as_str() {
+      "uia" => "uia".to_string(),
+      _ => "clipboard".to_string(),
+    };
+    self.paste_method = match self.paste_method.trim().to_lowercase().as_str() {
+      "shift_insert" => "shift_insert".to_string(),
+      "type" => "type".to_string(),
+      _ => "ctrl_v".to_string(),
+    };
+    self.focus_delay_ms = self.focus_delay_ms.min(5000);
+    self.copy_delay_ms = self.copy_delay_ms.min(5000);
+    self.paste_delay_ms = self.paste_delay_ms.min(5000);
+    self
+  }
+}
+
+/// Profile explicitly configured for a process name (case-insensitive), if any.
+pub fn configured_profile(process_name: &str) -> Option<AppProfile> {
+  let name = process_name.trim().to_lowercase();
+  if name.is_empty() { return None; }
+  let v = crate::config::load_settings_json();
+  let obj = v.get("app_profiles").and_then(|x| x.as_object())?;
+  obj
+    .iter()
+    .find(|(k, _)| k.trim().to_lowercase() == name)
+    .and_then(|(_, p)| serde_json::from_value::<AppProfile>(p.clone()).ok())
+    .map(AppProfile::normalized)
+}
+
+/// Effective profile for a process name: the configured one or the defaults.
+pub fn profile_for_process(process_name: &str) -> AppProfile {
+  configured_profile(process_name).unwrap_or_default()
+}
+
+/// Effective profile for the app that currently has focus.
+pub fn profile_for_foreground() -> AppProfile {
+  profile_for_process(&crate::quick_actions::foreground_process_name())
+}
+
+/// Effective profile for the window stored by `prepare_quick_actions`.
+pub fn profile_for_last_foreground() -> AppProfile {
+  let name = crate::quick_actions::last_foreground_handle_raw()
+    .map(crate::quick_actions::process_name_for_window)
+    .unwrap_or_default();
+  profile_for_process(&name)
+}
+
+/// Return the foreground process name and its effective profile (for the settings UI).
+#[tauri::command]
+pub fn get_active_app_profile() -> Result<serde_json::Value, String> {
+  let process = crate::quick_actions::foreground_process_name();
+  let configured = configured_profile(&process);
+  Ok(serde_json::json!({
+    "process": process,
+    "configured": configured.is_some(),
+    "profile": configured.unwrap_or_default(),
+  }))
+}
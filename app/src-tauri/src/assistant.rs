@@ -0,0 +1,362 @@
+// Hands-free voice assistant: wake word → microphone recording → STT → chat (with MCP tools) →
+// spoken reply, looping until stopped. Runs entirely in the backend on the default microphone
+// and speakers, so it works with the window hidden in the tray.
+//
+// Utterances are cut from the mic stream with an energy VAD. The wake word is spotted by
+// transcribing each utterance with the configured STT engine (the local engine keeps ambient
+// speech on the device); "<wake word> <request>" is handled in one go, a bare wake word opens a
+// short window for the request. Without a wake word every utterance is a request.
+//
+// Settings: `voice_assistant` { wake_word, silence_ms, system_prompt, voice_profile }, plus the usual chat,
+// STT and TTS preferences. An active persona (personas.rs) adds its system prompt, model, voice
+// profile and tool profile.
+//
+// Started with a conversation id, the loop continues that text conversation: its system prompt
+// and recent turns seed the chat history and transcripts carry the id for the window to append.
+//
+// Events:
+//   assistant:state      { session_id, state: "waiting"|"listening"|"thinking"|"speaking"|"stopped"|"error", message? }
+//   assistant:transcript { session_id, conversation_id?, role: "user"|"assistant", text }
+//   assistant:error      { session_id, message }   (turn failed, session keeps listening)
+
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::audio_io::{self, MicChunk};
+use crate::chat::{ChatContent, ChatMessage};
+use crate::error::AidcError;
+
+// 16 kHz mono is what both the cloud and local STT engines work with
+pub(crate) const SAMPLE_RATE: u32 = 16_000;
+const SPEECH_RMS: f32 = 0.015;
+// Speech must last this long to open an utterance (filters clicks and keyboard noise)
+const START_SAMPLES: usize = 1_920; // 120 ms
+const PRE_ROLL_SAMPLES: usize = 4_800; // 300 ms
+const MAX_UTTERANCE_SAMPLES: usize = 20 * SAMPLE_RATE as usize;
+const DEFAULT_SILENCE_MS: u64 = 900;
+// After a bare wake word, how long to wait for the request
+const COMMAND_WINDOW: Duration = Duration::from_secs(8);
+const HISTORY_TURNS: usize = 10;
+const DEFAULT_SYSTEM_PROMPT: &str = "You are a desktop voice assistant. Your replies are spoken aloud: answer briefly in plain sentences, without markdown, lists or code unless asked. Reply in the language the user speaks.";
+
+struct ActiveSession {
+  id: String,
+  cancel: CancellationToken,
+}
+
+static SESSION: Lazy<Mutex<Option<ActiveSession>>> = Lazy::new(|| Mutex::new(None));
+
+struct Options {
+  wake_word: Option<String>,
+  silence_ms: u64,
+  system_prompt: String,
+  conversation_id: Option<String>,
+  history: Vec<(String, String)>,
+}
+
+impl Options {
+  fn resolve(wake_word: Option<String>) -> Self {
+    let va = crate::config::get_voice_assistant_from_settings();
+    let s = |k: &str| va.get(k).and_then(|x| x.as_str()).map(|x| x.trim().to_string());
+    // An explicit empty wake word disables wake word detection
+    let wake_word = wake_word.map(|w| w.trim().to_string()).or_else(|| s("wake_word")).filter(|w| !w.is_empty());
+    Self {
+      wake_word,
+      silence_ms: va.get("silence_ms").and_then(|x| x.as_u64()).unwrap_or(DEFAULT_SILENCE_MS).clamp(300, 5_000),
+      system_prompt: Self::system_prompt(s("system_prompt").filter(|p| !p.is_empty())),
+      conversation_id: None,
+      history: Vec::new(),
+    }
+  }
+
+  // The active persona's prompt first, the spoken-reply rules after it
+  fn system_prompt(configured: Option<String>) -> String {
+    let rules = configured.unwrap_or_else(|| DEFAULT_SYSTEM_PROMPT.to_string());
+    match crate::personas::active_system_prompt() {
+      Some(persona) => format!("{persona}\n\n{rules}"),
+      None => rules,
+    }
+  }
+
+  // Continue a stored conversation: its system prompt first, the spoken-reply rules after it
+  fn attach(&mut self, conversation_id: String) -> Result<(), String> {
+    let ctx = crate::config::load_conversation_context(&conversation_id, HISTORY_TURNS * 2)?;
+    if let Some(sp) = ctx.system_prompt {
+      self.system_prompt = format!("{sp}\n\n{}", self.system_prompt);
+    }
+    self.history = ctx.history;
+    self.conversation_id = Some(conversation_id);
+    Ok(())
+  }
+}
+
+// Energy-based utterance segmentation over the mic stream (also used by live translation)
+pub(crate) struct Segmenter {
+  pre: VecDeque<i16>,
+  buf: Vec<i16>,
+  active: bool,
+  voiced: usize,
+  silence: usize,
+  end_silence: usize,
+  max_samples: usize,
+}
+
+impl Segmenter {
+  pub(crate) fn new(silence_ms: u64) -> Self {
+    Self {
+      pre: VecDeque::with_capacity(PRE_ROLL_SAMPLES * 2),
+      buf: Vec::new(),
+      active: false,
+      voiced: 0,
+      silence: 0,
+      end_silence: (silence_ms * SAMPLE_RATE as u64 / 1000) as usize,
+      max_samples: MAX_UTTERANCE_SAMPLES,
+    }
+  }
+
+  // Cut long utterances earlier (lower latency for continuous speech)
+  pub(crate) fn with_max_ms(mut self, ms: u64) -> Self {
+    self.max_samples = ((ms * SAMPLE_RATE as u64 / 1000) as usize).clamp(START_SAMPLES, MAX_UTTERANCE_SAMPLES);
+    self
+  }
+
+  pub(crate) fn in_utterance(&self) -> bool {
+    self.active
+  }
+
+  pub(crate) fn feed(&mut self, pcm: &[i16], level: f32) -> Option<Vec<i16>> {
+    let loud = level > SPEECH_RMS;
+    if !self.active {
+      self.pre.extend(pcm);
+      let excess = self.pre.len().saturating_sub(PRE_ROLL_SAMPLES);
+      self.pre.drain(..excess);
+      self.voiced = if loud { self.voiced + pcm.len() } else { 0 };
+      if self.voiced >= START_SAMPLES {
+        self.active = true;
+        self.silence = 0;
+        self.buf = self.pre.drain(..).collect();
+      }
+      return None;
+    }
+    self.buf.extend_from_slice(pcm);
+    self.silence = if loud { 0 } else { self.silence + pcm.len() };
+    if self.silence >= self.end_silence || self.buf.len() >= self.max_samples {
+      self.reset();
+      return Some(std::mem::take(&mut self.buf));
+    }
+    None
+  }
+
+  pub(crate) fn reset(&mut self) {
+    self.active = false;
+    self.voiced = 0;
+    self.silence = 0;
+    self.pre.clear();
+  }
+}
+
+pub(crate) fn wav_bytes(pcm: &[i16]) -> Result<Vec<u8>, String> {
+  let spec = hound::WavSpec { channels: 1, sample_rate: SAMPLE_RATE, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+  let mut cur = Cursor::new(Vec::with_capacity(pcm.len() * 2 + 44));
+  {
+    let mut w = hound::WavWriter::new(&mut cur, spec).map_err(|e| format!("wav encode failed: {e}"))?;
+    for s in pcm { w.write_sample(*s).map_err(|e| format!("wav encode failed: {e}"))?; }
+    w.finalize().map_err(|e| format!("wav encode failed: {e}"))?;
+  }
+  Ok(cur.into_inner())
+}
+
+fn norm_word(w: &str) -> String {
+  w.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+// Text following the wake word, or None when the utterance does not contain it
+fn after_wake_word(text: &str, wake: &str) -> Option<String> {
+  let wake: Vec<String> = wake.split_whitespace().map(norm_word).filter(|w| !w.is_empty()).collect();
+  let tokens: Vec<&str> = text.split_whitespace().filter(|t| !norm_word(t).is_empty()).collect();
+  let norm: Vec<String> = tokens.iter().map(|t| norm_word(t)).collect();
+  if wake.is_empty() || norm.len() < wake.len() { return None; }
+  let pos = norm.windows(wake.len()).position(|w| w == wake.as_slice())?;
+  Some(tokens[pos + wake.len()..].join(" ").trim_start_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace()).to_string())
+}
+
+fn emit_state(app: &AppHandle, id: &str, state: &str, message: Option<&str>) {
+  let _ = app.emit("assistant:state", json!({ "session_id": id, "state": state, "message": message }));
+}
+
+pub(crate) async fn transcribe(pcm: &[i16], post_process: bool) -> Result<String, AidcError> {
+  let wav = wav_bytes(pcm).map_err(AidcError::Internal)?;
+  let res = crate::stt_transcribe_inner(wav, "audio/wav".into(), Some(post_process), None).await?;
+  Ok(res.final_text)
+}
+
+async fn reply(app: &AppHandle, system_prompt: &str, history: &[(String, String)], request: &str) -> Result<String, AidcError> {
+  let (provider, model) = crate::chat::provider_from_settings()?;
+  let mut messages = vec![ChatMessage { role: "system".into(), content: ChatContent::Text(system_prompt.to_string()) }];
+  for (role, text) in history {
+    messages.push(ChatMessage { role: role.clone(), content: ChatContent::Text(text.clone()) });
+  }
+  messages.push(ChatMessage { role: "user".into(), content: ChatContent::Text(request.to_string()) });
+  let temp = crate::settings::get_temperature_from_settings_or_env();
+  crate::chat::chat_complete_with_mcp(app.clone(), messages, provider.as_ref(), model, temp, &crate::MCP_CLIENTS).await
+}
+
+// Synthesize with the active persona's or the assistant's voice profile (or the TTS settings) and
+// play it on the default output device
+pub(crate) async fn speak(text: &str, cancel: &CancellationToken) -> Result<(), String> {
+  let va = crate::config::get_voice_assistant_from_settings();
+  let profile = crate::personas::active_voice_profile().or_else(|| va.get("voice_profile").and_then(|x| x.as_str()).map(|x| x.to_string()));
+  let params = crate::voices::speech_params(profile.as_deref());
+  let path = crate::voices::synthesize_wav(text.to_string(), params).await?;
+  let res = audio_io::play_wav(Path::new(&path), cancel).await;
+  let _ = std::fs::remove_file(&path);
+  res
+}
+
+async fn run_loop(app: &AppHandle, id: &str, mut opts: Options, mut mic_rx: mpsc::UnboundedReceiver<MicChunk>, cancel: CancellationToken) -> Result<(), String> {
+  let idle_state = if opts.wake_word.is_some() { "waiting" } else { "listening" };
+  let mut seg = Segmenter::new(opts.silence_ms);
+  let mut history = std::mem::take(&mut opts.history);
+  // Set after a bare wake word: the next utterance is the request
+  let mut awake_until: Option<Instant> = None;
+  emit_state(app, id, idle_state, None);
+  loop {
+    let chunk = tokio::select! {
+      _ = cancel.cancelled() => return Ok(()),
+      c = mic_rx.recv() => c,
+    };
+    let Some((pcm, level)) = chunk else { return Err("Microphone stream ended".into()) };
+    if awake_until.map(|t| Instant::now() > t).unwrap_or(false) && !seg.in_utterance() {
+      awake_until = None;
+      emit_state(app, id, idle_state, None);
+    }
+    let Some(utterance) = seg.feed(&pcm, level) else { continue };
+
+    let awake = opts.wake_word.is_none() || awake_until.is_some();
+    if awake { emit_state(app, id, "thinking", None); }
+    // Wake word spotting skips post-processing; the request itself follows the STT settings
+    let text = match transcribe(&utterance, awake).await {
+      Ok(t) => t.trim().to_string(),
+      Err(e) => {
+        tracing::warn!(session = %id, error = %e.message(), "assistant transcription failed");
+        if awake { let _ = app.emit("assistant:error", json!({ "session_id": id, "message": e.message() })); }
+        awake_until = None;
+        emit_state(app, id, idle_state, None);
+        continue;
+      }
+    };
+    let request = match (&opts.wake_word, awake_until.take()) {
+      (Some(w), None) => match after_wake_word(&text, w) {
+        None => continue,
+        Some(rest) if rest.is_empty() => {
+          awake_until = Some(Instant::now() + COMMAND_WINDOW);
+          emit_state(app, id, "listening", None);
+          continue;
+        }
+        Some(rest) => rest,
+      },
+      _ => text,
+    };
+    if request.is_empty() {
+      emit_state(app, id, idle_state, None);
+      continue;
+    }
+
+    let _ = app.emit("assistant:transcript", json!({ "session_id": id, "conversation_id": opts.conversation_id, "role": "user", "text": request }));
+    emit_state(app, id, "thinking", None);
+    let answer = tokio::select! {
+      _ = cancel.cancelled() => return Ok(()),
+      r = reply(app, &opts.system_prompt, &history, &request) => r,
+    };
+    match answer {
+      Ok(answer) => {
+        let _ = app.emit("assistant:transcript", json!({ "session_id": id, "conversation_id": opts.conversation_id, "role": "assistant", "text": answer }));
+        history.push(("user".into(), request));
+        history.push(("assistant".into(), answer.clone()));
+        let excess = history.len().saturating_sub(HISTORY_TURNS * 2);
+        history.drain(..excess);
+        emit_state(app, id, "speaking", None);
+        if let Err(e) = speak(&answer, &cancel).await {
+          tracing::warn!(session = %id, error = %e, "assistant speech failed");
+          let _ = app.emit("assistant:error", json!({ "session_id": id, "message": e }));
+        }
+      }
+      Err(e) => {
+        tracing::warn!(session = %id, error = %e.message(), "assistant chat failed");
+        let _ = app.emit("assistant:error", json!({ "session_id": id, "message": e.message() }));
+      }
+    }
+    if cancel.is_cancelled() { return Ok(()); }
+    // Drop what the mic heard while thinking/speaking (mostly our own voice)
+    while mic_rx.try_recv().is_ok() {}
+    seg.reset();
+    emit_state(app, id, idle_state, None);
+  }
+}
+
+fn take_session() -> Option<ActiveSession> {
+  SESSION.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Start the hands-free assistant loop. `wake_word` overrides `voice_assistant.wake_word`
+/// (empty string = no wake word); `conversation_id` continues that stored conversation.
+/// Returns the session id used in `assistant:*` events.
+#[tauri::command]
+pub async fn assistant_start(app: AppHandle, wake_word: Option<String>, conversation_id: Option<String>) -> Result<String, AidcError> {
+  if let Some(prev) = take_session() { prev.cancel.cancel(); }
+  // Fail fast on a missing key instead of after the first utterance
+  crate::settings::require_openai_key()?;
+  let mut opts = Options::resolve(wake_word);
+  if let Some(cid) = conversation_id.filter(|c| !c.trim().is_empty()) {
+    opts.attach(cid).map_err(AidcError::InvalidInput)?;
+  }
+  let (mic, mic_rx) = tokio::task::spawn_blocking(|| audio_io::start_mic(SAMPLE_RATE))
+    .await
+    .map_err(|e| AidcError::Internal(format!("audio task failed: {e}")))?
+    .map_err(AidcError::Internal)?;
+
+  let id = uuid::Uuid::new_v4().to_string();
+  let cancel = CancellationToken::new();
+  *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = Some(ActiveSession { id: id.clone(), cancel: cancel.clone() });
+  tracing::info!(session = %id, wake_word = opts.wake_word.as_deref().unwrap_or(""), "voice assistant started");
+
+  let sid = id.clone();
+  tauri::async_runtime::spawn(async move {
+    let res = run_loop(&app, &sid, opts, mic_rx, cancel).await;
+    drop(mic);
+    {
+      let mut slot = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+      if slot.as_ref().map(|s| s.id == sid).unwrap_or(false) { *slot = None; }
+    }
+    match res {
+      Ok(()) => emit_state(&app, &sid, "stopped", None),
+      Err(e) => {
+        tracing::warn!(session = %sid, error = %e, "voice assistant ended with error");
+        emit_state(&app, &sid, "error", Some(&e));
+      }
+    }
+  });
+  Ok(id)
+}
+
+/// Stop the voice assistant. Returns false when it was not running.
+#[tauri::command]
+pub fn assistant_stop() -> Result<bool, String> {
+  match take_session() {
+    Some(s) => {
+      s.cancel.cancel();
+      tracing::info!(session = %s.id, "voice assistant stopped");
+      Ok(true)
+    }
+    None => Ok(false),
+  }
+}
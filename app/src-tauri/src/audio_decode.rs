@@ -0,0 +1,125 @@
+// Audio decoding shared by STT input and TTS post-processing. Symphonia demuxes every container the
+// webview and the TTS providers produce (WAV, MP3, Ogg, WebM/Matroska; MP4/AAC/FLAC with
+// local-stt). Symphonia has no Opus decoder, so Opus tracks (MediaRecorder's WebM/Opus, OpenAI's
+// Ogg/Opus) are decoded with libopus, which also comes with local-stt.
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CodecParameters, DecoderOptions, CODEC_TYPE_OPUS};
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+// libopus always decodes at 48 kHz internally
+#[cfg(feature = "local-stt")]
+const OPUS_RATE: u32 = 48_000;
+// Longest Opus packet: 120 ms at 48 kHz
+#[cfg(feature = "local-stt")]
+const OPUS_MAX_FRAME: usize = 5760;
+
+/// Interleaved f32 samples.
+pub struct Pcm {
+  pub sample_rate: u32,
+  pub channels: usize,
+  pub samples: Vec<f32>,
+}
+
+// Container hint from a MIME type such as "audio/webm;codecs=opus"
+fn hint_for(mime: &str) -> Hint {
+  let mut hint = Hint::new();
+  let base = mime.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+  if base.is_empty() {
+    return hint;
+  }
+  hint.mime_type(&base);
+  let ext = match base.as_str() {
+    "audio/webm" | "video/webm" => Some("webm"),
+    "audio/ogg" | "audio/opus" => Some("ogg"),
+    "audio/mpeg" | "audio/mp3" => Some("mp3"),
+    "audio/wav" | "audio/x-wav" | "audio/wave" => Some("wav"),
+    _ => None,
+  };
+  if let Some(ext) = ext {
+    hint.with_extension(ext);
+  }
+  hint
+}
+
+/// Decode a complete clip. `mime` may be empty; the container is then detected from the bytes.
+pub fn decode(bytes: &[u8], mime: &str) -> Result<Pcm, String> {
+  let mss = MediaSourceStream::new(Box::new(std::io::Cursor::new(bytes.to_vec())), Default::default());
+  let probed = symphonia::default::get_probe()
+    .format(&hint_for(mime), mss, &FormatOptions::default(), &MetadataOptions::default())
+    .map_err(|e| format!("audio probe failed: {e}"))?;
+  let mut format = probed.format;
+  let track = format.default_track().ok_or_else(|| "no default track".to_string())?;
+  let track_id = track.id;
+  let codec_params = track.codec_params.clone();
+
+  let pcm = if codec_params.codec == CODEC_TYPE_OPUS {
+    decode_opus(&mut format, track_id, &codec_params)?
+  } else {
+    decode_symphonia(&mut format, track_id, &codec_params)?
+  };
+  if pcm.samples.is_empty() {
+    return Err("decode produced no samples".into());
+  }
+  Ok(pcm)
+}
+
+fn decode_symphonia(format: &mut Box<dyn FormatReader>, track_id: u32, codec_params: &CodecParameters) -> Result<Pcm, String> {
+  let mut decoder = symphonia::default::get_codecs()
+    .make(codec_params, &DecoderOptions::default())
+    .map_err(|e| format!("decoder init failed: {e}"))?;
+
+  let mut sample_rate: u32 = codec_params.sample_rate.unwrap_or(44100);
+  let mut channels: usize = codec_params.channels.map(|c| c.count()).unwrap_or(1);
+  let mut samples: Vec<f32> = Vec::new();
+
+  loop {
+    let packet = match format.next_packet() { Ok(p) => p, Err(_) => break };
+    if packet.track_id() != track_id { continue; }
+    // A damaged packet should not lose the rest of the clip
+    let Ok(buf) = decoder.decode(&packet) else { continue };
+    let spec = *buf.spec();
+    sample_rate = spec.rate;
+    channels = spec.channels.count();
+    let mut sbuf = SampleBuffer::<f32>::new(buf.capacity() as u64, spec);
+    sbuf.copy_interleaved_ref(buf);
+    samples.extend_from_slice(sbuf.samples());
+  }
+  Ok(Pcm { sample_rate, channels: channels.max(1), samples })
+}
+
+#[cfg(feature = "local-stt")]
+fn decode_opus(format: &mut Box<dyn FormatReader>, track_id: u32, codec_params: &CodecParameters) -> Result<Pcm, String> {
+  let channels = codec_params.channels.map(|c| c.count()).unwrap_or(1).clamp(1, 2);
+  // OpusHead (extra data): "OpusHead", version, channels, pre-skip (u16 LE) ...
+  let pre_skip = codec_params
+    .extra_data
+    .as_deref()
+    .filter(|h| h.len() >= 12 && h.starts_with(b"OpusHead"))
+    .map(|h| u16::from_le_bytes([h[10], h[11]]) as usize)
+    .unwrap_or(0);
+  let layout = if channels == 2 { opus::Channels::Stereo } else { opus::Channels::Mono };
+  let mut decoder = opus::Decoder::new(OPUS_RATE, layout).map_err(|e| format!("opus decoder init failed: {e}"))?;
+
+  let mut frame = vec![0f32; OPUS_MAX_FRAME * channels];
+  let mut samples: Vec<f32> = Vec::new();
+  loop {
+    let packet = match format.next_packet() { Ok(p) => p, Err(_) => break };
+    if packet.track_id() != track_id { continue; }
+    match decoder.decode_float(&packet.data, &mut frame, false) {
+      Ok(n) => samples.extend_from_slice(&frame[..n * channels]),
+      Err(e) => tracing::debug!(error = %e, "opus packet skipped"),
+    }
+  }
+  // Encoder delay at the start of the stream
+  samples.drain(..(pre_skip * channels).min(samples.len()));
+  Ok(Pcm { sample_rate: OPUS_RATE, channels, samples })
+}
+
+#[cfg(not(feature = "local-stt"))]
+fn decode_opus(_format: &mut Box<dyn FormatReader>, _track_id: u32, _codec_params: &CodecParameters) -> Result<Pcm, String> {
+  Err("Opus audio is not available: app built without 'local-stt' feature.".into())
+}
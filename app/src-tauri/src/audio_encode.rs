@@ -0,0 +1,70 @@
+// Compressed audio output for files that get saved or shared: MP3 (LAME) and Ogg Vorbis. Speech
+// is encoded as mono; local SAPI WAVs are ~10x larger than the same clip as 64 kbps MP3. The
+// encoders are native libraries and come with the `local-tts` feature.
+
+use std::num::{NonZeroU32, NonZeroU8};
+use std::path::Path;
+
+pub(crate) fn to_i16(samples: &[f32]) -> Vec<i16> {
+  samples.iter().map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect()
+}
+
+#[cfg(feature = "local-tts")]
+pub(crate) fn encode_mp3(samples: &[f32], rate: u32) -> Result<Vec<u8>, String> {
+  use mp3lame_encoder::{Bitrate, Builder, FlushNoGap, MonoPcm, Quality};
+  let mut builder = Builder::new().ok_or_else(|| "MP3 encoder init failed".to_string())?;
+  builder.set_num_channels(1).map_err(|e| format!("MP3 encoder setup failed: {e:?}"))?;
+  builder.set_sample_rate(rate).map_err(|e| format!("MP3 encoder setup failed: {e:?}"))?;
+  builder.set_brate(Bitrate::Kbps64).map_err(|e| format!("MP3 encoder setup failed: {e:?}"))?;
+  builder.set_quality(Quality::Good).map_err(|e| format!("MP3 encoder setup failed: {e:?}"))?;
+  let mut encoder = builder.build().map_err(|e| format!("MP3 encoder init failed: {e:?}"))?;
+  let pcm = to_i16(samples);
+  let mut out = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(pcm.len()));
+  encoder.encode_to_vec(MonoPcm(&pcm), &mut out).map_err(|e| format!("MP3 encode failed: {e:?}"))?;
+  encoder.flush_to_vec::<FlushNoGap>(&mut out).map_err(|e| format!("MP3 encode failed: {e:?}"))?;
+  Ok(out)
+}
+
+#[cfg(not(feature = "local-tts"))]
+pub(crate) fn encode_mp3(_samples: &[f32], _rate: u32) -> Result<Vec<u8>, String> {
+  Err("MP3 output is not available: app built without 'local-tts' feature.".into())
+}
+
+#[cfg(feature = "local-tts")]
+pub(crate) fn encode_ogg(samples: &[f32], rate: u32) -> Result<Vec<u8>, String> {
+  let rate = NonZeroU32::new(rate).ok_or_else(|| "invalid sample rate".to_string())?;
+  let mut builder = vorbis_rs::VorbisEncoderBuilder::new(rate, NonZeroU8::MIN, Vec::new()).map_err(|e| format!("Ogg encoder init failed: {e}"))?;
+  let mut encoder = builder.build().map_err(|e| format!("Ogg encoder init failed: {e}"))?;
+  // Blocks of one second keep the encoder's buffers small
+  for block in samples.chunks(rate.get() as usize) {
+    encoder.encode_audio_block([block]).map_err(|e| format!("Ogg encode failed: {e}"))?;
+  }
+  encoder.finish().map_err(|e| format!("Ogg encode failed: {e}"))
+}
+
+#[cfg(not(feature = "local-tts"))]
+pub(crate) fn encode_ogg(_samples: &[f32], _rate: u32) -> Result<Vec<u8>, String> {
+  Err("Ogg output is not available: app built without 'local-tts' feature.".into())
+}
+
+/// Re-encode a WAV file as `format` ("mp3" or "ogg") next to it and delete the WAV; "wav" keeps
+/// the file as is. Returns the path of the result.
+pub fn convert_wav_file(wav_path: &str, format: &str) -> Result<String, String> {
+  let format = format.trim().to_lowercase();
+  if format.is_empty() || format == "wav" {
+    return Ok(wav_path.to_string());
+  }
+  let bytes = std::fs::read(wav_path).map_err(|e| format!("read wav failed: {e}"))?;
+  let (rate, channels, pcm) = crate::tts_utils::decode_to_pcm(&bytes)?;
+  let channels = channels.max(1) as usize;
+  let mono: Vec<f32> = pcm.chunks(channels).map(|f| f.iter().sum::<f32>() / f.len() as f32).collect();
+  let (encoded, ext) = match format.as_str() {
+    "mp3" => (encode_mp3(&mono, rate)?, "mp3"),
+    "ogg" | "vorbis" => (encode_ogg(&mono, rate)?, "ogg"),
+    other => return Err(format!("Unsupported output format '{other}' (expected wav, mp3 or ogg)")),
+  };
+  let target = Path::new(wav_path).with_extension(ext);
+  std::fs::write(&target, encoded).map_err(|e| format!("write {ext} failed: {e}"))?;
+  let _ = std::fs::remove_file(wav_path);
+  Ok(target.to_string_lossy().to_string())
+}
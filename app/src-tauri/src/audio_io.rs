@@ -0,0 +1,517 @@
+// Native audio I/O shared by the realtime and voice assistant sessions: microphone capture
+// (`stt_input_device`, else the default) as mono PCM16 at a fixed rate, streaming linear
+// resampling and WAV playback on the default output device. cpal streams are not Send, so they are
+// created on and owned by a dedicated thread that lives until the returned StreamsHandle is dropped.
+//
+// WAV playback can be sped up, slowed down and made louder or quieter while it runs
+// (`adjust_playback`, the tts_rate_* / tts_volume_* commands): samples are fed to the device a
+// little ahead of time, so a change is heard within ~200 ms without synthesizing again. Pause and
+// seek go through a `PlaybackControl` (used by tts_playback.rs). With `tts_muted` set (tray menu)
+// playback runs silently, and speech that does not play through here is refused (`is_muted`).
+//
+// The meeting recorder also captures what the speakers play (`open_loopback`, WASAPI loopback).
+//
+// Recording paths with a UI (dictation) report the input level through `LevelMeter`, which emits
+// `audio:level` (RMS, peak, clipping) about 20 times a second. The level monitor does the same for
+// a microphone that is not recording, so settings can show whether the chosen device picks up sound.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::sync::mpsc as std_mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Mono PCM16 block from the microphone with its RMS level (0..1)
+pub type MicChunk = (Vec<i16>, f32);
+
+// Live playback adjustments: rate in the -10..10 steps used for synthesis (2^(step/10) speed, pitch
+// follows), volume as a percentage of the clip's own level. Reset when playback starts from silence.
+const LIVE_RATE_MAX: i32 = 10;
+const LIVE_VOLUME_MAX: i32 = 200;
+// How far playback is fed ahead of the device, and the source block fed at a time
+const FEED_AHEAD_MS: usize = 200;
+const FEED_BLOCK_MS: usize = 50;
+
+const LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+// Peak at or above this counts as clipping
+const CLIP_LEVEL: f32 = 0.99;
+
+static LIVE_RATE: AtomicI32 = AtomicI32::new(0);
+static LIVE_VOLUME: AtomicI32 = AtomicI32::new(100);
+static ACTIVE_PLAYBACKS: AtomicUsize = AtomicUsize::new(0);
+static MUTED: AtomicBool = AtomicBool::new(false);
+// The level monitor's stop token; the capture thread ends with its task
+static MONITOR: Lazy<Mutex<Option<CancellationToken>>> = Lazy::new(|| Mutex::new(None));
+// Capture rate of the level monitor; only the level is used
+const MONITOR_RATE: u32 = 16_000;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PlaybackAdjust {
+  /// Whether a WAV is playing right now; adjustments made while idle are dropped on the next start
+  pub playing: bool,
+  /// -10..10 relative to the synthesized rate
+  pub rate: i32,
+  /// 0..200 percent of the synthesized volume
+  pub volume: i32,
+}
+
+pub fn playback_adjust() -> PlaybackAdjust {
+  PlaybackAdjust {
+    playing: ACTIVE_PLAYBACKS.load(Ordering::SeqCst) > 0,
+    rate: LIVE_RATE.load(Ordering::SeqCst),
+    volume: LIVE_VOLUME.load(Ordering::SeqCst),
+  }
+}
+
+/// Change the rate and volume of the running playback by the given steps.
+pub fn adjust_playback(rate_delta: i32, volume_delta: i32) -> PlaybackAdjust {
+  let _ = LIVE_RATE.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |r| Some((r + rate_delta).clamp(-LIVE_RATE_MAX, LIVE_RATE_MAX)));
+  let _ = LIVE_VOLUME.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| Some((v + volume_delta).clamp(0, LIVE_VOLUME_MAX)));
+  playback_adjust()
+}
+
+pub const MUTED_ERROR: &str = "Speech is muted (tray menu: Mute Speech)";
+
+/// Whether speech is muted. System voices and webview streaming bypass this module, so their
+/// entry points check it, as does the read-aloud queue to skip synthesis.
+pub fn is_muted() -> bool {
+  MUTED.load(Ordering::SeqCst)
+}
+
+/// Mute or unmute playback (running clips included) from the `tts_muted` setting.
+pub fn apply_mute_from_settings() {
+  let muted = crate::config::load_settings_json().get("tts_muted").and_then(|x| x.as_bool()).unwrap_or(false);
+  MUTED.store(muted, Ordering::SeqCst);
+}
+
+// Counts running playbacks; the first one resets the live adjustments
+struct LivePlayback;
+
+impl LivePlayback {
+  fn start() -> Self {
+    if ACTIVE_PLAYBACKS.fetch_add(1, Ordering::SeqCst) == 0 {
+      LIVE_RATE.store(0, Ordering::SeqCst);
+      LIVE_VOLUME.store(100, Ordering::SeqCst);
+    }
+    LivePlayback
+  }
+}
+
+impl Drop for LivePlayback {
+  fn drop(&mut self) {
+    ACTIVE_PLAYBACKS.fetch_sub(1, Ordering::SeqCst);
+  }
+}
+
+/// Streaming linear resampler; keeps the last input sample and fractional position between blocks
+pub struct Resampler {
+  step: f64,
+  pos: f64,
+  prev: f32,
+}
+
+impl Resampler {
+  pub fn new(from: u32, to: u32) -> Self {
+    Self { step: from.max(1) as f64 / to.max(1) as f64, pos: 0.0, prev: 0.0 }
+  }
+
+  /// Change the conversion ratio without losing the position between blocks.
+  pub fn set_rates(&mut self, from: u32, to: u32) {
+    self.step = from.max(1) as f64 / to.max(1) as f64;
+  }
+
+  pub fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+    // Positions index [prev, input[0], input[1], ...]
+    let n = input.len();
+    while self.pos < n as f64 {
+      let i = self.pos as usize;
+      let frac = (self.pos - i as f64) as f32;
+      let a = if i == 0 { self.prev } else { input[i - 1] };
+      out.push(a + (input[i] - a) * frac);
+      self.pos += self.step;
+    }
+    self.pos -= n as f64;
+    if let Some(&last) = input.last() { self.prev = last; }
+  }
+}
+
+pub fn rms(samples: &[f32]) -> f32 {
+  if samples.is_empty() { return 0.0; }
+  (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Accumulates captured PCM and emits its level as `audio:level` every LEVEL_INTERVAL.
+pub struct LevelMeter {
+  app: AppHandle,
+  source: &'static str,
+  sum_sq: f64,
+  count: usize,
+  peak: f32,
+  last: Instant,
+}
+
+impl LevelMeter {
+  /// `source` names the recording in the event ("dictation", "live_dictation").
+  pub fn new(app: &AppHandle, source: &'static str) -> Self {
+    Self { app: app.clone(), source, sum_sq: 0.0, count: 0, peak: 0.0, last: Instant::now() }
+  }
+
+  pub fn feed(&mut self, pcm: &[i16]) {
+    for s in pcm {
+      let v = *s as f32 / i16::MAX as f32;
+      self.sum_sq += (v * v) as f64;
+      self.peak = self.peak.max(v.abs());
+    }
+    self.count += pcm.len();
+    if self.last.elapsed() < LEVEL_INTERVAL || self.count == 0 { return; }
+    let rms = (self.sum_sq / self.count as f64).sqrt() as f32;
+    crate::events::emit(&self.app, crate::events::AUDIO_LEVEL, &crate::events::AudioLevel {
+      source: self.source.to_string(),
+      rms,
+      peak: self.peak.min(1.0),
+      clipping: self.peak >= CLIP_LEVEL,
+    });
+    self.sum_sq = 0.0;
+    self.count = 0;
+    self.peak = 0.0;
+    self.last = Instant::now();
+  }
+}
+
+/// Names of the microphones cpal can open, for the `stt_input_device` setting.
+pub fn input_device_names() -> Vec<String> {
+  cpal::default_host()
+    .input_devices()
+    .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+    .unwrap_or_default()
+}
+
+// The microphone named `wanted` (normally `stt_input_device`), else the system default
+fn input_device(wanted: Option<String>) -> Result<cpal::Device, String> {
+  let host = cpal::default_host();
+  if let Some(wanted) = wanted.filter(|w| !w.trim().is_empty()) {
+    let found = host.input_devices().ok().and_then(|mut devices| devices.find(|d| d.name().is_ok_and(|n| n == wanted)));
+    if let Some(device) = found {
+      return Ok(device);
+    }
+    tracing::warn!(device = %wanted, "configured microphone not found; using the default");
+  }
+  host.default_input_device().ok_or_else(|| "No microphone found".to_string())
+}
+
+/// Keeps the streams opened by `spawn_streams` alive; dropping it stops them.
+pub struct StreamsHandle {
+  _stop: std_mpsc::Sender<()>,
+}
+
+/// Open streams on a dedicated thread and keep them there until the handle is dropped.
+/// `open` returns the streams plus any value the caller needs (e.g. the device sample rate).
+pub fn spawn_streams<R, F>(name: &str, open: F) -> Result<(StreamsHandle, R), String>
+where
+  R: Send + 'static,
+  F: FnOnce() -> Result<(Vec<cpal::Stream>, R), String> + Send + 'static,
+{
+  let (stop_tx, stop_rx) = std_mpsc::channel::<()>();
+  let (ready_tx, ready_rx) = std_mpsc::channel::<Result<R, String>>();
+  std::thread::Builder::new()
+    .name(name.to_string())
+    .spawn(move || {
+      let (streams, value) = match open() {
+        Ok(s) => s,
+        Err(e) => {
+          let _ = ready_tx.send(Err(e));
+          return;
+        }
+      };
+      let _ = ready_tx.send(Ok(value));
+      // Returns once the StreamsHandle (sender) is dropped
+      let _ = stop_rx.recv();
+      drop(streams);
+    })
+    .map_err(|e| format!("audio thread failed: {e}"))?;
+  let value = ready_rx.recv().map_err(|_| "audio thread exited".to_string())??;
+  Ok((StreamsHandle { _stop: stop_tx }, value))
+}
+
+fn build_input<T>(device: &cpal::Device, config: &cpal::StreamConfig, rate: u32, tx: mpsc::UnboundedSender<MicChunk>) -> Result<cpal::Stream, String>
+where
+  T: SizedSample,
+  f32: FromSample<T>,
+{
+  let channels = config.channels.max(1) as usize;
+  let mut resampler = Resampler::new(config.sample_rate.0, rate);
+  let mut mono = Vec::new();
+  let mut out = Vec::new();
+  device
+    .build_input_stream(
+      config,
+      move |data: &[T], _: &cpal::InputCallbackInfo| {
+        mono.clear();
+        mono.extend(data.chunks(channels).map(|f| f.iter().map(|s| f32::from_sample(*s)).sum::<f32>() / channels as f32));
+        out.clear();
+        resampler.process(&mono, &mut out);
+        let pcm = out.iter().map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect();
+        let _ = tx.send((pcm, rms(&mono)));
+      },
+      |e| tracing::warn!(error = %e, "microphone stream error"),
+      None,
+    )
+    .map_err(|e| format!("open microphone failed: {e}"))
+}
+
+/// Build (and start) a stream on the configured microphone delivering mono PCM16 at `rate`.
+/// Must be called on the thread that will own the stream (see `spawn_streams`).
+pub fn open_input(rate: u32, tx: mpsc::UnboundedSender<MicChunk>) -> Result<cpal::Stream, String> {
+  open_input_on(crate::config::get_stt_input_device_from_settings(), rate, tx)
+}
+
+// `open_input` on the microphone named `device` (None: the system default)
+fn open_input_on(device: Option<String>, rate: u32, tx: mpsc::UnboundedSender<MicChunk>) -> Result<cpal::Stream, String> {
+  let input = input_device(device)?;
+  let cfg = input.default_input_config().map_err(|e| format!("microphone config failed: {e}"))?;
+  let stream = match cfg.sample_format() {
+    cpal::SampleFormat::F32 => build_input::<f32>(&input, &cfg.config(), rate, tx),
+    cpal::SampleFormat::I16 => build_input::<i16>(&input, &cfg.config(), rate, tx),
+    cpal::SampleFormat::U16 => build_input::<u16>(&input, &cfg.config(), rate, tx),
+    f => Err(format!("Unsupported microphone sample format: {f:?}")),
+  }?;
+  stream.play().map_err(|e| format!("start microphone failed: {e}"))?;
+  Ok(stream)
+}
+
+/// Build (and start) a stream capturing what the default output device plays (WASAPI loopback) as
+/// mono PCM16 at `rate`. Windows only; call it on the thread that will own the stream.
+#[cfg(windows)]
+pub fn open_loopback(rate: u32, tx: mpsc::UnboundedSender<MicChunk>) -> Result<cpal::Stream, String> {
+  let output = cpal::default_host().default_output_device().ok_or_else(|| "No audio output device found".to_string())?;
+  let cfg = output.default_output_config().map_err(|e| format!("audio output config failed: {e}"))?;
+  // cpal opens an input stream on an output device in loopback mode
+  let stream = match cfg.sample_format() {
+    cpal::SampleFormat::F32 => build_input::<f32>(&output, &cfg.config(), rate, tx),
+    cpal::SampleFormat::I16 => build_input::<i16>(&output, &cfg.config(), rate, tx),
+    cpal::SampleFormat::U16 => build_input::<u16>(&output, &cfg.config(), rate, tx),
+    f => Err(format!("Unsupported output sample format: {f:?}")),
+  }?;
+  stream.play().map_err(|e| format!("start system audio capture failed: {e}"))?;
+  Ok(stream)
+}
+
+#[cfg(not(windows))]
+pub fn open_loopback(_rate: u32, _tx: mpsc::UnboundedSender<MicChunk>) -> Result<cpal::Stream, String> {
+  Err("Recording system audio is only supported on Windows".into())
+}
+
+/// Capture the configured microphone as mono PCM16 at `rate` until the handle is dropped.
+pub fn start_mic(rate: u32) -> Result<(StreamsHandle, mpsc::UnboundedReceiver<MicChunk>), String> {
+  let (tx, rx) = mpsc::unbounded_channel();
+  let (handle, ()) = spawn_streams("mic-capture", move || Ok((vec![open_input(rate, tx)?], ())))?;
+  Ok((handle, rx))
+}
+
+/// Report the level of microphone `device` (None: `stt_input_device`) as `audio:level` with source
+/// "monitor" until `stop_level_monitor`. Replaces a running monitor. Blocks while the device opens.
+pub fn start_level_monitor(app: &AppHandle, device: Option<String>) -> Result<(), String> {
+  stop_level_monitor();
+  let device = device.or_else(crate::config::get_stt_input_device_from_settings);
+  let (tx, mut rx) = mpsc::unbounded_channel();
+  let (handle, ()) = spawn_streams("mic-monitor", move || Ok((vec![open_input_on(device, MONITOR_RATE, tx)?], ())))?;
+  let cancel = CancellationToken::new();
+  if let Some(previous) = MONITOR.lock().unwrap_or_else(|e| e.into_inner()).replace(cancel.clone()) {
+    previous.cancel();
+  }
+  let mut meter = LevelMeter::new(app, "monitor");
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::select! {
+        _ = cancel.cancelled() => break,
+        chunk = rx.recv() => {
+          let Some((pcm, _level)) = chunk else { break };
+          meter.feed(&pcm);
+        }
+      }
+    }
+    drop(handle);
+  });
+  Ok(())
+}
+
+/// Stop the level monitor; returns whether it was running.
+pub fn stop_level_monitor() -> bool {
+  match MONITOR.lock().unwrap_or_else(|e| e.into_inner()).take() {
+    Some(cancel) => {
+      cancel.cancel();
+      true
+    }
+    None => false,
+  }
+}
+
+fn build_queue_output<T>(device: &cpal::Device, config: &cpal::StreamConfig, queue: Arc<Mutex<VecDeque<f32>>>) -> Result<cpal::Stream, String>
+where
+  T: SizedSample + FromSample<f32>,
+{
+  let channels = config.channels.max(1) as usize;
+  device
+    .build_output_stream(
+      config,
+      move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+        let mut q = queue.lock().unwrap_or_else(|e| e.into_inner());
+        for frame in data.chunks_mut(channels) {
+          let v = T::from_sample(q.pop_front().unwrap_or(0.0));
+          for s in frame.iter_mut() { *s = v; }
+        }
+      },
+      |e| tracing::warn!(error = %e, "audio output stream error"),
+      None,
+    )
+    .map_err(|e| format!("open audio output failed: {e}"))
+}
+
+/// Build (and start) a stream on the default output device that plays mono samples pushed into
+/// `queue` (silence when empty). Returns the stream and the device sample rate.
+pub fn open_queue_output(queue: Arc<Mutex<VecDeque<f32>>>) -> Result<(cpal::Stream, u32), String> {
+  let output = cpal::default_host().default_output_device().ok_or_else(|| "No audio output device found".to_string())?;
+  let cfg = output.default_output_config().map_err(|e| format!("audio output config failed: {e}"))?;
+  let stream = match cfg.sample_format() {
+    cpal::SampleFormat::F32 => build_queue_output::<f32>(&output, &cfg.config(), queue),
+    cpal::SampleFormat::I16 => build_queue_output::<i16>(&output, &cfg.config(), queue),
+    cpal::SampleFormat::U16 => build_queue_output::<u16>(&output, &cfg.config(), queue),
+    f => Err(format!("Unsupported output sample format: {f:?}")),
+  }?;
+  stream.play().map_err(|e| format!("start audio output failed: {e}"))?;
+  Ok((stream, cfg.sample_rate().0))
+}
+
+// Mono f32 samples and sample rate of an audio file (WAV in any sample format, MP3, Ogg, ...)
+pub fn read_audio_mono(path: &Path) -> Result<(Vec<f32>, u32), String> {
+  let bytes = std::fs::read(path).map_err(|e| format!("open audio failed: {e}"))?;
+  let (rate, channels, interleaved) = crate::tts_utils::decode_to_pcm(&bytes)?;
+  let channels = channels.max(1) as usize;
+  let mono = interleaved.chunks(channels).map(|f| f.iter().sum::<f32>() / channels as f32).collect();
+  Ok((mono, rate))
+}
+
+/// Pause, seek and position of a running `play_samples`, shared with whoever controls it.
+pub struct PlaybackControl {
+  // Volume percent the clip starts with; live adjustments apply on top
+  base_volume: i32,
+  paused: AtomicBool,
+  // Requested position in source samples; NO_SEEK when none is pending
+  seek: AtomicUsize,
+  position: AtomicUsize,
+}
+
+const NO_SEEK: usize = usize::MAX;
+
+impl Default for PlaybackControl {
+  fn default() -> Self {
+    Self { base_volume: 100, paused: AtomicBool::new(false), seek: AtomicUsize::new(NO_SEEK), position: AtomicUsize::new(0) }
+  }
+}
+
+impl PlaybackControl {
+  /// Play at `volume` (0..200 percent) for clips without it applied.
+  pub fn with_volume(volume: i32) -> Self {
+    Self { base_volume: volume.clamp(0, LIVE_VOLUME_MAX), ..Self::default() }
+  }
+
+  pub fn set_paused(&self, paused: bool) {
+    self.paused.store(paused, Ordering::SeqCst);
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.paused.load(Ordering::SeqCst)
+  }
+
+  pub fn seek(&self, sample: usize) {
+    self.seek.store(sample, Ordering::SeqCst);
+    self.position.store(sample, Ordering::SeqCst);
+  }
+
+  /// Source sample being heard (approximately; the device buffer is not counted)
+  pub fn position(&self) -> usize {
+    self.position.load(Ordering::SeqCst)
+  }
+}
+
+/// Play a WAV file on the default output device; returns when playback ends or `cancel` fires.
+/// Rate and volume follow `adjust_playback` while it plays.
+pub async fn play_wav(path: &Path, cancel: &CancellationToken) -> Result<(), String> {
+  let (samples, rate) = read_audio_mono(path)?;
+  play_samples(&samples, rate, cancel, &PlaybackControl::default()).await
+}
+
+/// Play mono `samples` on the default output device until they end or `cancel` fires, honouring
+/// pause and seek requests on `control`.
+pub async fn play_samples(samples: &[f32], rate: u32, cancel: &CancellationToken, control: &PlaybackControl) -> Result<(), String> {
+  let queue = Arc::new(Mutex::new(VecDeque::new()));
+  let q = queue.clone();
+  let (handle, out_rate) = tokio::task::spawn_blocking(move || {
+    spawn_streams("wav-playback", move || {
+      let (stream, rate) = open_queue_output(q)?;
+      Ok((vec![stream], rate))
+    })
+  })
+  .await
+  .map_err(|e| format!("audio task failed: {e}"))??;
+  let _live = LivePlayback::start();
+  let ahead = out_rate as usize * FEED_AHEAD_MS / 1000;
+  let block = (rate as usize * FEED_BLOCK_MS / 1000).max(1);
+  let mut resampler = Resampler::new(rate, out_rate);
+  let mut out = Vec::new();
+  let mut fed = 0usize;
+  let mut speed = 1f64;
+  // Source samples per queued output sample at the current speed
+  let source_per_out = |speed: f64| rate as f64 * speed / out_rate as f64;
+  loop {
+    if cancel.is_cancelled() { break; }
+    let target = control.seek.swap(NO_SEEK, Ordering::SeqCst);
+    if target != NO_SEEK {
+      queue.lock().unwrap_or_else(|e| e.into_inner()).clear();
+      fed = target.min(samples.len());
+      resampler = Resampler::new(rate, out_rate);
+    }
+    if control.is_paused() {
+      // Drop what is queued so the pause is immediate, and rewind by the same amount
+      let dropped = std::mem::take(&mut *queue.lock().unwrap_or_else(|e| e.into_inner())).len();
+      fed = fed.saturating_sub((dropped as f64 * source_per_out(speed)) as usize);
+      control.position.store(fed, Ordering::SeqCst);
+      tokio::time::sleep(Duration::from_millis(20)).await;
+      continue;
+    }
+    let queued = queue.lock().unwrap_or_else(|e| e.into_inner()).len();
+    control.position.store(fed.saturating_sub((queued as f64 * source_per_out(speed)) as usize), Ordering::SeqCst);
+    if fed >= samples.len() {
+      if queued == 0 {
+        // Let the device drain its last buffer
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        break;
+      }
+    } else if queued < ahead {
+      let live = playback_adjust();
+      // Reading the source faster than its rate speeds playback up
+      speed = 2f64.powf(live.rate as f64 / 10.0);
+      resampler.set_rates((rate as f64 * speed).round() as u32, out_rate);
+      let gain = if MUTED.load(Ordering::SeqCst) { 0.0 } else { live.volume as f32 / 100.0 * control.base_volume as f32 / 100.0 };
+      let end = (fed + block).min(samples.len());
+      out.clear();
+      resampler.process(&samples[fed..end], &mut out);
+      fed = end;
+      queue.lock().unwrap_or_else(|e| e.into_inner()).extend(out.iter().map(|s| (s * gain).clamp(-1.0, 1.0)));
+      continue;
+    }
+    tokio::time::sleep(Duration::from_millis(20)).await;
+  }
+  control.position.store(fed.min(samples.len()), Ordering::SeqCst);
+  drop(handle);
+  Ok(())
+}
@@ -0,0 +1,299 @@
+// Audiobook export: every assistant message of a stored conversation is spoken with the
+// configured TTS engine (`tts_engine`: OpenAI, ElevenLabs, Piper or local SAPI) and the clips are
+// joined into one file with a pause between messages.
+//   mp3 — encoded in-process (LAME); chapters are written as ID3v2 CHAP/CTOC frames
+//   m4b — AAC with MP4 chapters; needs ffmpeg on PATH
+// One chapter per assistant message, titled after the user message it answers. Runs as an
+// "export" job, so progress and cancellation go through `job:update`.
+
+use std::path::{Path, PathBuf};
+
+use tauri::Manager;
+
+use crate::audio_encode::{encode_mp3, to_i16};
+
+const DEFAULT_GAP_MS: u32 = 1000;
+const MAX_GAP_MS: u32 = 10_000;
+// Below the OpenAI TTS input limit (3500), leaving room for the split to end on a sentence
+const MAX_TTS_CHARS: usize = 3400;
+const CHAPTER_TITLE_CHARS: usize = 60;
+
+struct Chapter {
+  title: String,
+  start_ms: u32,
+  end_ms: u32,
+}
+
+// Markdown is read literally by TTS engines; drop code blocks and the most common markup
+fn speakable(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  let mut in_code = false;
+  for line in text.lines() {
+    if line.trim_start().starts_with("```") {
+      if !in_code {
+        out.push_str("(Code omitted.)\n");
+      }
+      in_code = !in_code;
+      continue;
+    }
+    if in_code {
+      continue;
+    }
+    let l = line.trim_start().trim_start_matches('#').trim_start_matches("- ").trim_start_matches("* ");
+    out.push_str(&l.replace("**", "").replace('`', ""));
+    out.push('\n');
+  }
+  out.trim().to_string()
+}
+
+// First words of a message, on one line
+fn short_title(text: &str) -> Option<String> {
+  let q = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if q.is_empty() {
+    return None;
+  }
+  if q.chars().count() > CHAPTER_TITLE_CHARS {
+    let short: String = q.chars().take(CHAPTER_TITLE_CHARS).collect();
+    Some(format!("{}…", short.trim_end()))
+  } else {
+    Some(q)
+  }
+}
+
+fn chapter_title(n: usize, question: Option<&str>) -> String {
+  match question.and_then(short_title) {
+    Some(q) => format!("{n}. {q}"),
+    None => format!("Chapter {n}"),
+  }
+}
+
+// Decode a WAV clip to mono f32
+fn read_wav(path: &Path) -> Result<(u32, Vec<f32>), String> {
+  let mut reader = hound::WavReader::open(path).map_err(|e| format!("read wav failed: {e}"))?;
+  let spec = reader.spec();
+  let channels = spec.channels.max(1) as usize;
+  let interleaved: Vec<f32> = match spec.sample_format {
+    hound::SampleFormat::Float => reader.samples::<f32>().filter_map(|s| s.ok()).collect(),
+    hound::SampleFormat::Int => {
+      let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+      reader.samples::<i32>().filter_map(|s| s.ok()).map(|s| s as f32 / scale).collect()
+    }
+  };
+  let mono = interleaved.chunks(channels).map(|f| f.iter().sum::<f32>() / f.len() as f32).collect();
+  Ok((spec.sample_rate, mono))
+}
+
+// Linear resampling; clips from one engine share a rate, so this rarely runs
+fn resample(samples: Vec<f32>, from: u32, to: u32) -> Vec<f32> {
+  if from == to || samples.is_empty() {
+    return samples;
+  }
+  let ratio = to as f64 / from as f64;
+  let out_len = (samples.len() as f64 * ratio).round() as usize;
+  (0..out_len)
+    .map(|n| {
+      let t = n as f64 / ratio;
+      let i0 = (t.floor() as usize).min(samples.len() - 1);
+      let i1 = (i0 + 1).min(samples.len() - 1);
+      let frac = (t - i0 as f64) as f32;
+      samples[i0] * (1.0 - frac) + samples[i1] * frac
+    })
+    .collect()
+}
+
+#[cfg(feature = "local-tts")]
+fn write_id3(path: &Path, title: &str, chapters: &[Chapter]) -> Result<(), String> {
+  use id3::frame::{Chapter as ChapterFrame, TableOfContents};
+  use id3::{Frame, Tag, TagLike, Version};
+  let mut tag = Tag::new();
+  tag.set_title(title);
+  tag.set_genre("Audiobook");
+  if !chapters.is_empty() {
+    let ids: Vec<String> = (0..chapters.len()).map(|i| format!("ch{i}")).collect();
+    tag.add_frame(TableOfContents { element_id: "toc".into(), top_level: true, ordered: true, elements: ids.clone(), frames: Vec::new() });
+    for (c, id) in chapters.iter().zip(ids) {
+      tag.add_frame(ChapterFrame {
+        element_id: id,
+        start_time: c.start_ms,
+        end_time: c.end_ms,
+        start_offset: u32::MAX,
+        end_offset: u32::MAX,
+        frames: vec![Frame::text("TIT2", c.title.clone())],
+      });
+    }
+  }
+  tag.write_to_path(path, Version::Id3v24).map_err(|e| format!("write chapters failed: {e}"))
+}
+
+// Unreachable: MP3 encoding already fails in such builds
+#[cfg(not(feature = "local-tts"))]
+fn write_id3(_path: &Path, _title: &str, _chapters: &[Chapter]) -> Result<(), String> {
+  Err("MP3 output is not available: app built without 'local-tts' feature.".into())
+}
+
+fn ffmetadata_escape(s: &str) -> String {
+  s.chars().fold(String::new(), |mut out, c| {
+    if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+      out.push('\\');
+    }
+    out.push(c);
+    out
+  })
+}
+
+// AAC/MP4 with chapter atoms via ffmpeg (ffmetadata chapter list as the second input)
+async fn encode_m4b(samples: &[f32], rate: u32, title: &str, chapters: &[Chapter], dest: &Path) -> Result<(), String> {
+  let stem = format!("aidc_audiobook_{}", uuid::Uuid::new_v4());
+  let wav = std::env::temp_dir().join(format!("{stem}.wav"));
+  let meta = std::env::temp_dir().join(format!("{stem}.txt"));
+  let spec = hound::WavSpec { channels: 1, sample_rate: rate, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+  let write_wav = || -> Result<(), hound::Error> {
+    let mut w = hound::WavWriter::create(&wav, spec)?;
+    for s in to_i16(samples) {
+      w.write_sample(s)?;
+    }
+    w.finalize()
+  };
+  write_wav().map_err(|e| format!("write wav failed: {e}"))?;
+  crate::temp_files::register(crate::temp_files::AUDIOBOOK, &wav);
+  let mut md = format!(";FFMETADATA1\ntitle={}\ngenre=Audiobook\n", ffmetadata_escape(title));
+  for c in chapters {
+    md.push_str(&format!("[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n", c.start_ms, c.end_ms, ffmetadata_escape(&c.title)));
+  }
+  let result = async {
+    tokio::fs::write(&meta, md).await.map_err(|e| format!("write chapters failed: {e}"))?;
+    let mut cmd = tokio::process::Command::new("ffmpeg");
+    cmd.args(["-y", "-loglevel", "error", "-i"]).arg(&wav).arg("-i").arg(&meta);
+    cmd.args(["-map_metadata", "1", "-map_chapters", "1", "-c:a", "aac", "-b:a", "64k", "-f", "mp4"]).arg(dest);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    let out = cmd.output().await.map_err(|e| {
+      if e.kind() == std::io::ErrorKind::NotFound {
+        "M4B export needs ffmpeg on PATH; install ffmpeg or export as MP3".to_string()
+      } else {
+        format!("ffmpeg failed to start: {e}")
+      }
+    })?;
+    if !out.status.success() {
+      return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&out.stderr).trim()));
+    }
+    Ok(())
+  }
+  .await;
+  let _ = tokio::fs::remove_file(&wav).await;
+  let _ = tokio::fs::remove_file(&meta).await;
+  result
+}
+
+fn safe_file_stem(s: &str) -> String {
+  let cleaned: String = s.chars().map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' { c } else { '_' }).take(48).collect();
+  let cleaned = cleaned.trim().replace(' ', "_");
+  if cleaned.is_empty() { "conversation".to_string() } else { cleaned }
+}
+
+/// Export a stored conversation as one audio file and return its path. `format` is "mp3"
+/// (default) or "m4b"; `voice` overrides the voice from the TTS settings; `chapters` (default on)
+/// adds one chapter per assistant message; `gap_ms` is the pause between messages (default 1 s).
+/// Without `path` the file goes to the Downloads folder.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_conversation_audio(
+  app: tauri::AppHandle,
+  id: String,
+  voice: Option<String>,
+  format: Option<String>,
+  chapters: Option<bool>,
+  gap_ms: Option<u32>,
+  path: Option<String>,
+  task_id: Option<String>,
+) -> Result<String, String> {
+  let format = format.unwrap_or_else(|| "mp3".into()).trim().to_lowercase();
+  if format != "mp3" && format != "m4b" {
+    return Err(format!("Unsupported format \"{format}\" (use mp3 or m4b)"));
+  }
+  let ctx = crate::config::load_conversation_context(&id, usize::MAX)?;
+  // (question, answer) per assistant message
+  let mut items: Vec<(Option<String>, String)> = Vec::new();
+  let mut last_user: Option<String> = None;
+  for (role, text) in ctx.history {
+    if role == "user" {
+      last_user = Some(text);
+    } else {
+      let spoken = speakable(&text);
+      if !spoken.is_empty() {
+        items.push((last_user.take(), spoken));
+      }
+    }
+  }
+  if items.is_empty() {
+    return Err("Conversation has no assistant messages to read".into());
+  }
+  let title = items.iter().find_map(|(q, _)| q.as_deref().and_then(short_title)).unwrap_or_else(|| "Conversation".into());
+  let dest = match path.filter(|p| !p.trim().is_empty()) {
+    Some(p) => PathBuf::from(p),
+    None => {
+      let dir = app.path().download_dir().map_err(|e| format!("Could not resolve Downloads folder: {e}"))?;
+      dir.join(format!("{}.{format}", safe_file_stem(&title)))
+    }
+  };
+  let engine = match crate::config::load_settings_json().get("tts_engine").and_then(|x| x.as_str()) {
+    Some("local") => "local",
+    Some("elevenlabs") => "elevenlabs",
+    Some("piper") => "piper",
+    _ => "openai",
+  };
+  let with_chapters = chapters.unwrap_or(true);
+  let gap_ms = gap_ms.unwrap_or(DEFAULT_GAP_MS).min(MAX_GAP_MS);
+
+  let label = format!("Audiobook export ({} messages)", items.len());
+  crate::jobs::run(&app, "export", label, task_id, |job| async move {
+    let total = items.len() as u64;
+    let mut rate = 0u32;
+    let mut pcm: Vec<f32> = Vec::new();
+    let mut marks: Vec<Chapter> = Vec::new();
+    for (i, (question, answer)) in items.into_iter().enumerate() {
+      let chapter = chapter_title(i + 1, question.as_deref());
+      job.progress(i as u64, Some(total), Some(&chapter));
+      if i > 0 && rate > 0 {
+        pcm.extend(std::iter::repeat(0.0).take((rate as u64 * gap_ms as u64 / 1000) as usize));
+      }
+      let start = pcm.len();
+      for part in crate::tts_utils::split_for_tts(&answer, MAX_TTS_CHARS) {
+        let mut params = crate::voices::settings_params(engine);
+        if voice.is_some() {
+          params.voice = voice.clone();
+        }
+        let clip = crate::voices::synthesize_wav(part, params).await?;
+        let decoded = read_wav(Path::new(&clip));
+        let _ = std::fs::remove_file(&clip);
+        let (clip_rate, samples) = decoded?;
+        if rate == 0 {
+          rate = clip_rate;
+        }
+        pcm.extend(resample(samples, clip_rate, rate));
+      }
+      if rate > 0 {
+        let ms = |n: usize| (n as u64 * 1000 / rate as u64) as u32;
+        marks.push(Chapter { title: chapter, start_ms: ms(start), end_ms: ms(pcm.len()) });
+      }
+    }
+    if pcm.is_empty() || rate == 0 {
+      return Err("TTS produced no audio".into());
+    }
+    job.progress(total, Some(total), Some("Encoding"));
+    let chapters = if with_chapters { marks } else { Vec::new() };
+    if let Some(dir) = dest.parent() {
+      std::fs::create_dir_all(dir).map_err(|e| format!("create folder failed: {e}"))?;
+    }
+    if format == "m4b" {
+      encode_m4b(&pcm, rate, &title, &chapters, &dest).await?;
+    } else {
+      let bytes = tokio::task::spawn_blocking(move || encode_mp3(&pcm, rate)).await.map_err(|e| format!("MP3 encode failed: {e}"))??;
+      tokio::fs::write(&dest, bytes).await.map_err(|e| format!("write {} failed: {e}", dest.display()))?;
+      write_id3(&dest, &title, &chapters)?;
+    }
+    tracing::info!(conversation = %id, format = %format, file = %dest.display(), "conversation exported as audio");
+    Ok(dest.to_string_lossy().to_string())
+  })
+  .await
+}
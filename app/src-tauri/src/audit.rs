@@ -0,0 +1,73 @@
+// Audit log: security-relevant events as JSON lines in <logs dir>/audit.jsonl, one object per
+// line: { "at": RFC 3339, "kind": "...", ...details }. The file is rotated to audit.1.jsonl
+// once it grows past MAX_AUDIT_BYTES, so at most two files are kept.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+const AUDIT_FILE: &str = "audit.jsonl";
+const AUDIT_FILE_OLD: &str = "audit.1.jsonl";
+const MAX_AUDIT_BYTES: u64 = 2 * 1024 * 1024;
+const DEFAULT_RECENT_ENTRIES: usize = 100;
+const MAX_RECENT_ENTRIES: usize = 1000;
+
+// Serializes appends and rotation across threads
+static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn audit_path(name: &str) -> Option<PathBuf> {
+  crate::logging::logs_dir().map(|d| d.join(name))
+}
+
+/// Append one entry. `details` should be an object; its fields are merged after `at` and `kind`.
+/// Failures are logged and otherwise ignored so auditing never breaks the caller.
+pub fn record(kind: &str, details: serde_json::Value) {
+  let Some(path) = audit_path(AUDIT_FILE) else { return };
+  let mut entry = serde_json::Map::new();
+  entry.insert("at".to_string(), serde_json::Value::String(chrono::Local::now().to_rfc3339()));
+  entry.insert("kind".to_string(), serde_json::Value::String(kind.to_string()));
+  match details {
+    serde_json::Value::Object(map) => entry.extend(map),
+    serde_json::Value::Null => {}
+    other => {
+      entry.insert("details".to_string(), other);
+    }
+  }
+  let line = serde_json::Value::Object(entry).to_string();
+
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  if let Some(dir) = path.parent() {
+    let _ = fs::create_dir_all(dir);
+  }
+  if fs::metadata(&path).map(|m| m.len() > MAX_AUDIT_BYTES).unwrap_or(false) {
+    if let Some(old) = audit_path(AUDIT_FILE_OLD) {
+      let _ = fs::rename(&path, old);
+    }
+  }
+  let res = OpenOptions::new().create(true).append(true).open(&path).and_then(|mut f| writeln!(f, "{line}"));
+  if let Err(e) = res {
+    tracing::warn!(error = %e, %kind, "audit log write failed");
+  }
+}
+
+/// Most recent audit entries, newest last (default 100), optionally only of one kind.
+#[tauri::command]
+pub fn get_audit_log(kind: Option<String>, limit: Option<usize>) -> Result<Vec<serde_json::Value>, String> {
+  let want = limit.unwrap_or(DEFAULT_RECENT_ENTRIES).clamp(1, MAX_RECENT_ENTRIES);
+  let mut entries: Vec<serde_json::Value> = Vec::new();
+  for name in [AUDIT_FILE_OLD, AUDIT_FILE] {
+    let Some(path) = audit_path(name) else { continue };
+    let Ok(content) = fs::read_to_string(&path) else { continue };
+    entries.extend(
+      content
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter(|v| kind.as_deref().map(|k| v.get("kind").and_then(|x| x.as_str()) == Some(k)).unwrap_or(true)),
+    );
+  }
+  let take = want.min(entries.len());
+  Ok(entries.split_off(entries.len() - take))
+}
@@ -0,0 +1,68 @@
+// Current browser page: when the window the user works in belongs to a known browser, its tab
+// title and URL (read from the address bar through UI Automation, Windows only) are offered as
+// context: in the chat's desktop context block and as the {url} / {page_title} template
+// variables of quick prompts, so "summarize the page I'm looking at" can be answered with a fetch
+// tool. `browser_context_enabled` (off by default) turns the capture on.
+
+use serde::Serialize;
+
+// Executable name and the suffix the browser appends to window titles
+const BROWSERS: &[(&str, &str)] = &[
+  ("chrome.exe", "Google Chrome"),
+  ("msedge.exe", "Microsoft Edge"),
+  ("firefox.exe", "Mozilla Firefox"),
+  ("brave.exe", "Brave"),
+  ("opera.exe", "Opera"),
+  ("vivaldi.exe", "Vivaldi"),
+  ("arc.exe", "Arc"),
+  ("chromium.exe", "Chromium"),
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BrowserPage {
+  pub url: String,
+  pub title: String,
+  /// e.g. "Google Chrome"
+  pub browser: String,
+}
+
+fn browser_name(process: &str) -> Option<&'static str> {
+  BROWSERS.iter().find(|(exe, _)| exe.eq_ignore_ascii_case(process)).map(|(_, name)| *name)
+}
+
+// Chromium shows "example.com/path" without the scheme; text with spaces is a search being typed
+fn normalize_url(raw: &str) -> Option<String> {
+  let s = raw.trim();
+  if s.is_empty() || s.contains(char::is_whitespace) {
+    return None;
+  }
+  if s.contains("://") || s.starts_with("about:") {
+    return Some(s.to_string());
+  }
+  s.contains('.').then(|| format!("https://{s}"))
+}
+
+// "Page - Google Chrome" -> "Page"; Edge puts a zero-width space into its name
+fn page_title(window_title: &str, browser: &str) -> String {
+  let title = window_title.replace('\u{200b}', "");
+  let title = title.trim();
+  match title.strip_suffix(browser) {
+    Some(rest) => rest.trim_end().trim_end_matches(['-', '—']).trim_end().to_string(),
+    None => title.to_string(),
+  }
+}
+
+/// The page shown in `hwnd`, when it is a browser window and the capture is enabled. Blocking.
+pub fn page_for_window(hwnd: isize) -> Option<BrowserPage> {
+  if !crate::config::get_browser_context_enabled_from_settings() {
+    return None;
+  }
+  let browser = browser_name(&crate::quick_actions::process_name_for_window(hwnd))?;
+  let url = crate::uia::browser_address_bar(hwnd).and_then(|v| normalize_url(&v))?;
+  Some(BrowserPage { url, title: page_title(&crate::hotstrings::window_title(hwnd), browser), browser: browser.to_string() })
+}
+
+/// The page in the window the user is working in (see `target_window_handle_raw`). Blocking.
+pub fn current_page() -> Option<BrowserPage> {
+  crate::quick_actions::target_window_handle_raw().and_then(page_for_window)
+}
@@ -0,0 +1,44 @@
+// Which optional features this binary was built with, so the UI can hide engines that are not
+// compiled in (e.g. cloud-only builds without Whisper/Parakeet, local embeddings, system voices or the TTS proxy).
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct BuildCapabilities {
+  pub version: String,
+  pub os: String,
+  /// Built without any of the local-* features
+  pub cloud_only: bool,
+  /// Whisper and Parakeet on-device transcription
+  pub local_stt: bool,
+  /// On-device MiniLM embeddings
+  pub local_embeddings: bool,
+  /// System voices (Windows SAPI, macOS `say`)
+  pub local_tts: bool,
+  /// Local HTTP proxy used for streamed TTS playback
+  pub streaming_server: bool,
+  /// Containers/codecs the audio decoder understands
+  pub audio_formats: Vec<&'static str>,
+}
+
+fn audio_formats() -> Vec<&'static str> {
+  let mut v = vec!["wav", "mp3"];
+  if cfg!(feature = "local-stt") {
+    v.extend(["aac", "flac", "ogg", "vorbis", "mp4", "mkv", "webm"]);
+  }
+  v
+}
+
+#[tauri::command]
+pub fn get_build_capabilities() -> Result<BuildCapabilities, String> {
+  Ok(BuildCapabilities {
+    version: env!("CARGO_PKG_VERSION").to_string(),
+    os: std::env::consts::OS.to_string(),
+    cloud_only: !cfg!(any(feature = "local-stt", feature = "local-embeddings", feature = "local-tts")),
+    local_stt: cfg!(feature = "local-stt"),
+    local_embeddings: cfg!(feature = "local-embeddings"),
+    local_tts: cfg!(all(any(target_os = "windows", target_os = "macos"), feature = "local-tts")),
+    streaming_server: cfg!(feature = "streaming-server"),
+    audio_formats: audio_formats(),
+  })
+}
@@ -96,6 +96,7 @@ pub fn capture_region(app: tauri::AppHandle, x: i32, y: i32, width: i32, height:
     path.push(file_name);
 
     img.save(&path).map_err(|e| format!("image save failed: {e}"))?;
+    crate::temp_files::register(crate::temp_files::CAPTURE, &path);
 
     // Open main window and emit event
     if let Some(win) = app.get_webview_window("main") { let _ = win.show(); let _ = win.set_focus(); }
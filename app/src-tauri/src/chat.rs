@@ -1,10 +1,14 @@
 use serde::Deserialize;
 use base64::Engine;
+use futures_util::future::BoxFuture;
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
 use std::fs;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use rmcp::service::{RoleClient, DynService, RunningService};
 use tokio::sync::Mutex as AsyncMutex;
-use tauri::Emitter;
+
+use crate::error::AidcError;
 
 #[derive(Debug, Deserialize)]
 pub struct ChatMessage {
@@ -12,14 +16,16 @@ pub struct ChatMessage {
   pub content: ChatContent,
 }
 
-pub async fn chat_complete_with_mcp(
-  app: tauri::AppHandle,
-  messages: Vec<ChatMessage>,
-  key: String,
-  model: String,
-  temp: Option<f32>,
-  mcp_clients: &AsyncMutex<std::collections::HashMap<String, Arc<RunningService<RoleClient, Box<dyn DynService<RoleClient>>>>>>,
-) -> Result<String, String> {
+type McpClients = AsyncMutex<std::collections::HashMap<String, Arc<RunningService<RoleClient, Box<dyn DynService<RoleClient>>>>>>;
+
+// Request messages and tools shared by the blocking and streaming completions
+struct Prepared {
+  msgs: Vec<serde_json::Value>,
+  tools: Vec<serde_json::Value>,
+  allow_tools: bool,
+}
+
+async fn prepare(messages: Vec<ChatMessage>, model: &str, mcp_clients: &McpClients) -> Result<Prepared, AidcError> {
   use crate::mcp;
 
   // Normalize incoming messages to OpenAI format
@@ -35,17 +41,7 @@ pub async fn chat_complete_with_mcp(
             FrontendPart::InputText { text } => { out_parts.push(serde_json::json!({ "type": "text", "text": text })); }
             FrontendPart::InputImage { path, mime } => {
               // Validate image path is within temp directory to prevent path traversal
-              let file_path = std::path::PathBuf::from(&path);
-              let temp_dir = std::env::temp_dir();
-              let temp_canon = std::fs::canonicalize(&temp_dir).unwrap_or(temp_dir.clone());
-              let file_canon = std::fs::canonicalize(&file_path).map_err(|e| format!("Invalid image path '{}': {}", path, e))?;
-              if !file_canon.starts_with(&temp_canon) {
-                return Err(format!("Image path '{}' is outside temp directory — refusing to read", path));
-              }
-              let mime_final = mime.or_else(|| guess_mime_from_path_rs(&path).map(|s| s.to_string())).ok_or_else(|| format!("Missing/unknown image MIME for: {}", path))?;
-              let bytes = fs::read(&file_canon).map_err(|e| format!("Failed to read image '{}': {}", path, e))?;
-              let b64 = base64::engine::general_purpose::STANDARD.encode(bytes);
-              let url = format!("data:{};base64,{}", mime_final, b64);
+              let url = image_data_url(&path, mime)?;
               out_parts.push(serde_json::json!({ "type": "image_url", "image_url": { "url": url } }));
             }
           }
@@ -56,13 +52,23 @@ pub async fn chat_complete_with_mcp(
     norm_msgs.push(serde_json::json!({ "role": r, "content": content_value }));
   }
 
-  // Build tool definitions from connected MCP servers (via MCP module)
+  // Known capabilities of the model (models.rs); unknown models are sent as is
+  let info = crate::models::lookup(model);
+  if info.as_ref().is_some_and(|i| !i.vision) && norm_msgs.iter().any(has_image) {
+    return Err(AidcError::InvalidInput(format!("Model {model} does not accept images")));
+  }
+
+  // Build tool definitions from connected MCP servers (via MCP module), plus the built-in tools
   let tools = {
     let map = mcp_clients.lock().await;
-    mcp::build_openai_tools_from_mcp(&*map).await
+    let mut tools = mcp::build_openai_tools_from_mcp(&*map).await;
+    tools.extend(crate::memory::tool_definitions());
+    tools.extend(crate::drafts::tool_definitions());
+    tools.extend(crate::code_exec::tool_definitions());
+    crate::personas::retain_allowed_tools(&mut tools);
+    tools
   };
 
-  let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(120)).connect_timeout(std::time::Duration::from_secs(10)).build().unwrap_or_else(|_| reqwest::Client::new());
   // Determine whether tools are allowed by scanning system messages for a no-tools directive
   let mut allow_tools = true;
   for m in norm_msgs.iter() {
@@ -89,6 +95,10 @@ pub async fn chat_complete_with_mcp(
     }
   }
 
+  if info.as_ref().is_some_and(|i| !i.tools) {
+    allow_tools = false;
+  }
+
   let mut msgs_for_oai: Vec<serde_json::Value> = Vec::new();
   if allow_tools {
     let sys_tool_guidance = serde_json::json!({
@@ -98,111 +108,552 @@ pub async fn chat_complete_with_mcp(
     msgs_for_oai.push(sys_tool_guidance);
   }
   msgs_for_oai.extend(norm_msgs.clone());
-  let mut final_text: Option<String> = None;
+  if let Some(window) = info.as_ref().and_then(|i| i.context_window) {
+    let tool_tokens = if allow_tools { tools.iter().map(|t| t.to_string().len() / 4).sum() } else { 0 };
+    fit_to_context(&mut msgs_for_oai, window as usize, tool_tokens);
+  }
+  Ok(Prepared { msgs: msgs_for_oai, tools, allow_tools })
+}
 
-  for _ in 0..6u8 {
-    let mut body = serde_json::json!({ "model": &model, "messages": msgs_for_oai });
-    if let Some(t) = temp { if let serde_json::Value::Object(ref mut m) = body { m.insert("temperature".to_string(), serde_json::json!(t)); } }
-    if allow_tools && !tools.is_empty() {
-      if let serde_json::Value::Object(ref mut m) = body {
-        m.insert("tools".to_string(), serde_json::Value::Array(tools.clone()));
-        m.insert("tool_choice".to_string(), serde_json::Value::String("auto".to_string()));
-        m.insert("parallel_tool_calls".to_string(), serde_json::Value::Bool(true));
-      }
-    }
+/// One model turn. Tool calls use the Chat Completions shape
+/// (`{id, type: "function", function: {name, arguments}}`) whatever the provider.
+pub(crate) struct Turn {
+  pub text: Option<String>,
+  pub tool_calls: Vec<serde_json::Value>,
+}
 
-    let resp = client
-      .post("https://api.openai.com/v1/chat/completions")
-      .bearer_auth(&key)
-      .json(&body)
-      .send()
-      .await
-      .map_err(|e| format!("request failed: {e}"))?;
+/// A chat backend for `chat_complete_with_mcp`. History and tools stay in the Chat Completions
+/// format built by `prepare`; providers with another wire format convert on each request.
+pub(crate) trait ChatProvider: Send + Sync {
+  /// Provider id for rate limiting, errors and the response cache
+  fn id(&self) -> &'static str;
+  /// Send the history once; `tools` is empty when tools are not allowed. Records usage.
+  fn complete<'a>(
+    &'a self,
+    client: &'a reqwest::Client,
+    model: &'a str,
+    msgs: &'a [serde_json::Value],
+    tools: &'a [serde_json::Value],
+    temp: Option<f32>,
+  ) -> BoxFuture<'a, Result<Turn, AidcError>>;
+}
 
-    if !resp.status().is_success() {
-      let status = resp.status();
-      let body_text = resp.text().await.unwrap_or_default();
-      return Err(format!("OpenAI error: {status} {body_text}"));
+/// Chat Completions against `base_url` (api.openai.com or an OpenAI-compatible server).
+pub(crate) struct OpenAiChat {
+  pub key: String,
+  pub base_url: String,
+}
+
+// Models on OpenAI-compatible servers that rejected a request with tools (Ollama answers 400
+// "... does not support tools"); they are asked without tools for the rest of the session
+static TOOLLESS_MODELS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+fn toolless_key(base_url: &str, model: &str) -> String {
+  format!("{base_url}|{model}")
+}
+
+fn tools_rejected(status: reqwest::StatusCode, body: &str) -> bool {
+  (status == reqwest::StatusCode::BAD_REQUEST || status == reqwest::StatusCode::UNPROCESSABLE_ENTITY) && body.to_ascii_lowercase().contains("tool")
+}
+
+fn remember_toolless(base_url: &str, model: &str) {
+  tracing::info!(%base_url, %model, "model server rejected tools; continuing without tools");
+  TOOLLESS_MODELS.lock().unwrap_or_else(|e| e.into_inner()).insert(toolless_key(base_url, model));
+}
+
+fn is_toolless(base_url: &str, model: &str) -> bool {
+  TOOLLESS_MODELS.lock().unwrap_or_else(|e| e.into_inner()).contains(&toolless_key(base_url, model))
+}
+
+impl ChatProvider for OpenAiChat {
+  fn id(&self) -> &'static str {
+    "openai"
+  }
+
+  fn complete<'a>(
+    &'a self,
+    client: &'a reqwest::Client,
+    model: &'a str,
+    msgs: &'a [serde_json::Value],
+    tools: &'a [serde_json::Value],
+    temp: Option<f32>,
+  ) -> BoxFuture<'a, Result<Turn, AidcError>> {
+    Box::pin(async move {
+      let url = format!("{}/chat/completions", self.base_url);
+      let mut tools = if is_toolless(&self.base_url, model) { &[][..] } else { tools };
+      let resp = loop {
+        let mut body = serde_json::json!({ "model": model, "messages": msgs });
+        if let serde_json::Value::Object(ref mut m) = body {
+          if let Some(t) = temp { m.insert("temperature".to_string(), serde_json::json!(t)); }
+          if !tools.is_empty() {
+            m.insert("tools".to_string(), serde_json::Value::Array(tools.to_vec()));
+            m.insert("tool_choice".to_string(), serde_json::Value::String("auto".to_string()));
+            m.insert("parallel_tool_calls".to_string(), serde_json::Value::Bool(true));
+          }
+        }
+
+        let provider = crate::config::chat_rate_limit_provider(&self.base_url);
+        let resp = crate::rate_limit::send(provider, "chat", || crate::settings::with_chat_key(client.post(&url), &self.key).json(&body))
+          .await
+          .map_err(|e| AidcError::from_reqwest("openai", &e))?;
+
+        if !resp.status().is_success() {
+          let status = resp.status();
+          let body_text = resp.text().await.unwrap_or_default();
+          // Local models without tool support: ask again without tools
+          if !tools.is_empty() && !crate::config::is_default_openai_base_url(&self.base_url) && tools_rejected(status, &body_text) {
+            remember_toolless(&self.base_url, model);
+            tools = &[];
+            continue;
+          }
+          return Err(AidcError::from_status("openai", status, &body_text));
+        }
+        break resp;
+      };
+
+      let v: serde_json::Value = resp.json().await.map_err(|e| AidcError::invalid_response("openai", format!("json error: {e}")))?;
+      crate::usage::record_response(crate::usage::CHAT, model, &v);
+      let msg = v.get("choices").and_then(|c| c.get(0)).and_then(|c| c.get("message")).cloned().unwrap_or(serde_json::Value::Null);
+      Ok(Turn {
+        text: msg.get("content").and_then(|t| t.as_str()).map(|s| s.to_string()),
+        tool_calls: msg.get("tool_calls").and_then(|x| x.as_array()).cloned().unwrap_or_default(),
+      })
+    })
+  }
+}
+
+/// The provider selected by the `chat_provider` setting and the chat model configured for it.
+/// The configured chat provider and model; the active persona's model replaces the configured one.
+pub(crate) fn provider_from_settings() -> Result<(Box<dyn ChatProvider>, String), AidcError> {
+  let persona_model = crate::personas::active_model();
+  match crate::config::get_chat_provider_from_settings().as_str() {
+    "anthropic" => Ok((
+      Box::new(crate::anthropic::AnthropicChat { key: crate::settings::require_anthropic_key()? }),
+      persona_model.unwrap_or_else(crate::config::get_anthropic_model_from_settings_or_env),
+    )),
+    "gemini" => Ok((
+      Box::new(crate::gemini::GeminiChat { key: crate::settings::require_gemini_key()? }),
+      persona_model.unwrap_or_else(crate::config::get_gemini_model_from_settings_or_env),
+    )),
+    _ => {
+      let base_url = crate::config::get_openai_base_url_from_settings_or_env();
+      let key = crate::settings::require_chat_key(&base_url)?;
+      Ok((Box::new(OpenAiChat { key, base_url }), persona_model.unwrap_or_else(crate::settings::get_model_from_settings_or_env)))
     }
+  }
+}
 
-    let v: serde_json::Value = resp.json().await.map_err(|e| format!("json error: {e}"))?;
-    let choice0 = v.get("choices").and_then(|c| c.get(0)).cloned().unwrap_or(serde_json::Value::Null);
-    let msg = choice0.get("message").cloned().unwrap_or(serde_json::Value::Null);
-    let tool_calls_opt = msg.get("tool_calls").and_then(|x| x.as_array()).cloned();
-    let content_str_opt = msg.get("content").and_then(|t| t.as_str()).map(|s| s.to_string());
+pub async fn chat_complete_with_mcp(
+  app: tauri::AppHandle,
+  messages: Vec<ChatMessage>,
+  provider: &dyn ChatProvider,
+  model: String,
+  temp: Option<f32>,
+  mcp_clients: &AsyncMutex<std::collections::HashMap<String, Arc<RunningService<RoleClient, Box<dyn DynService<RoleClient>>>>>>,
+) -> Result<String, AidcError> {
+  let Prepared { msgs: mut msgs_for_oai, tools, allow_tools } = prepare(messages, &model, mcp_clients).await?;
+  let client = crate::timeouts::client(crate::timeouts::CHAT);
+  let mut final_text: Option<String> = None;
+  let offered: &[serde_json::Value] = if allow_tools { &tools } else { &[] };
 
-    if allow_tools && tool_calls_opt.is_some() {
-      let tool_calls = tool_calls_opt.unwrap();
-      // Append assistant message with tool_calls to history
-      let mut assistant_msg = serde_json::Map::new();
-      assistant_msg.insert("role".to_string(), serde_json::Value::String("assistant".to_string()));
-      // Always include content — OpenAI requires it even when null
-      assistant_msg.insert("content".to_string(),
-        content_str_opt.as_ref()
-          .map(|c| serde_json::Value::String(c.clone()))
-          .unwrap_or(serde_json::Value::Null));
-      assistant_msg.insert("tool_calls".to_string(), serde_json::Value::Array(tool_calls.clone()));
-      msgs_for_oai.push(serde_json::Value::Object(assistant_msg));
+  // Deterministic requests may be answered from the response cache; the offered tools are part of
+  // the key since they change what the model can answer
+  let cache_key = crate::response_cache::cacheable(temp).then(|| {
+    crate::response_cache::key(provider.id(), &model, &serde_json::json!({ "messages": msgs_for_oai, "tools": offered }))
+  });
+  if let Some(hit) = cache_key.as_deref().and_then(crate::response_cache::get) { return Ok(hit); }
+  let mut used_tools = false;
+
+  for _ in 0..6u8 {
+    let turn = provider.complete(&client, &model, &msgs_for_oai, offered, temp).await?;
+
+    if allow_tools && !turn.tool_calls.is_empty() {
+      used_tools = true;
+      // Append assistant message with tool_calls to history; content is required even when null
+      let content = turn.text.map(serde_json::Value::String).unwrap_or(serde_json::Value::Null);
+      msgs_for_oai.push(serde_json::json!({ "role": "assistant", "content": content, "tool_calls": turn.tool_calls }));
 
       // Dispatch each tool call sequentially and append tool results
-      for tc in tool_calls.into_iter() {
+      for tc in turn.tool_calls.into_iter() {
         let id = tc.get("id").and_then(|x| x.as_str()).unwrap_or("").to_string();
         let fname = tc.get("function").and_then(|f| f.get("name")).and_then(|x| x.as_str()).unwrap_or("").to_string();
         let fargs_str = tc.get("function").and_then(|f| f.get("arguments")).and_then(|x| x.as_str()).unwrap_or("{}");
         let mut fargs_val: serde_json::Value = serde_json::from_str(fargs_str).unwrap_or_else(|_| serde_json::json!({}));
         if !fargs_val.is_object() { fargs_val = serde_json::json!({}); }
 
-        if let Some((server_id, tool_name)) = mcp::parse_mcp_fn_call_name(&fname) {
-          let _ = app.emit("chat:tool-call", serde_json::json!({ "id": id, "function": fname, "serverId": server_id, "tool": tool_name, "args": fargs_val.clone() }));
-          // Respect disabled tools from settings
-          let disabled_map = crate::config::get_disabled_tools_map();
-          let is_disabled = disabled_map.get(&server_id).map(|set| set.contains(&tool_name)).unwrap_or(false);
-          let tool_result_text: String;
-          if is_disabled {
-            tool_result_text = serde_json::json!({ "serverId": server_id, "tool": tool_name, "error": "tool disabled by settings" }).to_string();
-            let _ = app.emit("chat:tool-result", serde_json::json!({ "id": id, "function": fname, "serverId": server_id, "tool": tool_name, "ok": false, "error": "tool disabled by settings" }));
-          } else {
-            let svc_opt = {
-              let map2 = mcp_clients.lock().await;
-              map2.get(&server_id).cloned()
-            };
-            if let Some(svc) = svc_opt {
-              let arg_map_opt = fargs_val.as_object().cloned();
-              match svc.call_tool(rmcp::model::CallToolRequestParam { name: tool_name.clone().into(), arguments: arg_map_opt }).await {
-                Ok(res) => {
-                  tool_result_text = serde_json::to_string(&serde_json::json!({ "serverId": server_id, "tool": tool_name, "result": res })).unwrap_or_else(|_| "{}".to_string());
-                  let _ = app.emit("chat:tool-result", serde_json::json!({ "id": id, "function": fname, "serverId": server_id, "tool": tool_name, "ok": true, "result": res }));
-                }
-                Err(e) => {
-                  tool_result_text = serde_json::json!({ "serverId": server_id, "tool": tool_name, "error": format!("call_tool failed: {}", e) }).to_string();
-                  let _ = app.emit("chat:tool-result", serde_json::json!({ "id": id, "function": fname, "serverId": server_id, "tool": tool_name, "ok": false, "error": format!("call_tool failed: {}", e) }));
-                }
-              }
-            } else {
-              tool_result_text = serde_json::json!({ "error": format!("MCP server not connected: {}", server_id) }).to_string();
-              let _ = app.emit("chat:tool-result", serde_json::json!({ "id": id, "function": fname, "serverId": server_id, "tool": tool_name, "ok": false, "error": format!("MCP server not connected: {}", server_id) }));
-            }
-          }
-
-          // Append tool result message
-          msgs_for_oai.push(serde_json::json!({ "role": "tool", "tool_call_id": id, "content": tool_result_text }));
-        } else {
-          let tool_result_text = serde_json::json!({ "error": format!("Unsupported tool function: {}", fname) }).to_string();
-          let _ = app.emit("chat:tool-result", serde_json::json!({ "id": id, "function": fname, "ok": false, "error": format!("Unsupported tool function: {}", fname) }));
-          msgs_for_oai.push(serde_json::json!({ "role": "tool", "tool_call_id": id, "content": tool_result_text }));
-        }
+        let tool_result_text = dispatch_tool_call(&app, mcp_clients, &id, &fname, fargs_val).await;
+        msgs_for_oai.push(serde_json::json!({ "role": "tool", "tool_call_id": id, "content": tool_result_text }));
       }
       // Continue loop for next assistant turn
       continue;
     }
 
-    final_text = Some(content_str_opt.unwrap_or_default());
+    final_text = Some(turn.text.unwrap_or_default());
     break;
   }
 
+  // Tool results depend on external state, so only tool-free answers are cached
+  if let (Some(k), Some(text), false) = (cache_key, final_text.as_ref(), used_tools) {
+    if !text.trim().is_empty() { crate::response_cache::put(k, text.clone()); }
+  }
   Ok(final_text.unwrap_or_else(|| "(Tool call loop exhausted after 6 rounds — no final response from model.)".to_string()))
 }
 
+// A tool call assembled from streamed deltas (fragments arrive keyed by index)
+#[derive(Default)]
+struct PendingToolCall {
+  id: String,
+  name: String,
+  arguments: String,
+}
+
+fn merge_tool_call_deltas(pending: &mut Vec<PendingToolCall>, deltas: &[serde_json::Value]) {
+  for d in deltas {
+    let index = d.get("index").and_then(|x| x.as_u64()).unwrap_or(0) as usize;
+    if pending.len() <= index {
+      pending.resize_with(index + 1, PendingToolCall::default);
+    }
+    let tc = &mut pending[index];
+    if let Some(id) = d.get("id").and_then(|x| x.as_str()) { tc.id.push_str(id); }
+    if let Some(f) = d.get("function") {
+      if let Some(n) = f.get("name").and_then(|x| x.as_str()) { tc.name.push_str(n); }
+      if let Some(a) = f.get("arguments").and_then(|x| x.as_str()) { tc.arguments.push_str(a); }
+    }
+  }
+}
+
+/// Like `chat_complete_with_mcp`, but with `stream: true`: text is emitted as `chat:stream:delta`
+/// while it arrives and `chat:stream:end` closes the stream (also on error). Tool call fragments
+/// are buffered until the round ends, then dispatched and the next round streams on. `stream_id`
+/// tags the events.
+pub async fn chat_complete_stream_with_mcp(
+  app: tauri::AppHandle,
+  messages: Vec<ChatMessage>,
+  key: String,
+  model: String,
+  temp: Option<f32>,
+  mcp_clients: &McpClients,
+  stream_id: &str,
+) -> Result<String, AidcError> {
+  use crate::events::{self, ChatStreamEnd};
+
+  let mut text = String::new();
+  let res = stream_rounds(&app, messages, &key, &model, temp, mcp_clients, stream_id, &mut text).await;
+  let error = res.as_ref().err().map(|e| e.message());
+  events::emit(&app, events::CHAT_STREAM_END, &ChatStreamEnd { id: stream_id.to_string(), text: text.clone(), error });
+  res.map(|_| text)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn stream_rounds(
+  app: &tauri::AppHandle,
+  messages: Vec<ChatMessage>,
+  key: &str,
+  model: &str,
+  temp: Option<f32>,
+  mcp_clients: &McpClients,
+  stream_id: &str,
+  text: &mut String,
+) -> Result<(), AidcError> {
+  use crate::events::{self, ChatStreamDelta};
+  use crate::tts_utils::{consume_leading_newlines, extract_sse_data, find_sse_event_boundary};
+  use futures_util::StreamExt;
+
+  let Prepared { msgs: mut msgs_for_oai, tools, allow_tools } = prepare(messages, model, mcp_clients).await?;
+  // No overall timeout: long answers stream for minutes; timeouts.chat bounds the wait for each chunk
+  let client = reqwest::Client::builder().connect_timeout(std::time::Duration::from_secs(10)).read_timeout(crate::timeouts::get(crate::timeouts::CHAT)).build().unwrap_or_else(|_| reqwest::Client::new());
+  let base_url = crate::config::get_openai_base_url_from_settings_or_env();
+  let url = format!("{base_url}/chat/completions");
+
+  for _ in 0..6u8 {
+    let offer_tools = allow_tools && !tools.is_empty() && !is_toolless(&base_url, model);
+    let mut body = serde_json::json!({ "model": model, "messages": msgs_for_oai, "stream": true, "stream_options": { "include_usage": true } });
+    if let serde_json::Value::Object(ref mut m) = body {
+      if let Some(t) = temp { m.insert("temperature".to_string(), serde_json::json!(t)); }
+      if offer_tools {
+        m.insert("tools".to_string(), serde_json::Value::Array(tools.clone()));
+        m.insert("tool_choice".to_string(), serde_json::Value::String("auto".to_string()));
+        m.insert("parallel_tool_calls".to_string(), serde_json::Value::Bool(true));
+      }
+    }
+
+    let resp = crate::rate_limit::send(crate::config::chat_rate_limit_provider(&base_url), "chat", || {
+      crate::settings::with_chat_key(client.post(&url), key).header("Accept", "text/event-stream").json(&body)
+    })
+    .await
+    .map_err(|e| AidcError::from_reqwest("openai", &e))?;
+    if !resp.status().is_success() {
+      let status = resp.status();
+      let body_text = resp.text().await.unwrap_or_default();
+      if offer_tools && !crate::config::is_default_openai_base_url(&base_url) && tools_rejected(status, &body_text) {
+        remember_toolless(&base_url, model);
+        continue;
+      }
+      return Err(AidcError::from_status("openai", status, &body_text));
+    }
+
+    let mut round_text = String::new();
+    let mut pending: Vec<PendingToolCall> = Vec::new();
+    let mut stream = resp.bytes_stream();
+    let mut buf: Vec<u8> = Vec::new();
+    'read: while let Some(chunk) = stream.next().await {
+      let chunk = chunk.map_err(|e| AidcError::from_reqwest("openai", &e))?;
+      buf.extend_from_slice(&chunk);
+      while let Some(pos) = find_sse_event_boundary(&buf) {
+        let ev_bytes = buf.drain(..pos).collect::<Vec<u8>>();
+        let _ = consume_leading_newlines(&mut buf);
+        let Some(data) = extract_sse_data(&ev_bytes) else { continue };
+        if data.trim() == "[DONE]" { break 'read; }
+        let Ok(val) = serde_json::from_str::<serde_json::Value>(&data) else { continue };
+        // With include_usage the last chunk carries usage and no choices
+        if val.get("usage").is_some_and(|u| !u.is_null()) {
+          crate::usage::record_response(crate::usage::CHAT, model, &val);
+        }
+        let Some(delta) = val.get("choices").and_then(|c| c.get(0)).and_then(|c| c.get("delta")) else { continue };
+        if let Some(piece) = delta.get("content").and_then(|x| x.as_str()).filter(|p| !p.is_empty()) {
+          round_text.push_str(piece);
+          text.push_str(piece);
+          events::emit(app, events::CHAT_STREAM_DELTA, &ChatStreamDelta { id: stream_id.to_string(), delta: piece.to_string() });
+        }
+        if let Some(deltas) = delta.get("tool_calls").and_then(|x| x.as_array()) {
+          merge_tool_call_deltas(&mut pending, deltas);
+        }
+      }
+    }
+
+    pending.retain(|tc| !tc.name.is_empty());
+    if !offer_tools || pending.is_empty() {
+      return Ok(());
+    }
+
+    let tool_calls: Vec<serde_json::Value> = pending
+      .iter()
+      .map(|tc| serde_json::json!({ "id": tc.id, "type": "function", "function": { "name": tc.name, "arguments": tc.arguments } }))
+      .collect();
+    let content = if round_text.is_empty() { serde_json::Value::Null } else { serde_json::Value::String(round_text) };
+    msgs_for_oai.push(serde_json::json!({ "role": "assistant", "content": content, "tool_calls": tool_calls }));
+    for tc in pending {
+      let mut fargs_val: serde_json::Value = serde_json::from_str(&tc.arguments).unwrap_or_else(|_| serde_json::json!({}));
+      if !fargs_val.is_object() { fargs_val = serde_json::json!({}); }
+      let result = dispatch_tool_call(app, mcp_clients, &tc.id, &tc.name, fargs_val).await;
+      msgs_for_oai.push(serde_json::json!({ "role": "tool", "tool_call_id": tc.id, "content": result }));
+    }
+  }
+  Err(AidcError::invalid_response("openai", "tool call loop exhausted after 6 rounds"))
+}
+
+/// Streaming chat: returns the full text once done; `chat:stream:delta` / `chat:stream:end` events
+/// carry the task id, so pass `task_id` to match them up (and to cancel).
+#[tauri::command]
+pub async fn chat_complete_stream(
+  app: tauri::AppHandle,
+  mut messages: Vec<ChatMessage>,
+  include_desktop_context: Option<bool>,
+  task_id: Option<String>,
+) -> Result<String, AidcError> {
+  let (provider, model) = provider_from_settings()?;
+  if include_desktop_context.unwrap_or(false) {
+    crate::context::inject(&app, &mut messages).await?;
+  }
+  let temp = crate::settings::get_temperature_from_settings_or_env();
+  let task = crate::tasks::register("chat", format!("Chat ({model}, streaming)"), task_id);
+  let label = format!("Chat ({model})");
+  let stream_id = task.id().to_string();
+  let res = if provider.id() == "openai" {
+    let key = crate::settings::require_chat_key(&crate::config::get_openai_base_url_from_settings_or_env())?;
+    task.run(chat_complete_stream_with_mcp(app.clone(), messages, key, model, temp, &crate::MCP_CLIENTS, &stream_id)).await
+  } else {
+    // Other providers answer in one piece: a single delta, then the end event
+    task
+      .run(async {
+        use crate::events::{self, ChatStreamDelta, ChatStreamEnd};
+        let res = chat_complete_with_mcp(app.clone(), messages, provider.as_ref(), model, temp, &crate::MCP_CLIENTS).await;
+        if let Ok(text) = &res {
+          events::emit(&app, events::CHAT_STREAM_DELTA, &ChatStreamDelta { id: stream_id.clone(), delta: text.clone() });
+        }
+        let end = ChatStreamEnd { id: stream_id.clone(), text: res.as_ref().cloned().unwrap_or_default(), error: res.as_ref().err().map(|e| e.message()) };
+        events::emit(&app, events::CHAT_STREAM_END, &end);
+        res
+      })
+      .await
+  };
+  // A cancelled stream is dropped mid-read; close it for listeners here
+  if let Err(AidcError::Cancelled) = &res {
+    let end = crate::events::ChatStreamEnd { id: stream_id.clone(), text: String::new(), error: Some(AidcError::Cancelled.message()) };
+    crate::events::emit(&app, crate::events::CHAT_STREAM_END, &end);
+  }
+  notify_if_cancelled(&app, &stream_id, &res);
+  if let Ok(text) = &res {
+    crate::recent_outputs::record(crate::recent_outputs::CHAT, label, text);
+  }
+  res
+}
+
+/// Stop a running chat request (chat_complete, chat_complete_stream or the Responses chat) by
+/// its task id. The in-flight HTTP request and any pending MCP tool calls are dropped; the
+/// command itself returns `Cancelled`. Returns false when no chat with that id is running.
+#[tauri::command]
+pub fn chat_cancel(id: String) -> Result<bool, String> {
+  Ok(crate::tasks::cancel(&id, Some("chat")))
+}
+
+/// Emit `chat:cancelled` when a chat task ended because it was cancelled.
+pub(crate) fn notify_if_cancelled<T>(app: &tauri::AppHandle, id: &str, res: &Result<T, AidcError>) {
+  if let Err(AidcError::Cancelled) = res {
+    crate::events::emit(app, crate::events::CHAT_CANCELLED, &crate::events::ChatCancelled { id: id.to_string() });
+  }
+}
+
+fn has_image(msg: &serde_json::Value) -> bool {
+  msg.get("content").and_then(|c| c.as_array()).is_some_and(|parts| parts.iter().any(|p| p.get("type").and_then(|t| t.as_str()) == Some("image_url")))
+}
+
+// Rough token estimate: ~4 characters per token, images at a flat rate
+fn estimate_tokens(msg: &serde_json::Value) -> usize {
+  const IMAGE_TOKENS: usize = 850;
+  match msg.get("content") {
+    Some(serde_json::Value::String(s)) => s.len() / 4 + 4,
+    Some(serde_json::Value::Array(parts)) => parts
+      .iter()
+      .map(|p| match p.get("type").and_then(|x| x.as_str()) {
+        Some("image_url") => IMAGE_TOKENS,
+        _ => p.get("text").and_then(|x| x.as_str()).map(|t| t.len() / 4).unwrap_or(0),
+      })
+      .sum::<usize>() + 4,
+    _ => 4,
+  }
+}
+
+// Drop the oldest turns (never system messages or the latest message) until the history fits the
+// model's context window with room left for the reply
+fn fit_to_context(msgs: &mut Vec<serde_json::Value>, window: usize, fixed_tokens: usize) {
+  let budget = window.saturating_sub((window / 8).min(16_384)).saturating_sub(fixed_tokens);
+  let mut total: usize = msgs.iter().map(estimate_tokens).sum();
+  let mut dropped = 0usize;
+  while total > budget {
+    let last = msgs.len().saturating_sub(1);
+    let Some(i) = msgs.iter().take(last).position(|m| m.get("role").and_then(|r| r.as_str()) != Some("system")) else { break };
+    total -= estimate_tokens(&msgs[i]);
+    msgs.remove(i);
+    dropped += 1;
+  }
+  if dropped > 0 {
+    tracing::info!(dropped, window, "chat history truncated to fit the context window");
+  }
+}
+
+/// Run one model tool call against the connected MCP servers and return the text handed back to
+/// the model. Emits `chat:tool-call` / `chat:tool-result` for the UI.
+pub(crate) async fn dispatch_tool_call(
+  app: &tauri::AppHandle,
+  mcp_clients: &AsyncMutex<std::collections::HashMap<String, Arc<RunningService<RoleClient, Box<dyn DynService<RoleClient>>>>>>,
+  id: &str,
+  fname: &str,
+  fargs_val: serde_json::Value,
+) -> String {
+  use crate::events::{self, ToolCall, ToolResult};
+  use crate::mcp;
+
+  let failed = |server_id: Option<&str>, tool: Option<&str>, error: String| {
+    events::emit(app, events::CHAT_TOOL_RESULT, &ToolResult {
+      id: id.to_string(),
+      function: fname.to_string(),
+      server_id: server_id.map(|s| s.to_string()),
+      tool: tool.map(|s| s.to_string()),
+      ok: false,
+      result: None,
+      error: Some(error),
+    });
+  };
+  // The active persona's tool profile limits what may run, not only what is offered
+  if !crate::personas::allows_tool(fname) {
+    failed(None, None, "tool not enabled for the active persona".to_string());
+    return serde_json::json!({ "error": "tool not enabled for the active persona" }).to_string();
+  }
+  // Built-in memory, drafting and code tools are answered locally; their prefix is the server id in the UI
+  let builtin = match crate::memory::call_tool(fname, &fargs_val) {
+    Some(res) => Some(("memory", res)),
+    None => match crate::drafts::call_tool(fname, &fargs_val).await {
+      Some(res) => Some(("drafts", res)),
+      None => crate::code_exec::call_tool(app, fname, &fargs_val).await.map(|res| ("code", res)),
+    },
+  };
+  if let Some((server_id, res)) = builtin {
+    let tool = fname.trim_start_matches(server_id).trim_start_matches("__").to_string();
+    events::emit(app, events::CHAT_TOOL_CALL, &ToolCall { id: id.to_string(), function: fname.to_string(), server_id: server_id.into(), tool: tool.clone(), args: fargs_val.clone() });
+    return match res {
+      Ok(v) => {
+        events::emit(app, events::CHAT_TOOL_RESULT, &ToolResult { id: id.to_string(), function: fname.to_string(), server_id: Some(server_id.into()), tool: Some(tool), ok: true, result: Some(v.clone()), error: None });
+        v.to_string()
+      }
+      Err(e) => {
+        failed(Some(server_id), Some(&tool), e.clone());
+        serde_json::json!({ "error": e }).to_string()
+      }
+    };
+  }
+  let Some((server_id, tool_name)) = mcp::parse_mcp_fn_call_name(fname) else {
+    failed(None, None, format!("Unsupported tool function: {}", fname));
+    return serde_json::json!({ "error": format!("Unsupported tool function: {}", fname) }).to_string();
+  };
+  events::emit(app, events::CHAT_TOOL_CALL, &ToolCall { id: id.to_string(), function: fname.to_string(), server_id: server_id.clone(), tool: tool_name.clone(), args: fargs_val.clone() });
+  // Respect disabled tools from settings
+  let disabled_map = crate::config::get_disabled_tools_map();
+  let is_disabled = disabled_map.get(&server_id).map(|set| set.contains(&tool_name)).unwrap_or(false);
+  if is_disabled {
+    failed(Some(&server_id), Some(&tool_name), "tool disabled by settings".to_string());
+    return serde_json::json!({ "serverId": server_id, "tool": tool_name, "error": "tool disabled by settings" }).to_string();
+  }
+  let svc_opt = {
+    let map2 = mcp_clients.lock().await;
+    map2.get(&server_id).cloned()
+  };
+  let Some(svc) = svc_opt else {
+    failed(Some(&server_id), Some(&tool_name), format!("MCP server not connected: {}", server_id));
+    return serde_json::json!({ "error": format!("MCP server not connected: {}", server_id) }).to_string();
+  };
+  let arg_map_opt = fargs_val.as_object().cloned();
+  let called = match mcp::authorize_destructive(&svc, &server_id, &tool_name).await {
+    Ok(()) => svc.call_tool(rmcp::model::CallToolRequestParam { name: tool_name.clone().into(), arguments: arg_map_opt }).await.map_err(|e| format!("call_tool failed: {}", e)),
+    Err(e) => Err(e.message()),
+  };
+  match called {
+    Ok(res) => {
+      let result_val = serde_json::to_value(&res).unwrap_or(serde_json::Value::Null);
+      let text = crate::guard::guard_json(app, &format!("mcp:{}/{}", server_id, tool_name), serde_json::json!({ "serverId": server_id, "tool": tool_name, "result": result_val.clone() }));
+      events::emit(app, events::CHAT_TOOL_RESULT, &ToolResult {
+        id: id.to_string(),
+        function: fname.to_string(),
+        server_id: Some(server_id),
+        tool: Some(tool_name),
+        ok: true,
+        result: Some(result_val),
+        error: None,
+      });
+      text
+    }
+    Err(e) => {
+      failed(Some(&server_id), Some(&tool_name), e.clone());
+      serde_json::json!({ "serverId": server_id, "tool": tool_name, "error": e }).to_string()
+    }
+  }
+}
+
+/// Read an attached image (only files under the temp directory) as a base64 data URL.
+pub(crate) fn image_data_url(path: &str, mime: Option<String>) -> Result<String, AidcError> {
+  let file_path = std::path::PathBuf::from(path);
+  let temp_dir = std::env::temp_dir();
+  let temp_canon = std::fs::canonicalize(&temp_dir).unwrap_or(temp_dir.clone());
+  let file_canon = std::fs::canonicalize(&file_path).map_err(|e| AidcError::InvalidInput(format!("Invalid image path '{}': {}", path, e)))?;
+  if !file_canon.starts_with(&temp_canon) {
+    return Err(AidcError::InvalidInput(format!("Image path '{}' is outside temp directory — refusing to read", path)));
+  }
+  let mime_final = mime.or_else(|| guess_mime_from_path_rs(path).map(|s| s.to_string())).ok_or_else(|| AidcError::InvalidInput(format!("Missing/unknown image MIME for: {}", path)))?;
+  let bytes = fs::read(&file_canon).map_err(|e| AidcError::InvalidInput(format!("Failed to read image '{}': {}", path, e)))?;
+  let b64 = base64::engine::general_purpose::STANDARD.encode(bytes);
+  Ok(format!("data:{};base64,{}", mime_final, b64))
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum ChatContent {
@@ -228,7 +679,6 @@ pub fn guess_mime_from_path_rs(path: &str) -> Option<&'static str> {
   None
 }
 
-#[allow(dead_code)]
 pub async fn chat_complete(
   _app: tauri::AppHandle,
   messages: Vec<ChatMessage>,
@@ -236,7 +686,7 @@ pub async fn chat_complete(
   model: String,
   temp: Option<f32>,
   tools: Vec<serde_json::Value>,
-) -> Result<String, String> {
+) -> Result<String, AidcError> {
   // Normalize incoming messages to OpenAI format
   let mut norm_msgs: Vec<serde_json::Value> = Vec::new();
   for m in messages.into_iter() {
@@ -259,14 +709,14 @@ pub async fn chat_complete(
               let file_path = std::path::PathBuf::from(&path);
               let temp_dir = std::env::temp_dir();
               let temp_canon = std::fs::canonicalize(&temp_dir).unwrap_or(temp_dir.clone());
-              let file_canon = std::fs::canonicalize(&file_path).map_err(|e| format!("Invalid image path '{}': {}", path, e))?;
+              let file_canon = std::fs::canonicalize(&file_path).map_err(|e| AidcError::InvalidInput(format!("Invalid image path '{}': {}", path, e)))?;
               if !file_canon.starts_with(&temp_canon) {
-                return Err(format!("Image path '{}' is outside temp directory — refusing to read", path));
+                return Err(AidcError::InvalidInput(format!("Image path '{}' is outside temp directory — refusing to read", path)));
               }
               let mime_final = mime
                 .or_else(|| guess_mime_from_path_rs(&path).map(|s| s.to_string()))
-                .ok_or_else(|| format!("Missing/unknown image MIME for: {}", path))?;
-              let bytes = fs::read(&file_canon).map_err(|e| format!("Failed to read image '{}': {}", path, e))?;
+                .ok_or_else(|| AidcError::InvalidInput(format!("Missing/unknown image MIME for: {}", path)))?;
+              let bytes = fs::read(&file_canon).map_err(|e| AidcError::InvalidInput(format!("Failed to read image '{}': {}", path, e)))?;
               let b64 = base64::engine::general_purpose::STANDARD.encode(bytes);
               let url = format!("data:{};base64,{}", mime_final, b64);
               out_parts.push(serde_json::json!({ "type": "image_url", "image_url": { "url": url } }));
@@ -279,7 +729,7 @@ pub async fn chat_complete(
     norm_msgs.push(serde_json::json!({ "role": r, "content": content_value }));
   }
 
-  let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(120)).connect_timeout(std::time::Duration::from_secs(10)).build().unwrap_or_else(|_| reqwest::Client::new());
+  let client = crate::timeouts::client(crate::timeouts::CHAT);
   // Prepend a short system directive to improve first-call argument completeness
   let sys_tool_guidance = serde_json::json!({
     "role": "system",
@@ -310,21 +760,20 @@ pub async fn chat_complete(
       }
     }
 
-    let resp = client
-      .post("https://api.openai.com/v1/chat/completions")
-      .bearer_auth(&key)
-      .json(&body)
-      .send()
-      .await
-      .map_err(|e| format!("request failed: {e}"))?;
+    let resp = crate::rate_limit::send("openai", "chat", || {
+      client.post("https://api.openai.com/v1/chat/completions").bearer_auth(&key).json(&body)
+    })
+    .await
+    .map_err(|e| AidcError::from_reqwest("openai", &e))?;
 
     if !resp.status().is_success() {
       let status = resp.status();
       let body_text = resp.text().await.unwrap_or_default();
-      return Err(format!("OpenAI error: {status} {body_text}"));
+      return Err(AidcError::from_status("openai", status, &body_text));
     }
 
-    let v: serde_json::Value = resp.json().await.map_err(|e| format!("json error: {e}"))?;
+    let v: serde_json::Value = resp.json().await.map_err(|e| AidcError::invalid_response("openai", format!("json error: {e}")))?;
+    crate::usage::record_response(crate::usage::CHAT, &model, &v);
     let choice0 = v.get("choices").and_then(|c| c.get(0)).cloned().unwrap_or(serde_json::Value::Null);
     let msg = choice0.get("message").cloned().unwrap_or(serde_json::Value::Null);
     let tool_calls_opt = msg.get("tool_calls").and_then(|x| x.as_array()).cloned();
@@ -0,0 +1,240 @@
+// Clipboard helpers shared by the selection capture flows (quick actions, quick prompts)
+
+use std::path::PathBuf;
+use std::{thread, time::Duration};
+
+use arboard::{Clipboard, ImageData};
+use enigo::{Enigo, Key, KeyboardControllable};
+
+use crate::app_profiles::AppProfile;
+
+/// Best-effort copy of the clipboard in its major formats (text, HTML, image, file list),
+/// taken before a simulated Ctrl+C/Ctrl+V so the user's clipboard can be put back afterwards.
+pub struct ClipboardSnapshot {
+  text: Option<String>,
+  // HTML flavor exactly as found (the whole CF_HTML payload on Windows), so restoring does not
+  // alter it; prompts read it through `read_html`, which cleans it
+  html: Option<Vec<u8>>,
+  image: Option<ImageData<'static>>,
+  files: Option<Vec<PathBuf>>,
+}
+
+impl ClipboardSnapshot {
+  pub fn take(clipboard: &mut Clipboard) -> Self {
+    let files = clipboard.get().file_list().ok().filter(|f| !f.is_empty());
+    // A file copy usually carries a thumbnail too; the file list is what matters then
+    let image = if files.is_none() { clipboard.get_image().ok() } else { None };
+    let text = clipboard.get_text().ok();
+    let html = get_raw_html(clipboard);
+    ClipboardSnapshot { text, html, image, files }
+  }
+
+  /// Write the snapshot back. Each write replaces the whole clipboard, so the richest
+  /// format wins: file list, then image, then HTML (with its plain-text alternative), then text.
+  pub fn restore(self, clipboard: &mut Clipboard) {
+    if let Some(files) = self.files.as_deref() {
+      if set_file_list(files) { return; }
+    }
+    if let Some(image) = self.image {
+      if clipboard.set_image(image).is_ok() { return; }
+    }
+    if let Some(html) = self.html.as_deref() {
+      if set_raw_html(clipboard, html, self.text.as_deref()) { return; }
+    }
+    if let Some(text) = self.text {
+      let _ = clipboard.set_text(text);
+    }
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn set_file_list(files: &[PathBuf]) -> bool {
+  let paths: Vec<String> = files.iter().map(|p| p.to_string_lossy().to_string()).collect();
+  let _guard = match clipboard_win::Clipboard::new_attempts(10) {
+    Ok(g) => g,
+    Err(_) => return false,
+  };
+  clipboard_win::raw::set_file_list_with(&paths, clipboard_win::options::DoClear).is_ok()
+}
+
+// arboard cannot write file lists on other platforms; fall back to the next format
+#[cfg(not(target_os = "windows"))]
+fn set_file_list(_files: &[PathBuf]) -> bool {
+  false
+}
+
+// arboard's set_html builds a new CF_HTML header, so the registered format is copied byte for byte
+#[cfg(target_os = "windows")]
+fn get_raw_html(_clipboard: &mut Clipboard) -> Option<Vec<u8>> {
+  let format = clipboard_win::register_format("HTML Format")?;
+  let _guard = clipboard_win::Clipboard::new_attempts(10).ok()?;
+  let mut out = Vec::new();
+  clipboard_win::raw::get_vec(format.get(), &mut out).ok()?;
+  Some(out).filter(|b| !b.is_empty())
+}
+
+#[cfg(target_os = "windows")]
+fn set_raw_html(_clipboard: &mut Clipboard, html: &[u8], text: Option<&str>) -> bool {
+  let Some(format) = clipboard_win::register_format("HTML Format") else { return false };
+  let _guard = match clipboard_win::Clipboard::new_attempts(10) {
+    Ok(g) => g,
+    Err(_) => return false,
+  };
+  if clipboard_win::raw::set(format.get(), html).is_err() {
+    return false;
+  }
+  if let Some(text) = text {
+    let _ = clipboard_win::raw::set_string_with(text, clipboard_win::options::NoClear);
+  }
+  true
+}
+
+#[cfg(not(target_os = "windows"))]
+fn get_raw_html(clipboard: &mut Clipboard) -> Option<Vec<u8>> {
+  clipboard.get().html().ok().filter(|h| !h.trim().is_empty()).map(String::into_bytes)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn set_raw_html(clipboard: &mut Clipboard, html: &[u8], text: Option<&str>) -> bool {
+  clipboard.set_html(String::from_utf8_lossy(html).into_owned(), text.map(str::to_string)).is_ok()
+}
+
+// Selection features with their own safe-mode default (settings: `safe_mode_defaults`)
+pub const SAFE_QUICK_PROMPTS: &str = "quick_prompts";
+pub const SAFE_TTS_SELECTION: &str = "tts_selection";
+pub const SAFE_PROMPT_ACTION: &str = "prompt_action";
+pub const SAFE_NOTES: &str = "notes";
+
+// Upper bound for read-from-cursor; about an hour of speech
+const READ_FROM_CURSOR_MAX_CHARS: usize = 60_000;
+
+/// Effective safe mode for a selection capture: an explicit `safe_mode` argument wins, then the
+/// app profile's `safe_mode`, then the feature's default from `safe_mode_defaults`.
+pub fn resolve_safe_mode(feature: &str, safe_mode: Option<bool>, profile: &AppProfile) -> bool {
+  safe_mode.or(profile.safe_mode).unwrap_or_else(|| crate::config::get_safe_mode_default_from_settings(feature))
+}
+
+/// Text (and HTML flavor, when enabled) captured from the focused application.
+pub struct CapturedSelection {
+  pub text: String,
+  pub html: Option<String>,
+}
+
+/// Capture the selection of the focused application according to its app profile.
+/// "uia" profiles read the selection through UI Automation first; otherwise (or when UIA
+/// yields nothing) Ctrl+C is sent and the previous clipboard content restored afterwards.
+/// On Linux the primary selection is read first, which needs no keys and leaves the clipboard alone.
+/// In safe mode no keys are sent and the current clipboard content is returned.
+pub fn capture_selection(safe: bool, profile: &AppProfile) -> Result<CapturedSelection, String> {
+  if profile.capture_method == "uia" {
+    if let Some(text) = crate::uia::focused_selection_text().filter(|t| !t.trim().is_empty()) {
+      return Ok(CapturedSelection { text, html: None });
+    }
+  }
+  #[cfg(target_os = "linux")]
+  if let Some(text) = crate::linux_input::primary_selection() {
+    return Ok(CapturedSelection { text, html: None });
+  }
+
+  let mut clipboard = Clipboard::new().map_err(|e| format!("clipboard init failed: {e}"))?;
+  let previous = if !safe { Some(ClipboardSnapshot::take(&mut clipboard)) } else { None };
+
+  if !safe {
+    send_copy();
+    // Allow some time for clipboard to update
+    thread::sleep(Duration::from_millis(profile.copy_delay_ms));
+  }
+
+  let text = clipboard.get_text().unwrap_or_default();
+  let html = read_html_if_enabled(&mut clipboard);
+
+  if let Some(prev) = previous { prev.restore(&mut clipboard); }
+
+  Ok(CapturedSelection { text, html })
+}
+
+/// `capture_selection` with the safe mode resolved for `feature` (see `resolve_safe_mode`).
+pub fn capture_selection_for(feature: &str, safe_mode: Option<bool>, profile: &AppProfile) -> Result<CapturedSelection, String> {
+  capture_selection(resolve_safe_mode(feature, safe_mode, profile), profile)
+}
+
+/// Text for "read selection aloud". With `tts_read_from_cursor` on, the focused control is read
+/// from the caret to its end (or its selection) through UI Automation, so long documents need no
+/// select-all first; controls without UIA text support fall back to the selection.
+pub fn capture_tts_text(safe_mode: Option<bool>, profile: &AppProfile) -> Result<String, String> {
+  if crate::config::get_tts_read_from_cursor_from_settings() {
+    if let Some(text) = crate::uia::text_from_caret(READ_FROM_CURSOR_MAX_CHARS) {
+      return Ok(text);
+    }
+  }
+  Ok(capture_selection_for(SAFE_TTS_SELECTION, safe_mode, profile)?.text)
+}
+
+fn send_copy() {
+  #[cfg(target_os = "linux")]
+  if crate::linux_input::send_chord("ctrl", "c") {
+    return;
+  }
+  let mut enigo = Enigo::new();
+  enigo.key_down(Key::Control);
+  enigo.key_click(Key::Layout('c'));
+  enigo.key_up(Key::Control);
+}
+
+/// Read the HTML flavor (CF_HTML on Windows) of the current clipboard content, if any.
+/// Returns the cleaned fragment or None when no HTML is available.
+pub fn read_html(clipboard: &mut Clipboard) -> Option<String> {
+  let raw = clipboard.get().html().ok()?;
+  let cleaned = clean_html_fragment(&raw);
+  if cleaned.trim().is_empty() { None } else { Some(cleaned) }
+}
+
+/// Read HTML only when the user opted into rich selections (selection_format != "plain").
+pub fn read_html_if_enabled(clipboard: &mut Clipboard) -> Option<String> {
+  if crate::config::get_selection_format_from_settings() == "plain" { return None; }
+  read_html(clipboard)
+}
+
+/// Reduce a CF_HTML payload to the copied fragment: drops the CF_HTML header, everything
+/// outside the StartFragment/EndFragment markers, comments, and script/style blocks.
+pub fn clean_html_fragment(raw: &str) -> String {
+  let mut s = raw.to_string();
+  if let (Some(a), Some(b)) = (s.find("<!--StartFragment-->"), s.find("<!--EndFragment-->")) {
+    if b > a { s = s[a + "<!--StartFragment-->".len()..b].to_string(); }
+  } else if s.starts_with("Version:") {
+    if let Some(idx) = s.find('<') { s = s[idx..].to_string(); }
+  }
+  s = strip_blocks(&s, "<!--", "-->");
+  s = strip_blocks(&s, "<script", "</script>");
+  s = strip_blocks(&s, "<style", "</style>");
+  s.trim().to_string()
+}
+
+// Remove every `open ... close` block (ASCII case-insensitive). Unterminated blocks are cut to the end.
+fn strip_blocks(s: &str, open: &str, close: &str) -> String {
+  let lower = s.to_ascii_lowercase();
+  let mut out = String::with_capacity(s.len());
+  let mut pos = 0usize;
+  while let Some(rel) = lower[pos..].find(open) {
+    let start = pos + rel;
+    out.push_str(&s[pos..start]);
+    match lower[start..].find(close) {
+      Some(end_rel) => { pos = start + end_rel + close.len(); }
+      None => { pos = s.len(); break; }
+    }
+  }
+  if pos < s.len() { out.push_str(&s[pos..]); }
+  out
+}
+
+/// Build the user content sent to the model from a captured selection, honoring the
+/// `selection_format` preference: "plain" (text only), "html" (HTML when available) or
+/// "both" (text followed by the HTML source).
+pub fn compose_selection_content(text: &str, html: Option<&str>) -> String {
+  let html = html.map(|h| h.trim()).filter(|h| !h.is_empty());
+  match (crate::config::get_selection_format_from_settings().as_str(), html) {
+    ("html", Some(h)) => h.to_string(),
+    ("both", Some(h)) => format!("{text}\n\n[HTML source of the selection]\n```html\n{h}\n```"),
+    _ => text.to_string(),
+  }
+}
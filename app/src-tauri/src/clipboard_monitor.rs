@@ -0,0 +1,172 @@
+// Opt-in clipboard monitor: classifies newly copied text and proposes a matching quick prompt
+// via `clipboard:suggestion` events. Enabled with the `clipboard_suggestions_enabled` setting.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{thread, time::Duration};
+
+use arboard::Clipboard;
+use serde::Serialize;
+use tauri::Emitter;
+
+static MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+
+const POLL_INTERVAL_MS: u64 = 1500;
+const MAX_CLASSIFY_CHARS: usize = 20_000;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ClipboardSuggestion {
+  pub kind: String,
+  pub reason: String,
+  pub preview: String,
+  pub prompt_index: Option<u8>,
+  pub prompt_label: Option<String>,
+}
+
+/// Start the background monitor thread once. The thread idles while the setting is off.
+pub fn start(app: tauri::AppHandle) {
+  if MONITOR_STARTED.swap(true, Ordering::SeqCst) { return; }
+  thread::spawn(move || {
+    let mut last_seen: Option<String> = None;
+    let mut last_classified: Option<String> = None;
+    loop {
+      thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+      if !crate::config::get_clipboard_suggestions_enabled_from_settings() {
+        last_seen = None;
+        continue;
+      }
+      let current = match Clipboard::new().and_then(|mut c| c.get_text()) {
+        Ok(t) => t,
+        Err(_) => continue,
+      };
+      // Require the content to be stable across two polls so the transient copy-restore
+      // writes of our own selection capture never trigger suggestions.
+      let stable = last_seen.as_deref() == Some(current.as_str());
+      last_seen = Some(current.clone());
+      if !stable || last_classified.as_deref() == Some(current.as_str()) { continue; }
+      last_classified = Some(current.clone());
+      if let Some(s) = suggest_for_text(&current) {
+        let _ = app.emit("clipboard:suggestion", &s);
+      }
+    }
+  });
+}
+
+/// Classify text and attach the best matching quick prompt. None when nothing stands out.
+pub fn suggest_for_text(text: &str) -> Option<ClipboardSuggestion> {
+  let trimmed = text.trim();
+  if trimmed.chars().count() < 12 || trimmed.len() > MAX_CLASSIFY_CHARS { return None; }
+  let (kind, reason) = classify(trimmed)?;
+  let prompts = crate::quick_prompts::get_quick_prompts().unwrap_or_else(|_| serde_json::json!({}));
+  let keywords: &[&str] = match kind {
+    "foreign_language" => &["translate"],
+    "error_message" => &["explain", "step-by-step"],
+    "meeting_invite" => &["action items", "email reply", "checklist"],
+    "address" => &["extract", "summar"],
+    _ => &[],
+  };
+  let mut prompt_index = None;
+  let mut prompt_label = None;
+  'outer: for kw in keywords {
+    for i in 1..=9u8 {
+      if let Some(t) = prompts.get(i.to_string()).and_then(|x| x.as_str()) {
+        if t.to_lowercase().contains(kw) {
+          prompt_index = Some(i);
+          prompt_label = Some(t.to_string());
+          break 'outer;
+        }
+      }
+    }
+  }
+  Some(ClipboardSuggestion {
+    kind: kind.to_string(),
+    reason: reason.to_string(),
+    preview: trimmed.chars().take(200).collect(),
+    prompt_index,
+    prompt_label,
+  })
+}
+
+/// Heuristic content classifier. Order matters: the most specific signals are checked first.
+pub fn classify(text: &str) -> Option<(&'static str, &'static str)> {
+  let lower = text.to_lowercase();
+  if is_meeting_invite(&lower) { return Some(("meeting_invite", "Looks like a meeting invitation")); }
+  if is_error_message(text, &lower) { return Some(("error_message", "Looks like an error message or stack trace")); }
+  if is_address(&lower) { return Some(("address", "Looks like a postal address")); }
+  if is_foreign_language(&lower) { return Some(("foreign_language", "Looks like non-English text")); }
+  None
+}
+
+fn is_meeting_invite(lower: &str) -> bool {
+  const LINKS: [&str; 5] = ["zoom.us/j/", "teams.microsoft.com/l/meetup-join", "meet.google.com/", "webex.com/meet", "begin:vcalendar"];
+  if LINKS.iter().any(|l| lower.contains(l)) { return true; }
+  let words = ["meeting", "invitation", "agenda", "join", "calendar"];
+  let hits = words.iter().filter(|w| lower.contains(*w)).count();
+  hits >= 2 && has_time_of_day(lower)
+}
+
+fn has_time_of_day(lower: &str) -> bool {
+  let b = lower.as_bytes();
+  for i in 1..b.len().saturating_sub(2) {
+    if b[i] == b':' && b[i - 1].is_ascii_digit() && b[i + 1].is_ascii_digit() && b[i + 2].is_ascii_digit() {
+      return true;
+    }
+  }
+  false
+}
+
+fn is_error_message(text: &str, lower: &str) -> bool {
+  const MARKERS: [&str; 8] = [
+    "traceback (most recent call last)",
+    "panicked at",
+    "exception in thread",
+    "unhandled exception",
+    "stack trace",
+    "segmentation fault",
+    "npm err!",
+    "fatal error",
+  ];
+  if MARKERS.iter().any(|m| lower.contains(m)) { return true; }
+  let error_lines = text
+    .lines()
+    .filter(|l| {
+      let t = l.trim_start();
+      t.starts_with("at ") || t.starts_with("error") || t.starts_with("Error") || t.contains("Error:") || t.contains("Exception:") || t.starts_with("error[E")
+    })
+    .count();
+  error_lines >= 2 || (error_lines == 1 && text.lines().count() <= 3)
+}
+
+fn is_address(lower: &str) -> bool {
+  if lower.len() > 400 { return false; }
+  const STREET: [&str; 12] = [" street", " st.", " avenue", " ave", " road", " rd.", " blvd", " lane", "straße", "strasse", " rue ", " calle "];
+  if !STREET.iter().any(|s| lower.contains(s)) { return false; }
+  // Postal code: a run of 4-5 digits
+  let mut run = 0usize;
+  let mut has_postal = false;
+  for ch in lower.chars() {
+    if ch.is_ascii_digit() { run += 1; } else { if run == 4 || run == 5 { has_postal = true; } run = 0; }
+  }
+  has_postal || run == 4 || run == 5
+}
+
+fn is_foreign_language(lower: &str) -> bool {
+  let letters: Vec<char> = lower.chars().filter(|c| c.is_alphabetic()).collect();
+  if letters.len() < 20 { return false; }
+  let non_ascii = letters.iter().filter(|c| !c.is_ascii()).count();
+  if non_ascii * 10 >= letters.len() * 3 { return true; }
+  const EN: [&str; 8] = ["the", "and", "is", "are", "of", "to", "with", "this"];
+  const OTHER: [&str; 16] = ["der", "die", "und", "ist", "nicht", "le", "la", "les", "est", "et", "el", "los", "las", "es", "y", "que"];
+  let mut en = 0usize;
+  let mut other = 0usize;
+  for w in lower.split(|c: char| !c.is_alphabetic()).filter(|w| !w.is_empty()) {
+    if EN.contains(&w) { en += 1; }
+    if OTHER.contains(&w) { other += 1; }
+  }
+  other >= 3 && other > en * 2
+}
+
+/// Classify arbitrary text on demand (used by the settings UI to preview the feature).
+#[tauri::command]
+pub fn clipboard_classify_text(text: String) -> Result<Option<ClipboardSuggestion>, String> {
+  Ok(suggest_for_text(&text))
+}
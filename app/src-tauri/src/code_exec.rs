@@ -0,0 +1,415 @@
+// Built-in code runner: `code__run_code` lets the chat model run a short Python or PowerShell
+// snippet (reshaping data, calculations, format conversions) and read back its output. Offered
+// only when `code_exec_enabled` is on, and every run needs the user's approval: `code:approval`
+// (events::CodeApproval) carries the code, the UI answers through `code_exec_respond`, and no
+// answer within APPROVAL_TIMEOUT counts as a refusal.
+//
+// Snippets run as a subprocess in a fresh temp folder with a minimal environment, killed together
+// with everything they started after `code_exec_timeout_secs` or once their memory passes
+// `code_exec_memory_mb` (a kill-on-close job object on Windows, a process group elsewhere).
+// Unless `code_exec_allow_network` is on, Python's socket module refuses to connect and
+// PowerShell runs in ConstrainedLanguage mode with the web cmdlets replaced. Only on Linux is the
+// network also cut at the OS level (a fresh network namespace through `unshare -rn`, when user
+// namespaces are allowed); elsewhere a determined snippet can still reach the network, and the
+// approval request says so (`network_isolated`). It is not a sandbox, which is why each run is
+// approved.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::{Lazy, OnceCell};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::oneshot;
+
+pub const RUN_CODE_TOOL: &str = "code__run_code";
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_CODE_CHARS: usize = 20_000;
+// Kept per stream; the rest is read and dropped so the child never blocks on a full pipe
+const MAX_OUTPUT_BYTES: usize = 16_000;
+const MEMORY_POLL: Duration = Duration::from_millis(100);
+const NETWORK_DISABLED: &str = "network access is disabled for this snippet";
+// Proxy settings pointing nowhere, for HTTP clients that bypass the blocks below
+const DEAD_PROXY: &str = "http://127.0.0.1:9";
+
+static PENDING: Lazy<Mutex<HashMap<String, oneshot::Sender<bool>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// Whether `unshare -rn` works here (checked once)
+static UNSHARE_WORKS: OnceCell<bool> = OnceCell::new();
+
+#[derive(Clone, Copy, PartialEq)]
+enum Language {
+  Python,
+  PowerShell,
+}
+
+impl Language {
+  fn parse(s: &str) -> Option<Self> {
+    match s.trim().to_lowercase().as_str() {
+      "python" | "py" | "python3" => Some(Self::Python),
+      "powershell" | "pwsh" | "ps1" => Some(Self::PowerShell),
+      _ => None,
+    }
+  }
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::Python => "python",
+      Self::PowerShell => "powershell",
+    }
+  }
+
+  fn file_name(self) -> &'static str {
+    match self {
+      Self::Python => "snippet.py",
+      Self::PowerShell => "snippet.ps1",
+    }
+  }
+
+  // Interpreters tried in order; the first one found on PATH runs the snippet
+  fn programs(self) -> &'static [&'static str] {
+    match self {
+      Self::Python if cfg!(target_os = "windows") => &["python", "py"],
+      Self::Python => &["python3", "python"],
+      Self::PowerShell if cfg!(target_os = "windows") => &["powershell.exe", "pwsh"],
+      Self::PowerShell => &["pwsh"],
+    }
+  }
+}
+
+pub fn enabled() -> bool {
+  crate::config::get_code_exec_enabled_from_settings()
+}
+
+// Runs the snippet file given as argv[1] as __main__, so tracebacks show its own line numbers
+fn python_runner(network: bool, memory_mb: u64) -> String {
+  let mut pre = String::from("import sys\n");
+  if !network {
+    pre.push_str(&format!(
+      "import socket as _s\n\
+       class _NoNetwork(_s.socket):\n    def __init__(self, *a, **k):\n        raise OSError('{NETWORK_DISABLED}')\n\
+       def _no_network(*a, **k):\n    raise OSError('{NETWORK_DISABLED}')\n\
+       _s.socket = _NoNetwork\n_s.create_connection = _no_network\n_s.getaddrinfo = _no_network\n"
+    ));
+  }
+  // Address-space cap where the resource module exists; the memory watchdog covers the rest
+  pre.push_str(&format!(
+    "try:\n    import resource as _r\n    _r.setrlimit(_r.RLIMIT_AS, ({0}, {0}))\nexcept Exception:\n    pass\n",
+    memory_mb * 1024 * 1024 * 2
+  ));
+  pre.push_str("import runpy\nrunpy.run_path(sys.argv[1], run_name='__main__')\n");
+  pre
+}
+
+fn powershell_runner(network: bool, script: &Path) -> String {
+  let mut pre = String::from("$ErrorActionPreference = 'Continue'; ");
+  if !network {
+    for cmdlet in ["Invoke-WebRequest", "Invoke-RestMethod", "Start-BitsTransfer", "Test-NetConnection", "Send-MailMessage"] {
+      pre.push_str(&format!("function {cmdlet} {{ throw '{NETWORK_DISABLED}' }}; "));
+    }
+    // No arbitrary .NET types (WebClient, sockets) from here on
+    pre.push_str("$ExecutionContext.SessionState.LanguageMode = 'ConstrainedLanguage'; ");
+  }
+  pre.push_str(&format!(". '{}'", script.to_string_lossy().replace('\'', "''")));
+  pre
+}
+
+// Whether runs without network get their own network namespace
+fn network_isolation_available() -> bool {
+  cfg!(target_os = "linux")
+    && *UNSHARE_WORKS.get_or_init(|| {
+      std::process::Command::new("unshare")
+        .args(["-rn", "true"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+    })
+}
+
+// `program` on PATH; unshare runs it by path so a missing interpreter is still told apart
+fn find_on_path(program: &str) -> Option<std::path::PathBuf> {
+  std::env::split_paths(&std::env::var_os("PATH")?).map(|d| d.join(program)).find(|p| p.is_file())
+}
+
+fn command_for(lang: Language, program: &str, dir: &Path, script: &Path, network: bool, memory_mb: u64) -> Option<tokio::process::Command> {
+  let mut cmd = if !network && network_isolation_available() {
+    let mut c = tokio::process::Command::new("unshare");
+    c.args(["-rn", "--"]).arg(find_on_path(program)?);
+    c
+  } else {
+    tokio::process::Command::new(program)
+  };
+  match lang {
+    Language::Python => {
+      cmd.args(["-I", "-B", "-c"]).arg(python_runner(network, memory_mb)).arg(script);
+    }
+    Language::PowerShell => {
+      cmd.args(["-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Bypass", "-Command"]).arg(powershell_runner(network, script));
+    }
+  }
+  cmd.current_dir(dir).env_clear();
+  for key in ["PATH", "PATHEXT", "SYSTEMROOT", "WINDIR", "COMSPEC", "LANG", "LC_ALL", "PSModulePath"] {
+    if let Some(v) = std::env::var_os(key) {
+      cmd.env(key, v);
+    }
+  }
+  for key in ["HOME", "USERPROFILE", "TEMP", "TMP", "TMPDIR"] {
+    cmd.env(key, dir);
+  }
+  cmd.env("PYTHONIOENCODING", "utf-8");
+  if !network {
+    for key in ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "http_proxy", "https_proxy", "all_proxy"] {
+      cmd.env(key, DEAD_PROXY);
+    }
+  }
+  cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).kill_on_drop(true);
+  #[cfg(target_os = "windows")]
+  cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+  // Own process group, so everything the snippet starts can be killed with it
+  #[cfg(unix)]
+  cmd.process_group(0);
+  Some(cmd)
+}
+
+// The snippet's process and everything it starts; killed as a whole
+#[cfg(target_os = "windows")]
+struct ProcessTree(windows::Win32::Foundation::HANDLE);
+
+// Safety: the job handle is only used through thread-safe Win32 calls
+#[cfg(target_os = "windows")]
+unsafe impl Send for ProcessTree {}
+
+#[cfg(target_os = "windows")]
+impl ProcessTree {
+  // Put the child in a job that also caps its memory and kills all of it when closed
+  fn attach(child: &tokio::process::Child, memory_mb: u64) -> Option<Self> {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::JobObjects::{
+      AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+      JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_JOB_MEMORY, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    let process = HANDLE(child.raw_handle()?);
+    unsafe {
+      let job = CreateJobObjectW(None, windows::core::PCWSTR::null()).ok()?;
+      let tree = Self(job);
+      let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+      info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE | JOB_OBJECT_LIMIT_JOB_MEMORY;
+      info.JobMemoryLimit = (memory_mb * 1024 * 1024) as usize;
+      SetInformationJobObject(
+        job,
+        JobObjectExtendedLimitInformation,
+        &info as *const _ as *const std::ffi::c_void,
+        std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+      )
+      .ok()?;
+      AssignProcessToJobObject(job, process).ok()?;
+      Some(tree)
+    }
+  }
+
+  fn kill(&self) {
+    let _ = unsafe { windows::Win32::System::JobObjects::TerminateJobObject(self.0, 1) };
+  }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for ProcessTree {
+  fn drop(&mut self) {
+    let _ = unsafe { windows::Win32::Foundation::CloseHandle(self.0) };
+  }
+}
+
+#[cfg(unix)]
+struct ProcessTree(u32);
+
+#[cfg(unix)]
+impl ProcessTree {
+  fn attach(child: &tokio::process::Child, _memory_mb: u64) -> Option<Self> {
+    child.id().map(Self)
+  }
+
+  fn kill(&self) {
+    // Negative pid: the whole process group the child leads
+    let _ = std::process::Command::new("kill").args(["-KILL", "--", &format!("-{}", self.0)]).status();
+  }
+}
+
+#[cfg(unix)]
+impl Drop for ProcessTree {
+  fn drop(&mut self) {
+    // Background processes the snippet left behind go with its folder
+    self.kill();
+  }
+}
+
+// Read a stream to the end, keeping the first MAX_OUTPUT_BYTES
+async fn read_capped<R: AsyncRead + Unpin>(mut r: R) -> (String, bool) {
+  let mut kept: Vec<u8> = Vec::new();
+  let mut truncated = false;
+  let mut buf = [0u8; 8192];
+  while let Ok(n) = r.read(&mut buf).await {
+    if n == 0 {
+      break;
+    }
+    let room = MAX_OUTPUT_BYTES.saturating_sub(kept.len());
+    kept.extend_from_slice(&buf[..n.min(room)]);
+    truncated |= n > room;
+  }
+  (String::from_utf8_lossy(&kept).to_string(), truncated)
+}
+
+async fn run(lang: Language, code: &str, network: bool, timeout: Duration, memory_mb: u64) -> Result<serde_json::Value, String> {
+  let dir = std::env::temp_dir().join(format!("aidc_code_{}", uuid::Uuid::new_v4()));
+  tokio::fs::create_dir_all(&dir).await.map_err(|e| format!("Create work folder failed: {e}"))?;
+  let result = async {
+    let script = dir.join(lang.file_name());
+    tokio::fs::write(&script, code).await.map_err(|e| format!("Write snippet failed: {e}"))?;
+
+    let mut child = None;
+    for program in lang.programs() {
+      let Some(mut cmd) = command_for(lang, program, &dir, &script, network, memory_mb) else { continue };
+      match cmd.spawn() {
+        Ok(c) => {
+          child = Some(c);
+          break;
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+        Err(e) => return Err(format!("{program} failed to start: {e}")),
+      }
+    }
+    let mut child = child.ok_or_else(|| format!("No {} interpreter found on PATH (tried {})", lang.name(), lang.programs().join(", ")))?;
+    let tree = ProcessTree::attach(&child, memory_mb);
+    let stdout = tokio::spawn(read_capped(child.stdout.take().ok_or("stdout unavailable")?));
+    let stderr = tokio::spawn(read_capped(child.stderr.take().ok_or("stderr unavailable")?));
+
+    let started = Instant::now();
+    let limit_bytes = memory_mb * 1024 * 1024;
+    let mut timed_out = false;
+    let mut memory_exceeded = false;
+    let status = loop {
+      tokio::select! {
+        status = child.wait() => break status.ok(),
+        _ = tokio::time::sleep(MEMORY_POLL) => {
+          if started.elapsed() >= timeout {
+            timed_out = true;
+          } else if child.id().and_then(crate::utils::child_memory).is_some_and(|m| m > limit_bytes) {
+            memory_exceeded = true;
+          } else {
+            continue;
+          }
+          if let Some(tree) = &tree {
+            tree.kill();
+          }
+          let _ = child.kill().await;
+          break None;
+        }
+      }
+    };
+    // Processes it left running would keep the output pipes open
+    drop(tree);
+    let (stdout, out_truncated) = stdout.await.unwrap_or_default();
+    let (stderr, err_truncated) = stderr.await.unwrap_or_default();
+    Ok(serde_json::json!({
+      "language": lang.name(),
+      "exit_code": status.and_then(|s| s.code()),
+      "stdout": stdout,
+      "stderr": stderr,
+      "truncated": out_truncated || err_truncated,
+      "timed_out": timed_out,
+      "memory_exceeded": memory_exceeded,
+      "duration_ms": started.elapsed().as_millis() as u64,
+    }))
+  }
+  .await;
+  let _ = tokio::fs::remove_dir_all(&dir).await;
+  result
+}
+
+// Ask the UI and wait for `code_exec_respond`; false on refusal or timeout
+async fn request_approval(app: &tauri::AppHandle, req: crate::events::CodeApproval) -> bool {
+  let (tx, rx) = oneshot::channel();
+  let id = req.id.clone();
+  PENDING.lock().unwrap_or_else(|e| e.into_inner()).insert(id.clone(), tx);
+  crate::events::emit(app, crate::events::CODE_APPROVAL, &req);
+  let approved = matches!(tokio::time::timeout(APPROVAL_TIMEOUT, rx).await, Ok(Ok(true)));
+  PENDING.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+  approved
+}
+
+async fn run_code(app: &tauri::AppHandle, args: &serde_json::Value) -> Result<serde_json::Value, String> {
+  let language = args.get("language").and_then(|x| x.as_str()).unwrap_or("");
+  let lang = Language::parse(language).ok_or_else(|| format!("Unsupported language '{language}' (expected python or powershell)"))?;
+  let code = args.get("code").and_then(|x| x.as_str()).unwrap_or("");
+  if code.trim().is_empty() {
+    return Err("No code given".into());
+  }
+  if code.chars().count() > MAX_CODE_CHARS {
+    return Err(format!("Snippets are limited to {MAX_CODE_CHARS} characters"));
+  }
+  let network = crate::config::get_code_exec_allow_network_from_settings();
+  let timeout_secs = crate::config::get_code_exec_timeout_secs_from_settings();
+  let memory_mb = crate::config::get_code_exec_memory_mb_from_settings();
+
+  let req = crate::events::CodeApproval {
+    id: uuid::Uuid::new_v4().to_string(),
+    language: lang.name().to_string(),
+    code: code.to_string(),
+    network,
+    network_isolated: !network && network_isolation_available(),
+    timeout_secs,
+    memory_mb,
+  };
+  if !request_approval(app, req).await {
+    crate::audit::record("code_run", serde_json::json!({ "language": lang.name(), "approved": false }));
+    return Err("The user did not approve running this code".into());
+  }
+  let res = run(lang, code, network, Duration::from_secs(timeout_secs), memory_mb).await;
+  crate::audit::record("code_run", serde_json::json!({
+    "language": lang.name(),
+    "approved": true,
+    "exit_code": res.as_ref().ok().and_then(|v| v.get("exit_code").cloned()),
+    "error": res.as_ref().err(),
+  }));
+  res
+}
+
+/// Tool definition (Chat Completions shape); empty when the runner is turned off.
+pub fn tool_definitions() -> Vec<serde_json::Value> {
+  if !enabled() {
+    return Vec::new();
+  }
+  vec![serde_json::json!({
+    "type": "function",
+    "function": {
+      "name": RUN_CODE_TOOL,
+      "description": "Run a short Python or PowerShell snippet on the user's computer and return its stdout, stderr and exit code. Use it for calculations and reshaping data; print the results. The user approves every run. Network access is usually blocked, runs are time- and memory-limited, and the working folder is empty and deleted afterwards.",
+      "parameters": {
+        "type": "object",
+        "properties": {
+          "language": { "type": "string", "enum": ["python", "powershell"] },
+          "code": { "type": "string", "description": "Complete program; print what should be returned" }
+        },
+        "required": ["language", "code"]
+      }
+    }
+  })]
+}
+
+/// Run the built-in code tool; None when `fname` is not it.
+pub async fn call_tool(app: &tauri::AppHandle, fname: &str, args: &serde_json::Value) -> Option<Result<serde_json::Value, String>> {
+  match fname {
+    RUN_CODE_TOOL if !enabled() => Some(Err("Running code is turned off in settings".to_string())),
+    RUN_CODE_TOOL => Some(run_code(app, args).await),
+    _ => None,
+  }
+}
+
+/// Answer a `code:approval` request. Returns false when it is no longer waiting (timed out).
+#[tauri::command]
+pub fn code_exec_respond(id: String, approved: bool) -> Result<bool, String> {
+  let Some(tx) = PENDING.lock().unwrap_or_else(|e| e.into_inner()).remove(&id) else { return Ok(false) };
+  Ok(tx.send(approved).is_ok())
+}
@@ -8,19 +8,6 @@ use std::time::{Duration, Instant};
 use arboard::Clipboard;
 use tauri::Emitter;
 
-#[cfg(target_os = "windows")]
-use std::ffi::c_void;
-#[cfg(target_os = "windows")]
-use windows::core::PWSTR;
-#[cfg(target_os = "windows")]
-use windows::Win32::Foundation::{CloseHandle, HANDLE, HWND};
-#[cfg(target_os = "windows")]
-use windows::Win32::System::Threading::{
-  OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_FORMAT, PROCESS_QUERY_LIMITED_INFORMATION,
-};
-#[cfg(target_os = "windows")]
-use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
-
 static COMMAND_RUNNING: AtomicBool = AtomicBool::new(false);
 
 const LOG_FILE_NAME: &str = "command-hook.log";
@@ -168,51 +155,10 @@ fn configured_script_name() -> String {
     .to_string()
 }
 
-#[cfg(target_os = "windows")]
-fn active_app_name_from_last_foreground() -> String {
-  let hraw = match crate::quick_actions::last_foreground_handle_raw() {
-    Some(v) => v,
-    None => return String::new(),
-  };
-
-  unsafe {
-    let hwnd = HWND(hraw as *mut c_void);
-    if hwnd.0.is_null() {
-      return String::new();
-    }
-
-    let mut pid: u32 = 0;
-    let _ = GetWindowThreadProcessId(hwnd, Some(&mut pid));
-    if pid == 0 {
-      return String::new();
-    }
-
-    let process: HANDLE = match OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
-      Ok(h) => h,
-      Err(_) => return String::new(),
-    };
-
-    let mut size: u32 = 32768;
-    let mut buf = vec![0u16; size as usize];
-    let ok = QueryFullProcessImageNameW(process, PROCESS_NAME_FORMAT(0), PWSTR(buf.as_mut_ptr()), &mut size).is_ok();
-    let _ = CloseHandle(process);
-
-    if !ok || size == 0 {
-      return String::new();
-    }
-
-    let full = String::from_utf16_lossy(&buf[..size as usize]);
-    Path::new(&full)
-      .file_name()
-      .and_then(|x| x.to_str())
-      .unwrap_or("")
-      .to_string()
-  }
-}
-
-#[cfg(not(target_os = "windows"))]
 fn active_app_name_from_last_foreground() -> String {
-  String::new()
+  crate::quick_actions::last_foreground_handle_raw()
+    .map(crate::quick_actions::process_name_for_window)
+    .unwrap_or_default()
 }
 
 fn collect_context_env(transcript: &str, selected_text: Option<String>) -> Vec<(String, String)> {
@@ -1,6 +1,22 @@
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use once_cell::sync::Lazy;
+use tokio::sync::Mutex as AsyncMutex;
+
+// Serializes read-merge-write cycles so concurrent saves cannot drop each other's keys
+static WRITE_LOCK: Lazy<AsyncMutex<()>> = Lazy::new(|| AsyncMutex::new(()));
+// Recovery from settings.json.bak is logged once, not on every settings read
+static RECOVERY_LOGGED: AtomicBool = AtomicBool::new(false);
+// Parsed settings.json with the modification time and size it was read at. Nearly every setting
+// getter reads the file; this keeps them from re-parsing it when nothing changed.
+static SETTINGS_CACHE: Lazy<std::sync::Mutex<Option<(std::time::SystemTime, u64, serde_json::Value)>>> = Lazy::new(|| std::sync::Mutex::new(None));
+// Latest conversation state pushed by the window. Kept even with persistence off so voice
+// sessions can attach to conversations that only live in memory.
+static CONVERSATION_SNAPSHOT: Lazy<std::sync::Mutex<Option<serde_json::Value>>> = Lazy::new(|| std::sync::Mutex::new(None));
 
 // ---------------------------
 // Settings helpers and commands
@@ -53,25 +69,226 @@ pub fn get_disabled_tools_map() -> HashMap<String, HashSet<String>> {
   out
 }
 
+// Last good copy of a JSON file, refreshed before every write
+fn backup_path(path: &Path) -> PathBuf {
+  path.with_extension("json.bak")
+}
+
+fn is_valid_json(text: &str) -> bool {
+  serde_json::from_str::<serde::de::IgnoredAny>(text).is_ok()
+}
+
+// The previous settings.json without the API keys the new one no longer holds in plaintext (moved
+// to the keychain or deleted), so the backup does not keep them around
+fn scrub_settings_backup(previous: &str, next: &str) -> Option<String> {
+  let mut prev = parse_settings_object(previous)?;
+  let next = parse_settings_object(next).unwrap_or_default();
+  let obj = prev.as_object_mut()?;
+  let stale: Vec<&str> = SECRET_KEYS.iter().copied().filter(|k| obj.contains_key(*k) && next.get(*k) != obj.get(*k)).collect();
+  if stale.is_empty() {
+    return None;
+  }
+  for k in stale {
+    obj.remove(k);
+  }
+  serde_json::to_string_pretty(&prev).ok()
+}
+
+/// Write a JSON file without ever leaving a truncated file behind: write and fsync a temp file,
+/// keep the previous version as `.bak` (or move a corrupted one aside as `.corrupt`), then
+/// rename over the target, which replaces it atomically on all platforms. The settings backup
+/// leaves out API keys the new settings no longer carry.
+pub(crate) fn write_json_atomic_blocking(path: &Path, contents: &str) -> Result<(), String> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {e}"))?;
+  }
+  let tmp_path = path.with_extension("json.tmp");
+  let mut f = fs::File::create(&tmp_path).map_err(|e| format!("Write {} failed: {e}", tmp_path.display()))?;
+  f.write_all(contents.as_bytes()).map_err(|e| format!("Write {} failed: {e}", tmp_path.display()))?;
+  f.sync_all().map_err(|e| format!("Flush {} failed: {e}", tmp_path.display()))?;
+  drop(f);
+  match fs::read_to_string(path) {
+    Ok(text) if is_valid_json(&text) => {
+      let scrubbed = if settings_config_path().as_deref() == Some(path) { scrub_settings_backup(&text, contents) } else { None };
+      match scrubbed {
+        Some(backup) => {
+          let _ = fs::write(backup_path(path), backup);
+        }
+        None => {
+          let _ = fs::copy(path, backup_path(path));
+        }
+      }
+    }
+    Ok(_) => {
+      tracing::warn!(path = %path.display(), "replacing corrupted file; keeping it as .corrupt");
+      let _ = fs::rename(path, path.with_extension("json.corrupt"));
+    }
+    Err(_) => {}
+  }
+  fs::rename(&tmp_path, path).map_err(|e| format!("Rename {} failed: {e}", path.display()))
+}
+
+/// `write_json_atomic_blocking` off the async runtime's worker threads.
+pub(crate) async fn write_json_atomic(path: &Path, contents: &str) -> Result<(), String> {
+  let (target, text) = (path.to_path_buf(), contents.to_string());
+  let written = tokio::task::spawn_blocking(move || write_json_atomic_blocking(&target, &text))
+    .await
+    .map_err(|e| format!("Write {} failed: {e}", path.display()))?;
+  // A write within the file system's timestamp granularity could keep size and mtime
+  *SETTINGS_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+  written
+}
+
+fn parse_settings_object(text: &str) -> Option<serde_json::Value> {
+  serde_json::from_str::<serde_json::Value>(text).ok().filter(|v| v.is_object())
+}
+
 pub fn load_settings_json() -> serde_json::Value {
   if let Some(path) = settings_config_path() {
+    let stamp = fs::metadata(&path).ok().and_then(|m| Some((m.modified().ok()?, m.len())));
+    if let Some((modified, len)) = stamp {
+      if let Some((m, l, v)) = SETTINGS_CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        if *m == modified && *l == len { return v.clone(); }
+      }
+    }
     if let Ok(text) = fs::read_to_string(&path) {
-      if let Ok(v) = serde_json::from_str::<serde_json::Value>(&text) {
-        if v.is_object() { return v; }
+      if let Some(v) = parse_settings_object(&text) {
+        if let Some((modified, len)) = stamp {
+          *SETTINGS_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some((modified, len, v.clone()));
+        }
+        return v;
+      }
+      // Corrupted settings.json: use the last good copy until the next save rewrites it
+      if let Some(v) = fs::read_to_string(backup_path(&path)).ok().and_then(|t| parse_settings_object(&t)) {
+        if !RECOVERY_LOGGED.swap(true, Ordering::Relaxed) {
+          tracing::warn!(path = %path.display(), "settings.json is corrupted; loaded settings.json.bak");
+        }
+        return v;
       }
     }
   }
   serde_json::json!({})
 }
 
-pub fn get_api_key_from_settings_or_env() -> Result<String, String> {
-  let v = load_settings_json();
-  if let Some(s) = v.get("openai_api_key").and_then(|x| x.as_str()) {
-    if !s.trim().is_empty() { return Ok(s.trim().to_string()); }
+// ---------------------------
+// API keys in the OS keychain
+// ---------------------------
+// Keys live in Windows Credential Manager, the macOS Keychain or the Secret Service (GNOME Keyring,
+// KWallet) under the service "AiDesktopCompanion", one entry per settings key (`openai_api_key`, ...).
+// settings.json is only used when no keychain is available, and for keys saved before this existed
+// until `migrate_api_key_to_keyring` moves them. Lookups are cached; a keychain read can take a
+// D-Bus round trip on Linux.
+
+const KEYRING_SERVICE: &str = "AiDesktopCompanion";
+
+static SECRET_CACHE: Lazy<std::sync::Mutex<HashMap<String, Option<String>>>> = Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+fn keyring_entry(name: &str) -> Result<keyring::Entry, String> {
+  keyring::Entry::new(KEYRING_SERVICE, name).map_err(|e| format!("keychain unavailable: {e}"))
+}
+
+/// Secret stored in the OS keychain, if any.
+pub fn keychain_secret(name: &str) -> Option<String> {
+  if let Some(hit) = SECRET_CACHE.lock().unwrap_or_else(|e| e.into_inner()).get(name) {
+    return hit.clone();
   }
-  std::env::var("OPENAI_API_KEY")
+  let value = keyring_entry(name)
+    .and_then(|e| e.get_password().map_err(|e| e.to_string()))
+    .ok()
     .map(|s| s.trim().to_string())
-    .map_err(|_| "OPENAI_API_KEY not set in settings or environment".to_string())
+    .filter(|s| !s.is_empty());
+  SECRET_CACHE.lock().unwrap_or_else(|e| e.into_inner()).insert(name.to_string(), value.clone());
+  value
+}
+
+/// Store (or with an empty value, delete) a secret in the OS keychain.
+pub fn set_keychain_secret(name: &str, value: &str) -> Result<(), String> {
+  let entry = keyring_entry(name)?;
+  let value = value.trim();
+  let res = if value.is_empty() {
+    match entry.delete_credential() {
+      Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+      Err(e) => Err(e),
+    }
+  } else {
+    entry.set_password(value)
+  };
+  res.map_err(|e| format!("keychain write failed: {e}"))?;
+  SECRET_CACHE.lock().unwrap_or_else(|e| e.into_inner()).insert(name.to_string(), Some(value.to_string()).filter(|s| !s.is_empty()));
+  Ok(())
+}
+
+/// A stored secret (`SECRET_KEYS`): the keychain first, then plaintext in settings.json.
+pub fn get_secret(name: &str) -> Option<String> {
+  keychain_secret(name).or_else(|| {
+    load_settings_json().get(name).and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+  })
+}
+
+// Drop `names` from settings.json; the backup write leaves them out too
+async fn remove_plaintext_secrets(names: &[&str]) -> Result<(), String> {
+  let path = settings_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  let _write = WRITE_LOCK.lock().await;
+  let mut v = load_settings_json();
+  let Some(obj) = v.as_object_mut() else { return Ok(()) };
+  if !names.iter().any(|k| obj.contains_key(*k)) { return Ok(()); }
+  for k in names { obj.remove(*k); }
+  let pretty = serde_json::to_string_pretty(&v).map_err(|e| format!("Serialize settings failed: {e}"))?;
+  write_json_atomic(&path, &pretty).await
+}
+
+/// Store an API key (one of `SECRET_KEYS`) in the OS keychain and remove any plaintext copy.
+/// An empty value deletes it.
+pub async fn set_secure_api_key(name: String, value: String) -> Result<(), String> {
+  if !SECRET_KEYS.contains(&name.as_str()) {
+    return Err(format!("'{name}' is not a stored secret"));
+  }
+  let key = name.clone();
+  tokio::task::spawn_blocking(move || set_keychain_secret(&key, &value)).await.map_err(|e| format!("keychain task failed: {e}"))??;
+  remove_plaintext_secrets(&[name.as_str()]).await
+}
+
+/// Move API keys stored in plaintext in settings.json into the OS keychain. Returns the names moved.
+pub async fn migrate_api_key_to_keyring() -> Result<Vec<String>, String> {
+  let v = load_settings_json();
+  let plain: Vec<(String, String)> = SECRET_KEYS
+    .iter()
+    .filter_map(|k| v.get(*k).and_then(|x| x.as_str()).map(|s| s.trim()).filter(|s| !s.is_empty() && *s != SECRET_PLACEHOLDER).map(|s| (k.to_string(), s.to_string())))
+    .collect();
+  if plain.is_empty() {
+    return Ok(Vec::new());
+  }
+  let moved = tokio::task::spawn_blocking(move || -> Result<Vec<String>, String> {
+    let mut moved = Vec::new();
+    for (k, value) in plain {
+      set_keychain_secret(&k, &value)?;
+      moved.push(k);
+    }
+    Ok(moved)
+  })
+  .await
+  .map_err(|e| format!("keychain task failed: {e}"))??;
+  let names: Vec<&str> = moved.iter().map(String::as_str).collect();
+  remove_plaintext_secrets(&names).await?;
+  tracing::info!(keys = ?moved, "API keys moved to the OS keychain");
+  Ok(moved)
+}
+
+/// The OpenAI key requests should use: the active key of the rotation pool (key_pool.rs).
+pub fn get_api_key_from_settings_or_env() -> Result<String, String> {
+  crate::key_pool::active_openai_key().ok_or_else(|| "OPENAI_API_KEY not set in settings or environment".to_string())
+}
+
+/// All configured OpenAI keys in rotation order: openai_api_key (or OPENAI_API_KEY), then the
+/// keys listed in openai_api_keys_extra (one per line), without duplicates.
+pub fn get_openai_api_keys_from_settings_or_env() -> Vec<String> {
+  let primary = get_secret("openai_api_key").or_else(|| std::env::var("OPENAI_API_KEY").ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()));
+  let extra = get_secret("openai_api_keys_extra").unwrap_or_default();
+  let mut keys: Vec<String> = Vec::new();
+  for k in primary.into_iter().chain(extra.split(|c: char| c.is_whitespace() || c == ',').map(|s| s.to_string())) {
+    if !k.is_empty() && !keys.contains(&k) { keys.push(k); }
+  }
+  keys
 }
 
 pub fn get_model_from_settings_or_env() -> String {
@@ -83,6 +300,90 @@ pub fn get_model_from_settings_or_env() -> String {
   std::env::var("OPENAI_CHAT_MODEL").unwrap_or_else(|_| "gpt-4o-mini".to_string())
 }
 
+pub const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+// OpenAI-compatible endpoint for chat and quick prompts, e.g. http://localhost:11434/v1 (Ollama)
+// or http://localhost:1234/v1 (LM Studio); other OpenAI features keep using api.openai.com
+pub fn get_openai_base_url_from_settings_or_env() -> String {
+  let v = load_settings_json();
+  if let Some(s) = v.get("openai_base_url").and_then(|x| x.as_str()) {
+    let t = s.trim().trim_end_matches('/');
+    if !t.is_empty() { return t.to_string(); }
+  }
+  std::env::var("AIDC_OPENAI_BASE_URL")
+    .ok()
+    .map(|s| s.trim().trim_end_matches('/').to_string())
+    .filter(|s| !s.is_empty())
+    .unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string())
+}
+
+/// True only for https URLs whose host is exactly api.openai.com, so look-alikes such as
+/// `https://api.openai.com.example` or `https://api.openai.com@example` never get the OpenAI keys.
+pub fn is_default_openai_base_url(base_url: &str) -> bool {
+  reqwest::Url::parse(base_url.trim())
+    .map(|u| u.scheme() == "https" && u.host_str() == Some("api.openai.com"))
+    .unwrap_or(false)
+}
+
+/// Key sent to a custom `openai_base_url`; the OpenAI keys only ever go to api.openai.com.
+pub fn get_custom_base_url_api_key() -> Option<String> {
+  get_secret("openai_base_url_api_key")
+}
+
+/// Rate-limit queue (rate_limit.rs) for chat requests to `base_url`. A custom server gets its own,
+/// so it neither shares OpenAI's limits nor takes part in OpenAI key failover.
+pub fn chat_rate_limit_provider(base_url: &str) -> &'static str {
+  if is_default_openai_base_url(base_url) { "openai" } else { "openai_compatible" }
+}
+
+// Chat provider for conversations and the voice assistant: "openai" (default), "anthropic" or "gemini"
+pub fn get_chat_provider_from_settings() -> String {
+  match load_settings_json().get("chat_provider").and_then(|x| x.as_str()).map(|s| s.trim().to_lowercase()) {
+    Some(p) if p == "anthropic" || p == "gemini" => p,
+    _ => "openai".to_string(),
+  }
+}
+
+pub fn get_anthropic_api_key_from_settings_or_env() -> Result<String, String> {
+  if let Some(s) = get_secret("anthropic_api_key") { return Ok(s); }
+  std::env::var("ANTHROPIC_API_KEY")
+    .map(|s| s.trim().to_string())
+    .map_err(|_| "ANTHROPIC_API_KEY not set in settings or environment".to_string())
+}
+
+pub fn get_anthropic_model_from_settings_or_env() -> String {
+  let v = load_settings_json();
+  if let Some(s) = v.get("anthropic_chat_model").and_then(|x| x.as_str()) {
+    let t = s.trim();
+    if !t.is_empty() { return t.to_string(); }
+  }
+  std::env::var("ANTHROPIC_CHAT_MODEL").unwrap_or_else(|_| "claude-sonnet-4-5".to_string())
+}
+
+pub fn get_gemini_api_key_from_settings_or_env() -> Result<String, String> {
+  if let Some(s) = get_secret("gemini_api_key") { return Ok(s); }
+  std::env::var("GEMINI_API_KEY")
+    .or_else(|_| std::env::var("GOOGLE_API_KEY"))
+    .map(|s| s.trim().to_string())
+    .map_err(|_| "GEMINI_API_KEY not set in settings or environment".to_string())
+}
+
+pub fn get_elevenlabs_api_key_from_settings_or_env() -> Result<String, String> {
+  if let Some(s) = get_secret("elevenlabs_api_key") { return Ok(s); }
+  std::env::var("ELEVENLABS_API_KEY")
+    .map(|s| s.trim().to_string())
+    .map_err(|_| "ELEVENLABS_API_KEY not set in settings or environment".to_string())
+}
+
+pub fn get_gemini_model_from_settings_or_env() -> String {
+  let v = load_settings_json();
+  if let Some(s) = v.get("gemini_chat_model").and_then(|x| x.as_str()) {
+    let t = s.trim();
+    if !t.is_empty() { return t.to_string(); }
+  }
+  std::env::var("GEMINI_CHAT_MODEL").unwrap_or_else(|_| "gemini-2.5-flash".to_string())
+}
+
 pub fn get_temperature_from_settings_or_env() -> Option<f32> {
   let v = load_settings_json();
   v.get("temperature").and_then(|x| x.as_f64()).map(|f| f as f32)
@@ -93,6 +394,265 @@ pub fn get_start_in_tray_from_settings() -> bool {
   v.get("start_in_tray").and_then(|x| x.as_bool()).unwrap_or(false)
 }
 
+// Captured selection format passed to prompts: "plain" (default), "html" or "both"
+pub fn get_selection_format_from_settings() -> String {
+  let v = load_settings_json();
+  if let Some(s) = v.get("selection_format").and_then(|x| x.as_str()) {
+    let t = s.trim().to_lowercase();
+    if t == "plain" || t == "html" || t == "both" { return t; }
+  }
+  "plain".to_string()
+}
+
+// Text insertion method: "auto" (default; paste, typing into terminals), "paste" or "type"
+pub fn get_insert_method_from_settings() -> String {
+  let v = load_settings_json();
+  if let Some(s) = v.get("insert_method").and_then(|x| x.as_str()) {
+    let t = s.trim().to_lowercase();
+    if t == "auto" || t == "paste" || t == "type" { return t; }
+  }
+  "auto".to_string()
+}
+
+// Typing rate for the "type" insert method in characters per second (0 = no delay)
+pub fn get_insert_typing_rate_from_settings() -> u32 {
+  let v = load_settings_json();
+  v.get("insert_typing_rate_cps").and_then(|x| x.as_u64()).map(|n| n.min(2000) as u32).unwrap_or(200)
+}
+
+// Default safe mode (no Ctrl+C, read the clipboard as is) per selection feature:
+// "quick_prompts", "tts_selection", "prompt_action", "notes"
+pub fn get_safe_mode_default_from_settings(feature: &str) -> bool {
+  load_settings_json().get("safe_mode_defaults").and_then(|d| d.get(feature)).and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Microphone (cpal device name) for native capture: dictation, voice assistant, realtime.
+// None uses the system default. Browser recording uses stt_input_device_id instead.
+pub fn get_stt_input_device_from_settings() -> Option<String> {
+  load_settings_json().get("stt_input_device").and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+// Piper voice ("<locale>-<name>-<quality>"); None uses tts_piper::DEFAULT_VOICE
+pub fn get_tts_piper_voice_from_settings() -> Option<String> {
+  load_settings_json().get("tts_piper_voice").and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+// Read selection aloud starts at the caret of the focused control instead of needing a selection
+pub fn get_tts_read_from_cursor_from_settings() -> bool {
+  load_settings_json().get("tts_read_from_cursor").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Entries kept for the paste-history popup (default 25, max 200; 0 turns the history off)
+pub fn get_recent_outputs_limit_from_settings() -> usize {
+  let v = load_settings_json();
+  v.get("recent_outputs_limit").and_then(|x| x.as_u64()).map(|n| n.min(200) as usize).unwrap_or(25)
+}
+
+// Per-app insert method overrides: lowercase process name -> "paste" | "type"
+pub fn get_insert_method_overrides_from_settings() -> HashMap<String, String> {
+  let mut out: HashMap<String, String> = HashMap::new();
+  let v = load_settings_json();
+  if let Some(obj) = v.get("insert_method_overrides").and_then(|x| x.as_object()) {
+    for (k, m) in obj.iter() {
+      let name = k.trim().to_lowercase();
+      if let Some(m) = m.as_str() {
+        let m = m.trim().to_lowercase();
+        if !name.is_empty() && (m == "paste" || m == "type") { out.insert(name, m); }
+      }
+    }
+  }
+  out
+}
+
+// Log verbosity for the file log: off|error|warn|info|debug|trace (default: info)
+pub fn get_log_level_from_settings() -> String {
+  let v = load_settings_json();
+  let l = v.get("log_level").and_then(|x| x.as_str()).unwrap_or("info").trim().to_lowercase();
+  match l.as_str() {
+    "off" | "error" | "warn" | "info" | "debug" | "trace" => l,
+    _ => "info".to_string(),
+  }
+}
+
+// Per-provider request limits from `rate_limits.<provider>`: { max_concurrent, requests_per_minute }.
+// Defaults: 4 concurrent requests, 60 requests per minute.
+pub fn get_rate_limits_from_settings(provider: &str) -> (usize, u32) {
+  let v = load_settings_json();
+  let entry = v.get("rate_limits").and_then(|x| x.get(provider.trim().to_lowercase()));
+  let max_concurrent = entry
+    .and_then(|e| e.get("max_concurrent"))
+    .and_then(|x| x.as_u64())
+    .map(|n| n.clamp(1, 32) as usize)
+    .unwrap_or(4);
+  let per_minute = entry
+    .and_then(|e| e.get("requests_per_minute"))
+    .and_then(|x| x.as_u64())
+    .map(|n| n.clamp(1, 10_000) as u32)
+    .unwrap_or(60);
+  (max_concurrent, per_minute)
+}
+
+// Request timeout in seconds for a subsystem from `timeouts.<kind>` (see timeouts.rs for defaults)
+pub fn get_timeout_secs_from_settings(kind: &str) -> Option<u64> {
+  load_settings_json().get("timeouts").and_then(|t| t.get(kind)).and_then(|x| x.as_u64()).filter(|n| *n > 0)
+}
+
+// Opt-in cache for deterministic (temperature 0) chat and quick prompt responses
+pub fn get_response_cache_enabled_from_settings() -> bool {
+  let v = load_settings_json();
+  v.get("response_cache_enabled").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Lifetime of cached responses in minutes (default 60, max one week)
+pub fn get_response_cache_ttl_minutes_from_settings() -> u64 {
+  let v = load_settings_json();
+  v.get("response_cache_ttl_minutes").and_then(|x| x.as_u64()).map(|n| n.clamp(1, 7 * 24 * 60)).unwrap_or(60)
+}
+
+// Let background jobs (downloads, exports, indexing) wait for the network instead of failing
+pub fn get_offline_queue_enabled_from_settings() -> bool {
+  let v = load_settings_json();
+  v.get("offline_queue_enabled").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Unload local STT models after this many idle minutes (default 15, 0 = keep loaded)
+pub fn get_stt_idle_unload_minutes_from_settings() -> u64 {
+  let v = load_settings_json();
+  v.get("stt_idle_unload_minutes").and_then(|x| x.as_u64()).map(|n| n.min(24 * 60)).unwrap_or(15)
+}
+
+// `assistant_realtime` object saved by Assistant Mode (model, voice, instructions, VAD, ...)
+pub fn get_assistant_realtime_from_settings() -> serde_json::Value {
+  let v = load_settings_json();
+  v.get("assistant_realtime").filter(|x| x.is_object()).cloned().unwrap_or_else(|| serde_json::json!({}))
+}
+
+// `voice_assistant` object for the hands-free loop (wake_word, silence_ms, system_prompt, voice_profile)
+pub fn get_voice_assistant_from_settings() -> serde_json::Value {
+  let v = load_settings_json();
+  v.get("voice_assistant").filter(|x| x.is_object()).cloned().unwrap_or_else(|| serde_json::json!({}))
+}
+
+// `translation` object for live speech translation (target_language, source_language, silence_ms, ...)
+pub fn get_translation_from_settings() -> serde_json::Value {
+  let v = load_settings_json();
+  v.get("translation").filter(|x| x.is_object()).cloned().unwrap_or_else(|| serde_json::json!({}))
+}
+
+// `tts_normalization` object with the text clean-up rules applied before speech synthesis
+// (strip_markdown, skip_code_blocks, expand_abbreviations, urls, spell_numbers, remove_emoji)
+pub fn get_tts_normalization_from_settings() -> serde_json::Value {
+  let v = load_settings_json();
+  v.get("tts_normalization").filter(|x| x.is_object()).cloned().unwrap_or_else(|| serde_json::json!({}))
+}
+
+pub fn get_hotstrings_enabled_from_settings() -> bool {
+  load_settings_json().get("hotstrings_enabled").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Handling of instruction-like text in tool results and attachments: off|flag|strip (default: flag)
+pub fn get_injection_guard_mode_from_settings() -> String {
+  let v = load_settings_json();
+  let m = v.get("injection_guard").and_then(|x| x.as_str()).unwrap_or("flag").trim().to_lowercase();
+  match m.as_str() {
+    "off" | "flag" | "strip" => m,
+    _ => "flag".to_string(),
+  }
+}
+
+// Embedding backend for local retrieval: "local" (on-device MiniLM) or "cloud" (OpenAI); local by default
+pub fn get_embedding_engine_from_settings() -> String {
+  let v = load_settings_json();
+  match v.get("embedding_engine").and_then(|x| x.as_str()).map(|s| s.trim().to_lowercase()).as_deref() {
+    Some("cloud") => "cloud".to_string(),
+    _ => "local".to_string(),
+  }
+}
+
+// Require Windows Hello / polkit before revealing keys, exporting secrets or running destructive MCP tools
+pub fn get_require_os_auth_from_settings() -> bool {
+  load_settings_json().get("require_os_auth").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Assistant memory tools (remember/recall); off by default for privacy
+pub fn get_memory_enabled_from_settings() -> bool {
+  load_settings_json().get("memory_enabled").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Outbound PII filter for selections sent to cloud providers; off by default
+pub fn get_pii_filter_from_settings() -> bool {
+  load_settings_json().get("pii_filter").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Per quick prompt override of `pii_filter`: { "1".."9": bool }; None when not set
+pub fn get_pii_filter_prompt_override(index: u8) -> Option<bool> {
+  load_settings_json().get("pii_filter_prompts").and_then(|m| m.get(index.to_string())).and_then(|x| x.as_bool())
+}
+
+// Built-in email/calendar drafting tools; on by default since drafts only open for review
+pub fn get_draft_tools_enabled_from_settings() -> bool {
+  load_settings_json().get("draft_tools_enabled").and_then(|x| x.as_bool()).unwrap_or(true)
+}
+
+// Current browser tab URL and title as context (browser_context.rs); a privacy switch, off by default
+pub fn get_browser_context_enabled_from_settings() -> bool {
+  load_settings_json().get("browser_context_enabled").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+// Built-in code runner (code_exec.rs); off by default, and each run still needs approval
+pub fn get_code_exec_enabled_from_settings() -> bool {
+  load_settings_json().get("code_exec_enabled").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+pub fn get_code_exec_allow_network_from_settings() -> bool {
+  load_settings_json().get("code_exec_allow_network").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+pub fn get_code_exec_timeout_secs_from_settings() -> u64 {
+  load_settings_json().get("code_exec_timeout_secs").and_then(|x| x.as_u64()).map(|n| n.clamp(1, 300)).unwrap_or(20)
+}
+
+pub fn get_code_exec_memory_mb_from_settings() -> u64 {
+  load_settings_json().get("code_exec_memory_mb").and_then(|x| x.as_u64()).map(|n| n.clamp(64, 8192)).unwrap_or(512)
+}
+
+// OCR engine ("local" = Windows.Media.Ocr, "cloud" = chat model vision); local on Windows by default
+pub fn get_ocr_engine_from_settings() -> String {
+  let v = load_settings_json();
+  match v.get("ocr_engine").and_then(|x| x.as_str()).map(|s| s.trim().to_ascii_lowercase()) {
+    Some(e) if e == "local" || e == "cloud" => e,
+    _ => if cfg!(target_os = "windows") { "local".into() } else { "cloud".into() },
+  }
+}
+
+// BCP-47 language tag for local OCR (e.g. "de-DE"); None = user profile languages
+pub fn get_ocr_language_from_settings() -> Option<String> {
+  let v = load_settings_json();
+  v.get("ocr_language").and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+// Default model for image generation/editing (`image_model`), e.g. gpt-image-1 or dall-e-3
+pub fn get_image_model_from_settings() -> Option<String> {
+  let v = load_settings_json();
+  v.get("image_model").and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+// Expected SHA-256 for a model download, from the optional `model_checksums` object keyed by URL or file name
+pub fn get_model_checksum_from_settings(url: &str, file_name: &str) -> Option<String> {
+  let v = load_settings_json();
+  let map = v.get("model_checksums").and_then(|x| x.as_object())?;
+  map.get(url)
+    .or_else(|| map.get(file_name))
+    .and_then(|x| x.as_str())
+    .map(|s| s.trim().to_string())
+    .filter(|s| !s.is_empty())
+}
+
+pub fn get_clipboard_suggestions_enabled_from_settings() -> bool {
+  let v = load_settings_json();
+  v.get("clipboard_suggestions_enabled").and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
 // Speech-To-Text engine selection: "openai" (default) or "local"
 pub fn get_stt_engine_from_settings_or_env() -> String {
   let v = load_settings_json();
@@ -149,11 +709,7 @@ pub fn get_stt_cloud_model_from_settings_or_env() -> String {
 }
 
 pub fn get_stt_cloud_api_key_from_settings_or_env() -> Option<String> {
-  let v = load_settings_json();
-  if let Some(s) = v.get("stt_cloud_api_key").and_then(|x| x.as_str()) {
-    let t = s.trim();
-    if !t.is_empty() { return Some(t.to_string()); }
-  }
+  if let Some(s) = get_secret("stt_cloud_api_key") { return Some(s); }
   std::env::var("AIDC_STT_CLOUD_API_KEY").ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
 }
 
@@ -195,23 +751,95 @@ pub fn get_stt_post_process_prompt_from_settings_or_env() -> String {
     .unwrap_or(default_prompt)
 }
 
+/// Settings keys holding credentials
+pub const SECRET_KEYS: [&str; 7] = ["openai_api_key", "openai_api_keys_extra", "openai_base_url_api_key", "stt_cloud_api_key", "anthropic_api_key", "gemini_api_key", "elevenlabs_api_key"];
+/// Stands in for a stored secret in `get_settings` while `require_os_auth` is on; ignored by `save_settings`
+pub const SECRET_PLACEHOLDER: &str = "__stored_secret__";
+
+fn mask_secrets(v: &mut serde_json::Value, placeholder: Option<&str>) {
+  let Some(obj) = v.as_object_mut() else { return };
+  for k in SECRET_KEYS {
+    let present = obj.get(k).and_then(|x| x.as_str()).map(|s| !s.trim().is_empty()).unwrap_or(false);
+    match placeholder {
+      Some(p) if present => { obj.insert(k.to_string(), serde_json::Value::String(p.to_string())); }
+      _ => { obj.remove(k); }
+    }
+  }
+}
+
 pub fn get_settings() -> Result<serde_json::Value, String> {
-  let v = load_settings_json();
+  let mut v = load_settings_json();
+  if get_require_os_auth_from_settings() { mask_secrets(&mut v, Some(SECRET_PLACEHOLDER)); }
+  // Keys in the keychain are never sent to the window; `reveal_secret` fetches them on demand
+  if let Some(obj) = v.as_object_mut() {
+    for k in SECRET_KEYS {
+      let plain = obj.get(k).and_then(|x| x.as_str()).is_some_and(|s| !s.trim().is_empty());
+      if !plain && keychain_secret(k).is_some() { obj.insert(k.to_string(), serde_json::Value::String(SECRET_PLACEHOLDER.to_string())); }
+    }
+  }
   Ok(v)
 }
 
-pub fn save_settings(map: serde_json::Value) -> Result<String, String> {
+/// Write the settings as JSON to `path`. API keys are left out unless `include_secrets`, which
+/// requires OS authentication when the policy is on.
+pub async fn export_settings(path: String, include_secrets: bool) -> Result<String, String> {
+  if include_secrets { crate::os_auth::authorize("export_settings", "Export settings including API keys").await?; }
+  let mut v = load_settings_json();
+  if include_secrets {
+    if let Some(obj) = v.as_object_mut() {
+      for k in SECRET_KEYS {
+        if let Some(s) = keychain_secret(k) { obj.insert(k.to_string(), serde_json::Value::String(s)); }
+      }
+    }
+  } else {
+    mask_secrets(&mut v, None);
+  }
+  let text = serde_json::to_string_pretty(&v).map_err(|e| format!("serialize failed: {e}"))?;
+  tokio::fs::write(&path, text).await.map_err(|e| format!("write failed: {e}"))?;
+  Ok(path)
+}
+
+pub async fn save_settings(map: serde_json::Value) -> Result<String, String> {
   let path = settings_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
-  if let Some(dir) = path.parent() {
-    fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {e}"))?;
+  // Turning the re-auth policy off is itself gated, otherwise it would protect nothing
+  if map.get("require_os_auth").and_then(|x| x.as_bool()) == Some(false) && get_require_os_auth_from_settings() {
+    crate::os_auth::authorize("disable_os_auth", "Turn off authentication for sensitive actions").await?;
+  }
+  let mut map = map;
+  if let Some(m) = map.as_object_mut() {
+    m.retain(|k, v| !(SECRET_KEYS.contains(&k.as_str()) && v.as_str() == Some(SECRET_PLACEHOLDER)));
+  }
+  // Keys go to the keychain when one is available; settings.json keeps them only as a fallback
+  let mut secured: Vec<&str> = Vec::new();
+  for k in SECRET_KEYS {
+    let Some(value) = map.get(k).and_then(|x| x.as_str()).map(|s| s.trim().to_string()) else { continue };
+    if keychain_secret(k).unwrap_or_default() == value || set_keychain_secret(k, &value).is_ok() {
+      secured.push(k);
+    } else {
+      tracing::warn!(key = k, "OS keychain unavailable; keeping the API key in settings.json");
+    }
   }
+  if let Some(m) = map.as_object_mut() { m.retain(|k, _| !secured.contains(&k.as_str())); }
+  let _write = WRITE_LOCK.lock().await;
   // Merge with existing settings. Only update known keys present in `map`.
   let current = load_settings_json();
   let mut obj = current.as_object().cloned().unwrap_or_default();
 
   // Existing keys
   if let Some(k) = map.get("openai_api_key").and_then(|x| x.as_str()) { obj.insert("openai_api_key".to_string(), serde_json::Value::String(k.to_string())); }
+  if let Some(k) = map.get("openai_api_keys_extra").and_then(|x| x.as_str()) { obj.insert("openai_api_keys_extra".to_string(), serde_json::Value::String(k.trim().to_string())); }
   if let Some(m) = map.get("openai_chat_model").and_then(|x| x.as_str()) { obj.insert("openai_chat_model".to_string(), serde_json::Value::String(m.to_string())); }
+  if let Some(u) = map.get("openai_base_url").and_then(|x| x.as_str()) { obj.insert("openai_base_url".to_string(), serde_json::Value::String(u.trim().to_string())); }
+  if let Some(k) = map.get("openai_base_url_api_key").and_then(|x| x.as_str()) { obj.insert("openai_base_url_api_key".to_string(), serde_json::Value::String(k.trim().to_string())); }
+  if let Some(p) = map.get("chat_provider").and_then(|x| x.as_str()) { obj.insert("chat_provider".to_string(), serde_json::Value::String(p.to_string())); }
+  if let Some(k) = map.get("anthropic_api_key").and_then(|x| x.as_str()) { obj.insert("anthropic_api_key".to_string(), serde_json::Value::String(k.to_string())); }
+  if let Some(m) = map.get("anthropic_chat_model").and_then(|x| x.as_str()) { obj.insert("anthropic_chat_model".to_string(), serde_json::Value::String(m.to_string())); }
+  if let Some(k) = map.get("gemini_api_key").and_then(|x| x.as_str()) { obj.insert("gemini_api_key".to_string(), serde_json::Value::String(k.to_string())); }
+  if let Some(m) = map.get("gemini_chat_model").and_then(|x| x.as_str()) { obj.insert("gemini_chat_model".to_string(), serde_json::Value::String(m.to_string())); }
+  if let Some(k) = map.get("elevenlabs_api_key").and_then(|x| x.as_str()) { obj.insert("elevenlabs_api_key".to_string(), serde_json::Value::String(k.to_string())); }
+  if let Some(m) = map.get("image_model").and_then(|x| x.as_str()) { obj.insert("image_model".to_string(), serde_json::Value::String(m.to_string())); }
+  if let Some(e) = map.get("ocr_engine").and_then(|x| x.as_str()) { obj.insert("ocr_engine".to_string(), serde_json::Value::String(e.to_string())); }
+  if let Some(l) = map.get("ocr_language").and_then(|x| x.as_str()) { obj.insert("ocr_language".to_string(), serde_json::Value::String(l.to_string())); }
   // Dedicated model for Quick Actions quick prompts (optional; empty string means fallback to global)
   if let Some(qpm) = map.get("quick_prompt_model").and_then(|x| x.as_str()) { obj.insert("quick_prompt_model".to_string(), serde_json::Value::String(qpm.to_string())); }
   if let Some(t) = map.get("temperature").and_then(|x| x.as_f64()) { obj.insert("temperature".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(t).unwrap_or_else(|| serde_json::Number::from_f64(1.0).unwrap()))); }
@@ -223,12 +851,70 @@ pub fn save_settings(map: serde_json::Value) -> Result<String, String> {
   if let Some(hide) = map.get("hide_tool_calls_in_chat").and_then(|x| x.as_bool()) { obj.insert("hide_tool_calls_in_chat".to_string(), serde_json::Value::Bool(hide)); }
   // Persist global hotkey
   if let Some(hk) = map.get("global_hotkey").and_then(|x| x.as_str()) { obj.insert("global_hotkey".to_string(), serde_json::Value::String(hk.to_string())); }
+  if let Some(hk) = map.get("summarize_hotkey").and_then(|x| x.as_str()) { obj.insert("summarize_hotkey".to_string(), serde_json::Value::String(hk.to_string())); }
+  if let Some(hk) = map.get("paste_history_hotkey").and_then(|x| x.as_str()) { obj.insert("paste_history_hotkey".to_string(), serde_json::Value::String(hk.to_string())); }
+  if let Some(hk) = map.get("dictation_hotkey").and_then(|x| x.as_str()) { obj.insert("dictation_hotkey".to_string(), serde_json::Value::String(hk.to_string())); }
+  if let Some(hk) = map.get("hotkeys").and_then(|x| x.as_object()) { obj.insert("hotkeys".to_string(), serde_json::Value::Object(hk.clone())); }
+  if let Some(n) = map.get("recent_outputs_limit").and_then(|x| x.as_u64()) { obj.insert("recent_outputs_limit".to_string(), serde_json::Value::Number(serde_json::Number::from(n.min(200)))); }
+  if let Some(on) = map.get("tts_playback_hotkeys").and_then(|x| x.as_bool()) { obj.insert("tts_playback_hotkeys".to_string(), serde_json::Value::Bool(on)); }
+  if let Some(m) = map.get("tts_muted").and_then(|x| x.as_bool()) { obj.insert("tts_muted".to_string(), serde_json::Value::Bool(m)); }
+  if let Some(on) = map.get("tts_read_from_cursor").and_then(|x| x.as_bool()) { obj.insert("tts_read_from_cursor".to_string(), serde_json::Value::Bool(on)); }
+  if let Some(sp) = map.get("summarize_window_prompt").and_then(|x| x.as_str()) { obj.insert("summarize_window_prompt".to_string(), serde_json::Value::String(sp.to_string())); }
   // Persist global system prompt
   if let Some(sp) = map.get("system_prompt").and_then(|x| x.as_str()) { obj.insert("system_prompt".to_string(), serde_json::Value::String(sp.to_string())); }
   // Persist Quick Prompts specific system prompt
   if let Some(qpsp) = map.get("quick_prompt_system_prompt").and_then(|x| x.as_str()) { obj.insert("quick_prompt_system_prompt".to_string(), serde_json::Value::String(qpsp.to_string())); }
   // Persist Quick Actions preview toggle for quick prompts
   if let Some(flag) = map.get("show_quick_prompt_result_in_popup").and_then(|x| x.as_bool()) { obj.insert("show_quick_prompt_result_in_popup".to_string(), serde_json::Value::Bool(flag)); }
+  // Selection capture format preference (plain | html | both)
+  if let Some(sf) = map.get("selection_format").and_then(|x| x.as_str()) { obj.insert("selection_format".to_string(), serde_json::Value::String(sf.to_string())); }
+  // Opt-in typed abbreviation expansion
+  if let Some(hs) = map.get("hotstrings_enabled").and_then(|x| x.as_bool()) { obj.insert("hotstrings_enabled".to_string(), serde_json::Value::Bool(hs)); }
+  // Opt-in smart clipboard suggestions
+  if let Some(cs) = map.get("clipboard_suggestions_enabled").and_then(|x| x.as_bool()) { obj.insert("clipboard_suggestions_enabled".to_string(), serde_json::Value::Bool(cs)); }
+  // Text insertion method, typing rate and per-app overrides
+  if let Some(im) = map.get("insert_method").and_then(|x| x.as_str()) { obj.insert("insert_method".to_string(), serde_json::Value::String(im.to_string())); }
+  if let Some(rate) = map.get("insert_typing_rate_cps").and_then(|x| x.as_u64()) { obj.insert("insert_typing_rate_cps".to_string(), serde_json::Value::Number(serde_json::Number::from(rate.min(2000)))); }
+  if let Some(ov) = map.get("insert_method_overrides") {
+    if ov.is_object() { obj.insert("insert_method_overrides".to_string(), ov.clone()); }
+  }
+  if let Some(ap) = map.get("app_profiles") {
+    if ap.is_object() { obj.insert("app_profiles".to_string(), ap.clone()); }
+  }
+  if let Some(l) = map.get("log_level").and_then(|x| x.as_str()) { obj.insert("log_level".to_string(), serde_json::Value::String(l.to_string())); }
+  if let Some(g) = map.get("injection_guard").and_then(|x| x.as_str()) { obj.insert("injection_guard".to_string(), serde_json::Value::String(g.to_string())); }
+  if let Some(e) = map.get("embedding_engine").and_then(|x| x.as_str()) { obj.insert("embedding_engine".to_string(), serde_json::Value::String(e.to_string())); }
+  if let Some(ra) = map.get("require_os_auth").and_then(|x| x.as_bool()) { obj.insert("require_os_auth".to_string(), serde_json::Value::Bool(ra)); }
+  if let Some(me) = map.get("memory_enabled").and_then(|x| x.as_bool()) { obj.insert("memory_enabled".to_string(), serde_json::Value::Bool(me)); }
+  if let Some(pf) = map.get("pii_filter").and_then(|x| x.as_bool()) { obj.insert("pii_filter".to_string(), serde_json::Value::Bool(pf)); }
+  if let Some(pp) = map.get("pii_filter_prompts") {
+    if pp.is_object() { obj.insert("pii_filter_prompts".to_string(), pp.clone()); }
+  }
+  if let Some(dt) = map.get("draft_tools_enabled").and_then(|x| x.as_bool()) { obj.insert("draft_tools_enabled".to_string(), serde_json::Value::Bool(dt)); }
+  if let Some(b) = map.get("browser_context_enabled").and_then(|x| x.as_bool()) { obj.insert("browser_context_enabled".to_string(), serde_json::Value::Bool(b)); }
+  if let Some(b) = map.get("code_exec_enabled").and_then(|x| x.as_bool()) { obj.insert("code_exec_enabled".to_string(), serde_json::Value::Bool(b)); }
+  if let Some(b) = map.get("code_exec_allow_network").and_then(|x| x.as_bool()) { obj.insert("code_exec_allow_network".to_string(), serde_json::Value::Bool(b)); }
+  if let Some(n) = map.get("code_exec_timeout_secs").and_then(|x| x.as_u64()) { obj.insert("code_exec_timeout_secs".to_string(), serde_json::Value::Number(serde_json::Number::from(n.clamp(1, 300)))); }
+  if let Some(n) = map.get("code_exec_memory_mb").and_then(|x| x.as_u64()) { obj.insert("code_exec_memory_mb".to_string(), serde_json::Value::Number(serde_json::Number::from(n.clamp(64, 8192)))); }
+  if let Some(rc) = map.get("response_cache_enabled").and_then(|x| x.as_bool()) { obj.insert("response_cache_enabled".to_string(), serde_json::Value::Bool(rc)); }
+  if let Some(ttl) = map.get("response_cache_ttl_minutes").and_then(|x| x.as_u64()) { obj.insert("response_cache_ttl_minutes".to_string(), serde_json::Value::Number(serde_json::Number::from(ttl.clamp(1, 7 * 24 * 60)))); }
+  if let Some(oq) = map.get("offline_queue_enabled").and_then(|x| x.as_bool()) { obj.insert("offline_queue_enabled".to_string(), serde_json::Value::Bool(oq)); }
+  if let Some(n) = map.get("stt_idle_unload_minutes").and_then(|x| x.as_u64()) { obj.insert("stt_idle_unload_minutes".to_string(), serde_json::Value::Number(n.into())); }
+  if let Some(rl) = map.get("rate_limits") {
+    if rl.is_object() { obj.insert("rate_limits".to_string(), rl.clone()); }
+  }
+  if let Some(d) = map.get("safe_mode_defaults") {
+    if d.is_object() { obj.insert("safe_mode_defaults".to_string(), d.clone()); }
+  }
+  if let Some(t) = map.get("timeouts") {
+    if t.is_object() { obj.insert("timeouts".to_string(), t.clone()); }
+  }
+  if let Some(tn) = map.get("tts_normalization") {
+    if tn.is_object() { obj.insert("tts_normalization".to_string(), tn.clone()); }
+  }
+  if let Some(mc) = map.get("model_checksums") {
+    if mc.is_object() { obj.insert("model_checksums".to_string(), mc.clone()); }
+  }
   // Remove deprecated global MCP auto_connect flag if present
   obj.remove("auto_connect");
   // Pass-through for MCP servers configuration when provided
@@ -240,6 +926,18 @@ pub fn save_settings(map: serde_json::Value) -> Result<String, String> {
   if let Some(ar) = map.get("assistant_realtime") {
     if !ar.is_null() { obj.insert("assistant_realtime".to_string(), ar.clone()); }
   }
+  if let Some(va) = map.get("voice_assistant") {
+    if va.is_object() { obj.insert("voice_assistant".to_string(), va.clone()); }
+  }
+  if let Some(tr) = map.get("translation") {
+    if tr.is_object() { obj.insert("translation".to_string(), tr.clone()); }
+  }
+  if let Some(sc) = map.get("schedules") {
+    if sc.is_array() { obj.insert("schedules".to_string(), sc.clone()); }
+  }
+  if let Some(wf) = map.get("watch_folders") {
+    if wf.is_array() { obj.insert("watch_folders".to_string(), wf.clone()); }
+  }
 
   // New TTS preference keys
   if let Some(e) = map.get("tts_engine").and_then(|x| x.as_str()) { obj.insert("tts_engine".to_string(), serde_json::Value::String(e.to_string())); }
@@ -249,8 +947,13 @@ pub fn save_settings(map: serde_json::Value) -> Result<String, String> {
   if let Some(ov) = map.get("tts_openai_voice").and_then(|x| x.as_str()) { obj.insert("tts_openai_voice".to_string(), serde_json::Value::String(ov.to_string())); }
   if let Some(om) = map.get("tts_openai_model").and_then(|x| x.as_str()) { obj.insert("tts_openai_model".to_string(), serde_json::Value::String(om.to_string())); }
   if let Some(of) = map.get("tts_openai_format").and_then(|x| x.as_str()) { obj.insert("tts_openai_format".to_string(), serde_json::Value::String(of.to_string())); }
+  if let Some(lf) = map.get("tts_local_format").and_then(|x| x.as_str()) { obj.insert("tts_local_format".to_string(), serde_json::Value::String(lf.to_string())); }
   if let Some(os) = map.get("tts_openai_streaming").and_then(|x| x.as_bool()) { obj.insert("tts_openai_streaming".to_string(), serde_json::Value::Bool(os)); }
   if let Some(ti) = map.get("tts_openai_instructions").and_then(|x| x.as_str()) { obj.insert("tts_openai_instructions".to_string(), serde_json::Value::String(ti.to_string())); }
+  if let Some(ev) = map.get("tts_elevenlabs_voice").and_then(|x| x.as_str()) { obj.insert("tts_elevenlabs_voice".to_string(), serde_json::Value::String(ev.trim().to_string())); }
+  if let Some(pv) = map.get("tts_piper_voice").and_then(|x| x.as_str()) { obj.insert("tts_piper_voice".to_string(), serde_json::Value::String(pv.trim().to_string())); }
+  if let Some(em) = map.get("tts_elevenlabs_model").and_then(|x| x.as_str()) { obj.insert("tts_elevenlabs_model".to_string(), serde_json::Value::String(em.trim().to_string())); }
+  if let Some(vp) = map.get("tts_voice_profile").and_then(|x| x.as_str()) { obj.insert("tts_voice_profile".to_string(), serde_json::Value::String(vp.to_string())); }
 
   // Tokenizer mode
   if let Some(tm) = map.get("tokenizer_mode").and_then(|x| x.as_str()) { obj.insert("tokenizer_mode".to_string(), serde_json::Value::String(tm.to_string())); }
@@ -263,6 +966,7 @@ pub fn save_settings(map: serde_json::Value) -> Result<String, String> {
   if let Some(sm) = map.get("stt_cloud_model").and_then(|x| x.as_str()) { obj.insert("stt_cloud_model".to_string(), serde_json::Value::String(sm.to_string())); }
   if let Some(sk) = map.get("stt_cloud_api_key").and_then(|x| x.as_str()) { obj.insert("stt_cloud_api_key".to_string(), serde_json::Value::String(sk.to_string())); }
   if let Some(did) = map.get("stt_input_device_id").and_then(|x| x.as_str()) { obj.insert("stt_input_device_id".to_string(), serde_json::Value::String(did.to_string())); }
+  if let Some(dev) = map.get("stt_input_device").and_then(|x| x.as_str()) { obj.insert("stt_input_device".to_string(), serde_json::Value::String(dev.to_string())); }
   if let Some(pp) = map.get("stt_post_process_enabled").and_then(|x| x.as_bool()) { obj.insert("stt_post_process_enabled".to_string(), serde_json::Value::Bool(pp)); }
   if let Some(pm) = map.get("stt_post_process_model").and_then(|x| x.as_str()) { obj.insert("stt_post_process_model".to_string(), serde_json::Value::String(pm.to_string())); }
   if let Some(ppp) = map.get("stt_post_process_prompt").and_then(|x| x.as_str()) { obj.insert("stt_post_process_prompt".to_string(), serde_json::Value::String(ppp.to_string())); }
@@ -279,14 +983,15 @@ pub fn save_settings(map: serde_json::Value) -> Result<String, String> {
 
   // Remove deprecated local STT model selector keys if present
   obj.remove("stt_local_base_url");
+  for k in &secured { obj.remove(*k); }
 
   let pretty = serde_json::to_string_pretty(&serde_json::Value::Object(obj)).map_err(|e| format!("Serialize settings failed: {e}"))?;
-  let tmp_path = path.with_extension("json.tmp");
-  fs::write(&tmp_path, &pretty).map_err(|e| format!("Write settings failed: {e}"))?;
-  // On Windows, fs::rename fails if target exists — remove first
-  #[cfg(target_os = "windows")]
-  { if path.exists() { let _ = fs::remove_file(&path); } }
-  fs::rename(&tmp_path, &path).map_err(|e| format!("Rename settings failed: {e}"))?;
+  write_json_atomic(&path, &pretty).await?;
+  crate::logging::apply_log_level_from_settings();
+  crate::hotstrings::apply_enabled_from_settings();
+  crate::hotkeys::apply_from_settings();
+  crate::audio_io::apply_mute_from_settings();
+  crate::tray::refresh();
   Ok(path.to_string_lossy().to_string())
 }
 
@@ -294,6 +999,9 @@ pub fn save_settings(map: serde_json::Value) -> Result<String, String> {
 // Conversation persistence
 // ---------------------------
 
+// Threads are stored by conversations.rs; these commands keep the window's whole-state interface.
+
+/// Legacy single-file history, imported by conversations.rs on first use
 pub fn conversation_state_path() -> Option<PathBuf> {
   #[cfg(target_os = "windows")]
   {
@@ -327,48 +1035,101 @@ pub fn load_conversation_state() -> Result<serde_json::Value, String> {
   if !persist_conversations_enabled() {
     return Ok(serde_json::json!({}));
   }
+  crate::conversations::load_state()
+}
+
+// Old conversations.json copies; the store holds the same history once imported
+fn remove_legacy_conversation_files() {
   if let Some(path) = conversation_state_path() {
-    match fs::read_to_string(&path) {
-      Ok(text) => {
-        match serde_json::from_str::<serde_json::Value>(&text) {
-          Ok(v) => Ok(v),
-          Err(e) => Err(format!("Invalid JSON in conversations.json: {e}")),
-        }
-      }
-      Err(_) => Ok(serde_json::json!({})),
-    }
-  } else {
-    Err("Unsupported platform for config path".into())
+    let _ = fs::remove_file(backup_path(&path));
+    let _ = fs::remove_file(path);
   }
 }
 
-pub fn save_conversation_state(state: serde_json::Value) -> Result<String, String> {
+pub async fn save_conversation_state(state: serde_json::Value) -> Result<String, String> {
+  *CONVERSATION_SNAPSHOT.lock().unwrap_or_else(|e| e.into_inner()) = Some(state.clone());
+  crate::tray::refresh();
   if !persist_conversations_enabled() {
-    if let Some(path) = conversation_state_path() {
-      let _ = fs::remove_file(path);
-    }
+    let _ = crate::conversations::clear();
+    remove_legacy_conversation_files();
     return Ok("persistence disabled".into());
   }
-  let path = conversation_state_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
-  if let Some(dir) = path.parent() {
-    fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {e}"))?;
-  }
-  let pretty = serde_json::to_string_pretty(&state).map_err(|e| format!("Serialize conversation failed: {e}"))?;
-  let tmp_path = path.with_extension("json.tmp");
-  fs::write(&tmp_path, &pretty).map_err(|e| format!("Write conversations failed: {e}"))?;
-  #[cfg(target_os = "windows")]
-  { if path.exists() { let _ = fs::remove_file(&path); } }
-  fs::rename(&tmp_path, &path).map_err(|e| format!("Rename conversations failed: {e}"))?;
+  let path = crate::conversations::db_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  tokio::task::spawn_blocking(move || crate::conversations::save_state(&state))
+    .await
+    .map_err(|e| format!("Save conversations failed: {e}"))??;
   Ok(path.to_string_lossy().to_string())
 }
 
 pub fn clear_conversations() -> Result<String, String> {
-  if let Some(path) = conversation_state_path() {
-    if path.exists() {
-      fs::remove_file(&path).map_err(|e| format!("Remove conversations failed: {e}"))?;
+  *CONVERSATION_SNAPSHOT.lock().unwrap_or_else(|e| e.into_inner()) = None;
+  crate::tray::refresh();
+  let path = crate::conversations::db_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  crate::conversations::clear()?;
+  remove_legacy_conversation_files();
+  Ok(path.to_string_lossy().to_string())
+}
+
+/// Id and title of the `limit` most recently updated conversations, from the window's latest state
+/// (stored history before the window has sent one).
+pub fn recent_conversations(limit: usize) -> Vec<(String, String)> {
+  let snapshot = CONVERSATION_SNAPSHOT.lock().unwrap_or_else(|e| e.into_inner()).clone();
+  let Some(state) = snapshot else {
+    if !persist_conversations_enabled() {
+      return Vec::new();
+    }
+    let list = crate::conversations::conversation_list().unwrap_or_default();
+    return list.into_iter().take(limit).map(|c| (c.id, c.title)).collect();
+  };
+  let mut list: Vec<&serde_json::Value> = state.get("conversations").and_then(|x| x.as_array()).into_iter().flatten().collect();
+  let updated = |c: &serde_json::Value| c.get("updatedAt").and_then(|x| x.as_f64()).unwrap_or(0.0);
+  list.sort_by(|a, b| updated(b).total_cmp(&updated(a)));
+  list
+    .into_iter()
+    .filter_map(|c| {
+      let id = c.get("id").and_then(|x| x.as_str())?;
+      let title = c.get("title").and_then(|x| x.as_str()).map(str::trim).unwrap_or("");
+      Some((id.to_string(), crate::conversations::display_title(title, c)))
+    })
+    .take(limit)
+    .collect()
+}
+
+/// Shared context of one stored conversation, used by voice sessions that continue it
+pub struct ConversationContext {
+  /// The conversation's own system message, else the global `system_prompt` setting
+  pub system_prompt: Option<String>,
+  /// Last text turns as (role, text), oldest first; only "user" and "assistant" roles
+  pub history: Vec<(String, String)>,
+}
+
+pub fn load_conversation_context(id: &str, max_messages: usize) -> Result<ConversationContext, String> {
+  let snapshot = CONVERSATION_SNAPSHOT.lock().unwrap_or_else(|e| e.into_inner()).clone();
+  let conv = match snapshot {
+    Some(state) => state
+      .get("conversations")
+      .and_then(|x| x.as_array())
+      .and_then(|list| list.iter().find(|c| c.get("id").and_then(|x| x.as_str()) == Some(id)))
+      .cloned(),
+    None if persist_conversations_enabled() => crate::conversations::get(id)?,
+    None => None,
+  };
+  let conv = conv.ok_or_else(|| format!("Conversation not found: {id}"))?;
+  let mut system_prompt = None;
+  let mut history = Vec::new();
+  for m in conv.get("messages").and_then(|x| x.as_array()).into_iter().flatten() {
+    if m.get("type").and_then(|x| x.as_str()) != Some("text") { continue; }
+    let Some(text) = m.get("text").and_then(|x| x.as_str()).map(|t| t.trim()).filter(|t| !t.is_empty()) else { continue };
+    match m.get("role").and_then(|x| x.as_str()) {
+      Some("system") => system_prompt = Some(text.to_string()),
+      Some(role @ ("user" | "assistant")) => history.push((role.to_string(), text.to_string())),
+      _ => {}
     }
-    Ok(path.to_string_lossy().to_string())
-  } else {
-    Err("Unsupported platform for config path".into())
   }
+  let excess = history.len().saturating_sub(max_messages);
+  history.drain(..excess);
+  let system_prompt = system_prompt.or_else(|| {
+    load_settings_json().get("system_prompt").and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+  });
+  Ok(ConversationContext { system_prompt, history })
 }
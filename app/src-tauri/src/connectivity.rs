@@ -0,0 +1,109 @@
+// Connectivity monitor. Probes the network in the background, emits `connectivity:changed`
+// when the state flips and lets non-interactive jobs wait for the connection to come back
+// (opt-in via `offline_queue_enabled`) instead of failing immediately.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use tauri::Emitter;
+use tokio::sync::Notify;
+
+const PROBE_URL: &str = "https://api.openai.com/";
+const ONLINE_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+const OFFLINE_PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+static ONLINE: AtomicBool = AtomicBool::new(true);
+static LAST_CHECKED_MS: AtomicU64 = AtomicU64::new(0);
+static STARTED: OnceCell<()> = OnceCell::new();
+// Wakes the monitor for an immediate re-probe (e.g. after a connect error)
+static RECHECK: Lazy<Notify> = Lazy::new(Notify::new);
+// Wakes everything waiting for the connection to return
+static CHANGED: Lazy<Notify> = Lazy::new(Notify::new);
+
+static PROBE_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+  reqwest::Client::builder()
+    .timeout(Duration::from_secs(5))
+    .connect_timeout(Duration::from_secs(5))
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+#[derive(Serialize, Clone)]
+pub struct ConnectivityStatus {
+  pub online: bool,
+  pub last_checked_ms: u64,
+}
+
+pub fn is_online() -> bool {
+  ONLINE.load(Ordering::Relaxed)
+}
+
+fn status() -> ConnectivityStatus {
+  ConnectivityStatus { online: is_online(), last_checked_ms: LAST_CHECKED_MS.load(Ordering::Relaxed) }
+}
+
+// Any HTTP response (even an error status) means the network path works
+async fn probe() -> bool {
+  PROBE_CLIENT.head(PROBE_URL).send().await.is_ok()
+}
+
+fn set_online(app: &tauri::AppHandle, online: bool) {
+  LAST_CHECKED_MS.store(
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+    Ordering::Relaxed,
+  );
+  if ONLINE.swap(online, Ordering::Relaxed) != online {
+    if online { tracing::info!("network connectivity restored"); } else { tracing::warn!("network connectivity lost"); }
+    let _ = app.emit("connectivity:changed", status());
+    CHANGED.notify_waiters();
+  }
+}
+
+/// Start the background monitor. Called once from setup.
+pub fn start(app: tauri::AppHandle) {
+  if STARTED.set(()).is_err() { return; }
+  tauri::async_runtime::spawn(async move {
+    loop {
+      let online = probe().await;
+      set_online(&app, online);
+      let interval = if online { ONLINE_PROBE_INTERVAL } else { OFFLINE_PROBE_INTERVAL };
+      tokio::select! {
+        _ = RECHECK.notified() => {}
+        _ = tokio::time::sleep(interval) => {}
+      }
+    }
+  });
+}
+
+/// Called when a request failed to connect so the monitor re-probes right away.
+pub fn note_connect_failure() {
+  RECHECK.notify_one();
+}
+
+/// Wait until the network is reachable. Returns immediately when online; when offline and
+/// queueing is disabled in settings, fails with the offline message instead.
+pub async fn wait_until_online() -> Result<(), String> {
+  loop {
+    let changed = CHANGED.notified();
+    if is_online() { return Ok(()); }
+    if !crate::config::get_offline_queue_enabled_from_settings() {
+      return Err(crate::error::AidcError::Offline.message());
+    }
+    changed.await;
+  }
+}
+
+#[tauri::command]
+pub fn get_connectivity() -> Result<ConnectivityStatus, String> {
+  Ok(status())
+}
+
+/// Probe right now and return the fresh state.
+#[tauri::command]
+pub async fn check_connectivity(app: tauri::AppHandle) -> Result<ConnectivityStatus, String> {
+  let online = probe().await;
+  set_online(&app, online);
+  Ok(status())
+}
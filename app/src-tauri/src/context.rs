@@ -0,0 +1,126 @@
+// Workspace context: "what am I working on" signals for chat — the window the user is working in
+// (with the page URL when it is a browser, browser_context.rs), the last selection captured from
+// it, the clipboard and recent screen captures. Gathered only when a request sets
+// `include_desktop_context`, then sent as a <desktop_context> block right before the latest user
+// message. The text comes from other apps, so it runs through the injection guard and is sent as
+// user content, never as a system message.
+
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+use crate::chat::{ChatContent, ChatMessage};
+
+// Per text field; clipboards and selections can be whole documents
+const MAX_FIELD_CHARS: usize = 2000;
+const MAX_CAPTURES: usize = 3;
+const CAPTURE_MAX_AGE: Duration = Duration::from_secs(30 * 60);
+// File name prefix used by capture_region
+const CAPTURE_PREFIX: &str = "aidc_capture_";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RecentCapture {
+  pub path: String,
+  pub age_secs: u64,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct DesktopContext {
+  pub window_title: Option<String>,
+  /// Executable of that window, e.g. "Code.exe"
+  pub app: Option<String>,
+  /// Tab shown in that window when it is a browser
+  pub browser_page: Option<crate::browser_context::BrowserPage>,
+  /// Last selection captured by a quick action
+  pub selected_text: Option<String>,
+  pub clipboard: Option<String>,
+  /// Newest first
+  pub recent_captures: Vec<RecentCapture>,
+}
+
+fn clip(s: String) -> Option<String> {
+  let s = s.trim();
+  if s.is_empty() {
+    return None;
+  }
+  if s.chars().count() <= MAX_FIELD_CHARS {
+    return Some(s.to_string());
+  }
+  let cut: String = s.chars().take(MAX_FIELD_CHARS).collect();
+  Some(format!("{cut}… [truncated]"))
+}
+
+fn recent_captures() -> Vec<RecentCapture> {
+  let now = SystemTime::now();
+  let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else { return Vec::new() };
+  let mut found: Vec<RecentCapture> = entries
+    .flatten()
+    .filter(|e| e.file_name().to_string_lossy().starts_with(CAPTURE_PREFIX))
+    .filter_map(|e| {
+      let age = now.duration_since(e.metadata().ok()?.modified().ok()?).ok()?;
+      (age <= CAPTURE_MAX_AGE).then(|| RecentCapture { path: e.path().to_string_lossy().to_string(), age_secs: age.as_secs() })
+    })
+    .collect();
+  found.sort_by_key(|c| c.age_secs);
+  found.truncate(MAX_CAPTURES);
+  found
+}
+
+/// Collect the current signals. Blocking (clipboard and window queries).
+pub fn gather() -> DesktopContext {
+  let hwnd = crate::quick_actions::target_window_handle_raw();
+  DesktopContext {
+    window_title: hwnd.and_then(|h| clip(crate::hotstrings::window_title(h))),
+    app: hwnd.and_then(|h| clip(crate::quick_actions::process_name_for_window(h))),
+    browser_page: hwnd.and_then(crate::browser_context::page_for_window),
+    selected_text: clip(crate::quick_actions::last_selected_text()),
+    clipboard: arboard::Clipboard::new().ok().and_then(|mut c| c.get_text().ok()).and_then(clip),
+    recent_captures: recent_captures(),
+  }
+}
+
+fn to_block(app: &tauri::AppHandle, ctx: &DesktopContext) -> Option<String> {
+  let mut lines: Vec<String> = Vec::new();
+  if let Some(t) = &ctx.window_title {
+    let owner = ctx.app.as_deref().map(|a| format!(" ({a})")).unwrap_or_default();
+    lines.push(format!("Active window: {t}{owner}"));
+  }
+  if let Some(p) = &ctx.browser_page {
+    // "this page" most likely means this tab; the model can fetch the URL with a fetch tool
+    lines.push(format!("Browser tab: {}\nURL: {}", p.title, p.url));
+  }
+  if let Some(s) = &ctx.selected_text {
+    let s = crate::pii::filter_text(app, "desktop_context:selection", s.clone(), crate::pii::enabled());
+    lines.push(format!("Selected text:\n{}", crate::guard::guard_text(app, "desktop_context:selection", s)));
+  }
+  if let Some(c) = &ctx.clipboard {
+    let c = crate::pii::filter_text(app, "desktop_context:clipboard", c.clone(), crate::pii::enabled());
+    lines.push(format!("Clipboard:\n{}", crate::guard::guard_text(app, "desktop_context:clipboard", c)));
+  }
+  if !ctx.recent_captures.is_empty() {
+    let list: Vec<String> = ctx.recent_captures.iter().map(|c| format!("- {} ({} min ago)", c.path, c.age_secs / 60)).collect();
+    lines.push(format!("Recent screen captures:\n{}", list.join("\n")));
+  }
+  if lines.is_empty() {
+    return None;
+  }
+  Some(format!(
+    "<desktop_context>\nWhat the user is currently working on; use it only when relevant to the request.\n\n{}\n</desktop_context>",
+    lines.join("\n\n")
+  ))
+}
+
+/// Insert the desktop context block before the last user message (or at the end).
+pub async fn inject(app: &tauri::AppHandle, messages: &mut Vec<ChatMessage>) -> Result<(), String> {
+  let ctx = tokio::task::spawn_blocking(gather).await.map_err(|e| format!("context task failed: {e}"))?;
+  let Some(block) = to_block(app, &ctx) else { return Ok(()) };
+  let at = messages.iter().rposition(|m| m.role.eq_ignore_ascii_case("user")).unwrap_or(messages.len());
+  messages.insert(at, ChatMessage { role: "user".into(), content: ChatContent::Text(block) });
+  Ok(())
+}
+
+/// Preview of what `include_desktop_context` would send.
+#[tauri::command]
+pub async fn get_desktop_context() -> Result<DesktopContext, String> {
+  tokio::task::spawn_blocking(gather).await.map_err(|e| format!("context task failed: {e}"))
+}
@@ -0,0 +1,398 @@
+// Conversation history: every chat thread is a row of conversations.sqlite (next to settings.json)
+// holding the thread as the window persists it (JSON), its title and an FTS5 index over title and
+// message text for `conversation_search`. Replaces the single conversations.json blob; an existing
+// conversations.json is imported when the store is first opened and kept as
+// conversations.json.migrated until history is cleared.
+//
+// The main window syncs its whole state through save_conversation_state (threads missing from the
+// state are removed). conversation_create / _rename / _delete change the store directly and emit
+// `conversations:changed` (events::ConversationsChanged) so an open window applies the same change.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::AppHandle;
+
+const MAX_TITLE_CHARS: usize = 120;
+// Titles derived from the first message are cut to this
+const DERIVED_TITLE_CHARS: usize = 40;
+const DEFAULT_SEARCH_LIMIT: usize = 30;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSummary {
+  pub id: String,
+  /// Set title, or the start of the first message
+  pub title: String,
+  pub created_at: u64,
+  pub updated_at: u64,
+  pub message_count: u32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSearchHit {
+  pub id: String,
+  pub title: String,
+  pub updated_at: u64,
+  /// Matching passage with the hits wrapped in [ ]
+  pub snippet: String,
+}
+
+// Opened on first use
+static DB: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+pub fn db_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name("conversations.sqlite"))
+}
+
+fn migrated_legacy_path() -> Option<PathBuf> {
+  crate::config::conversation_state_path().map(|p| p.with_extension("json.migrated"))
+}
+
+/// Close the database (app shutdown); the next use reopens it.
+pub fn close() {
+  DB.lock().unwrap_or_else(|e| e.into_inner()).take();
+}
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+  let mut guard = DB.lock().unwrap_or_else(|e| e.into_inner());
+  if guard.is_none() {
+    let path = db_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir).map_err(|e| format!("Create config dir failed: {e}"))?;
+    }
+    let conn = Connection::open(&path).map_err(|e| format!("Open conversation database failed: {e}"))?;
+    conn
+      .execute_batch(
+        "CREATE TABLE IF NOT EXISTS conversations (
+           id TEXT PRIMARY KEY,
+           title TEXT NOT NULL DEFAULT '',
+           created_ms INTEGER NOT NULL,
+           updated_ms INTEGER NOT NULL,
+           message_count INTEGER NOT NULL DEFAULT 0,
+           data TEXT NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+         CREATE VIRTUAL TABLE IF NOT EXISTS conversations_fts USING fts5(id UNINDEXED, title, body);",
+      )
+      .map_err(|e| format!("Create conversation tables failed: {e}"))?;
+    import_legacy_file(&conn);
+    *guard = Some(conn);
+  }
+  let conn = guard.as_ref().ok_or_else(|| "conversation database unavailable".to_string())?;
+  f(conn).map_err(|e| format!("Conversation database error: {e}"))
+}
+
+// One-time import of conversations.json (both the current { conversations, currentId } shape and
+// the older { currentConversation } one). The file is renamed only after the import committed.
+fn import_legacy_file(conn: &Connection) {
+  let Some(path) = crate::config::conversation_state_path() else { return };
+  let Ok(text) = std::fs::read_to_string(&path) else { return };
+  let state = match serde_json::from_str::<serde_json::Value>(&text) {
+    Ok(v) => v,
+    Err(e) => {
+      tracing::warn!(error = %e, "conversations.json is not valid JSON; not imported");
+      return;
+    }
+  };
+  let state = match state.get("currentConversation") {
+    Some(cc) if state.get("conversations").is_none() => {
+      serde_json::json!({ "conversations": [cc], "currentId": cc.get("id").cloned().unwrap_or_default() })
+    }
+    _ => state,
+  };
+  match sync_state(conn, &state, false) {
+    Ok(n) => {
+      tracing::info!(conversations = n, "imported conversations.json into conversations.sqlite");
+      if let Some(dest) = migrated_legacy_path() {
+        let _ = std::fs::rename(&path, dest);
+      }
+    }
+    Err(e) => tracing::warn!(error = %e, "conversations.json import failed"),
+  }
+}
+
+fn conv_id(conv: &serde_json::Value) -> Option<&str> {
+  conv.get("id").and_then(|x| x.as_str()).filter(|s| !s.trim().is_empty())
+}
+
+fn conv_ms(conv: &serde_json::Value, key: &str, fallback: u64) -> u64 {
+  conv.get(key).and_then(|x| x.as_f64()).map(|v| v.max(0.0) as u64).unwrap_or(fallback)
+}
+
+fn message_texts(conv: &serde_json::Value) -> impl Iterator<Item = &str> {
+  conv
+    .get("messages")
+    .and_then(|x| x.as_array())
+    .into_iter()
+    .flatten()
+    .filter_map(|m| m.get("text").and_then(|x| x.as_str()))
+    .filter(|t| !t.trim().is_empty())
+}
+
+fn set_title(conv: &serde_json::Value) -> String {
+  conv.get("title").and_then(|x| x.as_str()).map(|s| s.trim().to_string()).unwrap_or_default()
+}
+
+// Shown for untitled threads, like the history list in the window
+pub(crate) fn display_title(title: &str, data: &serde_json::Value) -> String {
+  if !title.is_empty() {
+    return title.to_string();
+  }
+  match message_texts(data).next() {
+    Some(first) => first.trim().chars().take(DERIVED_TITLE_CHARS).collect(),
+    None => "New conversation".to_string(),
+  }
+}
+
+fn upsert(conn: &Connection, conv: &serde_json::Value) -> rusqlite::Result<()> {
+  let Some(id) = conv_id(conv) else { return Ok(()) };
+  let now = crate::local_models::now_ms();
+  let created = conv_ms(conv, "createdAt", now);
+  let updated = conv_ms(conv, "updatedAt", created);
+  let title = set_title(conv);
+  let count = conv.get("messages").and_then(|x| x.as_array()).map(|m| m.len()).unwrap_or(0) as i64;
+  let data = conv.to_string();
+  // Unchanged threads (most of them on every window save) are skipped, index included
+  let changed = conn.execute(
+    "INSERT INTO conversations (id, title, created_ms, updated_ms, message_count, data) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+     ON CONFLICT(id) DO UPDATE SET title = excluded.title, created_ms = excluded.created_ms, updated_ms = excluded.updated_ms,
+       message_count = excluded.message_count, data = excluded.data
+     WHERE conversations.data <> excluded.data",
+    params![id, title, created as i64, updated as i64, count, data],
+  )?;
+  if changed > 0 {
+    let body = message_texts(conv).collect::<Vec<_>>().join("\n");
+    conn.execute("DELETE FROM conversations_fts WHERE id = ?1", params![id])?;
+    conn.execute("INSERT INTO conversations_fts (id, title, body) VALUES (?1, ?2, ?3)", params![id, title, body])?;
+  }
+  Ok(())
+}
+
+fn delete_row(conn: &Connection, id: &str) -> rusqlite::Result<usize> {
+  conn.execute("DELETE FROM conversations_fts WHERE id = ?1", params![id])?;
+  conn.execute("DELETE FROM conversations WHERE id = ?1", params![id])
+}
+
+// Store the window's { conversations, currentId } state; with `prune`, threads not in it are removed
+fn sync_state(conn: &Connection, state: &serde_json::Value, prune: bool) -> rusqlite::Result<usize> {
+  let convs: Vec<&serde_json::Value> = state.get("conversations").and_then(|x| x.as_array()).map(|a| a.iter().collect()).unwrap_or_default();
+  let tx = conn.unchecked_transaction()?;
+  for conv in &convs {
+    upsert(&tx, conv)?;
+  }
+  if prune {
+    let keep: std::collections::HashSet<&str> = convs.iter().filter_map(|c| conv_id(c)).collect();
+    let existing: Vec<String> = {
+      let mut stmt = tx.prepare("SELECT id FROM conversations")?;
+      let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+      rows.collect::<rusqlite::Result<_>>()?
+    };
+    for id in existing.iter().filter(|id| !keep.contains(id.as_str())) {
+      delete_row(&tx, id)?;
+    }
+  }
+  if let Some(current) = state.get("currentId").and_then(|x| x.as_str()) {
+    tx.execute("INSERT INTO meta (key, value) VALUES ('current_id', ?1) ON CONFLICT(key) DO UPDATE SET value = excluded.value", params![current])?;
+  }
+  tx.commit()?;
+  Ok(convs.len())
+}
+
+/// All threads in the shape save_state received them ({ conversations, currentId }), most
+/// recently updated first; an empty object when nothing is stored.
+pub fn load_state() -> Result<serde_json::Value, String> {
+  with_db(|c| {
+    let mut stmt = c.prepare("SELECT data FROM conversations ORDER BY updated_ms DESC")?;
+    let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+    let convs: Vec<serde_json::Value> = rows.filter_map(|r| r.ok()).filter_map(|d| serde_json::from_str(&d).ok()).collect();
+    if convs.is_empty() {
+      return Ok(serde_json::json!({}));
+    }
+    let current: Option<String> = c.query_row("SELECT value FROM meta WHERE key = 'current_id'", [], |r| r.get(0)).optional()?;
+    Ok(serde_json::json!({ "conversations": convs, "currentId": current.unwrap_or_default() }))
+  })
+}
+
+pub fn save_state(state: &serde_json::Value) -> Result<(), String> {
+  with_db(|c| sync_state(c, state, true)).map(|_| ())
+}
+
+/// One stored thread (window JSON shape), or None.
+pub fn get(id: &str) -> Result<Option<serde_json::Value>, String> {
+  let data: Option<String> = with_db(|c| c.query_row("SELECT data FROM conversations WHERE id = ?1", params![id], |r| r.get(0)).optional())?;
+  Ok(data.and_then(|d| serde_json::from_str(&d).ok()))
+}
+
+/// Paths of the images attached to stored threads (each message image's `path`).
+pub fn attached_paths() -> Result<Vec<PathBuf>, String> {
+  fn collect(v: &serde_json::Value, out: &mut Vec<PathBuf>) {
+    match v {
+      serde_json::Value::Object(map) => {
+        for (k, x) in map {
+          match (k.as_str(), x.as_str()) {
+            ("path", Some(p)) => out.push(PathBuf::from(p)),
+            _ => collect(x, out),
+          }
+        }
+      }
+      serde_json::Value::Array(items) => items.iter().for_each(|x| collect(x, out)),
+      _ => {}
+    }
+  }
+  let rows: Vec<String> = with_db(|c| {
+    let mut stmt = c.prepare("SELECT data FROM conversations WHERE instr(data, '\"path\"') > 0")?;
+    let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+  })?;
+  let mut out = Vec::new();
+  for data in rows.iter().filter_map(|d| serde_json::from_str::<serde_json::Value>(d).ok()) {
+    collect(&data, &mut out);
+  }
+  Ok(out)
+}
+
+/// Remove every stored thread, and the imported conversations.json copy with it.
+pub fn clear() -> Result<(), String> {
+  with_db(|c| c.execute_batch("DELETE FROM conversations; DELETE FROM conversations_fts; DELETE FROM meta;"))?;
+  if let Some(p) = migrated_legacy_path() {
+    let _ = std::fs::remove_file(p);
+  }
+  Ok(())
+}
+
+fn summary(id: String, title: String, created: i64, updated: i64, count: i64, data: &str) -> ConversationSummary {
+  let data = serde_json::from_str::<serde_json::Value>(data).unwrap_or_default();
+  ConversationSummary {
+    id,
+    title: display_title(&title, &data),
+    created_at: created.max(0) as u64,
+    updated_at: updated.max(0) as u64,
+    message_count: count.max(0) as u32,
+  }
+}
+
+fn load_summary(c: &Connection, id: &str) -> rusqlite::Result<Option<ConversationSummary>> {
+  c.query_row(
+    "SELECT id, title, created_ms, updated_ms, message_count, data FROM conversations WHERE id = ?1",
+    params![id],
+    |r| Ok(summary(r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, &r.get::<_, String>(5)?)),
+  )
+  .optional()
+}
+
+fn require_persistence() -> Result<(), String> {
+  if crate::config::persist_conversations_enabled() {
+    Ok(())
+  } else {
+    Err("Conversation history is off; enable 'Persist conversations' in settings".into())
+  }
+}
+
+fn emit_changed(app: &AppHandle, action: &'static str, id: &str, conversation: Option<serde_json::Value>) {
+  crate::events::emit(app, crate::events::CONVERSATIONS_CHANGED, &crate::events::ConversationsChanged { action, id: id.to_string(), conversation });
+}
+
+// Quote every word so FTS5 operators and punctuation in user input are matched literally;
+// the last word also matches as a prefix (search while typing)
+fn fts_query(query: &str) -> Option<String> {
+  let words: Vec<String> = query.split_whitespace().map(|w| format!("\"{}\"", w.replace('"', "\"\""))).collect();
+  let last = words.len().checked_sub(1)?;
+  Some(words.iter().enumerate().map(|(i, w)| if i == last { format!("{w}*") } else { w.clone() }).collect::<Vec<_>>().join(" "))
+}
+
+/// Stored threads, most recently updated first.
+#[tauri::command]
+pub fn conversation_list() -> Result<Vec<ConversationSummary>, String> {
+  with_db(|c| {
+    let mut stmt = c.prepare("SELECT id, title, created_ms, updated_ms, message_count, data FROM conversations ORDER BY updated_ms DESC")?;
+    let rows = stmt.query_map([], |r| Ok(summary(r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, &r.get::<_, String>(5)?)))?;
+    rows.collect()
+  })
+}
+
+/// Create an empty thread, optionally titled.
+#[tauri::command]
+pub fn conversation_create(app: AppHandle, title: Option<String>) -> Result<ConversationSummary, String> {
+  require_persistence()?;
+  let title = title.map(|t| t.trim().to_string()).unwrap_or_default();
+  if title.chars().count() > MAX_TITLE_CHARS {
+    return Err(format!("Title must be at most {MAX_TITLE_CHARS} characters"));
+  }
+  let now = crate::local_models::now_ms();
+  let id = format!("c_{}", uuid::Uuid::new_v4().simple());
+  let mut conv = serde_json::json!({ "id": id, "createdAt": now, "updatedAt": now, "messages": [] });
+  if !title.is_empty() {
+    conv["title"] = serde_json::Value::String(title);
+  }
+  let created = with_db(|c| {
+    upsert(c, &conv)?;
+    load_summary(c, &id)
+  })?
+  .ok_or_else(|| "Conversation was not stored".to_string())?;
+  emit_changed(&app, "created", &id, Some(conv));
+  Ok(created)
+}
+
+/// Rename a thread; an empty title goes back to the start of the first message.
+#[tauri::command]
+pub fn conversation_rename(app: AppHandle, id: String, title: String) -> Result<ConversationSummary, String> {
+  require_persistence()?;
+  let title = title.trim().to_string();
+  if title.chars().count() > MAX_TITLE_CHARS {
+    return Err(format!("Title must be at most {MAX_TITLE_CHARS} characters"));
+  }
+  let mut conv = get(&id)?.ok_or_else(|| format!("Conversation not found: {id}"))?;
+  if title.is_empty() {
+    if let Some(obj) = conv.as_object_mut() {
+      obj.remove("title");
+    }
+  } else {
+    conv["title"] = serde_json::Value::String(title.clone());
+  }
+  let renamed = with_db(|c| {
+    upsert(c, &conv)?;
+    load_summary(c, &id)
+  })?
+  .ok_or_else(|| format!("Conversation not found: {id}"))?;
+  emit_changed(&app, "renamed", &id, Some(serde_json::json!({ "id": id, "title": title })));
+  Ok(renamed)
+}
+
+/// Delete a thread; false when it did not exist.
+#[tauri::command]
+pub fn conversation_delete(app: AppHandle, id: String) -> Result<bool, String> {
+  let removed = with_db(|c| delete_row(c, &id))? > 0;
+  if removed {
+    emit_changed(&app, "deleted", &id, None);
+  }
+  Ok(removed)
+}
+
+/// Full-text search over titles and message text, best matches first.
+#[tauri::command]
+pub fn conversation_search(query: String, limit: Option<usize>) -> Result<Vec<ConversationSearchHit>, String> {
+  let Some(q) = fts_query(&query) else { return Ok(Vec::new()) };
+  let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, 200) as i64;
+  with_db(|c| {
+    let mut stmt = c.prepare(
+      "SELECT c.id, c.title, c.updated_ms, c.data, snippet(conversations_fts, 2, '[', ']', '…', 12)
+       FROM conversations_fts f JOIN conversations c ON c.id = f.id
+       WHERE conversations_fts MATCH ?1 ORDER BY bm25(conversations_fts, 0.0, 5.0, 1.0) LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(params![q, limit], |r| {
+      let data = serde_json::from_str::<serde_json::Value>(&r.get::<_, String>(3)?).unwrap_or_default();
+      Ok(ConversationSearchHit {
+        id: r.get(0)?,
+        title: display_title(&r.get::<_, String>(1)?, &data),
+        updated_at: r.get::<_, i64>(2)?.max(0) as u64,
+        snippet: r.get(4)?,
+      })
+    })?;
+    rows.collect()
+  })
+}
@@ -0,0 +1,268 @@
+// Crash reports written to <app dir>/crashes. A panic hook writes a text report with the app
+// version, backtrace and the last log lines; panics inside `catch_unwind` are handled by design and
+// only logged. On Windows an unhandled exception filter catches native crashes in STT/ONNX code;
+// the process is in an unknown state then, so it only appends the exception code and address to a
+// file opened at startup, which becomes a full report on the next start. Reports not yet seen
+// are surfaced on next startup.
+
+use std::cell::Cell;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+
+const REPORT_PREFIX: &str = "crash-";
+const REPORT_SUFFIX: &str = ".txt";
+// Reports are renamed with this suffix once the user has seen them
+const SEEN_SUFFIX: &str = ".seen.txt";
+const LOG_TAIL_LINES: usize = 200;
+const MAX_REPORTS: usize = 20;
+
+static INSTALLED: OnceCell<()> = OnceCell::new();
+// Open handle the exception filter writes to, so it needs no allocation or path lookup
+#[cfg(target_os = "windows")]
+static NATIVE_LOG: OnceCell<fs::File> = OnceCell::new();
+#[cfg(target_os = "windows")]
+const NATIVE_PENDING: &str = "native.pending";
+
+thread_local! {
+  // Set while code whose panics are caught and handled runs (see `catch_unwind`)
+  static EXPECTED_PANIC: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Serialize)]
+pub struct CrashReportInfo {
+  pub file: String,
+  pub path: String,
+  pub created_ms: u64,
+  /// First line of the crash message
+  pub summary: String,
+}
+
+pub fn crashes_dir() -> Option<PathBuf> {
+  #[cfg(target_os = "windows")]
+  {
+    if let Ok(appdata) = std::env::var("APPDATA") {
+      let mut p = PathBuf::from(appdata);
+      p.push("AiDesktopCompanion");
+      p.push("crashes");
+      return Some(p);
+    }
+    None
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    if let Ok(home) = std::env::var("HOME") {
+      let mut p = PathBuf::from(home);
+      p.push(".config");
+      p.push("AiDesktopCompanion");
+      p.push("crashes");
+      return Some(p);
+    }
+    None
+  }
+}
+
+fn write_report(kind: &str, summary: &str, details: &str) -> Option<PathBuf> {
+  let dir = crashes_dir()?;
+  fs::create_dir_all(&dir).ok()?;
+  let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f");
+  let path = dir.join(format!("{REPORT_PREFIX}{stamp}{REPORT_SUFFIX}"));
+  let logs = crate::logging::get_recent_logs(Some(LOG_TAIL_LINES)).unwrap_or_default();
+  let thread = std::thread::current();
+  let body = format!(
+    "{summary}\n\nkind: {kind}\nversion: {}\nos: {} {}\nthread: {}\ntime: {}\n\n{details}\n\n--- last {LOG_TAIL_LINES} log lines ---\n{logs}\n",
+    env!("CARGO_PKG_VERSION"),
+    std::env::consts::OS,
+    std::env::consts::ARCH,
+    thread.name().unwrap_or("<unnamed>"),
+    chrono::Local::now().to_rfc3339(),
+  );
+  let mut f = fs::File::create(&path).ok()?;
+  f.write_all(body.as_bytes()).ok()?;
+  let _ = f.flush();
+  Some(path)
+}
+
+/// `std::panic::catch_unwind` for code that is expected to panic now and then (UIA providers of
+/// other apps, window geometry); such panics are logged but not reported as crashes.
+pub fn catch_unwind<F: FnOnce() -> R + std::panic::UnwindSafe, R>(f: F) -> std::thread::Result<R> {
+  let outer = EXPECTED_PANIC.with(|c| c.replace(true));
+  let result = std::panic::catch_unwind(f);
+  EXPECTED_PANIC.with(|c| c.set(outer));
+  result
+}
+
+// Turn what the exception filter recorded during the last run into a report
+#[cfg(target_os = "windows")]
+fn collect_native_report(pending: &std::path::Path) {
+  let Ok(text) = fs::read_to_string(pending) else { return };
+  let Some(summary) = text.lines().find(|l| !l.trim().is_empty()) else { return };
+  let details = format!("recorded by the exception filter of the previous run (no backtrace):
+{}", text.trim());
+  let _ = write_report("native", summary.trim(), &details);
+  let _ = fs::write(pending, "");
+}
+
+/// Install the panic hook and, on Windows, the unhandled exception filter. Call once, early.
+pub fn install() {
+  if INSTALLED.set(()).is_err() { return; }
+  let previous = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    let message = info
+      .payload()
+      .downcast_ref::<&str>()
+      .map(|s| s.to_string())
+      .or_else(|| info.payload().downcast_ref::<String>().cloned())
+      .unwrap_or_else(|| "<non-string panic payload>".to_string());
+    let location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())).unwrap_or_default();
+    if EXPECTED_PANIC.with(|c| c.get()) {
+      tracing::warn!(%message, %location, "panic caught and handled");
+      return;
+    }
+    let backtrace = std::backtrace::Backtrace::force_capture();
+    tracing::error!(%message, %location, "panic");
+    let summary = format!("panic: {message}");
+    let details = format!("location: {location}\n\nbacktrace:\n{backtrace}");
+    if let Some(path) = write_report("panic", &summary, &details) {
+      eprintln!("crash report written to {}", path.display());
+    }
+    previous(info);
+  }));
+  #[cfg(target_os = "windows")]
+  {
+    use windows::Win32::System::Diagnostics::Debug::SetUnhandledExceptionFilter;
+    if let Some(dir) = crashes_dir().filter(|d| fs::create_dir_all(d).is_ok()) {
+      let pending = dir.join(NATIVE_PENDING);
+      collect_native_report(&pending);
+      if let Ok(file) = fs::OpenOptions::new().create(true).append(true).open(&pending) {
+        let _ = NATIVE_LOG.set(file);
+      }
+    }
+    unsafe {
+      SetUnhandledExceptionFilter(Some(unhandled_exception_filter));
+    }
+  }
+}
+
+// Last-chance handler for native faults (access violations etc.) that bypass the panic hook.
+// The heap may be corrupt: format on the stack, append to the pre-opened file and let Windows
+// continue with default crash handling.
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn unhandled_exception_filter(
+  info: *const windows::Win32::System::Diagnostics::Debug::EXCEPTION_POINTERS,
+) -> i32 {
+  use windows::Win32::System::Diagnostics::Debug::EXCEPTION_CONTINUE_SEARCH;
+  let (code, address) = if !info.is_null() && !(*info).ExceptionRecord.is_null() {
+    let rec = &*(*info).ExceptionRecord;
+    (rec.ExceptionCode.0 as u32, rec.ExceptionAddress as usize)
+  } else {
+    (0, 0)
+  };
+  let mut buf = [0u8; 64];
+  let mut line = std::io::Cursor::new(&mut buf[..]);
+  let _ = writeln!(line, "native exception 0x{code:08X} at 0x{address:016X}");
+  let len = line.position() as usize;
+  if let Some(mut file) = NATIVE_LOG.get() {
+    let _ = file.write_all(&buf[..len]);
+    let _ = file.sync_all();
+  }
+  EXCEPTION_CONTINUE_SEARCH
+}
+
+fn report_files(include_seen: bool) -> Vec<PathBuf> {
+  let Some(dir) = crashes_dir() else { return Vec::new() };
+  let mut files: Vec<PathBuf> = fs::read_dir(dir)
+    .map(|rd| {
+      rd.filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+          p.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+            n.starts_with(REPORT_PREFIX) && n.ends_with(REPORT_SUFFIX) && (include_seen || !n.ends_with(SEEN_SUFFIX))
+          })
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  files.sort();
+  files
+}
+
+// Keep the crashes folder bounded; oldest reports go first
+fn prune_reports() {
+  let files = report_files(true);
+  if files.len() > MAX_REPORTS {
+    for p in &files[..files.len() - MAX_REPORTS] { let _ = fs::remove_file(p); }
+  }
+}
+
+/// Crash reports written since the user last dismissed them (i.e. from previous runs).
+#[tauri::command]
+pub fn get_pending_crash_reports() -> Result<Vec<CrashReportInfo>, String> {
+  prune_reports();
+  Ok(
+    report_files(false)
+      .into_iter()
+      .map(|p| {
+        let summary = fs::read_to_string(&p).ok().and_then(|s| s.lines().next().map(|l| l.to_string())).unwrap_or_default();
+        let created_ms = fs::metadata(&p)
+          .and_then(|m| m.modified())
+          .ok()
+          .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+          .map(|d| d.as_millis() as u64)
+          .unwrap_or(0);
+        CrashReportInfo {
+          file: p.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string(),
+          path: p.to_string_lossy().to_string(),
+          created_ms,
+          summary,
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Mark all pending crash reports as seen. Returns how many were marked.
+#[tauri::command]
+pub fn dismiss_crash_reports() -> Result<usize, String> {
+  let mut n = 0usize;
+  for p in report_files(false) {
+    let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
+    let seen = p.with_file_name(format!("{}{SEEN_SUFFIX}", name.trim_end_matches(REPORT_SUFFIX)));
+    if fs::rename(&p, &seen).is_ok() { n += 1; }
+  }
+  Ok(n)
+}
+
+#[tauri::command]
+pub fn open_crash_folder() -> Result<(), String> {
+  let dir = crashes_dir().ok_or_else(|| "Could not resolve crashes directory".to_string())?;
+  fs::create_dir_all(&dir).map_err(|e| format!("Failed to create crashes directory: {e}"))?;
+  #[cfg(target_os = "windows")]
+  {
+    Command::new("explorer.exe")
+      .arg(dir)
+      .spawn()
+      .map_err(|e| format!("Failed to open crashes folder: {e}"))?;
+    Ok(())
+  }
+  #[cfg(target_os = "macos")]
+  {
+    Command::new("open")
+      .arg(dir)
+      .spawn()
+      .map_err(|e| format!("Failed to open crashes folder: {e}"))?;
+    Ok(())
+  }
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  {
+    Command::new("xdg-open")
+      .arg(dir)
+      .spawn()
+      .map_err(|e| format!("Failed to open crashes folder: {e}"))?;
+    Ok(())
+  }
+}
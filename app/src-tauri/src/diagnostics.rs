@@ -0,0 +1,286 @@
+// Self-check for the settings health dashboard: API key, configured models, MCP servers,
+// CUDA, free disk space for models, clipboard access and the global hotkeys. Each check
+// reports ok / warn / error / skipped with a human-readable message.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::mcp::ClientMap;
+
+// Below this much free space model downloads are likely to fail
+const LOW_DISK_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+const MCP_PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+  Ok,
+  Warn,
+  Error,
+  Skipped,
+}
+
+#[derive(Serialize)]
+pub struct DiagnosticCheck {
+  pub id: String,
+  pub label: String,
+  pub status: CheckStatus,
+  pub message: String,
+  pub duration_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct DiagnosticsReport {
+  pub version: String,
+  pub generated_at_ms: u64,
+  /// False when any check reported an error
+  pub ok: bool,
+  pub checks: Vec<DiagnosticCheck>,
+}
+
+struct Checks(Vec<DiagnosticCheck>);
+
+impl Checks {
+  fn push(&mut self, id: &str, label: impl Into<String>, started: Instant, status: CheckStatus, message: impl Into<String>) {
+    self.0.push(DiagnosticCheck {
+      id: id.to_string(),
+      label: label.into(),
+      status,
+      message: message.into(),
+      duration_ms: started.elapsed().as_millis() as u64,
+    });
+  }
+}
+
+// All model ids visible to the key, or the error from the models endpoint
+async fn fetch_openai_models(key: &str) -> Result<Vec<String>, crate::error::AidcError> {
+  let client = reqwest::Client::builder()
+    .timeout(Duration::from_secs(15))
+    .connect_timeout(Duration::from_secs(10))
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new());
+  let resp = client
+    .get("https://api.openai.com/v1/models")
+    .bearer_auth(key)
+    .send()
+    .await
+    .map_err(|e| crate::error::AidcError::from_reqwest("openai", &e))?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    return Err(crate::error::AidcError::from_status("openai", status, &body));
+  }
+  let v: serde_json::Value = resp
+    .json()
+    .await
+    .map_err(|e| crate::error::AidcError::invalid_response("openai", format!("json error: {e}")))?;
+  Ok(
+    v.get("data")
+      .and_then(|d| d.as_array())
+      .map(|arr| arr.iter().filter_map(|m| m.get("id").and_then(|x| x.as_str()).map(|s| s.to_string())).collect())
+      .unwrap_or_default(),
+  )
+}
+
+async fn check_openai(checks: &mut Checks) {
+  let started = Instant::now();
+  let key = match crate::config::get_api_key_from_settings_or_env() {
+    Ok(k) => k,
+    Err(_) => {
+      checks.push("api_key", "OpenAI API key", started, CheckStatus::Error, "No API key configured (settings or OPENAI_API_KEY).");
+      checks.push("models", "Configured models", started, CheckStatus::Skipped, "Requires a valid API key.");
+      return;
+    }
+  };
+  let models = match fetch_openai_models(&key).await {
+    Ok(m) => {
+      checks.push("api_key", "OpenAI API key", started, CheckStatus::Ok, format!("Key accepted; {} models available.", m.len()));
+      m
+    }
+    Err(e) => {
+      checks.push("api_key", "OpenAI API key", started, CheckStatus::Error, e.message());
+      checks.push("models", "Configured models", started, CheckStatus::Skipped, "Requires a valid API key.");
+      return;
+    }
+  };
+
+  let started = Instant::now();
+  let settings = crate::config::load_settings_json();
+  let mut wanted: Vec<(String, &str)> = vec![(crate::config::get_model_from_settings_or_env(), "chat")];
+  if let Some(qp) = settings.get("quick_prompt_model").and_then(|x| x.as_str()).map(|s| s.trim()).filter(|s| !s.is_empty()) {
+    wanted.push((qp.to_string(), "quick prompts"));
+  }
+  let stt_base = crate::config::get_stt_cloud_base_url_from_settings_or_env();
+  if crate::config::get_stt_engine_from_settings_or_env() != "local" && stt_base.trim().starts_with("https://api.openai.com") {
+    wanted.push((crate::config::get_stt_cloud_model_from_settings_or_env(), "speech-to-text"));
+  }
+  let missing: Vec<String> = wanted
+    .iter()
+    .filter(|(m, _)| !models.iter().any(|id| id == m))
+    .map(|(m, what)| format!("{m} ({what})"))
+    .collect();
+  if missing.is_empty() {
+    let names: Vec<&str> = wanted.iter().map(|(m, _)| m.as_str()).collect();
+    checks.push("models", "Configured models", started, CheckStatus::Ok, format!("Available: {}", names.join(", ")));
+  } else {
+    checks.push("models", "Configured models", started, CheckStatus::Error, format!("Not available for this key: {}", missing.join(", ")));
+  }
+}
+
+async fn check_mcp(checks: &mut Checks, clients: &AsyncMutex<ClientMap>) {
+  let settings = crate::config::load_settings_json();
+  let servers: Vec<(String, bool)> = settings
+    .get("mcp_servers")
+    .and_then(|x| x.as_array())
+    .map(|arr| {
+      arr.iter()
+        .filter_map(|s| {
+          let id = s.get("id").and_then(|x| x.as_str())?.trim().to_string();
+          if id.is_empty() { return None; }
+          Some((id, s.get("auto_connect").and_then(|x| x.as_bool()).unwrap_or(false)))
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  if servers.is_empty() {
+    checks.push("mcp", "MCP servers", Instant::now(), CheckStatus::Skipped, "No MCP servers configured.");
+    return;
+  }
+  for (id, auto_connect) in servers {
+    let started = Instant::now();
+    let connected = clients.lock().await.contains_key(&id);
+    let label = format!("MCP server {id}");
+    let check_id = format!("mcp:{id}");
+    if !connected {
+      let status = if auto_connect { CheckStatus::Error } else { CheckStatus::Skipped };
+      let msg = if auto_connect { "Not connected (auto-connect is enabled)." } else { "Not connected." };
+      checks.push(&check_id, label, started, status, msg);
+      continue;
+    }
+    match tokio::time::timeout(MCP_PING_TIMEOUT, crate::mcp::list_tools(clients, &id)).await {
+      Ok(Ok(v)) => {
+        let n = v.get("tools").and_then(|t| t.as_array()).map(|a| a.len()).unwrap_or(0);
+        checks.push(&check_id, label, started, CheckStatus::Ok, format!("Connected; {n} tools."));
+      }
+      Ok(Err(e)) => checks.push(&check_id, label, started, CheckStatus::Error, e.message()),
+      Err(_) => checks.push(&check_id, label, started, CheckStatus::Error, "Connected but not responding (timed out listing tools)."),
+    }
+  }
+}
+
+async fn check_cuda(checks: &mut Checks) {
+  let started = Instant::now();
+  let uses_parakeet = crate::config::get_stt_engine_from_settings_or_env() == "local"
+    && crate::config::get_stt_local_model_from_settings_or_env().to_lowercase().contains("parakeet");
+  let wants_cuda = crate::config::get_stt_parakeet_has_cuda_from_settings_or_env();
+  // ONNX Runtime session creation blocks
+  let res = tokio::task::spawn_blocking(crate::stt_parakeet::check_cuda_available)
+    .await
+    .unwrap_or_else(|e| Err(format!("CUDA check failed: {e}")));
+  match res {
+    Ok(()) => checks.push("cuda", "CUDA (Parakeet)", started, CheckStatus::Ok, "CUDA is available."),
+    Err(e) if uses_parakeet && wants_cuda => checks.push("cuda", "CUDA (Parakeet)", started, CheckStatus::Error, e),
+    Err(e) => checks.push("cuda", "CUDA (Parakeet)", started, CheckStatus::Skipped, format!("Not available ({e}); CPU is used.")),
+  }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn free_disk_bytes(path: &Path) -> Option<u64> {
+  use windows::core::HSTRING;
+  use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+  let mut free: u64 = 0;
+  unsafe { GetDiskFreeSpaceExW(&HSTRING::from(path.as_os_str()), Some(&mut free as *mut u64), None, None).ok()? };
+  Some(free)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn free_disk_bytes(path: &Path) -> Option<u64> {
+  // POSIX df: second line, fourth column is available 1K blocks
+  let out = std::process::Command::new("df").arg("-Pk").arg(path).output().ok()?;
+  let text = String::from_utf8_lossy(&out.stdout);
+  let kb: u64 = text.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+  Some(kb * 1024)
+}
+
+fn check_disk(checks: &mut Checks) {
+  let started = Instant::now();
+  let Some(models_root) = crate::stt_whisper::models_dir().and_then(|p| p.parent().map(|p| p.to_path_buf())) else {
+    checks.push("disk", "Disk space for models", started, CheckStatus::Skipped, "Models folder could not be resolved.");
+    return;
+  };
+  // The models folder may not exist yet; measure the closest existing ancestor
+  let mut probe: PathBuf = models_root.clone();
+  while !probe.exists() {
+    match probe.parent() {
+      Some(p) => probe = p.to_path_buf(),
+      None => break,
+    }
+  }
+  match free_disk_bytes(&probe) {
+    Some(free) => {
+      let gb = free as f64 / (1024.0 * 1024.0 * 1024.0);
+      let msg = format!("{gb:.1} GB free at {}", models_root.display());
+      let status = if free < LOW_DISK_BYTES { CheckStatus::Warn } else { CheckStatus::Ok };
+      checks.push("disk", "Disk space for models", started, status, msg);
+    }
+    None => checks.push("disk", "Disk space for models", started, CheckStatus::Warn, "Could not determine free disk space."),
+  }
+}
+
+fn check_clipboard(checks: &mut Checks) {
+  let started = Instant::now();
+  match arboard::Clipboard::new() {
+    Ok(mut cb) => match cb.get_text() {
+      Ok(_) => checks.push("clipboard", "Clipboard access", started, CheckStatus::Ok, "Clipboard is readable."),
+      // Empty or non-text content is not a failure
+      Err(arboard::Error::ContentNotAvailable) => {
+        checks.push("clipboard", "Clipboard access", started, CheckStatus::Ok, "Clipboard is accessible (no text content).")
+      }
+      Err(e) => checks.push("clipboard", "Clipboard access", started, CheckStatus::Error, format!("Clipboard read failed: {e}")),
+    },
+    Err(e) => checks.push("clipboard", "Clipboard access", started, CheckStatus::Error, format!("Clipboard unavailable: {e}")),
+  }
+}
+
+fn check_hotkey(checks: &mut Checks, app: &tauri::AppHandle) {
+  let started = Instant::now();
+  let bindings = crate::hotkeys::status();
+  let mut active = Vec::new();
+  let mut failed = Vec::new();
+  for b in &bindings {
+    match (&b.active, &b.error) {
+      (Some(sc), _) if app.global_shortcut().is_registered(sc.replace("Win", "Super").as_str()) => active.push(format!("{} ({sc})", b.label)),
+      (Some(sc), _) => failed.push(format!("{}: {sc} is no longer registered", b.label)),
+      (None, Some(e)) => failed.push(format!("{}: {e}", b.label)),
+      (None, None) => {}
+    }
+  }
+  if !failed.is_empty() {
+    checks.push("hotkey", "Global hotkeys", started, CheckStatus::Error, failed.join("\n"));
+  } else if active.is_empty() {
+    checks.push("hotkey", "Global hotkeys", started, CheckStatus::Skipped, "No global hotkeys are registered.");
+  } else {
+    checks.push("hotkey", "Global hotkeys", started, CheckStatus::Ok, format!("Registered: {}.", active.join(", ")));
+  }
+}
+
+pub async fn run(app: tauri::AppHandle, clients: &AsyncMutex<ClientMap>) -> DiagnosticsReport {
+  let mut checks = Checks(Vec::new());
+  check_openai(&mut checks).await;
+  check_mcp(&mut checks, clients).await;
+  check_cuda(&mut checks).await;
+  check_disk(&mut checks);
+  check_clipboard(&mut checks);
+  check_hotkey(&mut checks, &app);
+  let ok = !checks.0.iter().any(|c| c.status == CheckStatus::Error);
+  DiagnosticsReport {
+    version: env!("CARGO_PKG_VERSION").to_string(),
+    generated_at_ms: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+    ok,
+    checks: checks.0,
+  }
+}
@@ -0,0 +1,167 @@
+// Push-to-talk dictation. While the dictation hotkey (settings.dictation_hotkey, registered in
+// hotkeys.ts) is held, the microphone (stt_input_device) is recorded here; on release the
+// recording goes through the selected STT engine (including post-processing, like
+// stt_transcribe) and the text is typed into the focused app with insert_text_into_focused_app.
+//
+// Events (events.rs):
+//   dictation:state  DictationState  "recording" -> "transcribing" -> "idle", or "error"
+//   audio:level      AudioLevel      input level while recording (source "dictation")
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+use crate::assistant::SAMPLE_RATE;
+use crate::audio_io;
+use crate::events::{self, DictationState};
+
+// Presses shorter than this are treated as accidental taps
+const MIN_SAMPLES: usize = 4_800; // 300 ms
+// Recording stops on its own after this; the text is still inserted on release
+const MAX_SAMPLES: usize = 300 * SAMPLE_RATE as usize;
+
+pub const STATE_RECORDING: &str = "recording";
+pub const STATE_TRANSCRIBING: &str = "transcribing";
+pub const STATE_IDLE: &str = "idle";
+pub const STATE_ERROR: &str = "error";
+
+struct Recording {
+  cancel: CancellationToken,
+  done: oneshot::Receiver<Vec<i16>>,
+}
+
+enum Slot {
+  /// Pressed, microphone still opening; cancelled by a release that comes first
+  Starting(CancellationToken),
+  Recording(Recording),
+}
+
+static RECORDING: Lazy<Mutex<Option<Slot>>> = Lazy::new(|| Mutex::new(None));
+// Set from release until the text is inserted, so a quick second press does not overlap
+static TRANSCRIBING: AtomicBool = AtomicBool::new(false);
+
+fn emit_state(app: &AppHandle, state: &str, text: Option<String>, error: Option<String>) {
+  events::emit(app, events::DICTATION_STATE, &DictationState { state: state.to_string(), text, error });
+}
+
+/// Start recording for push-to-talk (hotkey pressed). Repeated presses while recording are
+/// ignored, since held keys auto-repeat.
+#[tauri::command]
+pub async fn dictation_start(app: AppHandle) -> Result<(), String> {
+  // Reserve the slot before the microphone opens, so a second press cannot open it again and a
+  // release in the meantime cancels the start
+  let starting = CancellationToken::new();
+  {
+    let mut slot = RECORDING.lock().unwrap_or_else(|e| e.into_inner());
+    if slot.is_some() {
+      return Ok(());
+    }
+    if TRANSCRIBING.load(Ordering::SeqCst) {
+      return Err("Still transcribing the previous dictation".into());
+    }
+    *slot = Some(Slot::Starting(starting.clone()));
+  }
+  let opened = match tokio::task::spawn_blocking(|| audio_io::start_mic(SAMPLE_RATE)).await {
+    Ok(Ok(v)) => Ok(v),
+    Ok(Err(e)) => Err(e),
+    Err(e) => Err(format!("audio task failed: {e}")),
+  };
+
+  let cancel = CancellationToken::new();
+  let (done_tx, done_rx) = oneshot::channel::<Vec<i16>>();
+  let (mic, mut mic_rx) = {
+    let mut slot = RECORDING.lock().unwrap_or_else(|e| e.into_inner());
+    if starting.is_cancelled() {
+      // Released before the microphone was open; dictation_stop already cleared the slot
+      return Ok(());
+    }
+    match opened {
+      Ok(v) => {
+        *slot = Some(Slot::Recording(Recording { cancel: cancel.clone(), done: done_rx }));
+        v
+      }
+      Err(e) => {
+        *slot = None;
+        drop(slot);
+        emit_state(&app, STATE_ERROR, None, Some(e.clone()));
+        return Err(e);
+      }
+    }
+  };
+  emit_state(&app, STATE_RECORDING, None, None);
+  tracing::info!("dictation recording started");
+
+  let mut meter = audio_io::LevelMeter::new(&app, "dictation");
+  tauri::async_runtime::spawn(async move {
+    let mut pcm: Vec<i16> = Vec::new();
+    loop {
+      tokio::select! {
+        _ = cancel.cancelled() => break,
+        chunk = mic_rx.recv() => {
+          let Some((samples, _level)) = chunk else { break };
+          meter.feed(&samples);
+          pcm.extend_from_slice(&samples);
+          if pcm.len() >= MAX_SAMPLES { break; }
+        }
+      }
+    }
+    drop(mic);
+    let _ = done_tx.send(pcm);
+  });
+  Ok(())
+}
+
+async fn transcribe_and_insert(pcm: Vec<i16>) -> Result<Option<String>, String> {
+  let wav = crate::assistant::wav_bytes(&pcm)?;
+  let res = crate::stt_transcribe_inner(wav, "audio/wav".into(), None, None).await.map_err(String::from)?;
+  let text = res.final_text.trim().to_string();
+  if text.is_empty() {
+    return Ok(None);
+  }
+  crate::recent_outputs::record(crate::recent_outputs::TRANSCRIPTION, "Dictation", &text);
+  let insert = text.clone();
+  tokio::task::spawn_blocking(move || crate::quick_actions::insert_text_into_focused_app(insert, None, None))
+    .await
+    .map_err(|e| format!("spawn_blocking failed: {e}"))??;
+  Ok(Some(text))
+}
+
+/// Stop recording (hotkey released), transcribe and type the text into the focused app.
+/// Returns the inserted text; None when nothing was recording or nothing was said.
+#[tauri::command]
+pub async fn dictation_stop(app: AppHandle) -> Result<Option<String>, String> {
+  let slot = RECORDING.lock().unwrap_or_else(|e| e.into_inner()).take();
+  let recording = match slot {
+    None => return Ok(None),
+    Some(Slot::Starting(starting)) => {
+      starting.cancel();
+      emit_state(&app, STATE_IDLE, None, None);
+      return Ok(None);
+    }
+    Some(Slot::Recording(r)) => r,
+  };
+  recording.cancel.cancel();
+  let pcm = recording.done.await.unwrap_or_default();
+  if pcm.len() < MIN_SAMPLES {
+    emit_state(&app, STATE_IDLE, None, None);
+    return Ok(None);
+  }
+
+  TRANSCRIBING.store(true, Ordering::SeqCst);
+  emit_state(&app, STATE_TRANSCRIBING, None, None);
+  tracing::info!(seconds = pcm.len() / SAMPLE_RATE as usize, "dictation recording stopped");
+  let res = transcribe_and_insert(pcm).await;
+  TRANSCRIBING.store(false, Ordering::SeqCst);
+  match &res {
+    Ok(text) => emit_state(&app, STATE_IDLE, text.clone(), None),
+    Err(e) => {
+      tracing::warn!(error = %e, "dictation failed");
+      emit_state(&app, STATE_ERROR, None, Some(e.clone()));
+    }
+  }
+  res
+}
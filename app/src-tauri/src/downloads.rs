@@ -0,0 +1,249 @@
+// Download manager for model assets (Whisper, Parakeet, and future local engines). Downloads go
+// to `<file>.part` and resume from there after a cancel, crash or dropped connection, are
+// verified against a SHA-256 checksum when one is known, and are limited to a few at a time.
+// Progress is reported through the job manager (`job:update`), so every downloader shares the
+// same event schema: current/total bytes with the file name as detail.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use tokio::sync::Semaphore;
+
+use crate::jobs::JobReporter;
+
+const MAX_CONCURRENT_DOWNLOADS: usize = 2;
+// Attempts per file; each retry resumes from the bytes already on disk
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+  reqwest::Client::builder()
+    .connect_timeout(Duration::from_secs(30))
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+static SLOTS: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(MAX_CONCURRENT_DOWNLOADS));
+// Destinations currently being written, so two jobs never share a .part file
+static ACTIVE: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+pub struct DownloadSpec<'a> {
+  pub url: &'a str,
+  pub dest: PathBuf,
+  /// Expected SHA-256 (hex), for assets pinned in code (the Piper release archives). Falls back to
+  /// `model_checksums` in settings, which covers the model files fetched from moving branches.
+  pub sha256: Option<String>,
+  /// Reject files smaller than this (guards against HTML error pages)
+  pub min_size: Option<u64>,
+}
+
+impl<'a> DownloadSpec<'a> {
+  pub fn new(url: &'a str, dest: PathBuf) -> Self {
+    Self { url, dest, sha256: None, min_size: None }
+  }
+
+  pub fn sha256(mut self, hex: impl Into<String>) -> Self {
+    self.sha256 = Some(hex.into());
+    self
+  }
+
+  pub fn min_size(mut self, bytes: u64) -> Self {
+    self.min_size = Some(bytes);
+    self
+  }
+}
+
+pub fn part_path(dest: &Path) -> PathBuf {
+  let name = dest.file_name().and_then(|s| s.to_str()).unwrap_or("download");
+  dest.with_file_name(format!("{name}.part"))
+}
+
+struct ActiveGuard(PathBuf);
+
+impl Drop for ActiveGuard {
+  fn drop(&mut self) {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.0);
+  }
+}
+
+// Hash the bytes of a partial download so a resumed download can still be verified
+fn hash_existing(path: &Path, hasher: &mut Sha256) -> Result<u64, String> {
+  let mut f = fs::File::open(path).map_err(|e| format!("open partial download failed: {e}"))?;
+  let mut buf = vec![0u8; 1024 * 1024];
+  let mut n_total = 0u64;
+  loop {
+    let n = f.read(&mut buf).map_err(|e| format!("read partial download failed: {e}"))?;
+    if n == 0 { break; }
+    hasher.update(&buf[..n]);
+    n_total += n as u64;
+  }
+  Ok(n_total)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+enum Attempt {
+  Done(String),
+  // Transient failure; the .part file is kept for the next attempt
+  Retry(String),
+}
+
+async fn attempt(job: Option<&JobReporter>, url: &str, tmp: &Path, file_name: &str) -> Result<Attempt, String> {
+  let mut hasher = Sha256::new();
+  let mut offset = if tmp.exists() {
+    let tmp = tmp.to_path_buf();
+    let (n, h) = tokio::task::spawn_blocking(move || {
+      let mut h = Sha256::new();
+      hash_existing(&tmp, &mut h).map(|n| (n, h))
+    })
+    .await
+    .map_err(|e| format!("hash task failed: {e}"))??;
+    hasher = h;
+    n
+  } else {
+    0
+  };
+
+  let mut req = CLIENT.get(url);
+  if offset > 0 {
+    req = req.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+  }
+  // timeouts.downloads bounds the wait for the response and for each chunk, not the whole file
+  let stall = crate::timeouts::get(crate::timeouts::DOWNLOADS);
+  let resp = match tokio::time::timeout(stall, req.send()).await {
+    Ok(Ok(r)) => r,
+    Ok(Err(e)) => {
+      if e.is_connect() { crate::connectivity::note_connect_failure(); }
+      return Ok(Attempt::Retry(format!("download failed: {e}")));
+    }
+    Err(_) => return Ok(Attempt::Retry(format!("download timed out: no response within {} s (timeouts.downloads)", stall.as_secs()))),
+  };
+  let status = resp.status();
+  if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+    // The partial file is already complete (or larger than the remote); start over
+    let _ = fs::remove_file(tmp);
+    return Ok(Attempt::Retry(format!("download error: {status}")));
+  }
+  if status.is_server_error() {
+    return Ok(Attempt::Retry(format!("download error: {status}")));
+  }
+  if !status.is_success() {
+    return Err(format!("download error: {status}"));
+  }
+  let resumed = offset > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT;
+  if offset > 0 && !resumed {
+    // Server ignored the Range header; restart from scratch
+    tracing::info!(file = file_name, "server does not support resume; restarting download");
+    hasher = Sha256::new();
+    offset = 0;
+  }
+  let total = resp.content_length().map(|n| n + offset);
+  let mut f = if resumed {
+    fs::OpenOptions::new().append(true).open(tmp).map_err(|e| format!("open tmp failed: {e}"))?
+  } else {
+    fs::File::create(tmp).map_err(|e| format!("write tmp failed: {e}"))?
+  };
+  let mut received = offset;
+  if let Some(job) = job { job.progress(received, total, Some(file_name)); }
+  let mut stream = resp.bytes_stream();
+  loop {
+    let chunk = match tokio::time::timeout(stall, stream.next()).await {
+      Ok(Some(chunk)) => chunk,
+      Ok(None) => break,
+      Err(_) => {
+        let _ = f.flush();
+        return Ok(Attempt::Retry(format!("download stalled: no data for {} s (timeouts.downloads)", stall.as_secs())));
+      }
+    };
+    let bytes = match chunk {
+      Ok(b) => b,
+      Err(e) => {
+        let _ = f.flush();
+        return Ok(Attempt::Retry(format!("download chunk failed: {e}")));
+      }
+    };
+    f.write_all(&bytes).map_err(|e| format!("write failed: {e}"))?;
+    hasher.update(&bytes);
+    received += bytes.len() as u64;
+    if let Some(job) = job { job.progress(received, total, Some(file_name)); }
+  }
+  f.flush().map_err(|e| format!("write failed: {e}"))?;
+  drop(f);
+  if let Some(t) = total {
+    if received < t {
+      return Ok(Attempt::Retry(format!("download incomplete: {received} of {t} bytes")));
+    }
+  }
+  Ok(Attempt::Done(to_hex(&hasher.finalize())))
+}
+
+/// Download `spec.url` to `spec.dest`, resuming any partial download, verifying the checksum
+/// when known and renaming into place only once complete.
+pub async fn download(job: Option<&JobReporter>, spec: DownloadSpec<'_>) -> Result<PathBuf, String> {
+  let dest = spec.dest.clone();
+  if let Some(dir) = dest.parent() {
+    fs::create_dir_all(dir).map_err(|e| format!("create model dir failed: {e}"))?;
+  }
+  {
+    let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    if !active.insert(dest.clone()) {
+      return Err(format!("{} is already being downloaded", dest.display()));
+    }
+  }
+  let _active = ActiveGuard(dest.clone());
+  let file_name = dest.file_name().and_then(|s| s.to_str()).unwrap_or("download").to_string();
+  let expected = spec
+    .sha256
+    .or_else(|| crate::config::get_model_checksum_from_settings(spec.url, &file_name))
+    .map(|s| s.trim().to_lowercase());
+
+  let _slot = SLOTS.acquire().await.map_err(|_| "download manager closed".to_string())?;
+  let tmp = part_path(&dest);
+  let mut last_err = String::new();
+  let mut digest = None;
+  for n in 1..=MAX_ATTEMPTS {
+    match attempt(job, spec.url, &tmp, &file_name).await? {
+      Attempt::Done(d) => {
+        digest = Some(d);
+        break;
+      }
+      Attempt::Retry(e) => {
+        tracing::warn!(file = %file_name, attempt = n, error = %e, "download attempt failed");
+        last_err = e;
+        if n < MAX_ATTEMPTS {
+          if let Some(job) = job { job.wait_for_network().await?; }
+          tokio::time::sleep(RETRY_DELAY).await;
+        }
+      }
+    }
+  }
+  let digest = digest.ok_or(last_err)?;
+
+  let size = fs::metadata(&tmp).map(|m| m.len()).unwrap_or(0);
+  if let Some(min) = spec.min_size {
+    if size < min {
+      let _ = fs::remove_file(&tmp);
+      return Err(format!("downloaded file {file_name} is too small ({size} bytes)"));
+    }
+  }
+  if let Some(exp) = expected {
+    if digest != exp {
+      let _ = fs::remove_file(&tmp);
+      return Err(format!("checksum mismatch for {file_name}: expected {exp}, got {digest}"));
+    }
+  }
+  #[cfg(target_os = "windows")]
+  { if dest.exists() { let _ = fs::remove_file(&dest); } }
+  fs::rename(&tmp, &dest).map_err(|e| format!("rename model failed: {e}"))?;
+  tracing::info!(file = %file_name, size, sha256 = %digest, "download complete");
+  Ok(dest)
+}
@@ -0,0 +1,316 @@
+// Built-in drafting tools: `drafts__email` opens a new message in the mail client and
+// `drafts__calendar_event` writes an .ics file and opens it in the calendar app, so "draft a reply
+// and open it in my mail client" works without an MCP server. Nothing is sent or booked: the user
+// reviews the draft in their own app. On Windows emails go through Outlook (COM, via PowerShell)
+// when it is installed, otherwise through a mailto: link. Offered next to the MCP tools in chat and
+// the Responses backend unless `draft_tools_enabled` is turned off.
+
+use std::path::PathBuf;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+
+pub const EMAIL_TOOL: &str = "drafts__email";
+pub const CALENDAR_TOOL: &str = "drafts__calendar_event";
+const DRAFTS_DIR: &str = "drafts";
+// Windows hands mailto: URLs to the mail client through a ~2 KB command line
+const MAX_MAILTO_CHARS: usize = 1800;
+const DEFAULT_EVENT_MINUTES: i64 = 60;
+
+pub fn enabled() -> bool {
+  crate::config::get_draft_tools_enabled_from_settings()
+}
+
+fn drafts_dir() -> Result<PathBuf, String> {
+  let dir = crate::config::settings_config_path()
+    .map(|p| p.with_file_name(DRAFTS_DIR))
+    .ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  std::fs::create_dir_all(&dir).map_err(|e| format!("Create drafts folder failed: {e}"))?;
+  Ok(dir)
+}
+
+// Open a file or URL with the system's default handler
+async fn open_default(target: &str) -> Result<(), String> {
+  #[cfg(target_os = "windows")]
+  let mut cmd = {
+    // rundll32 passes the argument through untouched; `cmd /C start` would split URLs at '&'
+    let mut c = tokio::process::Command::new("rundll32.exe");
+    c.args(["url.dll,FileProtocolHandler", target]);
+    c
+  };
+  #[cfg(target_os = "macos")]
+  let mut cmd = {
+    let mut c = tokio::process::Command::new("open");
+    c.arg(target);
+    c
+  };
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  let mut cmd = {
+    let mut c = tokio::process::Command::new("xdg-open");
+    c.arg(target);
+    c
+  };
+  cmd.spawn().map(|_| ()).map_err(|e| format!("Open default app failed: {e}"))
+}
+
+// ----- Email
+
+struct EmailDraft {
+  to: String,
+  cc: String,
+  subject: String,
+  body: String,
+}
+
+// RFC 6068: everything outside the unreserved set is percent-encoded (spaces as %20, not '+')
+fn mailto_encode(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  for b in s.bytes() {
+    if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+      out.push(b as char);
+    } else {
+      out.push_str(&format!("%{b:02X}"));
+    }
+  }
+  out
+}
+
+// Returns the URL and whether the body had to be shortened
+fn mailto_url(d: &EmailDraft) -> (String, bool) {
+  let mut url = format!("mailto:{}", d.to.split([',', ';']).map(|a| mailto_encode(a.trim())).filter(|a| !a.is_empty()).collect::<Vec<_>>().join(","));
+  let mut params = Vec::new();
+  if !d.cc.trim().is_empty() { params.push(format!("cc={}", mailto_encode(d.cc.trim()))); }
+  if !d.subject.trim().is_empty() { params.push(format!("subject={}", mailto_encode(d.subject.trim()))); }
+  if !params.is_empty() {
+    url.push('?');
+    url.push_str(&params.join("&"));
+  }
+  let body = d.body.replace("\r\n", "\n").replace('\n', "\r\n");
+  if body.trim().is_empty() {
+    return (url, false);
+  }
+  url.push(if params.is_empty() { '?' } else { '&' });
+  url.push_str("body=");
+  let room = MAX_MAILTO_CHARS.saturating_sub(url.len());
+  let mut encoded = String::new();
+  let mut truncated = false;
+  for c in body.chars() {
+    let e = mailto_encode(c.encode_utf8(&mut [0u8; 4]));
+    if encoded.len() + e.len() > room {
+      truncated = true;
+      break;
+    }
+    encoded.push_str(&e);
+  }
+  url.push_str(&encoded);
+  (url, truncated)
+}
+
+// New Outlook item shown for editing; the draft is passed as JSON on stdin to avoid quoting issues
+#[cfg(target_os = "windows")]
+async fn outlook_draft(d: &EmailDraft) -> Result<(), String> {
+  use tokio::io::AsyncWriteExt;
+  let ps = "$ErrorActionPreference='Stop'; $d = [Console]::In.ReadToEnd() | ConvertFrom-Json; \
+            $o = New-Object -ComObject Outlook.Application; $m = $o.CreateItem(0); \
+            $m.To = $d.to; $m.CC = $d.cc; $m.Subject = $d.subject; $m.Body = $d.body; $m.Display()";
+  let payload = serde_json::json!({ "to": d.to, "cc": d.cc, "subject": d.subject, "body": d.body }).to_string();
+  let mut child = tokio::process::Command::new("powershell.exe")
+    .args(["-NoProfile", "-NonInteractive", "-Command", ps])
+    .stdin(std::process::Stdio::piped())
+    .stdout(std::process::Stdio::null())
+    .stderr(std::process::Stdio::null())
+    .spawn()
+    .map_err(|e| format!("launch powershell failed: {e}"))?;
+  if let Some(mut stdin) = child.stdin.take() {
+    stdin.write_all(payload.as_bytes()).await.map_err(|e| format!("stdin write failed: {e}"))?;
+  }
+  let status = child.wait().await.map_err(|e| format!("powershell failed: {e}"))?;
+  if !status.success() {
+    return Err("Outlook is not available".into());
+  }
+  Ok(())
+}
+
+async fn draft_email(args: &serde_json::Value) -> Result<serde_json::Value, String> {
+  let arg = |k: &str| args.get(k).and_then(|x| x.as_str()).unwrap_or("").to_string();
+  let d = EmailDraft { to: arg("to"), cc: arg("cc"), subject: arg("subject"), body: arg("body") };
+  if d.subject.trim().is_empty() && d.body.trim().is_empty() {
+    return Err("An email draft needs a subject or a body".into());
+  }
+  #[cfg(target_os = "windows")]
+  {
+    match outlook_draft(&d).await {
+      Ok(()) => return Ok(serde_json::json!({ "opened": "outlook", "to": d.to, "subject": d.subject })),
+      Err(e) => tracing::debug!(error = %e, "outlook draft failed; using mailto"),
+    }
+  }
+  let (url, truncated) = mailto_url(&d);
+  open_default(&url).await?;
+  let mut out = serde_json::json!({ "opened": "mailto", "to": d.to, "subject": d.subject });
+  if truncated {
+    out["note"] = serde_json::json!("The body was too long for a mailto link and was shortened; tell the user to paste the rest.");
+  }
+  Ok(out)
+}
+
+// ----- Calendar
+
+// RFC 5545 TEXT escaping
+fn ics_escape(s: &str) -> String {
+  s.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace("\r\n", "\\n").replace('\n', "\\n")
+}
+
+// Content lines are folded at 75 octets with CRLF + space
+fn ics_line(out: &mut String, line: &str) {
+  let mut used = 0;
+  for c in line.chars() {
+    if used + c.len_utf8() > 75 {
+      out.push_str("\r\n ");
+      used = 1;
+    }
+    out.push(c);
+    used += c.len_utf8();
+  }
+  out.push_str("\r\n");
+}
+
+fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+  let s = s.trim().trim_end_matches('Z');
+  ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+    .iter()
+    .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+  NaiveDate::parse_from_str(s.trim().get(..10)?, "%Y-%m-%d").ok()
+}
+
+struct Event {
+  summary: String,
+  start: NaiveDateTime,
+  end: NaiveDateTime,
+  all_day: bool,
+  location: String,
+  description: String,
+}
+
+fn event_from_args(args: &serde_json::Value) -> Result<Event, String> {
+  let arg = |k: &str| args.get(k).and_then(|x| x.as_str()).unwrap_or("").to_string();
+  let summary = arg("title");
+  if summary.trim().is_empty() {
+    return Err("A calendar event needs a title".into());
+  }
+  let all_day = args.get("all_day").and_then(|x| x.as_bool()).unwrap_or(false);
+  let (start, end) = if all_day {
+    let day = parse_date(&arg("start")).ok_or_else(|| "start must be a date like 2026-10-20".to_string())?;
+    let last = parse_date(&arg("end")).unwrap_or(day).max(day);
+    (day.and_time(Default::default()), (last + Duration::days(1)).and_time(Default::default()))
+  } else {
+    let start = parse_datetime(&arg("start")).ok_or_else(|| "start must be a local date and time like 2026-10-20T14:00".to_string())?;
+    let minutes = args.get("duration_minutes").and_then(|x| x.as_i64()).filter(|m| *m > 0).unwrap_or(DEFAULT_EVENT_MINUTES);
+    let end = parse_datetime(&arg("end")).filter(|e| *e > start).unwrap_or(start + Duration::minutes(minutes));
+    (start, end)
+  };
+  Ok(Event { summary, start, end, all_day, location: arg("location"), description: arg("description") })
+}
+
+// Times are floating (no TZID): the calendar app places them in the user's local time zone
+fn to_ics(e: &Event) -> String {
+  let mut out = String::new();
+  ics_line(&mut out, "BEGIN:VCALENDAR");
+  ics_line(&mut out, "VERSION:2.0");
+  ics_line(&mut out, "PRODID:-//AiDesktopCompanion//Drafts//EN");
+  ics_line(&mut out, "METHOD:PUBLISH");
+  ics_line(&mut out, "BEGIN:VEVENT");
+  ics_line(&mut out, &format!("UID:{}@aidesktopcompanion", uuid::Uuid::new_v4()));
+  ics_line(&mut out, &format!("DTSTAMP:{}", Utc::now().format("%Y%m%dT%H%M%SZ")));
+  if e.all_day {
+    ics_line(&mut out, &format!("DTSTART;VALUE=DATE:{}", e.start.format("%Y%m%d")));
+    ics_line(&mut out, &format!("DTEND;VALUE=DATE:{}", e.end.format("%Y%m%d")));
+  } else {
+    ics_line(&mut out, &format!("DTSTART:{}", e.start.format("%Y%m%dT%H%M%S")));
+    ics_line(&mut out, &format!("DTEND:{}", e.end.format("%Y%m%dT%H%M%S")));
+  }
+  ics_line(&mut out, &format!("SUMMARY:{}", ics_escape(e.summary.trim())));
+  if !e.location.trim().is_empty() { ics_line(&mut out, &format!("LOCATION:{}", ics_escape(e.location.trim()))); }
+  if !e.description.trim().is_empty() { ics_line(&mut out, &format!("DESCRIPTION:{}", ics_escape(e.description.trim()))); }
+  ics_line(&mut out, "END:VEVENT");
+  ics_line(&mut out, "END:VCALENDAR");
+  out
+}
+
+async fn draft_event(args: &serde_json::Value) -> Result<serde_json::Value, String> {
+  let event = event_from_args(args)?;
+  let stem: String = event.summary.chars().filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_')).take(60).collect();
+  let stem = if stem.trim().is_empty() { "event".to_string() } else { stem.trim().to_string() };
+  let path = drafts_dir()?.join(format!("{} {}.ics", event.start.format("%Y-%m-%d"), stem));
+  std::fs::write(&path, to_ics(&event)).map_err(|e| format!("Write calendar file failed: {e}"))?;
+  let path_str = path.to_string_lossy().to_string();
+  open_default(&path_str).await?;
+  Ok(serde_json::json!({
+    "opened": path_str,
+    "title": event.summary,
+    "start": event.start.format("%Y-%m-%dT%H:%M").to_string(),
+    "end": event.end.format("%Y-%m-%dT%H:%M").to_string(),
+    "all_day": event.all_day,
+  }))
+}
+
+/// Tool definitions (Chat Completions shape) to offer alongside the MCP tools; empty when disabled.
+pub fn tool_definitions() -> Vec<serde_json::Value> {
+  if !enabled() {
+    return Vec::new();
+  }
+  vec![
+    serde_json::json!({
+      "type": "function",
+      "function": {
+        "name": EMAIL_TOOL,
+        "description": "Open a new email draft in the user's mail client for them to review and send. Nothing is sent automatically.",
+        "parameters": {
+          "type": "object",
+          "properties": {
+            "to": { "type": "string", "description": "Recipient addresses, comma separated; may be empty" },
+            "cc": { "type": "string", "description": "CC addresses, comma separated" },
+            "subject": { "type": "string" },
+            "body": { "type": "string", "description": "Plain text body" }
+          },
+          "required": ["subject", "body"]
+        }
+      }
+    }),
+    serde_json::json!({
+      "type": "function",
+      "function": {
+        "name": CALENDAR_TOOL,
+        "description": "Create a calendar entry and open it in the user's calendar app for them to save. Use the user's local time.",
+        "parameters": {
+          "type": "object",
+          "properties": {
+            "title": { "type": "string" },
+            "start": { "type": "string", "description": "Local start, e.g. 2026-10-20T14:00 (a date like 2026-10-20 for all-day events)" },
+            "end": { "type": "string", "description": "Local end; defaults to start plus duration_minutes" },
+            "duration_minutes": { "type": "integer", "description": "Used when end is omitted (default 60)" },
+            "all_day": { "type": "boolean" },
+            "location": { "type": "string" },
+            "description": { "type": "string" }
+          },
+          "required": ["title", "start"]
+        }
+      }
+    }),
+  ]
+}
+
+/// Handle a drafting tool call; None when `fname` is not a drafting tool.
+pub async fn call_tool(fname: &str, args: &serde_json::Value) -> Option<Result<serde_json::Value, String>> {
+  let res = match fname {
+    EMAIL_TOOL | CALENDAR_TOOL if !enabled() => Err("Drafting tools are turned off in settings".to_string()),
+    EMAIL_TOOL => draft_email(args).await,
+    CALENDAR_TOOL => draft_event(args).await,
+    _ => return None,
+  };
+  if let Ok(v) = &res {
+    crate::audit::record("draft_opened", serde_json::json!({ "tool": fname, "result": v }));
+  }
+  Some(res)
+}
@@ -0,0 +1,355 @@
+// Text embeddings for local retrieval (folder indexing / RAG). Two backends, chosen by
+// `embedding_engine`:
+//   "local" — all-MiniLM-L6-v2 (ONNX, 384 dims) run through onnxruntime; documents never leave
+//             the machine. Model files come from the download manager (`embeddings_prefetch_model`).
+//   "cloud" — OpenAI text-embedding-3-small.
+// When the local model is unavailable (no `local-embeddings` feature, not downloaded, failed to
+// load) and an OpenAI key is configured, "local" falls back to the cloud model; `Embeddings.engine`
+// reports which one produced the vectors.
+// Vectors are L2-normalized, so cosine similarity is a plain dot product. The local session is
+// cached after first use and released together with the STT models (see local_models).
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::error::AidcError;
+
+const CLOUD_URL: &str = "https://api.openai.com/v1/embeddings";
+const CLOUD_MODEL: &str = "text-embedding-3-small";
+const LOCAL_MODEL: &str = "all-minilm-l6-v2";
+const LOCAL_FILES: [(&str, &str, u64); 2] = [
+  ("model.onnx", "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/onnx/model.onnx?download=true", 10_000_000),
+  ("tokenizer.json", "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/tokenizer.json?download=true", 100_000),
+];
+// Inputs per model call; long lists are embedded in batches of this size
+const BATCH_SIZE: usize = 32;
+const MAX_TEXTS: usize = 2048;
+#[cfg_attr(not(feature = "local-embeddings"), allow(dead_code))]
+const MAX_TOKENS: usize = 256;
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+  reqwest::Client::builder()
+    .timeout(Duration::from_secs(120))
+    .connect_timeout(Duration::from_secs(10))
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+#[derive(Serialize)]
+pub struct Embeddings {
+  /// "local" | "cloud"
+  pub engine: String,
+  pub model: String,
+  pub dim: usize,
+  pub vectors: Vec<Vec<f32>>,
+}
+
+#[derive(Serialize)]
+pub struct EmbeddingsStatus {
+  pub engine: String,
+  pub local_model: String,
+  /// Built with the `local-embeddings` feature
+  pub local_available: bool,
+  pub local_downloaded: bool,
+  pub local_loaded: bool,
+  pub model_dir: Option<String>,
+}
+
+fn model_dir() -> Option<PathBuf> {
+  #[cfg(target_os = "windows")]
+  {
+    if let Ok(appdata) = std::env::var("APPDATA") {
+      let mut p = PathBuf::from(appdata);
+      p.push("AiDesktopCompanion");
+      p.push("models");
+      p.push("embeddings");
+      p.push(LOCAL_MODEL);
+      return Some(p);
+    }
+    None
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    if let Ok(home) = std::env::var("HOME") {
+      let mut p = PathBuf::from(home);
+      p.push(".cache");
+      p.push("AiDesktopCompanion");
+      p.push("models");
+      p.push("embeddings");
+      p.push(LOCAL_MODEL);
+      return Some(p);
+    }
+    None
+  }
+}
+
+fn local_downloaded() -> bool {
+  model_dir().map(|d| LOCAL_FILES.iter().all(|(name, _, _)| d.join(name).is_file())).unwrap_or(false)
+}
+
+fn normalize(v: &mut [f32]) {
+  let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+  if norm > 0.0 {
+    v.iter_mut().for_each(|x| *x /= norm);
+  }
+}
+
+#[cfg(feature = "local-embeddings")]
+mod local {
+  use std::path::Path;
+  use std::sync::Mutex;
+
+  use once_cell::sync::Lazy;
+  use ort::session::builder::GraphOptimizationLevel;
+  use ort::session::Session;
+  use ort::value::Tensor;
+  use tokenizers::{Tokenizer, TruncationParams};
+
+  use super::{normalize, MAX_TOKENS};
+
+  struct EmbedCache {
+    model_dir: String,
+    loaded_at_ms: u64,
+    session: Session,
+    tokenizer: Tokenizer,
+  }
+
+  static CACHE: Lazy<Mutex<Option<EmbedCache>>> = Lazy::new(|| Mutex::new(None));
+
+  fn load(dir: &Path) -> Result<EmbedCache, String> {
+    let mut tokenizer = Tokenizer::from_file(dir.join("tokenizer.json")).map_err(|e| format!("tokenizer load failed: {e}"))?;
+    tokenizer
+      .with_truncation(Some(TruncationParams { max_length: MAX_TOKENS, ..Default::default() }))
+      .map_err(|e| format!("tokenizer setup failed: {e}"))?;
+    tokenizer.with_padding(None);
+    let threads = std::cmp::max(1, num_cpus::get() / 2);
+    let session = Session::builder()
+      .and_then(|b| b.with_optimization_level(GraphOptimizationLevel::Level3))
+      .and_then(|b| b.with_intra_threads(threads))
+      .and_then(|b| b.commit_from_file(dir.join("model.onnx")))
+      .map_err(|e| format!("embedding model load failed: {e}"))?;
+    Ok(EmbedCache { model_dir: dir.to_string_lossy().to_string(), loaded_at_ms: crate::local_models::now_ms(), session, tokenizer })
+  }
+
+  /// Embed one batch: BERT forward pass, mean pooling over the attention mask, L2 normalization.
+  pub fn embed_batch(dir: &Path, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let mut cache = CACHE.lock().map_err(|_| "embedding cache lock poisoned".to_string())?;
+    let key = dir.to_string_lossy().to_string();
+    if cache.as_ref().map(|c| c.model_dir != key).unwrap_or(true) {
+      *cache = None;
+      *cache = Some(load(dir)?);
+    }
+    crate::local_models::touch();
+    let c = cache.as_mut().ok_or_else(|| "embedding cache init failed".to_string())?;
+
+    let encodings = c.tokenizer.encode_batch(texts.to_vec(), true).map_err(|e| format!("tokenize failed: {e}"))?;
+    let batch = encodings.len();
+    let seq = encodings.iter().map(|e| e.get_ids().len()).max().unwrap_or(0).max(1);
+    let mut ids = vec![0i64; batch * seq];
+    let mut mask = vec![0i64; batch * seq];
+    let types = vec![0i64; batch * seq];
+    for (b, enc) in encodings.iter().enumerate() {
+      for (t, (&id, &m)) in enc.get_ids().iter().zip(enc.get_attention_mask()).enumerate() {
+        ids[b * seq + t] = id as i64;
+        mask[b * seq + t] = m as i64;
+      }
+    }
+    let tensor = |data: Vec<i64>| Tensor::from_array(([batch, seq], data)).map_err(|e| format!("tensor build failed: {e}"));
+    let inputs = ort::inputs![
+      "input_ids" => tensor(ids)?,
+      "attention_mask" => tensor(mask.clone())?,
+      "token_type_ids" => tensor(types)?,
+    ];
+    let outputs = c.session.run(inputs).map_err(|e| format!("embedding inference failed: {e}"))?;
+    let (shape, hidden) = outputs["last_hidden_state"].try_extract_tensor::<f32>().map_err(|e| format!("embedding output invalid: {e}"))?;
+    let dim = shape.last().copied().unwrap_or(0) as usize;
+    if dim == 0 || hidden.len() != batch * seq * dim {
+      return Err("embedding output has an unexpected shape".into());
+    }
+
+    let mut out = Vec::with_capacity(batch);
+    for b in 0..batch {
+      let mut v = vec![0f32; dim];
+      let mut count = 0f32;
+      for t in 0..seq {
+        if mask[b * seq + t] == 0 {
+          continue;
+        }
+        count += 1.0;
+        let row = &hidden[(b * seq + t) * dim..(b * seq + t + 1) * dim];
+        v.iter_mut().zip(row).for_each(|(acc, x)| *acc += x);
+      }
+      if count > 0.0 {
+        v.iter_mut().for_each(|x| *x /= count);
+      }
+      normalize(&mut v);
+      out.push(v);
+    }
+    Ok(out)
+  }
+
+  pub fn loaded_models() -> Vec<crate::local_models::LoadedModel> {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+      .iter()
+      .map(|c| crate::local_models::LoadedModel {
+        engine: "embeddings".into(),
+        model: super::LOCAL_MODEL.into(),
+        cuda: false,
+        file_bytes: crate::local_models::size_on_disk(Path::new(&c.model_dir)),
+        loaded_at_ms: c.loaded_at_ms,
+      })
+      .collect()
+  }
+
+  pub fn unload_models() -> usize {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.take().map(|_| 1).unwrap_or(0)
+  }
+}
+
+#[cfg(feature = "local-embeddings")]
+pub fn loaded_models() -> Vec<crate::local_models::LoadedModel> {
+  local::loaded_models()
+}
+
+#[cfg(feature = "local-embeddings")]
+pub fn unload_models() -> usize {
+  local::unload_models()
+}
+
+#[cfg(not(feature = "local-embeddings"))]
+pub fn loaded_models() -> Vec<crate::local_models::LoadedModel> { Vec::new() }
+
+#[cfg(not(feature = "local-embeddings"))]
+pub fn unload_models() -> usize { 0 }
+
+/// Download the local model files (if missing) and return the model folder.
+#[cfg(feature = "local-embeddings")]
+pub async fn ensure_local_model(job: Option<&crate::jobs::JobReporter>) -> Result<PathBuf, String> {
+  let dir = model_dir().ok_or_else(|| "Could not resolve models directory".to_string())?;
+  std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create models directory: {e}"))?;
+  for (name, url, min_size) in LOCAL_FILES {
+    let dest = dir.join(name);
+    if dest.is_file() {
+      continue;
+    }
+    crate::downloads::download(job, crate::downloads::DownloadSpec::new(url, dest).min_size(min_size)).await?;
+  }
+  Ok(dir)
+}
+
+#[cfg(not(feature = "local-embeddings"))]
+pub async fn ensure_local_model(_job: Option<&crate::jobs::JobReporter>) -> Result<PathBuf, String> {
+  Err("Local embeddings are not available: app built without 'local-embeddings' feature.".into())
+}
+
+#[cfg(feature = "local-embeddings")]
+async fn embed_local(texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+  if !local_downloaded() {
+    return Err("Local embedding model is not downloaded yet (Settings → Embeddings → Download model).".into());
+  }
+  let dir = model_dir().ok_or_else(|| "Could not resolve models directory".to_string())?;
+  tokio::task::spawn_blocking(move || {
+    let mut out = Vec::with_capacity(texts.len());
+    for chunk in texts.chunks(BATCH_SIZE) {
+      out.extend(local::embed_batch(&dir, chunk)?);
+    }
+    Ok(out)
+  })
+  .await
+  .map_err(|e| format!("embedding task failed: {e}"))?
+}
+
+#[cfg(not(feature = "local-embeddings"))]
+async fn embed_local(_texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+  Err("Local embeddings are not available: app built without 'local-embeddings' feature.".into())
+}
+
+async fn embed_cloud(texts: Vec<String>) -> Result<Vec<Vec<f32>>, AidcError> {
+  let key = crate::settings::require_openai_key()?;
+  let mut out = Vec::with_capacity(texts.len());
+  for chunk in texts.chunks(BATCH_SIZE * 4) {
+    let body = serde_json::json!({ "model": CLOUD_MODEL, "input": chunk });
+    let resp = crate::rate_limit::send("openai", "embeddings", || CLIENT.post(CLOUD_URL).bearer_auth(&key).json(&body))
+      .await
+      .map_err(|e| AidcError::from_reqwest("openai", &e))?;
+    if !resp.status().is_success() {
+      let status = resp.status();
+      let text = resp.text().await.unwrap_or_default();
+      return Err(AidcError::from_status("openai", status, &text));
+    }
+    let v: serde_json::Value = resp.json().await.map_err(|e| AidcError::invalid_response("openai", e.to_string()))?;
+    crate::usage::record_response(crate::usage::EMBEDDINGS, CLOUD_MODEL, &v);
+    let data = v.get("data").and_then(|d| d.as_array()).ok_or_else(|| AidcError::invalid_response("openai", "missing data"))?;
+    if data.len() != chunk.len() {
+      return Err(AidcError::invalid_response("openai", format!("expected {} embeddings, got {}", chunk.len(), data.len())));
+    }
+    for item in data {
+      let mut vec: Vec<f32> = item
+        .get("embedding")
+        .and_then(|e| e.as_array())
+        .map(|a| a.iter().filter_map(|x| x.as_f64()).map(|x| x as f32).collect())
+        .unwrap_or_default();
+      normalize(&mut vec);
+      out.push(vec);
+    }
+  }
+  Ok(out)
+}
+
+/// Embed `texts` with the configured engine. Empty strings are embedded as-is; callers that
+/// index documents should chunk them first (the local model reads at most 256 tokens).
+pub async fn embed(texts: Vec<String>) -> Result<Embeddings, AidcError> {
+  if texts.len() > MAX_TEXTS {
+    return Err(AidcError::InvalidInput(format!("Too many texts ({}, max {MAX_TEXTS})", texts.len())));
+  }
+  let (engine, model, vectors) = if crate::config::get_embedding_engine_from_settings() == "cloud" {
+    ("cloud", CLOUD_MODEL, embed_cloud(texts).await?)
+  } else {
+    match embed_local(texts.clone()).await {
+      Ok(vectors) => ("local", LOCAL_MODEL, vectors),
+      Err(local_err) => {
+        tracing::warn!(error = %local_err, "local embeddings unavailable; falling back to the cloud model");
+        match embed_cloud(texts).await {
+          Ok(vectors) => ("cloud", CLOUD_MODEL, vectors),
+          // Without a key the local failure is the one worth reporting
+          Err(AidcError::MissingApiKey { .. }) => return Err(AidcError::Internal(local_err)),
+          Err(e) => return Err(e),
+        }
+      }
+    }
+  };
+  let dim = vectors.first().map(|v| v.len()).unwrap_or(0);
+  Ok(Embeddings { engine: engine.to_string(), model: model.to_string(), dim, vectors })
+}
+
+#[tauri::command]
+pub async fn embed_texts(texts: Vec<String>) -> Result<Embeddings, AidcError> {
+  embed(texts).await
+}
+
+/// Download the local embedding model as a "download" job (progress via `job:update`).
+#[tauri::command]
+pub async fn embeddings_prefetch_model(app: tauri::AppHandle, task_id: Option<String>) -> Result<String, String> {
+  crate::jobs::run(&app, "download", "Embedding model download", task_id, |job| async move {
+    job.wait_for_network().await?;
+    ensure_local_model(Some(&job)).await.map(|p| p.to_string_lossy().to_string())
+  })
+  .await
+}
+
+#[tauri::command]
+pub fn embeddings_status() -> Result<EmbeddingsStatus, String> {
+  Ok(EmbeddingsStatus {
+    engine: crate::config::get_embedding_engine_from_settings(),
+    local_model: LOCAL_MODEL.to_string(),
+    local_available: cfg!(feature = "local-embeddings"),
+    local_downloaded: local_downloaded(),
+    local_loaded: !loaded_models().is_empty(),
+    model_dir: model_dir().map(|p| p.to_string_lossy().to_string()),
+  })
+}
@@ -0,0 +1,182 @@
+// Unified error type for commands. Serialized to the frontend as
+// { code, message, retryable, provider } so the UI can branch on `code` instead of parsing text.
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+#[derive(Debug, Clone)]
+pub enum AidcError {
+  /// No API key configured for the provider
+  MissingApiKey { provider: String },
+  /// Provider rejected the credentials (401/403)
+  Auth { provider: String, message: String },
+  /// Provider rate limit or quota hit (429)
+  RateLimited { provider: String, message: String },
+  /// Provider-side failure (5xx)
+  ProviderUnavailable { provider: String, message: String },
+  /// Provider rejected the request (other 4xx)
+  ProviderRejected { provider: String, message: String },
+  /// Connection could not be established or was interrupted
+  Network { provider: String, message: String },
+  /// No network connection (reported by the connectivity monitor)
+  Offline,
+  /// Request timed out
+  Timeout { provider: String, message: String },
+  /// Response could not be read or parsed
+  InvalidResponse { provider: String, message: String },
+  /// Bad arguments or unusable input (empty text, unreadable file, ...)
+  InvalidInput(String),
+  /// MCP server (or other backend) is not connected
+  NotConnected { provider: String },
+  /// An MCP tool/resource/prompt call failed
+  ToolFailed { provider: String, message: String },
+  /// Operation was cancelled by the user
+  Cancelled,
+  /// Anything else; carries the legacy string message
+  Internal(String),
+}
+
+impl AidcError {
+  pub fn code(&self) -> &'static str {
+    match self {
+      AidcError::MissingApiKey { .. } => "missing_api_key",
+      AidcError::Auth { .. } => "auth",
+      AidcError::RateLimited { .. } => "rate_limited",
+      AidcError::ProviderUnavailable { .. } => "provider_unavailable",
+      AidcError::ProviderRejected { .. } => "provider_rejected",
+      AidcError::Network { .. } => "network",
+      AidcError::Offline => "offline",
+      AidcError::Timeout { .. } => "timeout",
+      AidcError::InvalidResponse { .. } => "invalid_response",
+      AidcError::InvalidInput(_) => "invalid_input",
+      AidcError::NotConnected { .. } => "not_connected",
+      AidcError::ToolFailed { .. } => "tool_failed",
+      AidcError::Cancelled => "cancelled",
+      AidcError::Internal(_) => "internal",
+    }
+  }
+
+  /// Whether retrying the same request later may succeed
+  pub fn retryable(&self) -> bool {
+    matches!(
+      self,
+      AidcError::RateLimited { .. }
+        | AidcError::ProviderUnavailable { .. }
+        | AidcError::Network { .. }
+        | AidcError::Offline
+        | AidcError::Timeout { .. }
+    )
+  }
+
+  pub fn provider(&self) -> Option<&str> {
+    match self {
+      AidcError::MissingApiKey { provider }
+      | AidcError::NotConnected { provider }
+      | AidcError::Auth { provider, .. }
+      | AidcError::RateLimited { provider, .. }
+      | AidcError::ProviderUnavailable { provider, .. }
+      | AidcError::ProviderRejected { provider, .. }
+      | AidcError::Network { provider, .. }
+      | AidcError::Timeout { provider, .. }
+      | AidcError::InvalidResponse { provider, .. }
+      | AidcError::ToolFailed { provider, .. } => Some(provider.as_str()),
+      AidcError::InvalidInput(_) | AidcError::Offline | AidcError::Cancelled | AidcError::Internal(_) => None,
+    }
+  }
+
+  pub fn message(&self) -> String {
+    match self {
+      AidcError::MissingApiKey { provider } => format!("No API key configured for {provider}"),
+      AidcError::Auth { provider, message } => format!("{provider} rejected the API key: {message}"),
+      AidcError::RateLimited { provider, message } => format!("{provider} rate limit reached: {message}"),
+      AidcError::ProviderUnavailable { provider, message } => format!("{provider} is unavailable: {message}"),
+      AidcError::ProviderRejected { provider, message } => format!("{provider} error: {message}"),
+      AidcError::Network { provider, message } => format!("Could not reach {provider}: {message}"),
+      AidcError::Offline => "You appear to be offline. Check your network connection and try again.".to_string(),
+      AidcError::Timeout { provider, message } => format!("{provider} request timed out: {message}"),
+      AidcError::InvalidResponse { provider, message } => format!("Unexpected response from {provider}: {message}"),
+      AidcError::InvalidInput(m) => m.clone(),
+      AidcError::NotConnected { provider } => format!("{provider} is not connected"),
+      AidcError::ToolFailed { provider, message } => format!("{provider}: {message}"),
+      AidcError::Cancelled => "Cancelled".to_string(),
+      AidcError::Internal(m) => m.clone(),
+    }
+  }
+
+  pub fn missing_api_key(provider: &str) -> Self {
+    AidcError::MissingApiKey { provider: provider.to_string() }
+  }
+
+  pub fn invalid_response(provider: &str, message: impl Into<String>) -> Self {
+    AidcError::InvalidResponse { provider: provider.to_string(), message: message.into() }
+  }
+
+  /// Classify a non-success HTTP response. Uses `error.message` from a JSON body when present.
+  pub fn from_status(provider: &str, status: reqwest::StatusCode, body: &str) -> Self {
+    let detail = serde_json::from_str::<serde_json::Value>(body)
+      .ok()
+      .and_then(|v| {
+        v.get("error")
+          .and_then(|e| e.get("message").and_then(|m| m.as_str()).or_else(|| e.as_str()))
+          .map(|s| s.to_string())
+      })
+      .unwrap_or_else(|| body.chars().take(300).collect());
+    let message = if detail.trim().is_empty() { status.to_string() } else { format!("{status} {}", detail.trim()) };
+    let provider = provider.to_string();
+    match status.as_u16() {
+      401 | 403 => AidcError::Auth { provider, message },
+      429 => AidcError::RateLimited { provider, message },
+      500..=599 => AidcError::ProviderUnavailable { provider, message },
+      _ => AidcError::ProviderRejected { provider, message },
+    }
+  }
+
+  /// Classify a transport-level reqwest failure. Connect failures while the connectivity
+  /// monitor reports no network become `Offline`.
+  pub fn from_reqwest(provider: &str, e: &reqwest::Error) -> Self {
+    let provider = provider.to_string();
+    let message = e.to_string();
+    if e.is_connect() {
+      crate::connectivity::note_connect_failure();
+      if !crate::connectivity::is_online() { return AidcError::Offline; }
+    }
+    if e.is_timeout() {
+      AidcError::Timeout { provider, message }
+    } else if e.is_decode() || e.is_body() {
+      AidcError::InvalidResponse { provider, message }
+    } else {
+      AidcError::Network { provider, message }
+    }
+  }
+}
+
+impl std::fmt::Display for AidcError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.message())
+  }
+}
+
+impl std::error::Error for AidcError {}
+
+impl Serialize for AidcError {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("AidcError", 4)?;
+    s.serialize_field("code", self.code())?;
+    s.serialize_field("message", &self.message())?;
+    s.serialize_field("retryable", &self.retryable())?;
+    s.serialize_field("provider", &self.provider())?;
+    s.end()
+  }
+}
+
+// Interop with the many helpers that still return Result<_, String>
+impl From<String> for AidcError {
+  fn from(m: String) -> Self { AidcError::Internal(m) }
+}
+
+impl From<&str> for AidcError {
+  fn from(m: &str) -> Self { AidcError::Internal(m.to_string()) }
+}
+
+impl From<AidcError> for String {
+  fn from(e: AidcError) -> Self { e.message() }
+}
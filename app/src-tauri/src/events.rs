@@ -0,0 +1,384 @@
+// Versioned event payloads shared by the UI and external integrations (CLI, HTTP API). Every event
+// emitted through `emit` carries `schema_version` next to the payload fields; fields are only
+// added within a version, so consumers can ignore what they do not know. Renaming or removing a
+// field, or changing its meaning, bumps SCHEMA_VERSION.
+//
+// Events:
+//   tts:stream:start      TtsStreamStart   audio stream opened (mime of the chunks that follow)
+//   tts:stream:chunk      TtsStreamChunk   base64 audio bytes, in order
+//   tts:stream:end        TtsStreamId      stream finished normally
+//   tts:stream:cancelled  TtsStreamId      stopped through tts_openai_stream_stop
+//   tts:stream:error      TtsStreamError   stream failed; no further events for this id
+//   tts:playback:start    TtsPlaybackStart native playback of a clip started (tts_playback.rs)
+//   tts:playback:progress TtsPlaybackProgress position while playing, paused or after a seek
+//   tts:playback:end      TtsPlaybackEnd   clip finished, was stopped or failed
+//   tts:playback:adjusted audio_io::PlaybackAdjust rate or volume of native WAV playback changed
+//   tts:progress          TtsProgress      chunk of a long text being read (tts_queue.rs)
+//   chat:tool-call        ToolCall         the model called an MCP or memory tool
+//   chat:tool-result      ToolResult       outcome of that call (same id)
+//   chat:stream:delta     ChatStreamDelta  next piece of a streamed chat answer
+//   chat:stream:end       ChatStreamEnd    streamed answer finished, failed or was cancelled
+//   chat:cancelled        ChatCancelled    a chat request was stopped (chat_cancel or cancel_task)
+//   stt:partial           SttPartial       interim transcript of live dictation (stt_stream.rs)
+//   stt:final             SttFinal         live dictation stopped: full transcript or error
+//   dictation:state       DictationState   push-to-talk dictation status (dictation.rs)
+//   audio:level           AudioLevel       microphone level while dictating or monitoring (audio_io::LevelMeter)
+//   recorder:state        RecorderState    meeting recorder started, stopped or failed (recorder.rs)
+//   recorder:transcript   RecorderTranscript new segments of the live meeting transcript
+//   hotkey:action         HotkeyAction     a hotkey or tray action that needs a window (hotkeys.rs, tray.rs)
+//   conversation:open     ConversationOpen a recent conversation was picked in the tray menu
+//   persona:changed       PersonaChanged   the active persona was switched or cleared (personas.rs)
+//   conversations:changed ConversationsChanged a thread was created, renamed or deleted (conversations.rs)
+//   provider:failover     ProviderFailover a rate-limited API key was swapped for the next one
+//   pii:masked            PiiMasked        personal data was masked in text before it was sent (pii.rs)
+//   code:approval         CodeApproval     a snippet waits for the user's approval (code_exec.rs)
+//   job:update            jobs::JobStatus  progress of downloads, cleanups, exports (replaces the
+//                                          old per-download events such as stt-model-download)
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const TTS_STREAM_START: &str = "tts:stream:start";
+pub const TTS_STREAM_CHUNK: &str = "tts:stream:chunk";
+pub const TTS_STREAM_END: &str = "tts:stream:end";
+pub const TTS_STREAM_CANCELLED: &str = "tts:stream:cancelled";
+pub const TTS_STREAM_ERROR: &str = "tts:stream:error";
+pub const TTS_PLAYBACK_START: &str = "tts:playback:start";
+pub const TTS_PLAYBACK_PROGRESS: &str = "tts:playback:progress";
+pub const TTS_PLAYBACK_END: &str = "tts:playback:end";
+pub const TTS_PLAYBACK_ADJUSTED: &str = "tts:playback:adjusted";
+pub const TTS_PROGRESS: &str = "tts:progress";
+pub const CHAT_TOOL_CALL: &str = "chat:tool-call";
+pub const CHAT_TOOL_RESULT: &str = "chat:tool-result";
+pub const CHAT_STREAM_DELTA: &str = "chat:stream:delta";
+pub const CHAT_STREAM_END: &str = "chat:stream:end";
+pub const CHAT_CANCELLED: &str = "chat:cancelled";
+pub const STT_PARTIAL: &str = "stt:partial";
+pub const STT_FINAL: &str = "stt:final";
+pub const DICTATION_STATE: &str = "dictation:state";
+pub const AUDIO_LEVEL: &str = "audio:level";
+pub const RECORDER_STATE: &str = "recorder:state";
+pub const RECORDER_TRANSCRIPT: &str = "recorder:transcript";
+pub const HOTKEY_ACTION: &str = "hotkey:action";
+pub const CONVERSATION_OPEN: &str = "conversation:open";
+pub const PROVIDER_FAILOVER: &str = "provider:failover";
+pub const PII_MASKED: &str = "pii:masked";
+pub const CODE_APPROVAL: &str = "code:approval";
+pub const CONVERSATIONS_CHANGED: &str = "conversations:changed";
+pub const PERSONA_CHANGED: &str = "persona:changed";
+
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+  schema_version: u32,
+  #[serde(flatten)]
+  payload: &'a T,
+}
+
+/// Emit `payload` (a struct from this module or another documented payload) with `schema_version`.
+pub fn emit<T: Serialize>(app: &AppHandle, event: &str, payload: &T) {
+  if let Err(e) = app.emit(event, Envelope { schema_version: SCHEMA_VERSION, payload }) {
+    tracing::debug!(event, error = %e, "event emit failed");
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TtsStreamStart {
+  pub id: u64,
+  /// e.g. "audio/mpeg" or "audio/ogg; codecs=opus"
+  pub mime: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TtsStreamChunk {
+  pub id: u64,
+  /// Base64-encoded audio bytes
+  pub data: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TtsStreamId {
+  pub id: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TtsStreamError {
+  pub id: u64,
+  pub message: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TtsPlaybackStart {
+  pub id: u64,
+  pub path: String,
+  pub duration_ms: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TtsPlaybackProgress {
+  pub id: u64,
+  pub position_ms: u64,
+  pub duration_ms: u64,
+  pub paused: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TtsPlaybackEnd {
+  pub id: u64,
+  /// Stopped through tts_playback_stop or by another clip starting
+  pub stopped: bool,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TtsProgress {
+  pub id: u64,
+  /// "synthesizing" | "playing" | "finished" | "stopped" | "error"
+  pub state: String,
+  /// Current chunk, 0-based
+  pub index: usize,
+  pub total: usize,
+  /// Text of the current chunk
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCall {
+  /// Tool call id from the model; repeated in the matching ToolResult
+  pub id: String,
+  /// Function name as offered to the model (mcp__<server>__<tool> or memory__<tool>)
+  pub function: String,
+  pub server_id: String,
+  pub tool: String,
+  pub args: serde_json::Value,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResult {
+  pub id: String,
+  pub function: String,
+  /// None when the function name is not a known tool
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub server_id: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tool: Option<String>,
+  pub ok: bool,
+  /// MCP CallToolResult when ok
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub result: Option<serde_json::Value>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ChatStreamDelta {
+  /// Task id of the chat_complete_stream call
+  pub id: String,
+  pub delta: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ChatStreamEnd {
+  pub id: String,
+  /// Everything streamed, across tool rounds
+  pub text: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ChatCancelled {
+  /// Task id of the cancelled chat request
+  pub id: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SttPartial {
+  /// Session id from stt_transcribe_stream_start
+  pub id: String,
+  /// committed + interim, what the user should see
+  pub text: String,
+  /// Final part that will not change any more
+  pub committed: String,
+  /// Latest guess for the words being spoken; may still change
+  pub interim: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SttFinal {
+  pub id: String,
+  pub text: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DictationState {
+  /// "recording" | "transcribing" | "idle" | "error"
+  pub state: String,
+  /// Inserted text, on the "idle" that ends a dictation
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AudioLevel {
+  /// Recording the level belongs to: "dictation" | "live_dictation" | "monitor" | "recorder"
+  pub source: String,
+  /// 0..1 over the last ~50 ms
+  pub rms: f32,
+  /// 0..1 over the last ~50 ms
+  pub peak: f32,
+  /// Peak reached full scale; the input gain is too high
+  pub clipping: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RecorderState {
+  /// Recording id from recorder_start
+  pub id: String,
+  /// "recording" | "stopped" | "error"
+  pub state: String,
+  /// WAV file being written
+  pub path: String,
+  /// "mix" (one mono track) or "dual" (microphone left, system audio right)
+  pub mode: String,
+  pub microphone: bool,
+  /// What the speakers play (Windows loopback)
+  pub system_audio: bool,
+  /// Whether a live transcript is being made
+  pub transcribing: bool,
+  pub elapsed_ms: u64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RecorderTranscript {
+  pub id: String,
+  /// Segments transcribed since the last event, timed from the start of the recording
+  pub segments: Vec<crate::transcript::Segment>,
+  /// A chunk that could not be transcribed; recording continues
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HotkeyAction {
+  /// "quick_actions" (toggle the popup) | "capture_region" (open the capture overlay)
+  pub action: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ConversationOpen {
+  pub id: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PersonaChanged {
+  /// Active persona; None when cleared
+  pub id: Option<String>,
+  pub name: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ConversationsChanged {
+  /// "created" | "renamed" | "deleted"
+  pub action: &'static str,
+  pub id: String,
+  /// The thread as the window stores it (created), or { id, title } (renamed)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub conversation: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProviderFailover {
+  pub provider: String,
+  /// Request that hit the limit, e.g. "chat" or "tts"
+  pub label: String,
+  /// Masked keys ("…abcd"); the full key is never sent
+  pub from_key: String,
+  pub to_key: String,
+  /// 1-based position of the new key in the configured list
+  pub key_index: usize,
+  pub key_count: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PiiMasked {
+  /// Where the text came from, e.g. "quick_prompt:3"
+  pub source: String,
+  /// Masked values per kind, e.g. { "email": 2 }; the values themselves are never sent
+  pub counts: std::collections::BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CodeApproval {
+  /// Answer with `code_exec_respond` and this id
+  pub id: String,
+  /// "python" | "powershell"
+  pub language: String,
+  pub code: String,
+  pub network: bool,
+  /// Network access is cut at the OS level, not only in the interpreter
+  pub network_isolated: bool,
+  pub timeout_secs: u64,
+  pub memory_mb: u64,
+}
+
+#[derive(Serialize)]
+pub struct EventInfo {
+  pub name: &'static str,
+  pub payload: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct EventSchema {
+  pub schema_version: u32,
+  pub events: Vec<EventInfo>,
+}
+
+/// Versioned events and their payload type names, for integrations to check compatibility.
+#[tauri::command]
+pub fn get_event_schema() -> EventSchema {
+  let events = [
+    (TTS_STREAM_START, "TtsStreamStart"),
+    (TTS_STREAM_CHUNK, "TtsStreamChunk"),
+    (TTS_STREAM_END, "TtsStreamId"),
+    (TTS_STREAM_CANCELLED, "TtsStreamId"),
+    (TTS_STREAM_ERROR, "TtsStreamError"),
+    (TTS_PLAYBACK_START, "TtsPlaybackStart"),
+    (TTS_PLAYBACK_PROGRESS, "TtsPlaybackProgress"),
+    (TTS_PLAYBACK_END, "TtsPlaybackEnd"),
+    (TTS_PLAYBACK_ADJUSTED, "PlaybackAdjust"),
+    (TTS_PROGRESS, "TtsProgress"),
+    (CHAT_TOOL_CALL, "ToolCall"),
+    (CHAT_TOOL_RESULT, "ToolResult"),
+    (CHAT_STREAM_DELTA, "ChatStreamDelta"),
+    (CHAT_STREAM_END, "ChatStreamEnd"),
+    (CHAT_CANCELLED, "ChatCancelled"),
+    (STT_PARTIAL, "SttPartial"),
+    (STT_FINAL, "SttFinal"),
+    (DICTATION_STATE, "DictationState"),
+    (AUDIO_LEVEL, "AudioLevel"),
+    (RECORDER_STATE, "RecorderState"),
+    (RECORDER_TRANSCRIPT, "RecorderTranscript"),
+    (HOTKEY_ACTION, "HotkeyAction"),
+    (CONVERSATION_OPEN, "ConversationOpen"),
+    (PROVIDER_FAILOVER, "ProviderFailover"),
+    (PII_MASKED, "PiiMasked"),
+    (CODE_APPROVAL, "CodeApproval"),
+    (CONVERSATIONS_CHANGED, "ConversationsChanged"),
+    (PERSONA_CHANGED, "PersonaChanged"),
+    (crate::jobs::JOB_EVENT, "JobStatus"),
+  ];
+  EventSchema { schema_version: SCHEMA_VERSION, events: events.into_iter().map(|(name, payload)| EventInfo { name, payload }).collect() }
+}
@@ -0,0 +1,261 @@
+// Google Gemini API as a chat provider (`chat_provider: "gemini"`). Like anthropic.rs, each request
+// converts the Chat Completions history: system messages become `systemInstruction`, image parts
+// (data URLs from attached files) become `inlineData`, assistant tool_calls become `functionCall`
+// parts and tool messages become `functionResponse` parts in a user turn. MCP and built-in function
+// tools are sent as function declarations with their JSON Schema unchanged.
+
+use std::collections::HashMap;
+
+use futures_util::future::BoxFuture;
+
+use crate::chat::{ChatProvider, Turn};
+use crate::error::AidcError;
+
+const API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+pub(crate) struct GeminiChat {
+  pub key: String,
+}
+
+fn text_of(content: &serde_json::Value) -> String {
+  match content {
+    serde_json::Value::String(s) => s.clone(),
+    serde_json::Value::Array(parts) => parts.iter().filter_map(|p| p.get("text").and_then(|t| t.as_str())).collect::<Vec<_>>().join("\n"),
+    _ => String::new(),
+  }
+}
+
+// Chat Completions content (string or text/image_url parts) as Gemini parts
+fn to_parts(content: &serde_json::Value) -> Vec<serde_json::Value> {
+  match content {
+    serde_json::Value::String(s) if !s.is_empty() => vec![serde_json::json!({ "text": s })],
+    serde_json::Value::Array(parts) => parts
+      .iter()
+      .filter_map(|p| match p.get("type").and_then(|t| t.as_str()) {
+        Some("text") => p.get("text").and_then(|t| t.as_str()).filter(|t| !t.is_empty()).map(|t| serde_json::json!({ "text": t })),
+        Some("image_url") => {
+          // Images arrive as data URLs (chat::image_data_url)
+          let url = p.get("image_url").and_then(|u| u.get("url")).and_then(|u| u.as_str())?;
+          let (meta, data) = url.strip_prefix("data:")?.split_once(',')?;
+          let mime_type = meta.strip_suffix(";base64")?;
+          Some(serde_json::json!({ "inlineData": { "mimeType": mime_type, "data": data } }))
+        }
+        _ => None,
+      })
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
+// Returns the system instruction and the alternating user/model contents
+fn to_contents(msgs: &[serde_json::Value]) -> (String, Vec<serde_json::Value>) {
+  let mut system: Vec<String> = Vec::new();
+  let mut out: Vec<(&'static str, Vec<serde_json::Value>)> = Vec::new();
+  // functionResponse parts name the function; tool messages only carry the call id
+  let mut call_names: HashMap<String, String> = HashMap::new();
+  for m in msgs {
+    let role = m.get("role").and_then(|r| r.as_str()).unwrap_or("user");
+    let content = m.get("content").cloned().unwrap_or(serde_json::Value::Null);
+    let (role, parts) = match role {
+      "system" => {
+        let t = text_of(&content);
+        if !t.trim().is_empty() { system.push(t); }
+        continue;
+      }
+      "assistant" => {
+        let mut parts = to_parts(&content);
+        for tc in m.get("tool_calls").and_then(|x| x.as_array()).into_iter().flatten() {
+          let f = tc.get("function");
+          let name = f.and_then(|f| f.get("name")).and_then(|x| x.as_str()).unwrap_or("");
+          let args = f.and_then(|f| f.get("arguments")).and_then(|a| a.as_str()).unwrap_or("{}");
+          let args = serde_json::from_str::<serde_json::Value>(args).ok().filter(|v| v.is_object()).unwrap_or_else(|| serde_json::json!({}));
+          let id = tc.get("id").and_then(|x| x.as_str()).unwrap_or("");
+          call_names.insert(id.to_string(), name.to_string());
+          let mut part = serde_json::json!({ "functionCall": { "name": name, "args": args } });
+          // Thinking models want their signature back with the call
+          if let Some(sig) = tc.get("thought_signature").and_then(|x| x.as_str()) {
+            part["thoughtSignature"] = serde_json::json!(sig);
+          }
+          parts.push(part);
+        }
+        ("model", parts)
+      }
+      "tool" => {
+        let id = m.get("tool_call_id").and_then(|x| x.as_str()).unwrap_or("");
+        let name = call_names.get(id).cloned().unwrap_or_default();
+        ("user", vec![serde_json::json!({ "functionResponse": { "name": name, "response": { "content": text_of(&content) } } })])
+      }
+      _ => ("user", to_parts(&content)),
+    };
+    if parts.is_empty() {
+      continue;
+    }
+    // Consecutive turns of one role are merged (responses to parallel calls share a user turn)
+    match out.last_mut() {
+      Some((r, p)) if *r == role => p.extend(parts),
+      _ => out.push((role, parts)),
+    }
+  }
+  // The conversation has to open with a user turn; history truncation can leave a model turn first
+  while out.first().is_some_and(|(r, _)| *r == "model") {
+    out.remove(0);
+  }
+  let contents = out.into_iter().map(|(role, parts)| serde_json::json!({ "role": role, "parts": parts })).collect();
+  (system.join("\n\n"), contents)
+}
+
+// Chat Completions function tools ({type, function: {...}}) as Gemini function declarations.
+// `parametersJsonSchema` takes full JSON Schema; `parameters` only an OpenAPI subset
+fn to_declarations(tools: &[serde_json::Value]) -> Vec<serde_json::Value> {
+  tools
+    .iter()
+    .filter_map(|t| {
+      let f = t.get("function")?;
+      let mut decl = serde_json::json!({
+        "name": f.get("name")?,
+        "parametersJsonSchema": f.get("parameters").cloned().unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} })),
+      });
+      if let Some(d) = f.get("description").and_then(|d| d.as_str()).filter(|d| !d.is_empty()) {
+        decl["description"] = serde_json::json!(d);
+      }
+      Some(decl)
+    })
+    .collect()
+}
+
+// First candidate as text plus Chat Completions tool calls
+fn to_turn(v: &serde_json::Value) -> Turn {
+  let mut text = String::new();
+  let mut tool_calls = Vec::new();
+  let parts = v.get("candidates").and_then(|c| c.get(0)).and_then(|c| c.get("content")).and_then(|c| c.get("parts")).and_then(|p| p.as_array());
+  for part in parts.into_iter().flatten() {
+    // Thought summaries are not part of the answer
+    if part.get("thought").and_then(|t| t.as_bool()) == Some(true) {
+      continue;
+    }
+    if let Some(t) = part.get("text").and_then(|t| t.as_str()) {
+      text.push_str(t);
+    }
+    if let Some(call) = part.get("functionCall") {
+      // Older models send no call id; the chat loop needs one to pair results with calls
+      let id = call.get("id").and_then(|x| x.as_str()).map(|s| s.to_string()).unwrap_or_else(|| format!("call_{}", uuid::Uuid::new_v4().simple()));
+      let mut tc = serde_json::json!({
+        "id": id,
+        "type": "function",
+        "function": {
+          "name": call.get("name").and_then(|x| x.as_str()).unwrap_or(""),
+          "arguments": call.get("args").map(|a| a.to_string()).unwrap_or_else(|| "{}".to_string()),
+        },
+      });
+      if let Some(sig) = part.get("thoughtSignature") {
+        tc["thought_signature"] = sig.clone();
+      }
+      tool_calls.push(tc);
+    }
+  }
+  Turn { text: Some(text).filter(|t| !t.is_empty()), tool_calls }
+}
+
+fn record_usage(model: &str, v: &serde_json::Value) {
+  let u = v.get("usageMetadata");
+  let field = |name: &str| u.and_then(|u| u.get(name)).and_then(|x| x.as_u64()).unwrap_or(0);
+  crate::usage::record(crate::usage::CHAT, model, crate::usage::Usage {
+    input_tokens: field("promptTokenCount"),
+    output_tokens: field("candidatesTokenCount") + field("thoughtsTokenCount"),
+    ..Default::default()
+  });
+}
+
+impl ChatProvider for GeminiChat {
+  fn id(&self) -> &'static str {
+    "gemini"
+  }
+
+  fn complete<'a>(
+    &'a self,
+    client: &'a reqwest::Client,
+    model: &'a str,
+    msgs: &'a [serde_json::Value],
+    tools: &'a [serde_json::Value],
+    temp: Option<f32>,
+  ) -> BoxFuture<'a, Result<Turn, AidcError>> {
+    Box::pin(async move {
+      let (system, contents) = to_contents(msgs);
+      if contents.is_empty() {
+        return Err(AidcError::InvalidInput("Nothing to send: the conversation has no user message".into()));
+      }
+      let mut body = serde_json::json!({ "contents": contents });
+      if let serde_json::Value::Object(ref mut m) = body {
+        if !system.trim().is_empty() { m.insert("systemInstruction".to_string(), serde_json::json!({ "parts": [{ "text": system }] })); }
+        if let Some(t) = temp { m.insert("generationConfig".to_string(), serde_json::json!({ "temperature": t.clamp(0.0, 2.0) })); }
+        let declarations = to_declarations(tools);
+        if !declarations.is_empty() {
+          m.insert("tools".to_string(), serde_json::json!([{ "functionDeclarations": declarations }]));
+          m.insert("toolConfig".to_string(), serde_json::json!({ "functionCallingConfig": { "mode": "AUTO" } }));
+        }
+      }
+
+      let url = format!("{API_BASE}/models/{model}:generateContent");
+      let resp = crate::rate_limit::send("gemini", "chat", || client.post(&url).header("x-goog-api-key", &self.key).json(&body))
+        .await
+        .map_err(|e| AidcError::from_reqwest("gemini", &e))?;
+
+      if !resp.status().is_success() {
+        let status = resp.status();
+        let body_text = resp.text().await.unwrap_or_default();
+        return Err(AidcError::from_status("gemini", status, &body_text));
+      }
+
+      let v: serde_json::Value = resp.json().await.map_err(|e| AidcError::invalid_response("gemini", format!("json error: {e}")))?;
+      record_usage(model, &v);
+      // A blocked prompt comes back without candidates
+      if let Some(reason) = v.get("promptFeedback").and_then(|f| f.get("blockReason")).and_then(|r| r.as_str()) {
+        return Err(AidcError::ProviderRejected { provider: "gemini".into(), message: format!("Prompt blocked ({reason})") });
+      }
+      match v.get("candidates").and_then(|c| c.get(0)).and_then(|c| c.get("finishReason")).and_then(|r| r.as_str()) {
+        Some("MAX_TOKENS") => tracing::warn!(model, "gemini reply cut off at max tokens"),
+        Some(reason @ ("SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT")) => {
+          return Err(AidcError::ProviderRejected { provider: "gemini".into(), message: format!("Reply blocked ({reason})") });
+        }
+        _ => {}
+      }
+      Ok(to_turn(&v))
+    })
+  }
+}
+
+/// Gemini models that support generateContent, for the settings UI.
+#[tauri::command]
+pub async fn list_gemini_models() -> Result<Vec<String>, String> {
+  let key = crate::config::get_gemini_api_key_from_settings_or_env()?;
+  let client = crate::timeouts::client(crate::timeouts::CHAT);
+  let resp = client
+    .get(format!("{API_BASE}/models"))
+    .query(&[("pageSize", "1000")])
+    .header("x-goog-api-key", key)
+    .send()
+    .await
+    .map_err(|e| format!("request failed: {e}"))?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let body_text = resp.text().await.unwrap_or_default();
+    return Err(format!("Gemini error: {status} {body_text}"));
+  }
+  let v: serde_json::Value = resp.json().await.map_err(|e| format!("json error: {e}"))?;
+  let mut ids: Vec<String> = v
+    .get("models")
+    .and_then(|m| m.as_array())
+    .into_iter()
+    .flatten()
+    .filter(|m| {
+      m.get("supportedGenerationMethods").and_then(|x| x.as_array()).is_some_and(|methods| methods.iter().any(|x| x.as_str() == Some("generateContent")))
+    })
+    .filter_map(|m| m.get("name").and_then(|x| x.as_str()))
+    .map(|name| name.trim_start_matches("models/").to_string())
+    // Embedding, image and TTS models also list generateContent
+    .filter(|id| id.starts_with("gemini-") && !id.contains("embedding") && !id.contains("-tts") && !id.contains("-image"))
+    .collect();
+  ids.sort();
+  ids.dedup();
+  Ok(ids)
+}
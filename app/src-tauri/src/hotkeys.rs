@@ -0,0 +1,287 @@
+// Global hotkeys for app actions, one configurable shortcut each: the Quick Actions popup
+// (`global_hotkey`), read selection aloud, quick prompts 1–9, region capture and push-to-talk
+// dictation (`dictation_hotkey`). The others are kept in the `hotkeys` settings object by action:
+//   "hotkeys": { "read_selection": "Ctrl+Alt+R", "quick_prompt_1": "Ctrl+Alt+1" }
+// Shortcuts are registered at startup and again whenever settings are saved; a shortcut used twice,
+// or held by another app, is reported per action instead of failing the rest. Without a Quick
+// Actions shortcut the first free one of QUICK_ACTIONS_DEFAULTS is used.
+//
+// The summarize, paste history and TTS playback shortcuts are registered by the UI (hotkeys.ts);
+// they count as taken here. Actions that need a window (Quick Actions popup, capture overlay) are
+// sent to the main window as `hotkey:action`; the others run here.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::events;
+
+pub const QUICK_ACTIONS: &str = "quick_actions";
+pub const READ_SELECTION: &str = "read_selection";
+pub const CAPTURE_REGION: &str = "capture_region";
+pub const DICTATION: &str = "dictation";
+const QUICK_PROMPT_PREFIX: &str = "quick_prompt_";
+
+// Tried in order when no Quick Actions shortcut is configured
+const QUICK_ACTIONS_DEFAULTS: [&str; 5] = ["Alt+A", "Alt+Shift+A", "Ctrl+Alt+A", "Command+Shift+A", "Command+Shift+G"];
+// Registered by hotkeys.ts while `tts_playback_hotkeys` is on
+const TTS_PLAYBACK_SHORTCUTS: [&str; 6] = ["Ctrl+Alt+Right", "Ctrl+Alt+Left", "Ctrl+Alt+Up", "Ctrl+Alt+Down", "Ctrl+Alt+PageDown", "Ctrl+Alt+PageUp"];
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+static REGISTRY: Lazy<Mutex<Registry>> = Lazy::new(|| Mutex::new(Registry::default()));
+// Serializes re-registration; REGISTRY is not held while the plugin registers on the main thread
+static APPLYING: Mutex<()> = Mutex::new(());
+
+#[derive(Default)]
+struct Registry {
+  registered: Vec<Shortcut>,
+  status: Vec<HotkeyBinding>,
+  // Bindings and UI shortcuts the registrations were made from; unchanged ones are left alone
+  applied: Option<(Vec<(String, String)>, Vec<Shortcut>)>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HotkeyBinding {
+  pub action: String,
+  pub label: String,
+  /// Configured shortcut; empty when unset
+  pub shortcut: String,
+  /// Shortcut actually registered (one of the defaults for an unset Quick Actions shortcut)
+  pub active: Option<String>,
+  /// Why the shortcut is not registered
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+  REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Actions in display order, with their labels
+fn actions() -> Vec<(String, String)> {
+  let mut out = vec![(QUICK_ACTIONS.to_string(), "Quick Actions popup".to_string()), (READ_SELECTION.to_string(), "Read selection aloud".to_string())];
+  out.extend((1..=9).map(|i| (format!("{QUICK_PROMPT_PREFIX}{i}"), format!("Quick prompt {i}"))));
+  out.push((CAPTURE_REGION.to_string(), "Capture screen region".to_string()));
+  out.push((DICTATION.to_string(), "Dictation (push-to-talk)".to_string()));
+  out
+}
+
+// Settings key of actions that predate the `hotkeys` object
+fn legacy_key(action: &str) -> Option<&'static str> {
+  match action {
+    QUICK_ACTIONS => Some("global_hotkey"),
+    DICTATION => Some("dictation_hotkey"),
+    _ => None,
+  }
+}
+
+// Configured shortcut of every action, in `actions()` order
+fn configured(settings: &serde_json::Value) -> Vec<(String, String)> {
+  actions()
+    .into_iter()
+    .map(|(action, _)| {
+      let value = match legacy_key(&action) {
+        Some(key) => settings.get(key),
+        None => settings.get("hotkeys").and_then(|h| h.get(&action)),
+      };
+      let shortcut = value.and_then(|x| x.as_str()).unwrap_or("").trim().to_string();
+      (action, shortcut)
+    })
+    .collect()
+}
+
+fn parse(shortcut: &str) -> Result<Shortcut, String> {
+  Shortcut::from_str(&shortcut.trim().replace("Win", "Super")).map_err(|e| format!("\"{shortcut}\" is not a valid shortcut: {e}"))
+}
+
+// Shortcuts the UI registers, labelled with what they do
+fn ui_shortcuts(settings: &serde_json::Value) -> Vec<(Shortcut, String)> {
+  let mut out = Vec::new();
+  for (key, label) in [("summarize_hotkey", "Summarize window"), ("paste_history_hotkey", "Paste history")] {
+    if let Some(sc) = settings.get(key).and_then(|x| x.as_str()).filter(|s| !s.trim().is_empty()).and_then(|s| parse(s).ok()) {
+      out.push((sc, label.to_string()));
+    }
+  }
+  if settings.get("tts_playback_hotkeys").and_then(|x| x.as_bool()) == Some(true) {
+    out.extend(TTS_PLAYBACK_SHORTCUTS.iter().filter_map(|s| parse(s).ok()).map(|sc| (sc, "Speech playback".to_string())));
+  }
+  out
+}
+
+fn label_of(action: &str) -> String {
+  actions().into_iter().find(|(a, _)| a == action).map(|(_, l)| l).unwrap_or_else(|| action.to_string())
+}
+
+fn report(app: &AppHandle, message: String) {
+  let _ = app.emit("command:error", serde_json::json!({ "message": message }));
+}
+
+fn run_action(app: &AppHandle, action: &str, state: ShortcutState) {
+  let app = app.clone();
+  if action == DICTATION {
+    // Held: record; released: transcribe and type
+    tauri::async_runtime::spawn(async move {
+      let res = match state {
+        ShortcutState::Pressed => crate::dictation::dictation_start(app.clone()).await,
+        ShortcutState::Released => crate::dictation::dictation_stop(app.clone()).await.map(|_| ()),
+      };
+      if let Err(e) = res {
+        report(&app, format!("Dictation: {e}"));
+      }
+    });
+    return;
+  }
+  if state != ShortcutState::Pressed {
+    return;
+  }
+  tracing::debug!(action, "hotkey pressed");
+  match action {
+    QUICK_ACTIONS | CAPTURE_REGION => events::emit(&app, events::HOTKEY_ACTION, &events::HotkeyAction { action: action.to_string() }),
+    // Failures are reported as tts:error
+    READ_SELECTION => {
+      tauri::async_runtime::spawn(async move {
+        let _ = crate::quick_actions::tts_selection(app, None).await;
+      });
+    }
+    _ => {
+      let Some(index) = action.strip_prefix(QUICK_PROMPT_PREFIX).and_then(|n| n.parse::<u8>().ok()) else { return };
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::quick_prompts::run_quick_prompt(app.clone(), index, None).await {
+          report(&app, format!("Quick prompt {index}: {e}"));
+        }
+      });
+    }
+  }
+}
+
+fn register(app: &AppHandle, action: &str, shortcut: &str, taken: &[(Shortcut, String)]) -> Result<Shortcut, String> {
+  let sc = parse(shortcut)?;
+  if let Some((_, other)) = taken.iter().find(|(t, _)| *t == sc) {
+    return Err(format!("Already used by {other}"));
+  }
+  let action = action.to_string();
+  app
+    .global_shortcut()
+    .on_shortcut(sc, move |app, _, event| run_action(app, &action, event.state))
+    .map_err(|e| format!("Not registered (possibly in use by another app): {e}"))?;
+  Ok(sc)
+}
+
+/// Register the configured shortcuts, replacing the previous ones. Called at startup and after
+/// settings are saved; does nothing when no binding changed.
+pub fn apply_from_settings() {
+  let Some(app) = APP.get() else { return };
+  let settings = crate::config::load_settings_json();
+  let bindings = configured(&settings);
+  let ui = ui_shortcuts(&settings);
+  let _applying = APPLYING.lock().unwrap_or_else(|e| e.into_inner());
+  let key = (bindings.clone(), ui.iter().map(|(sc, _)| *sc).collect::<Vec<_>>());
+  if registry().applied.as_ref() == Some(&key) {
+    return;
+  }
+  let previous = std::mem::take(&mut registry().registered);
+  for sc in previous {
+    let _ = app.global_shortcut().unregister(sc);
+  }
+  let mut taken = ui;
+  let mut registered = Vec::new();
+  let mut status = Vec::new();
+  for (action, shortcut) in bindings {
+    let label = label_of(&action);
+    let candidates: Vec<&str> = match (shortcut.is_empty(), action.as_str()) {
+      (true, QUICK_ACTIONS) => QUICK_ACTIONS_DEFAULTS.to_vec(),
+      (true, _) => Vec::new(),
+      (false, _) => vec![shortcut.as_str()],
+    };
+    let mut entry = HotkeyBinding { action: action.clone(), label: label.clone(), shortcut: shortcut.clone(), active: None, error: None };
+    for candidate in candidates {
+      match register(app, &action, candidate, &taken) {
+        Ok(sc) => {
+          taken.push((sc, label.clone()));
+          registered.push(sc);
+          entry.active = Some(candidate.to_string());
+          entry.error = None;
+          break;
+        }
+        Err(e) => entry.error = Some(e),
+      }
+    }
+    if shortcut.is_empty() && entry.active.is_none() && entry.error.is_some() {
+      entry.error = Some("None of the default shortcuts could be registered".into());
+    }
+    if let Some(e) = &entry.error {
+      tracing::warn!(action = %action, shortcut = %shortcut, error = %e, "hotkey not registered");
+    }
+    status.push(entry);
+  }
+  let mut reg = registry();
+  reg.registered = registered;
+  reg.status = status;
+  reg.applied = Some(key);
+}
+
+pub fn init(app: AppHandle) {
+  let _ = APP.set(app);
+  apply_from_settings();
+}
+
+/// Every action with its shortcut and registration state.
+pub fn status() -> Vec<HotkeyBinding> {
+  let reg = registry();
+  if !reg.status.is_empty() {
+    return reg.status.clone();
+  }
+  let settings = crate::config::load_settings_json();
+  configured(&settings)
+    .into_iter()
+    .map(|(action, shortcut)| HotkeyBinding { label: label_of(&action), action, shortcut, active: None, error: None })
+    .collect()
+}
+
+#[tauri::command]
+pub fn hotkeys_get() -> Result<Vec<HotkeyBinding>, String> {
+  Ok(status())
+}
+
+/// Change the shortcuts of the given actions ("" clears one) and register them. Fails without
+/// saving when a shortcut is invalid or used twice (including by the summarize, paste history and
+/// TTS playback hotkeys).
+#[tauri::command]
+pub async fn hotkeys_set(bindings: HashMap<String, String>) -> Result<Vec<HotkeyBinding>, String> {
+  let settings = crate::config::load_settings_json();
+  let mut next = configured(&settings);
+  for (action, shortcut) in bindings {
+    let slot = next.iter_mut().find(|(a, _)| *a == action).ok_or_else(|| format!("Unknown hotkey action '{action}'"))?;
+    slot.1 = shortcut.trim().to_string();
+  }
+  let mut taken = ui_shortcuts(&settings);
+  for (action, shortcut) in next.iter().filter(|(_, s)| !s.is_empty()) {
+    let sc = parse(shortcut)?;
+    if let Some((_, other)) = taken.iter().find(|(t, _)| *t == sc) {
+      return Err(format!("{shortcut} is used for both {} and {other}", label_of(action)));
+    }
+    taken.push((sc, label_of(action)));
+  }
+  let mut map = serde_json::Map::new();
+  let mut hotkeys = serde_json::Map::new();
+  for (action, shortcut) in next {
+    match legacy_key(&action) {
+      Some(key) => {
+        map.insert(key.to_string(), serde_json::Value::String(shortcut));
+      }
+      None if !shortcut.is_empty() => {
+        hotkeys.insert(action, serde_json::Value::String(shortcut));
+      }
+      None => {}
+    }
+  }
+  map.insert("hotkeys".to_string(), serde_json::Value::Object(hotkeys));
+  // Saving re-registers them (apply_from_settings)
+  crate::config::save_settings(serde_json::Value::Object(map)).await?;
+  Ok(status())
+}
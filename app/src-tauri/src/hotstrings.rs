@@ -0,0 +1,375 @@
+// Hotstrings: typed abbreviations such as ";sig" or ";meet" are replaced in place by a static
+// snippet or by AI-generated text. A low-level keyboard hook (Windows) keeps a short buffer of
+// typed characters; when it ends with a trigger, the trigger is erased with backspaces and the
+// expansion is inserted through the regular insertion path (paste or typing per settings/app).
+//
+// Definitions live in hotstrings.json next to settings.json:
+//   [
+//     { "trigger": ";sig", "kind": "static", "text": "Best regards,\nAlex" },
+//     { "trigger": ";meet", "kind": "ai", "text": "Write a short, friendly meeting request about: {clipboard}" }
+//   ]
+// Placeholders in `text`: {clipboard}, {window} (active window title), {app}, {date}, {time}.
+// "ai" entries use `text` as the prompt; the window title and app are passed as context.
+// The hook is only installed while `hotstrings_enabled` is true.
+//
+// Events: hotstring:expanded { trigger, kind }, hotstring:error { trigger, message }
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+const MAX_TRIGGER_CHARS: usize = 32;
+
+static APP: OnceCell<tauri::AppHandle> = OnceCell::new();
+// Definitions read by the keyboard hook, loaded before it is installed and replaced on save so
+// the hook never touches the disk
+static CACHE: Lazy<Mutex<Option<Vec<Hotstring>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Hotstring {
+  pub trigger: String,
+  /// "static" (snippet) or "ai" (text is a prompt)
+  #[serde(default = "default_kind")]
+  pub kind: String,
+  pub text: String,
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+}
+
+fn default_kind() -> String {
+  "static".to_string()
+}
+
+fn default_true() -> bool {
+  true
+}
+
+pub fn hotstrings_config_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name("hotstrings.json"))
+}
+
+fn load_hotstrings() -> Vec<Hotstring> {
+  hotstrings_config_path()
+    .and_then(|p| fs::read_to_string(p).ok())
+    .and_then(|text| serde_json::from_str::<Vec<Hotstring>>(&text).ok())
+    .unwrap_or_default()
+}
+
+fn validate(list: &[Hotstring]) -> Result<(), String> {
+  let mut seen = std::collections::HashSet::new();
+  for h in list {
+    let t = h.trigger.as_str();
+    if t.is_empty() || t.chars().count() > MAX_TRIGGER_CHARS {
+      return Err(format!("Trigger \"{t}\" must be 1-{MAX_TRIGGER_CHARS} characters"));
+    }
+    if t.chars().any(|c| c.is_whitespace()) {
+      return Err(format!("Trigger \"{t}\" must not contain whitespace"));
+    }
+    if h.kind != "static" && h.kind != "ai" {
+      return Err(format!("Hotstring \"{t}\": kind must be \"static\" or \"ai\""));
+    }
+    if !seen.insert(t) {
+      return Err(format!("Duplicate trigger \"{t}\""));
+    }
+  }
+  Ok(())
+}
+
+// Longest enabled trigger that the typed buffer ends with
+fn find_match(buffer: &str) -> Option<Hotstring> {
+  let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+  cache
+    .as_ref()?
+    .iter()
+    .filter(|h| h.enabled && !h.trigger.is_empty() && buffer.ends_with(&h.trigger))
+    .max_by_key(|h| h.trigger.chars().count())
+    .cloned()
+}
+
+fn fill_placeholders(text: &str, window: &str, app: &str) -> String {
+  let now = chrono::Local::now();
+  let mut out = text
+    .replace("{window}", window)
+    .replace("{app}", app)
+    .replace("{date}", &now.format("%Y-%m-%d").to_string())
+    .replace("{time}", &now.format("%H:%M").to_string());
+  if out.contains("{clipboard}") {
+    let clip = arboard::Clipboard::new().and_then(|mut c| c.get_text()).unwrap_or_default();
+    out = out.replace("{clipboard}", &clip);
+  }
+  out
+}
+
+async fn generate(prompt: &str, window: &str, app: &str) -> Result<String, String> {
+  let settings = crate::config::load_settings_json();
+  let model = settings
+    .get("quick_prompt_model")
+    .and_then(|x| x.as_str())
+    .map(|s| s.trim().to_string())
+    .filter(|s| !s.is_empty())
+    .unwrap_or_else(crate::config::get_model_from_settings_or_env);
+  let temp = crate::config::get_temperature_from_settings_or_env();
+  let system = format!(
+    "You expand text abbreviations typed by the user. Output only the text to insert at the cursor: no quotes, no preamble, no explanations.\nActive application: {app}\nActive window: {window}"
+  );
+  let out = crate::quick_prompts::complete_quick_prompt(&model, temp, &system, prompt).await?;
+  Ok(out.trim().to_string())
+}
+
+fn erase_trigger(chars: usize) {
+  use enigo::{Enigo, Key, KeyboardControllable};
+  let mut enigo = Enigo::new();
+  for _ in 0..chars {
+    enigo.key_click(Key::Backspace);
+  }
+}
+
+async fn expand(app: tauri::AppHandle, hs: Hotstring) {
+  let window = crate::quick_actions::target_window_handle_raw().map(window_title).unwrap_or_default();
+  let process = crate::quick_actions::foreground_process_name();
+  let trigger_chars = hs.trigger.chars().count();
+  let res = async {
+    // Erase first so the user sees the abbreviation disappear while AI text is generated
+    tokio::task::spawn_blocking(move || erase_trigger(trigger_chars)).await.map_err(|e| format!("spawn_blocking failed: {e}"))?;
+    let filled = fill_placeholders(&hs.text, &window, &process);
+    let text = if hs.kind == "ai" { generate(&filled, &window, &process).await? } else { filled };
+    if text.is_empty() { return Ok(()); }
+    tokio::task::spawn_blocking(move || crate::quick_actions::insert_text_into_focused_app(text, None, None))
+      .await
+      .map_err(|e| format!("spawn_blocking failed: {e}"))?
+  }
+  .await;
+  match res {
+    Ok(()) => {
+      let _ = app.emit("hotstring:expanded", serde_json::json!({ "trigger": hs.trigger, "kind": hs.kind }));
+    }
+    Err(e) => {
+      tracing::warn!(trigger = %hs.trigger, error = %e, "hotstring expansion failed");
+      let _ = app.emit("hotstring:error", serde_json::json!({ "trigger": hs.trigger, "message": e }));
+    }
+  }
+}
+
+// Called from the hook thread; must return quickly
+fn on_buffer(buffer: &str) -> bool {
+  let Some(hs) = find_match(buffer) else { return false };
+  let Some(app) = APP.get().cloned() else { return false };
+  tauri::async_runtime::spawn(expand(app, hs));
+  true
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn window_title(hraw: isize) -> String {
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::UI::WindowsAndMessaging::GetWindowTextW;
+  let mut buf = [0u16; 512];
+  let n = unsafe { GetWindowTextW(HWND(hraw as *mut std::ffi::c_void), &mut buf) };
+  String::from_utf16_lossy(&buf[..n.max(0) as usize])
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn window_title(_hraw: isize) -> String {
+  String::new()
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) mod hook {
+  use std::sync::atomic::{AtomicU32, Ordering};
+  use std::sync::Mutex;
+
+  use once_cell::sync::Lazy;
+  use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+  use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+  use windows::Win32::System::Threading::GetCurrentThreadId;
+  use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetKeyState, ToUnicode, VIRTUAL_KEY, VK_BACK, VK_CAPITAL, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU, VK_RCONTROL,
+    VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT,
+  };
+  use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, DispatchMessageW, GetForegroundWindow, GetMessageW, PostThreadMessageW, SetWindowsHookExW, TranslateMessage,
+    UnhookWindowsHookEx, HHOOK, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG, WH_KEYBOARD_LL, WM_KEYDOWN, WM_QUIT, WM_SYSKEYDOWN,
+  };
+
+  const BUFFER_CHARS: usize = 64;
+
+  struct Typed {
+    text: String,
+    window: isize,
+  }
+
+  static TYPED: Lazy<Mutex<Typed>> = Lazy::new(|| Mutex::new(Typed { text: String::new(), window: 0 }));
+  // Thread running the hook's message loop (0 = not running, STARTING while it installs the hook)
+  static HOOK_THREAD: AtomicU32 = AtomicU32::new(0);
+  const STARTING: u32 = u32::MAX;
+
+  pub(crate) fn pressed(vk: VIRTUAL_KEY) -> bool {
+    unsafe { (GetKeyState(vk.0 as i32) as u16 & 0x8000) != 0 }
+  }
+
+  // Character produced by the key with the current modifier state, without disturbing
+  // dead-key state in the focused app (ToUnicode flag 0x4)
+  pub(crate) fn key_char(vk: u32, scan: u32) -> Option<char> {
+    let mut state = [0u8; 256];
+    for k in [VK_SHIFT, VK_LSHIFT, VK_RSHIFT, VK_CONTROL, VK_LCONTROL, VK_RCONTROL, VK_MENU, VK_LMENU, VK_RMENU] {
+      if pressed(k) {
+        state[k.0 as usize] = 0x80;
+      }
+    }
+    if unsafe { GetKeyState(VK_CAPITAL.0 as i32) } & 1 != 0 {
+      state[VK_CAPITAL.0 as usize] = 0x01;
+    }
+    let mut buf = [0u16; 8];
+    let n = unsafe { ToUnicode(vk, scan, Some(&state), &mut buf, 0x4) };
+    if n != 1 {
+      return None;
+    }
+    char::from_u32(buf[0] as u32).filter(|c| !c.is_control())
+  }
+
+  unsafe extern "system" fn proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let msg = wparam.0 as u32;
+    if code >= 0 && (msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN) {
+      let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+      // Our own expansions (backspaces, typing, paste) are injected; never feed them back
+      if info.flags.0 & LLKHF_INJECTED.0 == 0 {
+        handle_key(info.vkCode, info.scanCode);
+      }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+  }
+
+  fn handle_key(vk: u32, scan: u32) {
+    let Ok(mut typed) = TYPED.lock() else { return };
+    let window = unsafe { GetForegroundWindow() }.0 as isize;
+    if typed.window != window {
+      typed.text.clear();
+      typed.window = window;
+    }
+    if vk == VK_BACK.0 as u32 {
+      typed.text.pop();
+      return;
+    }
+    // Shortcuts (Ctrl/Alt/Win combos) and navigation keys break the typed sequence;
+    // AltGr (Ctrl+Alt) still produces characters through ToUnicode
+    let altgr = pressed(VK_RMENU) && pressed(VK_LCONTROL);
+    if (!altgr && (pressed(VK_CONTROL) || pressed(VK_MENU))) || pressed(VK_LWIN) || pressed(VK_RWIN) {
+      typed.text.clear();
+      return;
+    }
+    let Some(c) = key_char(vk, scan) else {
+      // Modifiers alone keep the buffer; Enter, Tab, arrows etc. reset it
+      if ![VK_SHIFT, VK_LSHIFT, VK_RSHIFT, VK_CAPITAL].iter().any(|k| k.0 as u32 == vk) {
+        typed.text.clear();
+      }
+      return;
+    };
+    typed.text.push(c);
+    let extra = typed.text.chars().count().saturating_sub(BUFFER_CHARS);
+    if extra > 0 {
+      typed.text = typed.text.chars().skip(extra).collect();
+    }
+    if super::on_buffer(&typed.text) {
+      typed.text.clear();
+    }
+  }
+
+  pub fn start() {
+    // Claim the slot first so concurrent calls cannot install two hooks
+    if HOOK_THREAD.compare_exchange(0, STARTING, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+      return;
+    }
+    std::thread::spawn(|| unsafe {
+      let module = GetModuleHandleW(None).map(HINSTANCE::from).unwrap_or_default();
+      let hook = match SetWindowsHookExW(WH_KEYBOARD_LL, Some(proc), module, 0) {
+        Ok(h) => h,
+        Err(e) => {
+          tracing::warn!(error = %e, "hotstring keyboard hook failed");
+          let _ = HOOK_THREAD.compare_exchange(STARTING, 0, Ordering::SeqCst, Ordering::SeqCst);
+          return;
+        }
+      };
+      // stop() while the hook was being installed released the slot
+      if HOOK_THREAD.compare_exchange(STARTING, GetCurrentThreadId(), Ordering::SeqCst, Ordering::SeqCst).is_err() {
+        let _ = UnhookWindowsHookEx(hook);
+        return;
+      }
+      tracing::info!("hotstring listener started");
+      // Low-level hooks are serviced by this thread's message loop; WM_QUIT ends it
+      let mut msg = MSG::default();
+      while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+      }
+      let _ = UnhookWindowsHookEx(hook);
+      HOOK_THREAD.store(0, Ordering::SeqCst);
+      if let Ok(mut typed) = TYPED.lock() {
+        typed.text.clear();
+      }
+      tracing::info!("hotstring listener stopped");
+    });
+  }
+
+  pub fn stop() {
+    let tid = HOOK_THREAD.load(Ordering::SeqCst);
+    if tid == STARTING {
+      let _ = HOOK_THREAD.compare_exchange(STARTING, 0, Ordering::SeqCst, Ordering::SeqCst);
+    } else if tid != 0 {
+      let _ = unsafe { PostThreadMessageW(tid, WM_QUIT, WPARAM(0), LPARAM(0)) };
+    }
+  }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod hook {
+  pub fn start() {}
+  pub fn stop() {}
+}
+
+/// Install or remove the keyboard hook according to `hotstrings_enabled`.
+/// Called at startup (with the app handle) and after settings are saved.
+pub fn apply_enabled_from_settings() {
+  if crate::config::get_hotstrings_enabled_from_settings() && APP.get().is_some() {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.is_none() {
+      *cache = Some(load_hotstrings());
+    }
+    drop(cache);
+    hook::start();
+  } else {
+    hook::stop();
+  }
+}
+
+pub fn init(app: tauri::AppHandle) {
+  let _ = APP.set(app);
+  apply_enabled_from_settings();
+}
+
+#[tauri::command]
+pub fn get_hotstrings() -> Result<Vec<Hotstring>, String> {
+  Ok(load_hotstrings())
+}
+
+/// Replace all hotstring definitions. Returns the config file path.
+#[tauri::command]
+pub fn save_hotstrings(list: Vec<Hotstring>) -> Result<String, String> {
+  let list: Vec<Hotstring> = list.into_iter().map(|h| Hotstring { trigger: h.trigger.trim().to_string(), ..h }).collect();
+  validate(&list)?;
+  let path = hotstrings_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  let pretty = serde_json::to_string_pretty(&list).map_err(|e| format!("Serialize hotstrings failed: {e}"))?;
+  crate::config::write_json_atomic_blocking(&path, &pretty)?;
+  *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some(list);
+  Ok(path.to_string_lossy().to_string())
+}
+
+/// Turn the hotstring listener on or off (persisted as `hotstrings_enabled`).
+/// Returns whether the listener is active (always false off Windows).
+#[tauri::command]
+pub async fn set_hotstrings_enabled(enabled: bool) -> Result<bool, String> {
+  crate::config::save_settings(serde_json::json!({ "hotstrings_enabled": enabled })).await?;
+  Ok(enabled && cfg!(target_os = "windows"))
+}
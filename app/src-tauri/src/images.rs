@@ -0,0 +1,277 @@
+// OpenAI image generation and editing. Results are written as PNG files to the attachments
+// directory (under the temp dir, so chat accepts them as image parts) and announced with
+// `image:generated` { path, prompt, model, revised_prompt? } for the window to insert into chat
+// or copy.
+//
+// Edits take a source image (typically a capture-overlay screenshot) and an optional mask:
+// either a mask PNG (transparent = area to change) or rectangles in image pixels, rendered here
+// into such a PNG. Without a mask the model may change the whole image.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AidcError;
+
+const GENERATIONS_URL: &str = "https://api.openai.com/v1/images/generations";
+const EDITS_URL: &str = "https://api.openai.com/v1/images/edits";
+const DEFAULT_MODEL: &str = "gpt-image-1";
+const DEFAULT_SIZE: &str = "1024x1024";
+
+// Image models can take well over a minute for large or high-quality outputs
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+  reqwest::Client::builder()
+    .timeout(Duration::from_secs(240))
+    .connect_timeout(Duration::from_secs(10))
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+#[derive(Serialize, Clone)]
+pub struct GeneratedImage {
+  pub path: String,
+  pub prompt: String,
+  pub model: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub revised_prompt: Option<String>,
+}
+
+/// Area to change, in source image pixels
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct MaskRect {
+  pub x: u32,
+  pub y: u32,
+  pub width: u32,
+  pub height: u32,
+}
+
+/// `mask` argument of edit_image: a mask PNG path or rectangles to change
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ImageMask {
+  Path(String),
+  Rects(Vec<MaskRect>),
+}
+
+/// Directory for generated/edited images; inside the temp dir so chat can attach them
+pub fn attachments_dir() -> Result<PathBuf, String> {
+  let mut dir = std::env::temp_dir();
+  dir.push("AiDesktopCompanion");
+  dir.push("attachments");
+  std::fs::create_dir_all(&dir).map_err(|e| format!("create attachments dir failed: {e}"))?;
+  Ok(dir)
+}
+
+// "1024x1024", "1536x1024", ... or "auto"
+fn valid_size(size: &str) -> bool {
+  if size == "auto" { return true; }
+  match size.split_once('x') {
+    Some((w, h)) => w.parse::<u32>().map(|w| w > 0).unwrap_or(false) && h.parse::<u32>().map(|h| h > 0).unwrap_or(false),
+    None => false,
+  }
+}
+
+// Arguments win over the `image_model` setting; DALL·E models need b64 output requested explicitly
+fn resolve(size: Option<String>, model: Option<String>) -> Result<(String, String), AidcError> {
+  let clean = |v: Option<String>| v.map(|x| x.trim().to_string()).filter(|x| !x.is_empty());
+  let model = clean(model).or_else(crate::config::get_image_model_from_settings).unwrap_or_else(|| DEFAULT_MODEL.to_string());
+  let size = clean(size).unwrap_or_else(|| DEFAULT_SIZE.to_string()).to_ascii_lowercase();
+  if !valid_size(&size) {
+    return Err(AidcError::InvalidInput(format!("Invalid image size '{size}' (expected WIDTHxHEIGHT or auto)")));
+  }
+  Ok((size, model))
+}
+
+fn is_dalle(model: &str) -> bool {
+  model.starts_with("dall-e")
+}
+
+// gpt-image-1 reports token usage; DALL·E is billed per image
+fn record_usage(model: &str, v: &Value) {
+  let u = &v["usage"];
+  crate::usage::record(crate::usage::IMAGES, model, crate::usage::Usage {
+    input_tokens: u["input_tokens"].as_u64().unwrap_or(0),
+    output_tokens: u["output_tokens"].as_u64().unwrap_or(0),
+    images: v["data"].as_array().map(|d| d.len() as u64).unwrap_or(0),
+    ..Default::default()
+  });
+}
+
+async fn fetch_url(url: &str) -> Result<Vec<u8>, AidcError> {
+  let resp = CLIENT.get(url).send().await.map_err(|e| AidcError::from_reqwest("openai", &e))?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    return Err(AidcError::from_status("openai", status, &body));
+  }
+  Ok(resp.bytes().await.map_err(|e| AidcError::from_reqwest("openai", &e))?.to_vec())
+}
+
+// Write each `data[]` entry (b64_json or url) to the attachments dir and emit `image:generated`
+pub(crate) async fn save_results(app: &AppHandle, v: &Value, prompt: &str, model: &str, prefix: &str) -> Result<Vec<GeneratedImage>, AidcError> {
+  let data = v
+    .get("data")
+    .and_then(|x| x.as_array())
+    .filter(|a| !a.is_empty())
+    .ok_or_else(|| AidcError::invalid_response("openai", "no images in response"))?;
+  let dir = attachments_dir().map_err(AidcError::Internal)?;
+  let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+  let mut out = Vec::with_capacity(data.len());
+  for (i, item) in data.iter().enumerate() {
+    let bytes = if let Some(b64) = item.get("b64_json").and_then(|x| x.as_str()) {
+      B64.decode(b64).map_err(|e| AidcError::invalid_response("openai", format!("bad image data: {e}")))?
+    } else if let Some(url) = item.get("url").and_then(|x| x.as_str()) {
+      fetch_url(url).await?
+    } else {
+      return Err(AidcError::invalid_response("openai", "image entry without data"));
+    };
+    let path = dir.join(format!("aidc_{prefix}_{stamp}_{}_{i}.png", uuid::Uuid::new_v4().simple()));
+    tokio::fs::write(&path, &bytes).await.map_err(|e| AidcError::Internal(format!("write image failed: {e}")))?;
+    crate::temp_files::register(crate::temp_files::IMAGE, &path);
+    let img = GeneratedImage {
+      path: path.to_string_lossy().to_string(),
+      prompt: prompt.to_string(),
+      model: model.to_string(),
+      revised_prompt: item.get("revised_prompt").and_then(|x| x.as_str()).map(|s| s.to_string()),
+    };
+    let _ = app.emit("image:generated", &img);
+    out.push(img);
+  }
+  Ok(out)
+}
+
+/// Generate an image from a text prompt. `size` is WIDTHxHEIGHT or "auto" (default 1024x1024);
+/// `model` falls back to the `image_model` setting, then gpt-image-1.
+#[tauri::command]
+pub async fn generate_image(app: AppHandle, prompt: String, size: Option<String>, model: Option<String>) -> Result<Vec<GeneratedImage>, AidcError> {
+  let prompt = prompt.trim().to_string();
+  if prompt.is_empty() { return Err(AidcError::InvalidInput("Prompt is empty".into())); }
+  let (size, model) = resolve(size, model)?;
+  let key = crate::settings::require_openai_key()?;
+  let mut body = json!({ "model": model, "prompt": prompt, "size": size, "n": 1 });
+  if is_dalle(&model) { body["response_format"] = json!("b64_json"); }
+  let resp = crate::rate_limit::send("openai", "images", || CLIENT.post(GENERATIONS_URL).bearer_auth(&key).json(&body))
+    .await
+    .map_err(|e| AidcError::from_reqwest("openai", &e))?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    return Err(AidcError::from_status("openai", status, &text));
+  }
+  let v: Value = resp.json().await.map_err(|e| AidcError::invalid_response("openai", e.to_string()))?;
+  record_usage(&model, &v);
+  let images = save_results(&app, &v, &prompt, &model, "image").await?;
+  tracing::info!(model = %model, size = %size, count = images.len(), "image generated");
+  Ok(images)
+}
+
+fn encode_png(img: &image::DynamicImage) -> Result<Vec<u8>, AidcError> {
+  let mut out = std::io::Cursor::new(Vec::new());
+  img.write_to(&mut out, image::ImageFormat::Png).map_err(|e| AidcError::Internal(format!("encode png failed: {e}")))?;
+  Ok(out.into_inner())
+}
+
+// Opaque everywhere except the rectangles (clipped to the image), which become transparent
+fn mask_from_rects(width: u32, height: u32, rects: &[MaskRect]) -> Result<Vec<u8>, AidcError> {
+  let mut mask = image::RgbaImage::from_pixel(width, height, image::Rgba([0, 0, 0, 255]));
+  let mut any = false;
+  for r in rects {
+    let (x1, y1) = (r.x.min(width), r.y.min(height));
+    let (x2, y2) = (r.x.saturating_add(r.width).min(width), r.y.saturating_add(r.height).min(height));
+    for y in y1..y2 {
+      for x in x1..x2 {
+        mask.put_pixel(x, y, image::Rgba([0, 0, 0, 0]));
+        any = true;
+      }
+    }
+  }
+  if !any { return Err(AidcError::InvalidInput("Mask rectangles are outside the image".into())); }
+  encode_png(&image::DynamicImage::ImageRgba8(mask))
+}
+
+fn load_mask(mask: ImageMask, width: u32, height: u32) -> Result<Vec<u8>, AidcError> {
+  match mask {
+    ImageMask::Rects(rects) => mask_from_rects(width, height, &rects),
+    ImageMask::Path(path) => {
+      let img = image::open(&path).map_err(|e| AidcError::InvalidInput(format!("Failed to read mask '{path}': {e}")))?;
+      if (img.width(), img.height()) != (width, height) {
+        return Err(AidcError::InvalidInput(format!("Mask is {}x{} but the image is {width}x{height}", img.width(), img.height())));
+      }
+      encode_png(&img)
+    }
+  }
+}
+
+/// Edit an image (e.g. a capture-overlay screenshot) following `prompt`, optionally only inside
+/// `mask`. `size` and `model` behave as in generate_image; the default size is "auto".
+#[tauri::command]
+pub async fn edit_image(
+  app: AppHandle,
+  path: String,
+  mask: Option<ImageMask>,
+  prompt: String,
+  size: Option<String>,
+  model: Option<String>,
+) -> Result<Vec<GeneratedImage>, AidcError> {
+  let prompt = prompt.trim().to_string();
+  if prompt.is_empty() { return Err(AidcError::InvalidInput("Prompt is empty".into())); }
+  let (size, model) = resolve(size.or_else(|| Some("auto".into())), model)?;
+  let key = crate::settings::require_openai_key()?;
+  let src = path.clone();
+  let (image_png, mask_png) = tokio::task::spawn_blocking(move || {
+    let img = image::open(&src).map_err(|e| AidcError::InvalidInput(format!("Failed to read image '{src}': {e}")))?;
+    let mask_png = mask.map(|m| load_mask(m, img.width(), img.height())).transpose()?;
+    Ok::<_, AidcError>((encode_png(&img)?, mask_png))
+  })
+  .await
+  .map_err(|e| AidcError::Internal(format!("image task failed: {e}")))??;
+
+  // The form is rebuilt per attempt because a multipart body cannot be replayed
+  let resp = crate::rate_limit::send("openai", "images", || {
+    let png = |bytes: &Vec<u8>, name: &str| {
+      let part = reqwest::multipart::Part::bytes(bytes.clone()).file_name(name.to_string());
+      match part.mime_str("image/png") {
+        Ok(p) => p,
+        Err(_) => reqwest::multipart::Part::bytes(bytes.clone()).file_name(name.to_string()),
+      }
+    };
+    let mut form = reqwest::multipart::Form::new()
+      .text("model", model.clone())
+      .text("prompt", prompt.clone())
+      .text("n", "1")
+      .part("image", png(&image_png, "image.png"));
+    if let Some(m) = &mask_png { form = form.part("mask", png(m, "mask.png")); }
+    // DALL·E edits have no "auto" size; leaving it out keeps the source size
+    if !(is_dalle(&model) && size == "auto") { form = form.text("size", size.clone()); }
+    if is_dalle(&model) { form = form.text("response_format", "b64_json"); }
+    CLIENT.post(EDITS_URL).bearer_auth(&key).multipart(form)
+  })
+  .await
+  .map_err(|e| AidcError::from_reqwest("openai", &e))?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    return Err(AidcError::from_status("openai", status, &text));
+  }
+  let v: Value = resp.json().await.map_err(|e| AidcError::invalid_response("openai", e.to_string()))?;
+  record_usage(&model, &v);
+  let images = save_results(&app, &v, &prompt, &model, "edit").await?;
+  tracing::info!(model = %model, masked = mask_png.is_some(), count = images.len(), "image edited");
+  Ok(images)
+}
+
+/// Put an image file (e.g. a generated image) on the clipboard
+#[tauri::command]
+pub fn copy_image_to_clipboard(path: String) -> Result<(), String> {
+  let img = image::open(&path).map_err(|e| format!("open image failed: {e}"))?.to_rgba8();
+  let (width, height) = img.dimensions();
+  let data = arboard::ImageData { width: width as usize, height: height as usize, bytes: img.into_raw().into() };
+  let mut clipboard = arboard::Clipboard::new().map_err(|e| format!("clipboard unavailable: {e}"))?;
+  clipboard.set_image(data).map_err(|e| format!("copy image failed: {e}"))
+}
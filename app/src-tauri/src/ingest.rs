@@ -0,0 +1,200 @@
+// Drag-and-drop ingestion. Files dropped on the main window are classified (image, audio, PDF,
+// text), copied into the attachments directory and run through the matching extractor (OCR,
+// STT, PDF text, plain read). Each file is announced with `chat:attachment-ready`
+// (payload = Attachment) whether or not extraction succeeded; a batch runs as an "ingest" job.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AidcError;
+
+pub const ATTACHMENT_EVENT: &str = "chat:attachment-ready";
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+// Extracted text beyond this is cut (and flagged) to keep chat requests reasonable
+const MAX_TEXT_CHARS: usize = 200_000;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+  Image,
+  Audio,
+  Pdf,
+  Text,
+  Other,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Attachment {
+  pub id: String,
+  pub kind: FileKind,
+  pub name: String,
+  /// Managed copy in the attachments directory (None when copying failed)
+  pub path: Option<String>,
+  pub original_path: String,
+  pub size: u64,
+  pub mime: Option<String>,
+  /// Extracted text: OCR for images, transcript for audio, document text otherwise
+  pub text: Option<String>,
+  pub truncated: bool,
+  pub error: Option<String>,
+}
+
+fn extension(path: &Path) -> String {
+  path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).unwrap_or_default()
+}
+
+pub fn classify(path: &Path) -> (FileKind, Option<&'static str>) {
+  match extension(path).as_str() {
+    "png" => (FileKind::Image, Some("image/png")),
+    "jpg" | "jpeg" => (FileKind::Image, Some("image/jpeg")),
+    "webp" => (FileKind::Image, Some("image/webp")),
+    "gif" => (FileKind::Image, Some("image/gif")),
+    "bmp" => (FileKind::Image, Some("image/bmp")),
+    "tif" | "tiff" => (FileKind::Image, Some("image/tiff")),
+    "wav" => (FileKind::Audio, Some("audio/wav")),
+    "mp3" => (FileKind::Audio, Some("audio/mpeg")),
+    "m4a" | "mp4" => (FileKind::Audio, Some("audio/mp4")),
+    "ogg" | "oga" => (FileKind::Audio, Some("audio/ogg")),
+    "webm" => (FileKind::Audio, Some("audio/webm")),
+    "flac" => (FileKind::Audio, Some("audio/flac")),
+    "pdf" => (FileKind::Pdf, Some("application/pdf")),
+    "txt" | "md" | "markdown" | "csv" | "tsv" | "log" | "json" | "yaml" | "yml" | "toml" | "xml" | "html" | "htm" | "ini" | "rs" | "ts"
+    | "js" | "vue" | "py" | "java" | "c" | "h" | "cpp" | "cs" | "go" | "sh" | "ps1" | "sql" => (FileKind::Text, Some("text/plain")),
+    _ => (FileKind::Other, None),
+  }
+}
+
+fn truncate(text: String) -> (String, bool) {
+  match text.char_indices().nth(MAX_TEXT_CHARS) {
+    Some((cut, _)) => (text[..cut].to_string(), true),
+    None => (text, false),
+  }
+}
+
+// Files without a known extension are accepted as text when they decode as UTF-8
+fn read_text(path: &Path, strict: bool) -> Result<String, String> {
+  let bytes = std::fs::read(path).map_err(|e| format!("read failed: {e}"))?;
+  match String::from_utf8(bytes) {
+    Ok(s) => Ok(s),
+    Err(_) if strict => Err("Unsupported file type".into()),
+    Err(e) => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+  }
+}
+
+pub(crate) async fn extract(app: &AppHandle, kind: FileKind, path: &Path, mime: Option<&str>) -> Result<String, String> {
+  match kind {
+    FileKind::Image => crate::ocr::recognize_file(app, path).await.map_err(|e| e.message()),
+    FileKind::Audio => {
+      let audio = tokio::fs::read(path).await.map_err(|e| format!("read failed: {e}"))?;
+      let mime = mime.unwrap_or("application/octet-stream").to_string();
+      crate::stt_transcribe_inner(audio, mime, Some(false), None).await.map(|r| r.final_text).map_err(|e| e.message())
+    }
+    FileKind::Pdf => {
+      let p = path.to_path_buf();
+      tokio::task::spawn_blocking(move || pdf_extract::extract_text(&p).map_err(|e| format!("PDF text extraction failed: {e}")))
+        .await
+        .map_err(|e| format!("extract task failed: {e}"))?
+    }
+    FileKind::Text | FileKind::Other => {
+      let p = path.to_path_buf();
+      let strict = kind == FileKind::Other;
+      tokio::task::spawn_blocking(move || read_text(&p, strict)).await.map_err(|e| format!("extract task failed: {e}"))?
+    }
+  }
+}
+
+async fn ingest_one(app: &AppHandle, original: &Path) -> Attachment {
+  let name = original.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "file".into());
+  let (kind, mime) = classify(original);
+  let mut att = Attachment {
+    id: uuid::Uuid::new_v4().to_string(),
+    kind,
+    name: name.clone(),
+    path: None,
+    original_path: original.to_string_lossy().to_string(),
+    size: 0,
+    mime: mime.map(|m| m.to_string()),
+    text: None,
+    truncated: false,
+    error: None,
+  };
+  let meta = match tokio::fs::metadata(original).await {
+    Ok(m) if m.is_file() => m,
+    Ok(_) => {
+      att.error = Some("Folders are not supported".into());
+      return att;
+    }
+    Err(e) => {
+      att.error = Some(format!("read failed: {e}"));
+      return att;
+    }
+  };
+  att.size = meta.len();
+  if att.size > MAX_FILE_BYTES {
+    att.error = Some(format!("File is larger than {} MB", MAX_FILE_BYTES / (1024 * 1024)));
+    return att;
+  }
+  let dest = match crate::images::attachments_dir() {
+    Ok(dir) => dir.join(format!("{}_{}", &att.id[..8], name)),
+    Err(e) => {
+      att.error = Some(e);
+      return att;
+    }
+  };
+  if let Err(e) = tokio::fs::copy(original, &dest).await {
+    att.error = Some(format!("copy failed: {e}"));
+    return att;
+  }
+  att.path = Some(dest.to_string_lossy().to_string());
+  match extract(app, kind, &dest, mime).await {
+    Ok(text) => {
+      // Unknown extension that decoded as UTF-8
+      if kind == FileKind::Other {
+        att.kind = FileKind::Text;
+        att.mime = Some("text/plain".into());
+      }
+      // Attachment text is untrusted (often downloaded from the web) and goes straight into chat
+      let text = crate::guard::guard_text(app, &format!("file:{name}"), text.trim().to_string());
+      let (text, truncated) = truncate(text);
+      att.text = Some(text).filter(|t| !t.is_empty());
+      att.truncated = truncated;
+    }
+    Err(e) => {
+      tracing::warn!(file = %name, kind = ?kind, error = %e, "attachment extraction failed");
+      att.error = Some(e);
+    }
+  }
+  att
+}
+
+/// Ingest files in the background (one "ingest" job per batch). Returns the job id.
+pub fn ingest(app: &AppHandle, paths: Vec<PathBuf>) -> String {
+  let label = match paths.len() {
+    1 => format!("Attach {}", paths[0].file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()),
+    n => format!("Attach {n} files"),
+  };
+  let app2 = app.clone();
+  crate::jobs::spawn(app, "ingest", label, None, move |job| async move {
+    let total = paths.len() as u64;
+    let mut out = Vec::with_capacity(paths.len());
+    for (i, path) in paths.iter().enumerate() {
+      job.progress(i as u64, Some(total), path.file_name().and_then(|n| n.to_str()));
+      let att = ingest_one(&app2, path).await;
+      let _ = app2.emit(ATTACHMENT_EVENT, &att);
+      out.push(att);
+    }
+    job.progress(total, Some(total), None);
+    Ok::<_, String>(out)
+  })
+}
+
+/// Ingest files picked in the UI (e.g. a file dialog); same pipeline as a drop on the window.
+#[tauri::command]
+pub fn ingest_files(app: AppHandle, paths: Vec<String>) -> Result<String, AidcError> {
+  if paths.is_empty() {
+    return Err(AidcError::InvalidInput("No files given".into()));
+  }
+  Ok(ingest(&app, paths.into_iter().map(PathBuf::from).collect()))
+}
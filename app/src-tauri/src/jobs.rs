@@ -0,0 +1,235 @@
+// Background job manager: model downloads, cache cleanups, indexing and exports run as jobs that
+// share one progress event (`job:update`, payload = JobStatus, versioned via `events`), cancel through the task registry
+// and stay queryable for a while after they finish.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::tasks::{self, TaskHandle};
+
+pub const JOB_EVENT: &str = "job:update";
+// Finished jobs kept for `list_jobs`; the oldest are dropped first
+const MAX_FINISHED_JOBS: usize = 50;
+// Progress events are throttled; the job table itself is always current
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+  Running,
+  /// Queued until network connectivity returns
+  Waiting,
+  Done,
+  Failed,
+  Cancelled,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct JobStatus {
+  pub id: String,
+  /// "download", "cleanup", "index", "export", ...
+  pub kind: String,
+  pub label: String,
+  pub state: JobState,
+  /// Units of work done so far (bytes for downloads, files for cleanups, ...)
+  pub current: u64,
+  /// Total units when known
+  pub total: Option<u64>,
+  /// Short description of the current step, e.g. the file being downloaded
+  pub detail: Option<String>,
+  /// Serialized job output once done
+  pub result: Option<serde_json::Value>,
+  pub error: Option<String>,
+  pub started_at_ms: u64,
+  pub finished_at_ms: Option<u64>,
+}
+
+static JOBS: Lazy<Mutex<HashMap<String, JobStatus>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn now_ms() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn update<F: FnOnce(&mut JobStatus)>(id: &str, f: F) -> Option<JobStatus> {
+  let mut map = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+  let status = map.get_mut(id)?;
+  f(status);
+  Some(status.clone())
+}
+
+fn prune_finished(map: &mut HashMap<String, JobStatus>) {
+  let mut finished: Vec<(u64, String)> = map
+    .values()
+    .filter_map(|s| s.finished_at_ms.map(|t| (t, s.id.clone())))
+    .collect();
+  if finished.len() <= MAX_FINISHED_JOBS { return; }
+  finished.sort();
+  let excess = finished.len() - MAX_FINISHED_JOBS;
+  for (_, id) in finished.into_iter().take(excess) { map.remove(&id); }
+}
+
+/// Handle given to a job body for reporting progress.
+#[derive(Clone)]
+pub struct JobReporter {
+  app: tauri::AppHandle,
+  id: String,
+  last_emit: Arc<Mutex<Option<Instant>>>,
+}
+
+impl JobReporter {
+  #[allow(dead_code)]
+  pub fn id(&self) -> &str { &self.id }
+
+  pub fn progress(&self, current: u64, total: Option<u64>, detail: Option<&str>) {
+    let Some(status) = update(&self.id, |s| {
+      s.current = current;
+      s.total = total.filter(|t| *t > 0);
+      if let Some(d) = detail { s.detail = Some(d.to_string()); }
+    }) else { return };
+    let finished_step = total.is_some_and(|t| t > 0 && current >= t);
+    let mut last = self.last_emit.lock().unwrap_or_else(|e| e.into_inner());
+    if finished_step || last.map_or(true, |t| t.elapsed() >= PROGRESS_EMIT_INTERVAL) {
+      *last = Some(Instant::now());
+      crate::events::emit(&self.app, JOB_EVENT, &status);
+    }
+  }
+
+  fn set_state(&self, state: JobState, detail: Option<&str>) {
+    if let Some(status) = update(&self.id, |s| {
+      s.state = state;
+      s.detail = detail.map(|d| d.to_string());
+    }) {
+      crate::events::emit(&self.app, JOB_EVENT, &status);
+    }
+  }
+
+  /// For jobs that need the network: when offline, park the job in the `waiting` state until
+  /// connectivity returns (if the offline queue is enabled) or fail with the offline error.
+  pub async fn wait_for_network(&self) -> Result<(), String> {
+    if crate::connectivity::is_online() { return Ok(()); }
+    self.set_state(JobState::Waiting, Some("Waiting for network"));
+    let res = crate::connectivity::wait_until_online().await;
+    self.set_state(JobState::Running, None);
+    res
+  }
+}
+
+fn start(app: &tauri::AppHandle, kind: &str, label: String, id: Option<String>) -> (TaskHandle, JobReporter) {
+  let task = tasks::register(kind, label.clone(), id);
+  let status = JobStatus {
+    id: task.id().to_string(),
+    kind: kind.to_string(),
+    label,
+    state: JobState::Running,
+    current: 0,
+    total: None,
+    detail: None,
+    result: None,
+    error: None,
+    started_at_ms: now_ms(),
+    finished_at_ms: None,
+  };
+  {
+    let mut map = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    map.insert(status.id.clone(), status.clone());
+  }
+  crate::events::emit(app, JOB_EVENT, &status);
+  let reporter = JobReporter { app: app.clone(), id: status.id, last_emit: Arc::new(Mutex::new(None)) };
+  (task, reporter)
+}
+
+async fn drive<T, Fut>(task: TaskHandle, reporter: JobReporter, fut: Fut) -> Result<T, String>
+where
+  T: Serialize,
+  Fut: Future<Output = Result<T, String>>,
+{
+  let res = task.run(fut).await;
+  let cancelled = task.is_cancelled();
+  let finished = {
+    let mut map = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    let status = map.get_mut(&reporter.id).map(|s| {
+      s.finished_at_ms = Some(now_ms());
+      match &res {
+        Ok(v) => {
+          s.state = JobState::Done;
+          s.result = serde_json::to_value(v).ok();
+        }
+        Err(_) if cancelled => s.state = JobState::Cancelled,
+        Err(e) => {
+          s.state = JobState::Failed;
+          s.error = Some(e.clone());
+        }
+      }
+      s.clone()
+    });
+    prune_finished(&mut map);
+    status
+  };
+  if let Some(status) = finished { crate::events::emit(&reporter.app, JOB_EVENT, &status); }
+  res
+}
+
+/// Run a job to completion on the current task. `id` lets the caller pick the job id up front
+/// (to cancel or filter events while awaiting); a random one is used otherwise.
+pub async fn run<T, F, Fut>(app: &tauri::AppHandle, kind: &str, label: impl Into<String>, id: Option<String>, f: F) -> Result<T, String>
+where
+  T: Serialize,
+  F: FnOnce(JobReporter) -> Fut,
+  Fut: Future<Output = Result<T, String>>,
+{
+  let (task, reporter) = start(app, kind, label.into(), id);
+  let fut = f(reporter.clone());
+  drive(task, reporter, fut).await
+}
+
+/// Start a job in the background and return its id immediately. Completion is reported via
+/// `job:update` and `get_job`.
+pub fn spawn<T, F, Fut>(app: &tauri::AppHandle, kind: &str, label: impl Into<String>, id: Option<String>, f: F) -> String
+where
+  T: Serialize + Send + 'static,
+  F: FnOnce(JobReporter) -> Fut,
+  Fut: Future<Output = Result<T, String>> + Send + 'static,
+{
+  let (task, reporter) = start(app, kind, label.into(), id);
+  let id = reporter.id.clone();
+  let fut = f(reporter.clone());
+  tauri::async_runtime::spawn(async move {
+    let _ = drive(task, reporter, fut).await;
+  });
+  id
+}
+
+/// Running jobs plus recently finished ones, oldest first.
+#[tauri::command]
+pub fn list_jobs() -> Result<Vec<JobStatus>, String> {
+  let map = JOBS.lock().map_err(|_| "lock poisoned".to_string())?;
+  let mut out: Vec<JobStatus> = map.values().cloned().collect();
+  out.sort_by_key(|s| s.started_at_ms);
+  Ok(out)
+}
+
+#[tauri::command]
+pub fn get_job(id: String) -> Result<Option<JobStatus>, String> {
+  let map = JOBS.lock().map_err(|_| "lock poisoned".to_string())?;
+  Ok(map.get(&id).cloned())
+}
+
+/// Request cancellation of a running job. Returns false when it is not running.
+#[tauri::command]
+pub fn cancel_job(id: String) -> Result<bool, String> {
+  tasks::cancel_task(id)
+}
+
+/// Forget finished jobs. Returns how many were removed.
+#[tauri::command]
+pub fn clear_finished_jobs() -> Result<usize, String> {
+  let mut map = JOBS.lock().map_err(|_| "lock poisoned".to_string())?;
+  let before = map.len();
+  map.retain(|_, s| s.finished_at_ms.is_none());
+  Ok(before - map.len())
+}
@@ -0,0 +1,56 @@
+// OpenAI API key rotation. Besides openai_api_key, more keys can be listed in
+// openai_api_keys_extra (one per line, stored like the other secrets). Requests use the active
+// key; when it is rate limited or out of quota (429), rate_limit::send switches to the next key
+// that is not cooling down, retries with it and emits `provider:failover`. The new key stays
+// active until it fails in turn, and a failed key is skipped for COOLDOWN.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+const COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+// The key itself rather than an index, so editing the list in settings does not shift it
+static ACTIVE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+static COOLING: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub struct Failover {
+  pub key: String,
+  /// 1-based position of `key` in the configured list
+  pub index: usize,
+  pub count: usize,
+}
+
+/// The key to use for the next OpenAI request; None when no key is configured.
+pub fn active_openai_key() -> Option<String> {
+  let keys = crate::config::get_openai_api_keys_from_settings_or_env();
+  let active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+  match active {
+    Some(k) if keys.contains(&k) => Some(k),
+    _ => keys.into_iter().next(),
+  }
+}
+
+/// Mark `failed` as rate limited and make the next usable key active. None when `failed` is not
+/// a configured key or every other key is cooling down.
+pub fn fail_over(failed: &str) -> Option<Failover> {
+  let keys = crate::config::get_openai_api_keys_from_settings_or_env();
+  let pos = keys.iter().position(|k| k == failed)?;
+  let now = Instant::now();
+  let next = {
+    let mut cooling = COOLING.lock().unwrap_or_else(|e| e.into_inner());
+    cooling.retain(|_, until| *until > now);
+    cooling.insert(failed.to_string(), now + COOLDOWN);
+    (1..keys.len()).map(|i| (pos + i) % keys.len()).find(|i| !cooling.contains_key(&keys[*i]))?
+  };
+  *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = Some(keys[next].clone());
+  Some(Failover { key: keys[next].clone(), index: next + 1, count: keys.len() })
+}
+
+/// "…abcd": enough to tell keys apart in logs and events
+pub fn mask(key: &str) -> String {
+  let tail: String = key.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+  format!("…{tail}")
+}
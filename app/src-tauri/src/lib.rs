@@ -1,82 +1,56 @@
 // AiDesktopCompanion v0.1.13 build25
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+  startup::begin();
+  startup::phase("logging", logging::init);
+  startup::phase("crash_handler", crash::install);
   tauri::Builder::default()
     .plugin(tauri_plugin_global_shortcut::Builder::new().build())
     .plugin(tauri_plugin_dialog::init())
     .on_window_event(|window, event| {
       if let tauri::WindowEvent::CloseRequested { api, .. } = event {
         // Close-to-tray: prevent app exit and hide the main window
-        if window.label() == "main" || window.label() == "quick-actions" {
+        if window.label() == "main" || window.label() == "quick-actions" || window.label() == recent_outputs::WINDOW_LABEL {
           api.prevent_close();
           let _ = window.hide();
         }
       }
+      // Files dropped on the main window become chat attachments
+      if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+        if window.label() == "main" && !paths.is_empty() {
+          ingest::ingest(window.app_handle(), paths.clone());
+        }
+      }
     })
     .setup(|app| {
-      if cfg!(debug_assertions) {
-        app.handle().plugin(
-          tauri_plugin_log::Builder::default()
-            .level(log::LevelFilter::Info)
-            .build(),
-        )?;
-      }
-      // System tray: build a minimal menu and icon
-      // Menu items: Show (shows and focuses main window) and Exit (quits app)
-      let show_item = MenuItemBuilder::with_id("show", "Show").build(app)?;
-      let exit_item = MenuItemBuilder::with_id("exit", "Exit").build(app)?;
-      let tray_menu = MenuBuilder::new(app)
-        .items(&[&show_item, &exit_item])
-        .build()?;
-
-      let mut tray_builder = TrayIconBuilder::new()
-        .menu(&tray_menu)
-        .tooltip("AiDesktopCompanion")
-        .on_tray_icon_event(|tray, event| {
-          if let TrayIconEvent::Click {
-            button: MouseButton::Left,
-            button_state: MouseButtonState::Up,
-            ..
-          } = event
-          {
-            let app = tray.app_handle();
-            if let Some(window) = app.get_webview_window("main") {
-              let _ = window.unminimize();
-              let _ = window.show();
-              let _ = window.set_focus();
-            }
+      startup::phase("show_main_window", || {
+        if !config::get_start_in_tray_from_settings() {
+          if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
           }
-        })
-        .on_menu_event(|app, event| match event.id().as_ref() {
-          "show" => {
-            if let Some(window) = app.get_webview_window("main") {
-              let _ = window.unminimize();
-              let _ = window.show();
-              let _ = window.set_focus();
-            }
-          }
-          "exit" => {
-            app.exit(0);
-          }
-          _ => {}
-        });
-
-      if let Some(icon) = app.default_window_icon() {
-        tray_builder = tray_builder.icon(icon.clone());
-      }
-      let _tray = tray_builder.build(app)?;
-      if !config::get_start_in_tray_from_settings() {
-        if let Some(window) = app.get_webview_window("main") {
-          let _ = window.show();
-          let _ = window.set_focus();
         }
-      }
-      // Ensure default quick_prompts.json exists on first run to avoid errors when loading quick prompts
-      if let Some(p) = quick_prompts::quick_prompts_config_path() {
-        if !p.exists() {
-          let _ = quick_prompts::generate_default_quick_prompts();
+      });
+      startup::setup_done();
+      // The rest is not needed for the first window: queue it on the event loop (the tray has to
+      // be built on the main thread) so the window is shown first
+      let handle = app.handle().clone();
+      app.handle().run_on_main_thread(move || {
+        if let Err(e) = startup::phase("tray", || tray::build(&handle)) {
+          tracing::warn!(error = %e, "tray icon failed");
         }
-      }
+        startup::phase("background_services", || start_background_services(&handle));
+      })?;
+      // Ensure default quick_prompts.json exists on first run; until then reads fall back to the defaults
+      std::thread::spawn(|| {
+        startup::phase("default_quick_prompts", || {
+          if let Some(p) = quick_prompts::quick_prompts_config_path() {
+            if !p.exists() {
+              let _ = quick_prompts::generate_default_quick_prompts();
+            }
+          }
+        })
+      });
       Ok(())
     })
     .invoke_handler(tauri::generate_handler![
@@ -90,17 +64,57 @@ pub fn run() {
       tts_stop,
       tts_is_speaking,
       tts_list_voices,
+      tts_rate_up,
+      tts_rate_down,
+      tts_volume_up,
+      tts_volume_down,
       tts_synthesize_wav,
+      tts_synthesize_file,
       tts_openai_synthesize_wav,
       tts_openai_synthesize_file,
       tts_openai_stream_start,
       tts_openai_stream_stop,
       tts_openai_responses_stream_start,
+      tts_elevenlabs_synthesize_file,
+      tts_elevenlabs_stream_start,
+      tts_elevenlabs_stream_stop,
+      tts_elevenlabs_list_voices,
+      tts_piper::tts_piper_status,
+      tts_piper::tts_piper_prefetch,
+      tts_piper::tts_piper_synthesize_file,
+      tts_playback::tts_playback_play,
+      tts_playback::tts_playback_pause,
+      tts_playback::tts_playback_resume,
+      tts_playback::tts_playback_stop,
+      tts_playback::tts_playback_seek,
+      tts_playback::tts_playback_state,
+      tts_queue::tts_queue_start,
+      tts_queue::tts_queue_next,
+      tts_queue::tts_queue_previous,
+      tts_queue::tts_queue_stop,
+      tts_queue::tts_queue_state,
       tts_create_stream_session,
       tts_stop_stream_session,
       tts_stream_session_count,
+      tts_stream_session_list,
+      tts_stream_session_stop_all,
       tts_stream_cleanup_idle,
+      tts_prewarm,
+      tts_normalize::tts_normalize_preview,
+      tts_lexicon::tts_lexicon_get,
+      tts_lexicon::tts_lexicon_save,
       stt_transcribe,
+      stt_transcribe_to_subtitles,
+      stt_stream::stt_transcribe_stream_start,
+      stt_stream::stt_transcribe_stream_stop,
+      recorder::recorder_start,
+      recorder::recorder_stop,
+      recorder::recorder_status,
+      dictation::dictation_start,
+      dictation::dictation_stop,
+      audio_list_input_devices,
+      audio_monitor_level_start,
+      audio_monitor_level_stop,
       stt_post_process_text,
       stt_prefetch_whisper_model,
       stt_prefetch_parakeet_model,
@@ -115,15 +129,24 @@ pub fn run() {
       quick_prompts::run_quick_prompt,
       quick_prompts::run_quick_prompt_result,
       quick_prompts::run_quick_prompt_with_selection,
+      quick_prompts::quick_prompt_follow_up,
+      quick_prompts::summarize_active_window,
       quick_prompts::generate_default_quick_prompts,
       quick_prompts::get_quick_prompts,
       quick_prompts::save_quick_prompts,
+      quick_prompts::get_quick_prompt_configs,
       get_settings,
       save_settings,
       settings::list_openai_models,
+      gemini::list_gemini_models,
       load_conversation_state,
       save_conversation_state,
       clear_conversations,
+      conversations::conversation_list,
+      conversations::conversation_create,
+      conversations::conversation_rename,
+      conversations::conversation_delete,
+      conversations::conversation_search,
       quick_actions::copy_file_to_path,
       tts_delete_temp_wav,
       cleanup_stale_tts_wavs,
@@ -133,6 +156,32 @@ pub fn run() {
       quick_actions::copy_text_to_clipboard,
       quick_actions::dump_key_log,
       quick_actions::refocus_previous_app,
+      quick_actions::get_last_selection_html,
+      clipboard_monitor::clipboard_classify_text,
+      app_profiles::get_active_app_profile,
+      paste_as::paste_as,
+      logging::get_recent_logs,
+      tasks::cancel_task,
+      tasks::list_running_tasks,
+      jobs::list_jobs,
+      jobs::get_job,
+      jobs::cancel_job,
+      jobs::clear_finished_jobs,
+      response_cache::clear_response_cache,
+      response_cache::get_response_cache_stats,
+      connectivity::get_connectivity,
+      connectivity::check_connectivity,
+      crash::get_pending_crash_reports,
+      crash::dismiss_crash_reports,
+      crash::open_crash_folder,
+      run_diagnostics,
+      startup::startup_timings,
+      startup::startup_first_paint,
+      capabilities::get_build_capabilities,
+      stt_benchmark::stt_benchmark,
+      local_models::get_model_memory_usage,
+      local_models::unload_local_models,
+      logging::open_log_folder,
       command_hook::run_command_hook,
       command_hook::command_is_running,
       command_hook::list_command_scripts,
@@ -148,44 +197,226 @@ pub fn run() {
       mcp_get_prompt,
       mcp_ping,
       mcp_is_connected,
+      mcp_status,
       realtime_create_ephemeral_token,
-      realtime_build_tools
+      realtime_build_tools,
+      realtime::realtime_start,
+      realtime::realtime_stop,
+      realtime::realtime_client_secret,
+      realtime::realtime_call_tool,
+      assistant::assistant_start,
+      assistant::assistant_stop,
+      translation::translation_session_start,
+      translation::translation_session_stop,
+      images::generate_image,
+      images::edit_image,
+      images::copy_image_to_clipboard,
+      ocr::ocr_clipboard,
+      ingest::ingest_files,
+      hotstrings::get_hotstrings,
+      hotstrings::save_hotstrings,
+      hotstrings::set_hotstrings_enabled,
+      hotkeys::hotkeys_get,
+      hotkeys::hotkeys_set,
+      scheduler::get_schedules,
+      scheduler::save_schedules,
+      scheduler::run_schedule_now,
+      scheduler::preview_cron,
+      watcher::get_watch_folders,
+      watcher::save_watch_folders,
+      watcher::set_watch_folder_enabled,
+      watcher::get_watch_log,
+      audit::get_audit_log,
+      embeddings::embed_texts,
+      embeddings::embeddings_prefetch_model,
+      embeddings::embeddings_status,
+      system_info::get_system_info,
+      audiobook::export_conversation_audio,
+      usage::get_spend_breakdown,
+      os_auth::os_auth_status,
+      os_auth::reveal_secret,
+      export_settings,
+      migrate_api_key_to_keyring,
+      set_secure_api_key,
+      macros::record_insertion_macro,
+      macros::play_insertion_macro,
+      macros::get_insertion_macros,
+      macros::delete_insertion_macro,
+      voices::list_voice_profiles,
+      voices::save_voice_profile,
+      voices::delete_voice_profile,
+      personas::list_personas,
+      personas::save_persona,
+      personas::delete_persona,
+      personas::get_active_persona,
+      personas::set_active_persona,
+      prompt_library::prompt_library_list,
+      prompt_library::prompt_library_save,
+      prompt_library::prompt_library_delete,
+      code_exec::code_exec_respond,
+      transcript::export_transcript,
+      responses::chat_respond,
+      events::get_event_schema,
+      context::get_desktop_context,
+      memory::list_memories,
+      memory::delete_memory,
+      memory::clear_memories,
+      models::list_model_info,
+      models::get_model_info,
+      notes::notes_list,
+      notes::note_create,
+      notes::note_append,
+      notes::note_read,
+      notes::notes_search,
+      notes::note_export,
+      notes::append_selection_to_note,
+      chat::chat_complete_stream,
+      chat::chat_cancel,
+      temp_files::cleanup_temp_files,
+      temp_files::list_temp_files,
+      recent_outputs::list_recent_outputs,
+      recent_outputs::clear_recent_outputs,
+      recent_outputs::paste_recent_output
     ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|_app, event| {
+      if let tauri::RunEvent::Exit = event {
+        shutdown::run();
+      }
+    });
+}
+
+// Monitors and supervisors; each only spawns its thread or task
+fn start_background_services(app: &tauri::AppHandle) {
+  // Clipboard monitor thread (idle unless clipboard_suggestions_enabled is set)
+  clipboard_monitor::start(app.clone());
+  os_auth::init(app.clone());
+  hotstrings::init(app.clone());
+  hotkeys::init(app.clone());
+  audio_io::apply_mute_from_settings();
+  macros::init(app.clone());
+  scheduler::start(app.clone());
+  watcher::start(app.clone());
+  rate_limit::init(app.clone());
+  connectivity::start(app.clone());
+  local_models::start_idle_unload();
+  temp_files::start_cleanup();
+  mcp::start_supervisor(app.clone(), &MCP_CLIENTS);
+  // Bind the TTS streaming server now rather than on the first streamed playback
+  #[cfg(feature = "streaming-server")]
+  tauri::async_runtime::spawn(async {
+    if let Err(e) = tts_openai::ensure_streaming_server().await {
+      tracing::warn!(error = %e, "TTS streaming server failed to start");
+    }
+  });
 }
 
-use std::{thread, time::Duration};
 
 use tauri::Manager; // bring get_webview_window into scope
 use tauri::Emitter; // bring emit into scope
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
-use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use once_cell::sync::Lazy;
 use std::sync::Arc;
 use tokio::sync::Mutex as AsyncMutex;
-use arboard::Clipboard;
-use enigo::{Enigo, Key, KeyboardControllable};
 use serde::Serialize;
 
+use error::AidcError;
+
+#[cfg(feature = "streaming-server")]
 pub mod tts_streaming_server;
 mod utils;
 mod config;
 mod quick_prompts;
+mod prompt_library;
 mod mcp;
 mod tts_openai;
+mod tts_elevenlabs;
+mod tts_piper;
 mod tts_win_native;
+#[cfg(all(target_os = "macos", feature = "local-tts"))]
+mod tts_mac_native;
 mod tts_utils;
+mod tts_lexicon;
+mod tts_normalize;
+mod audio_decode;
+mod audio_encode;
 pub mod tts_mod;
 pub use tts_mod as tts;
 mod stt;
 mod stt_whisper;
 mod stt_parakeet;
+mod stt_benchmark;
+mod local_models;
 mod capture;
 mod chat;
 mod settings;
 mod quick_actions;
 mod command_hook;
+mod clipboard;
+mod clipboard_monitor;
+mod app_profiles;
+mod uia;
+mod browser_context;
+#[cfg(target_os = "linux")]
+mod linux_input;
+mod paste_as;
+mod logging;
+mod error;
+mod tasks;
+mod jobs;
+mod rate_limit;
+mod key_pool;
+mod timeouts;
+mod temp_files;
+mod recent_outputs;
+mod response_cache;
+mod connectivity;
+mod crash;
+mod diagnostics;
+mod downloads;
+mod capabilities;
+mod realtime;
+mod audio_io;
+mod tts_playback;
+mod tts_queue;
+mod assistant;
+mod stt_stream;
+mod recorder;
+mod dictation;
+mod translation;
+mod images;
+mod ocr;
+mod ingest;
+mod hotstrings;
+mod hotkeys;
+mod tray;
+mod scheduler;
+mod watcher;
+mod audit;
+mod guard;
+mod embeddings;
+mod system_info;
+mod audiobook;
+mod usage;
+mod os_auth;
+mod macros;
+mod voices;
+mod personas;
+mod transcript;
+mod responses;
+mod events;
+mod context;
+mod memory;
+mod conversations;
+mod drafts;
+mod code_exec;
+mod anthropic;
+mod gemini;
+mod pii;
+mod models;
+mod notes;
+mod shutdown;
+mod startup;
 
 use rmcp::{
   service::{RoleClient, DynService, RunningService},
@@ -202,18 +433,30 @@ use rmcp::{
 
 /// Start streaming using OpenAI Responses API with SSE, emitting tts:stream:* events.
 #[tauri::command]
-async fn tts_openai_responses_stream_start(app: tauri::AppHandle, text: String, voice: Option<String>, model: Option<String>, format: Option<String>) -> Result<u64, String> {
-  let key = settings::get_api_key_from_settings_or_env()?;
-  tts_openai::responses_stream_start(app, key, text, voice, model, format)
+async fn tts_openai_responses_stream_start(app: tauri::AppHandle, text: String, voice: Option<String>, model: Option<String>, format: Option<String>) -> Result<u64, AidcError> {
+  if audio_io::is_muted() {
+    return Err(audio_io::MUTED_ERROR.into());
+  }
+  let key = settings::require_openai_key()?;
+  Ok(tts_openai::responses_stream_start(app, key, text, voice, model, format)?)
 }
 
 // Helpers to parse SSE lines from a raw byte buffer (moved to tts module)
 
 /// Create a new TTS streaming session and return the stream URL
 #[tauri::command]
-async fn tts_create_stream_session(text: String, voice: Option<String>, model: Option<String>, format: Option<String>, instructions: Option<String>) -> Result<String, String> {
-  let api_key = settings::get_api_key_from_settings_or_env()?;
-  tts_openai::create_stream_session(text, voice, model, format, instructions, api_key).await
+async fn tts_create_stream_session(text: String, voice: Option<String>, model: Option<String>, format: Option<String>, instructions: Option<String>) -> Result<String, AidcError> {
+  if audio_io::is_muted() {
+    return Err(audio_io::MUTED_ERROR.into());
+  }
+  let api_key = settings::require_openai_key()?;
+  Ok(tts_openai::create_stream_session(text, voice, model, format, instructions, api_key).await?)
+}
+
+/// Start the streaming server and warm the speech connection (called when the TTS panel opens)
+#[tauri::command]
+async fn tts_prewarm() -> Result<(), String> {
+  tts_openai::prewarm().await
 }
 
 /// Stop a TTS streaming session
@@ -228,6 +471,18 @@ fn tts_stream_session_count() -> Result<usize, String> {
   tts_openai::stream_session_count()
 }
 
+/// QA: metadata of the open TTS streaming sessions (age, started, text length, format)
+#[tauri::command]
+fn tts_stream_session_list() -> Result<Vec<tts_openai::StreamSessionInfo>, String> {
+  tts_openai::stream_session_list()
+}
+
+/// QA: cancel and drop every TTS streaming session (stuck players)
+#[tauri::command]
+fn tts_stream_session_stop_all() -> Result<usize, String> {
+  tts_openai::stream_session_stop_all()
+}
+
 /// QA: cleanup idle TTS sessions older than ttl_seconds (that have not started)
 #[tauri::command]
 fn tts_stream_cleanup_idle(ttl_seconds: u64) -> Result<usize, String> {
@@ -244,7 +499,7 @@ fn tts_stream_cleanup_idle(ttl_seconds: u64) -> Result<usize, String> {
 fn load_conversation_state() -> Result<serde_json::Value, String> { config::load_conversation_state() }
 
 #[tauri::command]
-fn save_conversation_state(state: serde_json::Value) -> Result<String, String> { config::save_conversation_state(state) }
+async fn save_conversation_state(state: serde_json::Value) -> Result<String, String> { config::save_conversation_state(state).await }
 
 #[tauri::command]
 fn clear_conversations() -> Result<String, String> { config::clear_conversations() }
@@ -260,6 +515,7 @@ static MCP_CLIENTS: Lazy<AsyncMutex<std::collections::HashMap<String, Arc<Runnin
 // resolve_windows_program moved to mcp.rs
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn mcp_connect(
   app: tauri::AppHandle,
   server_id: String,
@@ -267,58 +523,71 @@ async fn mcp_connect(
   args: Vec<String>,
   cwd: Option<String>,
   env: Option<serde_json::Value>,
+  headers: Option<serde_json::Value>,
   transport: Option<String>,
-) -> Result<String, String> {
-  mcp::connect(&app, &MCP_CLIENTS, server_id, command, args, cwd, env, transport).await
+) -> Result<String, AidcError> {
+  mcp::connect(&app, &MCP_CLIENTS, server_id, command, args, cwd, env, headers, transport).await
 }
 
 #[tauri::command]
-async fn mcp_disconnect(app: tauri::AppHandle, server_id: String) -> Result<String, String> {
+async fn mcp_disconnect(app: tauri::AppHandle, server_id: String) -> Result<String, AidcError> {
   mcp::disconnect(&app, &MCP_CLIENTS, server_id).await
 }
 
 #[tauri::command]
-async fn mcp_list_tools(server_id: String) -> Result<serde_json::Value, String> {
+async fn mcp_list_tools(server_id: String) -> Result<serde_json::Value, AidcError> {
   mcp::list_tools(&MCP_CLIENTS, &server_id).await
 }
 
 #[tauri::command]
-async fn mcp_call_tool(server_id: String, name: String, args: serde_json::Value) -> Result<serde_json::Value, String> {
+async fn mcp_call_tool(server_id: String, name: String, args: serde_json::Value) -> Result<serde_json::Value, AidcError> {
   mcp::call_tool(&MCP_CLIENTS, &server_id, &name, args).await
 }
 
 #[tauri::command]
-async fn mcp_list_resources(server_id: String) -> Result<serde_json::Value, String> {
+async fn mcp_list_resources(server_id: String) -> Result<serde_json::Value, AidcError> {
   mcp::list_resources(&MCP_CLIENTS, &server_id).await
 }
 
 #[tauri::command]
-async fn mcp_read_resource(server_id: String, uri: String) -> Result<serde_json::Value, String> {
+async fn mcp_read_resource(server_id: String, uri: String) -> Result<serde_json::Value, AidcError> {
   mcp::read_resource(&MCP_CLIENTS, &server_id, &uri).await
 }
 
 #[tauri::command]
-async fn mcp_list_prompts(server_id: String) -> Result<serde_json::Value, String> {
+async fn mcp_list_prompts(server_id: String) -> Result<serde_json::Value, AidcError> {
   mcp::list_prompts(&MCP_CLIENTS, &server_id).await
 }
 
 #[tauri::command]
-async fn mcp_get_prompt(server_id: String, name: String, arguments: Option<serde_json::Value>) -> Result<serde_json::Value, String> {
+async fn mcp_get_prompt(server_id: String, name: String, arguments: Option<serde_json::Value>) -> Result<serde_json::Value, AidcError> {
   mcp::get_prompt(&MCP_CLIENTS, &server_id, &name, arguments).await
 }
 
 #[tauri::command]
-async fn mcp_ping(server_id: String) -> Result<String, String> {
+async fn mcp_ping(server_id: String) -> Result<String, AidcError> {
   mcp::ping(&MCP_CLIENTS, &server_id).await
 }
 
 /// Query whether an MCP server is currently connected (exists in the clients map).
 #[tauri::command]
-async fn mcp_is_connected(server_id: String) -> Result<bool, String> {
+async fn mcp_is_connected(server_id: String) -> Result<bool, AidcError> {
   let map = MCP_CLIENTS.lock().await;
   Ok(map.contains_key(&server_id))
 }
 
+/// Uptime, last error, restarts and ping latency of an MCP server (see mcp.rs health supervision).
+#[tauri::command]
+async fn mcp_status(server_id: String) -> Result<mcp::McpStatus, AidcError> {
+  Ok(mcp::status(&MCP_CLIENTS, &server_id).await)
+}
+
+/// Run the health checks (API key, models, MCP servers, CUDA, disk, clipboard, hotkey).
+#[tauri::command]
+async fn run_diagnostics(app: tauri::AppHandle) -> Result<diagnostics::DiagnosticsReport, String> {
+  Ok(diagnostics::run(app, &MCP_CLIENTS).await)
+}
+
 // get_disabled_tools_map local helper removed; use config::get_disabled_tools_map()
 
 // settings helpers moved to settings.rs
@@ -329,8 +598,23 @@ fn get_settings() -> Result<serde_json::Value, String> {
 }
 
 #[tauri::command]
-fn save_settings(map: serde_json::Value) -> Result<String, String> {
-  config::save_settings(map)
+async fn save_settings(map: serde_json::Value) -> Result<String, String> {
+  config::save_settings(map).await
+}
+
+#[tauri::command]
+async fn export_settings(path: String, include_secrets: Option<bool>) -> Result<String, String> {
+  config::export_settings(path, include_secrets.unwrap_or(false)).await
+}
+
+#[tauri::command]
+async fn migrate_api_key_to_keyring() -> Result<Vec<String>, String> {
+  config::migrate_api_key_to_keyring().await
+}
+
+#[tauri::command]
+async fn set_secure_api_key(name: String, value: String) -> Result<(), String> {
+  config::set_secure_api_key(name, value).await
 }
 
 // Open the main window TTS panel with provided text and optional autoplay.
@@ -351,27 +635,9 @@ fn open_tts_with_text(app: tauri::AppHandle, text: String, autoplay: Option<bool
 // Capture current selection text and open the TTS panel, optionally starting playback.
 #[tauri::command]
 fn tts_open_with_selection(app: tauri::AppHandle, safe_mode: Option<bool>, autoplay: Option<bool>) -> Result<(), String> {
-  let safe = safe_mode.unwrap_or(false);
-
   // Capture selection text (copy-restore pattern like prompt_action)
-  let mut clipboard = Clipboard::new().map_err(|e| format!("clipboard init failed: {e}"))?;
-  let previous_text = if !safe { clipboard.get_text().ok() } else { None };
-
-  if !safe {
-    let mut enigo = Enigo::new();
-    enigo.key_down(Key::Control);
-    enigo.key_click(Key::Layout('c'));
-    enigo.key_up(Key::Control);
-    thread::sleep(Duration::from_millis(120));
-  }
-
-  let selection = clipboard.get_text().unwrap_or_default();
-
-  if !safe {
-    if let Some(prev) = previous_text {
-      let _ = clipboard.set_text(prev);
-    }
-  }
+  let profile = app_profiles::profile_for_foreground();
+  let selection = clipboard::capture_tts_text(safe_mode, &profile)?;
 
   if selection.trim().is_empty() {
     let _ = app.emit("tts:error", serde_json::json!({ "message": "No text selected" }));
@@ -387,12 +653,18 @@ fn tts_open_with_selection(app: tauri::AppHandle, safe_mode: Option<bool>, autop
 
 #[tauri::command]
 fn tts_start(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<(), String> {
+  if audio_io::is_muted() {
+    return Err(audio_io::MUTED_ERROR.into());
+  }
   tts_win_native::local_tts_start(text, voice, rate, volume)
 }
 
+/// Stop local speech and native playback (read selection, TTS panel).
 #[tauri::command]
-fn tts_stop() -> Result<(), String> { 
-  tts_win_native::local_tts_stop() 
+fn tts_stop() -> Result<(), String> {
+  tts_queue::stop();
+  tts_playback::stop();
+  tts_win_native::local_tts_stop()
 }
 
 #[tauri::command]
@@ -405,31 +677,91 @@ fn tts_is_speaking() -> bool {
   tts_win_native::local_tts_is_speaking()
 }
 
+// Live rate/volume changes of native WAV playback (audio_io), e.g. for voice assistant replies.
+// Each call moves one rate step or 10 volume percent; `tts:playback:adjusted` reports the result.
+fn adjust_tts_playback(app: &tauri::AppHandle, rate_delta: i32, volume_delta: i32) -> audio_io::PlaybackAdjust {
+  let state = audio_io::adjust_playback(rate_delta, volume_delta);
+  events::emit(app, events::TTS_PLAYBACK_ADJUSTED, &state);
+  state
+}
+
+/// Microphones for the `stt_input_device` setting (cpal device names).
+#[tauri::command]
+async fn audio_list_input_devices() -> Result<Vec<String>, String> {
+  // Enumerating devices can block for a while (e.g. Bluetooth headsets waking up)
+  tokio::task::spawn_blocking(audio_io::input_device_names).await.map_err(|e| format!("device listing failed: {e}"))
+}
+
+/// Emit `audio:level` (source "monitor") for `device`, or the configured microphone, until
+/// `audio_monitor_level_stop`; lets settings show the input level before recording.
+#[tauri::command]
+async fn audio_monitor_level_start(app: tauri::AppHandle, device: Option<String>) -> Result<(), String> {
+  tokio::task::spawn_blocking(move || audio_io::start_level_monitor(&app, device))
+    .await
+    .map_err(|e| format!("audio task failed: {e}"))?
+}
+
+#[tauri::command]
+fn audio_monitor_level_stop() -> Result<bool, String> {
+  Ok(audio_io::stop_level_monitor())
+}
+
+#[tauri::command]
+fn tts_rate_up(app: tauri::AppHandle) -> audio_io::PlaybackAdjust {
+  adjust_tts_playback(&app, 1, 0)
+}
+
+#[tauri::command]
+fn tts_rate_down(app: tauri::AppHandle) -> audio_io::PlaybackAdjust {
+  adjust_tts_playback(&app, -1, 0)
+}
+
+#[tauri::command]
+fn tts_volume_up(app: tauri::AppHandle) -> audio_io::PlaybackAdjust {
+  adjust_tts_playback(&app, 0, 10)
+}
+
+#[tauri::command]
+fn tts_volume_down(app: tauri::AppHandle) -> audio_io::PlaybackAdjust {
+  adjust_tts_playback(&app, 0, -10)
+}
+
 #[tauri::command]
 fn tts_synthesize_wav(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, String> {
-  tts_win_native::local_tts_synthesize_wav(text, voice, rate, volume)
+  tts_win_native::local_tts_synthesize_wav(tts_normalize::normalize(&text), voice, rate, volume)
+}
+
+/// Local synthesis to a temp file; `format` is "wav" (default), "mp3" or "ogg".
+#[tauri::command]
+async fn tts_synthesize_file(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>, format: Option<String>) -> Result<String, String> {
+  tokio::task::spawn_blocking(move || {
+    let wav = tts_win_native::local_tts_synthesize_wav(tts_normalize::normalize(&text), voice, rate, volume)?;
+    audio_encode::convert_wav_file(&wav, format.as_deref().unwrap_or("wav"))
+  })
+  .await
+  .map_err(|e| format!("local TTS failed: {e}"))?
 }
 
 /// Back-compat wrapper: synthesize WAV via OpenAI and return a temp file path.
 #[tauri::command]
-async fn tts_openai_synthesize_wav(text: String, voice: Option<String>, model: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, String> {
-  let key = settings::get_api_key_from_settings_or_env()?;
-  tts_openai::openai_synthesize_wav(key, text, voice, model, rate, volume).await
+async fn tts_openai_synthesize_wav(text: String, voice: Option<String>, model: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, AidcError> {
+  let key = settings::require_openai_key()?;
+  tts_openai::openai_synthesize_wav(key, tts_normalize::normalize(&text), voice, model, rate, volume).await
 }
 
 /// Synthesize speech via OpenAI and return a temp file path. Supports wav/mp3/opus.
 #[tauri::command]
-async fn tts_openai_synthesize_file(text: String, voice: Option<String>, model: Option<String>, format: Option<String>, rate: Option<i32>, volume: Option<u8>, instructions: Option<String>) -> Result<String, String> {
-  let key = settings::get_api_key_from_settings_or_env()?;
-  tts_openai::openai_synthesize_file(key, text, voice, model, format, rate, volume, instructions).await
+async fn tts_openai_synthesize_file(text: String, voice: Option<String>, model: Option<String>, format: Option<String>, rate: Option<i32>, volume: Option<u8>, instructions: Option<String>) -> Result<String, AidcError> {
+  let key = settings::require_openai_key()?;
+  tts_openai::openai_synthesize_file(key, tts_normalize::normalize(&text), voice, model, format, rate, volume, instructions).await
 }
 
 /// Start a chunked download stream from OpenAI audio/speech and emit chunks to the frontend.
 /// NOTE: This streams raw container bytes (e.g., MP3 or OGG/Opus). Frontend must handle playback.
 #[tauri::command]
-async fn tts_openai_stream_start(app: tauri::AppHandle, text: String, voice: Option<String>, model: Option<String>, format: Option<String>) -> Result<u64, String> {
-  let key = settings::get_api_key_from_settings_or_env()?;
-  tts_openai::openai_stream_start(app, key, text, voice, model, format)
+async fn tts_openai_stream_start(app: tauri::AppHandle, text: String, voice: Option<String>, model: Option<String>, format: Option<String>) -> Result<u64, AidcError> {
+  let key = settings::require_openai_key()?;
+  Ok(tts_openai::openai_stream_start(app, key, text, voice, model, format)?)
 }
 
 #[tauri::command]
@@ -437,21 +769,47 @@ fn tts_openai_stream_stop(id: u64) -> Result<bool, String> {
   tts_openai::openai_stream_stop(id)
 }
 
+/// Synthesize speech via ElevenLabs and return a temp file path (wav, or mp3 for short texts).
+#[tauri::command]
+async fn tts_elevenlabs_synthesize_file(text: String, voice: Option<String>, model: Option<String>, format: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, AidcError> {
+  let key = settings::require_elevenlabs_key()?;
+  tts_elevenlabs::synthesize_file(key, tts_normalize::normalize(&text), voice, model, format, rate, volume).await
+}
+
+/// Stream MP3 from ElevenLabs as `tts:stream:*` events, like `tts_openai_stream_start`.
+#[tauri::command]
+async fn tts_elevenlabs_stream_start(app: tauri::AppHandle, text: String, voice: Option<String>, model: Option<String>) -> Result<u64, AidcError> {
+  let key = settings::require_elevenlabs_key()?;
+  Ok(tts_elevenlabs::stream_start(app, key, text, voice, model)?)
+}
+
+#[tauri::command]
+fn tts_elevenlabs_stream_stop(id: u64) -> Result<bool, String> {
+  tts_elevenlabs::stream_stop(id)
+}
+
+/// ElevenLabs voices for the settings UI.
+#[tauri::command]
+async fn tts_elevenlabs_list_voices() -> Result<Vec<tts_elevenlabs::ElevenLabsVoice>, AidcError> {
+  let key = settings::require_elevenlabs_key()?;
+  tts_elevenlabs::list_voices(key).await
+}
+
 // Local STT wrapper with feature gating to avoid referencing missing symbols
 #[cfg(feature = "local-stt")]
-async fn transcribe_local_wrapper(audio: Vec<u8>, mime: String) -> Result<String, String> {
+async fn transcribe_local_wrapper(audio: Vec<u8>, mime: String) -> Result<transcript::Timed, String> {
   let lm = config::get_stt_local_model_from_settings_or_env();
   let t = lm.trim().to_lowercase();
   if t.contains("parakeet") {
     let has_cuda = config::get_stt_parakeet_has_cuda_from_settings_or_env();
-    stt_parakeet::transcribe_local(audio, mime, has_cuda, lm).await
+    stt_parakeet::transcribe_local(audio, mime, has_cuda, lm).await.map(transcript::Timed::untimed)
   } else {
     stt_whisper::transcribe_local(audio, mime).await
   }
 }
 
 #[cfg(not(feature = "local-stt"))]
-async fn transcribe_local_wrapper(_audio: Vec<u8>, _mime: String) -> Result<String, String> {
+async fn transcribe_local_wrapper(_audio: Vec<u8>, _mime: String) -> Result<transcript::Timed, String> {
   Err("Local STT is not available: app built without 'local-stt' feature.".into())
 }
 
@@ -526,13 +884,8 @@ async fn maybe_post_process_stt_text(text: String, prompt_override: Option<Strin
   } else {
     format!("{}/v1/chat/completions", b)
   };
-  let resp = match client
-    .post(&chat_url)
-    .bearer_auth(&key)
-    .json(&body)
-    .send()
-    .await
-  {
+  let provider = stt::provider_for_base_url(&base_url);
+  let resp = match rate_limit::send(&provider, "stt post-processing", || client.post(&chat_url).bearer_auth(&key).json(&body)).await {
     Ok(v) => v,
     Err(e) => {
       return SttPostProcessOutcome {
@@ -569,6 +922,7 @@ async fn maybe_post_process_stt_text(text: String, prompt_override: Option<Strin
       };
     }
   };
+  usage::record_response(usage::STT, &model, &v);
   let cleaned = v
     .get("choices")
     .and_then(|c| c.get(0))
@@ -600,6 +954,8 @@ struct SttTranscriptionResult {
   final_text: String,
   post_process_applied: bool,
   post_process_error: Option<String>,
+  /// Timestamped segments of `original_text`
+  segments: Vec<transcript::Segment>,
 }
 
 #[derive(Serialize)]
@@ -612,26 +968,44 @@ struct SttPostProcessResult {
 /// Transcribe audio bytes. Engine is selected via settings (`stt_engine`: "openai" | "local").
 /// Local engine uses whisper-rs with an auto-downloaded ggml model.
 #[tauri::command]
-async fn stt_transcribe(audio: Vec<u8>, mime: String, apply_post_process: Option<bool>, prompt_override: Option<String>) -> Result<SttTranscriptionResult, String> {
+async fn stt_transcribe(audio: Vec<u8>, mime: String, apply_post_process: Option<bool>, prompt_override: Option<String>, task_id: Option<String>) -> Result<SttTranscriptionResult, AidcError> {
+  let task = tasks::register("stt", "Transcription", task_id);
+  let res = task.run(stt_transcribe_inner(audio, mime, apply_post_process, prompt_override)).await?;
+  transcript::remember(res.segments.clone());
+  recent_outputs::record(recent_outputs::TRANSCRIPTION, "Transcription", &res.final_text);
+  Ok(res)
+}
+
+// Run the configured STT engine (also used by the meeting recorder). With `timestamps` the cloud
+// path switches to a model that returns segments; local Whisper always has them, Parakeet never does.
+async fn transcribe_with_engine(audio: Vec<u8>, mime: String, timestamps: bool) -> Result<transcript::Timed, AidcError> {
   let engine = config::get_stt_engine_from_settings_or_env();
-  let transcript = if engine == "local" {
-    transcribe_local_wrapper(audio, mime).await?
+  if engine == "local" {
+    Ok(transcribe_local_wrapper(audio, mime).await?)
   } else {
     let base_url = config::get_stt_cloud_base_url_from_settings_or_env();
-    let model = config::get_stt_cloud_model_from_settings_or_env();
+    let mut model = config::get_stt_cloud_model_from_settings_or_env();
+    if timestamps {
+      model = stt::timestamp_model(&base_url, &model);
+    }
     let is_openai = base_url.trim().starts_with("https://api.openai.com");
     let key_opt = if is_openai {
-      let v = config::load_settings_json();
-      let from_settings = v.get("openai_api_key").and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
-      from_settings.or_else(|| std::env::var("OPENAI_API_KEY").ok())
+      config::get_api_key_from_settings_or_env().ok()
     } else {
       config::get_stt_cloud_api_key_from_settings_or_env()
     };
     if is_openai && key_opt.is_none() {
-      return Err("OPENAI_API_KEY not set in settings or environment".to_string());
+      return Err(AidcError::missing_api_key("openai"));
     }
-    stt::transcribe(key_opt, base_url, model, audio, mime).await?
-  };
+    stt::transcribe(key_opt, base_url, model, audio, mime).await
+  }
+}
+
+async fn stt_transcribe_inner(audio: Vec<u8>, mime: String, apply_post_process: Option<bool>, prompt_override: Option<String>) -> Result<SttTranscriptionResult, AidcError> {
+  let seconds = stt::wav_seconds(&audio);
+  let timed = transcribe_with_engine(audio, mime, false).await?;
+  let transcript = timed.text;
+  let segments = if timed.segments.is_empty() { transcript::single(&transcript, seconds) } else { timed.segments };
 
   let original_text = transcript.trim().to_string();
   let should_apply = apply_post_process.unwrap_or(true);
@@ -657,7 +1031,89 @@ async fn stt_transcribe(audio: Vec<u8>, mime: String, apply_post_process: Option
     final_text,
     post_process_applied: post_processed.applied,
     post_process_error: post_processed.error,
+    segments,
+  })
+}
+
+// OpenAI's transcription endpoint rejects uploads over 25 MB
+const CLOUD_STT_MAX_BYTES: u64 = 25 * 1024 * 1024;
+// Pieces sent to the cloud as 16 kHz mono WAV: 10 minutes is about 19 MB
+const CLOUD_STT_PIECE_SECONDS: usize = 600;
+
+// Container hint from the file extension; audio_decode also detects it from the bytes
+fn audio_mime_for_path(path: &std::path::Path) -> &'static str {
+  match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+    Some("wav") => "audio/wav",
+    Some("mp3") => "audio/mpeg",
+    Some("ogg" | "oga" | "opus") => "audio/ogg",
+    Some("webm") => "audio/webm",
+    Some("m4a" | "mp4" | "aac") => "audio/mp4",
+    Some("flac") => "audio/flac",
+    _ => "",
+  }
+}
+
+// Decode a clip to 16 kHz mono WAV pieces of CLOUD_STT_PIECE_SECONDS and transcribe them one after
+// another, shifting each piece's timestamps by where it starts
+async fn transcribe_in_pieces(audio: Vec<u8>, mime: String) -> Result<Vec<transcript::Segment>, AidcError> {
+  let pcm = tokio::task::spawn_blocking(move || -> Result<Vec<i16>, String> {
+    let pcm = audio_decode::decode(&audio, &mime)?;
+    let channels = pcm.channels.max(1);
+    let mono: Vec<f32> = pcm.samples.chunks(channels).map(|f| f.iter().sum::<f32>() / channels as f32).collect();
+    let mut resampled = Vec::new();
+    audio_io::Resampler::new(pcm.sample_rate, assistant::SAMPLE_RATE).process(&mono, &mut resampled);
+    Ok(audio_encode::to_i16(&resampled))
   })
+  .await
+  .map_err(|e| AidcError::Internal(format!("audio decode task failed: {e}")))??;
+
+  let piece_len = CLOUD_STT_PIECE_SECONDS * assistant::SAMPLE_RATE as usize;
+  let mut segments = Vec::new();
+  for (i, piece) in pcm.chunks(piece_len).enumerate() {
+    let offset_ms = (i * CLOUD_STT_PIECE_SECONDS * 1000) as u64;
+    let seconds = piece.len() as f64 / assistant::SAMPLE_RATE as f64;
+    let timed = transcribe_with_engine(assistant::wav_bytes(piece)?, "audio/wav".to_string(), true).await?;
+    let piece_segments = if timed.segments.is_empty() { transcript::single(&timed.text, Some(seconds)) } else { timed.segments };
+    segments.extend(piece_segments.into_iter().map(|mut seg| {
+      seg.start_ms += offset_ms;
+      seg.end_ms += offset_ms;
+      seg
+    }));
+  }
+  Ok(segments)
+}
+
+/// Transcribe the audio file at `path` and return it as subtitles: `format` is "srt" or "vtt"
+/// ("txt" gives timestamped text). With `out_path` the subtitles are also written there. Segments
+/// are the raw transcript (no post-processing) and become the transcript `export_transcript`
+/// writes. Cloud providers other than OpenAI get 16 kHz WAV, and files over the 25 MB upload limit
+/// are sent in 10-minute pieces.
+#[tauri::command]
+async fn stt_transcribe_to_subtitles(path: String, format: String, out_path: Option<String>, task_id: Option<String>) -> Result<String, AidcError> {
+  let task = tasks::register("stt", "Subtitles", task_id);
+  let source = std::path::PathBuf::from(path.trim());
+  let audio = tokio::fs::read(&source).await.map_err(|e| AidcError::InvalidInput(format!("Cannot read {}: {e}", source.display())))?;
+  let mime = audio_mime_for_path(&source).to_string();
+  let cloud = config::get_stt_engine_from_settings_or_env() != "local";
+  let is_openai = config::get_stt_cloud_base_url_from_settings_or_env().trim().starts_with("https://api.openai.com");
+  let segments = if cloud && (audio.len() as u64 > CLOUD_STT_MAX_BYTES || !is_openai) {
+    task.run(transcribe_in_pieces(audio, mime)).await?
+  } else {
+    let seconds = stt::wav_seconds(&audio);
+    let timed = task.run(transcribe_with_engine(audio, mime, true)).await?;
+    if timed.segments.is_empty() {
+      tracing::warn!("STT engine returned no timestamps; subtitles use a single cue");
+      transcript::single(&timed.text, seconds)
+    } else {
+      timed.segments
+    }
+  };
+  let body = transcript::render(&segments, &format).map_err(AidcError::InvalidInput)?;
+  if let Some(out) = out_path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+    tokio::fs::write(&out, &body).await.map_err(|e| AidcError::Internal(format!("Write subtitles failed: {e}")))?;
+  }
+  transcript::remember(segments);
+  Ok(body)
 }
 
 #[tauri::command]
@@ -670,16 +1126,25 @@ async fn stt_post_process_text(text: String, prompt_override: Option<String>) ->
   })
 }
 
-/// Prefetch Whisper model to the local models folder and emit progress via `stt-model-download` events.
+/// Prefetch Whisper model to the local models folder as a "download" job (progress via `job:update` events).
 #[tauri::command]
-async fn stt_prefetch_whisper_model(app: tauri::AppHandle, url: Option<String>) -> Result<String, String> {
-  stt_whisper::prefetch_model_with_progress(app, url).await
+async fn stt_prefetch_whisper_model(app: tauri::AppHandle, url: Option<String>, task_id: Option<String>) -> Result<String, String> {
+  jobs::run(&app, "download", "Whisper model download", task_id, |job| async move {
+    job.wait_for_network().await?;
+    stt_whisper::prefetch_model_with_progress(job, url).await
+  })
+  .await
 }
 
 #[tauri::command]
-async fn stt_prefetch_parakeet_model(app: tauri::AppHandle, local_model: Option<String>) -> Result<String, String> {
+async fn stt_prefetch_parakeet_model(app: tauri::AppHandle, local_model: Option<String>, task_id: Option<String>) -> Result<String, String> {
   let lm = local_model.unwrap_or_else(|| config::get_stt_local_model_from_settings_or_env());
-  stt_parakeet::prefetch_model_with_progress(app, lm).await
+  let label = format!("Parakeet model download ({lm})");
+  jobs::run(&app, "download", label, task_id, |job| async move {
+    job.wait_for_network().await?;
+    stt_parakeet::prefetch_model_with_progress(job, lm).await
+  })
+  .await
 }
 
 #[derive(Serialize)]
@@ -740,64 +1205,41 @@ fn tts_delete_temp_wav(path: String) -> Result<bool, String> {
 }
 
 #[tauri::command]
-fn cleanup_stale_tts_wavs(max_age_minutes: Option<u64>) -> Result<u32, String> {
-  tts::cleanup_stale_tts_wavs(max_age_minutes)
+async fn cleanup_stale_tts_wavs(app: tauri::AppHandle, max_age_minutes: Option<u64>) -> Result<u32, String> {
+  jobs::run(&app, "cleanup", "TTS temp file cleanup", None, |_job| async move {
+    tokio::task::spawn_blocking(move || tts::cleanup_stale_tts_wavs(max_age_minutes))
+      .await
+      .map_err(|e| format!("spawn_blocking failed: {e}"))?
+  })
+  .await
 }
 
 #[tauri::command]
-async fn chat_complete(app: tauri::AppHandle, messages: Vec<chat::ChatMessage>) -> Result<String, String> {
-  let key = settings::get_api_key_from_settings_or_env()?;
-  let model = settings::get_model_from_settings_or_env();
+async fn chat_complete(app: tauri::AppHandle, mut messages: Vec<chat::ChatMessage>, include_desktop_context: Option<bool>, task_id: Option<String>) -> Result<String, AidcError> {
+  let (provider, model) = chat::provider_from_settings()?;
+  if include_desktop_context.unwrap_or(false) { context::inject(&app, &mut messages).await?; }
   let temp = settings::get_temperature_from_settings_or_env();
-  chat::chat_complete_with_mcp(app, messages, key, model, temp, &MCP_CLIENTS).await
+  let task = tasks::register("chat", format!("Chat ({model})"), task_id);
+  let label = format!("Chat ({model})");
+  let res = task.run(chat::chat_complete_with_mcp(app.clone(), messages, provider.as_ref(), model, temp, &MCP_CLIENTS)).await;
+  chat::notify_if_cancelled(&app, task.id(), &res);
+  if let Ok(text) = &res { recent_outputs::record(recent_outputs::CHAT, label, text); }
+  res
 }
 
 // ---------------------------
 // OpenAI Realtime helpers
 // ---------------------------
 
-/// Create an ephemeral token for OpenAI Realtime WebRTC sessions.
-/// Frontend uses this token as the Bearer when exchanging the SDP offer.
+/// Back-compat wrapper: ephemeral token only. Prefer `realtime_client_secret`, which also
+/// returns the expiry and session config.
 #[tauri::command]
-async fn realtime_create_ephemeral_token(model: Option<String>, voice: Option<String>) -> Result<String, String> {
-  let key = settings::get_api_key_from_settings_or_env()?;
-  let client = reqwest::Client::builder()
-    .timeout(std::time::Duration::from_secs(15))
-    .connect_timeout(std::time::Duration::from_secs(10))
-    .build()
-    .unwrap_or_else(|_| reqwest::Client::new());
-  let model_name = model.unwrap_or_else(|| "gpt-4o-realtime-preview".to_string());
-  let voice_name = voice.unwrap_or_else(|| "verse".to_string());
-  let body = serde_json::json!({
-    "model": model_name,
-    "modalities": ["audio", "text"],
-    "voice": voice_name
-  });
-  let resp = client
-    .post("https://api.openai.com/v1/realtime/sessions")
-    .bearer_auth(&key)
-    .json(&body)
-    .send()
-    .await
-    .map_err(|e| format!("request failed: {e}"))?;
-  if !resp.status().is_success() {
-    let status = resp.status();
-    let text = resp.text().await.unwrap_or_default();
-    return Err(format!("OpenAI error: {status} {text}"));
-  }
-  let v: serde_json::Value = resp.json().await.map_err(|e| format!("json error: {e}"))?;
-  let token = v
-    .get("client_secret")
-    .and_then(|x| x.get("value"))
-    .and_then(|x| x.as_str())
-    .ok_or_else(|| "missing client_secret.value in response".to_string())?;
-  Ok(token.to_string())
+async fn realtime_create_ephemeral_token(model: Option<String>, voice: Option<String>) -> Result<String, AidcError> {
+  Ok(realtime::mint_client_secret(model, voice, None).await?.value)
 }
 
 /// Build OpenAI tool definitions from connected MCP servers for Realtime sessions.
 #[tauri::command]
 async fn realtime_build_tools() -> Result<serde_json::Value, String> {
-  let map = MCP_CLIENTS.lock().await;
-  let tools = mcp::build_openai_tools_from_mcp(&*map).await;
-  Ok(serde_json::Value::Array(tools))
+  Ok(serde_json::Value::Array(realtime::build_tools().await))
 }
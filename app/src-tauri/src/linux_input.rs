@@ -0,0 +1,97 @@
+// Linux selection capture and key injection through the desktop's command-line tools.
+// X11: xdotool (window focus and keys), xclip or xsel (primary selection).
+// Wayland: wl-paste (wl-clipboard) for the primary selection and wtype or ydotool for keys;
+// Wayland has no portable way to query or activate another app's window, so window tracking
+// works on X11 (and for XWayland windows) only. enigo is the fallback when no tool is installed.
+
+use std::process::{Command, Stdio};
+
+pub fn is_wayland() -> bool {
+  std::env::var_os("WAYLAND_DISPLAY").is_some_and(|v| !v.is_empty())
+}
+
+// stdout of a finished command, or None when it is missing or failed
+fn output(program: &str, args: &[&str]) -> Option<String> {
+  let out = Command::new(program).args(args).stdin(Stdio::null()).stderr(Stdio::null()).output().ok()?;
+  if !out.status.success() {
+    return None;
+  }
+  Some(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+fn run(program: &str, args: &[&str]) -> bool {
+  Command::new(program)
+    .args(args)
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .map(|s| s.success())
+    .unwrap_or(false)
+}
+
+/// Text currently highlighted in any app (the primary selection), read without sending keys.
+/// None when nothing is selected or no selection tool is installed.
+pub fn primary_selection() -> Option<String> {
+  let text = if is_wayland() {
+    output("wl-paste", &["--primary", "--no-newline", "--type", "text"])
+      // XWayland apps still publish the X11 primary selection
+      .or_else(|| output("xclip", &["-o", "-selection", "primary"]))
+  } else {
+    output("xclip", &["-o", "-selection", "primary"]).or_else(|| output("xsel", &["--primary", "--output"]))
+  };
+  text.filter(|t| !t.trim().is_empty())
+}
+
+/// X11 id of the active window (xdotool getactivewindow).
+pub fn active_window() -> Option<isize> {
+  output("xdotool", &["getactivewindow"])?.trim().parse().ok()
+}
+
+/// Raise and focus an X11 window; false when it could not be activated.
+pub fn activate_window(id: isize) -> bool {
+  run("xdotool", &["windowactivate", "--sync", &id.to_string()])
+}
+
+pub fn window_pid(id: isize) -> Option<String> {
+  output("xdotool", &["getwindowpid", &id.to_string()]).map(|pid| pid.trim().to_string()).filter(|pid| !pid.is_empty())
+}
+
+/// Executable name (/proc/<pid>/comm) of the process owning an X11 window; empty when unknown.
+pub fn process_name_for_window(id: isize) -> String {
+  window_pid(id)
+    .and_then(|pid| std::fs::read_to_string(format!("/proc/{pid}/comm")).ok())
+    .map(|name| name.trim().to_string())
+    .unwrap_or_default()
+}
+
+/// Send a key chord such as ("ctrl", "c") to the focused window. Returns false when no
+/// injection tool is available so the caller can fall back to enigo.
+pub fn send_chord(modifier: &str, key: &str) -> bool {
+  if is_wayland() {
+    // -k takes a keysym name; a bare argument would be typed as text
+    if run("wtype", &["-M", modifier, "-k", key, "-m", modifier]) {
+      return true;
+    }
+    // ydotool speaks Linux input event codes: keycode:1 presses, keycode:0 releases
+    if let (Some(m), Some(k)) = (evdev_code(modifier), evdev_code(key)) {
+      let (m, k) = (m.to_string(), k.to_string());
+      if run("ydotool", &["key", &format!("{m}:1"), &format!("{k}:1"), &format!("{k}:0"), &format!("{m}:0")]) {
+        return true;
+      }
+    }
+  }
+  // --clearmodifiers releases the modifiers of the hotkey that triggered the action
+  run("xdotool", &["key", "--clearmodifiers", &format!("{modifier}+{key}")])
+}
+
+fn evdev_code(key: &str) -> Option<u16> {
+  match key {
+    "ctrl" => Some(29),
+    "shift" => Some(42),
+    "c" => Some(46),
+    "v" => Some(47),
+    "Insert" => Some(110),
+    _ => None,
+  }
+}
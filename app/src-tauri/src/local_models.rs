@@ -0,0 +1,120 @@
+// Memory management for on-device models. Whisper contexts, Parakeet and embedding sessions are cached
+// after first use; this module reports what is resident, unloads on request and runs an idle
+// timer (`stt_idle_unload_minutes`, 0 = never) so an idle tray app releases RAM/VRAM.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+static LAST_USED_MS: AtomicU64 = AtomicU64::new(0);
+static STARTED: OnceCell<()> = OnceCell::new();
+
+#[derive(Serialize, Clone)]
+pub struct LoadedModel {
+  /// "whisper" | "parakeet" | "embeddings"
+  pub engine: String,
+  pub model: String,
+  pub cuda: bool,
+  /// Size of the model files on disk; a rough lower bound for the resident size
+  pub file_bytes: u64,
+  pub loaded_at_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct ModelMemoryUsage {
+  pub process_rss_bytes: Option<u64>,
+  pub process_peak_bytes: Option<u64>,
+  pub loaded: Vec<LoadedModel>,
+  pub last_used_ms: u64,
+  pub idle_unload_minutes: u64,
+}
+
+#[derive(Serialize)]
+pub struct UnloadResult {
+  pub unloaded: usize,
+  /// Resident memory released, when measurable
+  pub freed_bytes: Option<u64>,
+}
+
+pub fn now_ms() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// Record that a local model was just used (resets the idle timer).
+#[cfg_attr(not(any(feature = "local-stt", feature = "local-embeddings")), allow(dead_code))]
+pub fn touch() {
+  LAST_USED_MS.store(now_ms(), Ordering::Relaxed);
+}
+
+/// Total size of a model file or of all files in a model folder.
+#[cfg_attr(not(any(feature = "local-stt", feature = "local-embeddings")), allow(dead_code))]
+pub fn size_on_disk(path: &Path) -> u64 {
+  if path.is_file() {
+    return std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+  }
+  std::fs::read_dir(path)
+    .map(|rd| rd.filter_map(|e| e.ok()).map(|e| size_on_disk(&e.path())).sum())
+    .unwrap_or(0)
+}
+
+fn loaded() -> Vec<LoadedModel> {
+  let mut v = crate::stt_whisper::loaded_models();
+  v.extend(crate::stt_parakeet::loaded_models());
+  v.extend(crate::embeddings::loaded_models());
+  v
+}
+
+fn unload_all() -> UnloadResult {
+  let before = crate::utils::process_memory().map(|m| m.0);
+  let unloaded = crate::stt_whisper::unload_models() + crate::stt_parakeet::unload_models() + crate::embeddings::unload_models();
+  let after = crate::utils::process_memory().map(|m| m.0);
+  if unloaded > 0 {
+    tracing::info!(unloaded, "unloaded local models");
+  }
+  UnloadResult {
+    unloaded,
+    freed_bytes: match (before, after) {
+      (Some(b), Some(a)) => Some(b.saturating_sub(a)),
+      _ => None,
+    },
+  }
+}
+
+/// Start the idle-unload timer. Called once from setup.
+pub fn start_idle_unload() {
+  if STARTED.set(()).is_err() { return; }
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+      let minutes = crate::config::get_stt_idle_unload_minutes_from_settings();
+      if minutes == 0 { continue; }
+      let idle_ms = now_ms().saturating_sub(LAST_USED_MS.load(Ordering::Relaxed));
+      if idle_ms >= minutes * 60_000 && !loaded().is_empty() {
+        // Model teardown can block for a moment (CUDA contexts)
+        let _ = tokio::task::spawn_blocking(unload_all).await;
+      }
+    }
+  });
+}
+
+#[tauri::command]
+pub fn get_model_memory_usage() -> Result<ModelMemoryUsage, String> {
+  let mem = crate::utils::process_memory();
+  Ok(ModelMemoryUsage {
+    process_rss_bytes: mem.map(|m| m.0),
+    process_peak_bytes: mem.map(|m| m.1),
+    loaded: loaded(),
+    last_used_ms: LAST_USED_MS.load(Ordering::Relaxed),
+    idle_unload_minutes: crate::config::get_stt_idle_unload_minutes_from_settings(),
+  })
+}
+
+#[tauri::command]
+pub async fn unload_local_models() -> Result<UnloadResult, String> {
+  tokio::task::spawn_blocking(unload_all).await.map_err(|e| format!("unload failed: {e}"))
+}
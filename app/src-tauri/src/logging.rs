@@ -0,0 +1,167 @@
+// Structured file logging: tracing with a daily rolling file under <app dir>/logs.
+// `log::` macros are bridged into tracing, so existing call sites land in the same files.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use once_cell::sync::OnceCell;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+const LOG_FILE_PREFIX: &str = "app";
+const LOG_FILE_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+const DEFAULT_RECENT_LINES: usize = 500;
+const MAX_RECENT_LINES: usize = 5000;
+
+// Keeps the non-blocking writer flushing for the lifetime of the process
+static FILE_GUARD: OnceCell<WorkerGuard> = OnceCell::new();
+static LEVEL_HANDLE: OnceCell<reload::Handle<LevelFilter, Registry>> = OnceCell::new();
+
+pub fn logs_dir() -> Option<PathBuf> {
+  #[cfg(target_os = "windows")]
+  {
+    if let Ok(appdata) = std::env::var("APPDATA") {
+      let mut p = PathBuf::from(appdata);
+      p.push("AiDesktopCompanion");
+      p.push("logs");
+      return Some(p);
+    }
+    None
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    if let Ok(home) = std::env::var("HOME") {
+      let mut p = PathBuf::from(home);
+      p.push(".config");
+      p.push("AiDesktopCompanion");
+      p.push("logs");
+      return Some(p);
+    }
+    None
+  }
+}
+
+fn parse_level(level: &str) -> LevelFilter {
+  match level.trim().to_lowercase().as_str() {
+    "off" => LevelFilter::OFF,
+    "error" => LevelFilter::ERROR,
+    "warn" => LevelFilter::WARN,
+    "debug" => LevelFilter::DEBUG,
+    "trace" => LevelFilter::TRACE,
+    _ => LevelFilter::INFO,
+  }
+}
+
+/// Install the global subscriber: rolling file output (when the logs directory is writable)
+/// plus stdout in debug builds. Safe to call more than once; later calls are no-ops.
+pub fn init() {
+  if LEVEL_HANDLE.get().is_some() { return; }
+  let (filter, handle) = reload::Layer::new(parse_level(&crate::config::get_log_level_from_settings()));
+
+  let file_layer = logs_dir()
+    .filter(|dir| fs::create_dir_all(dir).is_ok())
+    .and_then(|dir| {
+      RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)
+        .ok()
+    })
+    .map(|appender| {
+      let (writer, guard) = tracing_appender::non_blocking(appender);
+      let _ = FILE_GUARD.set(guard);
+      fmt::layer().with_writer(writer).with_ansi(false).with_target(true)
+    });
+  let stdout_layer = if cfg!(debug_assertions) { Some(fmt::layer()) } else { None };
+
+  if tracing_subscriber::registry().with(filter).with(file_layer).with(stdout_layer).try_init().is_ok() {
+    let _ = LEVEL_HANDLE.set(handle);
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "logging initialized");
+  }
+}
+
+/// Re-read `log_level` from settings and apply it without a restart.
+pub fn apply_log_level_from_settings() {
+  if let Some(handle) = LEVEL_HANDLE.get() {
+    let _ = handle.modify(|f| *f = parse_level(&crate::config::get_log_level_from_settings()));
+  }
+}
+
+// Log files of this app, newest first (names embed the date, so a name sort is chronological)
+fn log_files_newest_first() -> Vec<PathBuf> {
+  let Some(dir) = logs_dir() else { return Vec::new() };
+  let mut files: Vec<PathBuf> = fs::read_dir(dir)
+    .map(|rd| {
+      rd.filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+          p.file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.starts_with(&format!("{LOG_FILE_PREFIX}.")) && n.ends_with(&format!(".{LOG_FILE_SUFFIX}")))
+            .unwrap_or(false)
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  files.sort();
+  files.reverse();
+  files
+}
+
+/// Return the last `max_lines` log lines (default 500) across the most recent log files.
+#[tauri::command]
+pub fn get_recent_logs(max_lines: Option<usize>) -> Result<String, String> {
+  let want = max_lines.unwrap_or(DEFAULT_RECENT_LINES).clamp(1, MAX_RECENT_LINES);
+  let mut chunks: Vec<String> = Vec::new();
+  let mut collected = 0usize;
+  for path in log_files_newest_first() {
+    if collected >= want { break; }
+    let content = match fs::read_to_string(&path) {
+      Ok(c) => c,
+      Err(_) => continue,
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let take = (want - collected).min(lines.len());
+    chunks.push(lines[lines.len() - take..].join("\n"));
+    collected += take;
+  }
+  chunks.reverse();
+  Ok(chunks.join("\n"))
+}
+
+#[tauri::command]
+pub fn open_log_folder() -> Result<(), String> {
+  let dir = logs_dir().ok_or_else(|| "Could not resolve logs directory".to_string())?;
+  fs::create_dir_all(&dir).map_err(|e| format!("Failed to create logs directory: {e}"))?;
+  #[cfg(target_os = "windows")]
+  {
+    Command::new("explorer.exe")
+      .arg(dir)
+      .spawn()
+      .map_err(|e| format!("Failed to open logs folder: {e}"))?;
+    Ok(())
+  }
+  #[cfg(target_os = "macos")]
+  {
+    Command::new("open")
+      .arg(dir)
+      .spawn()
+      .map_err(|e| format!("Failed to open logs folder: {e}"))?;
+    Ok(())
+  }
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  {
+    Command::new("xdg-open")
+      .arg(dir)
+      .spawn()
+      .map_err(|e| format!("Failed to open logs folder: {e}"))?;
+    Ok(())
+  }
+}
@@ -0,0 +1,446 @@
+// Insertion macros: recorded sequences of focus changes, pastes and keystrokes that put
+// AI-generated content into multi-field targets (web forms, CRM dialogs). While recording, a
+// low-level keyboard hook (Windows) turns the user's input into steps until Escape is pressed:
+// the first key in a new window records a focus step, Ctrl+V / Shift+Insert a paste step for the
+// next content slot, typed characters a text step and other keys (Tab, Enter, arrows, shortcuts)
+// key steps. Pauses longer than half a second are kept as waits so slow forms can catch up.
+// Typed characters may be a password, so they are only recorded with `record_text` and are
+// stored in plain text then; otherwise they are dropped and the content goes into paste slots.
+// Playback inserts `contents[slot]` for each paste step through the regular insertion path.
+//
+// Macros live in macros.json next to settings.json.
+// Events: macro:step { name, step } for each recorded step, with typed text masked
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::error::AidcError;
+
+const DEFAULT_RECORD_SECS: u64 = 300;
+const MAX_NAME_CHARS: usize = 64;
+// Pause between played steps so the target UI can move focus
+const STEP_DELAY_MS: u64 = 60;
+const FOCUS_SETTLE_MS: u64 = 150;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MacroStep {
+  /// Bring a window to the front; matched by exact title, then by process name
+  Focus { title: String, process: String },
+  /// Insert content slot `slot` (0-based)
+  Paste { slot: usize },
+  /// Virtual-key code with the modifiers held ("ctrl", "alt", "shift", "win")
+  Key {
+    vk: u16,
+    #[serde(default)]
+    modifiers: Vec<String>,
+  },
+  /// Literal text typed while recording
+  Text { text: String },
+  Wait { ms: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InsertionMacro {
+  pub name: String,
+  pub steps: Vec<MacroStep>,
+  /// Number of content slots playback expects
+  pub slots: usize,
+  pub created_ms: u64,
+}
+
+fn macros_config_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name("macros.json"))
+}
+
+fn load_macros() -> Vec<InsertionMacro> {
+  macros_config_path()
+    .and_then(|p| fs::read_to_string(p).ok())
+    .and_then(|text| serde_json::from_str::<Vec<InsertionMacro>>(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_macros(list: &[InsertionMacro]) -> Result<(), String> {
+  let path = macros_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  let pretty = serde_json::to_string_pretty(list).map_err(|e| format!("Serialize macros failed: {e}"))?;
+  crate::config::write_json_atomic_blocking(&path, &pretty)
+}
+
+#[cfg(target_os = "windows")]
+mod recorder {
+  use std::sync::atomic::{AtomicU32, Ordering};
+  use std::sync::Mutex;
+  use std::time::{Duration, Instant};
+
+  use once_cell::sync::Lazy;
+  use tokio::sync::oneshot;
+  use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+  use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+  use windows::Win32::System::Threading::{GetCurrentProcessId, GetCurrentThreadId};
+  use windows::Win32::UI::Input::KeyboardAndMouse::{
+    VIRTUAL_KEY, VK_CAPITAL, VK_CONTROL, VK_ESCAPE, VK_INSERT, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU, VK_RCONTROL, VK_RMENU,
+    VK_RSHIFT, VK_RWIN, VK_SHIFT,
+  };
+  use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, DispatchMessageW, GetForegroundWindow, GetMessageW, GetWindowThreadProcessId, PostThreadMessageW, SetWindowsHookExW,
+    TranslateMessage, UnhookWindowsHookEx, HHOOK, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG, WH_KEYBOARD_LL, WM_KEYDOWN, WM_QUIT, WM_SYSKEYDOWN,
+  };
+
+  use super::MacroStep;
+  use crate::hotstrings::hook::{key_char, pressed};
+
+  const MIN_WAIT: Duration = Duration::from_millis(500);
+  const MAX_WAIT: Duration = Duration::from_secs(3);
+  const MODIFIER_KEYS: [VIRTUAL_KEY; 12] =
+    [VK_SHIFT, VK_LSHIFT, VK_RSHIFT, VK_CONTROL, VK_LCONTROL, VK_RCONTROL, VK_MENU, VK_LMENU, VK_RMENU, VK_LWIN, VK_RWIN, VK_CAPITAL];
+
+  struct Recording {
+    name: String,
+    steps: Vec<MacroStep>,
+    window: isize,
+    slots: usize,
+    record_text: bool,
+    last_at: Instant,
+    done: Option<oneshot::Sender<()>>,
+  }
+
+  static RECORDING: Lazy<Mutex<Option<Recording>>> = Lazy::new(|| Mutex::new(None));
+  static HOOK_THREAD: AtomicU32 = AtomicU32::new(0);
+
+  fn modifiers() -> Vec<String> {
+    let mut out = Vec::new();
+    if pressed(VK_CONTROL) { out.push("ctrl".to_string()); }
+    if pressed(VK_MENU) { out.push("alt".to_string()); }
+    if pressed(VK_SHIFT) { out.push("shift".to_string()); }
+    if pressed(VK_LWIN) || pressed(VK_RWIN) { out.push("win".to_string()); }
+    out
+  }
+
+  fn push(rec: &mut Recording, step: MacroStep) {
+    if let Some(app) = super::APP.get() {
+      use tauri::Emitter;
+      let shown = match &step {
+        MacroStep::Text { text } => MacroStep::Text { text: "•".repeat(text.chars().count()) },
+        other => other.clone(),
+      };
+      let _ = app.emit("macro:step", serde_json::json!({ "name": rec.name, "step": shown }));
+    }
+    rec.steps.push(step);
+  }
+
+  unsafe extern "system" fn proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let msg = wparam.0 as u32;
+    if code >= 0 && (msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN) {
+      let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+      if info.flags.0 & LLKHF_INJECTED.0 == 0 {
+        handle_key(info.vkCode, info.scanCode);
+      }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+  }
+
+  fn handle_key(vk: u32, scan: u32) {
+    let Ok(mut guard) = RECORDING.lock() else { return };
+    let Some(rec) = guard.as_mut() else { return };
+    if MODIFIER_KEYS.iter().any(|k| k.0 as u32 == vk) {
+      return;
+    }
+    let mods = modifiers();
+    if vk == VK_ESCAPE.0 as u32 && mods.is_empty() {
+      if let Some(done) = rec.done.take() {
+        let _ = done.send(());
+      }
+      return;
+    }
+
+    // Keys pressed in our own windows (e.g. the recording controls) are not part of the macro
+    let hwnd = unsafe { GetForegroundWindow() };
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    if pid == unsafe { GetCurrentProcessId() } {
+      return;
+    }
+    let window = hwnd.0 as isize;
+    let gap = rec.last_at.elapsed();
+    rec.last_at = Instant::now();
+    if window != rec.window {
+      rec.window = window;
+      let title = crate::hotstrings::window_title(window);
+      let process = crate::quick_actions::process_name_for_window(window);
+      push(rec, MacroStep::Focus { title, process });
+    } else if gap >= MIN_WAIT && !rec.steps.is_empty() {
+      push(rec, MacroStep::Wait { ms: gap.min(MAX_WAIT).as_millis() as u64 });
+    }
+
+    let ctrl_v = mods == ["ctrl"] && vk == 'V' as u32;
+    let shift_ins = mods == ["shift"] && vk == VK_INSERT.0 as u32;
+    if ctrl_v || shift_ins {
+      let slot = rec.slots;
+      rec.slots += 1;
+      push(rec, MacroStep::Paste { slot });
+      return;
+    }
+    // AltGr (Ctrl+Alt) still produces characters
+    let altgr = pressed(VK_RMENU) && pressed(VK_LCONTROL);
+    let shortcut = mods.iter().any(|m| m == "win") || (!altgr && mods.iter().any(|m| m == "ctrl" || m == "alt"));
+    if !shortcut {
+      if let Some(c) = key_char(vk, scan) {
+        if !rec.record_text {
+          return;
+        }
+        if let Some(MacroStep::Text { text }) = rec.steps.last_mut() {
+          text.push(c);
+        } else {
+          push(rec, MacroStep::Text { text: c.to_string() });
+        }
+        return;
+      }
+    }
+    push(rec, MacroStep::Key { vk: vk as u16, modifiers: mods });
+  }
+
+  pub fn start(name: &str, record_text: bool, done: oneshot::Sender<()>) -> Result<(), String> {
+    {
+      let mut guard = RECORDING.lock().unwrap_or_else(|e| e.into_inner());
+      if guard.is_some() {
+        return Err("A macro is already being recorded".into());
+      }
+      *guard = Some(Recording {
+        name: name.to_string(),
+        steps: Vec::new(),
+        window: 0,
+        slots: 0,
+        record_text,
+        last_at: Instant::now(),
+        done: Some(done),
+      });
+    }
+    std::thread::spawn(|| unsafe {
+      let module = GetModuleHandleW(None).map(HINSTANCE::from).unwrap_or_default();
+      let hook = match SetWindowsHookExW(WH_KEYBOARD_LL, Some(proc), module, 0) {
+        Ok(h) => h,
+        Err(e) => {
+          tracing::warn!(error = %e, "macro keyboard hook failed");
+          if let Some(done) = RECORDING.lock().ok().and_then(|mut g| g.as_mut().and_then(|r| r.done.take())) {
+            let _ = done.send(());
+          }
+          return;
+        }
+      };
+      HOOK_THREAD.store(GetCurrentThreadId(), Ordering::SeqCst);
+      // Stopped before the thread id was published; nobody will post WM_QUIT
+      let stopped = RECORDING.lock().map(|g| g.is_none()).unwrap_or(true);
+      let mut msg = MSG::default();
+      while !stopped && GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+      }
+      let _ = UnhookWindowsHookEx(hook);
+      HOOK_THREAD.store(0, Ordering::SeqCst);
+    });
+    Ok(())
+  }
+
+  /// Remove the hook and return the recorded steps and slot count.
+  pub fn stop() -> (Vec<MacroStep>, usize) {
+    let tid = HOOK_THREAD.load(Ordering::SeqCst);
+    if tid != 0 {
+      let _ = unsafe { PostThreadMessageW(tid, WM_QUIT, WPARAM(0), LPARAM(0)) };
+    }
+    let rec = RECORDING.lock().unwrap_or_else(|e| e.into_inner()).take();
+    rec.map(|r| (r.steps, r.slots)).unwrap_or_default()
+  }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod recorder {
+  use super::MacroStep;
+
+  pub fn start(_name: &str, _record_text: bool, _done: tokio::sync::oneshot::Sender<()>) -> Result<(), String> {
+    Err("Macro recording is only supported on Windows".into())
+  }
+
+  pub fn stop() -> (Vec<MacroStep>, usize) {
+    (Vec::new(), 0)
+  }
+}
+
+static APP: once_cell::sync::OnceCell<tauri::AppHandle> = once_cell::sync::OnceCell::new();
+
+#[cfg(target_os = "windows")]
+fn find_window(title: &str, process: &str) -> Option<isize> {
+  use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+  use windows::Win32::UI::WindowsAndMessaging::{EnumWindows, IsWindowVisible};
+
+  unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let list = &mut *(lparam.0 as *mut Vec<isize>);
+    if IsWindowVisible(hwnd).as_bool() {
+      list.push(hwnd.0 as isize);
+    }
+    BOOL(1)
+  }
+  let mut visible: Vec<isize> = Vec::new();
+  unsafe {
+    let _ = EnumWindows(Some(collect), LPARAM(&mut visible as *mut Vec<isize> as isize));
+  }
+  let same_process = |h: isize| process.is_empty() || crate::quick_actions::process_name_for_window(h).eq_ignore_ascii_case(process);
+  // Titles often change (unsaved marker, page title); fall back to any window of the process
+  visible
+    .iter()
+    .copied()
+    .find(|&h| crate::hotstrings::window_title(h) == title && same_process(h))
+    .or_else(|| visible.iter().copied().find(|&h| !process.is_empty() && same_process(h) && !crate::hotstrings::window_title(h).is_empty()))
+}
+
+#[cfg(target_os = "windows")]
+fn focus(title: &str, process: &str) -> Result<(), String> {
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+  let hraw = find_window(title, process).ok_or_else(|| format!("Window '{title}' ({process}) not found"))?;
+  unsafe {
+    let _ = SetForegroundWindow(HWND(hraw as *mut std::ffi::c_void));
+  }
+  Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn focus(_title: &str, _process: &str) -> Result<(), String> {
+  Err("Window focus steps are only supported on Windows".into())
+}
+
+fn press(vk: u16, modifiers: &[String]) {
+  use enigo::{Enigo, Key, KeyboardControllable};
+  let mut enigo = Enigo::new();
+  let held: Vec<Key> = modifiers
+    .iter()
+    .filter_map(|m| match m.as_str() {
+      "ctrl" => Some(Key::Control),
+      "alt" => Some(Key::Alt),
+      "shift" => Some(Key::Shift),
+      "win" => Some(Key::Meta),
+      _ => None,
+    })
+    .collect();
+  for k in &held {
+    enigo.key_down(*k);
+  }
+  enigo.key_click(Key::Raw(vk));
+  for k in held.iter().rev() {
+    enigo.key_up(*k);
+  }
+}
+
+fn play_steps(steps: &[MacroStep], contents: &[String], safe: bool, cancel: &tokio_util::sync::CancellationToken) -> Result<(), String> {
+  use enigo::{Enigo, KeyboardControllable};
+  for step in steps {
+    if cancel.is_cancelled() {
+      return Err(AidcError::Cancelled.into());
+    }
+    match step {
+      MacroStep::Focus { title, process } => {
+        focus(title, process)?;
+        std::thread::sleep(std::time::Duration::from_millis(FOCUS_SETTLE_MS));
+      }
+      MacroStep::Paste { slot } => {
+        let text = contents.get(*slot).ok_or_else(|| format!("No content for paste #{}", slot + 1))?;
+        crate::quick_actions::insert_text_into_focused_app(text.clone(), Some(safe), None)?;
+      }
+      MacroStep::Key { vk, modifiers } => press(*vk, modifiers),
+      MacroStep::Text { text } => Enigo::new().key_sequence(text),
+      MacroStep::Wait { ms } => std::thread::sleep(std::time::Duration::from_millis(*ms)),
+    }
+    std::thread::sleep(std::time::Duration::from_millis(STEP_DELAY_MS));
+  }
+  Ok(())
+}
+
+pub fn init(app: tauri::AppHandle) {
+  let _ = APP.set(app);
+}
+
+/// Record a macro under `name` (replacing one with the same name) until Escape is pressed or
+/// `timeout_secs` (default 300) pass. Typed text is only kept with `record_text`. Cancelling the
+/// task discards the recording.
+#[tauri::command]
+pub async fn record_insertion_macro(
+  name: String,
+  record_text: Option<bool>,
+  timeout_secs: Option<u64>,
+  task_id: Option<String>,
+) -> Result<InsertionMacro, String> {
+  let name = name.trim().to_string();
+  if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+    return Err(format!("Macro name must be 1-{MAX_NAME_CHARS} characters"));
+  }
+  let task = crate::tasks::register("macro", format!("Recording macro '{name}'"), task_id);
+  let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+  recorder::start(&name, record_text.unwrap_or(false), tx)?;
+  let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_RECORD_SECS).max(1));
+  let waited: Result<(), String> = task
+    .run(async {
+      let _ = tokio::time::timeout(timeout, rx).await;
+      Ok(())
+    })
+    .await;
+  let (steps, slots) = recorder::stop();
+  waited?;
+  if steps.is_empty() {
+    return Err("Nothing was recorded".into());
+  }
+
+  let recorded = InsertionMacro { name: name.clone(), steps, slots, created_ms: crate::local_models::now_ms() };
+  let mut list = load_macros();
+  list.retain(|m| m.name != name);
+  list.push(recorded.clone());
+  write_macros(&list)?;
+  tracing::info!(name = %name, steps = recorded.steps.len(), slots, "insertion macro recorded");
+  Ok(recorded)
+}
+
+/// Replay macro `name`; paste step N inserts `contents[N]`. Runs as a cancellable task.
+#[tauri::command]
+pub async fn play_insertion_macro(
+  app: tauri::AppHandle,
+  name: String,
+  contents: Vec<String>,
+  safe_mode: Option<bool>,
+  task_id: Option<String>,
+) -> Result<(), String> {
+  let m = load_macros().into_iter().find(|m| m.name == name).ok_or_else(|| format!("Macro '{name}' not found"))?;
+  if contents.len() < m.slots {
+    return Err(format!("Macro '{name}' needs {} content item(s), got {}", m.slots, contents.len()));
+  }
+  let task = crate::tasks::register("macro", format!("Playing macro '{name}'"), task_id);
+  let cancel = task.token();
+  let safe = safe_mode.unwrap_or(false);
+  let steps = m.steps;
+  task
+    .run(async move {
+      tokio::task::spawn_blocking(move || play_steps(&steps, &contents, safe, &cancel))
+        .await
+        .map_err(|e| format!("spawn_blocking failed: {e}"))?
+    })
+    .await?;
+  let _ = app.emit("macro:played", serde_json::json!({ "name": name }));
+  Ok(())
+}
+
+#[tauri::command]
+pub fn get_insertion_macros() -> Result<Vec<InsertionMacro>, String> {
+  Ok(load_macros())
+}
+
+#[tauri::command]
+pub fn delete_insertion_macro(name: String) -> Result<bool, String> {
+  let mut list = load_macros();
+  let before = list.len();
+  list.retain(|m| m.name != name);
+  if list.len() == before {
+    return Ok(false);
+  }
+  write_macros(&list)?;
+  Ok(true)
+}
@@ -1,13 +1,18 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex as AsyncMutex;
 use rmcp::service::{RunningService, RoleClient, DynService};
 use rmcp::service::ServiceExt;
-use rmcp::transport::{TokioChildProcess, streamable_http_client::StreamableHttpClientTransport};
+use rmcp::transport::{TokioChildProcess, SseClientTransport, sse_client::SseClientConfig};
+use rmcp::transport::streamable_http_client::{StreamableHttpClientTransport, StreamableHttpClientTransportConfig};
 use tokio::process::Command as TokioCommand;
 use tauri::Emitter;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+
+use crate::error::AidcError;
 
 /// Reverse lookup: sanitized fn_name → (original_server_id, original_tool_name)
 /// Populated by `build_openai_tools_from_mcp`, consumed by `parse_mcp_fn_call_name`.
@@ -40,6 +45,255 @@ pub fn resolve_windows_program(prog: &str, cwd: Option<&str>) -> Option<String>
   None
 }
 
+// ---------------------------
+// Health supervision
+// ---------------------------
+// Every server connected through `connect` is pinged (list_tools) by a background task. A stdio
+// server whose transport fails, or that misses two pings in a row, is dropped from the clients
+// map and started again with exponential backoff; HTTP servers are only reported. Each check
+// emits `mcp:health` { serverId, status: "healthy"|"unhealthy"|"reconnecting"|"reconnected",
+// latencyMs?, error?, retryInSecs?, restarts }. `disconnect` ends supervision of that server.
+
+const SUPERVISE_TICK: Duration = Duration::from_secs(2);
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+const PING_TIMEOUT: Duration = Duration::from_secs(15);
+const MISSED_PINGS_BEFORE_RESTART: u32 = 2;
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+// What `connect` was called with, to start the server again
+#[derive(Clone)]
+struct ConnectSpec {
+  command: String,
+  args: Vec<String>,
+  cwd: Option<String>,
+  env: Option<serde_json::Value>,
+  headers: Option<serde_json::Value>,
+  transport: String,
+}
+
+struct Health {
+  spec: ConnectSpec,
+  connected_at: Option<(Instant, i64)>,
+  last_check: Option<Instant>,
+  last_ok_ms: Option<i64>,
+  latency_ms: Option<u64>,
+  last_error: Option<(String, i64)>,
+  /// Consecutive failed pings or reconnect attempts
+  failures: u32,
+  restarts: u32,
+  retry_at: Option<Instant>,
+}
+
+static HEALTH: Lazy<StdMutex<std::collections::HashMap<String, Health>>> = Lazy::new(|| StdMutex::new(std::collections::HashMap::new()));
+static SUPERVISOR: OnceCell<()> = OnceCell::new();
+
+/// Reported by `mcp_status`.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct McpStatus {
+  pub server_id: String,
+  pub connected: bool,
+  /// Whether the supervisor watches (and restarts) this server
+  pub supervised: bool,
+  pub transport: Option<String>,
+  pub uptime_secs: Option<u64>,
+  /// Unix times in milliseconds
+  pub connected_at: Option<i64>,
+  pub last_ok_at: Option<i64>,
+  pub latency_ms: Option<u64>,
+  pub last_error: Option<String>,
+  pub last_error_at: Option<i64>,
+  pub consecutive_failures: u32,
+  pub restarts: u32,
+  pub retry_in_secs: Option<u64>,
+}
+
+fn now_ms() -> i64 {
+  chrono::Utc::now().timestamp_millis()
+}
+
+fn backoff(failures: u32) -> Duration {
+  BACKOFF_BASE.saturating_mul(1u32 << failures.saturating_sub(1).min(16)).min(BACKOFF_MAX)
+}
+
+fn health_lock() -> std::sync::MutexGuard<'static, std::collections::HashMap<String, Health>> {
+  HEALTH.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn emit_health(app: &tauri::AppHandle, server_id: &str, status: &str, extra: serde_json::Value) {
+  let restarts = health_lock().get(server_id).map(|h| h.restarts).unwrap_or(0);
+  let mut payload = serde_json::json!({ "serverId": server_id, "status": status, "restarts": restarts });
+  if let (Some(obj), Some(more)) = (payload.as_object_mut(), extra.as_object()) {
+    obj.extend(more.clone());
+  }
+  let _ = app.emit("mcp:health", payload);
+}
+
+// Start (or restart) supervision after a successful connect; restart counts survive reconnects
+fn record_connected(server_id: &str, spec: ConnectSpec) {
+  let mut map = health_lock();
+  let restarts = map.get(server_id).map(|h| h.restarts).unwrap_or(0);
+  map.insert(server_id.to_string(), Health {
+    spec,
+    connected_at: Some((Instant::now(), now_ms())),
+    last_check: Some(Instant::now()),
+    last_ok_ms: Some(now_ms()),
+    latency_ms: None,
+    last_error: None,
+    failures: 0,
+    restarts,
+    retry_at: None,
+  });
+}
+
+/// Start the health supervisor over `clients`. Called once from setup.
+pub fn start_supervisor(app: tauri::AppHandle, clients: &'static AsyncMutex<ClientMap>) {
+  if SUPERVISOR.set(()).is_err() { return; }
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(SUPERVISE_TICK).await;
+      let ids: Vec<String> = health_lock().keys().cloned().collect();
+      for id in ids {
+        let svc = clients.lock().await.get(&id).cloned();
+        match svc {
+          Some(svc) => check_server(&app, clients, &id, svc).await,
+          None => retry_server(&app, clients, &id).await,
+        }
+      }
+    }
+  });
+}
+
+async fn check_server(app: &tauri::AppHandle, clients: &AsyncMutex<ClientMap>, server_id: &str, svc: Arc<RunningService<RoleClient, Box<dyn DynService<RoleClient>>>>) {
+  let due = health_lock().get(server_id).is_some_and(|h| h.last_check.is_none_or(|t| t.elapsed() >= PING_INTERVAL));
+  if !due { return; }
+  let started = Instant::now();
+  let (error, transport_failed) = match tokio::time::timeout(PING_TIMEOUT, svc.list_tools(Default::default())).await {
+    Ok(Ok(_)) => (None, false),
+    Ok(Err(e)) => (Some(format!("ping failed: {e}")), true),
+    Err(_) => (Some(format!("no answer to ping within {} s", PING_TIMEOUT.as_secs())), false),
+  };
+  let Some(error) = error else {
+    let latency = started.elapsed().as_millis() as u64;
+    if let Some(h) = health_lock().get_mut(server_id) {
+      h.last_check = Some(Instant::now());
+      h.last_ok_ms = Some(now_ms());
+      h.latency_ms = Some(latency);
+      h.failures = 0;
+    }
+    emit_health(app, server_id, "healthy", serde_json::json!({ "latencyMs": latency }));
+    return;
+  };
+  let restart = {
+    let mut map = health_lock();
+    let Some(h) = map.get_mut(server_id) else { return };
+    h.last_check = Some(Instant::now());
+    h.failures += 1;
+    h.last_error = Some((error.clone(), now_ms()));
+    let restart = h.spec.transport == "stdio" && (transport_failed || h.failures >= MISSED_PINGS_BEFORE_RESTART);
+    if restart {
+      h.connected_at = None;
+      h.retry_at = Some(Instant::now() + backoff(1));
+    }
+    restart
+  };
+  tracing::warn!(server = %server_id, %error, restart, "MCP server health check failed");
+  if !restart {
+    emit_health(app, server_id, "unhealthy", serde_json::json!({ "error": error }));
+    return;
+  }
+  // Drop the dead handle so callers see "not connected" instead of hanging on it
+  if let Some(old) = clients.lock().await.remove(server_id) {
+    old.cancellation_token().cancel();
+  }
+  emit_health(app, server_id, "reconnecting", serde_json::json!({ "error": error, "retryInSecs": backoff(1).as_secs() }));
+}
+
+async fn retry_server(app: &tauri::AppHandle, clients: &AsyncMutex<ClientMap>, server_id: &str) {
+  let spec = {
+    let map = health_lock();
+    match map.get(server_id) {
+      Some(h) if h.retry_at.is_some_and(|t| Instant::now() >= t) => h.spec.clone(),
+      _ => return,
+    }
+  };
+  let res = connect(app, clients, server_id.to_string(), spec.command, spec.args, spec.cwd, spec.env, spec.headers, Some(spec.transport)).await;
+  match res {
+    Ok(_) => {
+      if let Some(h) = health_lock().get_mut(server_id) { h.restarts += 1; }
+      tracing::info!(server = %server_id, "MCP server reconnected");
+      emit_health(app, server_id, "reconnected", serde_json::json!({}));
+    }
+    Err(e) => {
+      let error = e.message();
+      let wait = {
+        let mut map = health_lock();
+        let Some(h) = map.get_mut(server_id) else { return };
+        h.failures += 1;
+        h.last_error = Some((error.clone(), now_ms()));
+        let wait = backoff(h.failures);
+        h.retry_at = Some(Instant::now() + wait);
+        wait
+      };
+      tracing::warn!(server = %server_id, %error, retry_in_secs = wait.as_secs(), "MCP server reconnect failed");
+      emit_health(app, server_id, "reconnecting", serde_json::json!({ "error": error, "retryInSecs": wait.as_secs() }));
+    }
+  }
+}
+
+pub async fn status(clients: &AsyncMutex<ClientMap>, server_id: &str) -> McpStatus {
+  let connected = clients.lock().await.contains_key(server_id);
+  let map = health_lock();
+  let h = map.get(server_id);
+  McpStatus {
+    server_id: server_id.to_string(),
+    connected,
+    supervised: h.is_some(),
+    transport: h.map(|h| h.spec.transport.clone()),
+    uptime_secs: h.and_then(|h| h.connected_at).filter(|_| connected).map(|(t, _)| t.elapsed().as_secs()),
+    connected_at: h.and_then(|h| h.connected_at).map(|(_, ms)| ms),
+    last_ok_at: h.and_then(|h| h.last_ok_ms),
+    latency_ms: h.and_then(|h| h.latency_ms),
+    last_error: h.and_then(|h| h.last_error.as_ref()).map(|(e, _)| e.clone()),
+    last_error_at: h.and_then(|h| h.last_error.as_ref()).map(|(_, ms)| *ms),
+    consecutive_failures: h.map(|h| h.failures).unwrap_or(0),
+    restarts: h.map(|h| h.restarts).unwrap_or(0),
+    retry_in_secs: h.and_then(|h| h.retry_at).map(|t| t.saturating_duration_since(Instant::now()).as_secs()),
+  }
+}
+
+fn mcp_provider(server_id: &str) -> String {
+  format!("mcp:{server_id}")
+}
+
+fn not_connected(server_id: &str) -> AidcError {
+  AidcError::NotConnected { provider: mcp_provider(server_id) }
+}
+
+fn tool_failed(server_id: &str, message: String) -> AidcError {
+  AidcError::ToolFailed { provider: mcp_provider(server_id), message }
+}
+
+// HTTP client for the remote transports, sending `headers` ({"Authorization": "Bearer …"}) with every request
+fn remote_client(headers: Option<&serde_json::Value>) -> Result<reqwest::Client, AidcError> {
+  use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+  let mut map = HeaderMap::new();
+  for (k, v) in headers.and_then(|h| h.as_object()).into_iter().flatten() {
+    let Some(v) = v.as_str() else { continue };
+    let name = HeaderName::from_bytes(k.trim().as_bytes()).map_err(|e| AidcError::InvalidInput(format!("Invalid header name '{k}': {e}")))?;
+    let mut value = HeaderValue::from_str(v.trim()).map_err(|e| AidcError::InvalidInput(format!("Invalid value for header '{k}': {e}")))?;
+    value.set_sensitive(true);
+    map.insert(name, value);
+  }
+  reqwest::Client::builder()
+    .default_headers(map)
+    .connect_timeout(Duration::from_secs(10))
+    .build()
+    .map_err(|e| AidcError::Internal(format!("HTTP client setup failed: {e}")))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn connect(
   app: &tauri::AppHandle,
   clients: &AsyncMutex<ClientMap>,
@@ -48,8 +302,9 @@ pub async fn connect(
   args: Vec<String>,
   cwd: Option<String>,
   env: Option<serde_json::Value>,
+  headers: Option<serde_json::Value>,
   transport: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, AidcError> {
   // fast path: already connected
   {
     let map = clients.lock().await;
@@ -59,20 +314,32 @@ pub async fn connect(
   }
 
   let transport_kind = transport.unwrap_or_else(|| "stdio".to_string());
-  if transport_kind == "http" {
+  let spec = ConnectSpec { command: command.clone(), args: args.clone(), cwd: cwd.clone(), env: env.clone(), headers: headers.clone(), transport: transport_kind.clone() };
+  // Remote servers: streamable HTTP ("http") or the older HTTP+SSE protocol ("sse", URL of the /sse endpoint)
+  if transport_kind == "http" || transport_kind == "sse" {
     let uri = command.trim().to_string();
-    if uri.is_empty() { return Err("HTTP transport requires a non-empty URI in 'command'".into()); }
-    let http_transport = StreamableHttpClientTransport::<reqwest::Client>::from_uri(uri);
-    let service = ().into_dyn().serve(http_transport).await.map_err(|e| {
+    if uri.is_empty() { return Err(AidcError::InvalidInput(format!("{} transport requires a non-empty URI in 'command'", transport_kind.to_uppercase()))); }
+    let client = remote_client(headers.as_ref())?;
+    let served = if transport_kind == "sse" {
+      let sse_transport = SseClientTransport::start_with_client(client, SseClientConfig { sse_endpoint: uri.into(), ..Default::default() })
+        .await
+        .map_err(|e| tool_failed(&server_id, format!("SSE connect failed: {e}")))?;
+      ().into_dyn().serve(sse_transport).await.map_err(|e| e.to_string())
+    } else {
+      let http_transport = StreamableHttpClientTransport::with_client(client, StreamableHttpClientTransportConfig::with_uri(uri));
+      ().into_dyn().serve(http_transport).await.map_err(|e| e.to_string())
+    };
+    let service = served.map_err(|e| {
       let msg = format!("serve failed: {e}");
       let _ = app.emit("mcp:error", serde_json::json!({ "serverId": server_id, "message": msg }));
-      msg
+      tool_failed(&server_id, msg)
     })?;
     let service = Arc::new(service);
     {
       let mut map = clients.lock().await;
       map.insert(server_id.clone(), service.clone());
     }
+    record_connected(&server_id, spec);
     let _ = app.emit("mcp:connected", serde_json::json!({ "serverId": server_id }));
     return Ok("connected".into());
   }
@@ -91,71 +358,90 @@ pub async fn connect(
       for (k, v) in obj.iter() { if let Some(s) = v.as_str() { cmd.env(k, s); } }
     }
   }
-  let child_transport = TokioChildProcess::new(cmd).map_err(|e| format!("spawn failed: {e}"))?;
+  let child_transport = TokioChildProcess::new(cmd).map_err(|e| tool_failed(&server_id, format!("spawn failed: {e}")))?;
   let service = ().into_dyn().serve(child_transport).await.map_err(|e| {
     let msg = format!("serve failed: {e}");
     let _ = app.emit("mcp:error", serde_json::json!({ "serverId": server_id, "message": msg }));
-    msg
+    tool_failed(&server_id, msg)
   })?;
   let service = Arc::new(service);
   {
     let mut map = clients.lock().await;
     map.insert(server_id.clone(), service.clone());
   }
+  record_connected(&server_id, spec);
   let _ = app.emit("mcp:connected", serde_json::json!({ "serverId": server_id }));
   Ok("connected".into())
 }
 
-pub async fn disconnect(app: &tauri::AppHandle, clients: &AsyncMutex<ClientMap>, server_id: String) -> Result<String, String> {
+pub async fn disconnect(app: &tauri::AppHandle, clients: &AsyncMutex<ClientMap>, server_id: String) -> Result<String, AidcError> {
+  // A server waiting for a reconnect counts as connected for the user
+  let supervised = health_lock().remove(&server_id).is_some();
   let svc = {
     let mut map = clients.lock().await;
     map.remove(&server_id)
   };
-  let existed = svc.is_some();
+  let existed = svc.is_some() || supervised;
   if let Some(svc) = svc { svc.cancellation_token().cancel(); }
   let _ = app.emit("mcp:disconnected", serde_json::json!({ "serverId": server_id, "existed": existed }));
-  if existed { Ok("disconnected".into()) } else { Err("not connected".into()) }
+  if existed { Ok("disconnected".into()) } else { Err(not_connected(&server_id)) }
+}
+
+/// Cancel every connected server (app shutdown); stdio servers exit when their transport closes.
+pub async fn disconnect_all(clients: &AsyncMutex<ClientMap>) -> usize {
+  health_lock().clear();
+  let services: Vec<_> = clients.lock().await.drain().map(|(_, svc)| svc).collect();
+  for svc in &services { svc.cancellation_token().cancel(); }
+  services.len()
+}
+
+// One request to an MCP server, bounded by timeouts.mcp
+async fn timed<T>(server_id: &str, fut: impl std::future::Future<Output = Result<T, AidcError>>) -> Result<T, AidcError> {
+  crate::timeouts::run(crate::timeouts::MCP, server_id, fut).await
 }
 
 pub type ClientMap = std::collections::HashMap<String, Arc<RunningService<RoleClient, Box<dyn DynService<RoleClient>>>>>;
 
-pub async fn list_tools(clients: &AsyncMutex<ClientMap>, server_id: &str) -> Result<serde_json::Value, String> {
+pub async fn list_tools(clients: &AsyncMutex<ClientMap>, server_id: &str) -> Result<serde_json::Value, AidcError> {
   let svc = {
     let map = clients.lock().await;
     map.get(server_id).cloned()
-  }.ok_or_else(|| "not connected".to_string())?;
-  let res = svc.list_tools(Default::default()).await.map_err(|e| format!("list_tools failed: {e}"))?;
-  serde_json::to_value(res).map_err(|e| format!("serialize failed: {e}"))
+  }.ok_or_else(|| not_connected(server_id))?;
+  let res = timed(server_id, async { svc.list_tools(Default::default()).await.map_err(|e| tool_failed(server_id, format!("list_tools failed: {e}"))) }).await?;
+  serde_json::to_value(res).map_err(|e| AidcError::Internal(format!("serialize failed: {e}")))
 }
 
-pub async fn list_resources(clients: &AsyncMutex<ClientMap>, server_id: &str) -> Result<serde_json::Value, String> {
+pub async fn list_resources(clients: &AsyncMutex<ClientMap>, server_id: &str) -> Result<serde_json::Value, AidcError> {
   let svc = {
     let map = clients.lock().await;
     map.get(server_id).cloned()
-  }.ok_or_else(|| "not connected".to_string())?;
-  let res = svc.list_resources(Default::default()).await.map_err(|e| format!("list_resources failed: {e}"))?;
-  serde_json::to_value(res).map_err(|e| format!("serialize failed: {e}"))
+  }.ok_or_else(|| not_connected(server_id))?;
+  let res = timed(server_id, async { svc.list_resources(Default::default()).await.map_err(|e| tool_failed(server_id, format!("list_resources failed: {e}"))) }).await?;
+  serde_json::to_value(res).map_err(|e| AidcError::Internal(format!("serialize failed: {e}")))
 }
 
-pub async fn read_resource(clients: &AsyncMutex<ClientMap>, server_id: &str, uri: &str) -> Result<serde_json::Value, String> {
+pub async fn read_resource(clients: &AsyncMutex<ClientMap>, server_id: &str, uri: &str) -> Result<serde_json::Value, AidcError> {
   let svc = {
     let map = clients.lock().await;
     map.get(server_id).cloned()
-  }.ok_or_else(|| "not connected".to_string())?;
-  let res = svc
-    .read_resource(rmcp::model::ReadResourceRequestParam { uri: uri.to_string().into() })
-    .await
-    .map_err(|e| format!("read_resource failed: {e}"))?;
-  serde_json::to_value(res).map_err(|e| format!("serialize failed: {e}"))
+  }.ok_or_else(|| not_connected(server_id))?;
+  let res = timed(server_id, async {
+    svc
+      .read_resource(rmcp::model::ReadResourceRequestParam { uri: uri.to_string().into() })
+      .await
+      .map_err(|e| tool_failed(server_id, format!("read_resource failed: {e}")))
+  })
+  .await?;
+  serde_json::to_value(res).map_err(|e| AidcError::Internal(format!("serialize failed: {e}")))
 }
 
-pub async fn list_prompts(clients: &AsyncMutex<ClientMap>, server_id: &str) -> Result<serde_json::Value, String> {
+pub async fn list_prompts(clients: &AsyncMutex<ClientMap>, server_id: &str) -> Result<serde_json::Value, AidcError> {
   let svc = {
     let map = clients.lock().await;
     map.get(server_id).cloned()
-  }.ok_or_else(|| "not connected".to_string())?;
-  let res = svc.list_prompts(Default::default()).await.map_err(|e| format!("list_prompts failed: {e}"))?;
-  serde_json::to_value(res).map_err(|e| format!("serialize failed: {e}"))
+  }.ok_or_else(|| not_connected(server_id))?;
+  let res = timed(server_id, async { svc.list_prompts(Default::default()).await.map_err(|e| tool_failed(server_id, format!("list_prompts failed: {e}"))) }).await?;
+  serde_json::to_value(res).map_err(|e| AidcError::Internal(format!("serialize failed: {e}")))
 }
 
 pub async fn get_prompt(
@@ -163,50 +449,80 @@ pub async fn get_prompt(
   server_id: &str,
   name: &str,
   arguments: Option<serde_json::Value>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, AidcError> {
   let svc = {
     let map = clients.lock().await;
     map.get(server_id).cloned()
-  }.ok_or_else(|| "not connected".to_string())?;
+  }.ok_or_else(|| not_connected(server_id))?;
   let args_map = arguments.and_then(|v| v.as_object().cloned());
-  let res = svc
-    .get_prompt(rmcp::model::GetPromptRequestParam { name: name.to_string().into(), arguments: args_map })
-    .await
-    .map_err(|e| format!("get_prompt failed: {e}"))?;
-  serde_json::to_value(res).map_err(|e| format!("serialize failed: {e}"))
+  let res = timed(server_id, async {
+    svc
+      .get_prompt(rmcp::model::GetPromptRequestParam { name: name.to_string().into(), arguments: args_map })
+      .await
+      .map_err(|e| tool_failed(server_id, format!("get_prompt failed: {e}")))
+  })
+  .await?;
+  serde_json::to_value(res).map_err(|e| AidcError::Internal(format!("serialize failed: {e}")))
 }
 
-pub async fn ping(clients: &AsyncMutex<ClientMap>, server_id: &str) -> Result<String, String> {
+pub async fn ping(clients: &AsyncMutex<ClientMap>, server_id: &str) -> Result<String, AidcError> {
   let svc = {
     let map = clients.lock().await;
     map.get(server_id).cloned()
-  }.ok_or_else(|| "not connected".to_string())?;
-  let _ = svc.list_tools(Default::default()).await.map_err(|e| format!("ping(list_tools) failed: {e}"))?;
+  }.ok_or_else(|| not_connected(server_id))?;
+  timed(server_id, async { svc.list_tools(Default::default()).await.map_err(|e| tool_failed(server_id, format!("ping(list_tools) failed: {e}"))) }).await?;
   Ok("ok".into())
 }
 
+/// When `require_os_auth` is on, ask for OS authentication before a tool that may be destructive
+/// runs. MCP defaults `destructiveHint` to true, so only tools declaring `readOnlyHint: true` or
+/// `destructiveHint: false` skip the prompt; unannotated and unlisted tools are gated.
+pub async fn authorize_destructive(
+  svc: &RunningService<RoleClient, Box<dyn DynService<RoleClient>>>,
+  server_id: &str,
+  name: &str,
+) -> Result<(), AidcError> {
+  if !crate::os_auth::required() { return Ok(()); }
+  let res = timed(server_id, async { svc.list_tools(Default::default()).await.map_err(|e| tool_failed(server_id, format!("list_tools failed: {e}"))) }).await?;
+  let v = serde_json::to_value(&res).unwrap_or(serde_json::Value::Null);
+  let annotations = v
+    .get("tools")
+    .and_then(|x| x.as_array())
+    .and_then(|tools| tools.iter().find(|t| t.get("name").and_then(|x| x.as_str()) == Some(name)))
+    .and_then(|t| t.get("annotations"));
+  let flag = |key: &str| annotations.and_then(|a| a.get(key)).and_then(|x| x.as_bool());
+  if flag("readOnlyHint") == Some(true) || flag("destructiveHint") == Some(false) { return Ok(()); }
+  crate::os_auth::authorize(&format!("mcp:{server_id}:{name}"), &format!("Allow the MCP tool '{name}' ({server_id}) to run"))
+    .await
+    .map_err(|e| tool_failed(server_id, format!("not approved: {e}")))
+}
+
 pub async fn call_tool(
   clients: &AsyncMutex<ClientMap>,
   server_id: &str,
   name: &str,
   args: serde_json::Value,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, AidcError> {
   let svc = {
     let map = clients.lock().await;
     map.get(server_id).cloned()
-  }.ok_or_else(|| "not connected".to_string())?;
+  }.ok_or_else(|| not_connected(server_id))?;
   // Respect disabled tools from settings
   let disabled_map = crate::config::get_disabled_tools_map();
   if disabled_map.get(server_id).map(|set| set.contains(name)).unwrap_or(false) {
-    return Err("tool disabled by settings".into());
+    return Err(tool_failed(server_id, "tool disabled by settings".to_string()));
   }
+  authorize_destructive(&svc, server_id, name).await?;
   // Prepare arguments map if provided
-  let arg_map_opt = if args.is_null() { None } else if let Some(obj) = args.as_object() { Some(obj.clone()) } else { return Err("call_tool args must be an object".into()) };
-  let res = svc
-    .call_tool(rmcp::model::CallToolRequestParam { name: name.to_string().into(), arguments: arg_map_opt })
-    .await
-    .map_err(|e| format!("call_tool failed: {e}"))?;
-  serde_json::to_value(res).map_err(|e| format!("serialize failed: {e}"))
+  let arg_map_opt = if args.is_null() { None } else if let Some(obj) = args.as_object() { Some(obj.clone()) } else { return Err(AidcError::InvalidInput("call_tool args must be an object".into())) };
+  let res = timed(server_id, async {
+    svc
+      .call_tool(rmcp::model::CallToolRequestParam { name: name.to_string().into(), arguments: arg_map_opt })
+      .await
+      .map_err(|e| tool_failed(server_id, format!("call_tool failed: {e}")))
+  })
+  .await?;
+  serde_json::to_value(res).map_err(|e| AidcError::Internal(format!("serialize failed: {e}")))
 }
 
 // --- Pure helpers used by MCP integrations ---
@@ -0,0 +1,181 @@
+// Assistant memory: user facts ("preferred language: German", "manager: Dana") the model stores
+// and looks up through the built-in `memory__remember` / `memory__recall` tools, offered next to
+// the MCP tools in chat, the Responses backend and the voice assistant. Kept in a SQLite table
+// (memory.sqlite next to settings.json). Off unless `memory_enabled` is set; while off the tools
+// are not offered and stored facts are never read, but they stay listable and deletable.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+pub const REMEMBER_TOOL: &str = "memory__remember";
+pub const RECALL_TOOL: &str = "memory__recall";
+const MAX_KEY_CHARS: usize = 100;
+const MAX_VALUE_CHARS: usize = 2000;
+const MAX_RECALL: usize = 50;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Memory {
+  pub id: i64,
+  pub key: String,
+  pub value: String,
+  pub created_ms: u64,
+  pub updated_ms: u64,
+}
+
+// Opened on first use
+static DB: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+fn db_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name("memory.sqlite"))
+}
+
+/// Close the database (app shutdown); the next use reopens it.
+pub fn close() {
+  DB.lock().unwrap_or_else(|e| e.into_inner()).take();
+}
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+  let mut guard = DB.lock().unwrap_or_else(|e| e.into_inner());
+  if guard.is_none() {
+    let path = db_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir).map_err(|e| format!("Create config dir failed: {e}"))?;
+    }
+    let conn = Connection::open(&path).map_err(|e| format!("Open memory database failed: {e}"))?;
+    conn
+      .execute_batch(
+        "CREATE TABLE IF NOT EXISTS memories (
+           id INTEGER PRIMARY KEY AUTOINCREMENT,
+           key TEXT NOT NULL UNIQUE COLLATE NOCASE,
+           value TEXT NOT NULL,
+           created_ms INTEGER NOT NULL,
+           updated_ms INTEGER NOT NULL
+         );",
+      )
+      .map_err(|e| format!("Create memory table failed: {e}"))?;
+    *guard = Some(conn);
+  }
+  let conn = guard.as_ref().ok_or_else(|| "memory database unavailable".to_string())?;
+  f(conn).map_err(|e| format!("Memory database error: {e}"))
+}
+
+fn row_to_memory(row: &rusqlite::Row) -> rusqlite::Result<Memory> {
+  Ok(Memory {
+    id: row.get(0)?,
+    key: row.get(1)?,
+    value: row.get(2)?,
+    created_ms: row.get::<_, i64>(3)? as u64,
+    updated_ms: row.get::<_, i64>(4)? as u64,
+  })
+}
+
+pub fn enabled() -> bool {
+  crate::config::get_memory_enabled_from_settings()
+}
+
+fn remember(key: &str, value: &str) -> Result<Memory, String> {
+  let key = key.trim();
+  let value = value.trim();
+  if key.is_empty() || value.is_empty() {
+    return Err("Both key and value are required".into());
+  }
+  if key.chars().count() > MAX_KEY_CHARS || value.chars().count() > MAX_VALUE_CHARS {
+    return Err(format!("Memory too long (key up to {MAX_KEY_CHARS}, value up to {MAX_VALUE_CHARS} characters)"));
+  }
+  let now = crate::local_models::now_ms() as i64;
+  with_db(|c| {
+    c.execute(
+      "INSERT INTO memories (key, value, created_ms, updated_ms) VALUES (?1, ?2, ?3, ?3)
+       ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_ms = excluded.updated_ms",
+      params![key, value, now],
+    )?;
+    c.query_row("SELECT id, key, value, created_ms, updated_ms FROM memories WHERE key = ?1", params![key], row_to_memory)
+  })
+}
+
+fn recall(query: Option<&str>, limit: Option<usize>) -> Result<Vec<Memory>, String> {
+  let pattern = format!("%{}%", query.map(str::trim).unwrap_or("").replace('%', "").replace('_', ""));
+  with_db(|c| {
+    let mut stmt = c.prepare(
+      "SELECT id, key, value, created_ms, updated_ms FROM memories
+       WHERE key LIKE ?1 OR value LIKE ?1 ORDER BY updated_ms DESC LIMIT ?2",
+    )?;
+    // LIMIT -1 is no limit
+    let rows = stmt.query_map(params![pattern, limit.map(|l| l as i64).unwrap_or(-1)], row_to_memory)?;
+    rows.collect()
+  })
+}
+
+/// Tool definitions (Chat Completions shape) to offer alongside the MCP tools; empty when disabled.
+pub fn tool_definitions() -> Vec<serde_json::Value> {
+  if !enabled() {
+    return Vec::new();
+  }
+  vec![
+    serde_json::json!({
+      "type": "function",
+      "function": {
+        "name": REMEMBER_TOOL,
+        "description": "Store a lasting fact about the user (preferences, names, recurring details) so it is available in future conversations. Re-using a key replaces its value. Do not store secrets such as passwords.",
+        "parameters": {
+          "type": "object",
+          "properties": {
+            "key": { "type": "string", "description": "Short topic, e.g. \"preferred language\"" },
+            "value": { "type": "string", "description": "The fact to remember" }
+          },
+          "required": ["key", "value"]
+        }
+      }
+    }),
+    serde_json::json!({
+      "type": "function",
+      "function": {
+        "name": RECALL_TOOL,
+        "description": "Look up stored facts about the user. Call it when earlier preferences or personal details could matter for the answer.",
+        "parameters": {
+          "type": "object",
+          "properties": {
+            "query": { "type": "string", "description": "Words to match in keys or values; omit to list recent facts" }
+          }
+        }
+      }
+    }),
+  ]
+}
+
+/// Handle a memory tool call; None when `fname` is not a memory tool.
+pub fn call_tool(fname: &str, args: &serde_json::Value) -> Option<Result<serde_json::Value, String>> {
+  let arg = |k: &str| args.get(k).and_then(|x| x.as_str()).unwrap_or("");
+  let res = match fname {
+    REMEMBER_TOOL | RECALL_TOOL if !enabled() => Err("Memory is turned off in settings".to_string()),
+    REMEMBER_TOOL => remember(arg("key"), arg("value")).map(|m| serde_json::json!({ "stored": { "key": m.key, "value": m.value } })),
+    RECALL_TOOL => recall(Some(arg("query")), Some(MAX_RECALL)).map(|list| {
+      let facts: Vec<serde_json::Value> = list.into_iter().map(|m| serde_json::json!({ "key": m.key, "value": m.value })).collect();
+      serde_json::json!({ "memories": facts })
+    }),
+    _ => return None,
+  };
+  Some(res)
+}
+
+#[tauri::command]
+pub fn list_memories() -> Result<Vec<Memory>, String> {
+  recall(None, None)
+}
+
+#[tauri::command]
+pub fn delete_memory(id: i64) -> Result<bool, String> {
+  with_db(|c| c.execute("DELETE FROM memories WHERE id = ?1", params![id])).map(|n| n > 0)
+}
+
+/// Remove every stored memory; returns how many were deleted.
+#[tauri::command]
+pub fn clear_memories() -> Result<usize, String> {
+  let n = with_db(|c| c.execute("DELETE FROM memories", []))?;
+  crate::audit::record("memory_cleared", serde_json::json!({ "count": n }));
+  Ok(n)
+}
@@ -0,0 +1,209 @@
+// Model capability metadata: context window, vision and tool support, and list prices for the
+// models the app talks to. Built-in entries match by longest name prefix, so dated snapshots
+// (gpt-4o-2024-08-06) use their family entry. models.json next to settings.json adds models or
+// overrides single fields, e.g.
+//   { "my-finetune": { "context_window": 16000, "tools": false, "input_per_m": 3.0, "output_per_m": 12.0 } }
+// chat.rs sizes history truncation from the context window; the usage tracker prices from here.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+const OVERRIDE_FILE: &str = "models.json";
+
+// USD list prices. Token prices are per 1M tokens, character prices per 1M characters.
+#[derive(Serialize, Default, Clone, Copy, Debug)]
+pub struct Pricing {
+  pub input_per_m: f64,
+  pub output_per_m: f64,
+  pub chars_per_m: f64,
+  pub per_minute: f64,
+  pub per_image: f64,
+}
+
+const fn tokens(input_per_m: f64, output_per_m: f64) -> Pricing {
+  Pricing { input_per_m, output_per_m, chars_per_m: 0.0, per_minute: 0.0, per_image: 0.0 }
+}
+const fn chars(chars_per_m: f64) -> Pricing {
+  Pricing { input_per_m: 0.0, output_per_m: 0.0, chars_per_m, per_minute: 0.0, per_image: 0.0 }
+}
+const fn minutes(per_minute: f64) -> Pricing {
+  Pricing { input_per_m: 0.0, output_per_m: 0.0, chars_per_m: 0.0, per_minute, per_image: 0.0 }
+}
+const fn images(per_image: f64) -> Pricing {
+  Pricing { input_per_m: 0.0, output_per_m: 0.0, chars_per_m: 0.0, per_minute: 0.0, per_image }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ModelInfo {
+  /// Built-in prefix or override key the model matched
+  pub name: String,
+  /// Input plus output tokens; None for non-chat models
+  pub context_window: Option<u32>,
+  pub vision: bool,
+  pub tools: bool,
+  pub pricing: Option<Pricing>,
+  /// Some field comes from models.json
+  pub overridden: bool,
+}
+
+struct Builtin {
+  prefix: &'static str,
+  context_window: Option<u32>,
+  vision: bool,
+  tools: bool,
+  pricing: Pricing,
+}
+
+const fn chat(prefix: &'static str, context_window: u32, vision: bool, pricing: Pricing) -> Builtin {
+  Builtin { prefix, context_window: Some(context_window), vision, tools: true, pricing }
+}
+const fn other(prefix: &'static str, pricing: Pricing) -> Builtin {
+  Builtin { prefix, context_window: None, vision: false, tools: false, pricing }
+}
+
+const BUILTIN: &[Builtin] = &[
+  chat("gpt-5-nano", 400_000, true, tokens(0.05, 0.40)),
+  chat("gpt-5-mini", 400_000, true, tokens(0.25, 2.00)),
+  chat("gpt-5", 400_000, true, tokens(1.25, 10.00)),
+  chat("gpt-4.1-nano", 1_047_576, true, tokens(0.10, 0.40)),
+  chat("gpt-4.1-mini", 1_047_576, true, tokens(0.40, 1.60)),
+  chat("gpt-4.1", 1_047_576, true, tokens(2.00, 8.00)),
+  other("gpt-4o-mini-tts", chars(15.00)),
+  other("gpt-4o-mini-transcribe", minutes(0.003)),
+  other("gpt-4o-transcribe", minutes(0.006)),
+  chat("gpt-4o-mini", 128_000, true, tokens(0.15, 0.60)),
+  chat("gpt-4o", 128_000, true, tokens(2.50, 10.00)),
+  chat("o4-mini", 200_000, true, tokens(1.10, 4.40)),
+  chat("o3-mini", 200_000, false, tokens(1.10, 4.40)),
+  chat("o3", 200_000, true, tokens(2.00, 8.00)),
+  chat("claude-opus-4", 200_000, true, tokens(15.00, 75.00)),
+  chat("claude-sonnet-4", 200_000, true, tokens(3.00, 15.00)),
+  chat("claude-haiku-4", 200_000, true, tokens(1.00, 5.00)),
+  chat("claude-3-7-sonnet", 200_000, true, tokens(3.00, 15.00)),
+  chat("claude-3-5-haiku", 200_000, false, tokens(0.80, 4.00)),
+  chat("gemini-2.5-pro", 1_048_576, true, tokens(1.25, 10.00)),
+  chat("gemini-2.5-flash-lite", 1_048_576, true, tokens(0.10, 0.40)),
+  chat("gemini-2.5-flash", 1_048_576, true, tokens(0.30, 2.50)),
+  chat("gemini-2.0-flash-lite", 1_048_576, true, tokens(0.075, 0.30)),
+  chat("gemini-2.0-flash", 1_048_576, true, tokens(0.10, 0.40)),
+  other("tts-1-hd", chars(30.00)),
+  other("tts-1", chars(15.00)),
+  other("eleven_flash", chars(50.00)),
+  other("eleven_turbo", chars(50.00)),
+  other("eleven_", chars(100.00)),
+  other("whisper-1", minutes(0.006)),
+  other("text-embedding-3-small", tokens(0.02, 0.0)),
+  other("text-embedding-3-large", tokens(0.13, 0.0)),
+  other("gpt-image-1", tokens(5.00, 40.00)),
+  other("dall-e-3", images(0.04)),
+  other("dall-e-2", images(0.02)),
+];
+
+// One models.json entry; unset fields keep the built-in value
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+struct Override {
+  context_window: Option<u32>,
+  vision: Option<bool>,
+  tools: Option<bool>,
+  input_per_m: Option<f64>,
+  output_per_m: Option<f64>,
+  chars_per_m: Option<f64>,
+  per_minute: Option<f64>,
+  per_image: Option<f64>,
+}
+
+impl Override {
+  fn has_pricing(&self) -> bool {
+    self.input_per_m.is_some() || self.output_per_m.is_some() || self.chars_per_m.is_some() || self.per_minute.is_some() || self.per_image.is_some()
+  }
+
+  fn apply(&self, info: &mut ModelInfo) {
+    if let Some(c) = self.context_window { info.context_window = Some(c); }
+    if let Some(v) = self.vision { info.vision = v; }
+    if let Some(t) = self.tools { info.tools = t; }
+    if self.has_pricing() {
+      let p = info.pricing.get_or_insert_with(Pricing::default);
+      if let Some(x) = self.input_per_m { p.input_per_m = x; }
+      if let Some(x) = self.output_per_m { p.output_per_m = x; }
+      if let Some(x) = self.chars_per_m { p.chars_per_m = x; }
+      if let Some(x) = self.per_minute { p.per_minute = x; }
+      if let Some(x) = self.per_image { p.per_image = x; }
+    }
+    info.overridden = true;
+  }
+}
+
+fn override_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name(OVERRIDE_FILE))
+}
+
+// Keys lowercased; a missing or malformed file means no overrides
+fn load_overrides() -> BTreeMap<String, Override> {
+  let Some(text) = override_path().and_then(|p| std::fs::read_to_string(p).ok()) else { return BTreeMap::new() };
+  match serde_json::from_str::<BTreeMap<String, Override>>(&text) {
+    Ok(map) => map.into_iter().map(|(k, v)| (k.trim().to_lowercase(), v)).collect(),
+    Err(e) => {
+      tracing::warn!(error = %e, "models.json ignored");
+      BTreeMap::new()
+    }
+  }
+}
+
+fn from_builtin(b: &Builtin) -> ModelInfo {
+  ModelInfo { name: b.prefix.to_string(), context_window: b.context_window, vision: b.vision, tools: b.tools, pricing: Some(b.pricing), overridden: false }
+}
+
+// Models only known from models.json default to a plain text chat model
+fn from_override(name: &str, o: &Override) -> ModelInfo {
+  let mut info = ModelInfo { name: name.to_string(), context_window: None, vision: false, tools: true, pricing: None, overridden: false };
+  o.apply(&mut info);
+  info
+}
+
+/// Metadata for `model`, or None when neither the built-in table nor models.json knows it.
+pub fn lookup(model: &str) -> Option<ModelInfo> {
+  let m = model.trim().to_lowercase();
+  let builtin = BUILTIN.iter().filter(|b| m.starts_with(b.prefix)).max_by_key(|b| b.prefix.len());
+  let overrides = load_overrides();
+  let over = overrides.iter().filter(|(k, _)| m.starts_with(k.as_str())).max_by_key(|(k, _)| k.len());
+  match (builtin, over) {
+    (Some(b), Some((_, o))) => {
+      let mut info = from_builtin(b);
+      o.apply(&mut info);
+      Some(info)
+    }
+    (Some(b), None) => Some(from_builtin(b)),
+    (None, Some((k, o))) => Some(from_override(k, o)),
+    (None, None) => None,
+  }
+}
+
+pub fn pricing(model: &str) -> Option<Pricing> {
+  lookup(model).and_then(|i| i.pricing)
+}
+
+/// All built-in models with overrides applied, followed by models only defined in models.json.
+#[tauri::command]
+pub fn list_model_info() -> Vec<ModelInfo> {
+  let overrides = load_overrides();
+  let mut out: Vec<ModelInfo> = BUILTIN
+    .iter()
+    .map(|b| {
+      let mut info = from_builtin(b);
+      if let Some(o) = overrides.get(b.prefix) {
+        o.apply(&mut info);
+      }
+      info
+    })
+    .collect();
+  out.extend(overrides.iter().filter(|(k, _)| !BUILTIN.iter().any(|b| b.prefix == k.as_str())).map(|(k, o)| from_override(k, o)));
+  out
+}
+
+#[tauri::command]
+pub fn get_model_info(model: String) -> Option<ModelInfo> {
+  lookup(&model)
+}
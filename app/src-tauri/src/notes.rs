@@ -0,0 +1,188 @@
+// Quick notes: a scratchpad of markdown files in the notes folder next to settings.json (under
+// %APPDATA%\AiDesktopCompanion on Windows). Daily notes are named after the date (2026-10-16.md)
+// and are what quick actions append to when no note is named; other notes take their file name
+// from a title. Plain files, so they can be opened and synced with any editor.
+
+use std::path::PathBuf;
+
+use chrono::Local;
+use serde::Serialize;
+
+const NOTES_DIR: &str = "notes";
+const MAX_SEARCH_RESULTS: usize = 100;
+const MAX_NAME_CHARS: usize = 80;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NoteInfo {
+  /// File name without .md; used to address the note in the other commands
+  pub name: String,
+  pub path: String,
+  pub size: u64,
+  pub modified_ms: u64,
+  /// First non-empty line, without markdown heading marks
+  pub preview: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NoteMatch {
+  pub name: String,
+  /// 1-based
+  pub line: usize,
+  pub text: String,
+}
+
+fn notes_dir() -> Result<PathBuf, String> {
+  let dir = crate::config::settings_config_path()
+    .map(|p| p.with_file_name(NOTES_DIR))
+    .ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  std::fs::create_dir_all(&dir).map_err(|e| format!("Create notes folder failed: {e}"))?;
+  Ok(dir)
+}
+
+fn today() -> String {
+  Local::now().format("%Y-%m-%d").to_string()
+}
+
+// Note names become file names: keep letters, digits, spaces, '-' and '_' only
+fn sanitize_name(name: &str) -> Result<String, String> {
+  let clean: String = name
+    .trim()
+    .chars()
+    .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+    .take(MAX_NAME_CHARS)
+    .collect();
+  let clean = clean.trim().to_string();
+  if clean.is_empty() {
+    return Err("Note name is empty".into());
+  }
+  Ok(clean)
+}
+
+fn note_path(name: &str) -> Result<PathBuf, String> {
+  Ok(notes_dir()?.join(format!("{}.md", sanitize_name(name)?)))
+}
+
+fn info_for(path: &std::path::Path) -> Option<NoteInfo> {
+  let meta = std::fs::metadata(path).ok()?;
+  let modified_ms = meta.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?.as_millis() as u64;
+  let text = std::fs::read_to_string(path).unwrap_or_default();
+  let preview = text.lines().map(|l| l.trim().trim_start_matches('#').trim()).find(|l| !l.is_empty()).unwrap_or("").chars().take(120).collect();
+  Some(NoteInfo {
+    name: path.file_stem()?.to_string_lossy().to_string(),
+    path: path.to_string_lossy().to_string(),
+    size: meta.len(),
+    modified_ms,
+    preview,
+  })
+}
+
+fn append_to(name: &str, text: &str) -> Result<NoteInfo, String> {
+  use std::io::Write;
+  let text = text.trim();
+  if text.is_empty() {
+    return Err("Nothing to add".into());
+  }
+  let path = note_path(name)?;
+  let mut entry = String::new();
+  if !path.exists() {
+    entry.push_str(&format!("# {}\n", name.trim()));
+  }
+  entry.push_str(&format!("\n## {}\n\n{}\n", Local::now().format("%H:%M"), text));
+  let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path).map_err(|e| format!("Open note failed: {e}"))?;
+  file.write_all(entry.as_bytes()).map_err(|e| format!("Write note failed: {e}"))?;
+  info_for(&path).ok_or_else(|| "Note written but unreadable".to_string())
+}
+
+/// All notes, most recently changed first.
+#[tauri::command]
+pub fn notes_list() -> Result<Vec<NoteInfo>, String> {
+  let dir = notes_dir()?;
+  let mut notes: Vec<NoteInfo> = std::fs::read_dir(&dir)
+    .map_err(|e| format!("Read notes folder failed: {e}"))?
+    .flatten()
+    .map(|e| e.path())
+    .filter(|p| p.extension().and_then(|x| x.to_str()) == Some("md"))
+    .filter_map(|p| info_for(&p))
+    .collect();
+  notes.sort_by(|a, b| b.modified_ms.cmp(&a.modified_ms));
+  Ok(notes)
+}
+
+/// Create a note titled `title` (default: current date and time); a number is added if the name is taken.
+#[tauri::command]
+pub fn note_create(title: Option<String>, text: Option<String>) -> Result<NoteInfo, String> {
+  let title = title.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| Local::now().format("%Y-%m-%d %H-%M").to_string());
+  let base = sanitize_name(&title)?;
+  let dir = notes_dir()?;
+  let path = (1..)
+    .map(|n| if n == 1 { dir.join(format!("{base}.md")) } else { dir.join(format!("{base} {n}.md")) })
+    .find(|p| !p.exists())
+    .ok_or_else(|| "No free note name".to_string())?;
+  let mut body = format!("# {}\n", title.trim());
+  if let Some(t) = text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+    body.push_str(&format!("\n{t}\n"));
+  }
+  std::fs::write(&path, body).map_err(|e| format!("Write note failed: {e}"))?;
+  info_for(&path).ok_or_else(|| "Note written but unreadable".to_string())
+}
+
+/// Append `text` under a time heading; without `name` it goes to today's note.
+#[tauri::command]
+pub fn note_append(name: Option<String>, text: String) -> Result<NoteInfo, String> {
+  let name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(today);
+  append_to(&name, &text)
+}
+
+#[tauri::command]
+pub fn note_read(name: String) -> Result<String, String> {
+  std::fs::read_to_string(note_path(&name)?).map_err(|e| format!("Read note failed: {e}"))
+}
+
+/// Case-insensitive search over all notes, one result per matching line.
+#[tauri::command]
+pub fn notes_search(query: String) -> Result<Vec<NoteMatch>, String> {
+  let q = query.trim().to_lowercase();
+  if q.is_empty() {
+    return Ok(Vec::new());
+  }
+  let mut out = Vec::new();
+  for note in notes_list()? {
+    let Ok(text) = std::fs::read_to_string(&note.path) else { continue };
+    for (i, line) in text.lines().enumerate() {
+      if line.to_lowercase().contains(&q) {
+        out.push(NoteMatch { name: note.name.clone(), line: i + 1, text: line.trim().to_string() });
+        if out.len() >= MAX_SEARCH_RESULTS {
+          return Ok(out);
+        }
+      }
+    }
+  }
+  Ok(out)
+}
+
+/// Copy a note to `dest` (e.g. from a save dialog); returns the written path.
+#[tauri::command]
+pub fn note_export(name: String, dest: String) -> Result<String, String> {
+  let src = note_path(&name)?;
+  if !src.exists() {
+    return Err(format!("Note '{name}' not found"));
+  }
+  std::fs::copy(&src, &dest).map_err(|e| format!("Export note failed: {e}"))?;
+  Ok(dest)
+}
+
+/// Quick action: copy the selection from the focused app and append it to a note (default: today's).
+#[tauri::command]
+pub async fn append_selection_to_note(safe_mode: Option<bool>, name: Option<String>) -> Result<NoteInfo, String> {
+  // Clipboard + Enigo + sleep are blocking
+  let selection = tokio::task::spawn_blocking(move || -> Result<String, String> {
+    let profile = crate::app_profiles::profile_for_foreground();
+    Ok(crate::clipboard::capture_selection_for(crate::clipboard::SAFE_NOTES, safe_mode, &profile)?.text)
+  })
+  .await
+  .map_err(|e| format!("spawn_blocking failed: {e}"))??;
+  if selection.trim().is_empty() {
+    return Err("No text selected".into());
+  }
+  note_append(name, selection)
+}
@@ -0,0 +1,126 @@
+// Text recognition for images. The "local" engine uses Windows.Media.Ocr (offline, installed
+// OCR language packs); "cloud" asks the chat model (vision) to transcribe the image. Settings:
+// `ocr_engine` ("local" | "cloud", default local on Windows) and `ocr_language` (BCP-47 tag for
+// the local engine, default = user profile languages).
+
+use crate::chat::{ChatContent, ChatMessage, FrontendPart};
+use crate::error::AidcError;
+
+const CLOUD_PROMPT: &str = "Transcribe all text in this image exactly as written, preserving line breaks and reading order. Output only the text, without commentary. If there is no text, output nothing.";
+
+fn encode_png(img: &image::DynamicImage) -> Result<Vec<u8>, String> {
+  let mut out = std::io::Cursor::new(Vec::new());
+  img.write_to(&mut out, image::ImageFormat::Png).map_err(|e| format!("encode png failed: {e}"))?;
+  Ok(out.into_inner())
+}
+
+#[cfg(target_os = "windows")]
+fn recognize_local(png: &[u8], language: Option<&str>) -> Result<String, String> {
+  use windows::core::HSTRING;
+  use windows::Globalization::Language;
+  use windows::Graphics::Imaging::BitmapDecoder;
+  use windows::Media::Ocr::OcrEngine;
+  use windows::Storage::Streams::{DataWriter, InMemoryRandomAccessStream};
+  use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+  let err = |e: windows::core::Error| format!("Windows OCR failed: {}", e.message());
+  // S_FALSE / RPC_E_CHANGED_MODE just mean COM is already initialized on this thread
+  let _ = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+  let stream = InMemoryRandomAccessStream::new().map_err(err)?;
+  let writer = DataWriter::CreateDataWriter(&stream).map_err(err)?;
+  writer.WriteBytes(png).map_err(err)?;
+  writer.StoreAsync().map_err(err)?.get().map_err(err)?;
+  writer.FlushAsync().map_err(err)?.get().map_err(err)?;
+  writer.DetachStream().map_err(err)?;
+  stream.Seek(0).map_err(err)?;
+  let bitmap = BitmapDecoder::CreateAsync(&stream).map_err(err)?.get().map_err(err)?.GetSoftwareBitmapAsync().map_err(err)?.get().map_err(err)?;
+  let max = OcrEngine::MaxImageDimension().map_err(err)?;
+  if bitmap.PixelWidth().map_err(err)? as u32 > max || bitmap.PixelHeight().map_err(err)? as u32 > max {
+    return Err(format!("Image is too large for Windows OCR (max {max}px per side)"));
+  }
+  let engine = match language {
+    Some(tag) => OcrEngine::TryCreateFromLanguage(&Language::CreateLanguage(&HSTRING::from(tag)).map_err(err)?),
+    None => OcrEngine::TryCreateFromUserProfileLanguages(),
+  }
+  .map_err(|_| "No Windows OCR language pack is installed for the requested language".to_string())?;
+  let result = engine.RecognizeAsync(&bitmap).map_err(err)?.get().map_err(err)?;
+  // OcrResult::Text joins lines with spaces; keep the line structure instead
+  let lines: Vec<String> = result.Lines().map_err(err)?.into_iter().filter_map(|l| l.Text().ok()).map(|t| t.to_string()).collect();
+  Ok(lines.join("\n"))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn recognize_local(_png: &[u8], _language: Option<&str>) -> Result<String, String> {
+  Err("Local OCR is only available on Windows; set ocr_engine to \"cloud\"".into())
+}
+
+async fn recognize_cloud(app: &tauri::AppHandle, png: Vec<u8>) -> Result<String, AidcError> {
+  let key = crate::settings::require_openai_key()?;
+  // chat accepts image parts from the temp dir only
+  let path = std::env::temp_dir().join(format!("aidc_ocr_{}.png", uuid::Uuid::new_v4().simple()));
+  tokio::fs::write(&path, &png).await.map_err(|e| AidcError::Internal(format!("write image failed: {e}")))?;
+  crate::temp_files::register(crate::temp_files::OCR, &path);
+  let messages = vec![ChatMessage {
+    role: "user".into(),
+    content: ChatContent::Parts(vec![
+      FrontendPart::InputText { text: CLOUD_PROMPT.into() },
+      FrontendPart::InputImage { path: path.to_string_lossy().to_string(), mime: Some("image/png".into()) },
+    ]),
+  }];
+  let model = crate::settings::get_model_from_settings_or_env();
+  let res = crate::chat::chat_complete(app.clone(), messages, key, model, None, Vec::new()).await;
+  let _ = tokio::fs::remove_file(&path).await;
+  res
+}
+
+/// Recognize the text in an image with the engine chosen in settings
+pub async fn recognize(app: &tauri::AppHandle, img: image::DynamicImage) -> Result<String, AidcError> {
+  let png = tokio::task::spawn_blocking(move || encode_png(&img))
+    .await
+    .map_err(|e| AidcError::Internal(format!("ocr task failed: {e}")))?
+    .map_err(AidcError::Internal)?;
+  let text = if crate::config::get_ocr_engine_from_settings() == "cloud" {
+    recognize_cloud(app, png).await?
+  } else {
+    let language = crate::config::get_ocr_language_from_settings();
+    tokio::task::spawn_blocking(move || recognize_local(&png, language.as_deref()))
+      .await
+      .map_err(|e| AidcError::Internal(format!("ocr task failed: {e}")))?
+      .map_err(AidcError::Internal)?
+  };
+  Ok(text.trim().to_string())
+}
+
+/// Recognize the text in an image file
+pub async fn recognize_file(app: &tauri::AppHandle, path: &std::path::Path) -> Result<String, AidcError> {
+  let p = path.to_path_buf();
+  let img = tokio::task::spawn_blocking(move || image::open(&p))
+    .await
+    .map_err(|e| AidcError::Internal(format!("ocr task failed: {e}")))?
+    .map_err(|e| AidcError::InvalidInput(format!("Failed to read image '{}': {e}", path.display())))?;
+  recognize(app, img).await
+}
+
+fn clipboard_image() -> Result<image::DynamicImage, AidcError> {
+  let mut clipboard = arboard::Clipboard::new().map_err(|e| AidcError::Internal(format!("clipboard unavailable: {e}")))?;
+  let data = clipboard.get_image().map_err(|_| AidcError::InvalidInput("No image on the clipboard".into()))?;
+  let rgba = image::RgbaImage::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned())
+    .ok_or_else(|| AidcError::Internal("Clipboard image has an unexpected size".into()))?;
+  Ok(image::DynamicImage::ImageRgba8(rgba))
+}
+
+/// OCR the image on the clipboard. Returns the text; with `copy_result` the text also
+/// replaces the image on the clipboard.
+#[tauri::command]
+pub async fn ocr_clipboard(app: tauri::AppHandle, copy_result: Option<bool>, task_id: Option<String>) -> Result<String, AidcError> {
+  let task = crate::tasks::register("ocr", "Clipboard OCR", task_id);
+  let img = clipboard_image()?;
+  let text = task.run(recognize(&app, img)).await?;
+  if copy_result.unwrap_or(false) && !text.is_empty() {
+    arboard::Clipboard::new()
+      .and_then(|mut c| c.set_text(text.clone()))
+      .map_err(|e| AidcError::Internal(format!("copy text failed: {e}")))?;
+  }
+  tracing::info!(chars = text.len(), "clipboard OCR done");
+  Ok(text)
+}
@@ -0,0 +1,183 @@
+// OS re-authentication gate for sensitive actions: revealing stored API keys, exporting settings
+// with secrets and running MCP tools that declare themselves destructive. Off unless
+// `require_os_auth` is set. Windows asks Windows Hello (PIN, face or fingerprint), Linux asks
+// polkit; where neither is available a confirmation dialog is shown instead. A success is
+// remembered for a short grace period per scope (one MCP tool, one kind of action) so one task
+// does not prompt repeatedly without unlocking anything else.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::Mutex as AsyncMutex;
+
+const GRACE: Duration = Duration::from_secs(120);
+const CONFIRM_METHOD: &str = "confirm_dialog";
+
+static APP: OnceCell<tauri::AppHandle> = OnceCell::new();
+// Last successful authentication per scope; held across a prompt so concurrent requests share one dialog
+static LAST_OK: Lazy<AsyncMutex<HashMap<String, Instant>>> = Lazy::new(|| AsyncMutex::new(HashMap::new()));
+
+#[cfg(target_os = "windows")]
+mod platform {
+  use windows::core::{factory, HSTRING};
+  use windows::Foundation::IAsyncOperation;
+  use windows::Security::Credentials::UI::{
+    UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+  };
+  use windows::Win32::System::WinRT::IUserConsentVerifierInterop;
+  use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+  pub const METHOD: &str = "windows_hello";
+
+  pub fn available() -> bool {
+    UserConsentVerifier::CheckAvailabilityAsync()
+      .and_then(|op| op.get())
+      .map(|a| a == UserConsentVerifierAvailability::Available)
+      .unwrap_or(false)
+  }
+
+  pub fn verify(reason: &str) -> Result<bool, String> {
+    let message = HSTRING::from(reason);
+    let err = |e: windows::core::Error| format!("Windows Hello failed: {e}");
+    // Owning the dialog keeps it in front of the app instead of behind it
+    let hwnd = unsafe { GetForegroundWindow() };
+    let op: IAsyncOperation<UserConsentVerificationResult> = if hwnd.0.is_null() {
+      UserConsentVerifier::RequestVerificationAsync(&message).map_err(err)?
+    } else {
+      let interop = factory::<UserConsentVerifier, IUserConsentVerifierInterop>().map_err(err)?;
+      unsafe { interop.RequestVerificationForWindowAsync(hwnd, &message) }.map_err(err)?
+    };
+    match op.get().map_err(err)? {
+      UserConsentVerificationResult::Verified => Ok(true),
+      UserConsentVerificationResult::Canceled => Ok(false),
+      UserConsentVerificationResult::DeviceNotPresent | UserConsentVerificationResult::NotConfiguredForUser => {
+        Err("Windows Hello is not set up for this account".into())
+      }
+      UserConsentVerificationResult::DisabledByPolicy => Err("Windows Hello is disabled by policy".into()),
+      UserConsentVerificationResult::RetriesExhausted => Err("Too many failed attempts".into()),
+      _ => Err("Windows Hello is busy; try again".into()),
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  pub const METHOD: &str = "polkit";
+  // Requires authentication on standard polkit setups
+  const ACTION_ID: &str = "org.freedesktop.policykit.exec";
+
+  pub fn available() -> bool {
+    std::process::Command::new("pkcheck").arg("--version").output().map(|o| o.status.success()).unwrap_or(false)
+  }
+
+  pub fn verify(_reason: &str) -> Result<bool, String> {
+    let pid = std::process::id().to_string();
+    let status = std::process::Command::new("pkcheck")
+      .args(["--action-id", ACTION_ID, "--process", &pid, "--allow-user-interaction"])
+      .status()
+      .map_err(|e| format!("polkit is not available: {e}"))?;
+    // pkcheck: 0 authorized, 1 not authorized, 2 challenge, 3 dismissed
+    match status.code() {
+      Some(0) => Ok(true),
+      Some(1) | Some(2) | Some(3) => Ok(false),
+      other => Err(format!("pkcheck failed (exit {other:?})")),
+    }
+  }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+  pub const METHOD: &str = "none";
+
+  pub fn available() -> bool {
+    false
+  }
+
+  pub fn verify(_reason: &str) -> Result<bool, String> {
+    Err("OS authentication is not supported on this platform".into())
+  }
+}
+
+pub fn init(app: tauri::AppHandle) {
+  let _ = APP.set(app);
+}
+
+// Fallback when the OS offers no authentication: an explicit Allow/Deny dialog
+fn confirm(reason: &str) -> Result<bool, String> {
+  let app = APP.get().ok_or_else(|| "OS authentication is not available".to_string())?;
+  Ok(
+    app
+      .dialog()
+      .message(reason)
+      .title("Confirm action")
+      .kind(MessageDialogKind::Warning)
+      .buttons(MessageDialogButtons::OkCancelCustom("Allow".into(), "Deny".into()))
+      .blocking_show(),
+  )
+}
+
+/// Whether the re-authentication policy is enabled in settings.
+pub fn required() -> bool {
+  crate::config::get_require_os_auth_from_settings()
+}
+
+/// Succeeds when the policy is off, an authentication for the same `scope` is still valid, or
+/// the user passes the OS prompt (or the confirmation dialog where there is none). `reason` is
+/// shown in the prompt where the platform supports it.
+pub async fn authorize(scope: &str, reason: &str) -> Result<(), String> {
+  if !required() {
+    return Ok(());
+  }
+  let mut last = LAST_OK.lock().await;
+  if last.get(scope).is_some_and(|t| t.elapsed() < GRACE) {
+    return Ok(());
+  }
+  let text = reason.to_string();
+  let (method, res) = tokio::task::spawn_blocking(move || {
+    if platform::available() {
+      (platform::METHOD, platform::verify(&text))
+    } else {
+      (CONFIRM_METHOD, confirm(&text))
+    }
+  })
+  .await
+  .map_err(|e| format!("authentication task failed: {e}"))?;
+  let ok = matches!(res, Ok(true));
+  crate::audit::record("os_auth", serde_json::json!({ "reason": reason, "scope": scope, "method": method, "ok": ok }));
+  match res {
+    Ok(true) => {
+      last.insert(scope.to_string(), Instant::now());
+      Ok(())
+    }
+    Ok(false) => Err("Authentication was cancelled or denied".into()),
+    Err(e) => Err(e),
+  }
+}
+
+#[derive(Serialize)]
+pub struct OsAuthStatus {
+  /// "windows_hello" | "polkit" | "none"
+  pub method: &'static str,
+  pub available: bool,
+  pub required: bool,
+}
+
+#[tauri::command]
+pub async fn os_auth_status() -> Result<OsAuthStatus, String> {
+  let available = tokio::task::spawn_blocking(platform::available).await.unwrap_or(false);
+  Ok(OsAuthStatus { method: platform::METHOD, available, required: required() })
+}
+
+/// Return a stored secret (e.g. `openai_api_key`) after re-authentication. `get_settings` masks
+/// these while the policy is on.
+#[tauri::command]
+pub async fn reveal_secret(name: String) -> Result<String, String> {
+  if !crate::config::SECRET_KEYS.contains(&name.as_str()) {
+    return Err(format!("'{name}' is not a stored secret"));
+  }
+  authorize(&format!("reveal_secret:{name}"), "Reveal the stored API key").await?;
+  Ok(crate::config::get_secret(&name).unwrap_or_default())
+}
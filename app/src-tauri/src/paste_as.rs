@@ -0,0 +1,194 @@
+// Paste-as conversions for AI responses: insert a chat result as plain text, with markdown
+// syntax stripped, or wrapped in a code fence instead of pasting raw markdown into documents.
+
+use std::{thread, time::Duration};
+
+/// Convert `text` for insertion. Modes: "plain" (unchanged), "strip_markdown", "code_block".
+pub fn convert(text: &str, mode: &str) -> Result<String, String> {
+  match mode.trim().to_lowercase().as_str() {
+    "plain" => Ok(text.to_string()),
+    "strip_markdown" => Ok(strip_markdown(text)),
+    "code_block" => Ok(code_block(text)),
+    other => Err(format!("Unknown paste mode: {other}")),
+  }
+}
+
+/// Wrap text in a fenced code block. The fence is longer than any backtick run inside the
+/// text so embedded fences survive.
+pub fn code_block(text: &str) -> String {
+  let mut longest = 0usize;
+  let mut run = 0usize;
+  for ch in text.chars() {
+    if ch == '`' { run += 1; longest = longest.max(run); } else { run = 0; }
+  }
+  let fence = "`".repeat((longest + 1).max(3));
+  format!("{fence}\n{}\n{fence}", text.trim_end_matches(['\r', '\n']))
+}
+
+/// Remove markdown syntax while keeping the readable text: headings, emphasis, blockquotes,
+/// code fences (contents kept verbatim), rules and table pipes. Links become "text (url)".
+pub fn strip_markdown(input: &str) -> String {
+  let normalized = input.replace("\r\n", "\n");
+  let mut lines: Vec<String> = Vec::new();
+  let mut in_fence = false;
+  for raw in normalized.lines() {
+    let trimmed = raw.trim_start();
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") { in_fence = !in_fence; continue; }
+    if in_fence { lines.push(raw.to_string()); continue; }
+    if is_table_separator(trimmed) { continue; }
+    if is_rule(trimmed) { lines.push(String::new()); continue; }
+
+    let indent = &raw[..raw.len() - trimmed.len()];
+    let mut line = trimmed;
+    while let Some(rest) = line.strip_prefix('>') { line = rest.trim_start(); }
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) && line[hashes..].chars().next().map_or(true, |c| c == ' ') {
+      line = line[hashes..].trim();
+    }
+
+    let mut prefix = String::new();
+    for marker in ["- ", "* ", "+ "] {
+      if let Some(rest) = line.strip_prefix(marker) { prefix.push_str("• "); line = rest; break; }
+    }
+    let body = if line.starts_with('|') {
+      line
+        .trim_matches('|')
+        .split('|')
+        .map(|cell| strip_inline(cell.trim()))
+        .collect::<Vec<_>>()
+        .join("\t")
+    } else {
+      strip_inline(line)
+    };
+    lines.push(format!("{indent}{prefix}{body}"));
+  }
+
+  // Collapse runs of blank lines left behind by removed syntax
+  let mut out = String::new();
+  let mut blank_run = 0usize;
+  for l in lines {
+    if l.trim().is_empty() {
+      blank_run += 1;
+      if blank_run > 1 { continue; }
+    } else {
+      blank_run = 0;
+    }
+    out.push_str(&l);
+    out.push('\n');
+  }
+  out.trim().to_string()
+}
+
+fn is_rule(line: &str) -> bool {
+  let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+  compact.len() >= 3
+    && (compact.chars().all(|c| c == '-') || compact.chars().all(|c| c == '*') || compact.chars().all(|c| c == '_'))
+}
+
+fn is_table_separator(line: &str) -> bool {
+  line.starts_with('|')
+    && line.contains('-')
+    && line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+// Strip inline markup: code spans, images, links, emphasis and escapes.
+fn strip_inline(s: &str) -> String {
+  let chars: Vec<char> = s.chars().collect();
+  let mut out = String::with_capacity(s.len());
+  let mut i = 0usize;
+  while i < chars.len() {
+    let c = chars[i];
+    match c {
+      '\\' if i + 1 < chars.len() && chars[i + 1].is_ascii_punctuation() => {
+        out.push(chars[i + 1]);
+        i += 2;
+      }
+      '`' => {
+        let run = chars[i..].iter().take_while(|x| **x == '`').count();
+        let open_end = i + run;
+        match find_backtick_run(&chars, open_end, run) {
+          Some(close) => {
+            out.extend(&chars[open_end..close]);
+            i = close + run;
+          }
+          None => {
+            out.extend(&chars[i..open_end]);
+            i = open_end;
+          }
+        }
+      }
+      '!' if chars.get(i + 1) == Some(&'[') => match parse_link(&chars, i + 1) {
+        Some((alt, _url, end)) => { out.push_str(&alt); i = end; }
+        None => { out.push(c); i += 1; }
+      },
+      '[' => match parse_link(&chars, i) {
+        Some((text, url, end)) => {
+          let text = strip_inline(&text);
+          if text.is_empty() || text == url { out.push_str(&url); } else { out.push_str(&format!("{text} ({url})")); }
+          i = end;
+        }
+        None => { out.push(c); i += 1; }
+      },
+      '*' | '_' | '~' => {
+        let run = chars[i..].iter().take_while(|x| **x == c).count();
+        let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+        let next = chars.get(i + run).copied();
+        let is_marker = match c {
+          // Keep a lone "~" and a spaced-out "*" (e.g. multiplication)
+          '~' => run >= 2,
+          '*' => !(prev.map_or(true, char::is_whitespace) && next.map_or(true, char::is_whitespace)),
+          // Underscores inside words (snake_case) are not emphasis
+          _ => !(prev.is_some_and(char::is_alphanumeric) && next.is_some_and(char::is_alphanumeric)),
+        };
+        if !is_marker { out.extend(&chars[i..i + run]); }
+        i += run;
+      }
+      _ => { out.push(c); i += 1; }
+    }
+  }
+  out
+}
+
+fn find_backtick_run(chars: &[char], from: usize, run: usize) -> Option<usize> {
+  let mut j = from;
+  while j < chars.len() {
+    if chars[j] == '`' {
+      let r = chars[j..].iter().take_while(|x| **x == '`').count();
+      if r == run { return Some(j); }
+      j += r;
+    } else {
+      j += 1;
+    }
+  }
+  None
+}
+
+// Parse `[text](url)` starting at `[`; returns (text, url, index after the closing paren).
+fn parse_link(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+  let close_text = start + 1 + chars[start + 1..].iter().position(|c| *c == ']')?;
+  if chars.get(close_text + 1) != Some(&'(') { return None; }
+  let close_url = close_text + 2 + chars[close_text + 2..].iter().position(|c| *c == ')')?;
+  let text: String = chars[start + 1..close_text].iter().collect();
+  let url: String = chars[close_text + 2..close_url].iter().collect();
+  let url = url.split_whitespace().next().unwrap_or("").to_string();
+  Some((text, url, close_url + 1))
+}
+
+/// Convert an AI response and insert it into the application behind the calling window.
+/// When the calling window still has focus it is minimized first so the previously active
+/// application receives the paste.
+#[tauri::command]
+pub async fn paste_as(window: tauri::WebviewWindow, text: String, mode: String, safe_mode: Option<bool>) -> Result<(), String> {
+  let converted = convert(&text, &mode)?;
+  if converted.trim().is_empty() { return Err("Nothing to paste".into()); }
+  if window.is_focused().unwrap_or(false) {
+    let _ = window.minimize();
+  }
+  // Focus change, key injection and clipboard restore are blocking
+  tokio::task::spawn_blocking(move || {
+    thread::sleep(Duration::from_millis(150));
+    crate::quick_actions::insert_text_into_focused_app(converted, safe_mode, None)
+  })
+  .await
+  .map_err(|e| format!("spawn_blocking failed: {e}"))?
+}
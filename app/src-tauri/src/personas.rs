@@ -0,0 +1,205 @@
+// Personas: named assistant setups ("Coder", "Editor", "Tutor") bundling a system prompt, a voice
+// profile (voices.rs), a chat model and a tool profile. Stored in personas.json next to
+// settings.json together with the active persona's id. While a persona is active its values win
+// over the plain settings in chat, quick prompts (unless a prompt sets its own) and the voice
+// assistant; unset fields fall back to the settings. `persona:changed`
+// (events::PersonaChanged) is emitted on switch.
+//
+// Tool profiles: "all" (MCP and built-in tools), "builtin" (memory, drafts and code only),
+// "selected" (built-ins plus the MCP servers in `tool_servers`) and "none".
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+const MAX_NAME_CHARS: usize = 48;
+
+pub const TOOLS_ALL: &str = "all";
+pub const TOOLS_BUILTIN: &str = "builtin";
+pub const TOOLS_SELECTED: &str = "selected";
+pub const TOOLS_NONE: &str = "none";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Persona {
+  /// Assigned on first save
+  #[serde(default)]
+  pub id: String,
+  pub name: String,
+  #[serde(default)]
+  pub system_prompt: Option<String>,
+  /// Voice profile id (voices.json) for spoken replies
+  #[serde(default)]
+  pub voice_profile: Option<String>,
+  /// Chat model for the configured provider
+  #[serde(default)]
+  pub model: Option<String>,
+  /// "all" | "builtin" | "selected" | "none"
+  #[serde(default = "default_tool_profile")]
+  pub tool_profile: String,
+  /// MCP server ids offered with the "selected" tool profile
+  #[serde(default)]
+  pub tool_servers: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Store {
+  #[serde(default)]
+  active: Option<String>,
+  #[serde(default)]
+  personas: Vec<Persona>,
+}
+
+fn default_tool_profile() -> String {
+  TOOLS_ALL.to_string()
+}
+
+fn personas_config_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name("personas.json"))
+}
+
+fn load_store() -> Store {
+  personas_config_path()
+    .and_then(|p| fs::read_to_string(p).ok())
+    .and_then(|text| serde_json::from_str::<Store>(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_store(store: &Store) -> Result<(), String> {
+  let path = personas_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  let pretty = serde_json::to_string_pretty(store).map_err(|e| format!("Serialize personas failed: {e}"))?;
+  crate::config::write_json_atomic_blocking(&path, &pretty)
+}
+
+fn non_empty(s: Option<String>) -> Option<String> {
+  s.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// The active persona, if one is set and still exists.
+pub fn active() -> Option<Persona> {
+  let store = load_store();
+  let id = store.active?;
+  store.personas.into_iter().find(|p| p.id == id)
+}
+
+pub fn active_system_prompt() -> Option<String> {
+  active().and_then(|p| p.system_prompt)
+}
+
+pub fn active_model() -> Option<String> {
+  active().and_then(|p| p.model)
+}
+
+pub fn active_voice_profile() -> Option<String> {
+  active().and_then(|p| p.voice_profile)
+}
+
+// Whether the active persona's tool profile offers the tool with this function name
+fn tool_allowed(persona: &Persona, fn_name: &str) -> bool {
+  let builtin = fn_name.starts_with("memory__") || fn_name.starts_with("drafts__") || fn_name.starts_with("code__");
+  match persona.tool_profile.as_str() {
+    TOOLS_NONE => false,
+    TOOLS_BUILTIN => builtin,
+    TOOLS_SELECTED => {
+      builtin || crate::mcp::parse_mcp_fn_call_name(fn_name).is_some_and(|(server_id, _)| persona.tool_servers.iter().any(|s| *s == server_id))
+    }
+    _ => true,
+  }
+}
+
+pub fn allows_tool(fn_name: &str) -> bool {
+  active().is_none_or(|p| tool_allowed(&p, fn_name))
+}
+
+/// Drop tool definitions (chat `{function: {name}}` or Realtime `{name}` shape) the active persona does not offer.
+pub fn retain_allowed_tools(tools: &mut Vec<serde_json::Value>) {
+  let Some(persona) = active() else { return };
+  tools.retain(|t| {
+    let name = t.get("function").and_then(|f| f.get("name")).or_else(|| t.get("name")).and_then(|x| x.as_str()).unwrap_or("");
+    tool_allowed(&persona, name)
+  });
+}
+
+#[tauri::command]
+pub fn list_personas() -> Result<Vec<Persona>, String> {
+  Ok(load_store().personas)
+}
+
+/// Create (empty `id`) or update a persona. Names are unique, case-insensitively.
+#[tauri::command]
+pub fn save_persona(persona: Persona) -> Result<Persona, String> {
+  let name = persona.name.trim().to_string();
+  if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+    return Err(format!("Persona name must be 1-{MAX_NAME_CHARS} characters"));
+  }
+  let tool_profile = persona.tool_profile.trim().to_lowercase();
+  if ![TOOLS_ALL, TOOLS_BUILTIN, TOOLS_SELECTED, TOOLS_NONE].contains(&tool_profile.as_str()) {
+    return Err(format!("Unknown tool profile '{}' (expected all, builtin, selected or none)", persona.tool_profile));
+  }
+  let voice_profile = non_empty(persona.voice_profile);
+  if let Some(v) = &voice_profile {
+    if crate::voices::find(v).is_none() {
+      return Err(format!("Unknown voice profile '{v}'"));
+    }
+  }
+  let mut store = load_store();
+  let id = if persona.id.trim().is_empty() { uuid::Uuid::new_v4().to_string() } else { persona.id.trim().to_string() };
+  if store.personas.iter().any(|p| p.id != id && p.name.eq_ignore_ascii_case(&name)) {
+    return Err(format!("A persona named '{name}' already exists"));
+  }
+  let saved = Persona {
+    id: id.clone(),
+    name,
+    system_prompt: non_empty(persona.system_prompt),
+    voice_profile,
+    model: non_empty(persona.model),
+    tool_profile,
+    tool_servers: persona.tool_servers.into_iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+  };
+  match store.personas.iter_mut().find(|p| p.id == id) {
+    Some(existing) => *existing = saved.clone(),
+    None => store.personas.push(saved.clone()),
+  }
+  write_store(&store)?;
+  Ok(saved)
+}
+
+/// Delete a persona; deleting the active one leaves no persona active.
+#[tauri::command]
+pub fn delete_persona(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+  let mut store = load_store();
+  let before = store.personas.len();
+  store.personas.retain(|p| p.id != id);
+  if store.personas.len() == before {
+    return Ok(false);
+  }
+  let was_active = store.active.as_deref() == Some(id.as_str());
+  if was_active {
+    store.active = None;
+  }
+  write_store(&store)?;
+  if was_active {
+    crate::events::emit(&app, crate::events::PERSONA_CHANGED, &crate::events::PersonaChanged { id: None, name: None });
+  }
+  Ok(true)
+}
+
+#[tauri::command]
+pub fn get_active_persona() -> Result<Option<Persona>, String> {
+  Ok(active())
+}
+
+/// Make `id` the active persona; None (or an empty id) goes back to the plain settings.
+#[tauri::command]
+pub fn set_active_persona(app: tauri::AppHandle, id: Option<String>) -> Result<Option<Persona>, String> {
+  let mut store = load_store();
+  let id = non_empty(id);
+  let persona = match &id {
+    Some(id) => Some(store.personas.iter().find(|p| p.id == *id).cloned().ok_or_else(|| format!("Unknown persona '{id}'"))?),
+    None => None,
+  };
+  store.active = id;
+  write_store(&store)?;
+  crate::events::emit(&app, crate::events::PERSONA_CHANGED, &crate::events::PersonaChanged { id: persona.as_ref().map(|p| p.id.clone()), name: persona.as_ref().map(|p| p.name.clone()) });
+  Ok(persona)
+}
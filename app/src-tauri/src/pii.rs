@@ -0,0 +1,197 @@
+// Outbound PII filter: masks email addresses, phone numbers and payment card numbers in selected
+// text before it goes to a cloud provider (quick prompts, and the selection and clipboard in the
+// desktop context). Off unless `pii_filter` is set; `pii_filter_prompts` ({ "6": false }) turns it
+// on or off for single quick prompts. Matches are replaced with [EMAIL], [PHONE] and [CARD].
+// Each masking is written to the audit log (kind "pii_masked") with counts and partly hidden
+// samples, never the full value, and emitted as `pii:masked` (events::PiiMasked).
+
+use std::collections::BTreeMap;
+
+const EMAIL: &str = "email";
+const PHONE: &str = "phone";
+const CARD: &str = "card";
+const MAX_SAMPLES: usize = 5;
+// Phone numbers need this many digits (fewer is too often a date, amount or ID)
+const MIN_PHONE_DIGITS: usize = 10;
+const MIN_INTL_PHONE_DIGITS: usize = 8;
+const MAX_PHONE_DIGITS: usize = 15;
+
+struct Hit {
+  start: usize,
+  end: usize,
+  kind: &'static str,
+}
+
+fn placeholder(kind: &str) -> &'static str {
+  match kind {
+    EMAIL => "[EMAIL]",
+    PHONE => "[PHONE]",
+    _ => "[CARD]",
+  }
+}
+
+pub fn enabled() -> bool {
+  crate::config::get_pii_filter_from_settings()
+}
+
+/// Whether quick prompt `index` masks its selection: its override if set, otherwise `pii_filter`.
+pub fn enabled_for_prompt(index: u8) -> bool {
+  crate::config::get_pii_filter_prompt_override(index).unwrap_or_else(enabled)
+}
+
+fn is_local_char(c: char) -> bool {
+  c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-')
+}
+
+fn is_domain_char(c: char) -> bool {
+  c.is_ascii_alphanumeric() || matches!(c, '.' | '-')
+}
+
+fn scan_emails(text: &str, hits: &mut Vec<Hit>) {
+  for (at, _) in text.match_indices('@') {
+    let local_start = text[..at].char_indices().rev().take_while(|(_, c)| is_local_char(*c)).last().map(|(i, _)| i).unwrap_or(at);
+    let domain_len = text[at + 1..].chars().take_while(|c| is_domain_char(*c)).map(char::len_utf8).sum::<usize>();
+    // Sentence punctuation after the address is not part of it
+    let domain = text[at + 1..at + 1 + domain_len].trim_end_matches(['.', '-']);
+    let local = text[local_start..at].trim_start_matches('.');
+    let tld_ok = domain.rsplit_once('.').is_some_and(|(host, tld)| !host.is_empty() && tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+    if local.is_empty() || !tld_ok {
+      continue;
+    }
+    hits.push(Hit { start: at - local.len(), end: at + 1 + domain.len(), kind: EMAIL });
+  }
+}
+
+fn luhn_ok(digits: &[u32]) -> bool {
+  let sum: u32 = digits
+    .iter()
+    .rev()
+    .enumerate()
+    .map(|(i, &d)| if i % 2 == 1 { (d * 2) % 10 + (d * 2) / 10 } else { d })
+    .sum();
+  sum % 10 == 0
+}
+
+// Runs of digits with single separators between them; classified as card or phone number
+fn scan_numbers(text: &str, hits: &mut Vec<Hit>) {
+  let chars: Vec<(usize, char)> = text.char_indices().collect();
+  let mut i = 0;
+  while i < chars.len() {
+    let (start, c) = chars[i];
+    let prev_alnum = i > 0 && chars[i - 1].1.is_alphanumeric();
+    if prev_alnum || !(c.is_ascii_digit() || c == '+' || c == '(') {
+      i += 1;
+      continue;
+    }
+    let mut j = i;
+    let mut digits: Vec<u32> = Vec::new();
+    let mut last_digit = None;
+    while j < chars.len() {
+      let ch = chars[j].1;
+      if let Some(d) = ch.to_digit(10) {
+        digits.push(d);
+        last_digit = Some(j);
+      } else if matches!(ch, ' ' | '-' | '.' | '(' | ')') || (ch == '+' && j == i) {
+        // Two separators in a row end the number
+        if j > i && !chars[j - 1].1.is_ascii_digit() && !matches!((chars[j - 1].1, ch), (')', ' ') | (' ', '(')) {
+          break;
+        }
+      } else {
+        break;
+      }
+      j += 1;
+    }
+    let Some(last) = last_digit else {
+      i += 1;
+      continue;
+    };
+    let end_idx = last + 1;
+    let next_alnum = chars.get(end_idx).is_some_and(|(_, c)| c.is_alphanumeric());
+    let end = chars.get(end_idx).map(|(b, _)| *b).unwrap_or(text.len());
+    let span = &text[start..end];
+    let intl = c == '+';
+    let dots = span.matches('.').count();
+    let kind = if next_alnum {
+      None
+    } else if (13..=19).contains(&digits.len()) && !intl && !span.contains(['.', '(']) && luhn_ok(&digits) {
+      Some(CARD)
+    // A single dot is a decimal number; phone numbers written with dots have several
+    } else if (dots == 0 || dots >= 2) && digits.len() <= MAX_PHONE_DIGITS && digits.len() >= if intl { MIN_INTL_PHONE_DIGITS } else { MIN_PHONE_DIGITS } {
+      Some(PHONE)
+    } else {
+      None
+    };
+    if let Some(kind) = kind {
+      // An opening parenthesis is only part of the number when it is closed inside it
+      let start = if c == '(' && !span.contains(')') { start + 1 } else { start };
+      hits.push(Hit { start, end, kind });
+    }
+    i = end_idx.max(i + 1);
+  }
+}
+
+// Non-overlapping matches in text order; emails win over the numbers inside them, and of
+// overlapping emails (e.g. "a@b.com@c.org") the first one wins
+fn find(text: &str) -> Vec<Hit> {
+  let mut found = Vec::new();
+  scan_emails(text, &mut found);
+  found.sort_by_key(|h| h.start);
+  let mut hits: Vec<Hit> = Vec::new();
+  for h in found {
+    if !hits.iter().any(|e| h.start < e.end && e.start < h.end) {
+      hits.push(h);
+    }
+  }
+  let emails = hits.len();
+  scan_numbers(text, &mut hits);
+  let (emails, numbers) = hits.split_at(emails);
+  let numbers: Vec<&Hit> = numbers.iter().filter(|n| !emails.iter().any(|e| n.start < e.end && e.start < n.end)).collect();
+  let mut out: Vec<Hit> = emails.iter().chain(numbers).map(|h| Hit { start: h.start, end: h.end, kind: h.kind }).collect();
+  out.sort_by_key(|h| h.start);
+  out
+}
+
+// First and last characters only, e.g. "jo…om" or "41…11"
+fn sample(value: &str, kind: &str) -> String {
+  let chars: Vec<char> = value.chars().filter(|c| !c.is_whitespace()).collect();
+  let keep = if chars.len() >= 8 { 2 } else { 1 };
+  let head: String = chars.iter().take(keep).collect();
+  let tail: String = chars.iter().skip(chars.len().saturating_sub(keep)).collect();
+  format!("{kind}: {head}…{tail}")
+}
+
+// Replace every match with its placeholder; returns the masked text, counts per kind and samples
+fn mask(text: &str) -> (String, BTreeMap<&'static str, usize>, Vec<String>) {
+  let hits = find(text);
+  let mut counts = BTreeMap::new();
+  let mut samples = Vec::new();
+  let mut out = String::with_capacity(text.len());
+  let mut pos = 0;
+  for h in &hits {
+    out.push_str(&text[pos..h.start]);
+    out.push_str(placeholder(h.kind));
+    *counts.entry(h.kind).or_insert(0) += 1;
+    if samples.len() < MAX_SAMPLES {
+      samples.push(sample(&text[h.start..h.end], h.kind));
+    }
+    pos = h.end;
+  }
+  out.push_str(&text[pos..]);
+  (out, counts, samples)
+}
+
+/// Mask `text` when `enabled`; `source` names the origin in the audit log, e.g. "quick_prompt:3".
+pub fn filter_text(app: &tauri::AppHandle, source: &str, text: String, enabled: bool) -> String {
+  if !enabled || text.is_empty() {
+    return text;
+  }
+  let (masked, counts, samples) = mask(&text);
+  if counts.is_empty() {
+    return text;
+  }
+  tracing::info!(%source, ?counts, "personal data masked before sending");
+  crate::audit::record("pii_masked", serde_json::json!({ "source": source, "counts": counts, "samples": samples }));
+  let counts = counts.into_iter().map(|(kind, n)| (kind.to_string(), n)).collect();
+  crate::events::emit(app, crate::events::PII_MASKED, &crate::events::PiiMasked { source: source.to_string(), counts });
+  masked
+}
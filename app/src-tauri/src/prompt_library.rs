@@ -0,0 +1,176 @@
+// Prompt library: any number of named, tagged prompts in prompts.json next to settings.json, on
+// top of the nine quick prompts. A library prompt can be bound to a quick prompt slot (1-9); the
+// slot then runs it (popup digit, scheduler) instead of its quick_prompts.json entry, until the
+// binding is removed.
+//
+// Templates take the same variables as quick prompt templates ({selection}, {clipboard}, {date},
+// ...; see quick_prompts.rs).
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::quick_prompts::{QuickPrompt, OUTPUT_CLIPBOARD, OUTPUT_INSERT, OUTPUT_PREVIEW};
+
+const MAX_NAME_CHARS: usize = 64;
+const MAX_TAGS: usize = 16;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LibraryPrompt {
+  /// Assigned on first save
+  #[serde(default)]
+  pub id: String,
+  pub name: String,
+  pub template: String,
+  #[serde(default)]
+  pub tags: Vec<String>,
+  #[serde(default)]
+  pub model: Option<String>,
+  #[serde(default)]
+  pub temperature: Option<f32>,
+  #[serde(default)]
+  pub system_prompt: Option<String>,
+  /// "insert" | "preview" | "clipboard"; None follows `show_quick_prompt_result_in_popup`
+  #[serde(default)]
+  pub output: Option<String>,
+  /// Quick prompt slot (1-9) this prompt runs from; at most one prompt per slot
+  #[serde(default)]
+  pub slot: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Store {
+  #[serde(default)]
+  prompts: Vec<LibraryPrompt>,
+}
+
+fn library_config_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name("prompts.json"))
+}
+
+fn load_store() -> Store {
+  library_config_path()
+    .and_then(|p| fs::read_to_string(p).ok())
+    .and_then(|text| serde_json::from_str::<Store>(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_store(store: &Store) -> Result<(), String> {
+  let path = library_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  let pretty = serde_json::to_string_pretty(store).map_err(|e| format!("Serialize prompt library failed: {e}"))?;
+  crate::config::write_json_atomic_blocking(&path, &pretty)?;
+  // Bound prompts are listed by name in the tray menu
+  crate::tray::refresh();
+  Ok(())
+}
+
+fn non_empty(s: Option<String>) -> Option<String> {
+  s.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+impl LibraryPrompt {
+  fn to_quick_prompt(&self) -> QuickPrompt {
+    QuickPrompt {
+      template: self.template.clone(),
+      model: self.model.clone(),
+      temperature: self.temperature,
+      system_prompt: self.system_prompt.clone(),
+      output: self.output.clone(),
+      library_id: Some(self.id.clone()),
+    }
+  }
+}
+
+/// The library prompt bound to quick prompt slot `index`, as a quick prompt.
+pub fn bound_to(index: u8) -> Option<QuickPrompt> {
+  load_store().prompts.iter().find(|p| p.slot == Some(index)).map(LibraryPrompt::to_quick_prompt)
+}
+
+/// Name of the library prompt bound to quick prompt slot `index`.
+pub fn bound_name(index: u8) -> Option<String> {
+  load_store().prompts.into_iter().find(|p| p.slot == Some(index)).map(|p| p.name)
+}
+
+/// All library prompts, or those tagged `tag` (case-insensitive).
+#[tauri::command]
+pub fn prompt_library_list(tag: Option<String>) -> Result<Vec<LibraryPrompt>, String> {
+  let prompts = load_store().prompts;
+  Ok(match non_empty(tag) {
+    Some(tag) => prompts.into_iter().filter(|p| p.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag))).collect(),
+    None => prompts,
+  })
+}
+
+/// Create (empty `id`) or update a library prompt. Binding it to a slot unbinds whichever prompt
+/// held that slot before.
+#[tauri::command]
+pub fn prompt_library_save(prompt: LibraryPrompt) -> Result<LibraryPrompt, String> {
+  let name = prompt.name.trim().to_string();
+  if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+    return Err(format!("Prompt name must be 1-{MAX_NAME_CHARS} characters"));
+  }
+  let template = prompt.template.trim().to_string();
+  if template.is_empty() {
+    return Err("Prompt template is empty".into());
+  }
+  if let Some(slot) = prompt.slot {
+    if !(1..=9).contains(&slot) {
+      return Err("Quick prompt slot must be 1-9".into());
+    }
+  }
+  let output = non_empty(prompt.output).map(|o| o.to_lowercase());
+  if let Some(o) = &output {
+    if ![OUTPUT_INSERT, OUTPUT_PREVIEW, OUTPUT_CLIPBOARD].contains(&o.as_str()) {
+      return Err(format!("Unknown output '{o}' (expected insert, preview or clipboard)"));
+    }
+  }
+  let mut tags: Vec<String> = Vec::new();
+  for t in prompt.tags.iter().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()) {
+    if !tags.contains(&t) { tags.push(t); }
+  }
+  if tags.len() > MAX_TAGS {
+    return Err(format!("A prompt can have at most {MAX_TAGS} tags"));
+  }
+
+  let mut store = load_store();
+  let id = if prompt.id.trim().is_empty() { uuid::Uuid::new_v4().to_string() } else { prompt.id.trim().to_string() };
+  if store.prompts.iter().any(|p| p.id != id && p.name.eq_ignore_ascii_case(&name)) {
+    return Err(format!("A prompt named '{name}' already exists"));
+  }
+  let saved = LibraryPrompt {
+    id: id.clone(),
+    name,
+    template,
+    tags,
+    model: non_empty(prompt.model),
+    temperature: prompt.temperature.map(|t| t.clamp(0.0, 2.0)),
+    system_prompt: non_empty(prompt.system_prompt),
+    output,
+    slot: prompt.slot,
+  };
+  if saved.slot.is_some() {
+    for p in store.prompts.iter_mut().filter(|p| p.id != id && p.slot == saved.slot) {
+      p.slot = None;
+    }
+  }
+  match store.prompts.iter_mut().find(|p| p.id == id) {
+    Some(existing) => *existing = saved.clone(),
+    None => store.prompts.push(saved.clone()),
+  }
+  write_store(&store)?;
+  Ok(saved)
+}
+
+/// Delete a library prompt; a slot it was bound to goes back to its quick_prompts.json entry.
+#[tauri::command]
+pub fn prompt_library_delete(id: String) -> Result<bool, String> {
+  let mut store = load_store();
+  let before = store.prompts.len();
+  store.prompts.retain(|p| p.id != id);
+  if store.prompts.len() == before {
+    return Ok(false);
+  }
+  write_store(&store)?;
+  Ok(true)
+}
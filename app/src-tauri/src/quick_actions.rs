@@ -10,11 +10,13 @@ use std::ffi::c_void;
 #[cfg(target_os = "windows")]
 use windows::Win32::Foundation::HWND;
 
-// Store the last foreground window handle (Windows) as a raw isize so we can
-// briefly return focus to it to capture selection without hiding the QA window.
-#[cfg(target_os = "windows")]
+// Store the last foreground window handle (Windows HWND, X11 window id on Linux) as a raw isize
+// so we can briefly return focus to it to capture selection without hiding the QA window.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
 static LAST_FOREGROUND: Lazy<Mutex<Option<isize>>> = Lazy::new(|| Mutex::new(None));
 static LAST_SELECTED_TEXT: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(String::new()));
+// HTML flavor of the last captured selection (only when selection_format != "plain")
+static LAST_SELECTED_HTML: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
 
 pub fn last_selected_text() -> String {
   LAST_SELECTED_TEXT
@@ -23,49 +25,140 @@ pub fn last_selected_text() -> String {
     .unwrap_or_default()
 }
 
-#[cfg(target_os = "windows")]
+pub fn last_selected_html() -> Option<String> {
+  LAST_SELECTED_HTML.lock().ok().and_then(|g| g.clone())
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
 pub fn last_foreground_handle_raw() -> Option<isize> {
   LAST_FOREGROUND.lock().ok().and_then(|g| *g)
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub fn last_foreground_handle_raw() -> Option<isize> {
   None
 }
 
-// UI actions and quick insertions
+/// Executable file name (e.g. "WindowsTerminal.exe") of the process owning the given window.
+/// Returns an empty string when it cannot be determined.
+#[cfg(target_os = "windows")]
+pub fn process_name_for_window(hraw: isize) -> String {
+  use windows::core::PWSTR;
+  use windows::Win32::Foundation::{CloseHandle, HANDLE};
+  use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_FORMAT, PROCESS_QUERY_LIMITED_INFORMATION,
+  };
+  use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
 
-#[tauri::command]
-pub fn prompt_action(app: tauri::AppHandle, safe_mode: Option<bool>) -> Result<String, String> {
-  let safe = safe_mode.unwrap_or(false);
+  unsafe {
+    let hwnd = HWND(hraw as *mut c_void);
+    if hwnd.0.is_null() {
+      return String::new();
+    }
 
-  // Prepare clipboard access
-  let mut clipboard = Clipboard::new().map_err(|e| format!("clipboard init failed: {e}"))?;
+    let mut pid: u32 = 0;
+    let _ = GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    if pid == 0 {
+      return String::new();
+    }
 
-  // Save current clipboard text (best-effort) when aggressive mode
-  let previous_text = if !safe { clipboard.get_text().ok() } else { None };
+    let process: HANDLE = match OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
+      Ok(h) => h,
+      Err(_) => return String::new(),
+    };
 
-  // Simulate Ctrl+C to copy current selection (aggressive mode)
-  if !safe {
-    let mut enigo = Enigo::new();
-    enigo.key_down(Key::Control);
-    enigo.key_click(Key::Layout('c'));
-    enigo.key_up(Key::Control);
-    // Allow some time for clipboard to update
-    thread::sleep(Duration::from_millis(120));
+    let mut size: u32 = 32768;
+    let mut buf = vec![0u16; size as usize];
+    let ok = QueryFullProcessImageNameW(process, PROCESS_NAME_FORMAT(0), PWSTR(buf.as_mut_ptr()), &mut size).is_ok();
+    let _ = CloseHandle(process);
+
+    if !ok || size == 0 {
+      return String::new();
+    }
+
+    let full = String::from_utf16_lossy(&buf[..size as usize]);
+    std::path::Path::new(&full)
+      .file_name()
+      .and_then(|x| x.to_str())
+      .unwrap_or("")
+      .to_string()
   }
+}
 
-  // Read selection text (fallback to empty string)
-  let selection = clipboard.get_text().unwrap_or_default();
+#[cfg(target_os = "linux")]
+pub fn process_name_for_window(hraw: isize) -> String {
+  crate::linux_input::process_name_for_window(hraw)
+}
 
-  // Restore clipboard (best-effort) if we changed it
-  if !safe {
-    if let Some(prev) = previous_text { let _ = clipboard.set_text(prev); }
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn process_name_for_window(_hraw: isize) -> String {
+  String::new()
+}
+
+/// Process name of the window that currently has keyboard focus.
+pub fn foreground_process_name() -> String {
+  #[cfg(target_os = "windows")]
+  {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+    let h = unsafe { GetForegroundWindow() };
+    process_name_for_window(h.0 as isize)
+  }
+  #[cfg(target_os = "linux")]
+  { crate::linux_input::active_window().map(process_name_for_window).unwrap_or_default() }
+  #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+  { String::new() }
+}
+
+/// Window the user is working in: the foreground window, or the one stored by
+/// prepare_quick_actions when one of our own windows has focus.
+pub fn target_window_handle_raw() -> Option<isize> {
+  #[cfg(target_os = "windows")]
+  unsafe {
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+    let h = GetForegroundWindow();
+    let mut pid: u32 = 0;
+    let _ = GetWindowThreadProcessId(h, Some(&mut pid));
+    if !h.0.is_null() && pid != std::process::id() {
+      return Some(h.0 as isize);
+    }
+    last_foreground_handle_raw()
+  }
+  #[cfg(target_os = "linux")]
+  {
+    let own = std::process::id().to_string();
+    crate::linux_input::active_window()
+      .filter(|&w| crate::linux_input::window_pid(w).as_deref() != Some(own.as_str()))
+      .or_else(last_foreground_handle_raw)
+  }
+  #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+  { None }
+}
+
+/// Bring a window from `target_window_handle_raw` back to the foreground; false when it could not
+/// be activated.
+pub fn activate_window_raw(hraw: isize) -> bool {
+  #[cfg(target_os = "windows")]
+  unsafe {
+    use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+    SetForegroundWindow(HWND(hraw as *mut c_void)).as_bool()
   }
+  #[cfg(target_os = "linux")]
+  { crate::linux_input::activate_window(hraw) }
+  #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+  { let _ = hraw; false }
+}
+
+// UI actions and quick insertions
+
+#[tauri::command]
+pub fn prompt_action(app: tauri::AppHandle, safe_mode: Option<bool>) -> Result<String, String> {
+  // Copy the current selection using the focused app's capture strategy
+  let profile = crate::app_profiles::profile_for_foreground();
+  let captured = crate::clipboard::capture_selection_for(crate::clipboard::SAFE_PROMPT_ACTION, safe_mode, &profile)?;
 
   // Bring main window to front and emit event with selection details
   if let Some(win) = app.get_webview_window("main") { let _ = win.show(); let _ = win.set_focus(); }
-  let payload = serde_json::json!({ "text": selection });
+  let payload = serde_json::json!({ "text": captured.text, "html": captured.html });
   let _ = app.emit("prompt:new-conversation", payload);
   Ok("ok".to_string())
 }
@@ -78,6 +171,9 @@ pub fn prepare_quick_actions() -> Result<(), String> {
   if let Ok(mut guard) = LAST_SELECTED_TEXT.lock() {
     guard.clear();
   }
+  if let Ok(mut guard) = LAST_SELECTED_HTML.lock() {
+    *guard = None;
+  }
   #[cfg(target_os = "windows")]
   unsafe {
     use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
@@ -85,17 +181,21 @@ pub fn prepare_quick_actions() -> Result<(), String> {
     let mut guard = LAST_FOREGROUND.lock().map_err(|_| "lock poisoned".to_string())?;
     *guard = Some(h.0 as isize);
   }
+  #[cfg(target_os = "linux")]
+  {
+    let mut guard = LAST_FOREGROUND.lock().map_err(|_| "lock poisoned".to_string())?;
+    *guard = crate::linux_input::active_window();
+  }
   Ok(())
 }
 
 /// Refocus the previously active native window (if available) and copy the current
 /// selection using Ctrl+C, then restore focus to the Quick Actions window. Returns
-/// the copied text. When safe_mode is true, this just returns the current clipboard.
+/// the copied text. In safe mode (see `clipboard::resolve_safe_mode`) this just returns the current clipboard.
 #[tauri::command]
 pub fn focus_prev_then_copy_selection(app: tauri::AppHandle, safe_mode: Option<bool>) -> Result<String, String> {
-  let safe = safe_mode.unwrap_or(false);
-  let mut clipboard = Clipboard::new().map_err(|e| format!("clipboard init failed: {e}"))?;
-  let previous_text = if !safe { clipboard.get_text().ok() } else { None };
+  let profile = crate::app_profiles::profile_for_last_foreground();
+  let safe = crate::clipboard::resolve_safe_mode(crate::clipboard::SAFE_QUICK_PROMPTS, safe_mode, &profile);
 
   if !safe {
     #[cfg(target_os = "windows")]
@@ -106,25 +206,25 @@ pub fn focus_prev_then_copy_selection(app: tauri::AppHandle, safe_mode: Option<b
           let hwnd = HWND(hraw as *mut c_void);
           // Only SetForegroundWindow — no ShowWindow(SW_RESTORE) to avoid resizing maximized windows
           let _ = SetForegroundWindow(hwnd);
-          thread::sleep(Duration::from_millis(80));
+          thread::sleep(Duration::from_millis(profile.focus_delay_ms));
         }
       }
     }
-
-    let mut enigo = Enigo::new();
-    enigo.key_down(Key::Control);
-    enigo.key_click(Key::Layout('c'));
-    enigo.key_up(Key::Control);
-    thread::sleep(Duration::from_millis(140));
+    #[cfg(target_os = "linux")]
+    if let Some(id) = last_foreground_handle_raw() {
+      if crate::linux_input::activate_window(id) {
+        thread::sleep(Duration::from_millis(profile.focus_delay_ms));
+      }
+    }
   }
 
-  let selection = clipboard.get_text().unwrap_or_default();
+  let captured = crate::clipboard::capture_selection(safe, &profile)?;
+  let selection = captured.text;
   if let Ok(mut guard) = LAST_SELECTED_TEXT.lock() {
     *guard = selection.clone();
   }
-
-  if !safe {
-    if let Some(prev) = previous_text { let _ = clipboard.set_text(prev); }
+  if let Ok(mut guard) = LAST_SELECTED_HTML.lock() {
+    *guard = captured.html;
   }
 
   // Restore focus to quick-actions so the user sees the preview update
@@ -136,6 +236,13 @@ pub fn focus_prev_then_copy_selection(app: tauri::AppHandle, safe_mode: Option<b
   Ok(selection)
 }
 
+/// Return the HTML flavor of the selection captured by `focus_prev_then_copy_selection`,
+/// or None when no HTML was available or rich capture is disabled.
+#[tauri::command]
+pub fn get_last_selection_html() -> Result<Option<String>, String> {
+  Ok(last_selected_html())
+}
+
 /// Refocus the previously stored foreground window (from prepare_quick_actions).
 /// Used to restore focus to the correct app before pasting STT results.
 #[tauri::command]
@@ -151,6 +258,10 @@ pub fn refocus_previous_app() -> Result<(), String> {
       }
     }
   }
+  #[cfg(target_os = "linux")]
+  if let Some(id) = last_foreground_handle_raw() {
+    let _ = crate::linux_input::activate_window(id);
+  }
   Ok(())
 }
 
@@ -179,23 +290,162 @@ pub fn insert_prompt_text(app: tauri::AppHandle, text: String) -> Result<(), Str
   Ok(())
 }
 
+/// Insert text into the focused application. `method` overrides the configured insert
+/// method: "paste" (clipboard + Ctrl+V), "type" (Unicode key injection) or "auto" (paste,
+/// except for apps that block paste such as terminals, which are typed into).
 #[tauri::command]
-pub fn insert_text_into_focused_app(text: String, safe_mode: Option<bool>) -> Result<(), String> {
-  let safe = safe_mode.unwrap_or(false);
+pub fn insert_text_into_focused_app(text: String, safe_mode: Option<bool>, method: Option<String>) -> Result<(), String> {
+  let method = method.map(|m| m.trim().to_lowercase()).filter(|m| m == "paste" || m == "type" || m == "auto");
+  let process_name = foreground_process_name();
+  let profile = crate::app_profiles::profile_for_process(&process_name);
+  if resolve_insert_method(method.as_deref(), &process_name) == "type" {
+    type_text(&text, profile.typing_rate_cps.unwrap_or_else(crate::config::get_insert_typing_rate_from_settings));
+    return Ok(());
+  }
+  paste_text(text, safe_mode.unwrap_or(false), &profile)
+}
+
+/// Insert text into a specific window (from `target_window_handle_raw` at capture time) rather
+/// than whatever has focus when a slow request finishes. The window is brought back to the front
+/// first; when it was closed or cannot be focused, nothing is inserted and an error is returned.
+pub fn insert_text_into_window(text: String, window: Option<isize>, safe_mode: Option<bool>, method: Option<String>) -> Result<(), String> {
+  if let Some(hraw) = window {
+    let profile = crate::app_profiles::profile_for_process(&process_name_for_window(hraw));
+    focus_window_for_insert(hraw, profile.focus_delay_ms)?;
+  }
+  insert_text_into_focused_app(text, safe_mode, method)
+}
+
+#[cfg(target_os = "windows")]
+fn focus_window_for_insert(hraw: isize, focus_delay_ms: u64) -> Result<(), String> {
+  use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, IsWindow, SetForegroundWindow};
+  unsafe {
+    let hwnd = HWND(hraw as *mut c_void);
+    if !IsWindow(hwnd).as_bool() {
+      return Err(STALE_WINDOW_ERROR.into());
+    }
+    if GetForegroundWindow() == hwnd {
+      return Ok(());
+    }
+    // Only SetForegroundWindow — no ShowWindow(SW_RESTORE) to avoid resizing maximized windows
+    let _ = SetForegroundWindow(hwnd);
+    thread::sleep(Duration::from_millis(focus_delay_ms));
+    if GetForegroundWindow() != hwnd {
+      return Err(UNFOCUSABLE_WINDOW_ERROR.into());
+    }
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn focus_window_for_insert(hraw: isize, focus_delay_ms: u64) -> Result<(), String> {
+  if crate::linux_input::active_window() == Some(hraw) {
+    return Ok(());
+  }
+  if crate::linux_input::window_pid(hraw).is_none() {
+    return Err(STALE_WINDOW_ERROR.into());
+  }
+  if !crate::linux_input::activate_window(hraw) {
+    return Err(UNFOCUSABLE_WINDOW_ERROR.into());
+  }
+  thread::sleep(Duration::from_millis(focus_delay_ms));
+  Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn focus_window_for_insert(_hraw: isize, _focus_delay_ms: u64) -> Result<(), String> {
+  Ok(())
+}
+
+pub const STALE_WINDOW_ERROR: &str = "The window the text was selected in has been closed";
+const UNFOCUSABLE_WINDOW_ERROR: &str = "Could not bring the window the text was selected in back to the front";
+
+// Apps where Ctrl+V is commonly blocked or remapped; "auto" types into these instead.
+// Linux entries are /proc comm names (truncated to 15 characters).
+const TYPE_BY_DEFAULT_APPS: [&str; 17] = [
+  "windowsterminal.exe", "conhost.exe", "cmd.exe", "powershell.exe", "pwsh.exe",
+  "mintty.exe", "putty.exe", "alacritty.exe", "wezterm-gui.exe", "kitty.exe",
+  "gnome-terminal-", "konsole", "xterm", "alacritty", "kitty", "wezterm-gui", "xfce4-terminal",
+];
+
+/// Resolve the effective insert method ("paste" or "type") for a process name. An explicit
+/// "paste" or "type" wins; otherwise the app profile's paste_method, then
+/// `insert_method_overrides`, then the configured insert method.
+pub fn resolve_insert_method(method: Option<&str>, process_name: &str) -> &'static str {
+  match method {
+    Some("type") => return "type",
+    Some("paste") => return "paste",
+    _ => {}
+  }
+  if let Some(profile) = crate::app_profiles::configured_profile(process_name) {
+    return if profile.paste_method == "type" { "type" } else { "paste" };
+  }
+  let proc_lower = process_name.trim().to_lowercase();
+  if !proc_lower.is_empty() {
+    if let Some(m) = crate::config::get_insert_method_overrides_from_settings().get(&proc_lower) {
+      return if m == "type" { "type" } else { "paste" };
+    }
+  }
+  let method = method.map(str::to_string).unwrap_or_else(crate::config::get_insert_method_from_settings);
+  match method.as_str() {
+    "type" => "type",
+    "auto" if TYPE_BY_DEFAULT_APPS.contains(&proc_lower.as_str()) => "type",
+    _ => "paste",
+  }
+}
+
+/// Clipboard + paste shortcut insertion with best-effort clipboard restore (unless safe).
+/// The profile selects Ctrl+V or Shift+Insert and how long to wait before restoring.
+pub fn paste_text(text: String, safe: bool, profile: &crate::app_profiles::AppProfile) -> Result<(), String> {
   let mut clipboard = Clipboard::new().map_err(|e| format!("clipboard init failed: {e}"))?;
-  let previous_text = if !safe { clipboard.get_text().ok() } else { None };
+  let previous = if !safe { Some(crate::clipboard::ClipboardSnapshot::take(&mut clipboard)) } else { None };
   let _ = clipboard.set_text(text);
-  {
+  let shift_insert = profile.paste_method == "shift_insert";
+  #[cfg(target_os = "linux")]
+  let sent = if shift_insert { crate::linux_input::send_chord("shift", "Insert") } else { crate::linux_input::send_chord("ctrl", "v") };
+  #[cfg(not(target_os = "linux"))]
+  let sent = false;
+  if !sent {
     let mut enigo = Enigo::new();
-    enigo.key_down(Key::Control);
-    enigo.key_click(Key::Layout('v'));
-    enigo.key_up(Key::Control);
+    // enigo 0.1 has no Insert key; VK_INSERT only exists on Windows, elsewhere Ctrl+V is sent
+    if shift_insert && cfg!(target_os = "windows") {
+      enigo.key_down(Key::Shift);
+      enigo.key_click(Key::Raw(0x2D)); // VK_INSERT
+      enigo.key_up(Key::Shift);
+    } else {
+      enigo.key_down(Key::Control);
+      enigo.key_click(Key::Layout('v'));
+      enigo.key_up(Key::Control);
+    }
   }
-  thread::sleep(Duration::from_millis(120));
-  if !safe { if let Some(prev) = previous_text { let _ = clipboard.set_text(prev); } }
+  thread::sleep(Duration::from_millis(profile.paste_delay_ms));
+  if let Some(prev) = previous { prev.restore(&mut clipboard); }
   Ok(())
 }
 
+/// Type text via Unicode key injection (SendInput on Windows). `chars_per_sec` of 0 types
+/// as fast as possible; newlines and tabs are sent as Return/Tab key presses.
+pub fn type_text(text: &str, chars_per_sec: u32) {
+  let mut enigo = Enigo::new();
+  let delay = if chars_per_sec == 0 { None } else { Some(Duration::from_micros(1_000_000 / chars_per_sec as u64)) };
+  let mut pending = String::new();
+  for ch in text.chars() {
+    match ch {
+      '\r' => continue,
+      '\n' | '\t' => {
+        if !pending.is_empty() { enigo.key_sequence(&pending); pending.clear(); }
+        enigo.key_click(if ch == '\n' { Key::Return } else { Key::Tab });
+      }
+      _ => {
+        pending.push(ch);
+        if delay.is_some() { enigo.key_sequence(&pending); pending.clear(); }
+      }
+    }
+    if let Some(d) = delay { thread::sleep(d); }
+  }
+  if !pending.is_empty() { enigo.key_sequence(&pending); }
+}
+
 /// Return the work area (taskbar-excluded) of the monitor under `probe`, in
 /// physical pixels as `(left, top, right, bottom)`. Falls back to the whole
 /// virtual screen if the monitor query fails. So edge detection is relative to
@@ -228,8 +478,10 @@ unsafe fn work_area_for_point(probe: windows::Win32::Foundation::POINT) -> (i32,
 // placed below+right of the anchor by default, but flips above and/or to the
 // left when there isn't room on that side, then is clamped into the monitor
 // work area so it stays fully visible even when the cursor is in an edge zone.
+// `label` positions another popup (e.g. "paste-history") the same way.
 #[tauri::command]
-pub fn position_quick_actions(app: tauri::AppHandle) -> Result<(), String> {
+pub fn position_quick_actions(app: tauri::AppHandle, label: Option<String>) -> Result<(), String> {
+  let label = label.unwrap_or_else(|| "quick-actions".to_string());
   #[cfg(target_os = "windows")]
   {
     use windows::Win32::Foundation::POINT;
@@ -242,14 +494,14 @@ pub fn position_quick_actions(app: tauri::AppHandle) -> Result<(), String> {
     // Use the popup's actual size (physical px) so flip decisions are accurate;
     // fall back to the configured defaults if the window can't be measured yet.
     let (popup_w, popup_h) = app
-      .get_webview_window("quick-actions")
+      .get_webview_window(&label)
       .and_then(|w| w.outer_size().ok())
       .map(|s| (s.width as i32, s.height as i32))
       .filter(|(w, h)| *w > 0 && *h > 0)
       .unwrap_or((380, 95));
 
     // Wrap everything in catch_unwind so a panic never kills the app
-    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    let result = crate::crash::catch_unwind(std::panic::AssertUnwindSafe(|| {
       unsafe {
         // Candidate edges for the popup, all in screen (physical) coordinates:
         //   right_x  = popup left edge when placed to the RIGHT of the anchor
@@ -335,13 +587,13 @@ pub fn position_quick_actions(app: tauri::AppHandle) -> Result<(), String> {
       }
     };
 
-    if let Some(win) = app.get_webview_window("quick-actions") {
+    if let Some(win) = app.get_webview_window(&label) {
       let _ = win.set_position(tauri::Position::Physical(PhysicalPosition::new(x, y)));
     }
     Ok(())
   }
   #[cfg(not(target_os = "windows"))]
-  { Ok(()) }
+  { let _ = (app, label); Ok(()) }
 }
 
 /// Clamp the quick-actions window to screen bounds after a resize.
@@ -449,28 +701,10 @@ pub fn dump_key_log(text: String) -> Result<String, String> {
 
 #[tauri::command]
 pub async fn tts_selection(app: tauri::AppHandle, safe_mode: Option<bool>) -> Result<String, String> {
-  let safe = safe_mode.unwrap_or(false);
-
   // Clipboard + Enigo + sleep are blocking — run on a dedicated thread to avoid starving the async runtime
   let selection = tokio::task::spawn_blocking(move || -> Result<String, String> {
-    let mut clipboard = Clipboard::new().map_err(|e| format!("clipboard init failed: {e}"))?;
-    let previous_text = if !safe { clipboard.get_text().ok() } else { None };
-
-    if !safe {
-      let mut enigo = Enigo::new();
-      enigo.key_down(Key::Control);
-      enigo.key_click(Key::Layout('c'));
-      enigo.key_up(Key::Control);
-      thread::sleep(Duration::from_millis(120));
-    }
-
-    let selection = clipboard.get_text().unwrap_or_default();
-
-    if !safe {
-      if let Some(prev) = previous_text { let _ = clipboard.set_text(prev); }
-    }
-
-    Ok(selection)
+    let profile = crate::app_profiles::profile_for_foreground();
+    crate::clipboard::capture_tts_text(safe_mode, &profile)
   }).await.map_err(|e| format!("spawn_blocking failed: {e}"))??;
 
   if selection.trim().is_empty() {
@@ -478,42 +712,11 @@ pub async fn tts_selection(app: tauri::AppHandle, safe_mode: Option<bool>) -> Re
     return Err("No text selected".into());
   }
 
-  // Read user TTS settings
-  let settings = crate::config::load_settings_json();
-  let engine = settings.get("tts_engine").and_then(|x| x.as_str()).unwrap_or("local");
-  let rate = settings.get("tts_rate").and_then(|x| x.as_i64()).unwrap_or(-2).clamp(-10, 10) as i32;
-  let vol = settings.get("tts_volume").and_then(|x| x.as_i64()).unwrap_or(100).clamp(0, 100) as u8;
-
-  if engine == "openai" {
-    let voice = settings.get("tts_openai_voice").and_then(|x| x.as_str()).unwrap_or("alloy").to_string();
-    let model = settings.get("tts_openai_model").and_then(|x| x.as_str()).unwrap_or("gpt-4o-mini-tts").to_string();
-    let wav = crate::tts_openai_synthesize_wav(selection.clone(), Some(voice), Some(model), Some(rate), Some(vol)).await?;
-    #[cfg(target_os = "windows")]
-    { crate::utils::play_wav_blocking_windows(&app, &wav)?; }
-    #[cfg(not(target_os = "windows"))]
-    {
-      let _ = (selection);
-      let msg = "OpenAI TTS playback not implemented on this platform".to_string();
-      let _ = app.emit("tts:error", serde_json::json!({ "message": msg }));
-      return Err(msg);
-    }
-    Ok("ok".into())
-  } else {
-    // local_speak_blocking is blocking — run on dedicated thread
-    #[cfg(target_os = "windows")]
-    {
-      let voice = settings.get("tts_voice_local").and_then(|x| x.as_str()).unwrap_or("").to_string();
-      tokio::task::spawn_blocking(move || {
-        crate::tts::local_speak_blocking(selection, voice, rate, vol)
-      }).await.map_err(|e| format!("spawn_blocking failed: {e}"))??;
-      Ok("ok".into())
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-      let _ = (selection);
-      let msg = "TTS not implemented on this platform".to_string();
-      let _ = app.emit("tts:error", serde_json::json!({ "message": msg }));
-      Err(msg)
-    }
+  // Long selections are read in sentence chunks (tts_queue.rs) with the configured engine, so
+  // speech starts after the first few sentences and can be skipped through
+  if let Err(msg) = crate::tts::speak(&app, &selection).await {
+    let _ = app.emit("tts:error", serde_json::json!({ "message": msg }));
+    return Err(msg);
   }
+  Ok("ok".into())
 }
@@ -1,12 +1,14 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
-use std::{thread, time::Duration};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-use arboard::Clipboard;
-use enigo::{Enigo, Key, KeyboardControllable};
+use once_cell::sync::Lazy;
+use serde::Serialize;
 use tauri::{Manager, Emitter};
 
-use crate::config::{get_api_key_from_settings_or_env, get_model_from_settings_or_env, get_temperature_from_settings_or_env};
+use crate::config::{get_model_from_settings_or_env, get_temperature_from_settings_or_env};
 
 pub fn quick_prompts_config_path() -> Option<PathBuf> {
   #[cfg(target_os = "windows")]
@@ -32,318 +34,503 @@ pub fn quick_prompts_config_path() -> Option<PathBuf> {
   }
 }
 
-// Runs a predefined quick prompt (1–9) on the current selection and opens the main window with the AI result.
-// Uses aggressive copy-restore by default unless safe_mode is true.
-#[tauri::command]
-pub async fn run_quick_prompt(app: tauri::AppHandle, index: u8, safe_mode: Option<bool>) -> Result<(), String> {
-  if index < 1 || index > 9 { return Err("Quick prompt index must be 1-9".into()); }
-  let safe = safe_mode.unwrap_or(false);
-
-  // Capture selection text (duplication kept for clarity and simplicity)
-  let mut clipboard = Clipboard::new().map_err(|e| format!("clipboard init failed: {e}"))?;
-  let previous_text = if !safe { clipboard.get_text().ok() } else { None };
-
-  if !safe {
-    let mut enigo = Enigo::new();
-    enigo.key_down(Key::Control);
-    enigo.key_click(Key::Layout('c'));
-    enigo.key_up(Key::Control);
-    thread::sleep(Duration::from_millis(120));
+// Where a quick prompt result goes: inserted into the focused app, shown for review (the popup
+// preview, or the main window for `run_quick_prompt`) or copied to the clipboard
+pub const OUTPUT_INSERT: &str = "insert";
+pub const OUTPUT_PREVIEW: &str = "preview";
+pub const OUTPUT_CLIPBOARD: &str = "clipboard";
+
+/// One entry of quick_prompts.json: either a plain template string or an object whose optional
+/// fields override the quick prompt settings for this prompt only:
+/// `{ "template": "...", "model": "gpt-4o-mini", "temperature": 0.2, "system_prompt": "...", "output": "clipboard" }`
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct QuickPrompt {
+  pub template: String,
+  pub model: Option<String>,
+  pub temperature: Option<f32>,
+  /// Replaces `quick_prompt_system_prompt` / `system_prompt`
+  pub system_prompt: Option<String>,
+  /// "insert" | "preview" | "clipboard"; None follows `show_quick_prompt_result_in_popup`
+  pub output: Option<String>,
+  /// Set when the slot is bound to a prompt library entry (prompt_library.rs) instead
+  pub library_id: Option<String>,
+}
+
+impl QuickPrompt {
+  fn default_for(index: u8) -> Self {
+    Self { template: quick_prompt_template(index).to_string(), ..Default::default() }
   }
 
-  let selection = clipboard.get_text().unwrap_or_default();
+  // None for entries that are neither a string nor an object with a string `template`
+  fn parse(v: &serde_json::Value) -> Option<Self> {
+    if let Some(s) = v.as_str() {
+      return Some(Self { template: s.to_string(), ..Default::default() });
+    }
+    let obj = v.as_object()?;
+    let text = |k: &str| obj.get(k).and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    Some(Self {
+      template: obj.get("template")?.as_str()?.to_string(),
+      model: text("model"),
+      temperature: obj.get("temperature").and_then(|x| x.as_f64()).map(|t| t.clamp(0.0, 2.0) as f32),
+      system_prompt: text("system_prompt"),
+      output: text("output").map(|o| o.to_lowercase()).filter(|o| [OUTPUT_INSERT, OUTPUT_PREVIEW, OUTPUT_CLIPBOARD].contains(&o.as_str())),
+      library_id: None,
+    })
+  }
 
-  if !safe {
-    if let Some(prev) = previous_text {
-      let _ = clipboard.set_text(prev);
+  // Prompts without overrides stay plain strings so the file remains readable by older versions
+  fn to_json(&self) -> serde_json::Value {
+    if self.model.is_none() && self.temperature.is_none() && self.system_prompt.is_none() && self.output.is_none() {
+      return serde_json::Value::String(self.template.clone());
     }
+    let mut obj = serde_json::Map::new();
+    obj.insert("template".to_string(), serde_json::json!(self.template));
+    if let Some(m) = &self.model { obj.insert("model".to_string(), serde_json::json!(m)); }
+    if let Some(t) = self.temperature { obj.insert("temperature".to_string(), serde_json::json!(t)); }
+    if let Some(sp) = &self.system_prompt { obj.insert("system_prompt".to_string(), serde_json::json!(sp)); }
+    if let Some(o) = &self.output { obj.insert("output".to_string(), serde_json::json!(o)); }
+    serde_json::Value::Object(obj)
   }
+}
 
-  // If empty selection, open main window with a friendly message.
-  if selection.trim().is_empty() {
-    let _ = crate::quick_actions::open_prompt_with_text(app, "No selection. Type your input or paste it here.".to_string());
-    return Ok(());
+// Entry `index` (1-based) of an array or "1".."9" object
+fn entry_at(v: &serde_json::Value, index: u8) -> Option<&serde_json::Value> {
+  match v {
+    serde_json::Value::Array(arr) => arr.get((index as usize).saturating_sub(1)),
+    serde_json::Value::Object(map) => map.get(&index.to_string()),
+    _ => None,
   }
+}
 
-  // Build messages: global system prompt + quick template; user is raw selection
-  let template = load_quick_prompt_template_with_notify(Some(&app), index);
-  let settings = crate::config::load_settings_json();
-  // Prefer a dedicated quick prompts system prompt when provided; fall back to global
-  let base_candidate = {
-    let qp = settings
-      .get("quick_prompt_system_prompt")
-      .and_then(|x| x.as_str())
-      .unwrap_or("")
-      .trim();
-    if qp.is_empty() {
-      settings
-        .get("system_prompt")
-        .and_then(|x| x.as_str())
-        .unwrap_or("")
-        .trim()
-        .to_string()
-    } else {
-      qp.to_string()
-    }
+// Template variables. `{name}` (or `{{name}}`, as the prompt library first wrote them) becomes:
+//   selection   the captured selection; the filled template is then sent as the user message
+//               instead of the bare selection
+//   clipboard   current clipboard text
+//   app_name    executable of the app the user works in, e.g. "OUTLOOK.EXE"
+//   date        today's date, YYYY-MM-DD
+//   language    the user's display language, e.g. "English (United States)"
+//   url         URL of the browser tab the user is looking at (browser_context.rs), else empty
+//   page_title  title of that tab
+// Braces around any other text are kept as written (JSON examples stay intact), and `\{` writes a
+// literal `{`, so `\{date}` stays as text.
+const VAR_SELECTION: &str = "selection";
+const TEMPLATE_VARS: &[&str] = &[VAR_SELECTION, "clipboard", "app_name", "date", "language", "url", "page_title"];
+
+// The known variable `{name}` / `{{name}}` at the start of `s`, with the length it spans
+fn placeholder_at(s: &str) -> Option<(&'static str, usize)> {
+  let (inner, open, close) = match s.strip_prefix("{{") {
+    Some(inner) if inner.contains("}}") => (inner, 2, "}}"),
+    _ => (s.strip_prefix('{')?, 1, "}"),
   };
-  let base = base_candidate;
-  let system_content = if base.is_empty() {
-    template.clone()
-  } else {
-    format!("{base}\n\n{template}")
+  let end = inner.find(close)?;
+  let name = TEMPLATE_VARS.iter().find(|v| **v == &inner[..end])?;
+  Some((name, open + end + close.len()))
+}
+
+// One pass over `template`; `value` returns None to leave a placeholder as written
+fn expand_template(template: &str, mut value: impl FnMut(&'static str) -> Option<String>) -> String {
+  let mut out = String::with_capacity(template.len());
+  let mut rest = template;
+  while let Some(i) = rest.find(['\\', '{']) {
+    out.push_str(&rest[..i]);
+    rest = &rest[i..];
+    if let Some(after) = rest.strip_prefix("\\{") {
+      out.push('{');
+      rest = after;
+      continue;
+    }
+    match placeholder_at(rest).and_then(|(name, len)| value(name).map(|v| (v, len))) {
+      Some((v, len)) => {
+        out.push_str(&v);
+        rest = &rest[len..];
+      }
+      None => {
+        // A lone backslash or brace, both one byte
+        out.push_str(&rest[..1]);
+        rest = &rest[1..];
+      }
+    }
+  }
+  out.push_str(rest);
+  out
+}
+
+#[cfg(target_os = "windows")]
+fn user_language() -> String {
+  use windows::Globalization::{ApplicationLanguages, Language};
+  ApplicationLanguages::Languages()
+    .and_then(|langs| langs.GetAt(0))
+    .and_then(|tag| Language::CreateLanguage(&tag)?.DisplayName())
+    .map(|name| name.to_string())
+    .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn user_language() -> String {
+  // "de_DE.UTF-8" -> "de-DE"
+  ["LC_ALL", "LC_MESSAGES", "LANG"]
+    .iter()
+    .filter_map(|k| std::env::var(k).ok())
+    .map(|v| v.split(['.', '@']).next().unwrap_or_default().replace('_', "-"))
+    .find(|v| !v.is_empty() && v != "C" && v != "POSIX")
+    .unwrap_or_default()
+}
+
+/// A quick prompt template with the values of its variables, read once when the prompt is
+/// resolved. {selection} is filled by `fill`, since callers compose the selection content later.
+pub(crate) struct PromptTemplate {
+  text: String,
+  values: HashMap<&'static str, String>,
+  pub uses_selection: bool,
+}
+
+impl PromptTemplate {
+  pub fn new(text: &str) -> Self {
+    let mut used: Vec<&'static str> = Vec::new();
+    expand_template(text, |name| {
+      if !used.contains(&name) { used.push(name); }
+      None
+    });
+    let mut page = None;
+    let mut values = HashMap::new();
+    for name in used.iter().copied().filter(|n| *n != VAR_SELECTION) {
+      let value = match name {
+        "clipboard" => arboard::Clipboard::new().and_then(|mut c| c.get_text()).unwrap_or_default(),
+        "app_name" => crate::quick_actions::target_window_handle_raw().map(crate::quick_actions::process_name_for_window).unwrap_or_default(),
+        "date" => chrono::Local::now().format("%Y-%m-%d").to_string(),
+        "language" => user_language(),
+        _ => {
+          let page = page.get_or_insert_with(crate::browser_context::current_page);
+          match (name, page.as_ref()) {
+            ("url", Some(p)) => p.url.clone(),
+            ("page_title", Some(p)) => p.title.clone(),
+            _ => String::new(),
+          }
+        }
+      };
+      values.insert(name, value);
+    }
+    Self { text: text.to_string(), values, uses_selection: used.contains(&VAR_SELECTION) }
+  }
+
+  /// The template with its variables filled in, {selection} with `selection`.
+  pub fn fill(&self, selection: &str) -> String {
+    expand_template(&self.text, |name| match name {
+      VAR_SELECTION => Some(selection.to_string()),
+      _ => self.values.get(name).cloned(),
+    })
+  }
+}
+
+/// A quick prompt with its overrides applied on top of the settings.
+pub(crate) struct ResolvedQuickPrompt {
+  pub system_content: String,
+  /// Template containing {selection}: filled and sent as the user message
+  pub user_template: Option<PromptTemplate>,
+  pub model: String,
+  pub temp: Option<f32>,
+  pub output: &'static str,
+}
+
+impl ResolvedQuickPrompt {
+  pub fn user_content(&self, selection: &str, selection_html: Option<&str>) -> String {
+    let content = crate::clipboard::compose_selection_content(selection, selection_html);
+    match &self.user_template {
+      Some(t) => t.fill(&content),
+      None => content,
+    }
+  }
+}
+
+pub(crate) fn resolve_quick_prompt(app: &tauri::AppHandle, index: u8) -> ResolvedQuickPrompt {
+  resolve_prompt(load_quick_prompt_with_notify(Some(app), index))
+}
+
+pub(crate) fn resolve_prompt(prompt: QuickPrompt) -> ResolvedQuickPrompt {
+  let settings = crate::config::load_settings_json();
+  let str_setting = |k: &str| settings.get(k).and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+  // Global system prompt + quick template; the prompt's own, then the active persona's, then a
+  // dedicated quick prompts system prompt win over the global one (the model follows the same order)
+  let persona = crate::personas::active();
+  let base = prompt
+    .system_prompt
+    .clone()
+    .or_else(|| persona.as_ref().and_then(|p| p.system_prompt.clone()))
+    .or_else(|| str_setting("quick_prompt_system_prompt"))
+    .or_else(|| str_setting("system_prompt"));
+  let template = PromptTemplate::new(&prompt.template);
+  let (system_content, user_template) = match (base, template.uses_selection) {
+    (base, true) => (base.unwrap_or_default(), Some(template)),
+    (Some(base), false) => (format!("{base}\n\n{}", template.fill("")), None),
+    (None, false) => (template.fill(""), None),
   };
-  let user_content = selection.clone();
-
-  // Call OpenAI Chat Completions (respect settings overrides)
-  let key = get_api_key_from_settings_or_env()?;
-  // Prefer dedicated quick_prompt_model; fallback to global chat model
-  let model = {
-    let s = settings
-      .get("quick_prompt_model")
-      .and_then(|x| x.as_str())
-      .unwrap_or("")
-      .trim()
-      .to_string();
-    if s.is_empty() { get_model_from_settings_or_env() } else { s }
+  let output = match prompt.output.as_deref() {
+    Some(OUTPUT_PREVIEW) => OUTPUT_PREVIEW,
+    Some(OUTPUT_CLIPBOARD) => OUTPUT_CLIPBOARD,
+    _ => OUTPUT_INSERT,
   };
-  let temp = get_temperature_from_settings_or_env();
+  ResolvedQuickPrompt {
+    system_content,
+    user_template,
+    model: prompt.model.or_else(|| persona.and_then(|p| p.model)).or_else(|| str_setting("quick_prompt_model")).unwrap_or_else(get_model_from_settings_or_env),
+    temp: prompt.temperature.or_else(get_temperature_from_settings_or_env),
+    output,
+  }
+}
 
-  let mut body = serde_json::json!({
-    "model": model,
-    "messages": [
-      { "role": "system", "content": system_content },
-      { "role": "user", "content": user_content }
-    ]
-  });
-  if let Some(t) = temp { if let serde_json::Value::Object(ref mut m) = body { m.insert("temperature".to_string(), serde_json::json!(t)); } }
+// Follow-up sessions: a previewed quick prompt result can be refined ("make it shorter") while the
+// popup is open. The exchange is kept in memory under a frontend-chosen session id and dropped
+// after a few idle minutes.
+const SESSION_TTL: Duration = Duration::from_secs(10 * 60);
+// Follow-ups per session; each one resends the whole exchange
+const MAX_FOLLOW_UPS: usize = 10;
+
+struct FollowUpSession {
+  model: String,
+  temp: Option<f32>,
+  messages: Vec<serde_json::Value>,
+  follow_ups: usize,
+  touched: Instant,
+}
 
-  let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(120)).connect_timeout(std::time::Duration::from_secs(10)).build().unwrap_or_else(|_| reqwest::Client::new());
-  let resp = client
-    .post("https://api.openai.com/v1/chat/completions")
-    .bearer_auth(key)
-    .json(&body)
-    .send()
-    .await
-    .map_err(|e| format!("request failed: {e}"))?;
+static SESSIONS: Lazy<Mutex<HashMap<String, FollowUpSession>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn start_session(session_id: Option<String>, model: &str, temp: Option<f32>, system_content: &str, user_content: &str, out: &str) {
+  let Some(id) = session_id.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) else { return };
+  let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+  sessions.retain(|_, s| s.touched.elapsed() < SESSION_TTL);
+  sessions.insert(id, FollowUpSession {
+    model: model.to_string(),
+    temp,
+    messages: vec![
+      serde_json::json!({ "role": "system", "content": system_content }),
+      serde_json::json!({ "role": "user", "content": user_content }),
+      serde_json::json!({ "role": "assistant", "content": out }),
+    ],
+    follow_ups: 0,
+    touched: Instant::now(),
+  });
+}
 
-  if !resp.status().is_success() {
-    let status = resp.status();
-    let body_text = resp.text().await.unwrap_or_default();
-    return Err(format!("OpenAI error: {status} {body_text}"));
+/// Refine the result of a previewed quick prompt. `session_id` is the id passed to
+/// `run_quick_prompt_result` / `run_quick_prompt_with_selection`; returns the revised text.
+#[tauri::command]
+pub async fn quick_prompt_follow_up(session_id: String, instruction: String) -> Result<String, String> {
+  let instruction = instruction.trim().to_string();
+  if instruction.is_empty() { return Err("Follow-up instruction is empty".into()); }
+  let (model, temp, mut messages) = {
+    let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    sessions.retain(|_, s| s.touched.elapsed() < SESSION_TTL);
+    let s = sessions.get_mut(&session_id).ok_or_else(|| "This quick prompt session has expired; run the prompt again".to_string())?;
+    if s.follow_ups >= MAX_FOLLOW_UPS { return Err(format!("Follow-up limit reached ({MAX_FOLLOW_UPS}); run the prompt again")); }
+    s.touched = Instant::now();
+    (s.model.clone(), s.temp, s.messages.clone())
+  };
+  messages.push(serde_json::json!({ "role": "user", "content": instruction }));
+  let out = complete_messages(&model, temp, &messages).await?;
+  crate::recent_outputs::record(crate::recent_outputs::QUICK_PROMPT, "Quick prompt follow-up", &out);
+  messages.push(serde_json::json!({ "role": "assistant", "content": out }));
+  if let Some(s) = SESSIONS.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&session_id) {
+    s.messages = messages;
+    s.follow_ups += 1;
+    s.touched = Instant::now();
   }
+  Ok(out)
+}
 
-  let v: serde_json::Value = resp.json().await.map_err(|e| format!("json error: {e}"))?;
-  let text = v.get("choices")
-    .and_then(|c| c.get(0))
-    .and_then(|c| c.get("message"))
-    .and_then(|m| m.get("content"))
-    .and_then(|t| t.as_str())
-    .unwrap_or("")
-    .to_string();
+// Runs a predefined quick prompt (1–9) on the current selection and delivers the AI result per the
+// prompt's `output`: inserted into the focused app (default), copied, or shown in the main window.
+// Uses aggressive copy-restore unless safe mode applies (`safe_mode`, app profile or `safe_mode_defaults`).
+#[tauri::command]
+pub async fn run_quick_prompt(app: tauri::AppHandle, index: u8, safe_mode: Option<bool>) -> Result<(), String> {
+  if index < 1 || index > 9 { return Err("Quick prompt index must be 1-9".into()); }
 
-  let out = if text.trim().is_empty() { "No response received.".to_string() } else { text };
+  // The result goes back into this window, even if focus moves while the model answers
+  let target_window = crate::quick_actions::target_window_handle_raw();
 
-  // Insert result into the active application: set clipboard -> Ctrl+V -> restore clipboard
-  let after_restore_before_paste = clipboard.get_text().ok();
-  let _ = clipboard.set_text(out);
-  {
-    let mut enigo = Enigo::new();
-    enigo.key_down(Key::Control);
-    enigo.key_click(Key::Layout('v'));
-    enigo.key_up(Key::Control);
+  // Capture selection text using the focused app's capture strategy
+  let profile = crate::app_profiles::profile_for_foreground();
+  let captured = crate::clipboard::capture_selection_for(crate::clipboard::SAFE_QUICK_PROMPTS, safe_mode, &profile)?;
+  let selection = captured.text;
+  let selection_html = captured.html;
+
+  // If empty selection, open main window with a friendly message.
+  if selection.trim().is_empty() {
+    let _ = crate::quick_actions::open_prompt_with_text(app, "No selection. Type your input or paste it here.".to_string());
+    return Ok(());
   }
-  thread::sleep(Duration::from_millis(120));
-  if let Some(prev) = after_restore_before_paste {
-    let _ = clipboard.set_text(prev);
+
+  // Template, system prompt, model and temperature, with the prompt's own overrides applied
+  let prompt = resolve_quick_prompt(&app, index);
+  let pii = crate::pii::enabled_for_prompt(index);
+  let source = format!("quick_prompt:{index}");
+  let selection = crate::pii::filter_text(&app, &source, selection, pii);
+  let selection_html = selection_html.map(|h| crate::pii::filter_text(&app, &source, h, pii));
+  let user_content = prompt.user_content(&selection, selection_html.as_deref());
+
+  let out = complete_quick_prompt(&prompt.model, prompt.temp, &prompt.system_content, &user_content).await?;
+  crate::recent_outputs::record(crate::recent_outputs::QUICK_PROMPT, format!("Quick prompt {index}"), &out);
+
+  match prompt.output {
+    // Open the main window with the result instead of inserting it
+    OUTPUT_PREVIEW => crate::quick_actions::open_prompt_with_text(app, out),
+    OUTPUT_CLIPBOARD => crate::quick_actions::copy_text_to_clipboard(out),
+    // Insert result into the source window (paste with clipboard restore, or typing per settings);
+    // if that window is gone the result is kept on the clipboard instead of landing elsewhere
+    _ => crate::quick_actions::insert_text_into_window(out.clone(), target_window, Some(false), None).map_err(|e| {
+      let _ = crate::quick_actions::copy_text_to_clipboard(out);
+      let msg = format!("{e}; the result was copied to the clipboard");
+      let _ = app.emit("command:error", serde_json::json!({ "message": format!("Quick prompt {index}: {msg}") }));
+      msg
+    }),
   }
-  Ok(())
 }
 
 /// Runs a predefined quick prompt (1–9) on the current selection and RETURNS the AI result text
 /// without inserting it into the focused application. Use this for inline preview flows in the
 /// Quick Actions popup. Uses the same selection capture and system prompt composition as
-/// `run_quick_prompt`.
+/// `run_quick_prompt`. With `session_id` the exchange is kept for `quick_prompt_follow_up`.
 #[tauri::command]
-pub async fn run_quick_prompt_result(app: tauri::AppHandle, index: u8, safe_mode: Option<bool>) -> Result<String, String> {
+pub async fn run_quick_prompt_result(app: tauri::AppHandle, index: u8, safe_mode: Option<bool>, session_id: Option<String>) -> Result<String, String> {
   if index < 1 || index > 9 { return Err("Quick prompt index must be 1-9".into()); }
-  let safe = safe_mode.unwrap_or(false);
-
-  // Capture selection text (duplication kept for clarity and simplicity)
-  let mut clipboard = Clipboard::new().map_err(|e| format!("clipboard init failed: {e}"))?;
-  let previous_text = if !safe { clipboard.get_text().ok() } else { None };
-
-  if !safe {
-    let mut enigo = Enigo::new();
-    enigo.key_down(Key::Control);
-    enigo.key_click(Key::Layout('c'));
-    enigo.key_up(Key::Control);
-    thread::sleep(Duration::from_millis(120));
-  }
-
-  let selection = clipboard.get_text().unwrap_or_default();
 
-  if !safe {
-    if let Some(prev) = previous_text {
-      let _ = clipboard.set_text(prev);
-    }
-  }
+  // Capture selection text using the focused app's capture strategy
+  let profile = crate::app_profiles::profile_for_foreground();
+  let captured = crate::clipboard::capture_selection_for(crate::clipboard::SAFE_QUICK_PROMPTS, safe_mode, &profile)?;
+  let selection = captured.text;
+  let selection_html = captured.html;
 
   // If empty selection, return a friendly message for the preview UI.
   if selection.trim().is_empty() {
     return Ok("No selection. Type your input or paste it here.".to_string());
   }
 
-  // Build messages: global system prompt + quick template; user is raw selection
-  let template = load_quick_prompt_template_with_notify(Some(&app), index);
-  let settings = crate::config::load_settings_json();
-  // Prefer a dedicated quick prompts system prompt when provided; fall back to global
-  let base_candidate = {
-    let qp = settings
-      .get("quick_prompt_system_prompt")
-      .and_then(|x| x.as_str())
-      .unwrap_or("")
-      .trim();
-    if qp.is_empty() {
-      settings
-        .get("system_prompt")
-        .and_then(|x| x.as_str())
-        .unwrap_or("")
-        .trim()
-        .to_string()
-    } else {
-      qp.to_string()
-    }
-  };
-  let base = base_candidate;
-  let system_content = if base.is_empty() { template.clone() } else { format!("{base}\n\n{template}") };
-  let user_content = selection.clone();
-
-  // Call OpenAI Chat Completions (respect settings overrides)
-  let key = get_api_key_from_settings_or_env()?;
-  // Prefer dedicated quick_prompt_model; fallback to global chat model
-  let model = {
-    let s = settings
-      .get("quick_prompt_model")
-      .and_then(|x| x.as_str())
-      .unwrap_or("")
-      .trim()
-      .to_string();
-    if s.is_empty() { get_model_from_settings_or_env() } else { s }
-  };
-  let temp = get_temperature_from_settings_or_env();
-
-  let mut body = serde_json::json!({
-    "model": model,
-    "messages": [
-      { "role": "system", "content": system_content },
-      { "role": "user", "content": user_content }
-    ]
-  });
-  if let Some(t) = temp { if let serde_json::Value::Object(ref mut m) = body { m.insert("temperature".to_string(), serde_json::json!(t)); } }
-
-  let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(120)).connect_timeout(std::time::Duration::from_secs(10)).build().unwrap_or_else(|_| reqwest::Client::new());
-  let resp = client
-    .post("https://api.openai.com/v1/chat/completions")
-    .bearer_auth(key)
-    .json(&body)
-    .send()
-    .await
-    .map_err(|e| format!("request failed: {e}"))?;
-
-  if !resp.status().is_success() {
-    let status = resp.status();
-    let body_text = resp.text().await.unwrap_or_default();
-    return Err(format!("OpenAI error: {status} {body_text}"));
-  }
-
-  let v: serde_json::Value = resp.json().await.map_err(|e| format!("json error: {e}"))?;
-  let text = v.get("choices")
-    .and_then(|c| c.get(0))
-    .and_then(|c| c.get("message"))
-    .and_then(|m| m.get("content"))
-    .and_then(|t| t.as_str())
-    .unwrap_or("")
-    .to_string();
-
-  let out = if text.trim().is_empty() { "No response received.".to_string() } else { text };
+  // Template, system prompt, model and temperature, with the prompt's own overrides applied
+  let prompt = resolve_quick_prompt(&app, index);
+  let pii = crate::pii::enabled_for_prompt(index);
+  let source = format!("quick_prompt:{index}");
+  let selection = crate::pii::filter_text(&app, &source, selection, pii);
+  let selection_html = selection_html.map(|h| crate::pii::filter_text(&app, &source, h, pii));
+  let user_content = prompt.user_content(&selection, selection_html.as_deref());
+
+  let out = complete_quick_prompt(&prompt.model, prompt.temp, &prompt.system_content, &user_content).await?;
+  crate::recent_outputs::record(crate::recent_outputs::QUICK_PROMPT, format!("Quick prompt {index}"), &out);
+  start_session(session_id, &prompt.model, prompt.temp, &prompt.system_content, &user_content, &out);
   Ok(out)
 }
 
 /// Same as `run_quick_prompt_result` but uses the provided selection string directly,
 /// avoiding clipboard operations and window focus changes. This is intended for
 /// inline preview flows when the frontend has already captured the selection.
+/// When `selection_html` is omitted, the HTML captured alongside the same selection
+/// by `focus_prev_then_copy_selection` is used. With `session_id` the exchange is kept for
+/// `quick_prompt_follow_up`.
 #[tauri::command]
-pub async fn run_quick_prompt_with_selection(app: tauri::AppHandle, index: u8, selection: String) -> Result<String, String> {
+pub async fn run_quick_prompt_with_selection(app: tauri::AppHandle, index: u8, selection: String, selection_html: Option<String>, session_id: Option<String>) -> Result<String, String> {
   if index < 1 || index > 9 { return Err("Quick prompt index must be 1-9".into()); }
+  let selection_html = selection_html.or_else(|| {
+    if selection == crate::quick_actions::last_selected_text() { crate::quick_actions::last_selected_html() } else { None }
+  });
   // If empty selection, return a friendly message for the preview UI.
   if selection.trim().is_empty() {
     return Ok("No selection. Type your input or paste it here.".to_string());
   }
 
-  // Build messages: global system prompt + quick template; user is raw selection
-  let template = load_quick_prompt_template_with_notify(Some(&app), index);
+  // Template, system prompt, model and temperature, with the prompt's own overrides applied
+  let prompt = resolve_quick_prompt(&app, index);
+  let pii = crate::pii::enabled_for_prompt(index);
+  let source = format!("quick_prompt:{index}");
+  let selection = crate::pii::filter_text(&app, &source, selection, pii);
+  let selection_html = selection_html.map(|h| crate::pii::filter_text(&app, &source, h, pii));
+  let user_content = prompt.user_content(&selection, selection_html.as_deref());
+
+  let out = complete_quick_prompt(&prompt.model, prompt.temp, &prompt.system_content, &user_content).await?;
+  crate::recent_outputs::record(crate::recent_outputs::QUICK_PROMPT, format!("Quick prompt {index}"), &out);
+  start_session(session_id, &prompt.model, prompt.temp, &prompt.system_content, &user_content, &out);
+  Ok(out)
+}
+
+const SUMMARIZE_WINDOW_PROMPT: &str = "Summarize the content of the following application window in 3-7 bullet points. Ignore menus, toolbars and other interface chrome; focus on the document, page or conversation being shown.";
+// Window text sent to the model is capped to keep the request small
+const SUMMARIZE_WINDOW_MAX_CHARS: usize = 60_000;
+
+/// Summarizes the text content of the active window (read through UI Automation, so no
+/// selection or clipboard is involved). Opens the main window with the result via the
+/// `window:summary` event { title, process, summary, chars, truncated } and returns the summary.
+#[tauri::command]
+pub async fn summarize_active_window(app: tauri::AppHandle) -> Result<String, String> {
+  let hwnd = crate::quick_actions::target_window_handle_raw().ok_or_else(|| "No active window to summarize".to_string())?;
+  let process = crate::quick_actions::process_name_for_window(hwnd);
+  let window = tokio::task::spawn_blocking(move || crate::uia::window_text(hwnd, SUMMARIZE_WINDOW_MAX_CHARS))
+    .await
+    .map_err(|e| format!("spawn_blocking failed: {e}"))?
+    .ok_or_else(|| "The active window does not expose its content to UI Automation".to_string())?;
+  if window.text.trim().is_empty() {
+    return Err(format!("No readable text found in \"{}\"", window.title));
+  }
+
   let settings = crate::config::load_settings_json();
-  // Prefer a dedicated quick prompts system prompt when provided; fall back to global
-  let base_candidate = {
-    let qp = settings
-      .get("quick_prompt_system_prompt")
-      .and_then(|x| x.as_str())
-      .unwrap_or("")
-      .trim();
-    if qp.is_empty() {
-      settings
-        .get("system_prompt")
-        .and_then(|x| x.as_str())
-        .unwrap_or("")
-        .trim()
-        .to_string()
-    } else {
-      qp.to_string()
-    }
-  };
-  let base = base_candidate;
-  let system_content = if base.is_empty() { template.clone() } else { format!("{base}\n\n{template}") };
-  let user_content = selection.clone();
-
-  // Call OpenAI Chat Completions (respect settings overrides)
-  let key = get_api_key_from_settings_or_env()?;
-  // Prefer dedicated quick_prompt_model; fallback to global chat model
-  let model = {
-    let s = settings
-      .get("quick_prompt_model")
-      .and_then(|x| x.as_str())
-      .unwrap_or("")
-      .trim()
-      .to_string();
-    if s.is_empty() { get_model_from_settings_or_env() } else { s }
+  let str_setting = |k: &str| settings.get(k).and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+  let template = str_setting("summarize_window_prompt").unwrap_or_else(|| SUMMARIZE_WINDOW_PROMPT.to_string());
+  let persona = crate::personas::active();
+  let system_content = match persona.as_ref().and_then(|p| p.system_prompt.clone()).or_else(|| str_setting("quick_prompt_system_prompt")).or_else(|| str_setting("system_prompt")) {
+    Some(base) => format!("{base}\n\n{template}"),
+    None => template,
   };
+  let user_content = format!("Window: {}\nApplication: {}\n\n{}", window.title, process, window.text);
+  let model = persona.and_then(|p| p.model).or_else(|| str_setting("quick_prompt_model")).unwrap_or_else(get_model_from_settings_or_env);
   let temp = get_temperature_from_settings_or_env();
+  let summary = complete_quick_prompt(&model, temp, &system_content, &user_content).await?;
+  tracing::info!(process = %process, chars = window.text.chars().count(), truncated = window.truncated, "active window summarized");
+
+  if let Some(win) = app.get_webview_window("main") { let _ = win.show(); let _ = win.set_focus(); }
+  let _ = app.emit("window:summary", serde_json::json!({
+    "title": window.title,
+    "process": process,
+    "summary": summary,
+    "chars": window.text.chars().count(),
+    "truncated": window.truncated,
+  }));
+  Ok(summary)
+}
 
-  let mut body = serde_json::json!({
-    "model": model,
-    "messages": [
-      { "role": "system", "content": system_content },
-      { "role": "user", "content": user_content }
-    ]
-  });
+// Chat Completions call shared by the quick prompt commands, sent to `openai_base_url`. Served from
+// the response cache for deterministic (temperature 0) requests when caching is enabled.
+pub(crate) async fn complete_quick_prompt(model: &str, temp: Option<f32>, system_content: &str, user_content: &str) -> Result<String, String> {
+  let mut messages = Vec::new();
+  // Empty when the template is the user message and no system prompt is configured
+  if !system_content.trim().is_empty() {
+    messages.push(serde_json::json!({ "role": "system", "content": system_content }));
+  }
+  messages.push(serde_json::json!({ "role": "user", "content": user_content }));
+  complete_messages(model, temp, &messages).await
+}
+
+async fn complete_messages(model: &str, temp: Option<f32>, messages: &[serde_json::Value]) -> Result<String, String> {
+  let base_url = crate::config::get_openai_base_url_from_settings_or_env();
+  let key = crate::settings::require_chat_key(&base_url).map_err(|e| e.message())?;
+  let mut body = serde_json::json!({ "model": model, "messages": messages });
   if let Some(t) = temp { if let serde_json::Value::Object(ref mut m) = body { m.insert("temperature".to_string(), serde_json::json!(t)); } }
 
-  let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(120)).connect_timeout(std::time::Duration::from_secs(10)).build().unwrap_or_else(|_| reqwest::Client::new());
-  let resp = client
-    .post("https://api.openai.com/v1/chat/completions")
-    .bearer_auth(key)
-    .json(&body)
-    .send()
-    .await
-    .map_err(|e| format!("request failed: {e}"))?;
+  let cache_key = crate::response_cache::cacheable(temp).then(|| crate::response_cache::key(&base_url, model, &body["messages"]));
+  if let Some(hit) = cache_key.as_deref().and_then(crate::response_cache::get) { return Ok(hit); }
+
+  let client = crate::timeouts::client(crate::timeouts::CHAT);
+  let resp = crate::rate_limit::send(crate::config::chat_rate_limit_provider(&base_url), "quick prompt", || {
+    crate::settings::with_chat_key(client.post(format!("{base_url}/chat/completions")), &key).json(&body)
+  })
+  .await
+  .map_err(|e| format!("request failed: {e}"))?;
 
   if !resp.status().is_success() {
     let status = resp.status();
     let body_text = resp.text().await.unwrap_or_default();
-    return Err(format!("OpenAI error: {status} {body_text}"));
+    let server = if crate::config::is_default_openai_base_url(&base_url) { "OpenAI" } else { "Model server" };
+    return Err(format!("{server} error: {status} {body_text}"));
   }
 
   let v: serde_json::Value = resp.json().await.map_err(|e| format!("json error: {e}"))?;
+  crate::usage::record_response(crate::usage::QUICK_PROMPTS, model, &v);
   let text = v.get("choices")
     .and_then(|c| c.get(0))
     .and_then(|c| c.get("message"))
@@ -352,8 +539,9 @@ pub async fn run_quick_prompt_with_selection(app: tauri::AppHandle, index: u8, s
     .unwrap_or("")
     .to_string();
 
-  let out = if text.trim().is_empty() { "No response received.".to_string() } else { text };
-  Ok(out)
+  if text.trim().is_empty() { return Ok("No response received.".to_string()); }
+  if let Some(k) = cache_key { crate::response_cache::put(k, text.clone()); }
+  Ok(text)
 }
 
 pub fn quick_prompt_template(index: u8) -> &'static str {
@@ -371,22 +559,18 @@ pub fn quick_prompt_template(index: u8) -> &'static str {
   }
 }
 
-pub fn load_quick_prompt_template_with_notify(app: Option<&tauri::AppHandle>, index: u8) -> String {
+pub fn load_quick_prompt_with_notify(app: Option<&tauri::AppHandle>, index: u8) -> QuickPrompt {
+  if let Some(prompt) = crate::prompt_library::bound_to(index) {
+    return prompt;
+  }
   if let Some(path) = quick_prompts_config_path() {
     match fs::read_to_string(&path) {
       Ok(text) => {
         match serde_json::from_str::<serde_json::Value>(&text) {
           Ok(v) => {
-            if let Some(arr) = v.as_array() {
-              if let Some(s) = arr.get((index as usize).saturating_sub(1)).and_then(|x| x.as_str()) {
-                return s.to_string();
-              } else {
-                // Missing or invalid entry - silently fallback without toast
-              }
-            } else if let Some(obj) = v.as_object() {
-              let key = index.to_string();
-              if let Some(s) = obj.get(&key).and_then(|x| x.as_str()) {
-                return s.to_string();
+            if v.is_array() || v.is_object() {
+              if let Some(prompt) = entry_at(&v, index).and_then(QuickPrompt::parse) {
+                return prompt;
               } else {
                 // Missing or invalid entry - silently fallback without toast
               }
@@ -422,7 +606,11 @@ pub fn load_quick_prompt_template_with_notify(app: Option<&tauri::AppHandle>, in
       }
     }
   }
-  quick_prompt_template(index).to_string()
+  QuickPrompt::default_for(index)
+}
+
+pub fn load_quick_prompt_template_with_notify(app: Option<&tauri::AppHandle>, index: u8) -> String {
+  load_quick_prompt_with_notify(app, index).template
 }
 
 #[allow(dead_code)]
@@ -456,54 +644,79 @@ pub fn generate_default_quick_prompts() -> Result<String, String> {
   Ok(path.to_string_lossy().to_string())
 }
 
+fn read_quick_prompts_file() -> Option<serde_json::Value> {
+  quick_prompts_config_path()
+    .and_then(|path| fs::read_to_string(path).ok())
+    .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+}
+
+// Quick prompts 1..9: library bindings, else quick_prompts.json, missing or invalid entries
+// replaced by defaults
+fn read_quick_prompts() -> Vec<QuickPrompt> {
+  let file = read_quick_prompts_file();
+  (1..=9u8)
+    .map(|i| {
+      crate::prompt_library::bound_to(i)
+        .or_else(|| file.as_ref().and_then(|v| entry_at(v, i)).and_then(QuickPrompt::parse))
+        .unwrap_or_else(|| QuickPrompt::default_for(i))
+    })
+    .collect()
+}
+
+// Menu labels are cut to this many characters
+const LABEL_CHARS: usize = 40;
+
+/// Short label of each quick prompt 1..9 for menus: the bound library prompt's name, else the
+/// first line of the template.
+pub fn quick_prompt_labels() -> Vec<String> {
+  read_quick_prompts()
+    .into_iter()
+    .enumerate()
+    .map(|(i, prompt)| {
+      crate::prompt_library::bound_name(i as u8 + 1).unwrap_or_else(|| {
+        let line = prompt.template.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+        if line.chars().count() > LABEL_CHARS { format!("{}…", line.chars().take(LABEL_CHARS).collect::<String>()) } else { line.to_string() }
+      })
+    })
+    .collect()
+}
+
 #[tauri::command]
 pub fn get_quick_prompts() -> Result<serde_json::Value, String> {
-  // Return an object with keys "1".."9". Fill missing/invalid entries with defaults.
+  // Return an object with keys "1".."9" mapping to the template texts. Fill missing/invalid entries with defaults.
   let mut obj = serde_json::Map::new();
-  for i in 1..=9u8 { obj.insert(i.to_string(), serde_json::Value::String(quick_prompt_template(i).to_string())); }
-
-  if let Some(path) = quick_prompts_config_path() {
-    if let Ok(text) = fs::read_to_string(&path) {
-      if let Ok(v) = serde_json::from_str::<serde_json::Value>(&text) {
-        match v {
-          serde_json::Value::Array(arr) => {
-            for i in 1..=9u8 {
-              if let Some(s) = arr.get((i as usize) - 1).and_then(|x| x.as_str()) {
-                obj.insert(i.to_string(), serde_json::Value::String(s.to_string()));
-              }
-            }
-          }
-          serde_json::Value::Object(map_in) => {
-            for i in 1..=9u8 {
-              let k = i.to_string();
-              if let Some(s) = map_in.get(&k).and_then(|x| x.as_str()) {
-                obj.insert(k, serde_json::Value::String(s.to_string()));
-              }
-            }
-          }
-          _ => {}
-        }
-      }
-    }
+  for (i, prompt) in read_quick_prompts().into_iter().enumerate() {
+    obj.insert((i + 1).to_string(), serde_json::Value::String(prompt.template));
   }
+  Ok(serde_json::Value::Object(obj))
+}
 
+/// Like `get_quick_prompts`, but each entry is the full prompt object
+/// `{ template, model, temperature, system_prompt, output, library_id }` (unset overrides are null;
+/// `library_id` is set for slots bound to a prompt library entry).
+#[tauri::command]
+pub fn get_quick_prompt_configs() -> Result<serde_json::Value, String> {
+  let mut obj = serde_json::Map::new();
+  for (i, prompt) in read_quick_prompts().into_iter().enumerate() {
+    obj.insert((i + 1).to_string(), serde_json::to_value(prompt).map_err(|e| format!("Serialize prompts failed: {e}"))?);
+  }
   Ok(serde_json::Value::Object(obj))
 }
 
 #[tauri::command]
 pub fn save_quick_prompts(map: serde_json::Value) -> Result<String, String> {
-  // Accept either array or object; normalize to object of 1..9 with strings.
+  // Accept either array or object of template strings or prompt objects; normalize to an object of 1..9.
+  // Slots sent with a `library_id` show a library prompt, so their file entry is kept as it is.
+  let file = read_quick_prompts_file();
   let mut obj = serde_json::Map::new();
   for i in 1..=9u8 {
-    let k = i.to_string();
-    let v = match &map {
-      serde_json::Value::Array(arr) => arr.get((i as usize) - 1).and_then(|x| x.as_str()).unwrap_or(quick_prompt_template(i)),
-      serde_json::Value::Object(m) => m.get(&k).and_then(|x| x.as_str()).unwrap_or(quick_prompt_template(i)),
-      _ => quick_prompt_template(i),
-    };
-    let trimmed = v.trim();
-    let final_v = if trimmed.is_empty() { quick_prompt_template(i) } else { trimmed };
-    obj.insert(k, serde_json::Value::String(final_v.to_string()));
+    let entry = entry_at(&map, i);
+    let bound = entry.and_then(|e| e.get("library_id")).is_some_and(|id| id.as_str().is_some_and(|s| !s.is_empty()));
+    let source = if bound { file.as_ref().and_then(|v| entry_at(v, i)) } else { entry };
+    let mut prompt = source.and_then(QuickPrompt::parse).unwrap_or_else(|| QuickPrompt::default_for(i));
+    let trimmed = prompt.template.trim();
+    prompt.template = if trimmed.is_empty() { quick_prompt_template(i).to_string() } else { trimmed.to_string() };
+    obj.insert(i.to_string(), prompt.to_json());
   }
 
   let path = quick_prompts_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
@@ -518,5 +731,6 @@ pub fn save_quick_prompts(map: serde_json::Value) -> Result<String, String> {
   #[cfg(target_os = "windows")]
   { if path.exists() { let _ = fs::remove_file(&path); } }
   fs::rename(&tmp_path, &path).map_err(|e| format!("Rename config failed: {e}"))?;
+  crate::tray::refresh();
   Ok(path.to_string_lossy().to_string())
 }
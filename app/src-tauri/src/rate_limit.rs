@@ -0,0 +1,298 @@
+// Per-provider request limiter. Parallel quick prompts, chat and TTS share one queue per provider
+// with a concurrency cap and a token bucket (requests per minute, from `rate_limits` in settings).
+// Waiting requests are announced via `ratelimit:queue` events; 429 responses pause the provider
+// and the request is retried instead of failing straight away. OpenAI requests first fail over to
+// the next configured API key (key_pool.rs), announced as `provider:failover`. A request holds its
+// slot until the response body has been read, so streamed replies count for as long as they run.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use tauri::Emitter;
+use tokio::sync::Notify;
+
+const QUEUE_EVENT: &str = "ratelimit:queue";
+// Retries after a 429 before the response is handed back to the caller
+const MAX_RATE_LIMIT_RETRIES: u32 = 2;
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+// Key switches per request when every pool key is rate limited in turn
+const MAX_KEY_FAILOVERS: usize = 8;
+const OPENAI: &str = "openai";
+// Upper bound for a single wait so limit changes in settings are picked up
+const MAX_POLL: Duration = Duration::from_millis(500);
+
+static APP: OnceCell<tauri::AppHandle> = OnceCell::new();
+static LIMITERS: Lazy<Mutex<HashMap<String, Arc<Limiter>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_TICKET: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Serialize, Clone, Debug)]
+struct QueueEvent<'a> {
+  provider: &'a str,
+  ticket: u64,
+  label: &'a str,
+  /// 1-based position in the provider queue; 0 once the request has started
+  position: usize,
+  queued: usize,
+}
+
+struct Waiter {
+  ticket: u64,
+  label: String,
+}
+
+struct State {
+  in_flight: usize,
+  queue: VecDeque<Waiter>,
+  tokens: f64,
+  last_refill: Instant,
+  paused_until: Option<Instant>,
+}
+
+struct Limiter {
+  provider: String,
+  state: Mutex<State>,
+  notify: Notify,
+}
+
+impl Limiter {
+  fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+    self.state.lock().unwrap_or_else(|e| e.into_inner())
+  }
+}
+
+/// Keep an app handle for queue events. Called once from setup.
+pub fn init(app: tauri::AppHandle) {
+  let _ = APP.set(app);
+}
+
+fn emit(provider: &str, ticket: u64, label: &str, position: usize, queued: usize) {
+  if let Some(app) = APP.get() {
+    let _ = app.emit(QUEUE_EVENT, QueueEvent { provider, ticket, label, position, queued });
+  }
+}
+
+fn limiter_for(provider: &str) -> Arc<Limiter> {
+  let key = provider.trim().to_lowercase();
+  let mut map = LIMITERS.lock().unwrap_or_else(|e| e.into_inner());
+  map
+    .entry(key.clone())
+    .or_insert_with(|| {
+      let (_, per_minute) = crate::config::get_rate_limits_from_settings(&key);
+      Arc::new(Limiter {
+        provider: key,
+        state: Mutex::new(State {
+          in_flight: 0,
+          queue: VecDeque::new(),
+          tokens: per_minute as f64,
+          last_refill: Instant::now(),
+          paused_until: None,
+        }),
+        notify: Notify::new(),
+      })
+    })
+    .clone()
+}
+
+// Removes the ticket from the queue if the waiting future is dropped (e.g. task cancelled)
+struct QueueSlot {
+  limiter: Arc<Limiter>,
+  ticket: u64,
+  active: bool,
+}
+
+impl Drop for QueueSlot {
+  fn drop(&mut self) {
+    if !self.active { return; }
+    self.limiter.lock().queue.retain(|w| w.ticket != self.ticket);
+    self.limiter.notify.notify_waiters();
+  }
+}
+
+/// A granted request slot. Dropping it frees the concurrency slot; `send` ties it to the body.
+pub struct Permit {
+  limiter: Arc<Limiter>,
+}
+
+impl Permit {
+  /// Stop starting new requests for this provider for `delay` (used after a 429).
+  fn pause_for(&self, delay: Duration) {
+    let mut st = self.limiter.lock();
+    let until = Instant::now() + delay;
+    st.paused_until = Some(st.paused_until.map_or(until, |t| t.max(until)));
+    st.tokens = 0.0;
+  }
+}
+
+impl Drop for Permit {
+  fn drop(&mut self) {
+    {
+      let mut st = self.limiter.lock();
+      st.in_flight = st.in_flight.saturating_sub(1);
+    }
+    self.limiter.notify.notify_waiters();
+  }
+}
+
+/// Wait for a request slot for `provider`. Requests are granted in FIFO order.
+pub async fn acquire(provider: &str, label: &str) -> Permit {
+  let limiter = limiter_for(provider);
+  let ticket = NEXT_TICKET.fetch_add(1, Ordering::Relaxed);
+  limiter.lock().queue.push_back(Waiter { ticket, label: label.to_string() });
+  let mut slot = QueueSlot { limiter: limiter.clone(), ticket, active: true };
+  let mut announced: Option<usize> = None;
+
+  loop {
+    // Register interest before checking so a release in between is not missed
+    let notified = limiter.notify.notified();
+    let wait = {
+      let (max_concurrent, per_minute) = crate::config::get_rate_limits_from_settings(&limiter.provider);
+      let rate_per_sec = per_minute as f64 / 60.0;
+      let mut st = limiter.lock();
+      let now = Instant::now();
+      let elapsed = now.duration_since(st.last_refill).as_secs_f64();
+      st.tokens = (st.tokens + elapsed * rate_per_sec).min(per_minute as f64);
+      st.last_refill = now;
+      if st.paused_until.is_some_and(|t| t <= now) { st.paused_until = None; }
+
+      let pos = st.queue.iter().position(|w| w.ticket == ticket).unwrap_or(0);
+      if pos == 0 && st.in_flight < max_concurrent && st.tokens >= 1.0 && st.paused_until.is_none() {
+        st.queue.pop_front();
+        st.in_flight += 1;
+        st.tokens -= 1.0;
+        slot.active = false;
+        if announced.is_some() { emit(&limiter.provider, ticket, label, 0, st.queue.len()); }
+        // Everyone behind moved up one place
+        for (i, w) in st.queue.iter().enumerate() {
+          emit(&limiter.provider, w.ticket, &w.label, i + 1, st.queue.len());
+        }
+        break;
+      }
+      if announced != Some(pos) {
+        announced = Some(pos);
+        emit(&limiter.provider, ticket, label, pos + 1, st.queue.len());
+        tracing::debug!(provider = %limiter.provider, ticket, position = pos + 1, "request queued");
+      }
+      let mut wait = MAX_POLL;
+      if let Some(t) = st.paused_until { wait = wait.min(t.saturating_duration_since(now)); }
+      if st.tokens < 1.0 { wait = wait.min(Duration::from_secs_f64((1.0 - st.tokens) / rate_per_sec)); }
+      wait.max(Duration::from_millis(10))
+    };
+    tokio::select! {
+      _ = notified => {}
+      _ = tokio::time::sleep(wait) => {}
+    }
+  }
+
+  // Let the next in line re-check now that the head moved
+  limiter.notify.notify_waiters();
+  Permit { limiter }
+}
+
+// The same response with `permit` moved into its body, released once the body is read or dropped
+fn hold_until_consumed(resp: reqwest::Response, permit: Permit) -> reqwest::Response {
+  let (status, version, headers) = (resp.status(), resp.version(), resp.headers().clone());
+  let body = reqwest::Body::wrap_stream(resp.bytes_stream().map(move |chunk| {
+    let _held = &permit;
+    chunk
+  }));
+  let mut out = tauri::http::Response::new(body);
+  *out.status_mut() = status;
+  *out.version_mut() = version;
+  *out.headers_mut() = headers;
+  reqwest::Response::from(out)
+}
+
+fn retry_after(resp: &reqwest::Response, attempt: u32) -> Duration {
+  resp
+    .headers()
+    .get(reqwest::header::RETRY_AFTER)
+    .and_then(|v| v.to_str().ok())
+    .and_then(|s| s.trim().parse::<f64>().ok())
+    // "inf" or "1e20" would make from_secs_f64 panic; such values fall back to the backoff
+    .and_then(|secs| Duration::try_from_secs_f64(secs.max(0.0)).ok())
+    .unwrap_or_else(|| Duration::from_secs(2u64.pow(attempt + 1)))
+    .min(MAX_RETRY_AFTER)
+}
+
+fn bearer_key(req: &reqwest::Request) -> Option<String> {
+  let v = req.headers().get(reqwest::header::AUTHORIZATION)?.to_str().ok()?;
+  v.strip_prefix("Bearer ").map(|k| k.trim().to_string())
+}
+
+fn set_bearer_key(req: &mut reqwest::Request, key: &str) {
+  if let Ok(mut v) = reqwest::header::HeaderValue::from_str(&format!("Bearer {key}")) {
+    v.set_sensitive(true);
+    req.headers_mut().insert(reqwest::header::AUTHORIZATION, v);
+  }
+}
+
+// After a 429 on an OpenAI key from the rotation pool: the key to retry with, if another one is usable
+fn fail_over(provider: &str, label: &str, used: Option<&str>) -> Option<String> {
+  if provider != OPENAI { return None; }
+  let used = used?;
+  let next = crate::key_pool::fail_over(used)?;
+  let (from_key, to_key) = (crate::key_pool::mask(used), crate::key_pool::mask(&next.key));
+  tracing::warn!(provider, label, from = %from_key, to = %to_key, "rate limited; switching API key");
+  if let Some(app) = APP.get() {
+    crate::events::emit(app, crate::events::PROVIDER_FAILOVER, &crate::events::ProviderFailover {
+      provider: provider.to_string(),
+      label: label.to_string(),
+      from_key,
+      to_key,
+      key_index: next.index,
+      key_count: next.count,
+    });
+  }
+  Some(next.key)
+}
+
+/// Send a request through the provider limiter. `make` builds a fresh request per attempt;
+/// on 429 an OpenAI request moves on to the next key of the rotation pool (key_pool.rs) if there
+/// is one, otherwise the provider is paused for Retry-After and the request is queued again.
+pub async fn send<F>(provider: &str, label: &str, make: F) -> Result<reqwest::Response, reqwest::Error>
+where
+  F: Fn() -> reqwest::RequestBuilder,
+{
+  let mut attempt = 0u32;
+  let mut failovers = 0usize;
+  let mut key_override: Option<String> = None;
+  loop {
+    let permit = acquire(provider, label).await;
+    let (client, req) = make().build_split();
+    let mut req = req?;
+    let mut used = bearer_key(&req);
+    if let (Some(next), Some(k)) = (&key_override, &used) {
+      // Only keys from the pool are swapped; other providers' keys are left alone
+      if crate::config::get_openai_api_keys_from_settings_or_env().contains(k) {
+        set_bearer_key(&mut req, next);
+        used = Some(next.clone());
+      }
+    }
+    let resp = client.execute(req).await?;
+    if resp.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+      return Ok(hold_until_consumed(resp, permit));
+    }
+    if failovers < MAX_KEY_FAILOVERS {
+      if let Some(next) = fail_over(provider, label, used.as_deref()) {
+        key_override = Some(next);
+        failovers += 1;
+        drop(permit);
+        continue;
+      }
+    }
+    if attempt < MAX_RATE_LIMIT_RETRIES {
+      let delay = retry_after(&resp, attempt);
+      tracing::warn!(provider, label, ?delay, attempt, "rate limited; retrying");
+      permit.pause_for(delay);
+      drop(permit);
+      attempt += 1;
+      continue;
+    }
+    return Ok(hold_until_consumed(resp, permit));
+  }
+}
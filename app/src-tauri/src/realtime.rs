@@ -0,0 +1,780 @@
+// Native OpenAI Realtime voice sessions. Opens the Realtime WebSocket from the backend, streams
+// the default microphone up as 24 kHz PCM16 and plays response audio on the default output
+// device, so Assistant Mode does not depend on WebRTC support in the WebView.
+//
+// Events:
+//   realtime:state      { session_id, state: "connecting"|"connected"|"closed"|"error", message? }
+//   realtime:transcript { session_id, conversation_id?, role: "user"|"assistant", item_id, text, delta?, final }
+//   realtime:response   { session_id, response_id, status, text? }
+//   realtime:error      { session_id, message, code? }   (server-reported, session stays open)
+//   realtime:barge_in   { session_id, source: "local"|"server", played_ms }
+//   realtime:tool-call / realtime:tool-result   (MCP function calls, same shape as chat:tool-*)
+//
+// Barge-in: while assistant audio is playing the microphone is not uploaded (so the speaker
+// echo cannot trigger server VAD). A local detector compares mic level with what is being
+// played; sustained speech clearly above the echo stops playback, cancels the response,
+// truncates the assistant item to what was heard and resumes upload (with a short pre-roll).
+//
+// Handoff: a session started with a conversation id is seeded with that conversation's system
+// prompt and recent turns; its transcripts carry the id so the window appends them to it.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_util::sync::CancellationToken;
+
+use crate::audio_io::{self, MicChunk, Resampler, StreamsHandle};
+use crate::error::AidcError;
+
+const REALTIME_URL: &str = "wss://api.openai.com/v1/realtime";
+const SESSIONS_URL: &str = "https://api.openai.com/v1/realtime/sessions";
+const DEFAULT_MODEL: &str = "gpt-4o-realtime-preview";
+const DEFAULT_VOICE: &str = "verse";
+// Realtime PCM16 is always 24 kHz mono
+const SAMPLE_RATE: u32 = 24_000;
+// ~100 ms of audio per input_audio_buffer.append
+const CHUNK_SAMPLES: usize = 2_400;
+// Barge-in detector: mic RMS must exceed both an absolute floor and the (decaying) playback
+// level by ECHO_RATIO for BARGE_IN_SAMPLES in a row
+const MIN_SPEECH_RMS: f32 = 0.02;
+const ECHO_RATIO: f32 = 2.0;
+const ECHO_DECAY: f32 = 0.85;
+const BARGE_IN_SAMPLES: usize = 6_000; // 250 ms
+// Mic audio kept while gated so the start of the interruption reaches the server
+const PRE_ROLL_SAMPLES: usize = 9_600; // 400 ms
+// Conversation messages replayed into a session attached to a text chat
+const HANDOFF_MESSAGES: usize = 20;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+struct ActiveSession {
+  id: String,
+  cancel: CancellationToken,
+}
+
+static SESSION: Lazy<Mutex<Option<ActiveSession>>> = Lazy::new(|| Mutex::new(None));
+
+static SESSIONS_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+  reqwest::Client::builder()
+    .timeout(std::time::Duration::from_secs(15))
+    .connect_timeout(std::time::Duration::from_secs(10))
+    .build()
+    .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+struct SessionOptions {
+  model: String,
+  voice: String,
+  instructions: Option<String>,
+  temperature: Option<f64>,
+  silence_duration_ms: u64,
+  noise_reduction: bool,
+  barge_in: bool,
+  // MCP tools in Realtime function format; empty = tools off
+  tools: Vec<Value>,
+}
+
+impl SessionOptions {
+  // Explicit arguments win over the `assistant_realtime` settings saved by Assistant Mode; the
+  // active persona's system prompt comes before the instructions
+  fn resolve(model: Option<String>, voice: Option<String>, instructions: Option<String>) -> Self {
+    let ar = crate::config::get_assistant_realtime_from_settings();
+    let s = |k: &str| ar.get(k).and_then(|x| x.as_str()).map(|x| x.trim().to_string()).filter(|x| !x.is_empty());
+    let clean = |v: Option<String>| v.map(|x| x.trim().to_string()).filter(|x| !x.is_empty());
+    let instructions = match (crate::personas::active_system_prompt(), clean(instructions).or_else(|| s("instructions"))) {
+      (Some(p), Some(i)) => Some(format!("{p}\n\n{i}")),
+      (p, i) => i.or(p),
+    };
+    Self {
+      model: clean(model).or_else(|| s("model")).unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+      voice: clean(voice).or_else(|| s("voice")).unwrap_or_else(|| DEFAULT_VOICE.to_string()),
+      instructions,
+      temperature: ar.get("temperature").and_then(|x| x.as_f64()),
+      silence_duration_ms: ar.get("silence_duration_ms").and_then(|x| x.as_u64()).unwrap_or(800),
+      noise_reduction: ar.get("input_audio_noise_reduction").and_then(|x| x.as_bool()).unwrap_or(true),
+      barge_in: ar.get("barge_in").and_then(|x| x.as_bool()).unwrap_or(true),
+      tools: Vec::new(),
+    }
+  }
+
+  // Session object shared by session.update (WebSocket) and client secret minting (WebRTC)
+  fn session_config(&self) -> Value {
+    let mut session = json!({
+      "modalities": ["audio", "text"],
+      "voice": self.voice,
+      "input_audio_format": "pcm16",
+      "output_audio_format": "pcm16",
+      "input_audio_transcription": { "model": "whisper-1" },
+      "turn_detection": { "type": "server_vad", "silence_duration_ms": self.silence_duration_ms },
+    });
+    if let Some(i) = &self.instructions { session["instructions"] = json!(i); }
+    // The Realtime API only accepts 0.6..=1.2
+    if let Some(t) = self.temperature { session["temperature"] = json!(t.clamp(0.6, 1.2)); }
+    if self.noise_reduction { session["input_audio_noise_reduction"] = json!({ "type": "near_field" }); }
+    if !self.tools.is_empty() {
+      session["tools"] = json!(self.tools);
+      session["tool_choice"] = json!("auto");
+    }
+    session
+  }
+
+  fn session_update(&self) -> Value {
+    json!({ "type": "session.update", "session": self.session_config() })
+  }
+
+  // The conversation's system prompt comes first; voice-specific instructions refine it
+  fn attach(&mut self, ctx: &crate::config::ConversationContext) {
+    if let Some(sp) = &ctx.system_prompt {
+      self.instructions = Some(match self.instructions.take() {
+        Some(i) => format!("{sp}\n\n{i}"),
+        None => sp.clone(),
+      });
+    }
+  }
+}
+
+// conversation.item.create events replaying text history into a new session
+fn history_items(history: &[(String, String)]) -> Vec<Value> {
+  history
+    .iter()
+    .map(|(role, text)| {
+      let part = if role == "assistant" { "text" } else { "input_text" };
+      json!({
+        "type": "conversation.item.create",
+        "item": { "type": "message", "role": role, "content": [{ "type": part, "text": text }] },
+      })
+    })
+    .collect()
+}
+
+// Playback state shared between the output callback, the mic pump and the event reader
+#[derive(Default)]
+struct Duplex {
+  queue: Mutex<VecDeque<f32>>,
+  out_rate: AtomicU32,
+  // RMS of the last output block (f32 bits)
+  out_level: AtomicU32,
+  // Frames played of the current assistant item, for conversation.item.truncate
+  played_frames: AtomicU64,
+  item_id: Mutex<Option<String>>,
+  responding: AtomicBool,
+  // Set after an interruption; audio deltas of the cancelled response are dropped
+  discarding: AtomicBool,
+}
+
+impl Duplex {
+  fn is_playing(&self) -> bool {
+    !self.queue.lock().unwrap_or_else(|e| e.into_inner()).is_empty()
+  }
+
+  fn out_level(&self) -> f32 {
+    f32::from_bits(self.out_level.load(Ordering::Relaxed))
+  }
+
+  fn played_ms(&self) -> u64 {
+    let rate = self.out_rate.load(Ordering::Relaxed).max(1) as u64;
+    self.played_frames.load(Ordering::Relaxed) * 1000 / rate
+  }
+
+  // Stop local playback and return the client events that cancel/truncate the response
+  fn interrupt(&self) -> (Vec<Value>, u64) {
+    self.queue.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    self.out_level.store(0f32.to_bits(), Ordering::Relaxed);
+    self.discarding.store(true, Ordering::Relaxed);
+    let played_ms = self.played_ms();
+    let mut events = Vec::new();
+    if self.responding.swap(false, Ordering::Relaxed) {
+      events.push(json!({ "type": "response.cancel" }));
+    }
+    if let Some(item) = self.item_id.lock().unwrap_or_else(|e| e.into_inner()).take() {
+      events.push(json!({ "type": "conversation.item.truncate", "item_id": item, "content_index": 0, "audio_end_ms": played_ms }));
+    }
+    (events, played_ms)
+  }
+}
+
+// Echo-aware speech detector used while assistant audio is playing
+#[derive(Default)]
+struct EchoGate {
+  echo: f32,
+  speech_samples: usize,
+}
+
+impl EchoGate {
+  fn update(&mut self, mic_rms: f32, out_rms: f32, samples: usize) -> bool {
+    // Hold the playback level briefly to cover the speaker-to-mic delay
+    self.echo = out_rms.max(self.echo * ECHO_DECAY);
+    if mic_rms > MIN_SPEECH_RMS.max(self.echo * ECHO_RATIO) {
+      self.speech_samples += samples;
+    } else {
+      self.speech_samples = 0;
+    }
+    self.speech_samples >= BARGE_IN_SAMPLES
+  }
+
+  fn reset(&mut self) {
+    self.echo = 0.0;
+    self.speech_samples = 0;
+  }
+}
+
+fn emit(app: &AppHandle, event: &str, payload: Value) {
+  let _ = app.emit(event, payload);
+}
+
+fn emit_state(app: &AppHandle, id: &str, state: &str, message: Option<&str>) {
+  emit(app, "realtime:state", json!({ "session_id": id, "state": state, "message": message }));
+}
+
+// ---------------------------
+// Audio devices
+// ---------------------------
+
+fn build_output<T>(device: &cpal::Device, config: &cpal::StreamConfig, duplex: Arc<Duplex>) -> Result<cpal::Stream, String>
+where
+  T: SizedSample + FromSample<f32>,
+{
+  let channels = config.channels.max(1) as usize;
+  device
+    .build_output_stream(
+      config,
+      move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+        let mut q = duplex.queue.lock().unwrap_or_else(|e| e.into_inner());
+        let (mut played, mut sum) = (0u64, 0f32);
+        for frame in data.chunks_mut(channels) {
+          let v = match q.pop_front() {
+            Some(v) => {
+              played += 1;
+              sum += v * v;
+              v
+            }
+            None => 0.0,
+          };
+          let v = T::from_sample(v);
+          for s in frame.iter_mut() { *s = v; }
+        }
+        let level = if played > 0 { (sum / played as f32).sqrt() } else { 0.0 };
+        duplex.out_level.store(level.to_bits(), Ordering::Relaxed);
+        duplex.played_frames.fetch_add(played, Ordering::Relaxed);
+      },
+      |e| tracing::warn!(error = %e, "realtime output stream error"),
+      None,
+    )
+    .map_err(|e| format!("open audio output failed: {e}"))
+}
+
+// Mic and speaker streams on one audio thread; returns the output sample rate
+fn start_audio(mic_tx: mpsc::UnboundedSender<MicChunk>, duplex: Arc<Duplex>) -> Result<(StreamsHandle, u32), String> {
+  audio_io::spawn_streams("realtime-audio", move || {
+    let input = audio_io::open_input(SAMPLE_RATE, mic_tx)?;
+    let output = cpal::default_host().default_output_device().ok_or_else(|| "No audio output device found".to_string())?;
+    let cfg = output.default_output_config().map_err(|e| format!("audio output config failed: {e}"))?;
+    let out_stream = match cfg.sample_format() {
+      cpal::SampleFormat::F32 => build_output::<f32>(&output, &cfg.config(), duplex),
+      cpal::SampleFormat::I16 => build_output::<i16>(&output, &cfg.config(), duplex),
+      cpal::SampleFormat::U16 => build_output::<u16>(&output, &cfg.config(), duplex),
+      f => Err(format!("Unsupported output sample format: {f:?}")),
+    }?;
+    out_stream.play().map_err(|e| format!("start audio output failed: {e}"))?;
+    Ok((vec![input, out_stream], cfg.sample_rate().0))
+  })
+}
+
+// ---------------------------
+// WebSocket
+// ---------------------------
+
+async fn connect(key: &str, model: &str) -> Result<WsStream, AidcError> {
+  if !crate::connectivity::is_online() { return Err(AidcError::Offline); }
+  let mut req = format!("{REALTIME_URL}?model={model}")
+    .into_client_request()
+    .map_err(|e| AidcError::InvalidInput(format!("invalid realtime model: {e}")))?;
+  let auth = HeaderValue::from_str(&format!("Bearer {key}")).map_err(|_| AidcError::missing_api_key("openai"))?;
+  req.headers_mut().insert("Authorization", auth);
+  req.headers_mut().insert("OpenAI-Beta", HeaderValue::from_static("realtime=v1"));
+  match tokio_tungstenite::connect_async(req).await {
+    Ok((ws, _)) => Ok(ws),
+    Err(tokio_tungstenite::tungstenite::Error::Http(resp)) => {
+      let status = reqwest::StatusCode::from_u16(resp.status().as_u16()).unwrap_or(reqwest::StatusCode::BAD_GATEWAY);
+      let body = resp.body().as_deref().map(String::from_utf8_lossy).unwrap_or_default();
+      Err(AidcError::from_status("openai", status, &body))
+    }
+    Err(e) => Err(AidcError::Network { provider: "openai".into(), message: format!("realtime connect failed: {e}") }),
+  }
+}
+
+async fn send_json(sink: &mut SplitSink<WsStream, Message>, v: Value) -> Result<(), String> {
+  sink.send(Message::Text(v.to_string())).await.map_err(|e| format!("realtime send failed: {e}"))
+}
+
+// Uploads microphone audio in ~100 ms appends (gated during playback, see barge-in above) and
+// sends client events queued by the reader until the session is cancelled
+struct Writer {
+  app: AppHandle,
+  id: String,
+  duplex: Arc<Duplex>,
+  barge_in: bool,
+}
+
+impl Writer {
+  async fn run(
+    self,
+    mut sink: SplitSink<WsStream, Message>,
+    mut mic_rx: mpsc::UnboundedReceiver<MicChunk>,
+    mut control_rx: mpsc::UnboundedReceiver<Value>,
+    cancel: CancellationToken,
+  ) -> Result<(), String> {
+    let mut pending: Vec<i16> = Vec::with_capacity(CHUNK_SAMPLES * 2);
+    let mut pre_roll: VecDeque<i16> = VecDeque::with_capacity(PRE_ROLL_SAMPLES * 2);
+    let mut gate = EchoGate::default();
+    loop {
+      tokio::select! {
+        _ = cancel.cancelled() => break,
+        ev = control_rx.recv() => {
+          let Some(ev) = ev else { break };
+          send_json(&mut sink, ev).await?;
+        }
+        chunk = mic_rx.recv() => {
+          let Some((pcm, level)) = chunk else { break };
+          if self.duplex.is_playing() {
+            if !self.barge_in { continue; }
+            let n = pcm.len();
+            pre_roll.extend(pcm);
+            let excess = pre_roll.len().saturating_sub(PRE_ROLL_SAMPLES);
+            pre_roll.drain(..excess);
+            if !gate.update(level, self.duplex.out_level(), n) { continue; }
+            let (events, played_ms) = self.duplex.interrupt();
+            for ev in events { send_json(&mut sink, ev).await?; }
+            tracing::info!(session = %self.id, played_ms, "realtime barge-in");
+            emit(&self.app, "realtime:barge_in", json!({ "session_id": self.id, "source": "local", "played_ms": played_ms }));
+            pending.extend(pre_roll.drain(..));
+            gate.reset();
+          } else {
+            // Anything held while playing was echo
+            pre_roll.clear();
+            gate.reset();
+            pending.extend(pcm);
+          }
+          if pending.len() >= CHUNK_SAMPLES {
+            let bytes: Vec<u8> = pending.drain(..).flat_map(|s| s.to_le_bytes()).collect();
+            send_json(&mut sink, json!({ "type": "input_audio_buffer.append", "audio": B64.encode(bytes) })).await?;
+          }
+        }
+      }
+    }
+    let _ = sink.send(Message::Close(None)).await;
+    Ok(())
+  }
+}
+
+struct Reader {
+  app: AppHandle,
+  id: String,
+  conversation_id: Option<String>,
+  duplex: Arc<Duplex>,
+  control: mpsc::UnboundedSender<Value>,
+  barge_in: bool,
+  resampler: Resampler,
+  // Assistant transcript of the response in progress
+  transcript: String,
+  // Function calls still running and whether their outputs await a response.create
+  pending_calls: usize,
+  needs_followup: bool,
+  tool_tx: mpsc::UnboundedSender<(String, String)>,
+}
+
+impl Reader {
+  fn play(&mut self, item_id: Option<&str>, b64: &str) {
+    if self.duplex.discarding.load(Ordering::Relaxed) { return; }
+    if let Some(item) = item_id {
+      let mut cur = self.duplex.item_id.lock().unwrap_or_else(|e| e.into_inner());
+      if cur.as_deref() != Some(item) {
+        *cur = Some(item.to_string());
+        self.duplex.played_frames.store(0, Ordering::Relaxed);
+      }
+    }
+    let Ok(bytes) = B64.decode(b64) else { return };
+    let pcm: Vec<f32> = bytes.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0).collect();
+    let mut out = Vec::with_capacity(pcm.len() * 2);
+    self.resampler.process(&pcm, &mut out);
+    self.duplex.queue.lock().unwrap_or_else(|e| e.into_inner()).extend(out);
+  }
+
+  fn emit_transcript(&self, role: &str, item_id: Option<&str>, text: &str, delta: Option<&str>, is_final: bool) {
+    emit(
+      &self.app,
+      "realtime:transcript",
+      json!({
+        "session_id": self.id,
+        "conversation_id": self.conversation_id,
+        "role": role,
+        "item_id": item_id,
+        "text": text,
+        "delta": delta,
+        "final": is_final,
+      }),
+    );
+  }
+
+  fn handle(&mut self, ev: &Value) {
+    let kind = ev.get("type").and_then(|x| x.as_str()).unwrap_or("");
+    let str_of = |k: &str| ev.get(k).and_then(|x| x.as_str());
+    match kind {
+      "response.audio.delta" => {
+        if let Some(d) = str_of("delta") { self.play(str_of("item_id"), d); }
+      }
+      // Server VAD heard the user (e.g. headset, no echo gating needed): drop what is still queued
+      "input_audio_buffer.speech_started" => {
+        if self.barge_in && self.duplex.is_playing() {
+          let (events, played_ms) = self.duplex.interrupt();
+          for ev in events { let _ = self.control.send(ev); }
+          emit(&self.app, "realtime:barge_in", json!({ "session_id": self.id, "source": "server", "played_ms": played_ms }));
+        }
+      }
+      "response.audio_transcript.delta" => {
+        let delta = str_of("delta").unwrap_or("");
+        self.transcript.push_str(delta);
+        self.emit_transcript("assistant", str_of("item_id"), &self.transcript, Some(delta), false);
+      }
+      "response.audio_transcript.done" => {
+        let text = str_of("transcript").map(|s| s.to_string()).unwrap_or_else(|| self.transcript.clone());
+        self.emit_transcript("assistant", str_of("item_id"), &text, None, true);
+      }
+      "conversation.item.input_audio_transcription.completed" => {
+        let text = str_of("transcript").unwrap_or("").trim();
+        self.emit_transcript("user", str_of("item_id"), text, None, true);
+      }
+      "response.created" => {
+        self.transcript.clear();
+        self.duplex.responding.store(true, Ordering::Relaxed);
+        self.duplex.discarding.store(false, Ordering::Relaxed);
+        let r = ev.get("response");
+        let rid = r.and_then(|r| r.get("id")).and_then(|x| x.as_str());
+        emit(&self.app, "realtime:response", json!({ "session_id": self.id, "response_id": rid, "status": "in_progress" }));
+      }
+      "response.function_call_arguments.done" => {
+        let call_id = str_of("call_id").unwrap_or("").to_string();
+        let name = str_of("name").unwrap_or("").to_string();
+        let args = str_of("arguments").unwrap_or("{}").to_string();
+        self.pending_calls += 1;
+        // Run off the reader so audio keeps flowing while the tool works
+        let (app, id, tx) = (self.app.clone(), self.id.clone(), self.tool_tx.clone());
+        tauri::async_runtime::spawn(async move {
+          let output = dispatch_function_call(&app, &id, &call_id, &name, &args).await;
+          let _ = tx.send((call_id, output));
+        });
+      }
+      "response.done" => {
+        self.duplex.responding.store(false, Ordering::Relaxed);
+        self.maybe_follow_up();
+        let r = ev.get("response");
+        let rid = r.and_then(|r| r.get("id")).and_then(|x| x.as_str());
+        let status = r.and_then(|r| r.get("status")).and_then(|x| x.as_str()).unwrap_or("completed");
+        let text = Some(self.transcript.clone()).filter(|t| !t.is_empty());
+        emit(&self.app, "realtime:response", json!({ "session_id": self.id, "response_id": rid, "status": status, "text": text }));
+      }
+      "error" => {
+        let err = ev.get("error");
+        let message = err.and_then(|e| e.get("message")).and_then(|x| x.as_str()).unwrap_or("Realtime error");
+        let code = err.and_then(|e| e.get("code")).and_then(|x| x.as_str());
+        tracing::warn!(session = %self.id, code = code.unwrap_or(""), "realtime server error: {message}");
+        emit(&self.app, "realtime:error", json!({ "session_id": self.id, "message": message, "code": code }));
+      }
+      "session.created" | "session.updated" => tracing::debug!(session = %self.id, "{kind}"),
+      _ => {}
+    }
+  }
+
+  fn tool_done(&mut self, call_id: String, output: String) {
+    self.pending_calls = self.pending_calls.saturating_sub(1);
+    let _ = self.control.send(json!({
+      "type": "conversation.item.create",
+      "item": { "type": "function_call_output", "call_id": call_id, "output": output },
+    }));
+    self.needs_followup = true;
+    self.maybe_follow_up();
+  }
+
+  // Ask for the spoken answer once every tool output is in and the calling response has ended
+  fn maybe_follow_up(&mut self) {
+    if self.needs_followup && self.pending_calls == 0 && !self.duplex.responding.load(Ordering::Relaxed) {
+      self.needs_followup = false;
+      let _ = self.control.send(json!({ "type": "response.create" }));
+    }
+  }
+
+  async fn run(mut self, mut stream: SplitStream<WsStream>, mut tool_rx: mpsc::UnboundedReceiver<(String, String)>, cancel: CancellationToken) -> Result<(), String> {
+    loop {
+      let msg = tokio::select! {
+        _ = cancel.cancelled() => break,
+        Some((call_id, output)) = tool_rx.recv() => {
+          self.tool_done(call_id, output);
+          continue;
+        }
+        m = stream.next() => m,
+      };
+      let text = match msg {
+        None | Some(Ok(Message::Close(_))) => break,
+        Some(Err(e)) => return Err(format!("realtime connection error: {e}")),
+        Some(Ok(Message::Text(t))) => t,
+        Some(Ok(_)) => continue,
+      };
+      if let Ok(ev) = serde_json::from_str::<Value>(&text) { self.handle(&ev); }
+    }
+    Ok(())
+  }
+}
+
+// ---------------------------
+// Client secrets for WebRTC
+// ---------------------------
+
+#[derive(Serialize)]
+pub struct RealtimeClientSecret {
+  /// Short-lived bearer for the SDP exchange; the stored API key never leaves the backend
+  pub value: String,
+  /// Unix seconds
+  pub expires_at: Option<u64>,
+  /// Session configuration the secret was minted with, as echoed by OpenAI
+  pub session: Value,
+  /// Endpoint for the SDP offer
+  pub webrtc_url: String,
+}
+
+/// Mint an ephemeral client secret bound to a session configured from `assistant_realtime`
+/// settings (explicit arguments win).
+pub async fn mint_client_secret(model: Option<String>, voice: Option<String>, instructions: Option<String>) -> Result<RealtimeClientSecret, AidcError> {
+  let key = crate::settings::require_openai_key()?;
+  let opts = SessionOptions::resolve(model, voice, instructions);
+  let mut body = opts.session_config();
+  body["model"] = json!(opts.model);
+  let resp = crate::rate_limit::send("openai", "realtime session", || {
+    SESSIONS_CLIENT.post(SESSIONS_URL).bearer_auth(&key).json(&body)
+  })
+  .await
+  .map_err(|e| AidcError::from_reqwest("openai", &e))?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    return Err(AidcError::from_status("openai", status, &text));
+  }
+  let mut v: Value = resp.json().await.map_err(|e| AidcError::invalid_response("openai", format!("json error: {e}")))?;
+  let secret = v.get("client_secret").cloned().unwrap_or(Value::Null);
+  let value = secret
+    .get("value")
+    .and_then(|x| x.as_str())
+    .ok_or_else(|| AidcError::invalid_response("openai", "missing client_secret.value in response"))?
+    .to_string();
+  if let Some(obj) = v.as_object_mut() { obj.remove("client_secret"); }
+  Ok(RealtimeClientSecret {
+    value,
+    expires_at: secret.get("expires_at").and_then(|x| x.as_u64()),
+    webrtc_url: format!("https://api.openai.com/v1/realtime?model={}", opts.model),
+    session: v,
+  })
+}
+
+/// Mint a short-lived client secret plus session config for a WebRTC session in the webview.
+#[tauri::command]
+pub async fn realtime_client_secret(model: Option<String>, voice: Option<String>, instructions: Option<String>) -> Result<RealtimeClientSecret, AidcError> {
+  mint_client_secret(model, voice, instructions).await
+}
+
+// ---------------------------
+// MCP tools
+// ---------------------------
+
+/// Connected MCP tools in Realtime's flat function format (`{ type, name, description, parameters }`).
+pub async fn build_tools() -> Vec<Value> {
+  let mut tools = {
+    let map = crate::MCP_CLIENTS.lock().await;
+    crate::mcp::build_openai_tools_from_mcp(&map).await
+  };
+  crate::personas::retain_allowed_tools(&mut tools);
+  tools
+    .into_iter()
+    .filter_map(|t| {
+      let f = t.get("function")?;
+      Some(json!({
+        "type": "function",
+        "name": f.get("name")?,
+        "description": f.get("description").cloned().unwrap_or_else(|| json!("")),
+        "parameters": f.get("parameters").cloned().unwrap_or_else(|| json!({ "type": "object", "properties": {} })),
+      }))
+    })
+    .collect()
+}
+
+/// Run a Realtime function call on the MCP server it maps to and return the
+/// function_call_output text. Failures are reported in-band, as chat.rs does for tool messages.
+pub async fn dispatch_function_call(app: &AppHandle, session_id: &str, call_id: &str, name: &str, arguments: &str) -> String {
+  let mut args: Value = serde_json::from_str(arguments).unwrap_or_else(|_| json!({}));
+  if !args.is_object() { args = json!({}); }
+  let parsed = crate::mcp::parse_mcp_fn_call_name(name).filter(|_| crate::personas::allows_tool(name));
+  let Some((server_id, tool)) = parsed else {
+    let error = format!("Unsupported tool function: {name}");
+    emit(app, "realtime:tool-result", json!({ "session_id": session_id, "id": call_id, "function": name, "ok": false, "error": error }));
+    return json!({ "error": error }).to_string();
+  };
+  emit(app, "realtime:tool-call", json!({ "session_id": session_id, "id": call_id, "function": name, "serverId": server_id, "tool": tool, "args": args }));
+  match crate::mcp::call_tool(&crate::MCP_CLIENTS, &server_id, &tool, args).await {
+    Ok(result) => {
+      emit(app, "realtime:tool-result", json!({ "session_id": session_id, "id": call_id, "function": name, "serverId": server_id, "tool": tool, "ok": true, "result": result }));
+      crate::guard::guard_json(app, &format!("mcp:{server_id}/{tool}"), json!({ "serverId": server_id, "tool": tool, "result": result }))
+    }
+    Err(e) => {
+      let error = e.message();
+      tracing::warn!(server = %server_id, %tool, %error, "realtime tool call failed");
+      emit(app, "realtime:tool-result", json!({ "session_id": session_id, "id": call_id, "function": name, "serverId": server_id, "tool": tool, "ok": false, "error": error }));
+      json!({ "serverId": server_id, "tool": tool, "error": error }).to_string()
+    }
+  }
+}
+
+/// Execute a function call from a WebRTC Realtime session; returns the function_call_output text.
+#[tauri::command]
+pub async fn realtime_call_tool(
+  app: AppHandle,
+  name: String,
+  arguments: Option<String>,
+  call_id: Option<String>,
+  session_id: Option<String>,
+) -> Result<String, String> {
+  let args = arguments.unwrap_or_else(|| "{}".to_string());
+  Ok(dispatch_function_call(&app, session_id.as_deref().unwrap_or(""), call_id.as_deref().unwrap_or(""), &name, &args).await)
+}
+
+fn take_session() -> Option<ActiveSession> {
+  SESSION.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Start a realtime voice session on the default microphone and speakers. Any running session
+/// is stopped first. With `conversation_id` the session continues that stored conversation.
+/// Returns the session id used in `realtime:*` events.
+#[tauri::command]
+pub async fn realtime_start(
+  app: AppHandle,
+  model: Option<String>,
+  voice: Option<String>,
+  instructions: Option<String>,
+  enable_tools: Option<bool>,
+  conversation_id: Option<String>,
+) -> Result<String, AidcError> {
+  if let Some(prev) = take_session() { prev.cancel.cancel(); }
+  let key = crate::settings::require_openai_key()?;
+  let mut opts = SessionOptions::resolve(model, voice, instructions);
+  let conversation_id = conversation_id.filter(|c| !c.trim().is_empty());
+  let history = match &conversation_id {
+    Some(cid) => {
+      let ctx = crate::config::load_conversation_context(cid, HANDOFF_MESSAGES).map_err(AidcError::InvalidInput)?;
+      opts.attach(&ctx);
+      ctx.history
+    }
+    None => Vec::new(),
+  };
+  let tools_on = enable_tools.unwrap_or_else(|| {
+    crate::config::get_assistant_realtime_from_settings().get("enable_tools").and_then(|x| x.as_bool()).unwrap_or(false)
+  });
+  if tools_on { opts.tools = build_tools().await; }
+  let id = uuid::Uuid::new_v4().to_string();
+  emit_state(&app, &id, "connecting", None);
+
+  let started = async {
+    let mut ws = connect(&key, &opts.model).await?;
+    for ev in std::iter::once(opts.session_update()).chain(history_items(&history)) {
+      ws.send(Message::Text(ev.to_string()))
+        .await
+        .map_err(|e| AidcError::Network { provider: "openai".into(), message: format!("realtime send failed: {e}") })?;
+    }
+    let duplex = Arc::new(Duplex::default());
+    let (mic_tx, mic_rx) = mpsc::unbounded_channel();
+    let d = duplex.clone();
+    let (audio, out_rate) = tokio::task::spawn_blocking(move || start_audio(mic_tx, d))
+      .await
+      .map_err(|e| AidcError::Internal(format!("audio task failed: {e}")))?
+      .map_err(AidcError::Internal)?;
+    Ok::<_, AidcError>((ws, duplex, mic_rx, audio, out_rate))
+  };
+  let (ws, duplex, mic_rx, audio, out_rate) = match started.await {
+    Ok(v) => v,
+    Err(e) => {
+      emit_state(&app, &id, "error", Some(&e.message()));
+      return Err(e);
+    }
+  };
+
+  let cancel = CancellationToken::new();
+  *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = Some(ActiveSession { id: id.clone(), cancel: cancel.clone() });
+  tracing::info!(session = %id, model = %opts.model, voice = %opts.voice, tools = opts.tools.len(), history = history.len(), "realtime session started");
+  emit_state(&app, &id, "connected", None);
+
+  let (sink, stream) = ws.split();
+  duplex.out_rate.store(out_rate, Ordering::Relaxed);
+  let (control_tx, control_rx) = mpsc::unbounded_channel();
+  let (tool_tx, tool_rx) = mpsc::unbounded_channel();
+  let writer = Writer { app: app.clone(), id: id.clone(), duplex: duplex.clone(), barge_in: opts.barge_in };
+  let reader = Reader {
+    app: app.clone(),
+    id: id.clone(),
+    conversation_id,
+    duplex,
+    control: control_tx,
+    barge_in: opts.barge_in,
+    resampler: Resampler::new(SAMPLE_RATE, out_rate),
+    transcript: String::new(),
+    pending_calls: 0,
+    needs_followup: false,
+    tool_tx,
+  };
+  let sid = id.clone();
+  tauri::async_runtime::spawn(async move {
+    let res = tokio::select! {
+      r = writer.run(sink, mic_rx, control_rx, cancel.clone()) => r,
+      r = reader.run(stream, tool_rx, cancel.clone()) => r,
+    };
+    cancel.cancel();
+    drop(audio);
+    {
+      let mut slot = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+      if slot.as_ref().map(|s| s.id == sid).unwrap_or(false) { *slot = None; }
+    }
+    match res {
+      Ok(()) => emit_state(&app, &sid, "closed", None),
+      Err(e) => {
+        tracing::warn!(session = %sid, error = %e, "realtime session ended with error");
+        emit_state(&app, &sid, "error", Some(&e));
+      }
+    }
+  });
+  Ok(id)
+}
+
+/// Stop the running realtime session. Returns false when none was active.
+#[tauri::command]
+pub fn realtime_stop() -> Result<bool, String> {
+  match take_session() {
+    Some(s) => {
+      s.cancel.cancel();
+      tracing::info!(session = %s.id, "realtime session stopped");
+      Ok(true)
+    }
+    None => Ok(false),
+  }
+}
@@ -0,0 +1,96 @@
+// In-memory history of the last AI results (chat answers, quick prompt results and transcriptions)
+// for the paste-history popup: a global hotkey (`paste_history_hotkey`) opens a small window listing
+// them, and picking one inserts it into the app that was focused before, like Win+V does for the
+// clipboard. Nothing is written to disk; `recent_outputs_limit` caps the list (0 turns it off).
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::Manager;
+
+pub const CHAT: &str = "chat";
+pub const QUICK_PROMPT: &str = "quick_prompt";
+pub const TRANSCRIPTION: &str = "transcription";
+
+pub const WINDOW_LABEL: &str = "paste-history";
+// Longest preview line sent to the popup; the full text is inserted
+const PREVIEW_CHARS: usize = 240;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RecentOutput {
+  pub id: u64,
+  /// "chat", "quick_prompt" or "transcription"
+  pub kind: &'static str,
+  /// Where it came from, e.g. the chat model or "Quick prompt 3"
+  pub label: String,
+  pub text: String,
+  pub preview: String,
+  /// Unix time in milliseconds
+  pub created_at: i64,
+}
+
+static OUTPUTS: Lazy<Mutex<VecDeque<RecentOutput>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+fn preview(text: &str) -> String {
+  let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if line.chars().count() <= PREVIEW_CHARS {
+    return line;
+  }
+  let cut: String = line.chars().take(PREVIEW_CHARS).collect();
+  format!("{}…", cut.trim_end())
+}
+
+/// Remember an AI result. Repeating the newest entry moves it to the top instead of adding a copy.
+pub fn record(kind: &'static str, label: impl Into<String>, text: &str) {
+  let limit = crate::config::get_recent_outputs_limit_from_settings();
+  let text = text.trim();
+  if limit == 0 || text.is_empty() {
+    return;
+  }
+  let mut outputs = OUTPUTS.lock().unwrap_or_else(|e| e.into_inner());
+  outputs.retain(|o| o.text != text);
+  outputs.push_front(RecentOutput {
+    id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+    kind,
+    label: label.into(),
+    text: text.to_string(),
+    preview: preview(text),
+    created_at: chrono::Utc::now().timestamp_millis(),
+  });
+  outputs.truncate(limit);
+}
+
+/// Newest first; `limit` caps the number returned.
+#[tauri::command]
+pub fn list_recent_outputs(limit: Option<usize>) -> Result<Vec<RecentOutput>, String> {
+  let outputs = OUTPUTS.lock().unwrap_or_else(|e| e.into_inner());
+  Ok(outputs.iter().take(limit.unwrap_or(usize::MAX)).cloned().collect())
+}
+
+#[tauri::command]
+pub fn clear_recent_outputs() -> Result<(), String> {
+  OUTPUTS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+  Ok(())
+}
+
+/// Hide the popup, refocus the app that was active when it opened (recorded by
+/// `prepare_quick_actions`) and insert the output there with the configured insert method.
+#[tauri::command]
+pub fn paste_recent_output(app: tauri::AppHandle, id: u64, safe_mode: Option<bool>) -> Result<(), String> {
+  let text = {
+    let outputs = OUTPUTS.lock().unwrap_or_else(|e| e.into_inner());
+    outputs.iter().find(|o| o.id == id).map(|o| o.text.clone()).ok_or_else(|| "This result is no longer in the history".to_string())?
+  };
+  if let Some(win) = app.get_webview_window(WINDOW_LABEL) {
+    let _ = win.hide();
+  }
+  let profile = crate::app_profiles::profile_for_last_foreground();
+  crate::quick_actions::refocus_previous_app()?;
+  std::thread::sleep(Duration::from_millis(profile.focus_delay_ms));
+  crate::quick_actions::insert_text_into_focused_app(text, safe_mode, None)
+}
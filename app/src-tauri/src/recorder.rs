@@ -0,0 +1,419 @@
+// Meeting recorder: records the microphone and what the speakers play (WASAPI loopback, Windows
+// only) into one WAV file, either mixed down to a single track or as two tracks (microphone left,
+// system audio right), and transcribes it with the configured STT engine while it records.
+//
+// Both sources are captured as 16 kHz mono PCM16 (enough for speech, and what Whisper wants) and
+// written against the wall clock, JITTER behind it: loopback delivers nothing while the speakers
+// are silent, so a source that has not caught up by then is padded with silence. The live
+// transcript cuts each track at a pause once a chunk is CHUNK_MIN long (always at CHUNK_MAX) and
+// skips chunks without speech. Two-track recordings are transcribed per track, labelled "Me" and
+// "Others". When the recording stops, its transcript is the one `export_transcript` writes.
+//
+// Events (events.rs):
+//   recorder:state       RecorderState       recording started, stopped or failed
+//   recorder:transcript  RecorderTranscript  new segments of the live transcript
+//   audio:level          AudioLevel          level of the recording (source "recorder")
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+
+use crate::assistant::SAMPLE_RATE;
+use crate::audio_io::{self, MicChunk};
+use crate::events::{self, RecorderState, RecorderTranscript};
+use crate::transcript::Segment;
+
+const TICK: Duration = Duration::from_millis(100);
+// How late a source may deliver before its gap is filled with silence
+const JITTER_SAMPLES: usize = 4_800; // 300 ms
+const CHUNK_MIN_SAMPLES: usize = 20 * SAMPLE_RATE as usize;
+const CHUNK_MAX_SAMPLES: usize = 45 * SAMPLE_RATE as usize;
+// A pause this long ends a chunk once it reached CHUNK_MIN
+const PAUSE_SAMPLES: usize = 9_600; // 600 ms
+const SPEECH_RMS: f32 = 0.015;
+// The WAV header is rewritten this often so a crash still leaves a playable file
+const FLUSH_EVERY: Duration = Duration::from_secs(10);
+// How long stop waits for the last chunks to be transcribed
+const FINAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+type Writer = hound::WavWriter<std::io::BufWriter<std::fs::File>>;
+
+struct Active {
+  state: RecorderState,
+  started: Instant,
+  cancel: CancellationToken,
+  done: oneshot::Receiver<Result<RecorderSummary, String>>,
+}
+
+impl Active {
+  fn status(&self) -> RecorderState {
+    RecorderState { elapsed_ms: self.started.elapsed().as_millis() as u64, ..self.state.clone() }
+  }
+}
+
+static CURRENT: Lazy<Mutex<Option<Active>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RecorderSummary {
+  pub path: String,
+  pub duration_ms: u64,
+  /// Live transcript; empty when the recording was not transcribed
+  pub segments: Vec<Segment>,
+}
+
+fn current() -> std::sync::MutexGuard<'static, Option<Active>> {
+  CURRENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn samples_ms(samples: usize) -> u64 {
+  samples as u64 * 1000 / SAMPLE_RATE as u64
+}
+
+async fn recv(rx: &mut Option<mpsc::UnboundedReceiver<MicChunk>>) -> Option<MicChunk> {
+  match rx {
+    Some(rx) => rx.recv().await,
+    None => std::future::pending().await,
+  }
+}
+
+// `n` samples from the front of `buf`, padded with silence. A source running ahead of the clock
+// (device clocks drift) is trimmed back so the tracks stay in step.
+fn take(buf: &mut VecDeque<i16>, n: usize) -> Vec<i16> {
+  let mut out: Vec<i16> = buf.drain(..n.min(buf.len())).collect();
+  out.resize(n, 0);
+  if buf.len() > 3 * JITTER_SAMPLES {
+    buf.drain(..buf.len() - JITTER_SAMPLES);
+  }
+  out
+}
+
+fn mix(mic: Option<&[i16]>, system: Option<&[i16]>) -> Vec<i16> {
+  match (mic, system) {
+    (Some(a), Some(b)) => a.iter().zip(b).map(|(a, b)| (*a as i32 + *b as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16).collect(),
+    (Some(a), None) | (None, Some(a)) => a.to_vec(),
+    (None, None) => Vec::new(),
+  }
+}
+
+struct Chunk {
+  speaker: Option<&'static str>,
+  start_ms: u64,
+  pcm: Vec<i16>,
+}
+
+// Cuts one track into chunks for the live transcript
+struct Chunker {
+  speaker: Option<&'static str>,
+  // Position of `samples` in the recording
+  start: usize,
+  samples: Vec<i16>,
+  silent: usize,
+  speech: bool,
+}
+
+impl Chunker {
+  fn new(speaker: Option<&'static str>) -> Self {
+    Self { speaker, start: 0, samples: Vec::new(), silent: 0, speech: false }
+  }
+
+  fn push(&mut self, pcm: &[i16]) -> Option<Chunk> {
+    let level = audio_io::rms(&pcm.iter().map(|s| *s as f32 / i16::MAX as f32).collect::<Vec<_>>());
+    self.samples.extend_from_slice(pcm);
+    if level >= SPEECH_RMS {
+      self.speech = true;
+      self.silent = 0;
+    } else {
+      self.silent += pcm.len();
+    }
+    let len = self.samples.len();
+    if (len >= CHUNK_MIN_SAMPLES && self.silent >= PAUSE_SAMPLES) || len >= CHUNK_MAX_SAMPLES {
+      self.cut()
+    } else {
+      None
+    }
+  }
+
+  fn cut(&mut self) -> Option<Chunk> {
+    let pcm = std::mem::take(&mut self.samples);
+    let start = self.start;
+    self.start += pcm.len();
+    self.silent = 0;
+    let speech = std::mem::replace(&mut self.speech, false);
+    (speech && !pcm.is_empty()).then(|| Chunk { speaker: self.speaker, start_ms: samples_ms(start), pcm })
+  }
+}
+
+// Transcribe chunks in order and report each one's segments; returns all of them
+async fn transcribe_chunks(app: AppHandle, id: String, mut rx: mpsc::UnboundedReceiver<Chunk>) -> Vec<Segment> {
+  let mut all = Vec::new();
+  while let Some(chunk) = rx.recv().await {
+    let res = match crate::assistant::wav_bytes(&chunk.pcm) {
+      Ok(wav) => crate::transcribe_with_engine(wav, "audio/wav".into(), true).await.map_err(|e| e.to_string()),
+      Err(e) => Err(e),
+    };
+    match res {
+      Ok(timed) => {
+        let segments = if timed.segments.is_empty() { crate::transcript::single(&timed.text, Some(chunk.pcm.len() as f64 / SAMPLE_RATE as f64)) } else { timed.segments };
+        let segments: Vec<Segment> = segments
+          .into_iter()
+          .map(|s| Segment { start_ms: s.start_ms + chunk.start_ms, end_ms: s.end_ms + chunk.start_ms, speaker: chunk.speaker.map(str::to_string), text: s.text })
+          .collect();
+        if segments.is_empty() {
+          continue;
+        }
+        events::emit(&app, events::RECORDER_TRANSCRIPT, &RecorderTranscript { id: id.clone(), segments: segments.clone(), error: None });
+        all.extend(segments);
+      }
+      Err(e) => {
+        tracing::warn!(recording = %id, error = %e, "meeting transcript chunk failed");
+        events::emit(&app, events::RECORDER_TRANSCRIPT, &RecorderTranscript { id: id.clone(), segments: Vec::new(), error: Some(e) });
+      }
+    }
+  }
+  all.sort_by_key(|s| s.start_ms);
+  all
+}
+
+struct Sources {
+  mic: Option<mpsc::UnboundedReceiver<MicChunk>>,
+  system: Option<mpsc::UnboundedReceiver<MicChunk>>,
+}
+
+async fn run(app: &AppHandle, state: &RecorderState, mut writer: Writer, dual: bool, mut src: Sources, cancel: CancellationToken) -> Result<RecorderSummary, String> {
+  let id = state.id.as_str();
+  let transcribe = state.transcribing;
+  let (has_mic, has_system) = (src.mic.is_some(), src.system.is_some());
+  let (chunk_tx, chunk_rx) = mpsc::unbounded_channel();
+  let worker = transcribe.then(|| tauri::async_runtime::spawn(transcribe_chunks(app.clone(), id.to_string(), chunk_rx)));
+  let mut chunkers = match (transcribe, dual) {
+    (false, _) => Vec::new(),
+    (true, false) => vec![Chunker::new(None)],
+    (true, true) => vec![Chunker::new(Some("Me")), Chunker::new(Some("Others"))],
+  };
+  let mut meter = audio_io::LevelMeter::new(app, "recorder");
+  let (mut mic_buf, mut system_buf) = (VecDeque::new(), VecDeque::new());
+  let started = Instant::now();
+  let mut last_flush = Instant::now();
+  let mut written = 0usize;
+  let mut tick = tokio::time::interval(TICK);
+  tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+  let res: Result<(), String> = loop {
+    tokio::select! {
+      _ = cancel.cancelled() => break Ok(()),
+      chunk = recv(&mut src.mic) => match chunk {
+        Some((pcm, _)) => mic_buf.extend(pcm),
+        None => break Err("Microphone stream ended".into()),
+      },
+      chunk = recv(&mut src.system) => match chunk {
+        Some((pcm, _)) => system_buf.extend(pcm),
+        None => break Err("System audio stream ended".into()),
+      },
+      _ = tick.tick() => {
+        let due = (started.elapsed().as_millis() as usize * SAMPLE_RATE as usize / 1000).saturating_sub(JITTER_SAMPLES + written);
+        if due == 0 { continue; }
+        let mic = has_mic.then(|| take(&mut mic_buf, due));
+        let system = has_system.then(|| take(&mut system_buf, due));
+        let mixed = mix(mic.as_deref(), system.as_deref());
+        let wrote = match (dual, &mic, &system) {
+          (true, Some(left), Some(right)) => left.iter().zip(right).try_for_each(|(l, r)| writer.write_sample(*l).and_then(|()| writer.write_sample(*r))),
+          _ => mixed.iter().try_for_each(|s| writer.write_sample(*s)),
+        };
+        if let Err(e) = wrote {
+          break Err(format!("Write recording failed: {e}"));
+        }
+        written += due;
+        meter.feed(&mixed);
+        let tracks: Vec<&[i16]> = if dual { vec![mic.as_deref().unwrap_or(&[]), system.as_deref().unwrap_or(&[])] } else { vec![mixed.as_slice()] };
+        for (chunker, pcm) in chunkers.iter_mut().zip(tracks) {
+          if let Some(chunk) = chunker.push(pcm) {
+            let _ = chunk_tx.send(chunk);
+          }
+        }
+        if last_flush.elapsed() >= FLUSH_EVERY {
+          if let Err(e) = writer.flush() {
+            tracing::warn!(recording = %id, error = %e, "flushing the recording failed");
+          }
+          last_flush = Instant::now();
+        }
+      }
+    }
+  };
+  for chunker in chunkers.iter_mut() {
+    if let Some(chunk) = chunker.cut() {
+      let _ = chunk_tx.send(chunk);
+    }
+  }
+  drop(chunk_tx);
+  let finalized = writer.finalize().map_err(|e| format!("Finish recording failed: {e}"));
+  let segments = match worker {
+    Some(worker) => match tokio::time::timeout(FINAL_TIMEOUT, worker).await {
+      Ok(Ok(segments)) => segments,
+      Ok(Err(e)) => {
+        tracing::warn!(recording = %id, error = %e, "meeting transcript task failed");
+        Vec::new()
+      }
+      Err(_) => {
+        tracing::warn!(recording = %id, "timed out waiting for the meeting transcript");
+        Vec::new()
+      }
+    },
+    None => Vec::new(),
+  };
+  if !segments.is_empty() {
+    if let Ok(text) = crate::transcript::render(&segments, "txt") {
+      crate::recent_outputs::record(crate::recent_outputs::TRANSCRIPTION, "Meeting transcript", &text);
+    }
+    crate::transcript::remember(segments.clone());
+  }
+  res?;
+  finalized?;
+  Ok(RecorderSummary { path: state.path.clone(), duration_ms: samples_ms(written), segments })
+}
+
+/// Start recording a meeting. `mode` is "mix" (one track, default) or "dual" (microphone left,
+/// system audio right). The microphone is `stt_input_device`; system audio (what the speakers play)
+/// is recorded on Windows only and is on by default there. `transcribe` (default true) makes a
+/// live transcript with the configured STT engine, reported as `recorder:transcript`. Without
+/// `path` the WAV goes to the Downloads folder.
+#[tauri::command]
+pub async fn recorder_start(
+  app: AppHandle,
+  mode: Option<String>,
+  microphone: Option<bool>,
+  system_audio: Option<bool>,
+  transcribe: Option<bool>,
+  path: Option<String>,
+) -> Result<RecorderState, String> {
+  if current().is_some() {
+    return Err("A recording is already running".into());
+  }
+  let microphone = microphone.unwrap_or(true);
+  let system_audio = system_audio.unwrap_or(cfg!(windows));
+  if !microphone && !system_audio {
+    return Err("Nothing to record: enable the microphone or system audio".into());
+  }
+  let dual = match mode.as_deref().map(str::trim).unwrap_or("") {
+    "" | "mix" => false,
+    "dual" => true,
+    other => return Err(format!("Unknown recording mode '{other}' (expected mix or dual)")),
+  };
+  // A single source is always one track
+  let dual = dual && microphone && system_audio;
+  let transcribe = transcribe.unwrap_or(true);
+  let dest = match path.filter(|p| !p.trim().is_empty()) {
+    Some(p) => PathBuf::from(p),
+    None => {
+      let dir = app.path().download_dir().map_err(|e| format!("Could not resolve Downloads folder: {e}"))?;
+      dir.join(format!("Meeting {}.wav", chrono::Local::now().format("%Y-%m-%d %H-%M-%S")))
+    }
+  };
+
+  let (streams, src) = tokio::task::spawn_blocking(move || {
+    audio_io::spawn_streams("meeting-recorder", move || {
+      let mut streams = Vec::new();
+      let mut src = Sources { mic: None, system: None };
+      if microphone {
+        let (tx, rx) = mpsc::unbounded_channel();
+        streams.push(audio_io::open_input(SAMPLE_RATE, tx)?);
+        src.mic = Some(rx);
+      }
+      if system_audio {
+        let (tx, rx) = mpsc::unbounded_channel();
+        streams.push(audio_io::open_loopback(SAMPLE_RATE, tx)?);
+        src.system = Some(rx);
+      }
+      Ok((streams, src))
+    })
+  })
+  .await
+  .map_err(|e| format!("audio task failed: {e}"))??;
+
+  if let Some(dir) = dest.parent().filter(|d| !d.as_os_str().is_empty()) {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Create recording folder failed: {e}"))?;
+  }
+  let spec = hound::WavSpec { channels: if dual { 2 } else { 1 }, sample_rate: SAMPLE_RATE, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+  let writer = hound::WavWriter::create(&dest, spec).map_err(|e| format!("Create recording failed: {e}"))?;
+
+  let id = uuid::Uuid::new_v4().to_string();
+  let cancel = CancellationToken::new();
+  let (done_tx, done_rx) = oneshot::channel();
+  let state = RecorderState {
+    id: id.clone(),
+    state: "recording".into(),
+    path: dest.to_string_lossy().to_string(),
+    mode: if dual { "dual" } else { "mix" }.into(),
+    microphone,
+    system_audio,
+    transcribing: transcribe,
+    elapsed_ms: 0,
+    error: None,
+  };
+  {
+    let mut slot = current();
+    if slot.is_some() {
+      return Err("A recording is already running".into());
+    }
+    *slot = Some(Active { state: state.clone(), started: Instant::now(), cancel: cancel.clone(), done: done_rx });
+  }
+  tracing::info!(recording = %id, path = %state.path, dual, microphone, system_audio, transcribe, "meeting recording started");
+  events::emit(&app, events::RECORDER_STATE, &state);
+
+  let started = state.clone();
+  tauri::async_runtime::spawn(async move {
+    let res = run(&app, &started, writer, dual, src, cancel).await;
+    drop(streams);
+    {
+      let mut slot = current();
+      if slot.as_ref().is_some_and(|a| a.state.id == started.id) {
+        *slot = None;
+      }
+    }
+    let end = match &res {
+      Ok(summary) => RecorderState { state: "stopped".into(), elapsed_ms: summary.duration_ms, ..started.clone() },
+      Err(e) => {
+        tracing::warn!(recording = %started.id, error = %e, "meeting recording ended with error");
+        RecorderState { state: "error".into(), error: Some(e.clone()), ..started.clone() }
+      }
+    };
+    events::emit(&app, events::RECORDER_STATE, &end);
+    let _ = done_tx.send(res);
+  });
+  Ok(state)
+}
+
+/// Stop the recording and return the file and transcript once the last chunks are transcribed.
+/// Returns None when nothing was recording.
+#[tauri::command]
+pub async fn recorder_stop() -> Result<Option<RecorderSummary>, String> {
+  let Some(active) = current().take() else { return Ok(None) };
+  active.cancel.cancel();
+  tracing::info!(recording = %active.state.id, "meeting recording stopped");
+  match active.done.await {
+    Ok(res) => res.map(Some),
+    Err(_) => Err("Recording ended unexpectedly".into()),
+  }
+}
+
+/// The running recording, or None.
+#[tauri::command]
+pub fn recorder_status() -> Result<Option<RecorderState>, String> {
+  Ok(current().as_ref().map(Active::status))
+}
+
+/// Stop recording without waiting for the transcript (app exit); returns whether one was running.
+pub fn cancel() -> bool {
+  match current().take() {
+    Some(active) => {
+      active.cancel.cancel();
+      true
+    }
+    None => false,
+  }
+}
@@ -0,0 +1,97 @@
+// In-memory cache for deterministic chat and quick prompt responses. Only requests sent with
+// temperature 0 are cached, keyed by provider + model + messages, and entries expire after
+// `response_cache_ttl_minutes`. Disabled unless `response_cache_enabled` is set.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+const MAX_ENTRIES: usize = 256;
+
+struct Entry {
+  value: String,
+  stored_at: Instant,
+}
+
+static CACHE: Lazy<Mutex<HashMap<String, Entry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Serialize)]
+pub struct ResponseCacheStats {
+  pub enabled: bool,
+  pub entries: usize,
+  pub ttl_minutes: u64,
+}
+
+fn ttl() -> Duration {
+  Duration::from_secs(crate::config::get_response_cache_ttl_minutes_from_settings() * 60)
+}
+
+/// Whether a request with this temperature may be served from / stored in the cache.
+pub fn cacheable(temp: Option<f32>) -> bool {
+  temp == Some(0.0) && crate::config::get_response_cache_enabled_from_settings()
+}
+
+/// Cache key for a request. Two differently seeded 64-bit hashes make collisions negligible.
+pub fn key(provider: &str, model: &str, messages: &serde_json::Value) -> String {
+  let material = format!("{}\n{}\n{}", provider.trim().to_lowercase(), model.trim(), messages);
+  let half = |seed: u8| {
+    let mut h = DefaultHasher::new();
+    seed.hash(&mut h);
+    material.hash(&mut h);
+    h.finish()
+  };
+  format!("{:016x}{:016x}", half(0), half(1))
+}
+
+pub fn get(key: &str) -> Option<String> {
+  let mut map = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+  let ttl = ttl();
+  match map.get(key) {
+    Some(e) if e.stored_at.elapsed() < ttl => {
+      tracing::debug!(key, "response cache hit");
+      Some(e.value.clone())
+    }
+    Some(_) => {
+      map.remove(key);
+      None
+    }
+    None => None,
+  }
+}
+
+pub fn put(key: String, value: String) {
+  let mut map = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+  let ttl = ttl();
+  map.retain(|_, e| e.stored_at.elapsed() < ttl);
+  if map.len() >= MAX_ENTRIES {
+    if let Some(oldest) = map.iter().min_by_key(|(_, e)| e.stored_at).map(|(k, _)| k.clone()) {
+      map.remove(&oldest);
+    }
+  }
+  map.insert(key, Entry { value, stored_at: Instant::now() });
+}
+
+/// Drop all cached responses. Returns how many were removed.
+#[tauri::command]
+pub fn clear_response_cache() -> Result<usize, String> {
+  let mut map = CACHE.lock().map_err(|_| "lock poisoned".to_string())?;
+  let n = map.len();
+  map.clear();
+  Ok(n)
+}
+
+#[tauri::command]
+pub fn get_response_cache_stats() -> Result<ResponseCacheStats, String> {
+  let map = CACHE.lock().map_err(|_| "lock poisoned".to_string())?;
+  let ttl = ttl();
+  Ok(ResponseCacheStats {
+    enabled: crate::config::get_response_cache_enabled_from_settings(),
+    entries: map.values().filter(|e| e.stored_at.elapsed() < ttl).count(),
+    ttl_minutes: ttl.as_secs() / 60,
+  })
+}
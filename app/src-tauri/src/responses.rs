@@ -0,0 +1,225 @@
+// Responses API chat backend, selectable per conversation as an alternative to Chat Completions.
+// OpenAI keeps the conversation state server-side: a follow-up sends only the turns after the last
+// assistant reply together with `previous_response_id`, so long threads do not resend their whole
+// history. MCP tools are offered as function tools; conversations can also attach vector stores
+// for the built-in file_search tool. If the stored response has expired, the full history is sent.
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::chat::{ChatContent, ChatMessage, FrontendPart};
+use crate::error::AidcError;
+
+const RESPONSES_URL: &str = "https://api.openai.com/v1/responses";
+const MAX_TOOL_ROUNDS: u8 = 6;
+
+#[derive(Serialize)]
+pub struct ResponsesReply {
+  pub text: String,
+  /// Pass back as `previous_response_id` on the next turn
+  pub response_id: String,
+}
+
+// System messages become `instructions` (they are not carried over by previous_response_id);
+// the rest become input items
+fn to_input(messages: Vec<ChatMessage>) -> Result<(Option<String>, Vec<serde_json::Value>), AidcError> {
+  let mut instructions: Vec<String> = Vec::new();
+  let mut input = Vec::new();
+  for m in messages {
+    let role = match m.role.to_ascii_lowercase().as_str() {
+      "system" => "system",
+      "assistant" => "assistant",
+      _ => "user",
+    };
+    // Assistant turns are model output; the API wants them as output_text
+    let text_type = if role == "assistant" { "output_text" } else { "input_text" };
+    let parts: Vec<serde_json::Value> = match m.content {
+      ChatContent::Text(s) => vec![serde_json::json!({ "type": text_type, "text": s })],
+      ChatContent::Parts(parts) => {
+        let mut out = Vec::new();
+        for p in parts {
+          match p {
+            FrontendPart::InputText { text } => out.push(serde_json::json!({ "type": text_type, "text": text })),
+            FrontendPart::InputImage { path, mime } => {
+              out.push(serde_json::json!({ "type": "input_image", "image_url": crate::chat::image_data_url(&path, mime)? }))
+            }
+          }
+        }
+        out
+      }
+    };
+    if role == "system" {
+      instructions.extend(parts.iter().filter_map(|p| p.get("text").and_then(|t| t.as_str()).map(|t| t.to_string())));
+      continue;
+    }
+    if !parts.is_empty() {
+      input.push(serde_json::json!({ "role": role, "content": parts }));
+    }
+  }
+  let instructions = Some(instructions.join("\n\n")).filter(|s| !s.trim().is_empty());
+  Ok((instructions, input))
+}
+
+// Turns the server has not seen yet: those after the first `responded_turns`. Without that count,
+// everything after the last assistant turn; an error shown as an assistant turn then hides the
+// user turn that failed, so callers should pass it.
+fn new_turns(input: &[serde_json::Value], responded_turns: Option<usize>) -> Vec<serde_json::Value> {
+  if let Some(n) = responded_turns {
+    return input[n.min(input.len())..].to_vec();
+  }
+  let last_assistant = input.iter().rposition(|m| m.get("role").and_then(|r| r.as_str()) == Some("assistant"));
+  input[last_assistant.map(|i| i + 1).unwrap_or(0)..].to_vec()
+}
+
+// Chat Completions function tools ({type, function: {...}}) in the flat Responses shape
+fn to_response_tools(tools: Vec<serde_json::Value>) -> Vec<serde_json::Value> {
+  tools
+    .into_iter()
+    .filter_map(|t| {
+      let f = t.get("function")?;
+      Some(serde_json::json!({
+        "type": "function",
+        "name": f.get("name")?,
+        "description": f.get("description").cloned().unwrap_or(serde_json::Value::Null),
+        "parameters": f.get("parameters").cloned().unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} })),
+      }))
+    })
+    .collect()
+}
+
+fn output_text(v: &serde_json::Value) -> String {
+  let mut out = String::new();
+  for item in v.get("output").and_then(|o| o.as_array()).into_iter().flatten() {
+    if item.get("type").and_then(|t| t.as_str()) != Some("message") {
+      continue;
+    }
+    for part in item.get("content").and_then(|c| c.as_array()).into_iter().flatten() {
+      if part.get("type").and_then(|t| t.as_str()) == Some("output_text") {
+        out.push_str(part.get("text").and_then(|t| t.as_str()).unwrap_or(""));
+      }
+    }
+  }
+  out
+}
+
+fn is_stale_previous_id(status: reqwest::StatusCode, body: &str) -> bool {
+  (status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::BAD_REQUEST) && body.contains("previous_response")
+}
+
+async fn post(client: &reqwest::Client, key: &str, body: &serde_json::Value) -> Result<Result<serde_json::Value, (reqwest::StatusCode, String)>, AidcError> {
+  let resp = crate::rate_limit::send("openai", "chat", || client.post(RESPONSES_URL).bearer_auth(key).json(body))
+    .await
+    .map_err(|e| AidcError::from_reqwest("openai", &e))?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    return Ok(Err((status, resp.text().await.unwrap_or_default())));
+  }
+  let v = resp.json().await.map_err(|e| AidcError::invalid_response("openai", format!("json error: {e}")))?;
+  Ok(Ok(v))
+}
+
+pub async fn respond(
+  app: AppHandle,
+  messages: Vec<ChatMessage>,
+  previous_response_id: Option<String>,
+  responded_turns: Option<usize>,
+  vector_store_ids: Vec<String>,
+  key: String,
+  model: String,
+  temp: Option<f32>,
+) -> Result<ResponsesReply, AidcError> {
+  let (instructions, full_input) = to_input(messages)?;
+  let mut previous = previous_response_id.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+  let mut input = if previous.is_some() { new_turns(&full_input, responded_turns) } else { full_input.clone() };
+
+  let mut tools = {
+    let map = crate::MCP_CLIENTS.lock().await;
+    let mut tools = crate::mcp::build_openai_tools_from_mcp(&*map).await;
+    tools.extend(crate::memory::tool_definitions());
+    tools.extend(crate::drafts::tool_definitions());
+    tools.extend(crate::code_exec::tool_definitions());
+    to_response_tools(tools)
+  };
+  let vector_store_ids: Vec<String> = vector_store_ids.into_iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+  if !vector_store_ids.is_empty() {
+    tools.push(serde_json::json!({ "type": "file_search", "vector_store_ids": vector_store_ids }));
+  }
+
+  let client = crate::timeouts::client(crate::timeouts::CHAT);
+  for _ in 0..MAX_TOOL_ROUNDS {
+    let mut body = serde_json::json!({ "model": &model, "input": input, "store": true });
+    if let serde_json::Value::Object(ref mut m) = body {
+      if let Some(i) = &instructions { m.insert("instructions".to_string(), serde_json::json!(i)); }
+      if let Some(p) = &previous { m.insert("previous_response_id".to_string(), serde_json::json!(p)); }
+      if let Some(t) = temp { m.insert("temperature".to_string(), serde_json::json!(t)); }
+      if !tools.is_empty() { m.insert("tools".to_string(), serde_json::Value::Array(tools.clone())); }
+    }
+
+    let v = match post(&client, &key, &body).await? {
+      Ok(v) => v,
+      // Stored responses expire; start a fresh chain with the whole history
+      Err((status, text)) if previous.is_some() && is_stale_previous_id(status, &text) => {
+        tracing::info!("previous response expired; resending full history");
+        previous = None;
+        input = full_input.clone();
+        continue;
+      }
+      Err((status, text)) => return Err(AidcError::from_status("openai", status, &text)),
+    };
+    crate::usage::record_response(crate::usage::CHAT, &model, &v);
+    let response_id = v.get("id").and_then(|x| x.as_str()).unwrap_or("").to_string();
+
+    let calls: Vec<serde_json::Value> = v
+      .get("output")
+      .and_then(|o| o.as_array())
+      .map(|items| items.iter().filter(|i| i.get("type").and_then(|t| t.as_str()) == Some("function_call")).cloned().collect())
+      .unwrap_or_default();
+    if calls.is_empty() {
+      return Ok(ResponsesReply { text: output_text(&v), response_id });
+    }
+
+    // The server already holds the call items; only their outputs are sent back
+    let mut outputs = Vec::new();
+    for call in calls {
+      let call_id = call.get("call_id").and_then(|x| x.as_str()).unwrap_or("").to_string();
+      let fname = call.get("name").and_then(|x| x.as_str()).unwrap_or("");
+      let args_str = call.get("arguments").and_then(|x| x.as_str()).unwrap_or("{}");
+      let mut args: serde_json::Value = serde_json::from_str(args_str).unwrap_or_else(|_| serde_json::json!({}));
+      if !args.is_object() { args = serde_json::json!({}); }
+      let result = crate::chat::dispatch_tool_call(&app, &crate::MCP_CLIENTS, &call_id, fname, args).await;
+      outputs.push(serde_json::json!({ "type": "function_call_output", "call_id": call_id, "output": result }));
+    }
+    previous = Some(response_id);
+    input = outputs;
+  }
+  Err(AidcError::invalid_response("openai", format!("tool call loop exhausted after {MAX_TOOL_ROUNDS} rounds")))
+}
+
+/// Chat through the Responses API. Send the whole conversation; with `previous_response_id` only
+/// the turns after the first `responded_turns` non-system messages (what the server already holds
+/// for that response) are uploaded.
+#[tauri::command]
+pub async fn chat_respond(
+  app: AppHandle,
+  mut messages: Vec<ChatMessage>,
+  previous_response_id: Option<String>,
+  responded_turns: Option<usize>,
+  vector_store_ids: Option<Vec<String>>,
+  include_desktop_context: Option<bool>,
+  task_id: Option<String>,
+) -> Result<ResponsesReply, AidcError> {
+  let key = crate::settings::require_openai_key()?;
+  if include_desktop_context.unwrap_or(false) {
+    crate::context::inject(&app, &mut messages).await?;
+  }
+  let model = crate::settings::get_model_from_settings_or_env();
+  let temp = crate::settings::get_temperature_from_settings_or_env();
+  let task = crate::tasks::register("chat", format!("Chat ({model}, Responses)"), task_id);
+  let label = format!("Chat ({model})");
+  let res = task.run(respond(app.clone(), messages, previous_response_id, responded_turns, vector_store_ids.unwrap_or_default(), key, model, temp)).await;
+  crate::chat::notify_if_cancelled(&app, task.id(), &res);
+  if let Ok(reply) = &res {
+    crate::recent_outputs::record(crate::recent_outputs::CHAT, label, &reply.text);
+  }
+  res
+}
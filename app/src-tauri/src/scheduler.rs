@@ -0,0 +1,364 @@
+// Scheduled and recurring prompts. Entries live in the `schedules` settings array and use
+// five-field cron expressions in local time ("minute hour day-of-month month day-of-week",
+// e.g. "0 18 * * 1-5") or the shortcuts @hourly, @daily, @weekly, @monthly. Each entry runs a
+// free prompt or a quick prompt (1-9) on its input (nothing or the clipboard text) as an
+// "schedule" job and delivers the result with `schedule:result`:
+//   { id, name, deliver: "notification"|"conversation", prompt, text, ran_at }
+// Failures are reported with `schedule:error` { id, name, message }.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Timelike};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+// Minute (unix seconds / 60) each schedule last ran in, so a minute never fires twice
+static LAST_RUN: Lazy<Mutex<HashMap<String, i64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Schedule {
+  pub id: String,
+  pub name: String,
+  pub cron: String,
+  pub enabled: bool,
+  /// Free prompt; ignored when `quick_prompt` is set
+  pub prompt: String,
+  /// Quick prompt index (1-9) whose template is used instead of `prompt`
+  pub quick_prompt: Option<u8>,
+  /// "none" or "clipboard"
+  pub input: String,
+  /// "notification" or "conversation"
+  pub deliver: String,
+  /// Overrides the quick prompt / chat model
+  pub model: Option<String>,
+}
+
+impl Default for Schedule {
+  fn default() -> Self {
+    Schedule {
+      id: String::new(),
+      name: String::new(),
+      cron: "0 18 * * *".to_string(),
+      enabled: true,
+      prompt: String::new(),
+      quick_prompt: None,
+      input: "none".to_string(),
+      deliver: "notification".to_string(),
+      model: None,
+    }
+  }
+}
+
+// ---------------------------
+// Cron expressions
+// ---------------------------
+
+#[derive(Clone, Debug)]
+pub struct Cron {
+  minutes: u64,
+  hours: u64,
+  days: u64,
+  months: u64,
+  weekdays: u64,
+  // Standard cron: when both day fields are restricted, either one matching is enough
+  days_any: bool,
+  weekdays_any: bool,
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+  let mut mask = 0u64;
+  for part in field.split(',') {
+    let (range, step) = match part.split_once('/') {
+      Some((r, s)) => (r, s.parse::<u32>().map_err(|_| format!("invalid step in \"{part}\""))?),
+      None => (part, 1),
+    };
+    if step == 0 {
+      return Err(format!("invalid step in \"{part}\""));
+    }
+    let (lo, hi) = if range == "*" {
+      (min, max)
+    } else if let Some((a, b)) = range.split_once('-') {
+      let a = a.parse::<u32>().map_err(|_| format!("invalid value in \"{part}\""))?;
+      let b = b.parse::<u32>().map_err(|_| format!("invalid value in \"{part}\""))?;
+      (a, b)
+    } else {
+      let v = range.parse::<u32>().map_err(|_| format!("invalid value in \"{part}\""))?;
+      // "5/15" means from 5 to the end in steps of 15
+      (v, if part.contains('/') { max } else { v })
+    };
+    if lo < min || hi > max || lo > hi {
+      return Err(format!("\"{part}\" is outside {min}-{max}"));
+    }
+    let mut v = lo;
+    while v <= hi {
+      mask |= 1 << v;
+      v += step;
+    }
+  }
+  Ok(mask)
+}
+
+impl Cron {
+  pub fn parse(expr: &str) -> Result<Self, String> {
+    let expr = match expr.trim() {
+      "@hourly" => "0 * * * *",
+      "@daily" | "@midnight" => "0 0 * * *",
+      "@weekly" => "0 0 * * 0",
+      "@monthly" => "0 0 1 * *",
+      other => other,
+    };
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    if fields.len() != 5 {
+      return Err("Cron expression needs 5 fields: minute hour day-of-month month day-of-week".into());
+    }
+    let mut weekdays = parse_field(fields[4], 0, 7)?;
+    // 7 is Sunday as well
+    if weekdays & (1 << 7) != 0 {
+      weekdays = (weekdays | 1) & !(1 << 7);
+    }
+    Ok(Cron {
+      minutes: parse_field(fields[0], 0, 59)?,
+      hours: parse_field(fields[1], 0, 23)?,
+      days: parse_field(fields[2], 1, 31)?,
+      months: parse_field(fields[3], 1, 12)?,
+      weekdays,
+      days_any: fields[2] == "*",
+      weekdays_any: fields[4] == "*",
+    })
+  }
+
+  fn day_matches<Tz: TimeZone>(&self, t: &DateTime<Tz>) -> bool {
+    let dom = self.days & (1 << t.day()) != 0;
+    let dow = self.weekdays & (1 << t.weekday().num_days_from_sunday()) != 0;
+    let day = match (self.days_any, self.weekdays_any) {
+      (true, true) => true,
+      (true, false) => dow,
+      (false, true) => dom,
+      (false, false) => dom || dow,
+    };
+    day && self.months & (1 << t.month()) != 0
+  }
+
+  pub fn matches<Tz: TimeZone>(&self, t: &DateTime<Tz>) -> bool {
+    self.day_matches(t) && self.hours & (1 << t.hour()) != 0 && self.minutes & (1 << t.minute()) != 0
+  }
+
+  /// First matching minute strictly after `from` (searches up to about four years ahead)
+  pub fn next_after(&self, from: DateTime<Local>) -> Option<DateTime<Local>> {
+    let mut t = from.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+    let limit = from + Duration::days(4 * 366);
+    while t < limit {
+      if !self.day_matches(&t) {
+        // Jump to the start of the next day (DST gaps just land on the next valid time)
+        t = (t + Duration::days(1)).with_hour(0).and_then(|x| x.with_minute(0)).unwrap_or(t + Duration::hours(1));
+        continue;
+      }
+      if self.hours & (1 << t.hour()) == 0 {
+        t = (t + Duration::hours(1)).with_minute(0).unwrap_or(t + Duration::minutes(1));
+        continue;
+      }
+      if self.minutes & (1 << t.minute()) != 0 {
+        return Some(t);
+      }
+      t += Duration::minutes(1);
+    }
+    None
+  }
+}
+
+// ---------------------------
+// Running schedules
+// ---------------------------
+
+fn load_schedules() -> Vec<Schedule> {
+  crate::config::load_settings_json()
+    .get("schedules")
+    .cloned()
+    .and_then(|v| serde_json::from_value::<Vec<Schedule>>(v).ok())
+    .unwrap_or_default()
+}
+
+async fn execute(app: &tauri::AppHandle, s: &Schedule) -> Result<String, String> {
+  let input = match s.input.as_str() {
+    "clipboard" => {
+      let text = arboard::Clipboard::new().and_then(|mut c| c.get_text()).unwrap_or_default();
+      if text.trim().is_empty() {
+        return Err("The clipboard holds no text".into());
+      }
+      Some(text)
+    }
+    _ => None,
+  };
+  let settings = crate::config::load_settings_json();
+  let str_setting = |k: &str| settings.get(k).and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+  let model_override = s.model.clone().filter(|m| !m.trim().is_empty());
+  let (system_content, user_content, model, temp) = match s.quick_prompt {
+    Some(index) => {
+      // Same resolution as the hotkey: prompt library binding, persona and quick prompt settings
+      let resolved = crate::quick_prompts::resolve_prompt(crate::quick_prompts::load_quick_prompt_with_notify(Some(app), index));
+      let input = input.ok_or_else(|| "Quick prompts need an input (set input to \"clipboard\")".to_string())?;
+      // The input takes the place of the selection
+      let user_content = resolved.user_content(&input, None);
+      (resolved.system_content, user_content, model_override.unwrap_or(resolved.model), resolved.temp)
+    }
+    None => {
+      if s.prompt.trim().is_empty() {
+        return Err("The schedule has no prompt".into());
+      }
+      let user = match input {
+        Some(text) => format!("{}\n\n{}", s.prompt.trim(), text),
+        None => s.prompt.trim().to_string(),
+      };
+      let model = model_override.or_else(|| str_setting("quick_prompt_model")).unwrap_or_else(crate::config::get_model_from_settings_or_env);
+      (str_setting("system_prompt").unwrap_or_default(), user, model, crate::config::get_temperature_from_settings_or_env())
+    }
+  };
+  crate::quick_prompts::complete_quick_prompt(&model, temp, &system_content, &user_content).await
+}
+
+fn display_name(s: &Schedule) -> String {
+  if s.name.trim().is_empty() { s.id.clone() } else { s.name.trim().to_string() }
+}
+
+/// Run one schedule as a background job. Returns the job id.
+pub fn run(app: &tauri::AppHandle, s: Schedule) -> String {
+  let app2 = app.clone();
+  crate::jobs::spawn(app, "schedule", format!("Scheduled: {}", display_name(&s)), None, move |_job| async move {
+    let name = display_name(&s);
+    match execute(&app2, &s).await {
+      Ok(text) => {
+        let prompt = match s.quick_prompt {
+          Some(i) => format!("Quick prompt {i}"),
+          None => s.prompt.clone(),
+        };
+        let _ = app2.emit("schedule:result", serde_json::json!({
+          "id": s.id,
+          "name": name,
+          "deliver": s.deliver,
+          "prompt": prompt,
+          "text": text,
+          "ran_at": Local::now().to_rfc3339(),
+        }));
+        tracing::info!(schedule = %s.id, "scheduled prompt delivered");
+        Ok(text)
+      }
+      Err(e) => {
+        tracing::warn!(schedule = %s.id, error = %e, "scheduled prompt failed");
+        let _ = app2.emit("schedule:error", serde_json::json!({ "id": s.id, "name": name, "message": e }));
+        Err(e)
+      }
+    }
+  })
+}
+
+fn due_now(now: DateTime<Local>) -> Vec<Schedule> {
+  let minute = now.timestamp() / 60;
+  let mut last = LAST_RUN.lock().unwrap_or_else(|e| e.into_inner());
+  load_schedules()
+    .into_iter()
+    .filter(|s| s.enabled && !s.id.is_empty())
+    .filter(|s| Cron::parse(&s.cron).map(|c| c.matches(&now)).unwrap_or(false))
+    .filter(|s| last.insert(s.id.clone(), minute) != Some(minute))
+    .collect()
+}
+
+/// Start the scheduler loop once. It wakes at every minute boundary and runs due entries.
+pub fn start(app: tauri::AppHandle) {
+  if STARTED.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  tauri::async_runtime::spawn(async move {
+    loop {
+      let now = Local::now();
+      let wait_ms = 60_000 - (now.second() as u64 * 1000 + (now.timestamp_subsec_millis() % 1000) as u64);
+      tokio::time::sleep(std::time::Duration::from_millis(wait_ms.max(200))).await;
+      for s in due_now(Local::now()) {
+        run(&app, s);
+      }
+    }
+  });
+}
+
+// ---------------------------
+// Commands
+// ---------------------------
+
+#[derive(Serialize)]
+pub struct ScheduleInfo {
+  #[serde(flatten)]
+  pub schedule: Schedule,
+  pub next_run: Option<String>,
+  pub error: Option<String>,
+}
+
+fn info(s: Schedule) -> ScheduleInfo {
+  match Cron::parse(&s.cron) {
+    Ok(c) => {
+      let next_run = if s.enabled { c.next_after(Local::now()).map(|t| t.to_rfc3339()) } else { None };
+      ScheduleInfo { schedule: s, next_run, error: None }
+    }
+    Err(e) => ScheduleInfo { schedule: s, next_run: None, error: Some(e) },
+  }
+}
+
+/// All schedules with their next run time (local, RFC 3339) or cron error.
+#[tauri::command]
+pub fn get_schedules() -> Result<Vec<ScheduleInfo>, String> {
+  Ok(load_schedules().into_iter().map(info).collect())
+}
+
+/// Validate and store the schedules (replaces the `schedules` setting).
+#[tauri::command]
+pub async fn save_schedules(list: Vec<Schedule>) -> Result<Vec<ScheduleInfo>, String> {
+  let mut list = list;
+  for s in list.iter_mut() {
+    if s.id.trim().is_empty() {
+      s.id = uuid::Uuid::new_v4().to_string();
+    }
+    Cron::parse(&s.cron).map_err(|e| format!("{}: {e}", display_name(s)))?;
+    if let Some(i) = s.quick_prompt {
+      if !(1..=9).contains(&i) {
+        return Err(format!("{}: quick prompt must be 1-9", display_name(s)));
+      }
+    }
+    if s.input != "none" && s.input != "clipboard" {
+      return Err(format!("{}: input must be \"none\" or \"clipboard\"", display_name(s)));
+    }
+    if s.deliver != "notification" && s.deliver != "conversation" {
+      return Err(format!("{}: deliver must be \"notification\" or \"conversation\"", display_name(s)));
+    }
+  }
+  let value = serde_json::to_value(&list).map_err(|e| format!("Serialize schedules failed: {e}"))?;
+  crate::config::save_settings(serde_json::json!({ "schedules": value })).await?;
+  Ok(list.into_iter().map(info).collect())
+}
+
+/// Run a stored schedule immediately. Returns the job id.
+#[tauri::command]
+pub fn run_schedule_now(app: tauri::AppHandle, id: String) -> Result<String, String> {
+  let s = load_schedules().into_iter().find(|s| s.id == id).ok_or_else(|| format!("Unknown schedule: {id}"))?;
+  Ok(run(&app, s))
+}
+
+/// Next run times for a cron expression (for previews while editing).
+#[tauri::command]
+pub fn preview_cron(expr: String, count: Option<usize>) -> Result<Vec<String>, String> {
+  let cron = Cron::parse(&expr)?;
+  let mut out = Vec::new();
+  let mut t = Local::now();
+  for _ in 0..count.unwrap_or(3).clamp(1, 20) {
+    match cron.next_after(t) {
+      Some(n) => {
+        out.push(n.to_rfc3339());
+        t = n;
+      }
+      None => break,
+    }
+  }
+  Ok(out)
+}
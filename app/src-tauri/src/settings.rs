@@ -1,7 +1,37 @@
-// Settings helpers and OpenAI model listing
+// Settings helpers and OpenAI model listing (against `openai_base_url` when set)
 
-pub fn get_api_key_from_settings_or_env() -> Result<String, String> {
-  crate::config::get_api_key_from_settings_or_env()
+/// OpenAI key for commands returning AidcError (missing key → `missing_api_key`).
+pub fn require_openai_key() -> Result<String, crate::error::AidcError> {
+  crate::config::get_api_key_from_settings_or_env().map_err(|_| crate::error::AidcError::missing_api_key("openai"))
+}
+
+/// Key for chat requests to `base_url`: the OpenAI key (required) for api.openai.com, otherwise
+/// the optional `openai_base_url_api_key` (local servers usually need none). Empty when unset.
+pub fn require_chat_key(base_url: &str) -> Result<String, crate::error::AidcError> {
+  if crate::config::is_default_openai_base_url(base_url) {
+    return require_openai_key();
+  }
+  Ok(crate::config::get_custom_base_url_api_key().unwrap_or_default())
+}
+
+/// Add `key` from `require_chat_key` as the bearer token; no Authorization header when it is empty.
+pub fn with_chat_key(req: reqwest::RequestBuilder, key: &str) -> reqwest::RequestBuilder {
+  if key.is_empty() { req } else { req.bearer_auth(key) }
+}
+
+/// Anthropic key for commands returning AidcError (missing key → `missing_api_key`).
+pub fn require_anthropic_key() -> Result<String, crate::error::AidcError> {
+  crate::config::get_anthropic_api_key_from_settings_or_env().map_err(|_| crate::error::AidcError::missing_api_key("anthropic"))
+}
+
+/// Gemini key for commands returning AidcError (missing key → `missing_api_key`).
+pub fn require_gemini_key() -> Result<String, crate::error::AidcError> {
+  crate::config::get_gemini_api_key_from_settings_or_env().map_err(|_| crate::error::AidcError::missing_api_key("gemini"))
+}
+
+/// ElevenLabs key for commands returning AidcError (missing key → `missing_api_key`).
+pub fn require_elevenlabs_key() -> Result<String, crate::error::AidcError> {
+  crate::config::get_elevenlabs_api_key_from_settings_or_env().map_err(|_| crate::error::AidcError::missing_api_key("elevenlabs"))
 }
 
 pub fn get_model_from_settings_or_env() -> String {
@@ -14,15 +44,15 @@ pub fn get_temperature_from_settings_or_env() -> Option<f32> {
 
 #[tauri::command]
 pub async fn list_openai_models() -> Result<Vec<String>, String> {
-  let key = get_api_key_from_settings_or_env()?;
+  let base_url = crate::config::get_openai_base_url_from_settings_or_env();
+  let is_openai = crate::config::is_default_openai_base_url(&base_url);
+  let key = require_chat_key(&base_url).map_err(|e| e.message())?;
   let client = reqwest::Client::builder()
     .timeout(std::time::Duration::from_secs(15))
     .connect_timeout(std::time::Duration::from_secs(10))
     .build()
     .unwrap_or_else(|_| reqwest::Client::new());
-  let resp = client
-    .get("https://api.openai.com/v1/models")
-    .bearer_auth(key)
+  let resp = with_chat_key(client.get(format!("{base_url}/models")), &key)
     .send()
     .await
     .map_err(|e| format!("request failed: {e}"))?;
@@ -30,7 +60,8 @@ pub async fn list_openai_models() -> Result<Vec<String>, String> {
   if !resp.status().is_success() {
     let status = resp.status();
     let body_text = resp.text().await.unwrap_or_default();
-    return Err(format!("OpenAI error: {status} {body_text}"));
+    let server = if is_openai { "OpenAI" } else { "Model server" };
+    return Err(format!("{server} error: {status} {body_text}"));
   }
 
   let v: serde_json::Value = resp.json().await.map_err(|e| format!("json error: {e}"))?;
@@ -38,7 +69,8 @@ pub async fn list_openai_models() -> Result<Vec<String>, String> {
     .and_then(|d| d.as_array())
     .map(|arr| arr.iter()
       .filter_map(|m| m.get("id").and_then(|x| x.as_str()).map(|s| s.to_string()))
-      .filter(|id| id.starts_with("gpt-") || id.contains("gpt-4") || id.contains("gpt-4o"))
+      // Custom servers only list what they serve; OpenAI also lists non-chat models
+      .filter(|id| !is_openai || id.starts_with("gpt-") || id.contains("gpt-4") || id.contains("gpt-4o"))
       .collect())
     .unwrap_or_else(|| Vec::new());
   ids.sort();
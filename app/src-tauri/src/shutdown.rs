@@ -0,0 +1,43 @@
+// Orderly cleanup when the app exits (tray Exit, app.exit, OS logoff): cancels running tasks
+// (chats, transcriptions, downloads — partial downloads keep their .part file for resuming),
+// stops speech (local SAPI/`say` children and OpenAI streams) and the meeting recorder (its WAV is
+// finished, the rest of its transcript dropped), disconnects MCP servers so their child processes
+// end, closes the memory database, writes pending usage records and deletes temporary speech, OCR
+// and audiobook files (captures and images may still be attached to a conversation).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+// Time for cancelled tasks and MCP transports to wind down before the process ends
+const GRACE: Duration = Duration::from_millis(500);
+
+static DONE: AtomicBool = AtomicBool::new(false);
+
+/// Run once from the `RunEvent::Exit` handler, on the main thread.
+pub fn run() {
+  if DONE.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  let tasks = crate::tasks::cancel_all();
+  let _ = crate::tts_win_native::local_tts_stop();
+  crate::tts_queue::stop();
+  crate::tts_playback::stop();
+  crate::audio_io::stop_level_monitor();
+  let streams = crate::tts_openai::stop_all_streams() + crate::tts_elevenlabs::stop_all_streams() + crate::recorder::cancel() as usize;
+  let mcp = tauri::async_runtime::block_on(async {
+    let n = crate::mcp::disconnect_all(&crate::MCP_CLIENTS).await;
+    if tasks + streams + n > 0 {
+      tokio::time::sleep(GRACE).await;
+    }
+    n
+  });
+  crate::memory::close();
+  crate::conversations::close();
+  crate::usage::flush();
+  let temp_files: u32 = [crate::temp_files::TTS, crate::temp_files::OCR, crate::temp_files::AUDIOBOOK]
+    .into_iter()
+    .filter_map(|kind| crate::temp_files::cleanup(Some(kind), Some(Duration::ZERO)).ok())
+    .map(|r| r.removed)
+    .sum();
+  tracing::info!(tasks, streams, mcp, temp_files, "shutdown cleanup done");
+}
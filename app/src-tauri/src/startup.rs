@@ -0,0 +1,85 @@
+// Startup timings. `run()` and `setup` time their phases here; work the first window does not need
+// (tray menu, default quick prompts file, background monitors) runs after setup has returned, so
+// the window can show first, and is reported as deferred. The main window reports its first paint.
+// `startup_timings` returns the lot, in milliseconds since process start.
+
+use std::sync::Mutex;
+use std::time::Instant;
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+
+static PROCESS_START: Lazy<Instant> = Lazy::new(Instant::now);
+static PHASES: Lazy<Mutex<Vec<Phase>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static SETUP_DONE_MS: OnceCell<u64> = OnceCell::new();
+static FIRST_PAINT_MS: OnceCell<u64> = OnceCell::new();
+
+#[derive(Serialize, Clone)]
+pub struct Phase {
+  pub name: &'static str,
+  pub start_ms: u64,
+  pub duration_ms: u64,
+  /// Ran after setup returned
+  pub deferred: bool,
+}
+
+#[derive(Serialize)]
+pub struct StartupTimings {
+  pub phases: Vec<Phase>,
+  pub setup_done_ms: Option<u64>,
+  /// Main window mounted and painted (reported by the frontend)
+  pub first_paint_ms: Option<u64>,
+  pub uptime_ms: u64,
+}
+
+fn since_start(t: Instant) -> u64 {
+  t.saturating_duration_since(*PROCESS_START).as_millis() as u64
+}
+
+/// Start the clock; call first thing in `run()`.
+pub fn begin() {
+  Lazy::force(&PROCESS_START);
+}
+
+/// Run `f` as the named phase and record how long it took.
+pub fn phase<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+  let started = Instant::now();
+  let out = f();
+  let phase = Phase {
+    name,
+    start_ms: since_start(started),
+    duration_ms: started.elapsed().as_millis() as u64,
+    deferred: SETUP_DONE_MS.get().is_some(),
+  };
+  tracing::debug!(phase = name, duration_ms = phase.duration_ms, deferred = phase.deferred, "startup phase");
+  PHASES.lock().unwrap_or_else(|e| e.into_inner()).push(phase);
+  out
+}
+
+pub fn setup_done() {
+  let ms = since_start(Instant::now());
+  if SETUP_DONE_MS.set(ms).is_ok() {
+    tracing::info!(setup_done_ms = ms, "startup setup finished");
+  }
+}
+
+/// Called once by the main window after its first render.
+#[tauri::command]
+pub fn startup_first_paint() -> Result<(), String> {
+  let ms = since_start(Instant::now());
+  if FIRST_PAINT_MS.set(ms).is_ok() {
+    tracing::info!(first_paint_ms = ms, "main window painted");
+  }
+  Ok(())
+}
+
+/// Per-phase startup durations.
+#[tauri::command]
+pub fn startup_timings() -> Result<StartupTimings, String> {
+  Ok(StartupTimings {
+    phases: PHASES.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+    setup_done_ms: SETUP_DONE_MS.get().copied(),
+    first_paint_ms: FIRST_PAINT_MS.get().copied(),
+    uptime_ms: since_start(Instant::now()),
+  })
+}
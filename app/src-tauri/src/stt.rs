@@ -1,13 +1,7 @@
 use reqwest;
-use once_cell::sync::Lazy;
 
-static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
-  reqwest::Client::builder()
-    .timeout(std::time::Duration::from_secs(60))
-    .connect_timeout(std::time::Duration::from_secs(10))
-    .build()
-    .unwrap_or_else(|_| reqwest::Client::new())
-});
+use crate::error::AidcError;
+use crate::transcript::{Segment, Timed};
 
 fn build_transcriptions_url(base_url: &str) -> String {
   let b = base_url.trim().trim_end_matches('/');
@@ -18,47 +12,112 @@ fn build_transcriptions_url(base_url: &str) -> String {
   }
 }
 
+// Provider label for errors: "openai" for the official endpoint, otherwise the endpoint host
+pub(crate) fn provider_for_base_url(base_url: &str) -> String {
+  let b = base_url.trim();
+  if b.starts_with("https://api.openai.com") { return "openai".to_string(); }
+  b.split("://").nth(1).unwrap_or(b).split('/').next().unwrap_or("stt").to_string()
+}
+
+// Upload file name; the API picks the decoder from its extension
+fn upload_name(mime: &str) -> &'static str {
+  let m = mime.to_ascii_lowercase();
+  if m.contains("webm") { "audio.webm" }
+  else if m.contains("wav") || m.contains("wave") { "audio.wav" }
+  else if m.contains("mpeg") || m.contains("mp3") { "audio.mp3" }
+  else if m.contains("ogg") || m.contains("opus") { "audio.ogg" }
+  else if m.contains("mp4") || m.contains("m4a") || m.contains("aac") { "audio.m4a" }
+  else if m.contains("flac") { "audio.flac" }
+  else { "audio.bin" }
+}
+
+/// Model to use when segment timestamps are required: the official endpoint's gpt-4o transcribe
+/// models only return text, so Whisper is used there instead.
+pub(crate) fn timestamp_model(base_url: &str, model: &str) -> String {
+  if base_url.trim().starts_with("https://api.openai.com") && !model.trim().to_lowercase().starts_with("whisper") {
+    "whisper-1".to_string()
+  } else {
+    model.to_string()
+  }
+}
+
+// Duration of a WAV upload; compressed formats are only known when the response reports it
+pub(crate) fn wav_seconds(audio: &[u8]) -> Option<f64> {
+  let reader = hound::WavReader::new(std::io::Cursor::new(audio)).ok()?;
+  let spec = reader.spec();
+  Some(reader.duration() as f64 / spec.sample_rate.max(1) as f64)
+}
+
+// Segment timestamps from a verbose_json response
+fn parse_segments(v: &serde_json::Value) -> Vec<Segment> {
+  let ms = |s: &serde_json::Value, k: &str| (s.get(k).and_then(|x| x.as_f64()).unwrap_or(0.0).max(0.0) * 1000.0).round() as u64;
+  v.get("segments")
+    .and_then(|s| s.as_array())
+    .map(|arr| {
+      arr
+        .iter()
+        .filter_map(|s| {
+          let text = s.get("text").and_then(|t| t.as_str())?.trim().to_string();
+          (!text.is_empty()).then(|| Segment { start_ms: ms(s, "start"), end_ms: ms(s, "end"), speaker: None, text })
+        })
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
 /// Transcribe audio bytes using OpenAI Whisper API (expects WEBM/Opus by default).
-/// Returns the transcribed text on success.
-pub async fn transcribe(key: Option<String>, base_url: String, model: String, audio: Vec<u8>, mime: String) -> Result<String, String> {
-  if audio.is_empty() { return Err("Audio data is empty".into()); }
+/// Returns the transcribed text on success, with segment timestamps for Whisper models.
+pub async fn transcribe(key: Option<String>, base_url: String, model: String, audio: Vec<u8>, mime: String) -> Result<Timed, AidcError> {
+  if audio.is_empty() { return Err(AidcError::InvalidInput("Audio data is empty".into())); }
+  let provider = provider_for_base_url(&base_url);
   // Build multipart form: model + file
-  let file_name = if mime.contains("webm") { "audio.webm" } else { "audio.bin" };
-  let part = reqwest::multipart::Part::bytes(audio)
-    .file_name(file_name.to_string())
-    .mime_str(&mime)
-    .map_err(|e| format!("mime error: {e}"))?;
-
-  let form = reqwest::multipart::Form::new()
-    .text("model", model)
-    .part("file", part);
+  let file_name = upload_name(&mime);
 
-  let client = &*CLIENT;
+  let client = crate::timeouts::client(crate::timeouts::STT);
   let url = build_transcriptions_url(&base_url);
-  let req = client
-    .post(url)
-    .multipart(form);
-  let req = if let Some(k) = key {
-    if k.trim().is_empty() { req } else { req.bearer_auth(k) }
-  } else {
-    req
-  };
-  let resp = req
-    .send()
-    .await
-    .map_err(|e| format!("request failed: {e}"))?;
+  let key = key.filter(|k| !k.trim().is_empty());
+  // Only Whisper models return segment timestamps (the gpt-4o transcribe models reject verbose_json)
+  let timed = model.trim().to_lowercase().starts_with("whisper");
+  // The form is rebuilt per attempt because a multipart body cannot be replayed
+  let resp = crate::rate_limit::send(&provider, "transcription", || {
+    let part = reqwest::multipart::Part::bytes(audio.clone()).file_name(file_name.to_string());
+    let part = match part.mime_str(&mime) {
+      Ok(p) => p,
+      Err(_) => reqwest::multipart::Part::bytes(audio.clone()).file_name(file_name.to_string()),
+    };
+    let mut form = reqwest::multipart::Form::new()
+      .text("model", model.clone())
+      .part("file", part);
+    if timed {
+      form = form.text("response_format", "verbose_json").text("timestamp_granularities[]", "segment");
+    }
+    let req = client.post(&url).multipart(form);
+    match &key {
+      Some(k) => req.bearer_auth(k),
+      None => req,
+    }
+  })
+  .await
+  .map_err(|e| AidcError::from_reqwest(&provider, &e))?;
 
   if !resp.status().is_success() {
     let status = resp.status();
     let body = resp.text().await.unwrap_or_default();
-    return Err(format!("STT error: {status} {body}"));
+    return Err(AidcError::from_status(&provider, status, &body));
   }
 
-  let body = resp.bytes().await.map_err(|e| format!("read body error: {e}"))?;
-  if let Ok(v) = serde_json::from_slice::<serde_json::Value>(&body) {
+  let body = resp.bytes().await.map_err(|e| AidcError::from_reqwest(&provider, &e))?;
+  let parsed = serde_json::from_slice::<serde_json::Value>(&body).ok();
+  let audio_seconds = parsed.as_ref().and_then(|v| v.pointer("/usage/seconds")).and_then(|s| s.as_f64()).or_else(|| wav_seconds(&audio));
+  crate::usage::record(crate::usage::STT, &model, crate::usage::Usage { audio_seconds, ..Default::default() });
+  if let Some(v) = parsed {
     let text = v.get("text").and_then(|t| t.as_str()).unwrap_or("").to_string();
-    if !text.trim().is_empty() { return Ok(text); }
+    if !text.trim().is_empty() {
+      let mut segments = parse_segments(&v);
+      if segments.is_empty() { segments = crate::transcript::single(&text, audio_seconds); }
+      return Ok(Timed { text, segments });
+    }
   }
   let text = String::from_utf8_lossy(&body).to_string();
-  Ok(text)
+  Ok(Timed { segments: crate::transcript::single(&text, audio_seconds), text })
 }
@@ -0,0 +1,166 @@
+// Local STT benchmark: transcribes a fixed sample clip with a chosen engine/model and reports
+// the real-time factor (processing time / audio length), memory growth and accuracy against the
+// known transcript, so users can pick between Whisper sizes and Parakeet on their hardware.
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::jobs::JobReporter;
+
+// The 11 s JFK clip shipped with whisper.cpp; fetched once and kept next to the models
+const SAMPLE_URL: &str = "https://github.com/ggerganov/whisper.cpp/raw/master/samples/jfk.wav";
+const SAMPLE_FILE: &str = "jfk.wav";
+const SAMPLE_TRANSCRIPT: &str =
+  "And so my fellow Americans, ask not what your country can do for you, ask what you can do for your country.";
+const MAX_RUNS: u32 = 5;
+
+#[derive(Serialize)]
+pub struct SttBenchmarkResult {
+  pub engine: String,
+  pub model: String,
+  pub cuda: bool,
+  pub audio_seconds: f64,
+  /// First run, including model load
+  pub cold_ms: u64,
+  /// Average of the remaining runs (equals cold_ms when only one run)
+  pub warm_ms: u64,
+  pub cold_rtf: f64,
+  /// Warm processing time / audio length; below 1.0 is faster than real time
+  pub rtf: f64,
+  pub rss_before_bytes: Option<u64>,
+  pub rss_after_bytes: Option<u64>,
+  pub peak_rss_bytes: Option<u64>,
+  pub transcript: String,
+  /// Share of reference words recognized in order (0..1)
+  pub word_accuracy: f64,
+}
+
+fn sample_path() -> Option<PathBuf> {
+  let models = crate::stt_whisper::models_dir()?;
+  Some(models.parent()?.join("benchmark").join(SAMPLE_FILE))
+}
+
+async fn ensure_sample(job: &JobReporter) -> Result<PathBuf, String> {
+  let path = sample_path().ok_or_else(|| "Unsupported platform for model path".to_string())?;
+  if path.exists() { return Ok(path); }
+  job.wait_for_network().await?;
+  crate::downloads::download(Some(job), crate::downloads::DownloadSpec::new(SAMPLE_URL, path)).await
+}
+
+fn words(s: &str) -> Vec<String> {
+  s.split_whitespace()
+    .map(|w| w.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase())
+    .filter(|w| !w.is_empty())
+    .collect()
+}
+
+// Longest common subsequence of words, relative to the reference length
+fn word_accuracy(reference: &str, hypothesis: &str) -> f64 {
+  let r = words(reference);
+  let h = words(hypothesis);
+  if r.is_empty() { return 0.0; }
+  let mut prev = vec![0usize; h.len() + 1];
+  for rw in &r {
+    let mut cur = vec![0usize; h.len() + 1];
+    for (j, hw) in h.iter().enumerate() {
+      cur[j + 1] = if rw == hw { prev[j] + 1 } else { cur[j].max(prev[j + 1]) };
+    }
+    prev = cur;
+  }
+  prev[h.len()] as f64 / r.len() as f64
+}
+
+async fn transcribe(engine: &str, model: &str, has_cuda: bool, audio: Vec<u8>) -> Result<String, String> {
+  let mime = "audio/wav".to_string();
+  if engine == "parakeet" {
+    crate::stt_parakeet::transcribe_local(audio, mime, has_cuda, model.to_string()).await
+  } else {
+    crate::stt_whisper::transcribe_with_model(audio, mime, model).await
+  }
+}
+
+/// Benchmark a local engine ("whisper" | "parakeet"). `model` is a Whisper size/URL or a
+/// Parakeet model id; defaults come from settings. Missing models are downloaded first.
+#[tauri::command]
+pub async fn stt_benchmark(
+  app: tauri::AppHandle,
+  engine: String,
+  model: Option<String>,
+  has_cuda: Option<bool>,
+  runs: Option<u32>,
+  task_id: Option<String>,
+) -> Result<SttBenchmarkResult, String> {
+  if !cfg!(feature = "local-stt") {
+    return Err("Local STT is not available: app built without 'local-stt' feature.".into());
+  }
+  let engine = engine.trim().to_lowercase();
+  if engine != "whisper" && engine != "parakeet" {
+    return Err(format!("Unknown benchmark engine: {engine}"));
+  }
+  let model = model.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()).unwrap_or_else(|| {
+    if engine == "parakeet" {
+      let lm = crate::config::get_stt_local_model_from_settings_or_env();
+      if lm.to_lowercase().contains("parakeet") { lm } else { "parakeet-tdt-0.6b-v2".to_string() }
+    } else {
+      crate::stt_whisper::model_url_for("")
+    }
+  });
+  let cuda = engine == "parakeet" && has_cuda.unwrap_or_else(crate::config::get_stt_parakeet_has_cuda_from_settings_or_env);
+  let runs = runs.unwrap_or(3).clamp(1, MAX_RUNS);
+  let label = format!("STT benchmark ({engine}: {model})");
+
+  crate::jobs::run(&app, "benchmark", label, task_id, move |job| async move {
+    let sample = ensure_sample(&job).await?;
+    // Fetch the model up front so download time is not counted as load time
+    job.wait_for_network().await?;
+    if engine == "parakeet" {
+      crate::stt_parakeet::prefetch_model_with_progress(job.clone(), model.clone()).await?;
+    } else {
+      crate::stt_whisper::prefetch_model_with_progress(job.clone(), Some(crate::stt_whisper::model_url_for(&model))).await?;
+    }
+    let audio = std::fs::read(&sample).map_err(|e| format!("read sample failed: {e}"))?;
+    let audio_seconds = crate::stt_whisper::decode_to_f32_mono_16k(&audio, "audio/wav")?.len() as f64 / 16000.0;
+    if audio_seconds <= 0.0 { return Err("Benchmark sample is empty".into()); }
+
+    // Start cold so the first run includes model load and memory growth is attributable
+    crate::stt_whisper::unload_models();
+    crate::stt_parakeet::unload_models();
+    let before = crate::utils::process_memory();
+    let mut timings = Vec::with_capacity(runs as usize);
+    let mut transcript = String::new();
+    for i in 0..runs {
+      job.progress(i as u64, Some(runs as u64), Some(&format!("Run {} of {runs}", i + 1)));
+      let started = Instant::now();
+      transcript = transcribe(&engine, &model, cuda, audio.clone()).await?;
+      timings.push(started.elapsed().as_millis() as u64);
+    }
+    job.progress(runs as u64, Some(runs as u64), Some("Done"));
+    let after = crate::utils::process_memory();
+
+    let cold_ms = timings[0];
+    let warm_ms = if timings.len() > 1 {
+      timings[1..].iter().sum::<u64>() / (timings.len() as u64 - 1)
+    } else {
+      cold_ms
+    };
+    tracing::info!(%engine, %model, cuda, cold_ms, warm_ms, audio_seconds, "stt benchmark finished");
+    Ok(SttBenchmarkResult {
+      engine,
+      model,
+      cuda,
+      audio_seconds,
+      cold_ms,
+      warm_ms,
+      cold_rtf: cold_ms as f64 / 1000.0 / audio_seconds,
+      rtf: warm_ms as f64 / 1000.0 / audio_seconds,
+      rss_before_bytes: before.map(|m| m.0),
+      rss_after_bytes: after.map(|m| m.0),
+      peak_rss_bytes: after.map(|m| m.1),
+      word_accuracy: word_accuracy(SAMPLE_TRANSCRIPT, &transcript),
+      transcript,
+    })
+  })
+  .await
+}
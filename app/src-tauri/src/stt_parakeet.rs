@@ -1,30 +1,16 @@
 use std::fs;
-use std::io::Write;
 use std::path::PathBuf;
 
 #[cfg(feature = "local-stt")]
 use once_cell::sync::Lazy;
 #[cfg(feature = "local-stt")]
 use std::sync::Mutex;
-#[cfg(feature = "local-stt")]
-use reqwest;
-#[cfg(feature = "local-stt")]
-use serde_json::json;
-#[cfg(feature = "local-stt")]
-use tauri::Emitter;
-
-#[cfg(feature = "local-stt")]
-static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
-  reqwest::Client::builder()
-    .connect_timeout(std::time::Duration::from_secs(30))
-    .build()
-    .unwrap_or_else(|_| reqwest::Client::new())
-});
 
 #[cfg(feature = "local-stt")]
 struct ParakeetAsrCache {
   has_cuda: bool,
   model_dir: String,
+  loaded_at_ms: u64,
   asr: parakeet_rs_jason::asr::ParakeetASR,
 }
 
@@ -35,6 +21,7 @@ static PARKEET_ASR_CACHE: Lazy<Mutex<Option<ParakeetAsrCache>>> = Lazy::new(|| M
 struct ParakeetTdtCache {
   has_cuda: bool,
   model_dir: String,
+  loaded_at_ms: u64,
   asr: parakeet_rs_alt::ParakeetTDT,
 }
 
@@ -97,45 +84,8 @@ fn file_name_from_url(url: &str) -> String {
 }
 
 #[cfg(feature = "local-stt")]
-async fn download_file_with_progress(app: Option<&tauri::AppHandle>, url: &str, path: &PathBuf, event_name: &str) -> Result<(), String> {
-  let mut tmp = path.clone();
-  let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("download");
-  tmp.set_file_name(format!("{}.part", file_name));
-
-  let resp = CLIENT.get(url).send().await.map_err(|e| format!("download failed: {e}"))?;
-  if !resp.status().is_success() {
-    return Err(format!("download error: {}", resp.status()));
-  }
-
-  let total = resp.content_length().unwrap_or(0);
-
-  let mut stream = resp.bytes_stream();
-  let mut f = fs::File::create(&tmp).map_err(|e| format!("write tmp failed: {e}"))?;
-  use futures_util::StreamExt;
-  let mut received: u64 = 0;
-  while let Some(chunk) = stream.next().await {
-    let bytes = chunk.map_err(|e| format!("download chunk failed: {e}"))?;
-    f.write_all(&bytes).map_err(|e| format!("write failed: {e}"))?;
-    received += bytes.len() as u64;
-    if let Some(app) = app {
-      let _ = app.emit(
-        event_name,
-        json!({"kind":"progress","file":file_name,"received":received,"total":total}),
-      );
-    }
-  }
-  drop(f);
-  #[cfg(target_os = "windows")]
-  { if path.exists() { let _ = fs::remove_file(path); } }
-  fs::rename(&tmp, path).map_err(|e| format!("rename model failed: {e}"))?;
-
-  if let Some(app) = app {
-    let _ = app.emit(
-      event_name,
-      json!({"kind":"done","file":file_name,"path":path.to_string_lossy().to_string()}),
-    );
-  }
-  Ok(())
+async fn download_file_with_progress(job: Option<&crate::jobs::JobReporter>, url: &str, path: &PathBuf) -> Result<(), String> {
+  crate::downloads::download(job, crate::downloads::DownloadSpec::new(url, path.clone())).await.map(|_| ())
 }
 
 #[cfg(feature = "local-stt")]
@@ -214,7 +164,7 @@ fn extract_tar_gz(archive_path: &PathBuf, dest_dir: &PathBuf) -> Result<(), Stri
 }
 
 #[cfg(feature = "local-stt")]
-async fn ensure_model_files(app: Option<&tauri::AppHandle>) -> Result<PathBuf, String> {
+async fn ensure_model_files(job: Option<&crate::jobs::JobReporter>) -> Result<PathBuf, String> {
   let dir = models_dir("parakeet-tdt-0.6b-v2").ok_or_else(|| "Unsupported platform for model path".to_string())?;
   if !dir.exists() {
     fs::create_dir_all(&dir).map_err(|e| format!("create model dir failed: {e}"))?;
@@ -228,7 +178,7 @@ async fn ensure_model_files(app: Option<&tauri::AppHandle>) -> Result<PathBuf, S
   let mut tar_path = dir.clone();
   tar_path.push(&tar_name);
 
-  download_file_with_progress(app, MODEL_TARBALL_URL, &tar_path, "stt-parakeet-model-download").await?;
+  download_file_with_progress(job, MODEL_TARBALL_URL, &tar_path).await?;
   extract_tar_gz(&tar_path, &dir)?;
 
   if let Some(root) = find_model_root(&dir) {
@@ -239,7 +189,7 @@ async fn ensure_model_files(app: Option<&tauri::AppHandle>) -> Result<PathBuf, S
 }
 
 #[cfg(feature = "local-stt")]
-async fn ensure_model_files_v3(app: Option<&tauri::AppHandle>) -> Result<PathBuf, String> {
+async fn ensure_model_files_v3(job: Option<&crate::jobs::JobReporter>) -> Result<PathBuf, String> {
   let dir = models_dir("parakeet-tdt-0.6b-v3").ok_or_else(|| "Unsupported platform for model path".to_string())?;
   if !dir.exists() {
     fs::create_dir_all(&dir).map_err(|e| format!("create model dir failed: {e}"))?;
@@ -261,7 +211,7 @@ async fn ensure_model_files_v3(app: Option<&tauri::AppHandle>) -> Result<PathBuf
     if path.exists() {
       continue;
     }
-    download_file_with_progress(app, url, &path, "stt-parakeet-model-download").await?;
+    download_file_with_progress(job, url, &path).await?;
   }
 
   let all_present = required.iter().all(|f| dir.join(f).exists());
@@ -386,17 +336,17 @@ fn validate_model_files_for_mode(model_dir: &PathBuf, has_cuda: bool) -> Result<
 }
 
 #[cfg(feature = "local-stt")]
-pub async fn prefetch_model_with_progress(app: tauri::AppHandle, local_model: String) -> Result<String, String> {
+pub async fn prefetch_model_with_progress(job: crate::jobs::JobReporter, local_model: String) -> Result<String, String> {
   let root = if is_parakeet_v3_local_model(&local_model) {
-    ensure_model_files_v3(Some(&app)).await?
+    ensure_model_files_v3(Some(&job)).await?
   } else {
-    ensure_model_files(Some(&app)).await?
+    ensure_model_files(Some(&job)).await?
   };
   Ok(root.to_string_lossy().to_string())
 }
 
 #[cfg(not(feature = "local-stt"))]
-pub async fn prefetch_model_with_progress(_app: tauri::AppHandle, _local_model: String) -> Result<String, String> {
+pub async fn prefetch_model_with_progress(_job: crate::jobs::JobReporter, _local_model: String) -> Result<String, String> {
   Err("Local STT is not available: app built without 'local-stt' feature.".into())
 }
 
@@ -430,9 +380,11 @@ pub async fn transcribe_local(audio: Vec<u8>, mime: String, has_cuda: bool, loca
       *cache = Some(ParakeetTdtCache {
         has_cuda,
         model_dir: model_dir_key.clone(),
+        loaded_at_ms: crate::local_models::now_ms(),
         asr,
       });
     }
+    crate::local_models::touch();
 
     let asr = cache.as_mut().ok_or_else(|| "parakeet v3 cache init failed".to_string())?;
     let res = asr
@@ -477,15 +429,53 @@ pub async fn transcribe_local(audio: Vec<u8>, mime: String, has_cuda: bool, loca
     *cache = Some(ParakeetAsrCache {
       has_cuda,
       model_dir: model_dir_key.clone(),
+      loaded_at_ms: crate::local_models::now_ms(),
       asr,
     });
   }
+  crate::local_models::touch();
 
   let asr = cache.as_mut().ok_or_else(|| "parakeet cache init failed".to_string())?;
   let res = asr.asr.infer_buffer(&pcm).map_err(|e| format!("parakeet transcribe failed: {e}"))?;
   Ok(res.to_text().trim().to_string())
 }
 
+#[cfg(feature = "local-stt")]
+fn loaded_model(engine_model: &str, has_cuda: bool, model_dir: &str, loaded_at_ms: u64) -> crate::local_models::LoadedModel {
+  crate::local_models::LoadedModel {
+    engine: "parakeet".into(),
+    model: engine_model.into(),
+    cuda: has_cuda,
+    file_bytes: crate::local_models::size_on_disk(std::path::Path::new(model_dir)),
+    loaded_at_ms,
+  }
+}
+
+#[cfg(feature = "local-stt")]
+pub fn loaded_models() -> Vec<crate::local_models::LoadedModel> {
+  let mut out = Vec::new();
+  if let Some(c) = PARKEET_ASR_CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+    out.push(loaded_model("parakeet-tdt-0.6b-v2", c.has_cuda, &c.model_dir, c.loaded_at_ms));
+  }
+  if let Some(c) = PARKEET_TDT_CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+    out.push(loaded_model("parakeet-tdt-0.6b-v3", c.has_cuda, &c.model_dir, c.loaded_at_ms));
+  }
+  out
+}
+
+#[cfg(feature = "local-stt")]
+pub fn unload_models() -> usize {
+  let v2 = PARKEET_ASR_CACHE.lock().unwrap_or_else(|e| e.into_inner()).take().is_some();
+  let v3 = PARKEET_TDT_CACHE.lock().unwrap_or_else(|e| e.into_inner()).take().is_some();
+  v2 as usize + v3 as usize
+}
+
+#[cfg(not(feature = "local-stt"))]
+pub fn loaded_models() -> Vec<crate::local_models::LoadedModel> { Vec::new() }
+
+#[cfg(not(feature = "local-stt"))]
+pub fn unload_models() -> usize { 0 }
+
 #[cfg(not(feature = "local-stt"))]
 pub async fn transcribe_local(_audio: Vec<u8>, _mime: String, _has_cuda: bool, _local_model: String) -> Result<String, String> {
   Err("Local STT is not available: app built without 'local-stt' feature.".into())
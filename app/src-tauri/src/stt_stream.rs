@@ -0,0 +1,207 @@
+// Live dictation with local Whisper: the microphone (stt_input_device) is transcribed in a
+// sliding window while the user speaks, so interim text appears within about a second instead of
+// only after recording stops.
+//
+// The window grows from the last committed point and is re-transcribed every STEP. Text is
+// committed (never revised again) when the speaker pauses, or, when the window reaches
+// MAX_WINDOW, up to the start of its last segment so the words being spoken are not cut.
+// Silence before the first word is not sent to Whisper (it tends to hallucinate on it).
+//
+// Events (events.rs):
+//   stt:partial  SttPartial  committed text plus the current interim guess
+//   stt:final    SttFinal    full transcript after stop, or the error that ended the session
+//   audio:level  AudioLevel  microphone level for the meter (source "live_dictation")
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use tauri::AppHandle;
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+
+use crate::assistant::SAMPLE_RATE;
+use crate::audio_io::{self, MicChunk};
+use crate::events::{self, SttFinal, SttPartial};
+
+const STEP: Duration = Duration::from_millis(1_000);
+const MAX_WINDOW_SAMPLES: usize = 15 * SAMPLE_RATE as usize;
+// A pause this long commits everything heard so far
+const PAUSE_SAMPLES: usize = 11_200; // 700 ms
+const SPEECH_RMS: f32 = 0.015;
+// Kept before the first word so its onset is not clipped
+const PRE_ROLL_SAMPLES: usize = 4_800; // 300 ms
+// How long stop waits for the last window to be transcribed
+const FINAL_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct ActiveStream {
+  id: String,
+  cancel: CancellationToken,
+  done: oneshot::Receiver<Result<String, String>>,
+}
+
+static STREAM: Lazy<Mutex<Option<ActiveStream>>> = Lazy::new(|| Mutex::new(None));
+
+struct Window {
+  samples: Vec<f32>,
+  committed: String,
+  heard_speech: bool,
+  silent_samples: usize,
+  new_samples: usize,
+}
+
+impl Window {
+  fn push(&mut self, pcm: &[i16], level: f32) {
+    self.samples.extend(pcm.iter().map(|s| *s as f32 / i16::MAX as f32));
+    self.new_samples += pcm.len();
+    if level >= SPEECH_RMS {
+      self.heard_speech = true;
+      self.silent_samples = 0;
+    } else {
+      self.silent_samples += pcm.len();
+      if !self.heard_speech && self.samples.len() > PRE_ROLL_SAMPLES {
+        self.samples.drain(..self.samples.len() - PRE_ROLL_SAMPLES);
+      }
+    }
+  }
+
+  fn commit(&mut self, text: &str) {
+    let text = text.trim();
+    if text.is_empty() { return; }
+    if !self.committed.is_empty() { self.committed.push(' '); }
+    self.committed.push_str(text);
+  }
+
+  fn text_with(&self, interim: &str) -> String {
+    match (self.committed.is_empty(), interim.trim().is_empty()) {
+      (_, true) => self.committed.clone(),
+      (true, false) => interim.trim().to_string(),
+      (false, false) => format!("{} {}", self.committed, interim.trim()),
+    }
+  }
+}
+
+async fn transcribe(model: &Path, pcm: Vec<f32>) -> Result<crate::transcript::Timed, String> {
+  let model = model.to_path_buf();
+  tokio::task::spawn_blocking(move || crate::stt_whisper::transcribe_pcm(&model, &pcm))
+    .await
+    .map_err(|e| format!("whisper task failed: {e}"))?
+}
+
+fn emit_partial(app: &AppHandle, id: &str, w: &Window, interim: &str) {
+  events::emit(app, events::STT_PARTIAL, &SttPartial {
+    id: id.to_string(),
+    text: w.text_with(interim),
+    committed: w.committed.clone(),
+    interim: interim.trim().to_string(),
+  });
+}
+
+async fn run_loop(app: &AppHandle, id: &str, model: PathBuf, mut mic_rx: mpsc::UnboundedReceiver<MicChunk>, cancel: CancellationToken) -> Result<String, String> {
+  let mut w = Window { samples: Vec::new(), committed: String::new(), heard_speech: false, silent_samples: 0, new_samples: 0 };
+  let mut meter = audio_io::LevelMeter::new(app, "live_dictation");
+  let mut tick = tokio::time::interval(STEP);
+  tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+  loop {
+    tokio::select! {
+      _ = cancel.cancelled() => break,
+      chunk = mic_rx.recv() => {
+        let Some((pcm, level)) = chunk else { return Err("Microphone stream ended".into()) };
+        meter.feed(&pcm);
+        w.push(&pcm, level);
+      }
+      _ = tick.tick() => {
+        if w.new_samples == 0 || !w.heard_speech { continue; }
+        w.new_samples = 0;
+        let timed = transcribe(&model, w.samples.clone()).await?;
+        if w.silent_samples >= PAUSE_SAMPLES {
+          w.commit(&timed.text);
+          w.samples.clear();
+          w.heard_speech = false;
+          emit_partial(app, id, &w, "");
+        } else if w.samples.len() >= MAX_WINDOW_SAMPLES && timed.segments.len() > 1 {
+          // Keep the last segment (possibly cut mid-word) in the window for the next pass
+          let (done, last) = timed.segments.split_at(timed.segments.len() - 1);
+          w.commit(&done.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" "));
+          let keep_from = (last[0].start_ms as usize * SAMPLE_RATE as usize / 1000).min(w.samples.len());
+          w.samples.drain(..keep_from);
+          emit_partial(app, id, &w, &last[0].text);
+        } else if w.samples.len() >= MAX_WINDOW_SAMPLES * 2 {
+          // One endless segment: commit it rather than growing without bound
+          w.commit(&timed.text);
+          w.samples.clear();
+          emit_partial(app, id, &w, "");
+        } else {
+          emit_partial(app, id, &w, &timed.text);
+        }
+      }
+    }
+  }
+  if w.heard_speech && !w.samples.is_empty() {
+    let timed = transcribe(&model, std::mem::take(&mut w.samples)).await?;
+    w.commit(&timed.text);
+  }
+  Ok(w.committed)
+}
+
+fn take_stream() -> Option<ActiveStream> {
+  STREAM.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Start live dictation on the configured microphone and return its session id. Interim text
+/// arrives as `stt:partial`; a running session is stopped first.
+#[tauri::command]
+pub async fn stt_transcribe_stream_start(app: AppHandle) -> Result<String, String> {
+  if !cfg!(feature = "local-stt") {
+    return Err("Live dictation needs local Whisper: app built without 'local-stt' feature.".into());
+  }
+  if let Some(prev) = take_stream() { prev.cancel.cancel(); }
+  // Download or locate the model before the microphone opens
+  let model = crate::stt_whisper::ensure_model_file().await?;
+  let (mic, mic_rx) = tokio::task::spawn_blocking(|| audio_io::start_mic(SAMPLE_RATE))
+    .await
+    .map_err(|e| format!("audio task failed: {e}"))??;
+
+  let id = uuid::Uuid::new_v4().to_string();
+  let cancel = CancellationToken::new();
+  let (done_tx, done_rx) = oneshot::channel::<Result<String, String>>();
+  *STREAM.lock().unwrap_or_else(|e| e.into_inner()) = Some(ActiveStream { id: id.clone(), cancel: cancel.clone(), done: done_rx });
+  tracing::info!(session = %id, "live dictation started");
+
+  let sid = id.clone();
+  tauri::async_runtime::spawn(async move {
+    let res = run_loop(&app, &sid, model, mic_rx, cancel).await;
+    drop(mic);
+    {
+      let mut slot = STREAM.lock().unwrap_or_else(|e| e.into_inner());
+      if slot.as_ref().map(|s| s.id == sid).unwrap_or(false) { *slot = None; }
+    }
+    match &res {
+      Ok(text) => {
+        crate::recent_outputs::record(crate::recent_outputs::TRANSCRIPTION, "Live dictation", text);
+        events::emit(&app, events::STT_FINAL, &SttFinal { id: sid.clone(), text: text.clone(), error: None });
+      }
+      Err(e) => {
+        tracing::warn!(session = %sid, error = %e, "live dictation ended with error");
+        events::emit(&app, events::STT_FINAL, &SttFinal { id: sid.clone(), text: String::new(), error: Some(e.clone()) });
+      }
+    }
+    let _ = done_tx.send(res);
+  });
+  Ok(id)
+}
+
+/// Stop live dictation and return the full transcript once the last window is transcribed.
+/// Returns None when no session was running.
+#[tauri::command]
+pub async fn stt_transcribe_stream_stop() -> Result<Option<String>, String> {
+  let Some(stream) = take_stream() else { return Ok(None) };
+  stream.cancel.cancel();
+  tracing::info!(session = %stream.id, "live dictation stopped");
+  match tokio::time::timeout(FINAL_TIMEOUT, stream.done).await {
+    Ok(Ok(res)) => res.map(Some),
+    Ok(Err(_)) => Err("Live dictation ended unexpectedly".into()),
+    Err(_) => Err("Timed out waiting for the final transcript".into()),
+  }
+}
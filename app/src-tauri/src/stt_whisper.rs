@@ -1,27 +1,27 @@
 use std::fs;
-use std::io::Write;
 use std::path::PathBuf;
 
+#[cfg(feature = "local-stt")]
 use once_cell::sync::Lazy;
-use reqwest;
-use symphonia::core::audio::{AudioBufferRef, SampleBuffer};
-use symphonia::core::codecs::DecoderOptions;
-use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
-use symphonia::core::probe::Hint;
-use tauri::Emitter;
+#[cfg(feature = "local-stt")]
+use std::sync::Mutex;
 #[cfg(feature = "local-stt")]
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
 static DEFAULT_MODEL_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin";
 
-static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
-  reqwest::Client::builder()
-    .connect_timeout(std::time::Duration::from_secs(30))
-    .build()
-    .unwrap_or_else(|_| reqwest::Client::new())
-});
+#[cfg(feature = "local-stt")]
+struct WhisperCache {
+  model_path: String,
+  loaded_at_ms: u64,
+  ctx: WhisperContext,
+}
+
+#[cfg(feature = "local-stt")]
+static WHISPER_CACHE: Lazy<Mutex<Option<WhisperCache>>> = Lazy::new(|| Mutex::new(None));
+
+// Anything smaller is an error page or a truncated download
+const MIN_MODEL_BYTES: u64 = 10 * 1024 * 1024;
 
 pub(crate) fn models_dir() -> Option<PathBuf> {
   #[cfg(target_os = "windows")]
@@ -63,160 +63,61 @@ pub fn local_model_status(url: String) -> Result<(bool, String, Vec<String>), St
     return Ok((false, p, vec![file_name]));
   }
   if let Ok(md) = fs::metadata(&path) {
-    if md.len() > 10 * 1024 * 1024 {
+    if md.len() > MIN_MODEL_BYTES {
       return Ok((true, p, Vec::new()));
     }
   }
   Ok((false, p, vec![file_name]))
 }
 
-async fn ensure_model_file() -> Result<PathBuf, String> {
-  let dir = models_dir().ok_or_else(|| "Unsupported platform for model path".to_string())?;
-  if !dir.exists() { fs::create_dir_all(&dir).map_err(|e| format!("create model dir failed: {e}"))?; }
-  // Determine model URL from settings, env, or default.
-  let url = {
-    let v = crate::config::load_settings_json();
-    if let Some(s) = v.get("stt_whisper_model_url").and_then(|x| x.as_str()) {
-      if !s.trim().is_empty() { s.trim().to_string() } else { std::env::var("AIDC_WHISPER_MODEL_URL").unwrap_or_else(|_| DEFAULT_MODEL_URL.to_string()) }
-    } else {
-      std::env::var("AIDC_WHISPER_MODEL_URL").unwrap_or_else(|_| DEFAULT_MODEL_URL.to_string())
-    }
-  };
-  let file_name = file_name_from_url(&url);
-  let mut path = dir.clone();
-  path.push(&file_name);
-  if path.exists() {
-    // Basic sanity: file size > 10MB
-    if let Ok(md) = fs::metadata(&path) {
-      if md.len() > 10 * 1024 * 1024 { return Ok(path); }
-    }
-  }
-  // Download into temp then rename
-  let mut tmp = dir.clone();
-  tmp.push(format!("{}.part", file_name));
-  let resp = CLIENT.get(&url).send().await.map_err(|e| format!("download failed: {e}"))?;
-  if !resp.status().is_success() { return Err(format!("download error: {}", resp.status())); }
-  let bytes = resp.bytes().await.map_err(|e| format!("download bytes failed: {e}"))?;
-  if bytes.len() < 10 * 1024 * 1024 { return Err("downloaded model too small".into()); }
-  let mut f = fs::File::create(&tmp).map_err(|e| format!("write tmp failed: {e}"))?;
-  f.write_all(&bytes).map_err(|e| format!("write tmp failed: {e}"))?;
-  drop(f);
-  #[cfg(target_os = "windows")]
-  { if path.exists() { let _ = fs::remove_file(&path); } }
-  fs::rename(&tmp, &path).map_err(|e| format!("rename model failed: {e}"))?;
-  Ok(path)
-}
-
-// Prefetch helper with progress events. Emits `stt-model-download` events with JSON: { kind: "progress", received, total } and { kind: "done", path }
-pub async fn prefetch_model_with_progress(app: tauri::AppHandle, url_opt: Option<String>) -> Result<String, String> {
-  let dir = models_dir().ok_or_else(|| "Unsupported platform for model path".to_string())?;
-  if !dir.exists() { fs::create_dir_all(&dir).map_err(|e| format!("create model dir failed: {e}"))?; }
-  let url = url_opt
+// Model URL from the explicit argument, settings, env, or default
+fn model_url(url_opt: Option<String>) -> String {
+  url_opt
     .and_then(|s| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) })
     .or_else(|| {
       let v = crate::config::load_settings_json();
-      v.get("stt_whisper_model_url").and_then(|x| x.as_str()).map(|s| s.to_string())
+      v.get("stt_whisper_model_url").and_then(|x| x.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
     })
-    .unwrap_or_else(|| std::env::var("AIDC_WHISPER_MODEL_URL").unwrap_or_else(|_| DEFAULT_MODEL_URL.to_string()));
+    .unwrap_or_else(|| std::env::var("AIDC_WHISPER_MODEL_URL").unwrap_or_else(|_| DEFAULT_MODEL_URL.to_string()))
+}
 
-  let file_name = file_name_from_url(&url);
-  let mut path = dir.clone();
-  path.push(&file_name);
-  if path.exists() {
-    if let Ok(md) = fs::metadata(&path) { if md.len() > 10 * 1024 * 1024 { return Ok(path.to_string_lossy().to_string()); } }
-  }
-  let mut tmp = dir.clone();
-  tmp.push(format!("{}.part", file_name));
+// Accepts a full URL or a ggml model size ("tiny", "base.en", "small", "large-v3", ...)
+pub(crate) fn model_url_for(model: &str) -> String {
+  let m = model.trim();
+  if m.is_empty() { return model_url(None); }
+  if m.starts_with("http://") || m.starts_with("https://") { return m.to_string(); }
+  let name = m.trim_start_matches("ggml-").trim_end_matches(".bin");
+  format!("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{name}.bin")
+}
 
-  let resp = CLIENT.get(&url).send().await.map_err(|e| format!("download failed: {e}"))?;
-  if !resp.status().is_success() { return Err(format!("download error: {}", resp.status())); }
-  let total = resp.content_length().unwrap_or(0);
-  let mut stream = resp.bytes_stream();
-  let mut f = fs::File::create(&tmp).map_err(|e| format!("write tmp failed: {e}"))?;
-  let mut received: u64 = 0;
-  use futures_util::StreamExt;
-  while let Some(chunk) = stream.next().await {
-    let bytes = chunk.map_err(|e| format!("download chunk failed: {e}"))?;
-    f.write_all(&bytes).map_err(|e| format!("write failed: {e}"))?;
-    received += bytes.len() as u64;
-    let _ = app.emit("stt-model-download", serde_json::json!({"kind":"progress","received":received,"total":total}));
+async fn ensure_model_file_at(job: Option<&crate::jobs::JobReporter>, url: &str) -> Result<PathBuf, String> {
+  let dir = models_dir().ok_or_else(|| "Unsupported platform for model path".to_string())?;
+  let path = dir.join(file_name_from_url(url));
+  if path.exists() {
+    // Basic sanity: file size > 10MB
+    if let Ok(md) = fs::metadata(&path) {
+      if md.len() > MIN_MODEL_BYTES { return Ok(path); }
+    }
   }
-  drop(f);
-  #[cfg(target_os = "windows")]
-  { if path.exists() { let _ = fs::remove_file(&path); } }
-  fs::rename(&tmp, &path).map_err(|e| format!("rename model failed: {e}"))?;
-  let p = path.to_string_lossy().to_string();
-  let _ = app.emit("stt-model-download", serde_json::json!({"kind":"done","path":p}));
-  Ok(p)
+  crate::downloads::download(job, crate::downloads::DownloadSpec::new(url, path).min_size(MIN_MODEL_BYTES)).await
 }
 
-pub(crate) fn decode_to_f32_mono_16k(audio: &[u8], _mime: &str) -> Result<Vec<f32>, String> {
-  // Decode container using Symphonia to interleaved f32 and track sample rate/channels
-  let mss = MediaSourceStream::new(Box::new(std::io::Cursor::new(audio.to_vec())), Default::default());
-  let hint = Hint::new();
-  let probed = symphonia::default::get_probe()
-    .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
-    .map_err(|e| format!("audio probe failed: {e}"))?;
-  let mut format = probed.format;
-  let track = format.default_track().ok_or_else(|| "no default track".to_string())?;
-  let track_id = track.id;
-  let codec_params = track.codec_params.clone();
-  let mut decoder = symphonia::default::get_codecs()
-    .make(&codec_params, &DecoderOptions::default())
-    .map_err(|e| format!("decoder init failed: {e}"))?;
-
-  let mut src_rate: u32 = codec_params.sample_rate.unwrap_or(16000);
-  let mut channels: usize = codec_params.channels.map(|c| c.count()).unwrap_or(1);
-  let mut pcm: Vec<f32> = Vec::new();
+pub(crate) async fn ensure_model_file() -> Result<PathBuf, String> {
+  ensure_model_file_at(None, &model_url(None)).await
+}
 
-  loop {
-    let packet = match format.next_packet() { Ok(p) => p, Err(_) => break };
-    if packet.track_id() != track_id { continue; }
-    match decoder.decode(&packet) {
-      Ok(buf) => {
-        match buf {
-          AudioBufferRef::F32(b) => {
-            let spec = *b.spec();
-            src_rate = spec.rate;
-            channels = spec.channels.count();
-            let mut sbuf = SampleBuffer::<f32>::new(b.capacity() as u64, spec);
-            sbuf.copy_interleaved_ref(AudioBufferRef::F32(b));
-            pcm.extend_from_slice(sbuf.samples());
-          }
-          AudioBufferRef::S16(b) => {
-            let spec = *b.spec();
-            src_rate = spec.rate;
-            channels = spec.channels.count();
-            let mut sbuf = SampleBuffer::<i16>::new(b.capacity() as u64, spec);
-            sbuf.copy_interleaved_ref(AudioBufferRef::S16(b));
-            pcm.extend(sbuf.samples().iter().map(|v| *v as f32 / 32768.0));
-          }
-          AudioBufferRef::S32(b) => {
-            let spec = *b.spec();
-            src_rate = spec.rate;
-            channels = spec.channels.count();
-            let mut sbuf = SampleBuffer::<i32>::new(b.capacity() as u64, spec);
-            sbuf.copy_interleaved_ref(AudioBufferRef::S32(b));
-            let max = i32::MAX as f32;
-            pcm.extend(sbuf.samples().iter().map(|v| *v as f32 / max));
-          }
-          AudioBufferRef::U8(b) => {
-            let spec = *b.spec();
-            src_rate = spec.rate;
-            channels = spec.channels.count();
-            let mut sbuf = SampleBuffer::<u8>::new(b.capacity() as u64, spec);
-            sbuf.copy_interleaved_ref(AudioBufferRef::U8(b));
-            pcm.extend(sbuf.samples().iter().map(|v| (*v as f32 - 128.0) / 128.0));
-          }
-          _ => {}
-        }
-      }
-      Err(_) => {}
-    }
-  }
+// Prefetch helper reporting download progress (bytes) through the job manager
+pub async fn prefetch_model_with_progress(job: crate::jobs::JobReporter, url_opt: Option<String>) -> Result<String, String> {
+  let path = ensure_model_file_at(Some(&job), &model_url(url_opt)).await?;
+  Ok(path.to_string_lossy().to_string())
+}
 
-  if pcm.is_empty() { return Err("decode produced no samples".into()); }
-  if channels == 0 { channels = 1; }
+pub(crate) fn decode_to_f32_mono_16k(audio: &[u8], mime: &str) -> Result<Vec<f32>, String> {
+  // Decode container to interleaved f32 with its sample rate/channels (Opus via libopus)
+  let decoded = crate::audio_decode::decode(audio, mime)?;
+  let src_rate = decoded.sample_rate;
+  let channels = decoded.channels.max(1);
+  let pcm = decoded.samples;
 
   // Downmix to mono
   let mut mono: Vec<f32> = Vec::with_capacity(pcm.len() / channels.max(1));
@@ -252,17 +153,41 @@ pub(crate) fn decode_to_f32_mono_16k(audio: &[u8], _mime: &str) -> Result<Vec<f3
 }
 
 #[cfg(feature = "local-stt")]
-pub async fn transcribe_local(audio: Vec<u8>, mime: String) -> Result<String, String> {
+pub async fn transcribe_local(audio: Vec<u8>, mime: String) -> Result<crate::transcript::Timed, String> {
   let model_path = ensure_model_file().await?;
+  transcribe_with_model_path(&model_path, &audio, &mime)
+}
+
+// Transcribe with an explicit model (URL or ggml size name such as "small"); used by the benchmark
+#[cfg(feature = "local-stt")]
+pub async fn transcribe_with_model(audio: Vec<u8>, mime: String, model: &str) -> Result<String, String> {
+  let model_path = ensure_model_file_at(None, &model_url_for(model)).await?;
+  transcribe_with_model_path(&model_path, &audio, &mime).map(|t| t.text)
+}
+
+#[cfg(feature = "local-stt")]
+fn transcribe_with_model_path(model_path: &std::path::Path, audio: &[u8], mime: &str) -> Result<crate::transcript::Timed, String> {
   // Safety: whisper-rs expects 16k mono f32 PCM samples in [-1,1]
-  let pcm = decode_to_f32_mono_16k(&audio, &mime)?;
+  let pcm = decode_to_f32_mono_16k(audio, mime)?;
+  transcribe_pcm(model_path, &pcm)
+}
 
+/// Transcribe 16 kHz mono f32 samples; used directly by live dictation (stt_stream.rs).
+#[cfg(feature = "local-stt")]
+pub(crate) fn transcribe_pcm(model_path: &std::path::Path, pcm: &[f32]) -> Result<crate::transcript::Timed, String> {
   let n_threads = std::cmp::max(1, num_cpus::get() as i32 - 1);
 
-  let ctx = WhisperContext::new_with_params(
-    model_path.to_string_lossy().as_ref(),
-    WhisperContextParameters::default(),
-  ).map_err(|e| format!("whisper init failed: {e}"))?;
+  // Reuse the loaded context while the model is unchanged; unloaded via local_models
+  let model_key = model_path.to_string_lossy().to_string();
+  let mut cache = WHISPER_CACHE.lock().map_err(|_| "whisper cache lock poisoned".to_string())?;
+  if cache.as_ref().map(|c| c.model_path != model_key).unwrap_or(true) {
+    *cache = None;
+    let ctx = WhisperContext::new_with_params(&model_key, WhisperContextParameters::default())
+      .map_err(|e| format!("whisper init failed: {e}"))?;
+    *cache = Some(WhisperCache { model_path: model_key.clone(), loaded_at_ms: crate::local_models::now_ms(), ctx });
+  }
+  crate::local_models::touch();
+  let ctx = &cache.as_ref().ok_or_else(|| "whisper cache init failed".to_string())?.ctx;
 
   let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
   params.set_n_threads(n_threads);
@@ -275,19 +200,64 @@ pub async fn transcribe_local(audio: Vec<u8>, mime: String) -> Result<String, St
   params.set_print_realtime(false);
 
   let mut state = ctx.create_state().map_err(|e| format!("whisper state create failed: {e}"))?;
-  state.full(params, &pcm).map_err(|e| format!("whisper full failed: {e}"))?;
+  state.full(params, pcm).map_err(|e| format!("whisper full failed: {e}"))?;
 
   let num_segments = state.full_n_segments();
   let mut out = String::new();
+  let mut segments = Vec::new();
   for i in 0..num_segments {
     if let Some(seg) = state.get_segment(i) {
-      if let Ok(text) = seg.to_str() { out.push_str(text); }
+      if let Ok(text) = seg.to_str() {
+        out.push_str(text);
+        // Whisper timestamps are in centiseconds
+        let (t0, t1) = (seg.start_timestamp().max(0) as u64 * 10, seg.end_timestamp().max(0) as u64 * 10);
+        if !text.trim().is_empty() {
+          segments.push(crate::transcript::Segment { start_ms: t0, end_ms: t1, speaker: None, text: text.trim().to_string() });
+        }
+      }
     }
   }
-  Ok(out.trim().to_string())
+  Ok(crate::transcript::Timed { text: out.trim().to_string(), segments })
+}
+
+#[cfg(feature = "local-stt")]
+pub fn loaded_models() -> Vec<crate::local_models::LoadedModel> {
+  let cache = WHISPER_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+  cache
+    .iter()
+    .map(|c| crate::local_models::LoadedModel {
+      engine: "whisper".into(),
+      model: std::path::Path::new(&c.model_path).file_name().and_then(|n| n.to_str()).unwrap_or("").to_string(),
+      cuda: false,
+      file_bytes: crate::local_models::size_on_disk(std::path::Path::new(&c.model_path)),
+      loaded_at_ms: c.loaded_at_ms,
+    })
+    .collect()
+}
+
+#[cfg(feature = "local-stt")]
+pub fn unload_models() -> usize {
+  let mut cache = WHISPER_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+  cache.take().map(|_| 1).unwrap_or(0)
+}
+
+#[cfg(not(feature = "local-stt"))]
+pub fn loaded_models() -> Vec<crate::local_models::LoadedModel> { Vec::new() }
+
+#[cfg(not(feature = "local-stt"))]
+pub fn unload_models() -> usize { 0 }
+
+#[cfg(not(feature = "local-stt"))]
+pub async fn transcribe_local(_audio: Vec<u8>, _mime: String) -> Result<crate::transcript::Timed, String> {
+  Err("Local STT is not available: app built without 'local-stt' feature.".into())
+}
+
+#[cfg(not(feature = "local-stt"))]
+pub(crate) fn transcribe_pcm(_model_path: &std::path::Path, _pcm: &[f32]) -> Result<crate::transcript::Timed, String> {
+  Err("Local STT is not available: app built without 'local-stt' feature.".into())
 }
 
 #[cfg(not(feature = "local-stt"))]
-pub async fn transcribe_local(_audio: Vec<u8>, _mime: String) -> Result<String, String> {
+pub async fn transcribe_with_model(_audio: Vec<u8>, _mime: String, _model: &str) -> Result<String, String> {
   Err("Local STT is not available: app built without 'local-stt' feature.".into())
 }
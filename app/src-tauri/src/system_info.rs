@@ -0,0 +1,224 @@
+// Hardware summary for the settings UI: CPU, RAM, GPUs with VRAM, ONNX Runtime execution
+// providers and disk space in the models folder. Settings use it to suggest a Whisper model size
+// and to warn before multi-GB downloads.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+const GB: u64 = 1024 * 1024 * 1024;
+// Largest single model download (Whisper large-v3) plus headroom
+const LOW_DISK_BYTES: u64 = 5 * GB;
+
+#[derive(Serialize)]
+pub struct GpuInfo {
+  pub name: String,
+  /// Dedicated video memory; None when the driver does not report it
+  pub vram_bytes: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct SystemInfo {
+  pub os: String,
+  pub arch: String,
+  pub cpu_model: Option<String>,
+  /// Logical cores
+  pub cpu_cores: usize,
+  pub ram_total_bytes: Option<u64>,
+  pub ram_available_bytes: Option<u64>,
+  pub gpus: Vec<GpuInfo>,
+  /// ONNX Runtime execution providers usable by Parakeet / local embeddings, e.g. ["cpu", "cuda"];
+  /// empty until one of them has loaded a model
+  pub onnx_providers: Vec<String>,
+  pub models_dir: Option<String>,
+  /// Space used by downloaded models
+  pub models_bytes: u64,
+  pub disk_free_bytes: Option<u64>,
+  /// Less than 5 GB free in the models folder
+  pub low_disk: bool,
+  /// Whisper preset that fits this machine: "base" | "small" | "large-v3-turbo"
+  pub recommended_whisper_preset: String,
+}
+
+fn models_root() -> Option<PathBuf> {
+  crate::stt_whisper::models_dir().and_then(|p| p.parent().map(|p| p.to_path_buf()))
+}
+
+#[cfg(target_os = "windows")]
+fn memory() -> (Option<u64>, Option<u64>) {
+  use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+  let mut status = MEMORYSTATUSEX { dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32, ..Default::default() };
+  match unsafe { GlobalMemoryStatusEx(&mut status) } {
+    Ok(()) => (Some(status.ullTotalPhys), Some(status.ullAvailPhys)),
+    Err(_) => (None, None),
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn memory() -> (Option<u64>, Option<u64>) {
+  let out = std::process::Command::new("sysctl").args(["-n", "hw.memsize"]).output().ok();
+  let total = out.and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse::<u64>().ok());
+  (total, None)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn memory() -> (Option<u64>, Option<u64>) {
+  // /proc/meminfo values are in kB
+  let Ok(info) = std::fs::read_to_string("/proc/meminfo") else { return (None, None) };
+  let field = |name: &str| -> Option<u64> {
+    let line = info.lines().find(|l| l.starts_with(name))?;
+    line[name.len()..].trim().trim_end_matches("kB").trim().parse::<u64>().ok().map(|kb| kb * 1024)
+  };
+  (field("MemTotal:"), field("MemAvailable:"))
+}
+
+#[cfg(target_os = "windows")]
+fn cpu_model() -> Option<String> {
+  use windows::core::w;
+  use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+  let mut buf = [0u16; 256];
+  let mut len = (buf.len() * 2) as u32;
+  unsafe {
+    RegGetValueW(
+      HKEY_LOCAL_MACHINE,
+      w!("HARDWARE\\DESCRIPTION\\System\\CentralProcessor\\0"),
+      w!("ProcessorNameString"),
+      RRF_RT_REG_SZ,
+      None,
+      Some(buf.as_mut_ptr() as *mut _),
+      Some(&mut len),
+    )
+    .ok()
+    .ok()?;
+  }
+  let end = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+  Some(String::from_utf16_lossy(&buf[..end]).trim().to_string()).filter(|s| !s.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn cpu_model() -> Option<String> {
+  let out = std::process::Command::new("sysctl").args(["-n", "machdep.cpu.brand_string"]).output().ok()?;
+  Some(String::from_utf8_lossy(&out.stdout).trim().to_string()).filter(|s| !s.is_empty())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn cpu_model() -> Option<String> {
+  let info = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+  let line = info.lines().find(|l| l.starts_with("model name"))?;
+  Some(line.split_once(':')?.1.trim().to_string())
+}
+
+// Hardware adapters from DXGI; the Microsoft Basic Render Driver and other software adapters are skipped
+#[cfg(target_os = "windows")]
+fn gpus() -> Vec<GpuInfo> {
+  use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1, DXGI_ADAPTER_FLAG_SOFTWARE};
+  let mut out = Vec::new();
+  let Ok(factory) = (unsafe { CreateDXGIFactory1::<IDXGIFactory1>() }) else { return out };
+  let mut i = 0u32;
+  while let Ok(adapter) = unsafe { factory.EnumAdapters1(i) } {
+    i += 1;
+    let Ok(desc) = (unsafe { adapter.GetDesc1() }) else { continue };
+    if desc.Flags & (DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32) != 0 {
+      continue;
+    }
+    let end = desc.Description.iter().position(|&c| c == 0).unwrap_or(desc.Description.len());
+    let name = String::from_utf16_lossy(&desc.Description[..end]).trim().to_string();
+    let vram = desc.DedicatedVideoMemory as u64;
+    out.push(GpuInfo { name, vram_bytes: Some(vram).filter(|v| *v > 0) });
+  }
+  out
+}
+
+#[cfg(not(target_os = "windows"))]
+fn gpus() -> Vec<GpuInfo> {
+  // NVIDIA only; other vendors are not reported
+  let Ok(out) = std::process::Command::new("nvidia-smi").args(["--query-gpu=name,memory.total", "--format=csv,noheader,nounits"]).output() else {
+    return Vec::new();
+  };
+  String::from_utf8_lossy(&out.stdout)
+    .lines()
+    .filter_map(|l| {
+      let (name, mib) = l.rsplit_once(',')?;
+      Some(GpuInfo { name: name.trim().to_string(), vram_bytes: mib.trim().parse::<u64>().ok().map(|m| m * 1024 * 1024) })
+    })
+    .collect()
+}
+
+// Probing loads the ONNX Runtime library, which cloud-only setups never need: it waits until a
+// Parakeet or embeddings model has loaded it anyway, and runs once
+#[cfg(any(feature = "local-stt", feature = "local-embeddings"))]
+fn onnx_providers() -> Vec<String> {
+  static PROVIDERS: once_cell::sync::OnceCell<Vec<String>> = once_cell::sync::OnceCell::new();
+  if PROVIDERS.get().is_none() && crate::stt_parakeet::loaded_models().is_empty() && crate::embeddings::loaded_models().is_empty() {
+    return Vec::new();
+  }
+  PROVIDERS.get_or_init(probe_onnx_providers).clone()
+}
+
+#[cfg(any(feature = "local-stt", feature = "local-embeddings"))]
+fn probe_onnx_providers() -> Vec<String> {
+  use ort::execution_providers::cuda::CUDAExecutionProvider;
+  use ort::execution_providers::directml::DirectMLExecutionProvider;
+  use ort::execution_providers::tensorrt::TensorRTExecutionProvider;
+  use ort::execution_providers::ExecutionProvider;
+  let mut out = vec!["cpu".to_string()];
+  let candidates: [(&str, bool); 3] = [
+    ("cuda", CUDAExecutionProvider::default().is_available().unwrap_or(false)),
+    ("tensorrt", TensorRTExecutionProvider::default().is_available().unwrap_or(false)),
+    ("directml", DirectMLExecutionProvider::default().is_available().unwrap_or(false)),
+  ];
+  out.extend(candidates.iter().filter(|(_, ok)| *ok).map(|(name, _)| name.to_string()));
+  out
+}
+
+#[cfg(not(any(feature = "local-stt", feature = "local-embeddings")))]
+fn onnx_providers() -> Vec<String> {
+  Vec::new()
+}
+
+fn recommend_whisper(ram: Option<u64>, gpus: &[GpuInfo]) -> &'static str {
+  let vram = gpus.iter().filter_map(|g| g.vram_bytes).max().unwrap_or(0);
+  let ram = ram.unwrap_or(0);
+  if ram >= 16 * GB || vram >= 6 * GB {
+    "large-v3-turbo"
+  } else if ram >= 8 * GB {
+    "small"
+  } else {
+    "base"
+  }
+}
+
+fn collect() -> SystemInfo {
+  let (ram_total, ram_available) = memory();
+  let gpus = gpus();
+  let root = models_root();
+  // The models folder may not exist yet; measure the closest existing ancestor
+  let free = root.as_ref().and_then(|r| {
+    let mut probe = r.clone();
+    while !probe.exists() {
+      probe = probe.parent()?.to_path_buf();
+    }
+    crate::diagnostics::free_disk_bytes(&probe)
+  });
+  SystemInfo {
+    os: std::env::consts::OS.to_string(),
+    arch: std::env::consts::ARCH.to_string(),
+    cpu_model: cpu_model(),
+    cpu_cores: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+    ram_total_bytes: ram_total,
+    ram_available_bytes: ram_available,
+    recommended_whisper_preset: recommend_whisper(ram_total, &gpus).to_string(),
+    gpus,
+    onnx_providers: onnx_providers(),
+    models_bytes: root.as_deref().map(crate::local_models::size_on_disk).unwrap_or(0),
+    models_dir: root.map(|p| p.to_string_lossy().to_string()),
+    disk_free_bytes: free,
+    low_disk: free.map(|f| f < LOW_DISK_BYTES).unwrap_or(false),
+  }
+}
+
+/// Probe the hardware (runs off the main thread; GPU and provider queries can take a moment).
+#[tauri::command]
+pub async fn get_system_info() -> Result<SystemInfo, String> {
+  tokio::task::spawn_blocking(collect).await.map_err(|e| format!("system info failed: {e}"))
+}
@@ -0,0 +1,116 @@
+// Registry of long-running commands (chat, STT transcription, model downloads, OCR, batch jobs)
+// so the UI can list and cancel them. Each entry owns a CancellationToken; the entry is removed
+// when its TaskHandle is dropped. Registering an id that is still running cancels the older task.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::AidcError;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskInfo {
+  pub id: String,
+  /// "chat", "stt", "download", "ocr", "batch", ...
+  pub kind: String,
+  pub label: String,
+  pub started_at_ms: u64,
+}
+
+// id -> (info, token, registration number)
+static TASKS: Lazy<Mutex<HashMap<String, (TaskInfo, CancellationToken, u64)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// Tells a replaced task's handle apart from the one now registered under the same id
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(0);
+
+/// Registration of a running task. Dropping it unregisters the task.
+pub struct TaskHandle {
+  id: String,
+  token: CancellationToken,
+  serial: u64,
+}
+
+impl TaskHandle {
+  pub fn id(&self) -> &str { &self.id }
+
+  #[allow(dead_code)]
+  pub fn token(&self) -> CancellationToken { self.token.clone() }
+
+  pub fn is_cancelled(&self) -> bool { self.token.is_cancelled() }
+
+  /// Drive `fut` to completion unless the task is cancelled first, in which case the future is
+  /// dropped and `AidcError::Cancelled` is returned (converted into the caller's error type).
+  pub async fn run<T, E, F>(&self, fut: F) -> Result<T, E>
+  where
+    F: Future<Output = Result<T, E>>,
+    E: From<AidcError>,
+  {
+    tokio::select! {
+      biased;
+      _ = self.token.cancelled() => Err(AidcError::Cancelled.into()),
+      r = fut => r,
+    }
+  }
+}
+
+impl Drop for TaskHandle {
+  fn drop(&mut self) {
+    if let Ok(mut map) = TASKS.lock() {
+      // A newer task registered under this id stays
+      if map.get(&self.id).is_some_and(|(_, _, serial)| *serial == self.serial) { map.remove(&self.id); }
+    }
+  }
+}
+
+/// Register a task. `id` lets the frontend pick the id up front so it can cancel the task while
+/// the command is still pending; a random id is generated when omitted. A task still running
+/// under the same id is cancelled and replaced, so the id always refers to the latest command.
+pub fn register(kind: &str, label: impl Into<String>, id: Option<String>) -> TaskHandle {
+  let token = CancellationToken::new();
+  let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+  let mut map = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+  let id = id.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+  let started_at_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+  let info = TaskInfo { id: id.clone(), kind: kind.to_string(), label: label.into(), started_at_ms };
+  if let Some((previous, old_token, _)) = map.insert(id.clone(), (info, token.clone(), serial)) {
+    old_token.cancel();
+    tracing::warn!(id = %id, kind = %previous.kind, "task id reused; cancelled the running task");
+  }
+  TaskHandle { id, token, serial }
+}
+
+/// Cancel the task `id`, if it is running and (when `kind` is given) of that kind.
+pub fn cancel(id: &str, kind: Option<&str>) -> bool {
+  let map = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+  match map.get(id) {
+    Some((info, token, _)) if kind.is_none_or(|k| info.kind == k) => { token.cancel(); true }
+    _ => false,
+  }
+}
+
+/// Cancel every running task (app shutdown); returns how many were running.
+pub fn cancel_all() -> usize {
+  let map = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+  for (_, token, _) in map.values() { token.cancel(); }
+  map.len()
+}
+
+/// Cancel a running task. Returns false when no task with that id is running.
+#[tauri::command]
+pub fn cancel_task(id: String) -> Result<bool, String> {
+  Ok(cancel(&id, None))
+}
+
+/// List running tasks, oldest first.
+#[tauri::command]
+pub fn list_running_tasks() -> Result<Vec<TaskInfo>, String> {
+  let map = TASKS.lock().map_err(|_| "lock poisoned".to_string())?;
+  let mut out: Vec<TaskInfo> = map.values().map(|(info, _, _)| info.clone()).collect();
+  out.sort_by_key(|t| t.started_at_ms);
+  Ok(out)
+}
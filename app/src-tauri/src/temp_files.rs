@@ -0,0 +1,194 @@
+// Temporary files the app writes: synthesized speech (aidc_tts_*), screen captures
+// (aidc_capture_*), OCR images (aidc_ocr_*), audiobook intermediates (aidc_audiobook_*) and
+// generated/edited images in the attachments folder. Files are registered when written; cleanup
+// also scans for the name prefixes so files left by earlier runs are found. A background task
+// removes each kind once it is older than its default age; `cleanup_temp_files` does it on demand.
+// Generated images are shown in saved conversations, so they are only removed on demand; captures
+// attached to a saved conversation are skipped by the age-based cleanup.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+
+pub const TTS: &str = "tts";
+pub const CAPTURE: &str = "capture";
+pub const OCR: &str = "ocr";
+pub const AUDIOBOOK: &str = "audiobook";
+pub const IMAGE: &str = "image";
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+struct Kind {
+  name: &'static str,
+  prefix: &'static str,
+  /// Age after which the background task deletes the file (None: only on demand)
+  max_age: Option<Duration>,
+}
+
+const fn minutes(m: u64) -> Duration {
+  Duration::from_secs(m * 60)
+}
+
+// Captures can still be attached to a conversation, so they are kept longer
+const KINDS: &[Kind] = &[
+  Kind { name: TTS, prefix: "aidc_tts_", max_age: Some(minutes(240)) },
+  Kind { name: CAPTURE, prefix: "aidc_capture_", max_age: Some(minutes(24 * 60)) },
+  Kind { name: OCR, prefix: "aidc_ocr_", max_age: Some(minutes(60)) },
+  Kind { name: AUDIOBOOK, prefix: "aidc_audiobook_", max_age: Some(minutes(60)) },
+  Kind { name: IMAGE, prefix: "aidc_", max_age: None },
+];
+
+static REGISTRY: Lazy<Mutex<HashMap<PathBuf, &'static str>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static STARTED: OnceCell<()> = OnceCell::new();
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct CleanupReport {
+  pub removed: u32,
+  pub freed_bytes: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TempKindInfo {
+  pub kind: &'static str,
+  pub files: u32,
+  pub bytes: u64,
+  pub max_age_minutes: Option<u64>,
+}
+
+fn kind(name: &str) -> Option<&'static Kind> {
+  KINDS.iter().find(|k| k.name == name)
+}
+
+fn dir_for(kind: &Kind) -> Option<PathBuf> {
+  if kind.name == IMAGE {
+    crate::images::attachments_dir().ok()
+  } else {
+    Some(std::env::temp_dir())
+  }
+}
+
+/// A new path for synthesized speech from `engine`. Names carry a random suffix: chunks of one
+/// text are synthesized concurrently and would otherwise share a per-second timestamp.
+pub fn tts_path(engine: &str, ext: &str) -> String {
+  let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+  let unique = &uuid::Uuid::new_v4().simple().to_string()[..8];
+  std::env::temp_dir().join(format!("aidc_tts_{stamp}_{engine}_{unique}.{ext}")).to_string_lossy().to_string()
+}
+
+/// Record a temp file written by the app (`kind` is one of the constants above).
+pub fn register(kind: &'static str, path: impl AsRef<Path>) {
+  REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).insert(path.as_ref().to_path_buf(), kind);
+}
+
+// Files of one kind: registered paths plus prefix matches in its folder
+fn files_of(kind: &Kind) -> Vec<PathBuf> {
+  let mut out: Vec<PathBuf> = {
+    let mut reg = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    reg.retain(|p, _| p.exists());
+    reg.iter().filter(|(_, k)| **k == kind.name).map(|(p, _)| p.clone()).collect()
+  };
+  if let Some(entries) = dir_for(kind).and_then(|d| std::fs::read_dir(d).ok()) {
+    for e in entries.flatten() {
+      let path = e.path();
+      let ours = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(kind.prefix));
+      if ours && path.is_file() && !out.contains(&path) {
+        out.push(path);
+      }
+    }
+  }
+  out
+}
+
+/// Delete files of `kind` (all kinds when None) older than `max_age` (each kind's default when None;
+/// kinds without a default are then left alone, as are captures attached to a conversation).
+pub fn cleanup(kind_name: Option<&str>, max_age: Option<Duration>) -> Result<CleanupReport, String> {
+  let kinds: Vec<&Kind> = match kind_name {
+    Some(name) => vec![kind(name).ok_or_else(|| format!("Unknown temp file kind: {name}"))?],
+    None => KINDS.iter().collect(),
+  };
+  let now = SystemTime::now();
+  let mut report = CleanupReport::default();
+  for k in kinds {
+    let Some(limit) = max_age.or(k.max_age) else { continue };
+    // File names of captures a conversation still shows; compared by name since the stored path
+    // may spell the temp folder differently
+    let attached: HashSet<OsString> = if k.name == CAPTURE && max_age.is_none() {
+      match crate::conversations::attached_paths() {
+        Ok(paths) => paths.iter().filter_map(|p| p.file_name().map(|n| n.to_os_string())).collect(),
+        Err(e) => {
+          tracing::warn!(error = %e, "reading conversation attachments failed; captures kept");
+          continue;
+        }
+      }
+    } else {
+      HashSet::new()
+    };
+    for path in files_of(k) {
+      if path.file_name().is_some_and(|n| attached.contains(n)) {
+        continue;
+      }
+      let Ok(meta) = std::fs::metadata(&path) else { continue };
+      let age = meta.modified().ok().and_then(|m| now.duration_since(m).ok()).unwrap_or_default();
+      if age < limit {
+        continue;
+      }
+      if std::fs::remove_file(&path).is_ok() {
+        report.removed = report.removed.saturating_add(1);
+        report.freed_bytes += meta.len();
+        REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).remove(&path);
+      }
+    }
+  }
+  Ok(report)
+}
+
+/// Start the periodic cleanup (each kind past its default age). Called once from setup.
+pub fn start_cleanup() {
+  if STARTED.set(()).is_err() { return; }
+  tauri::async_runtime::spawn(async move {
+    loop {
+      match tokio::task::spawn_blocking(|| cleanup(None, None)).await {
+        Ok(Ok(r)) if r.removed > 0 => tracing::info!(removed = r.removed, freed_bytes = r.freed_bytes, "temp files cleaned up"),
+        Ok(Err(e)) => tracing::warn!(error = %e, "temp file cleanup failed"),
+        _ => {}
+      }
+      tokio::time::sleep(CLEANUP_INTERVAL).await;
+    }
+  });
+}
+
+/// Delete temp files now. `kind`: "tts", "capture", "ocr", "audiobook" or "image" (default: all);
+/// `max_age_minutes` overrides each kind's default age (0 deletes everything of that kind); images
+/// are only removed when it is given.
+#[tauri::command]
+pub async fn cleanup_temp_files(app: tauri::AppHandle, kind: Option<String>, max_age_minutes: Option<u64>) -> Result<CleanupReport, String> {
+  let label = format!("Temp file cleanup ({})", kind.as_deref().unwrap_or("all"));
+  crate::jobs::run(&app, "cleanup", label, None, |_job| async move {
+    tokio::task::spawn_blocking(move || cleanup(kind.as_deref(), max_age_minutes.map(minutes)))
+      .await
+      .map_err(|e| format!("spawn_blocking failed: {e}"))?
+  })
+  .await
+}
+
+/// Count and size of the temp files of each kind.
+#[tauri::command]
+pub async fn list_temp_files() -> Result<Vec<TempKindInfo>, String> {
+  tokio::task::spawn_blocking(|| {
+    KINDS
+      .iter()
+      .map(|k| {
+        let files = files_of(k);
+        let bytes = files.iter().filter_map(|p| std::fs::metadata(p).ok()).map(|m| m.len()).sum();
+        TempKindInfo { kind: k.name, files: files.len() as u32, bytes, max_age_minutes: k.max_age.map(|d| d.as_secs() / 60) }
+      })
+      .collect()
+  })
+  .await
+  .map_err(|e| format!("spawn_blocking failed: {e}"))
+}
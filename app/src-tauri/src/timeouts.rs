@@ -0,0 +1,70 @@
+// Request timeouts per subsystem, in seconds (settings: `timeouts`), e.g.
+//   "timeouts": { "chat": 180, "tts": 120, "stt": 300, "mcp": 60, "downloads": 60 }
+// chat, tts and stt bound a whole HTTP request (for streamed chat answers: the wait for the next
+// chunk); mcp bounds one tool, resource or prompt call; downloads bounds a stall between chunks,
+// since model downloads legitimately take many minutes. Expiry surfaces as AidcError::Timeout
+// (code "timeout") naming the setting that ran out.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::error::AidcError;
+
+pub const CHAT: &str = "chat";
+pub const TTS: &str = "tts";
+pub const STT: &str = "stt";
+pub const MCP: &str = "mcp";
+pub const DOWNLOADS: &str = "downloads";
+
+const MIN_SECS: u64 = 5;
+const MAX_SECS: u64 = 3600;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// One client per (subsystem, timeout) so connections are pooled and a changed setting takes effect
+static CLIENTS: Lazy<Mutex<HashMap<(&'static str, u64), reqwest::Client>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn default_secs(kind: &str) -> u64 {
+  match kind {
+    STT | MCP | DOWNLOADS => 60,
+    _ => 120,
+  }
+}
+
+/// Configured timeout for `kind` (one of the constants above).
+pub fn get(kind: &str) -> Duration {
+  let secs = crate::config::get_timeout_secs_from_settings(kind).unwrap_or_else(|| default_secs(kind));
+  Duration::from_secs(secs.clamp(MIN_SECS, MAX_SECS))
+}
+
+/// Shared HTTP client whose requests are bounded by the `kind` timeout.
+pub fn client(kind: &'static str) -> reqwest::Client {
+  let timeout = get(kind);
+  let mut map = CLIENTS.lock().unwrap_or_else(|e| e.into_inner());
+  map
+    .entry((kind, timeout.as_secs()))
+    .or_insert_with(|| {
+      reqwest::Client::builder().timeout(timeout).connect_timeout(CONNECT_TIMEOUT).build().unwrap_or_else(|_| reqwest::Client::new())
+    })
+    .clone()
+}
+
+/// Timeout error for `kind`, attributed to `provider`.
+pub fn error(kind: &str, provider: &str) -> AidcError {
+  AidcError::Timeout { provider: provider.to_string(), message: format!("no response within {} s (timeouts.{kind})", get(kind).as_secs()) }
+}
+
+/// Run `fut`, failing with the `kind` timeout error when it takes longer than configured.
+pub async fn run<T, E, F>(kind: &str, provider: &str, fut: F) -> Result<T, E>
+where
+  F: Future<Output = Result<T, E>>,
+  E: From<AidcError>,
+{
+  match tokio::time::timeout(get(kind), fut).await {
+    Ok(r) => r,
+    Err(_) => Err(error(kind, provider).into()),
+  }
+}
@@ -0,0 +1,126 @@
+// Timestamped transcripts and subtitle export. Transcriptions carry segments: timed ones from local
+// Whisper or verbose_json cloud responses, otherwise a single segment over the whole clip. The
+// last `stt_transcribe` result is kept so `export_transcript` can write it as SRT, WebVTT or plain
+// text; callers with their own segments (e.g. a multi-speaker session) can pass them instead.
+// `stt_transcribe_to_subtitles` transcribes an audio file and returns (and optionally writes) the
+// subtitle text directly.
+// Segment text is the raw transcript, before any AI post-processing.
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+// Reading speed used to size a segment when the audio length is unknown
+const WORDS_PER_SECOND: f64 = 2.5;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Segment {
+  pub start_ms: u64,
+  pub end_ms: u64,
+  /// Label such as "Speaker 1" or "Me"; omitted from the output when None
+  #[serde(default)]
+  pub speaker: Option<String>,
+  pub text: String,
+}
+
+/// Engine output: full text plus segments (empty when the engine has no timestamps).
+pub struct Timed {
+  pub text: String,
+  pub segments: Vec<Segment>,
+}
+
+impl Timed {
+  pub fn untimed(text: String) -> Self {
+    Timed { text, segments: Vec::new() }
+  }
+}
+
+static LAST: Lazy<Mutex<Vec<Segment>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// One segment spanning the clip, for engines without timestamps.
+pub fn single(text: &str, seconds: Option<f64>) -> Vec<Segment> {
+  let text = text.trim();
+  if text.is_empty() {
+    return Vec::new();
+  }
+  let seconds = seconds.filter(|s| *s > 0.0).unwrap_or_else(|| (text.split_whitespace().count() as f64 / WORDS_PER_SECOND).max(1.0));
+  vec![Segment { start_ms: 0, end_ms: (seconds * 1000.0).round() as u64, speaker: None, text: text.to_string() }]
+}
+
+/// Keep `segments` as the transcript `export_transcript` writes by default.
+pub fn remember(segments: Vec<Segment>) {
+  *LAST.lock().unwrap_or_else(|e| e.into_inner()) = segments;
+}
+
+// 3723004 ms -> 01:02:03<sep>004
+fn timestamp(ms: u64, sep: char) -> String {
+  format!("{:02}:{:02}:{:02}{}{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, sep, ms % 1000)
+}
+
+fn labelled(seg: &Segment) -> String {
+  match seg.speaker.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+    Some(speaker) => format!("{speaker}: {}", seg.text.trim()),
+    None => seg.text.trim().to_string(),
+  }
+}
+
+fn to_srt(segments: &[Segment]) -> String {
+  let mut out = String::new();
+  for (i, seg) in segments.iter().enumerate() {
+    out.push_str(&format!("{}\n{} --> {}\n{}\n\n", i + 1, timestamp(seg.start_ms, ','), timestamp(seg.end_ms, ','), labelled(seg)));
+  }
+  out
+}
+
+fn to_vtt(segments: &[Segment]) -> String {
+  let mut out = String::from("WEBVTT\n\n");
+  for seg in segments {
+    // Voice spans are the WebVTT way to mark speakers; "-->" may not appear in cue text
+    let text = seg.text.trim().replace("-->", "->");
+    let cue = match seg.speaker.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+      Some(speaker) => format!("<v {speaker}>{text}"),
+      None => text,
+    };
+    out.push_str(&format!("{} --> {}\n{}\n\n", timestamp(seg.start_ms, '.'), timestamp(seg.end_ms, '.'), cue));
+  }
+  out
+}
+
+fn to_text(segments: &[Segment]) -> String {
+  segments.iter().map(|seg| format!("[{}] {}\n", &timestamp(seg.start_ms, '.')[..8], labelled(seg))).collect()
+}
+
+/// Render segments as "srt", "vtt" or "txt".
+pub fn render(segments: &[Segment], format: &str) -> Result<String, String> {
+  let mut segments: Vec<Segment> = segments.iter().filter(|s| !s.text.trim().is_empty()).cloned().collect();
+  if segments.is_empty() {
+    return Err("No transcript to export; transcribe something first".into());
+  }
+  segments.sort_by_key(|s| s.start_ms);
+  match format.trim().to_lowercase().as_str() {
+    "srt" => Ok(to_srt(&segments)),
+    "vtt" | "webvtt" => Ok(to_vtt(&segments)),
+    "txt" | "text" => Ok(to_text(&segments)),
+    other => Err(format!("Unknown transcript format '{other}' (expected srt, vtt or txt)")),
+  }
+}
+
+/// Write a transcript to `path`. `format` is "srt", "vtt" or "txt" (default: from the file
+/// extension, else SRT). Without `segments` the last `stt_transcribe` result is exported.
+#[tauri::command]
+pub fn export_transcript(path: String, format: Option<String>, segments: Option<Vec<Segment>>) -> Result<String, String> {
+  let path = path.trim().to_string();
+  if path.is_empty() {
+    return Err("No output path given".into());
+  }
+  let format = format
+    .map(|f| f.trim().to_lowercase())
+    .filter(|f| !f.is_empty())
+    .or_else(|| std::path::Path::new(&path).extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()))
+    .unwrap_or_else(|| "srt".to_string());
+  let segments = segments.unwrap_or_else(|| LAST.lock().unwrap_or_else(|e| e.into_inner()).clone());
+  let body = render(&segments, &format)?;
+  std::fs::write(&path, body).map_err(|e| format!("Write transcript failed: {e}"))?;
+  Ok(path)
+}
@@ -0,0 +1,241 @@
+// Live speech translation: microphone utterances are transcribed, translated with the chat model
+// and spoken in the target language, continuously until stopped. Utterances are cut short
+// (`max_segment_ms`) and processed concurrently, while the results are emitted and spoken in
+// the order they were said, so a long sentence does not hold up the next one.
+//
+// Settings: `translation` { target_language, source_language, silence_ms, max_segment_ms, speak,
+// pause_while_speaking }, plus the usual chat, STT and TTS preferences. With
+// `pause_while_speaking` (default) the mic is ignored during playback so the spoken
+// translation is not picked up again; turn it off when using headphones.
+//
+// Events:
+//   translation:state   { session_id, state: "listening"|"stopped"|"error", message? }
+//   translation:segment { session_id, index, source_text, translated_text }
+//   translation:error   { session_id, index?, message }   (segment failed, session keeps running)
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::assistant::{self, Segmenter};
+use crate::audio_io::{self, MicChunk};
+use crate::chat::{ChatContent, ChatMessage};
+use crate::error::AidcError;
+
+const DEFAULT_SILENCE_MS: u64 = 500;
+const DEFAULT_MAX_SEGMENT_MS: u64 = 6_000;
+// Previous source sentences passed to the translator for context (names, pronouns)
+const CONTEXT_SEGMENTS: usize = 3;
+
+struct ActiveSession {
+  id: String,
+  cancel: CancellationToken,
+}
+
+static SESSION: Lazy<Mutex<Option<ActiveSession>>> = Lazy::new(|| Mutex::new(None));
+
+struct Options {
+  target_language: String,
+  source_language: Option<String>,
+  silence_ms: u64,
+  max_segment_ms: u64,
+  speak: bool,
+  pause_while_speaking: bool,
+}
+
+impl Options {
+  // Explicit arguments win over the `translation` settings
+  fn resolve(target_language: Option<String>, source_language: Option<String>, speak: Option<bool>) -> Result<Self, String> {
+    let tr = crate::config::get_translation_from_settings();
+    let s = |k: &str| tr.get(k).and_then(|x| x.as_str()).map(|x| x.trim().to_string()).filter(|x| !x.is_empty());
+    let clean = |v: Option<String>| v.map(|x| x.trim().to_string()).filter(|x| !x.is_empty());
+    let target_language = clean(target_language).or_else(|| s("target_language")).ok_or_else(|| "Target language is required".to_string())?;
+    Ok(Self {
+      target_language,
+      source_language: clean(source_language).or_else(|| s("source_language")),
+      silence_ms: tr.get("silence_ms").and_then(|x| x.as_u64()).unwrap_or(DEFAULT_SILENCE_MS).clamp(200, 3_000),
+      max_segment_ms: tr.get("max_segment_ms").and_then(|x| x.as_u64()).unwrap_or(DEFAULT_MAX_SEGMENT_MS).clamp(1_000, 20_000),
+      speak: speak.or_else(|| tr.get("speak").and_then(|x| x.as_bool())).unwrap_or(true),
+      pause_while_speaking: tr.get("pause_while_speaking").and_then(|x| x.as_bool()).unwrap_or(true),
+    })
+  }
+
+  fn system_prompt(&self) -> String {
+    let from = match &self.source_language {
+      Some(l) => format!(" from {l}"),
+      None => String::new(),
+    };
+    format!(
+      "You are a live interpreter. Translate the user's spoken text{from} into {}. Reply with the translation only: no quotes, notes or explanations. Keep the tone and register; fix obvious speech recognition errors. If the text is already in {}, return it unchanged.",
+      self.target_language, self.target_language
+    )
+  }
+}
+
+fn emit_state(app: &AppHandle, id: &str, state: &str, message: Option<&str>) {
+  let _ = app.emit("translation:state", json!({ "session_id": id, "state": state, "message": message }));
+}
+
+async fn translate(app: &AppHandle, system_prompt: &str, context: &[String], text: &str) -> Result<String, AidcError> {
+  let key = crate::settings::require_openai_key()?;
+  let mut prompt = system_prompt.to_string();
+  if !context.is_empty() {
+    prompt.push_str("\n\nPreceding speech, for context only (do not translate):\n");
+    prompt.push_str(&context.join("\n"));
+  }
+  let messages = vec![
+    ChatMessage { role: "system".into(), content: ChatContent::Text(prompt) },
+    ChatMessage { role: "user".into(), content: ChatContent::Text(text.to_string()) },
+  ];
+  let model = crate::settings::get_model_from_settings_or_env();
+  let temp = crate::settings::get_temperature_from_settings_or_env();
+  crate::chat::chat_complete(app.clone(), messages, key, model, temp, Vec::new()).await
+}
+
+// STT → translation for one utterance; None when nothing was said
+async fn process(app: AppHandle, system_prompt: Arc<String>, context: Vec<String>, pcm: Vec<i16>) -> Result<Option<(String, String)>, AidcError> {
+  let source = assistant::transcribe(&pcm, false).await?.trim().to_string();
+  if source.is_empty() { return Ok(None); }
+  let translated = translate(&app, &system_prompt, &context, &source).await?.trim().to_string();
+  Ok(Some((source, translated)))
+}
+
+type Pending = (u64, JoinHandle<Result<Option<(String, String)>, AidcError>>);
+
+// Emits (and speaks) finished segments in capture order
+async fn deliver(app: AppHandle, id: String, speak: bool, speaking: Arc<AtomicBool>, mut rx: mpsc::UnboundedReceiver<Pending>, cancel: CancellationToken) {
+  while let Some((index, handle)) = tokio::select! {
+    _ = cancel.cancelled() => None,
+    p = rx.recv() => p,
+  } {
+    let res = tokio::select! {
+      _ = cancel.cancelled() => return,
+      r = handle => r.unwrap_or_else(|e| Err(AidcError::Internal(format!("translation task failed: {e}")))),
+    };
+    match res {
+      Ok(Some((source, translated))) => {
+        let _ = app.emit("translation:segment", json!({ "session_id": id, "index": index, "source_text": source, "translated_text": translated }));
+        if speak && !translated.is_empty() {
+          speaking.store(true, Ordering::Relaxed);
+          if let Err(e) = assistant::speak(&translated, &cancel).await {
+            tracing::warn!(session = %id, error = %e, "translation speech failed");
+            let _ = app.emit("translation:error", json!({ "session_id": id, "index": index, "message": e }));
+          }
+          speaking.store(false, Ordering::Relaxed);
+        }
+      }
+      Ok(None) => {}
+      Err(e) => {
+        tracing::warn!(session = %id, index, error = %e.message(), "translation segment failed");
+        let _ = app.emit("translation:error", json!({ "session_id": id, "index": index, "message": e.message() }));
+      }
+    }
+  }
+}
+
+async fn run_loop(app: &AppHandle, id: &str, opts: Options, mut mic_rx: mpsc::UnboundedReceiver<MicChunk>, cancel: CancellationToken) -> Result<(), String> {
+  let mut seg = Segmenter::new(opts.silence_ms).with_max_ms(opts.max_segment_ms);
+  let system_prompt = Arc::new(opts.system_prompt());
+  let speaking = Arc::new(AtomicBool::new(false));
+  let (tx, rx) = mpsc::unbounded_channel::<Pending>();
+  let delivery = tauri::async_runtime::spawn(deliver(app.clone(), id.to_string(), opts.speak, speaking.clone(), rx, cancel.clone()));
+  // Source text of recent segments, filled as their transcripts complete
+  let recent: Arc<Mutex<VecDeque<String>>> = Arc::new(Mutex::new(VecDeque::new()));
+  let mut index = 0u64;
+  emit_state(app, id, "listening", None);
+  let res = loop {
+    let chunk = tokio::select! {
+      _ = cancel.cancelled() => break Ok(()),
+      c = mic_rx.recv() => c,
+    };
+    let Some((pcm, level)) = chunk else { break Err("Microphone stream ended".to_string()) };
+    if opts.pause_while_speaking && speaking.load(Ordering::Relaxed) {
+      seg.reset();
+      continue;
+    }
+    let Some(utterance) = seg.feed(&pcm, level) else { continue };
+    let context: Vec<String> = recent.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect();
+    let (app2, prompt, recent2) = (app.clone(), system_prompt.clone(), recent.clone());
+    let handle = tokio::spawn(async move {
+      let res = process(app2, prompt, context, utterance).await;
+      if let Ok(Some((source, _))) = &res {
+        let mut r = recent2.lock().unwrap_or_else(|e| e.into_inner());
+        r.push_back(source.clone());
+        if r.len() > CONTEXT_SEGMENTS { r.pop_front(); }
+      }
+      res
+    });
+    if tx.send((index, handle)).is_err() { break Ok(()); }
+    index += 1;
+  };
+  drop(tx);
+  if res.is_err() { cancel.cancel(); }
+  let _ = delivery.await;
+  res
+}
+
+fn take_session() -> Option<ActiveSession> {
+  SESSION.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Start live translation of the default microphone into `target_language` (falls back to
+/// `translation.target_language`). `speak` overrides `translation.speak`. Returns the session
+/// id used in `translation:*` events.
+#[tauri::command]
+pub async fn translation_session_start(
+  app: AppHandle,
+  target_language: Option<String>,
+  source_language: Option<String>,
+  speak: Option<bool>,
+) -> Result<String, AidcError> {
+  let opts = Options::resolve(target_language, source_language, speak).map_err(AidcError::InvalidInput)?;
+  if let Some(prev) = take_session() { prev.cancel.cancel(); }
+  crate::settings::require_openai_key()?;
+  let (mic, mic_rx) = tokio::task::spawn_blocking(|| audio_io::start_mic(assistant::SAMPLE_RATE))
+    .await
+    .map_err(|e| AidcError::Internal(format!("audio task failed: {e}")))?
+    .map_err(AidcError::Internal)?;
+
+  let id = uuid::Uuid::new_v4().to_string();
+  let cancel = CancellationToken::new();
+  *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = Some(ActiveSession { id: id.clone(), cancel: cancel.clone() });
+  tracing::info!(session = %id, target = %opts.target_language, speak = opts.speak, "live translation started");
+
+  let sid = id.clone();
+  tauri::async_runtime::spawn(async move {
+    let res = run_loop(&app, &sid, opts, mic_rx, cancel).await;
+    drop(mic);
+    {
+      let mut slot = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+      if slot.as_ref().map(|s| s.id == sid).unwrap_or(false) { *slot = None; }
+    }
+    match res {
+      Ok(()) => emit_state(&app, &sid, "stopped", None),
+      Err(e) => {
+        tracing::warn!(session = %sid, error = %e, "live translation ended with error");
+        emit_state(&app, &sid, "error", Some(&e));
+      }
+    }
+  });
+  Ok(id)
+}
+
+/// Stop live translation. Returns false when it was not running.
+#[tauri::command]
+pub fn translation_session_stop() -> Result<bool, String> {
+  match take_session() {
+    Some(s) => {
+      s.cancel.cancel();
+      tracing::info!(session = %s.id, "live translation stopped");
+      Ok(true)
+    }
+    None => Ok(false),
+  }
+}
@@ -0,0 +1,184 @@
+// System tray icon and menu: Show, the quick prompts 1–9, Read selection aloud, Capture region,
+// the most recent conversations, a Mute speech toggle (`tts_muted`) and Exit.
+//
+// The menu is rebuilt when quick_prompts.json, the prompt library, settings or the conversations
+// are saved (`refresh`), and when the pointer enters the icon so files edited by hand show up
+// before the menu opens. It is only replaced when its content changed.
+//
+// Clicking the tray takes focus from the app the user was working in. That window is remembered
+// when the pointer enters the icon and brought back before a selection is read; actions that need
+// a window go to the main window as `hotkey:action` and `conversation:open`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::{Lazy, OnceCell};
+use tauri::menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::events;
+
+const TRAY_ID: &str = "main";
+const RECENT_CONVERSATIONS: usize = 5;
+const QUICK_PROMPT_PREFIX: &str = "quick_prompt:";
+const CONVERSATION_PREFIX: &str = "conversation:";
+// Time for the refocused window to become active before its selection is copied
+const REFOCUS_DELAY: Duration = Duration::from_millis(150);
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+// Content of the menu being shown
+static SHOWN: Lazy<Mutex<Option<MenuContent>>> = Lazy::new(|| Mutex::new(None));
+// Window in front when the pointer entered the icon (see quick_actions::target_window_handle_raw)
+static TARGET: Lazy<Mutex<Option<isize>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Clone, PartialEq)]
+struct MenuContent {
+  quick_prompts: Vec<String>,
+  conversations: Vec<(String, String)>,
+  muted: bool,
+}
+
+fn content() -> MenuContent {
+  MenuContent {
+    quick_prompts: crate::quick_prompts::quick_prompt_labels(),
+    conversations: crate::config::recent_conversations(RECENT_CONVERSATIONS),
+    muted: crate::config::load_settings_json().get("tts_muted").and_then(|x| x.as_bool()).unwrap_or(false),
+  }
+}
+
+fn shown() -> std::sync::MutexGuard<'static, Option<MenuContent>> {
+  SHOWN.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn build_menu(app: &AppHandle, c: &MenuContent) -> tauri::Result<Menu<Wry>> {
+  let mut prompts = SubmenuBuilder::new(app, "Quick Prompts");
+  for (i, label) in c.quick_prompts.iter().enumerate() {
+    prompts = prompts.text(format!("{QUICK_PROMPT_PREFIX}{}", i + 1), format!("{}  {label}", i + 1));
+  }
+  let mut recent = SubmenuBuilder::new(app, "Recent Conversations");
+  if c.conversations.is_empty() {
+    recent = recent.item(&MenuItemBuilder::new("No conversations").enabled(false).build(app)?);
+  }
+  for (id, title) in &c.conversations {
+    recent = recent.text(format!("{CONVERSATION_PREFIX}{id}"), title);
+  }
+  let mute = CheckMenuItemBuilder::with_id("mute_tts", "Mute Speech").checked(c.muted).build(app)?;
+  MenuBuilder::new(app)
+    .text("show", "Show")
+    .separator()
+    .item(&prompts.build()?)
+    .text(crate::hotkeys::READ_SELECTION, "Read Selection Aloud")
+    .text(crate::hotkeys::CAPTURE_REGION, "Capture Region")
+    .separator()
+    .item(&recent.build()?)
+    .item(&mute)
+    .separator()
+    .text("exit", "Exit")
+    .build()
+}
+
+// Replace the menu when its content changed; runs on the main thread
+fn rebuild(app: &AppHandle) {
+  let next = content();
+  if shown().as_ref() == Some(&next) {
+    return;
+  }
+  let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+  match build_menu(app, &next).and_then(|menu| tray.set_menu(Some(menu))) {
+    Ok(()) => *shown() = Some(next),
+    Err(e) => tracing::warn!(error = %e, "tray menu rebuild failed"),
+  }
+}
+
+/// Rebuild the tray menu if what it lists changed. Callable from any thread.
+pub fn refresh() {
+  let Some(app) = APP.get() else { return };
+  let handle = app.clone();
+  let _ = app.run_on_main_thread(move || rebuild(&handle));
+}
+
+fn show_main(app: &AppHandle) {
+  if let Some(window) = app.get_webview_window("main") {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+  }
+}
+
+// Bring back the window the user was in, then run `action` on its selection
+fn in_target<F, Fut>(app: &AppHandle, action: F)
+where
+  F: FnOnce(AppHandle) -> Fut + Send + 'static,
+  Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+  let target = *TARGET.lock().unwrap_or_else(|e| e.into_inner());
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    if target.is_some_and(crate::quick_actions::activate_window_raw) {
+      tokio::time::sleep(REFOCUS_DELAY).await;
+    }
+    action(app).await;
+  });
+}
+
+fn on_menu(app: &AppHandle, id: &str) {
+  match id {
+    "show" => show_main(app),
+    "exit" => app.exit(0),
+    // Failures are reported as tts:error
+    crate::hotkeys::READ_SELECTION => in_target(app, |app| async move {
+      let _ = crate::quick_actions::tts_selection(app, None).await;
+    }),
+    crate::hotkeys::CAPTURE_REGION => {
+      events::emit(app, events::HOTKEY_ACTION, &events::HotkeyAction { action: crate::hotkeys::CAPTURE_REGION.to_string() })
+    }
+    "mute_tts" => {
+      let muted = !content().muted;
+      tauri::async_runtime::spawn(async move {
+        // Saving applies the mute and rebuilds the menu
+        if let Err(e) = crate::config::save_settings(serde_json::json!({ "tts_muted": muted })).await {
+          tracing::warn!(error = %e, "saving tts_muted failed");
+        }
+      });
+    }
+    _ => {
+      if let Some(index) = id.strip_prefix(QUICK_PROMPT_PREFIX).and_then(|n| n.parse::<u8>().ok()) {
+        in_target(app, move |app| async move {
+          if let Err(e) = crate::quick_prompts::run_quick_prompt(app.clone(), index, None).await {
+            let _ = app.emit("command:error", serde_json::json!({ "message": format!("Quick prompt {index}: {e}") }));
+          }
+        });
+      } else if let Some(conversation) = id.strip_prefix(CONVERSATION_PREFIX) {
+        show_main(app);
+        events::emit(app, events::CONVERSATION_OPEN, &events::ConversationOpen { id: conversation.to_string() });
+      }
+    }
+  }
+}
+
+/// Create the tray icon with its menu. Called once at startup, on the main thread.
+pub fn build(app: &AppHandle) -> tauri::Result<()> {
+  let _ = APP.set(app.clone());
+  let initial = content();
+  let menu = build_menu(app, &initial)?;
+  let mut tray_builder = TrayIconBuilder::with_id(TRAY_ID)
+    .menu(&menu)
+    .tooltip("AiDesktopCompanion")
+    .on_tray_icon_event(|tray, event| match event {
+      TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } => show_main(tray.app_handle()),
+      TrayIconEvent::Enter { .. } => {
+        *TARGET.lock().unwrap_or_else(|e| e.into_inner()) = crate::quick_actions::target_window_handle_raw();
+        rebuild(tray.app_handle());
+      }
+      _ => {}
+    })
+    .on_menu_event(|app, event| on_menu(app, event.id().as_ref()));
+
+  if let Some(icon) = app.default_window_icon() {
+    tray_builder = tray_builder.icon(icon.clone());
+  }
+  tray_builder.build(app)?;
+  *shown() = Some(initial);
+  Ok(())
+}
@@ -0,0 +1,246 @@
+// ElevenLabs text-to-speech (`tts_engine: "elevenlabs"`). File synthesis mirrors tts_openai.rs:
+// long texts are split at sentence boundaries and the MP3 pieces joined into one WAV. Streams use
+// the /stream endpoint and the same `tts:stream:*` events (and id counter) as OpenAI streams, so
+// the frontend can play either. The voice and model come from `tts_elevenlabs_voice` and
+// `tts_elevenlabs_model`.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use base64::Engine;
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::error::AidcError;
+use crate::events;
+
+const API_BASE: &str = "https://api.elevenlabs.io/v1";
+// "Rachel", one of the premade voices every account has
+pub const DEFAULT_VOICE: &str = "21m00Tcm4TlvDq8ikWAM";
+pub const DEFAULT_MODEL: &str = "eleven_multilingual_v2";
+// Per-request limit of the multilingual model (flash and turbo accept more)
+const MAX_INPUT_CHARS: usize = 5000;
+const MAX_PARTS: usize = 20;
+const OUTPUT_FORMAT: &str = "mp3_44100_128";
+
+static STREAM_STOPPERS: Lazy<Mutex<HashMap<u64, oneshot::Sender<()>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ElevenLabsVoice {
+  pub id: String,
+  pub name: String,
+  /// "premade" | "cloned" | "generated" | "professional"
+  pub category: String,
+}
+
+fn voice_or_default(voice: Option<String>) -> String {
+  voice.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).unwrap_or_else(|| DEFAULT_VOICE.to_string())
+}
+
+fn model_or_default(model: Option<String>) -> String {
+  model.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()).unwrap_or_else(|| DEFAULT_MODEL.to_string())
+}
+
+fn record_usage(model: &str, text: &str) {
+  crate::usage::record(crate::usage::TTS, model, crate::usage::Usage { characters: text.chars().count() as u64, ..Default::default() });
+}
+
+// text-to-speech/<voice>[/stream]; the voice id is percent-encoded as a single path segment
+fn speech_url(voice: &str, stream: bool) -> Result<reqwest::Url, AidcError> {
+  let mut url = reqwest::Url::parse(API_BASE).map_err(|e| AidcError::Internal(format!("invalid ElevenLabs URL: {e}")))?;
+  {
+    let mut segments = url.path_segments_mut().map_err(|_| AidcError::Internal("invalid ElevenLabs URL".into()))?;
+    segments.push("text-to-speech").push(voice);
+    if stream { segments.push("stream"); }
+  }
+  Ok(url)
+}
+
+// One text-to-speech request; returns the MP3 bytes
+async fn request_speech(client: &reqwest::Client, key: &str, voice: &str, model: &str, text: &str) -> Result<Vec<u8>, AidcError> {
+  let url = speech_url(voice, false)?;
+  let body = serde_json::json!({ "text": text, "model_id": model });
+  let resp = crate::rate_limit::send("elevenlabs", "tts", || {
+    client.post(url.clone()).query(&[("output_format", OUTPUT_FORMAT)]).header("xi-api-key", key).header("Accept", "audio/mpeg").json(&body)
+  })
+  .await
+  .map_err(|e| AidcError::from_reqwest("elevenlabs", &e))?;
+
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let body_text = resp.text().await.unwrap_or_default();
+    return Err(AidcError::from_status("elevenlabs", status, &body_text));
+  }
+  record_usage(model, text);
+  let bytes = resp.bytes().await.map_err(|e| AidcError::from_reqwest("elevenlabs", &e))?;
+  Ok(bytes.to_vec())
+}
+
+/// Synthesize `text` (already through tts_normalize) into a temp file: "mp3" keeps the API's MP3
+/// when the text fits one request, anything else (and any split text) becomes a PCM16 WAV with
+/// `rate` and `volume` applied.
+pub async fn synthesize_file(
+  key: String,
+  text: String,
+  voice: Option<String>,
+  model: Option<String>,
+  format: Option<String>,
+  rate: Option<i32>,
+  volume: Option<u8>,
+) -> Result<String, AidcError> {
+  if text.trim().is_empty() {
+    return Err(AidcError::InvalidInput("Text is empty".into()));
+  }
+  let parts = crate::tts_utils::split_for_tts(&text, MAX_INPUT_CHARS);
+  if parts.len() > MAX_PARTS {
+    return Err(AidcError::InvalidInput(format!(
+      "Text is too long for ElevenLabs TTS ({} parts of up to {} characters; the limit is {})",
+      parts.len(),
+      MAX_INPUT_CHARS,
+      MAX_PARTS
+    )));
+  }
+  let voice = voice_or_default(voice);
+  let model = model_or_default(model);
+  let client = crate::timeouts::client(crate::timeouts::TTS);
+
+  // Pieces are requested in order so a failure stops before spending on the rest
+  let mut clips: Vec<Vec<u8>> = Vec::with_capacity(parts.len());
+  for part in &parts {
+    clips.push(request_speech(&client, &key, &voice, &model, part).await?);
+  }
+
+  let ext = if clips.len() == 1 && format.as_deref() == Some("mp3") { "mp3" } else { "wav" };
+  let target = crate::temp_files::tts_path("elevenlabs", ext);
+
+  let r = rate.unwrap_or(0).clamp(-10, 10);
+  let vol = volume.unwrap_or(100).min(100);
+  let write_result = if clips.len() > 1 {
+    crate::tts_utils::join_pcm16_wav(&clips, &target, r, vol)
+  } else if ext == "wav" {
+    crate::tts_utils::write_pcm16_wav_from_any(&clips[0], &target, r, vol)
+  } else {
+    std::fs::write(&target, &clips[0]).map_err(|e| format!("write failed: {e}"))
+  };
+  if let Err(e) = write_result {
+    let _ = std::fs::remove_file(&target);
+    return Err(e.into());
+  }
+  crate::temp_files::register(crate::temp_files::TTS, &target);
+  Ok(target)
+}
+
+pub async fn synthesize_wav(key: String, text: String, voice: Option<String>, model: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, AidcError> {
+  synthesize_file(key, text, voice, model, Some("wav".to_string()), rate, volume).await
+}
+
+/// Stream MP3 for `text` as `tts:stream:*` events; returns the stream id.
+pub fn stream_start(app: tauri::AppHandle, key: String, text: String, voice: Option<String>, model: Option<String>) -> Result<u64, String> {
+  let text = crate::tts_normalize::normalize(&text);
+  if text.trim().is_empty() { return Err("Text is empty".into()); }
+  if text.chars().count() > MAX_INPUT_CHARS { return Err(format!("Text exceeds TTS limit of {MAX_INPUT_CHARS} characters")); }
+  let voice = voice_or_default(voice);
+  let model = model_or_default(model);
+  let url = speech_url(&voice, true).map_err(|e| e.to_string())?;
+
+  let (tx, mut rx) = oneshot::channel::<()>();
+  let id = crate::tts_openai::next_stream_id();
+  STREAM_STOPPERS.lock().map_err(|_| "Mutex poisoned")?.insert(id, tx);
+
+  tauri::async_runtime::spawn(async move {
+    // Bound the connection and each wait for data, not the whole stream: long texts keep
+    // streaming past the tts timeout
+    let client = reqwest::Client::builder()
+      .connect_timeout(std::time::Duration::from_secs(10))
+      .read_timeout(crate::timeouts::get(crate::timeouts::TTS))
+      .build()
+      .unwrap_or_else(|_| reqwest::Client::new());
+    let body = serde_json::json!({ "text": text, "model_id": model });
+    let resp_res = crate::rate_limit::send("elevenlabs", "tts stream", || {
+      client.post(url.clone()).query(&[("output_format", OUTPUT_FORMAT)]).header("xi-api-key", &key).header("Accept", "audio/mpeg").json(&body)
+    })
+    .await;
+
+    let app2 = app.clone();
+    let emit_err = |msg: String| events::emit(&app2, events::TTS_STREAM_ERROR, &events::TtsStreamError { id, message: msg });
+    let remove = || {
+      if let Ok(mut map) = STREAM_STOPPERS.lock() { map.remove(&id); }
+    };
+
+    let resp = match resp_res {
+      Ok(r) => r,
+      Err(e) => { emit_err(format!("request failed: {e}")); remove(); return; }
+    };
+    if !resp.status().is_success() {
+      let status = resp.status();
+      let body_text = resp.text().await.unwrap_or_default();
+      emit_err(format!("ElevenLabs error: {status} {body_text}"));
+      remove();
+      return;
+    }
+    record_usage(&model, &text);
+
+    events::emit(&app, events::TTS_STREAM_START, &events::TtsStreamStart { id, mime: "audio/mpeg".to_string() });
+    let mut stream = resp.bytes_stream();
+    loop {
+      tokio::select! {
+        _ = &mut rx => { events::emit(&app, events::TTS_STREAM_CANCELLED, &events::TtsStreamId { id }); break; }
+        next = stream.next() => {
+          match next {
+            Some(Ok(chunk)) => {
+              let b64 = base64::engine::general_purpose::STANDARD.encode(&chunk);
+              events::emit(&app, events::TTS_STREAM_CHUNK, &events::TtsStreamChunk { id, data: b64 });
+            }
+            Some(Err(e)) => { emit_err(format!("stream error: {e}")); break; }
+            None => { events::emit(&app, events::TTS_STREAM_END, &events::TtsStreamId { id }); break; }
+          }
+        }
+      }
+    }
+    remove();
+  });
+  Ok(id)
+}
+
+pub fn stream_stop(id: u64) -> Result<bool, String> {
+  let tx = STREAM_STOPPERS.lock().map_err(|_| "Mutex poisoned")?.remove(&id);
+  if let Some(tx) = tx { let _ = tx.send(()); Ok(true) } else { Ok(false) }
+}
+
+/// Stop every running stream (app shutdown); returns how many were running.
+pub fn stop_all_streams() -> usize {
+  let stoppers: Vec<oneshot::Sender<()>> = STREAM_STOPPERS.lock().map(|mut m| m.drain().map(|(_, tx)| tx).collect()).unwrap_or_default();
+  let n = stoppers.len();
+  for tx in stoppers { let _ = tx.send(()); }
+  n
+}
+
+/// Voices available to the account (premade and the user's own), sorted by name.
+pub async fn list_voices(key: String) -> Result<Vec<ElevenLabsVoice>, AidcError> {
+  let client = crate::timeouts::client(crate::timeouts::TTS);
+  let resp = client
+    .get(format!("{API_BASE}/voices"))
+    .header("xi-api-key", key)
+    .send()
+    .await
+    .map_err(|e| AidcError::from_reqwest("elevenlabs", &e))?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let body_text = resp.text().await.unwrap_or_default();
+    return Err(AidcError::from_status("elevenlabs", status, &body_text));
+  }
+  let v: serde_json::Value = resp.json().await.map_err(|e| AidcError::invalid_response("elevenlabs", format!("json error: {e}")))?;
+  let field = |x: &serde_json::Value, name: &str| x.get(name).and_then(|s| s.as_str()).unwrap_or("").to_string();
+  let mut voices: Vec<ElevenLabsVoice> = v
+    .get("voices")
+    .and_then(|x| x.as_array())
+    .into_iter()
+    .flatten()
+    .filter(|x| x.get("voice_id").and_then(|s| s.as_str()).is_some())
+    .map(|x| ElevenLabsVoice { id: field(x, "voice_id"), name: field(x, "name"), category: field(x, "category") })
+    .collect();
+  voices.sort_by_key(|x| x.name.to_lowercase());
+  Ok(voices)
+}
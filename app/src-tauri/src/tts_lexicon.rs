@@ -0,0 +1,145 @@
+// Pronunciation lexicon: user entries that rewrite words before synthesis so acronyms, product
+// names and people's names are spoken the way the user wants ("SQL" -> "sequel", "Nguyen" ->
+// "win"). Applied by tts_normalize after markdown and emoji removal, before the built-in
+// abbreviation, URL and number rules, so every engine (SAPI, `say`, OpenAI, ElevenLabs, Piper) gets
+// the rewritten text.
+//
+// Entries live in tts_lexicon.json next to settings.json:
+//   [
+//     { "pattern": "SQL", "replacement": "sequel" },
+//     { "pattern": "v(\\d+)\\.(\\d+)", "replacement": "version $1 point $2", "regex": true }
+//   ]
+// Plain patterns match whole words, case-insensitively unless `case_sensitive` is set. Regex
+// patterns use the `regex` crate syntax and may refer to groups as $1 / ${name}. Replacements are
+// plain text: the engines read markup such as IPA literally, so spell the sound out instead.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+const MAX_ENTRIES: usize = 1000;
+const MAX_PATTERN_CHARS: usize = 200;
+
+// Compiled entries (loaded lazily, replaced on save)
+static CACHE: Lazy<Mutex<Option<Vec<Compiled>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LexiconEntry {
+  pub pattern: String,
+  pub replacement: String,
+  /// `pattern` is a regular expression instead of a word or phrase
+  #[serde(default)]
+  pub regex: bool,
+  #[serde(default)]
+  pub case_sensitive: bool,
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+struct Compiled {
+  re: Regex,
+  replacement: String,
+  expand: bool,
+}
+
+pub fn lexicon_config_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name("tts_lexicon.json"))
+}
+
+fn load_entries() -> Vec<LexiconEntry> {
+  lexicon_config_path()
+    .and_then(|p| fs::read_to_string(p).ok())
+    .and_then(|text| serde_json::from_str::<Vec<LexiconEntry>>(&text).ok())
+    .unwrap_or_default()
+}
+
+fn compile(entry: &LexiconEntry) -> Result<Regex, String> {
+  let source = if entry.regex {
+    entry.pattern.clone()
+  } else {
+    // \b only works next to word characters; "C++" or ".NET" match at their word edge
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let start = if is_word(entry.pattern.chars().next()) { r"\b" } else { "" };
+    let end = if is_word(entry.pattern.chars().last()) { r"\b" } else { "" };
+    format!("{start}{}{end}", regex::escape(&entry.pattern))
+  };
+  RegexBuilder::new(&source)
+    .case_insensitive(!entry.case_sensitive)
+    .build()
+    .map_err(|e| format!("Pattern \"{}\" is not a valid regular expression: {e}", entry.pattern))
+}
+
+fn compile_all(entries: &[LexiconEntry]) -> Vec<Compiled> {
+  entries
+    .iter()
+    .filter(|e| e.enabled && !e.pattern.is_empty())
+    .filter_map(|e| match compile(e) {
+      Ok(re) => Some(Compiled { re, replacement: e.replacement.clone(), expand: e.regex }),
+      Err(err) => {
+        tracing::warn!(error = %err, "skipping pronunciation entry");
+        None
+      }
+    })
+    .collect()
+}
+
+fn validate(list: &[LexiconEntry]) -> Result<(), String> {
+  if list.len() > MAX_ENTRIES {
+    return Err(format!("The pronunciation lexicon is limited to {MAX_ENTRIES} entries"));
+  }
+  let mut seen = std::collections::HashSet::new();
+  for e in list {
+    let p = e.pattern.as_str();
+    if p.is_empty() || p.chars().count() > MAX_PATTERN_CHARS {
+      return Err(format!("Pattern \"{p}\" must be 1-{MAX_PATTERN_CHARS} characters"));
+    }
+    let re = compile(e)?;
+    if re.is_match("") {
+      return Err(format!("Pattern \"{p}\" matches empty text"));
+    }
+    if !seen.insert((p.to_string(), e.regex, e.case_sensitive)) {
+      return Err(format!("Duplicate pattern \"{p}\""));
+    }
+  }
+  Ok(())
+}
+
+/// Rewrite `text` with the enabled entries, in list order.
+pub fn apply(text: &str) -> String {
+  let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+  let compiled = cache.get_or_insert_with(|| compile_all(&load_entries()));
+  let mut out = text.to_string();
+  for c in compiled.iter() {
+    let next = if c.expand { c.re.replace_all(&out, c.replacement.as_str()) } else { c.re.replace_all(&out, NoExpand(&c.replacement)) };
+    out = next.into_owned();
+  }
+  out
+}
+
+#[tauri::command]
+pub fn tts_lexicon_get() -> Result<Vec<LexiconEntry>, String> {
+  Ok(load_entries())
+}
+
+/// Replace all pronunciation entries. Returns the lexicon file path.
+#[tauri::command]
+pub fn tts_lexicon_save(list: Vec<LexiconEntry>) -> Result<String, String> {
+  let list: Vec<LexiconEntry> = list
+    .into_iter()
+    .map(|e| LexiconEntry { pattern: if e.regex { e.pattern } else { e.pattern.trim().to_string() }, ..e })
+    .collect();
+  validate(&list)?;
+  let path = lexicon_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  let pretty = serde_json::to_string_pretty(&list).map_err(|e| format!("Serialize lexicon failed: {e}"))?;
+  crate::config::write_json_atomic_blocking(&path, &pretty)?;
+  *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some(compile_all(&list));
+  Ok(path.to_string_lossy().to_string())
+}
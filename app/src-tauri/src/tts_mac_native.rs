@@ -0,0 +1,114 @@
+// macOS system voices through the `say` CLI, behind the same API as the Windows SAPI backend
+// (tts_win_native re-exports these on macOS). Rates use the SAPI scale -10..10 and are mapped
+// to words per minute; volume is set with the `[[volm x]]` embedded speech command.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+static TTS_CHILD: Lazy<Mutex<Option<std::process::Child>>> = Lazy::new(|| Mutex::new(None));
+
+// `say` speaks about 175 words per minute by default; SAPI -10..10 halves to doubles that
+const DEFAULT_WPM: f32 = 175.0;
+
+fn words_per_minute(rate: i32) -> u32 {
+  (DEFAULT_WPM * 2f32.powf(rate.clamp(-10, 10) as f32 / 10.0)).round() as u32
+}
+
+fn with_volume(text: &str, vol: u8) -> String {
+  if vol >= 100 { return text.to_string(); }
+  format!("[[volm {:.2}]] {}", vol as f32 / 100.0, text)
+}
+
+// `say` reading the text from stdin (`-f -`), so long texts are not limited by argv
+fn say_command(voice: &str, rate: i32, extra: &[&str]) -> Command {
+  let mut cmd = Command::new("say");
+  if !voice.trim().is_empty() { cmd.args(["-v", voice.trim()]); }
+  cmd.args(["-r", &words_per_minute(rate).to_string()]);
+  cmd.args(extra);
+  cmd.args(["-f", "-"]);
+  cmd.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null());
+  cmd
+}
+
+fn spawn_say(text: &str, voice: &str, rate: i32, vol: u8, extra: &[&str]) -> Result<std::process::Child, String> {
+  let mut child = say_command(voice, rate, extra).spawn().map_err(|e| format!("launch say failed: {e}"))?;
+  if let Some(stdin) = child.stdin.as_mut() { stdin.write_all(with_volume(&text, vol).as_bytes()).map_err(|e| format!("stdin write failed: {e}"))?; }
+  drop(child.stdin.take());
+  Ok(child)
+}
+
+pub fn local_tts_start(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<(), String> {
+  if text.trim().is_empty() { return Err("Text is empty".into()); }
+  if let Ok(mut guard) = TTS_CHILD.lock() {
+    if let Some(mut c) = guard.take() { let _ = c.kill(); let _ = c.wait(); }
+  }
+  let text = crate::tts_normalize::normalize(&text);
+  let child = spawn_say(&text, &voice.unwrap_or_default(), rate.unwrap_or(-2), volume.unwrap_or(100).min(100), &[])?;
+  if let Ok(mut guard) = TTS_CHILD.lock() { *guard = Some(child); }
+  Ok(())
+}
+
+pub fn local_tts_stop() -> Result<(), String> {
+  if let Ok(mut guard) = TTS_CHILD.lock() {
+    if let Some(mut c) = guard.take() {
+      let _ = c.kill(); let _ = c.wait();
+    }
+  }
+  Ok(())
+}
+
+pub fn local_tts_is_speaking() -> bool {
+  if let Ok(mut guard) = TTS_CHILD.lock() {
+    if let Some(ref mut c) = *guard {
+      match c.try_wait() {
+        Ok(Some(_)) => { guard.take(); false }
+        Ok(None) => true,
+        Err(_) => { guard.take(); false }
+      }
+    } else { false }
+  } else { false }
+}
+
+/// Voice names from `say -v ?`, whose lines look like
+/// `Eddy (English (US))   en_US    # Hello! My name is Eddy.`
+pub fn local_tts_list_voices() -> Result<Vec<String>, String> {
+  let out = Command::new("say").args(["-v", "?"]).output().map_err(|e| format!("launch say failed: {e}"))?;
+  if !out.status.success() { return Err(format!("say exited with status: {}", out.status)); }
+  let s = String::from_utf8_lossy(&out.stdout);
+  let mut names: Vec<String> = s
+    .lines()
+    .filter_map(|l| {
+      let head = l.split('#').next()?.trim_end();
+      // Drop the trailing locale column
+      let (name, _locale) = head.rsplit_once(char::is_whitespace)?;
+      let name = name.trim();
+      if name.is_empty() { None } else { Some(name.to_string()) }
+    })
+    .collect();
+  names.dedup();
+  Ok(names)
+}
+
+#[allow(dead_code)]
+pub fn local_speak_blocking(text: String, voice: String, rate: i32, vol: u8) -> Result<(), String> {
+  let text = crate::tts_normalize::normalize(&text);
+  let mut child = spawn_say(&text, &voice, rate, vol.min(100), &[])?;
+  let status = child.wait().map_err(|e| format!("say wait failed: {e}"))?;
+  if !status.success() { return Err(format!("say exited with status: {status}")); }
+  Ok(())
+}
+
+pub fn local_tts_synthesize_wav(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, String> {
+  if text.trim().is_empty() { return Err("Text is empty".into()); }
+  let target = crate::temp_files::tts_path("say", "wav");
+  // 16-bit little-endian PCM, like the SAPI output the rest of the pipeline expects
+  let extra = ["-o", target.as_str(), "--file-format=WAVE", "--data-format=LEI16@22050"];
+  let mut child = spawn_say(&text, &voice.unwrap_or_default(), rate.unwrap_or(-2), volume.unwrap_or(100).min(100), &extra)?;
+  let status = child.wait().map_err(|e| format!("say wait failed: {e}"))?;
+  if !status.success() { return Err(format!("say exited with status: {status}")); }
+  crate::temp_files::register(crate::temp_files::TTS, &target);
+  Ok(target)
+}
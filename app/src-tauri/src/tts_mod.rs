@@ -1,4 +1,5 @@
-// TTS module facade: re-export OpenAI, generic utils, and Windows-native TTS helpers
+// TTS module facade: re-export OpenAI, generic utils, Windows-native TTS helpers and the chunked
+// reading queue
 // This file replaces the legacy tts.rs implementation to avoid duplication.
 #![allow(unused_imports)]
 
@@ -27,6 +28,8 @@ pub use crate::tts_openai::{
   stream_cleanup_idle,
 };
 
+pub use crate::tts_queue::speak;
+
 pub use crate::tts_win_native::{
   local_tts_start,
   local_tts_stop,
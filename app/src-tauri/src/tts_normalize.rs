@@ -0,0 +1,302 @@
+// Text clean-up before speech synthesis. Selections and chat answers are full of markdown, links,
+// code and emoji that sound terrible when read aloud, so local (SAPI, `say`) and OpenAI synthesis
+// run `normalize` first. Rules come from the `tts_normalization` settings object:
+//   strip_markdown (default on)        headings, emphasis, list markers, tables and link syntax
+//   skip_code_blocks (on)              leave fenced code blocks out instead of reading them
+//   expand_abbreviations (on)          "e.g." -> "for example", "approx." -> "approximately", ...
+//   urls: "domain" (default)           read a link as its host ("github.com")
+//         "spell"                      read the whole link ("github dot com slash ...")
+//         "keep"                       leave links as they are
+//   spell_numbers (off)                "1,250" -> "one thousand two hundred fifty"
+//   remove_emoji (on)
+// The user's pronunciation lexicon (tts_lexicon.rs) runs after markdown and emoji removal, so its
+// entries take precedence over the built-in abbreviations.
+
+const URLS_DOMAIN: &str = "domain";
+const URLS_SPELL: &str = "spell";
+const URLS_KEEP: &str = "keep";
+
+// Matched case-insensitively against whole words (trailing commas etc. are kept)
+const ABBREVIATIONS: &[(&str, &str)] = &[
+  ("e.g.", "for example"),
+  ("i.e.", "that is"),
+  ("etc.", "et cetera"),
+  ("vs.", "versus"),
+  ("vs", "versus"),
+  ("approx.", "approximately"),
+  ("incl.", "including"),
+  ("esp.", "especially"),
+  ("w/", "with"),
+  ("w/o", "without"),
+  ("mr.", "Mister"),
+  ("mrs.", "Missus"),
+  ("dr.", "Doctor"),
+  ("prof.", "Professor"),
+  ("fyi", "for your information"),
+  ("btw", "by the way"),
+  ("imo", "in my opinion"),
+  ("asap", "as soon as possible"),
+  ("tbd", "to be decided"),
+  ("&", "and"),
+];
+
+// Characters dropped around a word before matching URLs and numbers
+const LEADING_PUNCT: &[char] = &['(', '[', '{', '"', '\'', '“', '‘'];
+const TRAILING_PUNCT: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '"', '\'', '”', '’'];
+
+const ONES: [&str; 20] = [
+  "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve", "thirteen",
+  "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+const SCALES: [(u64, &str); 3] = [(1_000_000_000, "billion"), (1_000_000, "million"), (1_000, "thousand")];
+
+#[derive(Clone, Debug)]
+pub struct Rules {
+  pub strip_markdown: bool,
+  pub skip_code_blocks: bool,
+  pub expand_abbreviations: bool,
+  /// "domain" | "spell" | "keep"
+  pub urls: String,
+  pub spell_numbers: bool,
+  pub remove_emoji: bool,
+}
+
+impl Rules {
+  pub fn from_settings() -> Self {
+    let v = crate::config::get_tts_normalization_from_settings();
+    let flag = |key: &str, default: bool| v.get(key).and_then(|x| x.as_bool()).unwrap_or(default);
+    let urls = v.get("urls").and_then(|x| x.as_str()).map(|s| s.trim().to_lowercase()).unwrap_or_default();
+    Rules {
+      strip_markdown: flag("strip_markdown", true),
+      skip_code_blocks: flag("skip_code_blocks", true),
+      expand_abbreviations: flag("expand_abbreviations", true),
+      urls: if urls == URLS_SPELL || urls == URLS_KEEP { urls } else { URLS_DOMAIN.to_string() },
+      spell_numbers: flag("spell_numbers", false),
+      remove_emoji: flag("remove_emoji", true),
+    }
+  }
+}
+
+/// Apply the configured rules to `text` before it is synthesized.
+pub fn normalize(text: &str) -> String {
+  apply(text, &Rules::from_settings())
+}
+
+pub fn apply(text: &str, rules: &Rules) -> String {
+  let mut out = text.replace("\r\n", "\n");
+  if rules.skip_code_blocks {
+    out = remove_code_blocks(&out);
+  }
+  if rules.strip_markdown {
+    // Bullets and table cells from strip_markdown read better as plain pauses
+    out = crate::paste_as::strip_markdown(&out).replace("• ", "").replace('\t', ", ");
+  }
+  if rules.remove_emoji {
+    out = out.chars().filter(|c| !is_emoji(*c)).collect();
+  }
+  out = crate::tts_lexicon::apply(&out);
+
+  // Rewrite word by word; this also collapses the whitespace left behind by removed syntax
+  let mut lines: Vec<String> = Vec::new();
+  for line in out.lines() {
+    let words: Vec<String> = line.split_whitespace().map(|w| rewrite_word(w, rules)).collect();
+    let line = words.join(" ");
+    if line.is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+      continue;
+    }
+    lines.push(line);
+  }
+  lines.join("\n").trim().to_string()
+}
+
+// Drop ``` / ~~~ fenced blocks together with their fences
+fn remove_code_blocks(text: &str) -> String {
+  let mut kept: Vec<&str> = Vec::new();
+  let mut in_fence = false;
+  for line in text.lines() {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+      in_fence = !in_fence;
+      continue;
+    }
+    if !in_fence {
+      kept.push(line);
+    }
+  }
+  kept.join("\n")
+}
+
+fn rewrite_word(word: &str, rules: &Rules) -> String {
+  if rules.expand_abbreviations {
+    let bare = word.trim_start_matches(LEADING_PUNCT);
+    let lead = &word[..word.len() - bare.len()];
+    // Keep the abbreviation's own dot; only other trailing punctuation is split off
+    let core = bare.trim_end_matches(|c: char| c != '.' && TRAILING_PUNCT.contains(&c));
+    let trail = &bare[core.len()..];
+    if let Some((_, spoken)) = ABBREVIATIONS.iter().find(|(abbr, _)| abbr.eq_ignore_ascii_case(core)) {
+      return format!("{lead}{spoken}{trail}");
+    }
+  }
+
+  let bare = word.trim_start_matches(LEADING_PUNCT);
+  let lead = &word[..word.len() - bare.len()];
+  let core = bare.trim_end_matches(TRAILING_PUNCT);
+  let trail = &bare[core.len()..];
+  if core.is_empty() {
+    return word.to_string();
+  }
+  if rules.urls != URLS_KEEP && is_url(core) {
+    let spoken = if rules.urls == URLS_SPELL { spell_url(core) } else { url_domain(core) };
+    return format!("{lead}{spoken}{trail}");
+  }
+  if rules.spell_numbers {
+    if let Some(spoken) = spell_number_word(core) {
+      return format!("{lead}{spoken}{trail}");
+    }
+  }
+  word.to_string()
+}
+
+fn is_url(s: &str) -> bool {
+  let lower = s.to_ascii_lowercase();
+  lower.starts_with("http://") || lower.starts_with("https://") || (lower.starts_with("www.") && lower.len() > 4)
+}
+
+fn strip_scheme(url: &str) -> &str {
+  let rest = url.split_once("://").map_or(url, |(_, r)| r);
+  rest.strip_prefix("www.").or_else(|| rest.strip_prefix("WWW.")).unwrap_or(rest)
+}
+
+fn url_domain(url: &str) -> String {
+  let host = strip_scheme(url).split(['/', '?', '#']).next().unwrap_or("");
+  // Drop credentials and the port
+  let host = host.rsplit('@').next().unwrap_or(host);
+  host.split(':').next().unwrap_or(host).to_lowercase()
+}
+
+fn spell_url(url: &str) -> String {
+  let rest = strip_scheme(url).trim_end_matches('/');
+  let mut out = String::new();
+  for c in rest.chars() {
+    let spoken = match c {
+      '.' => " dot ",
+      '/' => " slash ",
+      '-' => " dash ",
+      '_' => " underscore ",
+      '?' => " question mark ",
+      '=' => " equals ",
+      '&' => " and ",
+      '#' => " hash ",
+      '@' => " at ",
+      ':' => " colon ",
+      _ => {
+        out.push(c);
+        continue;
+      }
+    };
+    out.push_str(spoken);
+  }
+  out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// "42", "-7", "1,250", "3.14" and "15%"; anything else (versions, dates, ids) is left alone
+fn spell_number_word(word: &str) -> Option<String> {
+  let (body, percent) = match word.strip_suffix('%') {
+    Some(b) => (b, true),
+    None => (word, false),
+  };
+  let (body, negative) = match body.strip_prefix('-') {
+    Some(b) => (b, true),
+    None => (body, false),
+  };
+  let (int_part, frac_part) = match body.split_once('.') {
+    Some((i, f)) => (i, Some(f)),
+    None => (body, None),
+  };
+  if int_part.is_empty() || !int_part.chars().all(|c| c.is_ascii_digit() || c == ',') {
+    return None;
+  }
+  if int_part.contains(',') {
+    let mut groups = int_part.split(',');
+    let first = groups.next().unwrap_or("");
+    if first.is_empty() || first.len() > 3 || !groups.all(|g| g.len() == 3) {
+      return None;
+    }
+  }
+  let digits: String = int_part.chars().filter(|c| *c != ',').collect();
+  if digits.len() > 12 {
+    return None;
+  }
+  let n: u64 = digits.parse().ok()?;
+  let mut out = if negative { format!("minus {}", number_words(n)) } else { number_words(n) };
+  if let Some(frac) = frac_part {
+    if frac.is_empty() || !frac.chars().all(|c| c.is_ascii_digit()) {
+      return None;
+    }
+    out.push_str(" point");
+    for d in frac.chars() {
+      out.push(' ');
+      out.push_str(ONES[(d as u8 - b'0') as usize]);
+    }
+  }
+  if percent {
+    out.push_str(" percent");
+  }
+  Some(out)
+}
+
+fn number_words(n: u64) -> String {
+  if n == 0 {
+    return ONES[0].to_string();
+  }
+  let mut parts: Vec<String> = Vec::new();
+  let mut rest = n;
+  for (scale, name) in SCALES {
+    if rest >= scale {
+      parts.push(format!("{} {name}", under_thousand(rest / scale)));
+      rest %= scale;
+    }
+  }
+  if rest > 0 {
+    parts.push(under_thousand(rest));
+  }
+  parts.join(" ")
+}
+
+// 1..=999 as words (numbers are capped at 12 digits, so every scale group fits)
+fn under_thousand(n: u64) -> String {
+  let mut parts: Vec<String> = Vec::new();
+  let hundreds = n / 100;
+  let rest = n % 100;
+  if hundreds > 0 {
+    parts.push(format!("{} hundred", ONES[hundreds as usize]));
+  }
+  if rest >= 20 {
+    let tens = TENS[(rest / 10) as usize];
+    parts.push(if rest % 10 == 0 { tens.to_string() } else { format!("{tens} {}", ONES[(rest % 10) as usize]) });
+  } else if rest > 0 {
+    parts.push(ONES[rest as usize].to_string());
+  }
+  parts.join(" ")
+}
+
+fn is_emoji(c: char) -> bool {
+  matches!(
+    c,
+    '\u{1F000}'..='\u{1FAFF}'
+      | '\u{2600}'..='\u{27BF}'
+      | '\u{2B00}'..='\u{2BFF}'
+      | '\u{231A}'..='\u{23FF}'
+      | '\u{FE0E}'..='\u{FE0F}'
+      | '\u{200D}'
+      | '\u{20E3}'
+      | '\u{E0020}'..='\u{E007F}'
+  )
+}
+
+/// Preview the normalization of `text` with the saved rules and lexicon (settings "Test" box).
+#[tauri::command]
+pub fn tts_normalize_preview(text: String) -> Result<String, String> {
+  Ok(normalize(&text))
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "streaming-server")]
 use std::time::Duration;
 
 use base64::Engine;
@@ -5,7 +6,6 @@ use futures_util::StreamExt;
 use once_cell::sync::Lazy as OnceLazy;
 use once_cell::sync::Lazy as GlobalLazy;
 use serde_json;
-use tauri::Emitter;
 use tokio::sync::oneshot;
 use crate::tts_utils::{
   write_pcm16_wav_from_any,
@@ -18,9 +18,14 @@ use std::collections::HashMap;
 use std::sync::Mutex as StdMutex;
 use std::sync::atomic::{AtomicU64, Ordering};
 
+#[cfg(feature = "streaming-server")]
 use crate::tts_streaming_server::TtsStreamingServer;
+use crate::error::AidcError;
+use crate::events;
 
 const OPENAI_TTS_MAX_INPUT_CHARS: usize = 3500;
+// Longer texts are split; this caps the number of requests one synthesis may make
+const OPENAI_TTS_MAX_PARTS: usize = 30;
 
 // Audio decode helpers moved to tts_utils
 
@@ -28,8 +33,27 @@ const OPENAI_TTS_MAX_INPUT_CHARS: usize = 3500;
 // TTS Streaming Server state and helpers
 // ---------------------------
 
+/// One session of the local streaming proxy, as listed by `tts_stream_session_list`.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct StreamSessionInfo {
+  pub id: String,
+  /// Unix time in milliseconds
+  pub created_at: i64,
+  pub age_secs: u64,
+  /// The player has connected and audio is (or was) streaming
+  pub started: bool,
+  pub text_chars: usize,
+  /// First characters of the text
+  pub preview: String,
+  pub format: String,
+  pub voice: String,
+  pub model: String,
+}
+
+#[cfg(feature = "streaming-server")]
 static TTS_STREAMING_SERVER: OnceLazy<StdMutex<Option<TtsStreamingServer>>> = OnceLazy::new(|| StdMutex::new(None));
 
+#[cfg(feature = "streaming-server")]
 pub async fn ensure_streaming_server() -> Result<(), String> {
   let need_init = {
     let guard = TTS_STREAMING_SERVER.lock().map_err(|_| "Mutex poisoned")?;
@@ -46,7 +70,9 @@ pub async fn ensure_streaming_server() -> Result<(), String> {
   Ok(())
 }
 
+#[cfg(feature = "streaming-server")]
 pub async fn create_stream_session(text: String, voice: Option<String>, model: Option<String>, format: Option<String>, instructions: Option<String>, api_key: String) -> Result<String, String> {
+  let text = crate::tts_normalize::normalize(&text);
   if text.trim().is_empty() { return Err("Text is empty".into()); }
   if text.len() > OPENAI_TTS_MAX_INPUT_CHARS { return Err(format!("Text exceeds TTS limit of {} characters", OPENAI_TTS_MAX_INPUT_CHARS)); }
   ensure_streaming_server().await?;
@@ -60,21 +86,91 @@ pub async fn create_stream_session(text: String, voice: Option<String>, model: O
   Ok(url)
 }
 
+#[cfg(feature = "streaming-server")]
 pub fn stop_stream_session(session_id: String) -> Result<bool, String> {
   let guard = TTS_STREAMING_SERVER.lock().map_err(|_| "Mutex poisoned")?;
   if let Some(server) = guard.as_ref() { Ok(server.stop_session(&session_id)) } else { Err("TTS streaming server not available".into()) }
 }
 
+#[cfg(feature = "streaming-server")]
 pub fn stream_session_count() -> Result<usize, String> {
   let guard = TTS_STREAMING_SERVER.lock().map_err(|_| "Mutex poisoned")?;
   if let Some(server) = guard.as_ref() { Ok(server.count_sessions()) } else { Ok(0) }
 }
 
+#[cfg(feature = "streaming-server")]
+pub fn stream_session_list() -> Result<Vec<StreamSessionInfo>, String> {
+  let guard = TTS_STREAMING_SERVER.lock().map_err(|_| "Mutex poisoned")?;
+  Ok(guard.as_ref().map(|s| s.list_sessions()).unwrap_or_default())
+}
+
+#[cfg(feature = "streaming-server")]
+pub fn stream_session_stop_all() -> Result<usize, String> {
+  let guard = TTS_STREAMING_SERVER.lock().map_err(|_| "Mutex poisoned")?;
+  Ok(guard.as_ref().map(|s| s.stop_all()).unwrap_or(0))
+}
+
+#[cfg(feature = "streaming-server")]
 pub fn stream_cleanup_idle(ttl_seconds: u64) -> Result<usize, String> {
   let guard = TTS_STREAMING_SERVER.lock().map_err(|_| "Mutex poisoned")?;
   if let Some(server) = guard.as_ref() { Ok(server.cleanup_idle(Duration::from_secs(ttl_seconds))) } else { Ok(0) }
 }
 
+/// Start the local streaming server and open the connection to the speech endpoint ahead of the
+/// first playback. Called during app setup and when the TTS panel opens; cheap when already warm.
+#[cfg(feature = "streaming-server")]
+pub async fn prewarm() -> Result<(), String> {
+  ensure_streaming_server().await?;
+  if !crate::connectivity::is_online() {
+    return Ok(());
+  }
+  crate::tts_streaming_server::warm_speech_connection().await
+}
+
+// Builds without the `streaming-server` feature have no local HTTP proxy for streamed playback
+#[cfg(not(feature = "streaming-server"))]
+const STREAMING_UNAVAILABLE: &str = "TTS streaming is not available in this build";
+
+#[cfg(not(feature = "streaming-server"))]
+pub async fn ensure_streaming_server() -> Result<(), String> {
+  Err(STREAMING_UNAVAILABLE.into())
+}
+
+#[cfg(not(feature = "streaming-server"))]
+pub async fn create_stream_session(_text: String, _voice: Option<String>, _model: Option<String>, _format: Option<String>, _instructions: Option<String>, _api_key: String) -> Result<String, String> {
+  Err(STREAMING_UNAVAILABLE.into())
+}
+
+#[cfg(not(feature = "streaming-server"))]
+pub async fn prewarm() -> Result<(), String> {
+  Ok(())
+}
+
+#[cfg(not(feature = "streaming-server"))]
+pub fn stop_stream_session(_session_id: String) -> Result<bool, String> {
+  Err(STREAMING_UNAVAILABLE.into())
+}
+
+#[cfg(not(feature = "streaming-server"))]
+pub fn stream_session_count() -> Result<usize, String> {
+  Ok(0)
+}
+
+#[cfg(not(feature = "streaming-server"))]
+pub fn stream_session_list() -> Result<Vec<StreamSessionInfo>, String> {
+  Ok(Vec::new())
+}
+
+#[cfg(not(feature = "streaming-server"))]
+pub fn stream_session_stop_all() -> Result<usize, String> {
+  Ok(0)
+}
+
+#[cfg(not(feature = "streaming-server"))]
+pub fn stream_cleanup_idle(_ttl_seconds: u64) -> Result<usize, String> {
+  Ok(0)
+}
+
 // ---------------------------
 // OpenAI direct streaming (speech) and Responses SSE
 // ---------------------------
@@ -82,6 +178,11 @@ pub fn stream_cleanup_idle(ttl_seconds: u64) -> Result<usize, String> {
 static STREAM_COUNTER: GlobalLazy<AtomicU64> = GlobalLazy::new(|| AtomicU64::new(0));
 static STREAM_STOPPERS: GlobalLazy<StdMutex<HashMap<u64, oneshot::Sender<()>>>> = GlobalLazy::new(|| StdMutex::new(HashMap::new()));
 
+/// Next `tts:stream:*` id; shared with the ElevenLabs streams so ids never collide.
+pub fn next_stream_id() -> u64 {
+  STREAM_COUNTER.fetch_add(1, Ordering::SeqCst) + 1
+}
+
 pub fn openai_stream_start(
   app: tauri::AppHandle,
   key: String,
@@ -90,6 +191,7 @@ pub fn openai_stream_start(
   model: Option<String>,
   format: Option<String>,
 ) -> Result<u64, String> {
+  let text = crate::tts_normalize::normalize(&text);
   if text.trim().is_empty() { return Err("Text is empty".into()); }
   if text.len() > OPENAI_TTS_MAX_INPUT_CHARS { return Err(format!("Text exceeds TTS limit of {} characters", OPENAI_TTS_MAX_INPUT_CHARS)); }
   let fmt = format.unwrap_or_else(|| "opus".to_string());
@@ -102,7 +204,7 @@ pub fn openai_stream_start(
   let body = serde_json::json!({ "model": m, "input": text, "voice": v, "response_format": body_format });
 
   let (tx, rx) = oneshot::channel::<()>();
-  let id = STREAM_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+  let id = next_stream_id();
   {
     let mut map = STREAM_STOPPERS.lock().map_err(|_| "Mutex poisoned")?;
     map.insert(id, tx);
@@ -121,6 +223,18 @@ pub fn openai_stream_stop(id: u64) -> Result<bool, String> {
   if let Some(tx) = tx { let _ = tx.send(()); Ok(true) } else { Ok(false) }
 }
 
+/// Stop every direct and proxied stream (app shutdown); returns how many were running.
+pub fn stop_all_streams() -> usize {
+  let stoppers: Vec<oneshot::Sender<()>> = STREAM_STOPPERS.lock().map(|mut m| m.drain().map(|(_, tx)| tx).collect()).unwrap_or_default();
+  let direct = stoppers.len();
+  for tx in stoppers { let _ = tx.send(()); }
+  #[cfg(feature = "streaming-server")]
+  let proxied = TTS_STREAMING_SERVER.lock().ok().and_then(|g| g.as_ref().map(|s| s.stop_all())).unwrap_or(0);
+  #[cfg(not(feature = "streaming-server"))]
+  let proxied = 0;
+  direct + proxied
+}
+
 pub fn responses_stream_start(
   app: tauri::AppHandle,
   key: String,
@@ -129,6 +243,7 @@ pub fn responses_stream_start(
   model: Option<String>,
   format: Option<String>,
 ) -> Result<u64, String> {
+  let text = crate::tts_normalize::normalize(&text);
   if text.trim().is_empty() { return Err("Text is empty".into()); }
   if text.len() > OPENAI_TTS_MAX_INPUT_CHARS { return Err(format!("Text exceeds TTS limit of {} characters", OPENAI_TTS_MAX_INPUT_CHARS)); }
   let fmt = format.unwrap_or_else(|| "opus".to_string());
@@ -143,7 +258,7 @@ pub fn responses_stream_start(
     "stream": true
   });
   let (tx, rx) = oneshot::channel::<()>();
-  let id = STREAM_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+  let id = next_stream_id();
   {
     let mut map = STREAM_STOPPERS.lock().map_err(|_| "Mutex poisoned")?;
     map.insert(id, tx);
@@ -165,17 +280,14 @@ pub fn spawn_speech_stream(
   on_remove: impl FnOnce(u64) + Send + 'static,
 ) {
   tauri::async_runtime::spawn(async move {
-    let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(120)).connect_timeout(std::time::Duration::from_secs(10)).build().unwrap_or_else(|_| reqwest::Client::new());
-    let resp_res = client
-      .post("https://api.openai.com/v1/audio/speech")
-      .bearer_auth(key)
-      .header("Accept", accept)
-      .json(&body)
-      .send()
-      .await;
+    let client = crate::timeouts::client(crate::timeouts::TTS);
+    let resp_res = crate::rate_limit::send("openai", "tts stream", || {
+      client.post("https://api.openai.com/v1/audio/speech").bearer_auth(&key).header("Accept", accept).json(&body)
+    })
+    .await;
 
     let app2 = app.clone();
-    let emit_err = |msg: String| { let _ = app2.emit("tts:stream:error", serde_json::json!({ "id": id, "message": msg })); };
+    let emit_err = |msg: String| events::emit(&app2, events::TTS_STREAM_ERROR, &events::TtsStreamError { id, message: msg });
 
     let resp = match resp_res {
       Ok(r) => r,
@@ -189,21 +301,22 @@ pub fn spawn_speech_stream(
       on_remove(id);
       return;
     }
+    crate::usage::record_tts(&body);
 
-    let _ = app.emit("tts:stream:start", serde_json::json!({ "id": id, "mime": mime }));
+    events::emit(&app, events::TTS_STREAM_START, &events::TtsStreamStart { id, mime: mime.to_string() });
 
     let mut stream = resp.bytes_stream();
     loop {
       tokio::select! {
-        _ = &mut rx => { let _ = app.emit("tts:stream:cancelled", serde_json::json!({ "id": id })); break; }
+        _ = &mut rx => { events::emit(&app, events::TTS_STREAM_CANCELLED, &events::TtsStreamId { id }); break; }
         next = stream.next() => {
           match next {
             Some(Ok(chunk)) => {
               let b64 = base64::engine::general_purpose::STANDARD.encode(&chunk);
-              let _ = app.emit("tts:stream:chunk", serde_json::json!({ "id": id, "data": b64 }));
+              events::emit(&app, events::TTS_STREAM_CHUNK, &events::TtsStreamChunk { id, data: b64 });
             }
             Some(Err(e)) => { emit_err(format!("stream error: {e}")); break; }
-            None => { let _ = app.emit("tts:stream:end", serde_json::json!({ "id": id })); break; }
+            None => { events::emit(&app, events::TTS_STREAM_END, &events::TtsStreamId { id }); break; }
           }
         }
       }
@@ -223,17 +336,14 @@ pub fn spawn_responses_stream(
   on_remove: impl FnOnce(u64) + Send + 'static,
 ) {
   tauri::async_runtime::spawn(async move {
-    let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(120)).connect_timeout(std::time::Duration::from_secs(10)).build().unwrap_or_else(|_| reqwest::Client::new());
-    let resp_res = client
-      .post("https://api.openai.com/v1/responses")
-      .bearer_auth(key)
-      .header("Accept", "text/event-stream")
-      .json(&body)
-      .send()
-      .await;
+    let client = crate::timeouts::client(crate::timeouts::TTS);
+    let resp_res = crate::rate_limit::send("openai", "tts stream", || {
+      client.post("https://api.openai.com/v1/responses").bearer_auth(&key).header("Accept", "text/event-stream").json(&body)
+    })
+    .await;
 
     let app2 = app.clone();
-    let emit_err = |msg: String| { let _ = app2.emit("tts:stream:error", serde_json::json!({ "id": id, "message": msg })); };
+    let emit_err = |msg: String| events::emit(&app2, events::TTS_STREAM_ERROR, &events::TtsStreamError { id, message: msg });
 
     let resp = match resp_res {
       Ok(r) => r,
@@ -247,20 +357,21 @@ pub fn spawn_responses_stream(
       on_remove(id);
       return;
     }
+    crate::usage::record_tts(&body);
 
     let mime = match fmt.as_str() {
       "mp3" => "audio/mpeg",
       "wav" => "audio/wav",
       _ => "audio/ogg; codecs=opus",
     };
-    let _ = app.emit("tts:stream:start", serde_json::json!({ "id": id, "mime": mime }));
+    events::emit(&app, events::TTS_STREAM_START, &events::TtsStreamStart { id, mime: mime.to_string() });
 
     let mut stream = resp.bytes_stream();
     let mut buf: Vec<u8> = Vec::new();
     let mut done = false;
     loop {
       tokio::select! {
-        _ = &mut rx => { let _ = app.emit("tts:stream:cancelled", serde_json::json!({ "id": id })); break; }
+        _ = &mut rx => { events::emit(&app, events::TTS_STREAM_CANCELLED, &events::TtsStreamId { id }); break; }
         next = stream.next() => {
           match next {
             Some(Ok(chunk)) => {
@@ -270,7 +381,7 @@ pub fn spawn_responses_stream(
                   let ev_bytes = buf.drain(..pos).collect::<Vec<u8>>();
                   let _ = consume_leading_newlines(&mut buf);
                   if let Some(data_json) = extract_sse_data(&ev_bytes) {
-                    if data_json.trim() == "[DONE]" { let _ = app.emit("tts:stream:end", serde_json::json!({ "id": id })); done = true; break; }
+                    if data_json.trim() == "[DONE]" { events::emit(&app, events::TTS_STREAM_END, &events::TtsStreamId { id }); done = true; break; }
                     if let Ok(val) = serde_json::from_str::<serde_json::Value>(&data_json) {
                       let typ = val.get("type").and_then(|v| v.as_str()).unwrap_or("");
                       // OpenAI Responses API uses "response.audio.delta" for audio chunks
@@ -278,9 +389,9 @@ pub fn spawn_responses_stream(
                         let b64 = val.get("delta").and_then(|v| v.as_str())
                           .or_else(|| val.get("audio").and_then(|v| v.as_str()))
                           .unwrap_or("");
-                        if !b64.is_empty() { let _ = app.emit("tts:stream:chunk", serde_json::json!({ "id": id, "data": b64 })); }
+                        if !b64.is_empty() { events::emit(&app, events::TTS_STREAM_CHUNK, &events::TtsStreamChunk { id, data: b64.to_string() }); }
                       } else if typ == "response.completed" {
-                        let _ = app.emit("tts:stream:end", serde_json::json!({ "id": id }));
+                        events::emit(&app, events::TTS_STREAM_END, &events::TtsStreamId { id });
                         done = true;
                         break;
                       }
@@ -291,7 +402,7 @@ pub fn spawn_responses_stream(
               if done { break; }
             }
             Some(Err(e)) => { emit_err(format!("stream error: {e}")); break; }
-            None => { if !done { let _ = app.emit("tts:stream:end", serde_json::json!({ "id": id })); } break; }
+            None => { if !done { events::emit(&app, events::TTS_STREAM_END, &events::TtsStreamId { id }); } break; }
           }
         }
       }
@@ -307,6 +418,34 @@ pub fn spawn_responses_stream(
 // OpenAI synth helpers (file and wav)
 // ---------------------------
 
+// One /v1/audio/speech request; returns the Content-Type header and the audio bytes
+async fn request_speech(client: &reqwest::Client, key: &str, body: &serde_json::Value, accept: &str) -> Result<(String, Vec<u8>), AidcError> {
+  let resp = crate::rate_limit::send("openai", "tts", || {
+    client.post("https://api.openai.com/v1/audio/speech").bearer_auth(key).header("Accept", accept).json(body)
+  })
+  .await
+  .map_err(|e| AidcError::from_reqwest("openai", &e))?;
+
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let body_text = resp.text().await.unwrap_or_default();
+    return Err(AidcError::from_status("openai", status, &body_text));
+  }
+  crate::usage::record_tts(body);
+
+  let ct_hdr = resp
+    .headers()
+    .get(reqwest::header::CONTENT_TYPE)
+    .and_then(|v| v.to_str().ok())
+    .unwrap_or("")
+    .to_string();
+  let bytes = resp.bytes().await.map_err(|e| AidcError::from_reqwest("openai", &e))?;
+  Ok((ct_hdr, bytes.to_vec()))
+}
+
+/// Synthesize `text` (already through tts_normalize) into a temp file. Texts over the OpenAI input
+/// limit are split at sentence boundaries, synthesized piece by piece and joined into one WAV
+/// (whatever `format` was asked for, since MP3 and Opus pieces cannot be joined without re-encoding).
 pub async fn openai_synthesize_file(
   key: String,
   text: String,
@@ -316,16 +455,21 @@ pub async fn openai_synthesize_file(
   rate: Option<i32>,
   volume: Option<u8>,
   instructions: Option<String>,
-) -> Result<String, String> {
-  let text = text.trim().to_string();
-  if text.is_empty() {
-    return Err("Text is empty".into());
+) -> Result<String, AidcError> {
+  if text.trim().is_empty() {
+    return Err(AidcError::InvalidInput("Text is empty".into()));
   }
-  if text.len() > OPENAI_TTS_MAX_INPUT_CHARS {
-    return Err(format!("Text exceeds OpenAI TTS limit of {} characters", OPENAI_TTS_MAX_INPUT_CHARS));
+  let parts = crate::tts_utils::split_for_tts(&text, OPENAI_TTS_MAX_INPUT_CHARS);
+  if parts.len() > OPENAI_TTS_MAX_PARTS {
+    return Err(AidcError::InvalidInput(format!(
+      "Text is too long for OpenAI TTS ({} parts of up to {} characters; the limit is {})",
+      parts.len(),
+      OPENAI_TTS_MAX_INPUT_CHARS,
+      OPENAI_TTS_MAX_PARTS
+    )));
   }
 
-  let fmt_in = format.unwrap_or_else(|| "wav".to_string());
+  let fmt_in = if parts.len() > 1 { "wav".to_string() } else { format.unwrap_or_else(|| "wav".to_string()) };
   let (accept, body_format) = match fmt_in.as_str() {
     "mp3" => ("audio/mpeg", "mp3"),
     "opus" => ("audio/ogg", "opus"),
@@ -333,10 +477,9 @@ pub async fn openai_synthesize_file(
   };
   let m = model.unwrap_or_else(|| "gpt-4o-mini-tts".to_string());
   let v = voice.unwrap_or_else(|| "alloy".to_string());
-  let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(120)).connect_timeout(std::time::Duration::from_secs(10)).build().unwrap_or_else(|_| reqwest::Client::new());
+  let client = crate::timeouts::client(crate::timeouts::TTS);
   let mut body_obj = serde_json::Map::new();
   body_obj.insert("model".to_string(), serde_json::Value::String(m));
-  body_obj.insert("input".to_string(), serde_json::Value::String(text));
   body_obj.insert("voice".to_string(), serde_json::Value::String(v));
   body_obj.insert("response_format".to_string(), serde_json::Value::String(body_format.to_string()));
   if let Some(instr) = instructions {
@@ -344,29 +487,18 @@ pub async fn openai_synthesize_file(
       body_obj.insert("instructions".to_string(), serde_json::Value::String(instr));
     }
   }
-  let body = serde_json::Value::Object(body_obj);
-
-  let resp = client
-    .post("https://api.openai.com/v1/audio/speech")
-    .bearer_auth(&key)
-    .header("Accept", accept)
-    .json(&body)
-    .send()
-    .await
-    .map_err(|e| format!("request failed: {e}"))?;
 
-  if !resp.status().is_success() {
-    let status = resp.status();
-    let body_text = resp.text().await.unwrap_or_default();
-    return Err(format!("OpenAI error: {status} {body_text}"));
+  // Pieces are requested in order so a failure stops before spending on the rest
+  let mut clips: Vec<Vec<u8>> = Vec::with_capacity(parts.len());
+  let mut ct_hdr = String::new();
+  for part in parts {
+    body_obj.insert("input".to_string(), serde_json::Value::String(part));
+    let (ct, bytes) = request_speech(&client, &key, &serde_json::Value::Object(body_obj.clone()), accept).await?;
+    ct_hdr = ct;
+    clips.push(bytes);
   }
 
-  let ct_hdr = resp
-    .headers()
-    .get(reqwest::header::CONTENT_TYPE)
-    .and_then(|v| v.to_str().ok())
-    .unwrap_or("");
-  let ext = if ct_hdr.contains("wav") {
+  let ext = if clips.len() > 1 || ct_hdr.contains("wav") {
     "wav"
   } else if ct_hdr.contains("mpeg") || ct_hdr.contains("mp3") {
     "mp3"
@@ -382,25 +514,26 @@ pub async fn openai_synthesize_file(
     "wav"
   };
 
-  let file_name = format!("aidc_tts_{}_openai.{}", chrono::Local::now().format("%Y%m%d_%H%M%S"), ext);
-  let mut path = std::env::temp_dir(); path.push(file_name); let target = path.to_string_lossy().to_string();
-  let bytes_to_write = resp.bytes().await.map_err(|e| format!("bytes error: {e}"))?;
+  let target = crate::temp_files::tts_path("openai", ext);
 
-  let write_result = if ext == "wav" {
-    let r = rate.unwrap_or(0).clamp(-10, 10);
-    let vol = volume.unwrap_or(100).min(100);
-    write_pcm16_wav_from_any(&bytes_to_write, &target, r, vol)
+  let r = rate.unwrap_or(0).clamp(-10, 10);
+  let vol = volume.unwrap_or(100).min(100);
+  let write_result = if clips.len() > 1 {
+    crate::tts_utils::join_pcm16_wav(&clips, &target, r, vol)
+  } else if ext == "wav" {
+    write_pcm16_wav_from_any(&clips[0], &target, r, vol)
   } else {
-    std::fs::write(&target, &bytes_to_write).map_err(|e| format!("write failed: {e}"))
+    std::fs::write(&target, &clips[0]).map_err(|e| format!("write failed: {e}"))
   };
   if let Err(e) = write_result {
     let _ = std::fs::remove_file(&target);
-    return Err(e);
+    return Err(e.into());
   }
+  crate::temp_files::register(crate::temp_files::TTS, &target);
   Ok(target)
 }
 
-pub async fn openai_synthesize_wav(key: String, text: String, voice: Option<String>, model: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, String> {
+pub async fn openai_synthesize_wav(key: String, text: String, voice: Option<String>, model: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, AidcError> {
   openai_synthesize_file(key, text, voice, model, Some("wav".to_string()), rate, volume, None).await
 }
 
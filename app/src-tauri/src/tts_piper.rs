@@ -0,0 +1,252 @@
+// Piper neural TTS (`tts_engine: "piper"`), fully offline once downloaded. Voices are ONNX models
+// from the rhasspy/piper-voices catalog, named "<locale>-<name>-<quality>" (en_US-lessac-medium),
+// and are run by the Piper command-line release, which bundles onnxruntime and the espeak-ng
+// phonemizer the models need. Both come from the download manager on first use, like the Parakeet
+// models: models/piper/bin and models/piper/voices/<voice> under the app's model folder.
+// `tts_piper_voice` picks the voice.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+pub const DEFAULT_VOICE: &str = "en_US-lessac-medium";
+const VOICES_BASE_URL: &str = "https://huggingface.co/rhasspy/piper-voices/resolve/main";
+const RELEASE_BASE_URL: &str = "https://github.com/rhasspy/piper/releases/download/2023.11.14-2";
+// Voices (~60 MB for medium quality) and release archives; anything smaller is an error page
+const MIN_DOWNLOAD_BYTES: u64 = 1_000_000;
+
+#[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+const RELEASE_ASSET: Option<&str> = Some("piper_windows_amd64.zip");
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const RELEASE_ASSET: Option<&str> = Some("piper_linux_x86_64.tar.gz");
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+const RELEASE_ASSET: Option<&str> = Some("piper_linux_aarch64.tar.gz");
+#[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+const RELEASE_ASSET: Option<&str> = Some("piper_macos_x64.tar.gz");
+#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+const RELEASE_ASSET: Option<&str> = Some("piper_macos_aarch64.tar.gz");
+#[cfg(not(any(
+  all(target_os = "windows", target_arch = "x86_64"),
+  all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")),
+  all(target_os = "macos", any(target_arch = "x86_64", target_arch = "aarch64")),
+)))]
+const RELEASE_ASSET: Option<&str> = None;
+
+// SHA-256 of each release asset, checked before the archive is unpacked. The executable runs
+// unsandboxed, so an asset without a pin here is refused unless `model_checksums` supplies one.
+const RELEASE_SHA256: &[(&str, &str)] = &[];
+
+#[cfg(target_os = "windows")]
+const EXE_NAME: &str = "piper.exe";
+#[cfg(not(target_os = "windows"))]
+const EXE_NAME: &str = "piper";
+
+#[derive(Serialize)]
+pub struct PiperStatus {
+  pub voice: String,
+  /// A Piper release exists for this platform
+  pub available: bool,
+  pub binary_downloaded: bool,
+  pub voice_downloaded: bool,
+  pub dir: Option<String>,
+}
+
+fn models_dir() -> Option<PathBuf> {
+  #[cfg(target_os = "windows")]
+  {
+    if let Ok(appdata) = std::env::var("APPDATA") {
+      let mut p = PathBuf::from(appdata);
+      p.push("AiDesktopCompanion");
+      p.push("models");
+      p.push("piper");
+      return Some(p);
+    }
+    None
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    if let Ok(home) = std::env::var("HOME") {
+      let mut p = PathBuf::from(home);
+      p.push(".cache");
+      p.push("AiDesktopCompanion");
+      p.push("models");
+      p.push("piper");
+      return Some(p);
+    }
+    None
+  }
+}
+
+fn voice_or_default(voice: Option<String>) -> String {
+  voice.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).unwrap_or_else(|| DEFAULT_VOICE.to_string())
+}
+
+// Catalog path of a voice: en_US-lessac-medium -> en/en_US/lessac/medium/en_US-lessac-medium
+fn voice_catalog_path(voice: &str) -> Result<String, String> {
+  let invalid = || format!("Invalid Piper voice '{voice}' (expected e.g. {DEFAULT_VOICE})");
+  if !voice.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+    return Err(invalid());
+  }
+  let (locale, rest) = voice.split_once('-').ok_or_else(invalid)?;
+  let (name, quality) = rest.rsplit_once('-').ok_or_else(invalid)?;
+  let lang = locale.split('_').next().unwrap_or(locale);
+  if lang.is_empty() || name.is_empty() || quality.is_empty() {
+    return Err(invalid());
+  }
+  Ok(format!("{lang}/{locale}/{name}/{quality}/{voice}"))
+}
+
+fn voice_files(voice: &str) -> Option<(PathBuf, PathBuf)> {
+  let dir = models_dir()?.join("voices").join(voice);
+  Some((dir.join(format!("{voice}.onnx")), dir.join(format!("{voice}.onnx.json"))))
+}
+
+// The release archives unpack into a `piper` folder
+fn binary_path() -> Option<PathBuf> {
+  models_dir().map(|d| d.join("bin").join("piper").join(EXE_NAME))
+}
+
+fn voice_downloaded(voice: &str) -> bool {
+  voice_files(voice).is_some_and(|(model, config)| model.is_file() && config.is_file())
+}
+
+// Windows 10+ ships bsdtar, which also reads zip files
+async fn extract_archive(archive: &Path, dest: &Path) -> Result<(), String> {
+  let mut cmd = tokio::process::Command::new("tar");
+  cmd.arg("-xf").arg(archive).arg("-C").arg(dest);
+  #[cfg(target_os = "windows")]
+  cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+  let out = cmd.output().await.map_err(|e| format!("tar failed to start: {e}"))?;
+  if !out.status.success() {
+    return Err(format!("extract failed: {}", String::from_utf8_lossy(&out.stderr).trim()));
+  }
+  Ok(())
+}
+
+async fn ensure_binary(job: Option<&crate::jobs::JobReporter>) -> Result<PathBuf, String> {
+  let asset = RELEASE_ASSET.ok_or_else(|| "Piper is not available for this platform".to_string())?;
+  let exe = binary_path().ok_or_else(|| "Unsupported platform for model path".to_string())?;
+  if exe.is_file() {
+    return Ok(exe);
+  }
+  let bin_dir = exe.parent().and_then(|p| p.parent()).map(Path::to_path_buf).ok_or_else(|| "Unsupported platform for model path".to_string())?;
+  std::fs::create_dir_all(&bin_dir).map_err(|e| format!("create model dir failed: {e}"))?;
+  let archive = bin_dir.join(asset);
+  let url = format!("{RELEASE_BASE_URL}/{asset}");
+  let sha256 = RELEASE_SHA256
+    .iter()
+    .find(|(name, _)| *name == asset)
+    .map(|(_, hex)| hex.to_string())
+    .or_else(|| crate::config::get_model_checksum_from_settings(&url, asset))
+    .ok_or_else(|| format!("No checksum is pinned for {asset}; add its SHA-256 to model_checksums in settings"))?;
+  let spec = crate::downloads::DownloadSpec::new(&url, archive.clone()).sha256(sha256).min_size(MIN_DOWNLOAD_BYTES);
+  crate::downloads::download(job, spec).await?;
+  extract_archive(&archive, &bin_dir).await?;
+  let _ = std::fs::remove_file(&archive);
+  if !exe.is_file() {
+    return Err("Piper download finished but the piper executable was not found.".into());
+  }
+  Ok(exe)
+}
+
+async fn ensure_voice(job: Option<&crate::jobs::JobReporter>, voice: &str) -> Result<PathBuf, String> {
+  let catalog = voice_catalog_path(voice)?;
+  let (model, config) = voice_files(voice).ok_or_else(|| "Unsupported platform for model path".to_string())?;
+  if let Some(dir) = model.parent() {
+    std::fs::create_dir_all(dir).map_err(|e| format!("create model dir failed: {e}"))?;
+  }
+  if !model.is_file() {
+    let url = format!("{VOICES_BASE_URL}/{catalog}.onnx?download=true");
+    crate::downloads::download(job, crate::downloads::DownloadSpec::new(&url, model.clone()).min_size(MIN_DOWNLOAD_BYTES)).await?;
+  }
+  if !config.is_file() {
+    let url = format!("{VOICES_BASE_URL}/{catalog}.onnx.json?download=true");
+    crate::downloads::download(job, crate::downloads::DownloadSpec::new(&url, config.clone())).await?;
+  }
+  Ok(model)
+}
+
+pub async fn prefetch_with_progress(job: crate::jobs::JobReporter, voice: Option<String>) -> Result<String, String> {
+  let voice = voice_or_default(voice);
+  ensure_binary(Some(&job)).await?;
+  let model = ensure_voice(Some(&job), &voice).await?;
+  Ok(model.to_string_lossy().to_string())
+}
+
+pub fn status(voice: Option<String>) -> PiperStatus {
+  let voice = voice_or_default(voice);
+  PiperStatus {
+    available: RELEASE_ASSET.is_some(),
+    binary_downloaded: binary_path().is_some_and(|p| p.is_file()),
+    voice_downloaded: voice_downloaded(&voice),
+    dir: models_dir().map(|p| p.to_string_lossy().to_string()),
+    voice,
+  }
+}
+
+/// Synthesize `text` (already through tts_normalize) into a temporary WAV, downloading Piper and
+/// the voice first if needed. `rate` (-10..10) sets Piper's length scale, so faster speech keeps
+/// its pitch.
+pub async fn synthesize_wav(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, String> {
+  if text.trim().is_empty() {
+    return Err("Text is empty".into());
+  }
+  let voice = voice_or_default(voice);
+  let exe = ensure_binary(None).await?;
+  let model = ensure_voice(None, &voice).await?;
+
+  let raw = crate::temp_files::tts_path("piper_raw", "wav");
+  let target = crate::temp_files::tts_path("piper", "wav");
+  let length_scale = 2f32.powf(-(rate.unwrap_or(0).clamp(-10, 10) as f32) / 10.0);
+
+  let mut cmd = tokio::process::Command::new(&exe);
+  cmd.arg("--model").arg(&model).arg("--output_file").arg(&raw).arg("--length_scale").arg(format!("{length_scale:.3}"));
+  cmd.stdin(std::process::Stdio::piped()).stdout(std::process::Stdio::null()).stderr(std::process::Stdio::piped()).kill_on_drop(true);
+  #[cfg(target_os = "windows")]
+  cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+  let mut child = cmd.spawn().map_err(|e| format!("piper failed to start: {e}"))?;
+  // Piper reads one utterance per line; espeak-ng splits the sentences within it
+  let line = text.split_whitespace().collect::<Vec<_>>().join(" ") + "\n";
+  if let Some(mut stdin) = child.stdin.take() {
+    stdin.write_all(line.as_bytes()).await.map_err(|e| format!("piper input failed: {e}"))?;
+  }
+  let out = child.wait_with_output().await.map_err(|e| format!("piper failed: {e}"))?;
+  if !out.status.success() {
+    let _ = std::fs::remove_file(&raw);
+    return Err(format!("piper failed: {}", String::from_utf8_lossy(&out.stderr).trim()));
+  }
+
+  let bytes = std::fs::read(&raw).map_err(|e| format!("read piper output failed: {e}"));
+  let _ = std::fs::remove_file(&raw);
+  // Rate is already in the length scale; only the volume is applied here
+  if let Err(e) = crate::tts_utils::write_pcm16_wav_from_any(&bytes?, &target, 0, volume.unwrap_or(100).min(100)) {
+    let _ = std::fs::remove_file(&target);
+    return Err(e);
+  }
+  crate::temp_files::register(crate::temp_files::TTS, &target);
+  Ok(target)
+}
+
+/// Download Piper and a voice (default: `tts_piper_voice`) as a "download" job.
+#[tauri::command]
+pub async fn tts_piper_prefetch(app: tauri::AppHandle, voice: Option<String>, task_id: Option<String>) -> Result<String, String> {
+  let voice = voice_or_default(voice.or_else(crate::config::get_tts_piper_voice_from_settings));
+  let label = format!("Piper voice download ({voice})");
+  crate::jobs::run(&app, "download", label, task_id, |job| async move {
+    job.wait_for_network().await?;
+    prefetch_with_progress(job, Some(voice)).await
+  })
+  .await
+}
+
+#[tauri::command]
+pub fn tts_piper_status(voice: Option<String>) -> Result<PiperStatus, String> {
+  Ok(status(voice.or_else(crate::config::get_tts_piper_voice_from_settings)))
+}
+
+/// Synthesize with Piper and return the temp WAV path.
+#[tauri::command]
+pub async fn tts_piper_synthesize_file(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, String> {
+  synthesize_wav(crate::tts_normalize::normalize(&text), voice.or_else(crate::config::get_tts_piper_voice_from_settings), rate, volume).await
+}
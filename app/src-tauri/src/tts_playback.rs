@@ -0,0 +1,175 @@
+// Native playback of synthesized speech for the TTS panel and read selection aloud, replacing the
+// PowerShell SoundPlayer (slow to start, PCM WAV only). Files are decoded in-process (WAV of any
+// sample format, MP3, Ogg/Opus) and played on the default output device through audio_io, so the
+// live rate/volume commands apply as well. One clip plays at a time; starting another stops it.
+//
+// Events (payloads in events.rs):
+//   tts:playback:start     TtsPlaybackStart     clip decoded and playing
+//   tts:playback:progress  TtsPlaybackProgress  position about 4 times a second, and on pause/seek
+//   tts:playback:end       TtsPlaybackEnd       clip finished, was stopped or failed
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use tauri::AppHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::audio_io::PlaybackControl;
+use crate::events;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static CURRENT: Lazy<Mutex<Option<Active>>> = Lazy::new(|| Mutex::new(None));
+
+struct Active {
+  id: u64,
+  rate: u32,
+  total: usize,
+  cancel: CancellationToken,
+  control: Arc<PlaybackControl>,
+}
+
+fn ms(samples: usize, rate: u32) -> u64 {
+  samples as u64 * 1000 / rate.max(1) as u64
+}
+
+impl Active {
+  fn progress(&self) -> events::TtsPlaybackProgress {
+    events::TtsPlaybackProgress {
+      id: self.id,
+      position_ms: ms(self.control.position().min(self.total), self.rate),
+      duration_ms: ms(self.total, self.rate),
+      paused: self.control.is_paused(),
+    }
+  }
+}
+
+fn current() -> std::sync::MutexGuard<'static, Option<Active>> {
+  CURRENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start playing `path` (stopping whatever plays) and return its playback id. `stretch` is a
+/// -10..10 rate step applied by time-stretching the clip once decoded (0 for clips synthesized
+/// with their rate); `control` carries the starting volume. The returned receiver resolves when
+/// the clip ends (false), is stopped (true) or fails.
+pub async fn start(
+  app: &AppHandle,
+  path: &str,
+  stretch: i32,
+  control: PlaybackControl,
+) -> Result<(u64, tokio::sync::oneshot::Receiver<Result<bool, String>>), String> {
+  let p = path.to_string();
+  let (samples, rate) = tokio::task::spawn_blocking(move || {
+    let (samples, rate) = crate::audio_io::read_audio_mono(Path::new(&p))?;
+    let samples = if stretch == 0 { samples } else { crate::tts_utils::time_stretch(&samples, rate, 1, crate::tts_utils::rate_factor(stretch)) };
+    Ok::<_, String>((samples, rate))
+  })
+  .await
+  .map_err(|e| format!("audio task failed: {e}"))??;
+  if samples.is_empty() {
+    return Err("Audio file has no samples".into());
+  }
+  stop();
+  let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+  let cancel = CancellationToken::new();
+  let control = Arc::new(control);
+  *current() = Some(Active { id, rate, total: samples.len(), cancel: cancel.clone(), control: control.clone() });
+  events::emit(app, events::TTS_PLAYBACK_START, &events::TtsPlaybackStart { id, path: path.to_string(), duration_ms: ms(samples.len(), rate) });
+
+  let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let ticker_cancel = cancel.child_token();
+    let ticker_app = app.clone();
+    let ticker_stop = ticker_cancel.clone();
+    tauri::async_runtime::spawn(async move {
+      loop {
+        tokio::select! {
+          _ = ticker_stop.cancelled() => break,
+          _ = tokio::time::sleep(PROGRESS_INTERVAL) => {}
+        }
+        let progress = current().as_ref().filter(|a| a.id == id).map(Active::progress);
+        match progress {
+          Some(p) => events::emit(&ticker_app, events::TTS_PLAYBACK_PROGRESS, &p),
+          None => break,
+        }
+      }
+    });
+    let res = crate::audio_io::play_samples(&samples, rate, &cancel, &control).await;
+    ticker_cancel.cancel();
+    let stopped = cancel.is_cancelled();
+    {
+      let mut cur = current();
+      if cur.as_ref().is_some_and(|a| a.id == id) {
+        *cur = None;
+      }
+    }
+    events::emit(&app, events::TTS_PLAYBACK_END, &events::TtsPlaybackEnd { id, stopped, error: res.as_ref().err().cloned() });
+    let _ = done_tx.send(res.map(|()| stopped));
+  });
+  Ok((id, done_rx))
+}
+
+/// Stop the current clip; returns whether one was playing.
+pub fn stop() -> bool {
+  match current().take() {
+    Some(a) => {
+      a.cancel.cancel();
+      true
+    }
+    None => false,
+  }
+}
+
+// Apply `f` to the current clip and report its new position
+fn with_current(app: &AppHandle, f: impl FnOnce(&Active)) -> Result<events::TtsPlaybackProgress, String> {
+  let cur = current();
+  let active = cur.as_ref().ok_or_else(|| "Nothing is playing".to_string())?;
+  f(active);
+  let progress = active.progress();
+  drop(cur);
+  events::emit(app, events::TTS_PLAYBACK_PROGRESS, &progress);
+  Ok(progress)
+}
+
+/// Play an audio file (WAV, MP3, Ogg) natively; returns the playback id used in `tts:playback:*`.
+/// `rate` and `volume` are for files synthesized without them (MP3/Opus from OpenAI).
+#[tauri::command]
+pub async fn tts_playback_play(app: AppHandle, path: String, rate: Option<i32>, volume: Option<u8>) -> Result<u64, String> {
+  let control = PlaybackControl::with_volume(volume.map(i32::from).unwrap_or(100));
+  start(&app, &path, rate.unwrap_or(0).clamp(-10, 10), control).await.map(|(id, _)| id)
+}
+
+#[tauri::command]
+pub fn tts_playback_pause(app: AppHandle) -> Result<events::TtsPlaybackProgress, String> {
+  with_current(&app, |a| a.control.set_paused(true))
+}
+
+#[tauri::command]
+pub fn tts_playback_resume(app: AppHandle) -> Result<events::TtsPlaybackProgress, String> {
+  with_current(&app, |a| a.control.set_paused(false))
+}
+
+#[tauri::command]
+pub fn tts_playback_stop() -> Result<bool, String> {
+  Ok(stop())
+}
+
+/// Jump to `position_ms` in the current clip (clamped to its length).
+#[tauri::command]
+pub fn tts_playback_seek(app: AppHandle, position_ms: u64) -> Result<events::TtsPlaybackProgress, String> {
+  with_current(&app, |a| {
+    let sample = (position_ms.saturating_mul(a.rate as u64) / 1000).min(a.total as u64) as usize;
+    a.control.seek(sample);
+  })
+}
+
+/// Position of the current clip, or None when nothing plays.
+#[tauri::command]
+pub fn tts_playback_state() -> Result<Option<events::TtsPlaybackProgress>, String> {
+  Ok(current().as_ref().map(Active::progress))
+}
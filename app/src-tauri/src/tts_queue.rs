@@ -0,0 +1,358 @@
+// Sentence-chunked reading of long texts (read selection aloud). Instead of one giant request the
+// text is normalized once, cut into chunks of whole sentences (paragraph ends are preferred cut
+// points) and each chunk is synthesized with the configured engine and played through
+// tts_playback. While a chunk plays the next one is synthesized, so parts follow without a gap.
+// The first chunk is kept short so speech starts quickly. Skipping back replays a chunk that was
+// already synthesized; all chunk files are deleted when the queue ends. One queue runs at a time.
+//
+// Events (payloads in events.rs):
+//   tts:progress  TtsProgress  chunk being synthesized or played, then how the queue ended
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use tauri::AppHandle;
+use tokio::sync::{oneshot, Notify};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::events;
+
+const FIRST_CHUNK_CHARS: usize = 300;
+const CHUNK_CHARS: usize = 1000;
+
+const STATE_SYNTHESIZING: &str = "synthesizing";
+const STATE_PLAYING: &str = "playing";
+const STATE_FINISHED: &str = "finished";
+const STATE_STOPPED: &str = "stopped";
+const STATE_ERROR: &str = "error";
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static CURRENT: Lazy<Mutex<Option<Queue>>> = Lazy::new(|| Mutex::new(None));
+
+struct Queue {
+  id: u64,
+  index: usize,
+  total: usize,
+  state: &'static str,
+  text: String,
+  /// Chunk to continue with, set by next/previous
+  jump: Option<usize>,
+  skip: Arc<Notify>,
+  cancel: CancellationToken,
+}
+
+impl Queue {
+  fn progress(&self) -> events::TtsProgress {
+    events::TtsProgress { id: self.id, state: self.state.to_string(), index: self.index, total: self.total, text: Some(self.text.clone()), error: None }
+  }
+}
+
+fn current() -> std::sync::MutexGuard<'static, Option<Queue>> {
+  CURRENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Whole sentences grouped into chunks; sentences end after . ! ? or at a line break
+fn chunk_text(text: &str) -> Vec<String> {
+  fn push(chunks: &mut Vec<String>, piece: &str) {
+    let piece = piece.trim();
+    if piece.is_empty() {
+      return;
+    }
+    let limit = if chunks.is_empty() { FIRST_CHUNK_CHARS } else { CHUNK_CHARS };
+    // A single sentence longer than a chunk is cut at whitespace
+    chunks.extend(crate::tts_utils::split_for_tts(piece, limit));
+  }
+  let limit = |chunks: &Vec<String>| if chunks.is_empty() { FIRST_CHUNK_CHARS } else { CHUNK_CHARS };
+
+  let mut chunks: Vec<String> = Vec::new();
+  let mut start = 0;
+  let mut last_end = 0;
+  let mut prev: Option<char> = None;
+  for (i, c) in text.char_indices() {
+    let boundary = c.is_whitespace() && (c == '\n' || matches!(prev, Some('.' | '!' | '?')));
+    prev = Some(c);
+    if !boundary {
+      continue;
+    }
+    if text[start..i].trim().chars().count() > limit(&chunks) && last_end > start {
+      push(&mut chunks, &text[start..last_end]);
+      start = last_end;
+    }
+    // A paragraph end closes a chunk that is already reasonably long
+    let paragraph_end = c == '\n' && text[i + 1..].starts_with('\n');
+    if paragraph_end && text[start..i].trim().chars().count() >= limit(&chunks) / 2 {
+      push(&mut chunks, &text[start..i]);
+      start = i;
+    }
+    last_end = i;
+  }
+  if text[start..].trim().chars().count() > limit(&chunks) && last_end > start {
+    push(&mut chunks, &text[start..last_end]);
+    start = last_end;
+  }
+  push(&mut chunks, &text[start..]);
+  chunks
+}
+
+fn emit_progress(app: &AppHandle, id: u64, state: &'static str, index: usize, chunks: &[String]) {
+  let progress = {
+    let mut cur = current();
+    let Some(q) = cur.as_mut().filter(|q| q.id == id) else { return };
+    q.index = index;
+    q.state = state;
+    q.text = chunks[index].clone();
+    q.progress()
+  };
+  events::emit(app, events::TTS_PROGRESS, &progress);
+}
+
+fn take_jump(id: u64) -> Option<usize> {
+  current().as_mut().filter(|q| q.id == id).and_then(|q| q.jump.take())
+}
+
+fn has_jump(id: u64) -> bool {
+  current().as_ref().is_some_and(|q| q.id == id && q.jump.is_some())
+}
+
+fn spawn_synthesis(engine: &str, text: &str) -> JoinHandle<Result<String, String>> {
+  let engine = engine.to_string();
+  let text = text.to_string();
+  tokio::spawn(async move { crate::voices::synthesize_normalized_wav(text, crate::voices::settings_params(&engine)).await })
+}
+
+// Drop a synthesis that is no longer wanted, keeping its file if it already finished
+async fn settle(pending: &mut Option<(usize, JoinHandle<Result<String, String>>)>, files: &mut [Option<String>]) {
+  let Some((i, handle)) = pending.take() else { return };
+  if handle.is_finished() {
+    if let Ok(Ok(path)) = handle.await {
+      files[i] = Some(path);
+    }
+  } else {
+    handle.abort();
+  }
+}
+
+enum Step<T> {
+  Jump,
+  Cancelled,
+  Done(T),
+}
+
+// Wait for `fut` unless the queue is stopped or told to skip. Wake-ups without a pending jump
+// (a permit left from a skip that was already handled) are ignored.
+async fn wait<F: std::future::Future + Unpin>(id: u64, fut: &mut F, skip: &Notify, cancel: &CancellationToken) -> Step<F::Output> {
+  loop {
+    tokio::select! {
+      _ = cancel.cancelled() => return Step::Cancelled,
+      _ = skip.notified() => if has_jump(id) { return Step::Jump },
+      r = &mut *fut => return Step::Done(r),
+    }
+  }
+}
+
+// Synthesize and play the chunks in order; returns whether the queue was stopped
+async fn run(app: &AppHandle, id: u64, engine: &str, chunks: &[String], skip: &Notify, cancel: &CancellationToken) -> Result<bool, String> {
+  let mut files: Vec<Option<String>> = vec![None; chunks.len()];
+  let mut pending: Option<(usize, JoinHandle<Result<String, String>>)> = None;
+  let mut index = 0;
+  let result = loop {
+    if index >= chunks.len() {
+      break Ok(false);
+    }
+    let path = match files[index].clone() {
+      Some(p) => p,
+      None => {
+        emit_progress(app, id, STATE_SYNTHESIZING, index, chunks);
+        let mut handle = match pending.take() {
+          Some((i, h)) if i == index => h,
+          other => {
+            pending = other;
+            settle(&mut pending, &mut files).await;
+            spawn_synthesis(engine, &chunks[index])
+          }
+        };
+        match wait(id, &mut handle, skip, cancel).await {
+          Step::Cancelled => {
+            handle.abort();
+            break Ok(true);
+          }
+          Step::Jump => {
+            // Left running in case the jump lands on this chunk again
+            pending = Some((index, handle));
+            index = take_jump(id).unwrap_or(index);
+            continue;
+          }
+          Step::Done(Ok(Ok(p))) => {
+            files[index] = Some(p.clone());
+            p
+          }
+          Step::Done(Ok(Err(e))) => break Err(e),
+          Step::Done(Err(e)) => break Err(format!("synthesis task failed: {e}")),
+        }
+      }
+    };
+    if let Some(j) = take_jump(id) {
+      index = j;
+      continue;
+    }
+
+    // Synthesize the next chunk while this one plays
+    let next = index + 1;
+    if next < chunks.len() && files[next].is_none() && pending.as_ref().map(|(i, _)| *i) != Some(next) {
+      settle(&mut pending, &mut files).await;
+      pending = Some((next, spawn_synthesis(engine, &chunks[next])));
+    }
+
+    emit_progress(app, id, STATE_PLAYING, index, chunks);
+    let (_, mut done) = match crate::tts_playback::start(app, &path, 0, crate::audio_io::PlaybackControl::default()).await {
+      Ok(v) => v,
+      Err(e) => break Err(e),
+    };
+    match wait(id, &mut done, skip, cancel).await {
+      Step::Cancelled => {
+        crate::tts_playback::stop();
+        break Ok(true);
+      }
+      Step::Jump => {
+        crate::tts_playback::stop();
+        index = take_jump(id).unwrap_or(index);
+      }
+      Step::Done(Ok(Err(e))) => break Err(e),
+      Step::Done(res) => {
+        let stopped = res.unwrap_or(Ok(true)).unwrap_or(true);
+        match take_jump(id) {
+          Some(j) => index = j,
+          // Stopped from elsewhere (Stop in the panel, another clip started)
+          None if stopped => break Ok(true),
+          None => index += 1,
+        }
+      }
+    }
+  };
+
+  settle(&mut pending, &mut files).await;
+  for path in files.into_iter().flatten() {
+    let _ = std::fs::remove_file(path);
+  }
+  result
+}
+
+/// Read `text` aloud in chunks with the configured engine, stopping any running queue. Returns the
+/// queue id and a receiver that resolves when the queue finished (false), was stopped (true) or
+/// failed.
+pub fn start(app: &AppHandle, text: &str) -> Result<(u64, oneshot::Receiver<Result<bool, String>>), String> {
+  if crate::audio_io::is_muted() {
+    return Err(crate::audio_io::MUTED_ERROR.into());
+  }
+  let chunks = chunk_text(&crate::tts_normalize::normalize(text));
+  if chunks.is_empty() {
+    return Err("Text is empty".into());
+  }
+  stop();
+  let engine = crate::config::load_settings_json().get("tts_engine").and_then(|x| x.as_str()).unwrap_or("local").to_string();
+  let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+  let skip = Arc::new(Notify::new());
+  let cancel = CancellationToken::new();
+  *current() = Some(Queue {
+    id,
+    index: 0,
+    total: chunks.len(),
+    state: STATE_SYNTHESIZING,
+    text: chunks[0].clone(),
+    jump: None,
+    skip: skip.clone(),
+    cancel: cancel.clone(),
+  });
+  tracing::info!(id, engine = %engine, chunks = chunks.len(), "tts queue started");
+
+  let (done_tx, done_rx) = oneshot::channel();
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let res = run(&app, id, &engine, &chunks, &skip, &cancel).await;
+    let (index, text) = {
+      let mut cur = current();
+      let last = cur.as_ref().filter(|q| q.id == id).map(|q| (q.index, q.text.clone()));
+      if last.is_some() {
+        *cur = None;
+      }
+      last.unwrap_or((0, String::new()))
+    };
+    let state = match &res {
+      Ok(false) => STATE_FINISHED,
+      Ok(true) => STATE_STOPPED,
+      Err(_) => STATE_ERROR,
+    };
+    if let Err(e) = &res {
+      tracing::warn!(id, error = %e, "tts queue failed");
+    }
+    events::emit(&app, events::TTS_PROGRESS, &events::TtsProgress {
+      id,
+      state: state.to_string(),
+      index,
+      total: chunks.len(),
+      text: Some(text).filter(|t| !t.is_empty()),
+      error: res.as_ref().err().cloned(),
+    });
+    let _ = done_tx.send(res);
+  });
+  Ok((id, done_rx))
+}
+
+/// Read `text` aloud in chunks and wait until the queue ends (or was stopped).
+pub async fn speak(app: &AppHandle, text: &str) -> Result<(), String> {
+  let (_, done) = start(app, text)?;
+  done.await.unwrap_or(Ok(true)).map(|_| ())
+}
+
+/// Stop the running queue; returns whether one was running.
+pub fn stop() -> bool {
+  match current().take() {
+    Some(q) => {
+      q.cancel.cancel();
+      true
+    }
+    None => false,
+  }
+}
+
+// Move `delta` chunks from the current one; past the last chunk the queue finishes
+fn skip_by(delta: isize) -> Result<bool, String> {
+  let mut cur = current();
+  let q = cur.as_mut().ok_or_else(|| "Nothing is being read".to_string())?;
+  let from = q.jump.unwrap_or(q.index) as isize;
+  let target = (from + delta).clamp(0, q.total as isize) as usize;
+  q.jump = Some(target);
+  q.skip.notify_one();
+  Ok(target < q.total)
+}
+
+/// Read `text` in chunks with the configured engine; returns the queue id used in `tts:progress`.
+#[tauri::command]
+pub fn tts_queue_start(app: AppHandle, text: String) -> Result<u64, String> {
+  start(&app, &text).map(|(id, _)| id)
+}
+
+/// Skip to the next chunk; returns false when that ends the queue.
+#[tauri::command]
+pub fn tts_queue_next() -> Result<bool, String> {
+  skip_by(1)
+}
+
+/// Go back one chunk (or restart the first).
+#[tauri::command]
+pub fn tts_queue_previous() -> Result<bool, String> {
+  skip_by(-1)
+}
+
+#[tauri::command]
+pub fn tts_queue_stop() -> Result<bool, String> {
+  Ok(stop())
+}
+
+/// Chunk and state of the running queue, or None.
+#[tauri::command]
+pub fn tts_queue_state() -> Result<Option<events::TtsProgress>, String> {
+  Ok(current().as_ref().map(Queue::progress))
+}
@@ -9,6 +9,42 @@ use serde_json;
 use uuid::Uuid;
 use futures_util::StreamExt;
 use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+
+const SPEECH_URL: &str = "https://api.openai.com/v1/audio/speech";
+// Idle pooled connections older than this are dropped; a prewarm within it is skipped
+const WARM_TTL: Duration = Duration::from_secs(240);
+
+// One client for all sessions so the TLS connection to the speech endpoint is reused
+// instead of being set up again for every playback.
+static SPEECH_CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .pool_idle_timeout(WARM_TTL)
+        .tcp_keepalive(Duration::from_secs(30))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+});
+static LAST_WARM: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+/// Open (or refresh) the pooled connection to the speech endpoint with a cheap HEAD request,
+/// so the first playback does not pay for DNS, TCP and TLS setup. Any HTTP status counts as warm.
+pub async fn warm_speech_connection() -> Result<(), String> {
+    {
+        let guard = LAST_WARM.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_some_and(|t| t.elapsed() < WARM_TTL / 2) {
+            return Ok(());
+        }
+    }
+    SPEECH_CLIENT
+        .head(SPEECH_URL)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| format!("warm-up request failed: {}", e))?;
+    *LAST_WARM.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+    Ok(())
+}
 
 #[derive(Clone)]
 pub struct StreamingSession {
@@ -20,6 +56,8 @@ pub struct StreamingSession {
     pub instructions: Option<String>,
     pub cancel: Arc<AtomicBool>,
     pub created_at: Instant,
+    /// Unix time in milliseconds, for the QA session list
+    pub created_unix_ms: i64,
     pub started: Arc<AtomicBool>,
 }
 
@@ -110,6 +148,7 @@ impl TtsStreamingServer {
             instructions,
             cancel: Arc::new(AtomicBool::new(false)),
             created_at: Instant::now(),
+            created_unix_ms: chrono::Utc::now().timestamp_millis(),
             started: Arc::new(AtomicBool::new(false)),
         };
         
@@ -126,6 +165,17 @@ impl TtsStreamingServer {
         sessions.remove(session_id).is_some()
     }
     
+    /// Cancel and forget every session (app shutdown); running streams end at their next chunk.
+    pub fn stop_all(&self) -> usize {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        for sess in sessions.values() {
+            sess.cancel.store(true, Ordering::SeqCst);
+        }
+        let n = sessions.len();
+        sessions.clear();
+        n
+    }
+
     pub fn get_stream_url(&self, session_id: &str) -> String {
         format!("http://127.0.0.1:{}/tts-stream/{}", self.port, session_id)
     }
@@ -135,6 +185,27 @@ impl TtsStreamingServer {
         guard.len()
     }
 
+    /// Metadata of every open session, oldest first (QA panel).
+    pub fn list_sessions(&self) -> Vec<crate::tts_openai::StreamSessionInfo> {
+        let guard = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let mut list: Vec<_> = guard
+            .iter()
+            .map(|(id, s)| crate::tts_openai::StreamSessionInfo {
+                id: id.clone(),
+                created_at: s.created_unix_ms,
+                age_secs: s.created_at.elapsed().as_secs(),
+                started: s.started.load(Ordering::SeqCst),
+                text_chars: s.text.chars().count(),
+                preview: s.text.chars().take(60).collect(),
+                format: s.format.clone(),
+                voice: s.voice.clone(),
+                model: s.model.clone(),
+            })
+            .collect();
+        list.sort_by_key(|s| s.created_at);
+        list
+    }
+
     pub fn cleanup_idle(&self, ttl: Duration) -> usize {
         let mut removed = 0usize;
         let mut guard = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
@@ -198,12 +269,8 @@ async fn handle_tts_stream(
     // Mark started
     started_flag.store(true, Ordering::SeqCst);
     
-    // Create OpenAI request
-    let client = Client::builder()
-        .timeout(Duration::from_secs(120))
-        .connect_timeout(Duration::from_secs(10))
-        .build()
-        .unwrap_or_else(|_| Client::new());
+    // Create OpenAI request on the shared (possibly prewarmed) client
+    let client = &*SPEECH_CLIENT;
     // Build JSON body, omitting 'instructions' when not provided
     let mut body_obj = serde_json::Map::new();
     body_obj.insert("model".to_string(), serde_json::Value::String(session.model.clone()));
@@ -224,13 +291,15 @@ async fn handle_tts_stream(
         _ => "audio/mpeg",
     };
 
-    let openai_response = match client
-        .post("https://api.openai.com/v1/audio/speech")
-        .bearer_auth(&session.api_key)
-        .header("Accept", accept)
-        .json(&body)
-        .send()
-        .await
+    let openai_response = match crate::rate_limit::send("openai", "tts stream", || {
+        client
+            .post(SPEECH_URL)
+            .timeout(crate::timeouts::get(crate::timeouts::TTS))
+            .bearer_auth(&session.api_key)
+            .header("Accept", accept)
+            .json(&body)
+    })
+    .await
     {
         Ok(resp) => resp,
         Err(e) => {
@@ -249,6 +318,7 @@ async fn handle_tts_stream(
             .body(Body::from(format!("OpenAI error {}: {}", status, error_text)))
             .unwrap());
     }
+    crate::usage::record_tts(&body);
     
     // Determine content type based on format
     let content_type = match session.format.as_str() {
@@ -1,14 +1,8 @@
 use std::io::Cursor;
 use std::fs;
 use std::path::PathBuf;
-use std::time::{SystemTime, Duration};
+use std::time::Duration;
 
-use symphonia::core::audio::{AudioBufferRef, SampleBuffer};
-use symphonia::core::codecs::DecoderOptions;
-use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
-use symphonia::core::probe::Hint;
 
 // ---------------------------
 // Audio decoding and WAV processing helpers (generic)
@@ -20,92 +14,51 @@ pub fn write_pcm16_wav_from_any(bytes: &[u8], target_path: &str, rate: i32, volu
     return Ok(());
   }
 
-  // Fallback: generic decode using Symphonia
-  let mss = MediaSourceStream::new(Box::new(Cursor::new(bytes.to_vec())), Default::default());
-  let hint = Hint::new();
-  let probed = symphonia::default::get_probe()
-    .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
-    .map_err(|e| format!("audio probe failed: {e}"))?;
-
-  let mut format = probed.format;
-  let track = format.default_track().ok_or_else(|| "no default track".to_string())?;
-  let track_id = track.id;
-  let codec_params = track.codec_params.clone();
-  let mut decoder = symphonia::default::get_codecs()
-    .make(&codec_params, &DecoderOptions::default())
-    .map_err(|e| format!("decoder init failed: {e}"))?;
-
-  let mut out_rate: u32 = codec_params.sample_rate.unwrap_or(44100);
-  let mut out_channels: u16 = codec_params
-    .channels
-    .map(|c| c.count() as u16)
-    .unwrap_or(1);
-
-  let mut pcm: Vec<f32> = Vec::new();
+  // Fallback: generic decode (MP3, Ogg/Opus, WebM, ...)
+  let pcm = crate::audio_decode::decode(bytes, "")?;
+  write_pcm16_wav(&pcm.samples, pcm.sample_rate, pcm.channels as u16, target_path, rate, volume)
+}
 
-  loop {
-    let packet = match format.next_packet() { Ok(p) => p, Err(_) => break };
-    if packet.track_id() != track_id { continue; }
-    match decoder.decode(&packet) {
-      Ok(buf) => {
-        match buf {
-          AudioBufferRef::F32(b) => {
-            let spec = *b.spec();
-            out_rate = spec.rate;
-            out_channels = spec.channels.count() as u16;
-            let mut sbuf = SampleBuffer::<f32>::new(b.capacity() as u64, spec);
-            sbuf.copy_interleaved_ref(AudioBufferRef::F32(b));
-            pcm.extend_from_slice(sbuf.samples());
-          }
-          AudioBufferRef::S16(b) => {
-            let spec = *b.spec();
-            out_rate = spec.rate;
-            out_channels = spec.channels.count() as u16;
-            let mut sbuf = SampleBuffer::<i16>::new(b.capacity() as u64, spec);
-            sbuf.copy_interleaved_ref(AudioBufferRef::S16(b));
-            pcm.extend(sbuf.samples().iter().map(|v| *v as f32 / 32768.0));
-          }
-          AudioBufferRef::S32(b) => {
-            let spec = *b.spec();
-            out_rate = spec.rate;
-            out_channels = spec.channels.count() as u16;
-            let mut sbuf = SampleBuffer::<i32>::new(b.capacity() as u64, spec);
-            sbuf.copy_interleaved_ref(AudioBufferRef::S32(b));
-            let max = i32::MAX as f32;
-            pcm.extend(sbuf.samples().iter().map(|v| *v as f32 / max));
-          }
-          AudioBufferRef::U8(b) => {
-            let spec = *b.spec();
-            out_rate = spec.rate;
-            out_channels = spec.channels.count() as u16;
-            let mut sbuf = SampleBuffer::<u8>::new(b.capacity() as u64, spec);
-            sbuf.copy_interleaved_ref(AudioBufferRef::U8(b));
-            pcm.extend(sbuf.samples().iter().map(|v| (*v as f32 - 128.0) / 128.0));
-          }
-          _ => {}
-        }
+/// Decode any supported clip (WAV, MP3, ...) to interleaved f32 samples: (sample rate, channels, samples).
+pub fn decode_to_pcm(bytes: &[u8]) -> Result<(u32, u16, Vec<f32>), String> {
+  if let Ok(mut reader) = hound::WavReader::new(Cursor::new(bytes)) {
+    let spec = reader.spec();
+    let pcm: Vec<f32> = match spec.sample_format {
+      // Streamed WAVs declare a bogus data length; stop at the first read error
+      hound::SampleFormat::Float => reader.samples::<f32>().map_while(|s| s.ok()).collect(),
+      hound::SampleFormat::Int => {
+        let scale = (1i64 << (spec.bits_per_sample.clamp(1, 32) - 1)) as f32;
+        reader.samples::<i32>().map_while(|s| s.ok()).map(|s| s as f32 / scale).collect()
       }
-      Err(_) => {}
+    };
+    if !pcm.is_empty() {
+      return Ok((spec.sample_rate, spec.channels.max(1), pcm));
     }
   }
+  let pcm = crate::audio_decode::decode(bytes, "")?;
+  Ok((pcm.sample_rate, pcm.channels as u16, pcm.samples))
+}
 
-  if pcm.is_empty() { return Err("decode produced no samples".into()); }
-
-  let r = rate.clamp(-10, 10);
-  if r != 0 {
-    let factor = (2f32).powf(r as f32 / 10.0);
-    let new_rate = ((out_rate as f32) * factor).round() as u32;
-    out_rate = new_rate.clamp(8000, 192000);
-  }
+/// Write interleaved f32 samples as 16-bit WAV, applying the -10..10 rate (time-stretched, so the
+/// pitch stays) and 0..100 volume.
+pub fn write_pcm16_wav(pcm: &[f32], sample_rate: u32, channels: u16, target_path: &str, rate: i32, volume: u8) -> Result<(), String> {
+  let stretched;
+  let pcm = match rate.clamp(-10, 10) {
+    0 => pcm,
+    r => {
+      stretched = time_stretch(pcm, sample_rate, channels, rate_factor(r));
+      &stretched[..]
+    }
+  };
   let gain: f32 = (volume as f32 / 100.0).max(0.0);
   let mut writer = hound::WavWriter::create(target_path, hound::WavSpec {
-    channels: out_channels,
-    sample_rate: out_rate,
+    channels,
+    sample_rate,
     bits_per_sample: 16,
     sample_format: hound::SampleFormat::Int,
   }).map_err(|e| format!("wav writer create failed: {e}"))?;
 
-  for v in pcm.into_iter() {
+  for v in pcm.iter() {
     let s = (v * gain).clamp(-1.0, 1.0);
     let i = (s * 32767.0).round() as i16;
     writer.write_sample(i).map_err(|e| format!("wav write sample failed: {e}"))?;
@@ -114,22 +67,62 @@ pub fn write_pcm16_wav_from_any(bytes: &[u8], target_path: &str, rate: i32, volu
   Ok(())
 }
 
+/// Decode clips synthesized from consecutive pieces of one text and write them as a single WAV.
+pub fn join_pcm16_wav(clips: &[Vec<u8>], target_path: &str, rate: i32, volume: u8) -> Result<(), String> {
+  let mut format: Option<(u32, u16)> = None;
+  let mut pcm: Vec<f32> = Vec::new();
+  for (i, clip) in clips.iter().enumerate() {
+    let (sr, ch, samples) = decode_to_pcm(clip).map_err(|e| format!("part {}: {e}", i + 1))?;
+    match format {
+      None => format = Some((sr, ch)),
+      Some(f) if f != (sr, ch) => return Err(format!("part {} has a different audio format ({sr} Hz, {ch} ch)", i + 1)),
+      Some(_) => {}
+    }
+    pcm.extend(samples);
+  }
+  let (sr, ch) = format.ok_or_else(|| "no audio to join".to_string())?;
+  write_pcm16_wav(&pcm, sr, ch, target_path, rate, volume)
+}
+
+/// Split `text` at sentence ends (falling back to whitespace) into pieces of at most `max_chars`.
+pub fn split_for_tts(text: &str, max_chars: usize) -> Vec<String> {
+  let mut parts = Vec::new();
+  let mut rest = text.trim();
+  while rest.chars().count() > max_chars {
+    let limit = rest.char_indices().nth(max_chars).map(|(i, _)| i).unwrap_or(rest.len());
+    let head = &rest[..limit];
+    let cut = head
+      .rfind(|c: char| matches!(c, '.' | '!' | '?' | '\n'))
+      .map(|i| i + 1)
+      .or_else(|| head.rfind(char::is_whitespace))
+      .filter(|&i| i > 0)
+      .unwrap_or(limit);
+    parts.push(rest[..cut].trim().to_string());
+    rest = rest[cut..].trim_start();
+  }
+  if !rest.is_empty() {
+    parts.push(rest.to_string());
+  }
+  parts.retain(|p| !p.is_empty());
+  parts
+}
+
+/// Rewrite a WAV clip as 16-bit PCM with `rate` (time-stretched) and `volume` applied.
 pub fn apply_wav_gain_and_rate(bytes: &[u8], target_path: &str, rate: i32, volume: u8) -> Result<(), String> {
   let mut reader = hound::WavReader::new(Cursor::new(bytes))
     .map_err(|e| format!("wav decode failed: {e}"))?;
   let in_spec = reader.spec();
 
-  let gain: f32 = (volume as f32 / 100.0).max(0.0);
-  let r = rate.clamp(-10, 10);
-  let mut out_rate = in_spec.sample_rate;
-  if r != 0 {
-    let factor = (2f32).powf(r as f32 / 10.0);
-    out_rate = ((out_rate as f32) * factor).round() as u32;
-    out_rate = out_rate.clamp(8000, 192000);
+  // Stretching needs the whole clip; the sample-by-sample copy below only handles volume
+  if rate.clamp(-10, 10) != 0 {
+    let (sr, ch, pcm) = decode_to_pcm(bytes)?;
+    return write_pcm16_wav(&pcm, sr, ch, target_path, rate, volume);
   }
+
+  let gain: f32 = (volume as f32 / 100.0).max(0.0);
   let out_spec = hound::WavSpec {
     channels: in_spec.channels,
-    sample_rate: out_rate,
+    sample_rate: in_spec.sample_rate,
     bits_per_sample: 16,
     sample_format: hound::SampleFormat::Int,
   };
@@ -174,6 +167,90 @@ pub fn apply_wav_gain_and_rate(bytes: &[u8], target_path: &str, rate: i32, volum
   Ok(())
 }
 
+/// Speed factor of a -10..10 rate step: 2^(step/10), so +10 is twice as fast.
+pub fn rate_factor(rate: i32) -> f32 {
+  2f32.powf(rate.clamp(-10, 10) as f32 / 10.0)
+}
+
+// WSOLA frame length and the distance a frame may move to line up with the previous one
+const STRETCH_FRAME_MS: u32 = 30;
+const STRETCH_TOLERANCE_MS: u32 = 8;
+
+/// Change the speed of interleaved `pcm` by `factor` (> 1 is faster) without changing the pitch.
+/// WSOLA (waveform-similarity overlap-add): Hann-windowed frames are taken from the input every
+/// `factor` × hop and overlap-added every hop; each frame is shifted within a few milliseconds to
+/// where it best continues the previous one (cross-correlation of the mono mix), which avoids the
+/// phasing of plain overlap-add. Works well for speech; music would want a phase vocoder.
+pub fn time_stretch(pcm: &[f32], sample_rate: u32, channels: u16, factor: f32) -> Vec<f32> {
+  let ch = channels.max(1) as usize;
+  let frames = pcm.len() / ch;
+  let frame = ((sample_rate * STRETCH_FRAME_MS / 1000) as usize / 2 * 2).max(64);
+  let hop = frame / 2;
+  let tolerance = (sample_rate * STRETCH_TOLERANCE_MS / 1000) as usize;
+  if (factor - 1.0).abs() < 1e-3 || !factor.is_finite() || factor <= 0.0 || frames < frame + 2 * tolerance {
+    return pcm.to_vec();
+  }
+
+  let mono: Vec<f32> = pcm.chunks(ch).map(|f| f.iter().sum::<f32>() / ch as f32).collect();
+  // Periodic Hann windows at 50% overlap sum to one
+  let window: Vec<f32> = (0..frame).map(|n| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / frame as f32).cos()).collect();
+  let out_frames = (frames as f64 / factor as f64).round() as usize;
+  let mut out = vec![0f32; (out_frames + frame) * ch];
+
+  // Lag in -tolerance..=tolerance that best matches `template` (every other sample, coarse then fine)
+  let best_offset = |nominal: usize, template: usize| -> usize {
+    let lo = nominal.saturating_sub(tolerance);
+    let hi = (nominal + tolerance).min(frames - frame);
+    let score = |pos: usize| -> f32 { (0..hop).step_by(2).map(|n| mono[pos + n] * mono[template + n]).sum() };
+    let mut best = lo;
+    let mut best_score = f32::MIN;
+    for pos in (lo..=hi).step_by(2) {
+      let s = score(pos);
+      if s > best_score {
+        best = pos;
+        best_score = s;
+      }
+    }
+    for pos in [best.saturating_sub(1).max(lo), (best + 1).min(hi)] {
+      if score(pos) > best_score {
+        best_score = score(pos);
+        best = pos;
+      }
+    }
+    best
+  };
+
+  let mut prev: usize = 0;
+  let mut k = 0usize;
+  loop {
+    let out_pos = k * hop;
+    if out_pos >= out_frames {
+      break;
+    }
+    // The last frames are held at the end of the input
+    let nominal = ((k as f64 * hop as f64 * factor as f64).round() as usize).min(frames - frame);
+    let pos = if k == 0 {
+      0
+    } else {
+      // The natural continuation of the previous frame is the template
+      let template = (prev + hop).min(frames - frame);
+      best_offset(nominal, template)
+    };
+    // The first frame is not faded in, nor the last one out
+    let last = out_pos + frame >= out_frames;
+    for n in 0..frame {
+      let w = if (k == 0 && n < hop) || (last && n >= hop) { 1.0 } else { window[n] };
+      for c in 0..ch {
+        out[(out_pos + n) * ch + c] += pcm[(pos + n) * ch + c] * w;
+      }
+    }
+    prev = pos;
+    k += 1;
+  }
+  out.truncate(out_frames * ch);
+  out
+}
+
 // ---------------------------
 // SSE parsing helpers (generic)
 // ---------------------------
@@ -222,25 +299,8 @@ pub fn delete_temp_wav(path: String) -> Result<bool, String> {
   match fs::remove_file(&file_canon) { Ok(_) => Ok(true), Err(e) => { if e.kind() == std::io::ErrorKind::NotFound { Ok(false) } else { Err(format!("remove failed: {e}")) } } }
 }
 
+/// TTS files older than `max_age_minutes` (default 240); see temp_files for the other kinds.
 pub fn cleanup_stale_tts_wavs(max_age_minutes: Option<u64>) -> Result<u32, String> {
-  let age_min = max_age_minutes.unwrap_or(240);
-  let cutoff = SystemTime::now().checked_sub(Duration::from_secs(age_min.saturating_mul(60))).ok_or_else(|| "Invalid cutoff time".to_string())?;
-  let temp_dir = std::env::temp_dir();
-  let mut removed: u32 = 0;
-  let it = match fs::read_dir(&temp_dir) { Ok(i) => i, Err(_) => return Ok(0) };
-  for ent in it {
-    if let Ok(de) = ent {
-      let p = de.path();
-      if let Some(name) = p.file_name().and_then(|s| s.to_str()) {
-        if name.starts_with("aidc_tts_") {
-          let lower = name.to_ascii_lowercase();
-          let valid_ext = lower.ends_with(".wav") || lower.ends_with(".mp3") || lower.ends_with(".opus") || lower.ends_with(".ogg");
-          if valid_ext {
-            if let Ok(md) = de.metadata() { if let Ok(modified) = md.modified() { if modified < cutoff { let _ = fs::remove_file(&p).map(|_| { removed = removed.saturating_add(1); }); } } }
-          }
-        }
-      }
-    }
-  }
-  Ok(removed)
+  let max_age = Duration::from_secs(max_age_minutes.unwrap_or(240).saturating_mul(60));
+  Ok(crate::temp_files::cleanup(Some(crate::temp_files::TTS), Some(max_age))?.removed)
 }
@@ -1,19 +1,34 @@
+// Windows SAPI voices via PowerShell System.Speech; macOS builds re-export the `say` backend
+// from tts_mac_native, and other platforms get stubs.
+
+#[cfg(all(target_os = "macos", feature = "local-tts"))]
+pub use crate::tts_mac_native::{
+  local_tts_start,
+  local_tts_stop,
+  local_tts_is_speaking,
+  local_tts_list_voices,
+  local_speak_blocking,
+  local_tts_synthesize_wav,
+};
+
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 use crate::utils::ps_escape_single_quoted;
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 use std::io::Write;
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 use std::process::{Command, Stdio};
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 use once_cell::sync::Lazy;
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 use std::sync::Mutex;
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 static TTS_CHILD: Lazy<Mutex<Option<std::process::Child>>> = Lazy::new(|| Mutex::new(None));
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 pub fn local_tts_start(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<(), String> {
+  let text = crate::tts_normalize::normalize(&text);
   if text.trim().is_empty() { return Err("Text is empty".into()); }
   if let Ok(mut guard) = TTS_CHILD.lock() {
     if let Some(mut c) = guard.take() { let _ = c.kill(); let _ = c.wait(); }
@@ -48,12 +63,12 @@ try {{
   Ok(())
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), feature = "local-tts")))]
 pub fn local_tts_start(_text: String, _voice: Option<String>, _rate: Option<i32>, _volume: Option<u8>) -> Result<(), String> {
-  Err("TTS not implemented on this platform".into())
+  Err("Local TTS is not available on this platform or build".into())
 }
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 pub fn local_tts_stop() -> Result<(), String> {
   if let Ok(mut guard) = TTS_CHILD.lock() {
     if let Some(mut c) = guard.take() {
@@ -63,10 +78,10 @@ pub fn local_tts_stop() -> Result<(), String> {
   Ok(())
 }
 
-#[cfg(not(target_os = "windows"))]
-pub fn local_tts_stop() -> Result<(), String> { Err("TTS not implemented on this platform".into()) }
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), feature = "local-tts")))]
+pub fn local_tts_stop() -> Result<(), String> { Err("Local TTS is not available on this platform or build".into()) }
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 pub fn local_tts_is_speaking() -> bool {
   if let Ok(mut guard) = TTS_CHILD.lock() {
     if let Some(ref mut c) = *guard {
@@ -79,10 +94,10 @@ pub fn local_tts_is_speaking() -> bool {
   } else { false }
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), feature = "local-tts")))]
 pub fn local_tts_is_speaking() -> bool { false }
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 pub fn local_tts_list_voices() -> Result<Vec<String>, String> {
   let ps = r#"
 Add-Type -AssemblyName System.Speech;
@@ -102,12 +117,13 @@ $names | ForEach-Object { $_ }
   Ok(names)
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), feature = "local-tts")))]
 pub fn local_tts_list_voices() -> Result<Vec<String>, String> { Ok(vec![]) }
 
 #[allow(dead_code)]
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 pub fn local_speak_blocking(text: String, voice: String, rate: i32, vol: u8) -> Result<(), String> {
+  let text = crate::tts_normalize::normalize(&text);
   let v_escaped = ps_escape_single_quoted(&voice);
   let ps = format!(
     r#"
@@ -138,22 +154,19 @@ try {{
   Ok(())
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), feature = "local-tts")))]
 pub fn local_speak_blocking(_text: String, _voice: String, _rate: i32, _vol: u8) -> Result<(), String> {
-  Err("TTS not implemented on this platform".into())
+  Err("Local TTS is not available on this platform or build".into())
 }
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", feature = "local-tts"))]
 pub fn local_tts_synthesize_wav(text: String, voice: Option<String>, rate: Option<i32>, volume: Option<u8>) -> Result<String, String> {
   if text.trim().is_empty() { return Err("Text is empty".into()); }
   let v = voice.unwrap_or_default();
   let v_escaped = ps_escape_single_quoted(&v);
   let r = rate.unwrap_or(-2).clamp(-10, 10);
   let vol = volume.unwrap_or(100).min(100);
-  let file_name = format!("aidc_tts_{}.wav", chrono::Local::now().format("%Y%m%d_%H%M%S"));
-  let mut path = std::env::temp_dir();
-  path.push(file_name);
-  let target = path.to_string_lossy().to_string();
+  let target = crate::temp_files::tts_path("sapi", "wav");
   let ps = format!(
     r#"
 Add-Type -AssemblyName System.Speech;
@@ -180,10 +193,11 @@ try {{
   drop(child.stdin.take());
   let status = child.wait().map_err(|e| format!("powershell wait failed: {e}"))?;
   if !status.success() { return Err(format!("powershell exited with status: {status}")); }
+  crate::temp_files::register(crate::temp_files::TTS, &target);
   Ok(target)
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(all(any(target_os = "windows", target_os = "macos"), feature = "local-tts")))]
 pub fn local_tts_synthesize_wav(_text: String, _voice: Option<String>, _rate: Option<i32>, _volume: Option<u8>) -> Result<String, String> {
-  Err("TTS not implemented on this platform".into())
+  Err("Local TTS is not available on this platform or build".into())
 }
@@ -0,0 +1,227 @@
+// Windows UI Automation helpers (with stubs for other platforms)
+
+/// Selected text of the focused element via the UIA TextPattern, without touching the clipboard.
+/// Returns None when the element does not expose a text pattern or nothing is selected.
+#[cfg(target_os = "windows")]
+pub fn focused_selection_text() -> Option<String> {
+  use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+  use windows::Win32::UI::Accessibility::{CUIAutomation, IUIAutomation, IUIAutomationTextPattern, UIA_TextPatternId};
+
+  let result = crate::crash::catch_unwind(|| unsafe {
+    // S_FALSE / RPC_E_CHANGED_MODE just mean COM is already initialized on this thread
+    let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+    let element = automation.GetFocusedElement().ok()?;
+    let pattern: IUIAutomationTextPattern = element.GetCurrentPatternAs(UIA_TextPatternId).ok()?;
+    let ranges = pattern.GetSelection().ok()?;
+    let len = ranges.Length().ok()?;
+    let mut out = String::new();
+    for i in 0..len {
+      if let Ok(range) = ranges.GetElement(i) {
+        if let Ok(text) = range.GetText(-1) {
+          out.push_str(&text.to_string());
+        }
+      }
+    }
+    if out.is_empty() { None } else { Some(out) }
+  });
+  result.ok().flatten()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn focused_selection_text() -> Option<String> {
+  None
+}
+
+/// Text of the focused editable control from the caret to the end of the document (UIA
+/// TextPattern), at most `max_chars`. When text is selected, only the selection is returned.
+/// None when the control does not expose a text pattern or there is nothing after the caret.
+#[cfg(target_os = "windows")]
+pub fn text_from_caret(max_chars: usize) -> Option<String> {
+  use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+  use windows::Win32::UI::Accessibility::{
+    CUIAutomation, IUIAutomation, IUIAutomationTextPattern, TextPatternRangeEndpoint_End, TextPatternRangeEndpoint_Start, UIA_TextPatternId,
+  };
+
+  let result = crate::crash::catch_unwind(|| unsafe {
+    let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+    let element = automation.GetFocusedElement().ok()?;
+    let pattern: IUIAutomationTextPattern = element.GetCurrentPatternAs(UIA_TextPatternId).ok()?;
+    // The caret is reported as an empty selection range
+    let caret = pattern.GetSelection().ok()?.GetElement(0).ok()?;
+    let limit = max_chars.min(i32::MAX as usize) as i32;
+    let selected = caret.CompareEndpoints(TextPatternRangeEndpoint_Start, &caret, TextPatternRangeEndpoint_End).ok()? != 0;
+    if !selected {
+      let document = pattern.DocumentRange().ok()?;
+      caret.MoveEndpointByRange(TextPatternRangeEndpoint_End, &document, TextPatternRangeEndpoint_End).ok()?;
+    }
+    let text = caret.GetText(limit).ok()?.to_string();
+    if text.trim().is_empty() { None } else { Some(text) }
+  });
+  result.ok().flatten()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn text_from_caret(_max_chars: usize) -> Option<String> {
+  None
+}
+
+/// Text content of a top-level window read through UI Automation.
+#[derive(Debug, Clone, Default)]
+pub struct WindowText {
+  pub title: String,
+  pub text: String,
+  pub truncated: bool,
+}
+
+// Upper bound on visited elements so huge trees (spreadsheets, IDEs) cannot stall the walk
+#[cfg(target_os = "windows")]
+const MAX_WALK_ELEMENTS: usize = 5_000;
+
+#[cfg(target_os = "windows")]
+struct TextCollector {
+  text: WindowText,
+  chars: usize,
+  max_chars: usize,
+}
+
+#[cfg(target_os = "windows")]
+impl TextCollector {
+  fn remaining(&self) -> usize {
+    self.max_chars.saturating_sub(self.chars)
+  }
+
+  // Appends one block of text; false once the character budget is used up
+  fn push(&mut self, s: &str) -> bool {
+    let s = s.trim();
+    if s.is_empty() { return true; }
+    let take: String = s.chars().take(self.remaining()).collect();
+    self.chars += take.chars().count();
+    if !self.text.text.is_empty() { self.text.text.push('\n'); }
+    self.text.text.push_str(&take);
+    if self.chars >= self.max_chars {
+      self.text.truncated = true;
+      return false;
+    }
+    true
+  }
+}
+
+/// Text of the given window (raw HWND): full document ranges of elements exposing the UIA
+/// TextPattern (documents, editors, browsers), plus the names of plain text controls elsewhere.
+/// Stops after `max_chars` characters. Returns None when the window cannot be read.
+#[cfg(target_os = "windows")]
+pub fn window_text(hraw: isize, max_chars: usize) -> Option<WindowText> {
+  use std::ffi::c_void;
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+  use windows::Win32::UI::Accessibility::{
+    CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationTextPattern, UIA_TextControlTypeId, UIA_TextPatternId,
+  };
+
+  let result = crate::crash::catch_unwind(|| unsafe {
+    let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+    let root = automation.ElementFromHandle(HWND(hraw as *mut c_void)).ok()?;
+    let walker = automation.ControlViewWalker().ok()?;
+    let title = root.CurrentName().map(|n| n.to_string()).unwrap_or_default();
+    let mut out = TextCollector { text: WindowText { title, ..Default::default() }, chars: 0, max_chars };
+
+    let mut stack: Vec<IUIAutomationElement> = vec![root];
+    let mut visited = 0usize;
+    while let Some(el) = stack.pop() {
+      visited += 1;
+      if visited > MAX_WALK_ELEMENTS {
+        out.text.truncated = true;
+        break;
+      }
+      // A text pattern covers the element's whole subtree; do not descend into it
+      if let Ok(pattern) = el.GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId) {
+        let limit = (out.remaining() + 1).min(i32::MAX as usize) as i32;
+        if let Ok(text) = pattern.DocumentRange().and_then(|r| r.GetText(limit)) {
+          if !out.push(&text.to_string()) { break; }
+          continue;
+        }
+      }
+      if el.CurrentControlType().map(|t| t == UIA_TextControlTypeId).unwrap_or(false) {
+        if let Ok(name) = el.CurrentName() {
+          if !out.push(&name.to_string()) { break; }
+        }
+      }
+      // Children are pushed in reverse so the walk follows reading order
+      let mut children = Vec::new();
+      let mut child = walker.GetFirstChildElement(&el).ok();
+      while let Some(c) = child {
+        child = walker.GetNextSiblingElement(&c).ok();
+        children.push(c);
+      }
+      stack.extend(children.into_iter().rev());
+    }
+    Some(out.text)
+  });
+  result.ok().flatten()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn window_text(_hraw: isize, _max_chars: usize) -> Option<WindowText> {
+  None
+}
+
+// The address bar sits near the top of the browser's own UI; page content is never searched
+#[cfg(target_os = "windows")]
+const MAX_ADDRESS_BAR_ELEMENTS: usize = 150;
+
+/// Text of a browser's address bar (Chrome/Edge omnibox, Firefox URL bar) in the given window
+/// (raw HWND): the value of the first edit control outside the page document, breadth-first.
+/// None when there is none or it is empty.
+#[cfg(target_os = "windows")]
+pub fn browser_address_bar(hraw: isize) -> Option<String> {
+  use std::collections::VecDeque;
+  use std::ffi::c_void;
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+  use windows::Win32::UI::Accessibility::{
+    CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationValuePattern, UIA_DocumentControlTypeId, UIA_EditControlTypeId,
+    UIA_ValuePatternId,
+  };
+
+  let result = crate::crash::catch_unwind(|| unsafe {
+    let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+    let root = automation.ElementFromHandle(HWND(hraw as *mut c_void)).ok()?;
+    let walker = automation.ControlViewWalker().ok()?;
+
+    let mut queue: VecDeque<IUIAutomationElement> = VecDeque::from([root]);
+    let mut visited = 0usize;
+    while let Some(el) = queue.pop_front() {
+      visited += 1;
+      if visited > MAX_ADDRESS_BAR_ELEMENTS { break; }
+      match el.CurrentControlType() {
+        // Inputs on the page itself are not the address bar
+        Ok(t) if t == UIA_DocumentControlTypeId => continue,
+        Ok(t) if t == UIA_EditControlTypeId => {
+          if let Ok(pattern) = el.GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId) {
+            if let Ok(value) = pattern.CurrentValue() {
+              let value = value.to_string();
+              if !value.trim().is_empty() { return Some(value); }
+            }
+          }
+        }
+        _ => {}
+      }
+      let mut child = walker.GetFirstChildElement(&el).ok();
+      while let Some(c) = child {
+        child = walker.GetNextSiblingElement(&c).ok();
+        queue.push_back(c);
+      }
+    }
+    None
+  });
+  result.ok().flatten()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn browser_address_bar(_hraw: isize) -> Option<String> {
+  None
+}
@@ -0,0 +1,269 @@
+// Usage tracker: per-day, per-feature, per-model counters for cloud API calls (tokens, characters,
+// audio seconds) with an estimated cost from list prices. Kept in <app dir>/usage.json as
+// { "YYYY-MM-DD": { feature: { model: Totals } } } and aggregated by `get_spend_breakdown`.
+// Costs are estimates; the provider's billing page is authoritative.
+// `record` only updates the in-memory book; a background thread writes it at most every
+// FLUSH_INTERVAL, and `flush` writes what is left on exit.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+
+const USAGE_FILE: &str = "usage.json";
+const DATE_FMT: &str = "%Y-%m-%d";
+const FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+pub const CHAT: &str = "chat";
+pub const QUICK_PROMPTS: &str = "quick_prompts";
+pub const TTS: &str = "tts";
+pub const STT: &str = "stt";
+pub const IMAGES: &str = "images";
+pub const EMBEDDINGS: &str = "embeddings";
+// Always listed in a breakdown, even without usage in the period
+const CORE_FEATURES: [&str; 4] = [CHAT, QUICK_PROMPTS, TTS, STT];
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Totals {
+  pub requests: u64,
+  pub input_tokens: u64,
+  pub output_tokens: u64,
+  /// Characters sent to text-to-speech
+  pub characters: u64,
+  pub audio_seconds: f64,
+  pub images: u64,
+  pub cost_usd: f64,
+  /// Requests for models without a known price (counted at $0)
+  pub unpriced_requests: u64,
+}
+
+impl Totals {
+  fn add(&mut self, o: &Totals) {
+    self.requests += o.requests;
+    self.input_tokens += o.input_tokens;
+    self.output_tokens += o.output_tokens;
+    self.characters += o.characters;
+    self.audio_seconds += o.audio_seconds;
+    self.images += o.images;
+    self.cost_usd += o.cost_usd;
+    self.unpriced_requests += o.unpriced_requests;
+  }
+}
+
+/// What one request consumed.
+#[derive(Default)]
+pub struct Usage {
+  pub input_tokens: u64,
+  pub output_tokens: u64,
+  pub characters: u64,
+  pub audio_seconds: Option<f64>,
+  pub images: u64,
+}
+
+// Loaded on first use; None until then
+static BOOK: Lazy<Mutex<Option<Book>>> = Lazy::new(|| Mutex::new(None));
+// Set when the book has records not yet written to usage.json
+static DIRTY: AtomicBool = AtomicBool::new(false);
+// Serializes writes so an older snapshot never lands after a newer one
+static WRITE: Mutex<()> = Mutex::new(());
+static FLUSHER: OnceCell<()> = OnceCell::new();
+
+type Book = BTreeMap<String, BTreeMap<String, BTreeMap<String, Totals>>>;
+
+fn usage_path() -> Option<PathBuf> {
+  crate::logging::logs_dir().and_then(|d| d.parent().map(|p| p.join(USAGE_FILE)))
+}
+
+fn load_book() -> Book {
+  usage_path()
+    .and_then(|p| fs::read_to_string(p).ok())
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default()
+}
+
+fn save_book(book: &Book) {
+  let Some(path) = usage_path() else { return };
+  let res = serde_json::to_string(book)
+    .map_err(|e| e.to_string())
+    .and_then(|text| crate::config::write_json_atomic_blocking(&path, &text));
+  if let Err(e) = res {
+    tracing::warn!(error = %e, "usage file write failed");
+  }
+}
+
+/// Count one request for `feature` against today's totals.
+pub fn record(feature: &str, model: &str, usage: Usage) {
+  let model = if model.trim().is_empty() { "unknown" } else { model.trim() };
+  let mut entry = Totals {
+    requests: 1,
+    input_tokens: usage.input_tokens,
+    output_tokens: usage.output_tokens,
+    characters: usage.characters,
+    audio_seconds: usage.audio_seconds.unwrap_or(0.0),
+    images: usage.images,
+    ..Default::default()
+  };
+  // List prices come from the model metadata (models.rs, overridable in models.json)
+  match crate::models::pricing(model) {
+    Some(r) => {
+      entry.cost_usd = usage.input_tokens as f64 * r.input_per_m / 1e6
+        + usage.output_tokens as f64 * r.output_per_m / 1e6
+        + usage.characters as f64 * r.chars_per_m / 1e6
+        + entry.audio_seconds / 60.0 * r.per_minute
+        + usage.images as f64 * r.per_image;
+    }
+    None => entry.unpriced_requests = 1,
+  }
+
+  let day = Local::now().format(DATE_FMT).to_string();
+  let mut guard = BOOK.lock().unwrap_or_else(|e| e.into_inner());
+  let book = guard.get_or_insert_with(load_book);
+  book.entry(day).or_default().entry(feature.to_string()).or_default().entry(model.to_string()).or_default().add(&entry);
+  drop(guard);
+  DIRTY.store(true, Ordering::SeqCst);
+  FLUSHER.get_or_init(|| {
+    let spawned = std::thread::Builder::new().name("usage-flush".into()).spawn(|| loop {
+      std::thread::sleep(FLUSH_INTERVAL);
+      flush();
+    });
+    if let Err(e) = spawned {
+      tracing::warn!(error = %e, "usage flush thread failed to start");
+    }
+  });
+}
+
+/// Write the book to usage.json if it has unsaved records. Called periodically and on exit.
+pub fn flush() {
+  let _write = WRITE.lock().unwrap_or_else(|e| e.into_inner());
+  if !DIRTY.swap(false, Ordering::SeqCst) {
+    return;
+  }
+  // Copy under the lock, write outside it so `record` never waits on the disk
+  let snapshot = BOOK.lock().unwrap_or_else(|e| e.into_inner()).clone();
+  if let Some(book) = snapshot {
+    save_book(&book);
+  }
+}
+
+/// Record a response that carries an OpenAI `usage` object (Chat Completions, Responses, Embeddings).
+pub fn record_response(feature: &str, model: &str, v: &serde_json::Value) {
+  let u = v.get("usage");
+  let field = |names: &[&str]| names.iter().find_map(|n| u.and_then(|u| u.get(*n)).and_then(|x| x.as_u64())).unwrap_or(0);
+  record(feature, model, Usage {
+    input_tokens: field(&["prompt_tokens", "input_tokens"]),
+    output_tokens: field(&["completion_tokens", "output_tokens"]),
+    ..Default::default()
+  });
+}
+
+/// Record a speech request from its JSON body (`model` and `input` fields).
+pub fn record_tts(body: &serde_json::Value) {
+  let model = body.get("model").and_then(|x| x.as_str()).unwrap_or("");
+  let characters = body.get("input").and_then(|x| x.as_str()).map(|s| s.chars().count() as u64).unwrap_or(0);
+  record(TTS, model, Usage { characters, ..Default::default() });
+}
+
+#[derive(Serialize)]
+pub struct ModelSpend {
+  pub model: String,
+  #[serde(flatten)]
+  pub totals: Totals,
+}
+
+#[derive(Serialize)]
+pub struct FeatureSpend {
+  pub feature: String,
+  #[serde(flatten)]
+  pub totals: Totals,
+  /// Most expensive first
+  pub models: Vec<ModelSpend>,
+}
+
+#[derive(Serialize)]
+pub struct DaySpend {
+  pub date: String,
+  pub cost_usd: f64,
+}
+
+#[derive(Serialize)]
+pub struct SpendBreakdown {
+  pub period: String,
+  /// First day included (YYYY-MM-DD); None for "all"
+  pub from: Option<String>,
+  pub to: String,
+  pub total: Totals,
+  /// Most expensive first; chat, quick prompts, TTS and STT are always present
+  pub features: Vec<FeatureSpend>,
+  /// Days with usage, oldest first
+  pub days: Vec<DaySpend>,
+}
+
+fn period_start(period: &str, today: NaiveDate) -> Result<Option<NaiveDate>, String> {
+  match period {
+    "today" => Ok(Some(today)),
+    "week" => Ok(Some(today - Duration::days(6))),
+    "month" => Ok(today.with_day(1)),
+    "all" => Ok(None),
+    other => Err(format!("unknown period '{other}' (expected today, week, month or all)")),
+  }
+}
+
+fn by_cost_desc(a: f64, b: f64) -> std::cmp::Ordering {
+  b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal)
+}
+
+/// Spend per feature and model for `period`: "today", "week" (last 7 days), "month" (calendar
+/// month, default) or "all".
+#[tauri::command]
+pub fn get_spend_breakdown(period: Option<String>) -> Result<SpendBreakdown, String> {
+  let period = period.map(|p| p.trim().to_lowercase()).filter(|p| !p.is_empty()).unwrap_or_else(|| "month".to_string());
+  let today = Local::now().date_naive();
+  let from = period_start(&period, today)?.map(|d| d.format(DATE_FMT).to_string());
+
+  let mut features: BTreeMap<String, BTreeMap<String, Totals>> =
+    CORE_FEATURES.iter().map(|f| (f.to_string(), BTreeMap::new())).collect();
+  let mut days = Vec::new();
+  let mut total = Totals::default();
+  {
+    let mut guard = BOOK.lock().unwrap_or_else(|e| e.into_inner());
+    let book = guard.get_or_insert_with(load_book);
+    // Keys are ISO dates, so string order is date order
+    for (day, per_feature) in book.iter().filter(|(d, _)| from.as_deref().map(|f| d.as_str() >= f).unwrap_or(true)) {
+      let mut day_cost = 0.0;
+      for (feature, per_model) in per_feature {
+        let models = features.entry(feature.clone()).or_default();
+        for (model, t) in per_model {
+          models.entry(model.clone()).or_default().add(t);
+          total.add(t);
+          day_cost += t.cost_usd;
+        }
+      }
+      days.push(DaySpend { date: day.clone(), cost_usd: day_cost });
+    }
+  }
+
+  let mut features: Vec<FeatureSpend> = features
+    .into_iter()
+    .map(|(feature, per_model)| {
+      let mut totals = Totals::default();
+      let mut models: Vec<ModelSpend> = per_model
+        .into_iter()
+        .map(|(model, t)| {
+          totals.add(&t);
+          ModelSpend { model, totals: t }
+        })
+        .collect();
+      models.sort_by(|a, b| by_cost_desc(a.totals.cost_usd, b.totals.cost_usd));
+      FeatureSpend { feature, totals, models }
+    })
+    .collect();
+  features.sort_by(|a, b| by_cost_desc(a.totals.cost_usd, b.totals.cost_usd));
+
+  Ok(SpendBreakdown { period, from, to: today.format(DATE_FMT).to_string(), total, features, days })
+}
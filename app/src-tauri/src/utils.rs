@@ -11,8 +11,6 @@ pub fn ps_escape_single_quoted(s: &str) -> String { s.to_string() }
 
 use std::path::PathBuf;
 use std::fs;
-#[cfg(target_os = "windows")]
-use tauri::Emitter;
 
 // Utility: Copy a file to destination (used by Save As flow)
 pub fn copy_file_to_path(src: String, dest: String, overwrite: Option<bool>) -> Result<String, String> {
@@ -28,48 +26,49 @@ pub fn copy_file_to_path(src: String, dest: String, overwrite: Option<bool>) ->
   Ok(dest_path.to_string_lossy().to_string())
 }
 
-// Play a WAV file synchronously using PowerShell SoundPlayer on Windows
+/// Resident memory of this process as (current, peak) bytes, when the platform exposes it.
 #[cfg(target_os = "windows")]
-pub fn play_wav_blocking_windows(app: &tauri::AppHandle, wav_path: &str) -> Result<(), String> {
-  use std::process::Command;
-  // Sanity checks
-  match fs::metadata(&wav_path) {
-    Ok(meta) => {
-      if meta.len() < 44 { // smaller than typical WAV header
-        let msg = format!("synthesized WAV too small: {} bytes at {}", meta.len(), &wav_path);
-        let _ = app.emit("tts:error", serde_json::json!({ "message": msg }));
-        return Err(msg);
-      }
-    }
-    Err(e) => {
-      let msg = format!("synthesized WAV not found: {} ({})", &wav_path, e);
-      let _ = app.emit("tts:error", serde_json::json!({ "message": msg }));
-      return Err(msg);
-    }
-  }
-  let wav_escaped = ps_escape_single_quoted(&wav_path);
-  let ps = format!(
-    r#"$p = New-Object System.Media.SoundPlayer '{path}'; $p.PlaySync();"#,
-    path = wav_escaped
-  );
-  let out = Command::new("powershell.exe")
-    .args(["-NoProfile", "-NonInteractive", "-Command", &ps])
-    .output()
-    .map_err(|e| format!("launch powershell failed: {e}"))?;
-  if !out.status.success() {
-    let stderr_s = String::from_utf8_lossy(&out.stderr);
-    let msg = if stderr_s.trim().is_empty() {
-      format!("audio play failed: {}", out.status)
-    } else {
-      format!("audio play failed: {}\n{}", out.status, stderr_s)
-    };
-    let _ = app.emit("tts:error", serde_json::json!({ "message": msg }));
-    return Err(msg);
+pub fn process_memory() -> Option<(u64, u64)> {
+  use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+  use windows::Win32::System::Threading::GetCurrentProcess;
+  let mut pmc = PROCESS_MEMORY_COUNTERS::default();
+  let cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+  unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut pmc, cb).ok()? };
+  Some((pmc.WorkingSetSize as u64, pmc.PeakWorkingSetSize as u64))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn process_memory() -> Option<(u64, u64)> {
+  // Linux: VmRSS / VmHWM in kB
+  let status = fs::read_to_string("/proc/self/status").ok()?;
+  let field = |name: &str| -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with(name))?;
+    line[name.len()..].trim().trim_end_matches("kB").trim().parse::<u64>().ok().map(|kb| kb * 1024)
+  };
+  let rss = field("VmRSS:")?;
+  Some((rss, field("VmHWM:").unwrap_or(rss)))
+}
+
+/// Resident memory of another process in bytes (Windows, Linux).
+#[cfg(target_os = "windows")]
+pub fn child_memory(pid: u32) -> Option<u64> {
+  use windows::Win32::Foundation::CloseHandle;
+  use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+  use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_VM_READ};
+  let mut pmc = PROCESS_MEMORY_COUNTERS::default();
+  let cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+  unsafe {
+    let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, false, pid).ok()?;
+    let res = GetProcessMemoryInfo(handle, &mut pmc, cb);
+    let _ = CloseHandle(handle);
+    res.ok()?;
   }
-  Ok(())
+  Some(pmc.WorkingSetSize as u64)
 }
 
 #[cfg(not(target_os = "windows"))]
-pub fn play_wav_blocking_windows(_app: &tauri::AppHandle, _wav_path: &str) -> Result<(), String> {
-  Err("WAV playback not implemented on this platform".into())
+pub fn child_memory(pid: u32) -> Option<u64> {
+  let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+  let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+  line["VmRSS:".len()..].trim().trim_end_matches("kB").trim().parse::<u64>().ok().map(|kb| kb * 1024)
 }
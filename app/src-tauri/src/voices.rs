@@ -0,0 +1,188 @@
+// Voice profiles: named TTS presets ("narrator", "casual", "meeting-notes") bundling provider,
+// voice, model, style instructions, rate and volume. Stored in voices.json next to settings.json.
+// `tts_voice_profile` is the profile last picked in the TTS panel; `voice_assistant.voice_profile`
+// is the one the hands-free assistant speaks with. Without a profile the plain tts_* settings apply.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+const MAX_NAME_CHARS: usize = 48;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VoiceProfile {
+  /// Assigned on first save
+  #[serde(default)]
+  pub id: String,
+  pub name: String,
+  /// "openai" | "elevenlabs" | "piper" | "local"
+  pub provider: String,
+  /// Provider voice id (OpenAI voice name, ElevenLabs voice id, Piper voice or SAPI voice name);
+  /// None = provider default
+  #[serde(default)]
+  pub voice: Option<String>,
+  /// Provider model, e.g. gpt-4o-mini-tts or eleven_multilingual_v2
+  #[serde(default)]
+  pub model: Option<String>,
+  /// Style prompt sent with the request (OpenAI gpt-4o-mini-tts)
+  #[serde(default)]
+  pub instructions: Option<String>,
+  /// -10..10
+  #[serde(default)]
+  pub rate: Option<i32>,
+  /// 0..100
+  #[serde(default)]
+  pub volume: Option<u8>,
+}
+
+/// Effective speech parameters for one request: a profile, or the tts_* settings.
+pub struct SpeechParams {
+  pub provider: String,
+  pub voice: Option<String>,
+  pub model: Option<String>,
+  pub instructions: Option<String>,
+  pub rate: Option<i32>,
+  pub volume: Option<u8>,
+}
+
+fn voices_config_path() -> Option<PathBuf> {
+  crate::config::settings_config_path().map(|p| p.with_file_name("voices.json"))
+}
+
+fn load_profiles() -> Vec<VoiceProfile> {
+  voices_config_path()
+    .and_then(|p| fs::read_to_string(p).ok())
+    .and_then(|text| serde_json::from_str::<Vec<VoiceProfile>>(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_profiles(list: &[VoiceProfile]) -> Result<(), String> {
+  let path = voices_config_path().ok_or_else(|| "Unsupported platform for config path".to_string())?;
+  let pretty = serde_json::to_string_pretty(list).map_err(|e| format!("Serialize voice profiles failed: {e}"))?;
+  crate::config::write_json_atomic_blocking(&path, &pretty)
+}
+
+fn non_empty(s: Option<String>) -> Option<String> {
+  s.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+pub fn find(id: &str) -> Option<VoiceProfile> {
+  let id = id.trim();
+  if id.is_empty() {
+    return None;
+  }
+  load_profiles().into_iter().find(|p| p.id == id)
+}
+
+/// Parameters for `profile_id`, falling back to the tts_* settings when it is unset or unknown.
+pub fn speech_params(profile_id: Option<&str>) -> SpeechParams {
+  if let Some(p) = profile_id.and_then(find) {
+    return SpeechParams { provider: p.provider, voice: p.voice, model: p.model, instructions: p.instructions, rate: p.rate, volume: p.volume };
+  }
+  let provider = crate::config::load_settings_json().get("tts_engine").and_then(|x| x.as_str()).filter(|x| !x.is_empty()).unwrap_or("openai").to_string();
+  settings_params(&provider)
+}
+
+/// The tts_* settings (voice, model, instructions, rate and volume) for `provider`.
+pub fn settings_params(provider: &str) -> SpeechParams {
+  let s = crate::config::load_settings_json();
+  let str_of = |k: &str| s.get(k).and_then(|x| x.as_str()).map(|x| x.to_string()).filter(|x| !x.is_empty());
+  let (voice, model, instructions) = if provider == "local" {
+    (str_of("tts_voice_local"), None, None)
+  } else if provider == "piper" {
+    (str_of("tts_piper_voice"), None, None)
+  } else if provider == "elevenlabs" {
+    (str_of("tts_elevenlabs_voice"), str_of("tts_elevenlabs_model"), None)
+  } else {
+    (str_of("tts_openai_voice"), str_of("tts_openai_model"), str_of("tts_openai_instructions"))
+  };
+  SpeechParams {
+    provider: provider.to_string(),
+    voice,
+    model,
+    instructions,
+    rate: s.get("tts_rate").and_then(|x| x.as_i64()).map(|r| r as i32),
+    volume: s.get("tts_volume").and_then(|x| x.as_u64()).map(|v| v.min(100) as u8),
+  }
+}
+
+/// Synthesize `text` to a temporary WAV with `params`. This is the engine dispatch for everything
+/// that speaks through a file (voice profiles, the assistant, audiobooks, the read-aloud queue).
+/// Local voices need a Windows or macOS build with `local-tts`; elsewhere they fall back to OpenAI.
+pub async fn synthesize_wav(text: String, params: SpeechParams) -> Result<String, String> {
+  synthesize_normalized_wav(crate::tts_normalize::normalize(&text), params).await
+}
+
+/// `synthesize_wav` for text that already went through tts_normalize (the read-aloud queue
+/// normalizes the whole text once before cutting it into chunks).
+pub async fn synthesize_normalized_wav(text: String, params: SpeechParams) -> Result<String, String> {
+  if params.provider == "local" && cfg!(all(any(target_os = "windows", target_os = "macos"), feature = "local-tts")) {
+    let SpeechParams { voice, rate, volume, .. } = params;
+    return tokio::task::spawn_blocking(move || crate::tts_win_native::local_tts_synthesize_wav(text, voice, rate, volume))
+      .await
+      .map_err(|e| format!("tts task failed: {e}"))?;
+  }
+  if params.provider == "piper" {
+    return crate::tts_piper::synthesize_wav(text, params.voice, params.rate, params.volume).await;
+  }
+  if params.provider == "elevenlabs" {
+    let key = crate::settings::require_elevenlabs_key().map_err(|e| e.message())?;
+    return crate::tts_elevenlabs::synthesize_wav(key, text, params.voice, params.model, params.rate, params.volume).await.map_err(|e| e.message());
+  }
+  let key = crate::settings::require_openai_key().map_err(|e| e.message())?;
+  crate::tts_openai::openai_synthesize_file(key, text, params.voice, params.model, Some("wav".to_string()), params.rate, params.volume, params.instructions)
+    .await
+    .map_err(|e| e.message())
+}
+
+#[tauri::command]
+pub fn list_voice_profiles() -> Result<Vec<VoiceProfile>, String> {
+  Ok(load_profiles())
+}
+
+/// Create (empty `id`) or update a profile. Names are unique, case-insensitively.
+#[tauri::command]
+pub fn save_voice_profile(profile: VoiceProfile) -> Result<VoiceProfile, String> {
+  let name = profile.name.trim().to_string();
+  if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+    return Err(format!("Profile name must be 1-{MAX_NAME_CHARS} characters"));
+  }
+  let provider = profile.provider.trim().to_lowercase();
+  if !["openai", "elevenlabs", "piper", "local"].contains(&provider.as_str()) {
+    return Err(format!("Unknown provider '{}' (expected openai, elevenlabs, piper or local)", profile.provider));
+  }
+  let mut list = load_profiles();
+  let id = if profile.id.trim().is_empty() { uuid::Uuid::new_v4().to_string() } else { profile.id.trim().to_string() };
+  if list.iter().any(|p| p.id != id && p.name.eq_ignore_ascii_case(&name)) {
+    return Err(format!("A voice profile named '{name}' already exists"));
+  }
+  let saved = VoiceProfile {
+    id: id.clone(),
+    name,
+    provider,
+    voice: non_empty(profile.voice),
+    model: non_empty(profile.model),
+    instructions: non_empty(profile.instructions),
+    rate: profile.rate.map(|r| r.clamp(-10, 10)),
+    volume: profile.volume.map(|v| v.min(100)),
+  };
+  match list.iter_mut().find(|p| p.id == id) {
+    Some(existing) => *existing = saved.clone(),
+    None => list.push(saved.clone()),
+  }
+  write_profiles(&list)?;
+  Ok(saved)
+}
+
+#[tauri::command]
+pub fn delete_voice_profile(id: String) -> Result<bool, String> {
+  let mut list = load_profiles();
+  let before = list.len();
+  list.retain(|p| p.id != id);
+  if list.len() == before {
+    return Ok(false);
+  }
+  write_profiles(&list)?;
+  Ok(true)
+}
@@ -0,0 +1,291 @@
+// Watch folders: new files in user-configured folders (e.g. Screenshots) are run through a
+// pipeline automatically. Configured in the `watch_folders` settings array:
+//   [{ "id": "shots", "path": "C:\\Users\\me\\Pictures\\Screenshots", "enabled": true,
+//      "recursive": false, "extensions": ["png", "jpg"],
+//      "steps": ["extract", "summarize", "save_markdown"], "prompt": "Summarize ..." }]
+// Steps: extract (OCR / transcript / document text, as for dropped files), summarize (chat
+// model with `prompt`), save_markdown (writes "<file>.summary.md" next to the file).
+//
+// Folders are polled; a file is processed once its size stopped changing, and files present
+// when a watch starts are ignored. Each file runs as a "watch" job. Every stage is logged
+// (see `get_watch_log`) and emitted as `watch:event` (payload = WatchEvent).
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const MAX_LOG_ENTRIES: usize = 200;
+// Directory entries scanned per watch and poll, so a huge folder cannot stall the loop
+const MAX_SCAN_ENTRIES: usize = 5_000;
+const OUTPUT_SUFFIX: &str = ".summary.md";
+const DEFAULT_PROMPT: &str = "Summarize the following content in a few concise bullet points. Start with a one-line title.";
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static LOG: Lazy<Mutex<VecDeque<WatchEvent>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct WatchFolder {
+  pub id: String,
+  pub path: String,
+  pub enabled: bool,
+  pub recursive: bool,
+  /// Lowercase extensions without the dot; empty = every file the extractors understand
+  pub extensions: Vec<String>,
+  pub steps: Vec<String>,
+  pub prompt: Option<String>,
+}
+
+impl Default for WatchFolder {
+  fn default() -> Self {
+    WatchFolder {
+      id: String::new(),
+      path: String::new(),
+      enabled: true,
+      recursive: false,
+      extensions: Vec::new(),
+      steps: vec!["extract".into(), "summarize".into(), "save_markdown".into()],
+      prompt: None,
+    }
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WatchEvent {
+  pub watch_id: String,
+  pub path: String,
+  /// "detected" | "done" | "error"
+  pub stage: String,
+  pub message: Option<String>,
+  /// Markdown file written by save_markdown
+  pub output: Option<String>,
+  pub at_ms: u64,
+}
+
+fn now_ms() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn log_event(app: &tauri::AppHandle, watch_id: &str, path: &Path, stage: &str, message: Option<String>, output: Option<String>) {
+  let ev = WatchEvent { watch_id: watch_id.to_string(), path: path.to_string_lossy().to_string(), stage: stage.to_string(), message, output, at_ms: now_ms() };
+  {
+    let mut log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    log.push_back(ev.clone());
+    while log.len() > MAX_LOG_ENTRIES {
+      log.pop_front();
+    }
+  }
+  let _ = app.emit("watch:event", &ev);
+}
+
+fn load_watches() -> Vec<WatchFolder> {
+  crate::config::load_settings_json()
+    .get("watch_folders")
+    .cloned()
+    .and_then(|v| serde_json::from_value::<Vec<WatchFolder>>(v).ok())
+    .unwrap_or_default()
+}
+
+fn wants(w: &WatchFolder, path: &Path) -> bool {
+  let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+  if name.ends_with(OUTPUT_SUFFIX) || name.starts_with('.') || name.starts_with('~') {
+    return false;
+  }
+  let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+  if w.extensions.is_empty() {
+    crate::ingest::classify(path).0 != crate::ingest::FileKind::Other
+  } else {
+    w.extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+  }
+}
+
+// Files (path → size) currently in the watched folder
+fn scan(w: &WatchFolder) -> HashMap<PathBuf, u64> {
+  let mut out = HashMap::new();
+  let mut dirs = vec![PathBuf::from(&w.path)];
+  let mut seen = 0usize;
+  while let Some(dir) = dirs.pop() {
+    let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+    for entry in entries.flatten() {
+      seen += 1;
+      if seen > MAX_SCAN_ENTRIES {
+        return out;
+      }
+      let Ok(meta) = entry.metadata() else { continue };
+      let path = entry.path();
+      if meta.is_dir() {
+        if w.recursive {
+          dirs.push(path);
+        }
+      } else if meta.is_file() && wants(w, &path) {
+        out.insert(path, meta.len());
+      }
+    }
+  }
+  out
+}
+
+async fn process(app: &tauri::AppHandle, w: &WatchFolder, path: &Path) -> Result<Option<String>, String> {
+  let has = |s: &str| w.steps.iter().any(|x| x == s);
+  let mut text = None;
+  if has("extract") {
+    let (kind, mime) = crate::ingest::classify(path);
+    text = Some(crate::ingest::extract(app, kind, path, mime).await?.trim().to_string());
+  }
+  let mut summary = None;
+  if has("summarize") {
+    let content = match &text {
+      Some(t) if !t.is_empty() => t.clone(),
+      Some(_) => return Err("No text found to summarize".into()),
+      None => std::fs::read_to_string(path).map_err(|e| format!("read failed: {e}"))?,
+    };
+    let prompt = w.prompt.clone().filter(|p| !p.trim().is_empty()).unwrap_or_else(|| DEFAULT_PROMPT.to_string());
+    let settings = crate::config::load_settings_json();
+    let model = settings
+      .get("quick_prompt_model")
+      .and_then(|x| x.as_str())
+      .map(|s| s.trim().to_string())
+      .filter(|s| !s.is_empty())
+      .unwrap_or_else(crate::config::get_model_from_settings_or_env);
+    let temp = crate::config::get_temperature_from_settings_or_env();
+    summary = Some(crate::quick_prompts::complete_quick_prompt(&model, temp, &prompt, &content).await?);
+  }
+  if !has("save_markdown") {
+    return Ok(None);
+  }
+  let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+  let mut md = format!("# {name}\n\n_Source: {}_\n", path.display());
+  if let Some(s) = &summary {
+    md.push_str(&format!("\n## Summary\n\n{}\n", s.trim()));
+  }
+  if let Some(t) = text.as_ref().filter(|t| !t.is_empty()) {
+    md.push_str(&format!("\n## Extracted text\n\n```\n{t}\n```\n"));
+  }
+  let out = PathBuf::from(format!("{}{OUTPUT_SUFFIX}", path.display()));
+  tokio::fs::write(&out, md).await.map_err(|e| format!("write markdown failed: {e}"))?;
+  Ok(Some(out.to_string_lossy().to_string()))
+}
+
+fn run(app: &tauri::AppHandle, w: WatchFolder, path: PathBuf) {
+  log_event(app, &w.id, &path, "detected", None, None);
+  let label = format!("Watch: {}", path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default());
+  let app2 = app.clone();
+  crate::jobs::spawn(app, "watch", label, None, move |_job| async move {
+    match process(&app2, &w, &path).await {
+      Ok(output) => {
+        log_event(&app2, &w.id, &path, "done", None, output.clone());
+        Ok(output)
+      }
+      Err(e) => {
+        tracing::warn!(watch = %w.id, file = %path.display(), error = %e, "watch pipeline failed");
+        log_event(&app2, &w.id, &path, "error", Some(e.clone()), None);
+        Err(e)
+      }
+    }
+  });
+}
+
+struct WatchState {
+  // Path last seen at that size; a file is handled when two polls agree
+  known: HashMap<PathBuf, u64>,
+  pending: HashMap<PathBuf, u64>,
+  done: HashSet<PathBuf>,
+}
+
+/// Start the polling loop once. Watches are re-read from settings on every poll, so
+/// enabling, disabling or editing a watch takes effect within one interval.
+pub fn start(app: tauri::AppHandle) {
+  if STARTED.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  std::thread::spawn(move || {
+    let mut states: HashMap<String, WatchState> = HashMap::new();
+    loop {
+      std::thread::sleep(POLL_INTERVAL);
+      let watches: Vec<WatchFolder> = load_watches().into_iter().filter(|w| w.enabled && !w.id.is_empty() && !w.path.trim().is_empty()).collect();
+      // Forget watches that were removed or disabled; re-enabling starts from a fresh baseline
+      states.retain(|id, _| watches.iter().any(|w| &w.id == id));
+      for w in watches {
+        let files = scan(&w);
+        let Some(state) = states.get_mut(&w.id) else {
+          states.insert(w.id.clone(), WatchState { known: files, pending: HashMap::new(), done: HashSet::new() });
+          continue;
+        };
+        for (path, size) in files.iter() {
+          if state.done.contains(path) || state.known.get(path) == Some(size) {
+            continue;
+          }
+          if state.pending.get(path) == Some(size) {
+            // Stable across two polls: the file has been fully written
+            state.pending.remove(path);
+            state.done.insert(path.clone());
+            state.known.insert(path.clone(), *size);
+            run(&app, w.clone(), path.clone());
+          } else {
+            state.pending.insert(path.clone(), *size);
+          }
+        }
+        state.done.retain(|p| files.contains_key(p));
+        state.known.retain(|p, _| files.contains_key(p));
+      }
+    }
+  });
+}
+
+#[tauri::command]
+pub fn get_watch_folders() -> Result<Vec<WatchFolder>, String> {
+  Ok(load_watches())
+}
+
+/// Validate and store the watch folders (replaces the `watch_folders` setting).
+#[tauri::command]
+pub async fn save_watch_folders(list: Vec<WatchFolder>) -> Result<Vec<WatchFolder>, String> {
+  let mut list = list;
+  let mut ids = HashSet::new();
+  for w in list.iter_mut() {
+    if w.id.trim().is_empty() {
+      w.id = uuid::Uuid::new_v4().to_string();
+    }
+    if !ids.insert(w.id.clone()) {
+      return Err(format!("Duplicate watch id \"{}\"", w.id));
+    }
+    if !Path::new(&w.path).is_dir() {
+      return Err(format!("Folder not found: {}", w.path));
+    }
+    if let Some(bad) = w.steps.iter().find(|s| !matches!(s.as_str(), "extract" | "summarize" | "save_markdown")) {
+      return Err(format!("Unknown step \"{bad}\" (use extract, summarize, save_markdown)"));
+    }
+    w.extensions = w.extensions.iter().map(|e| e.trim().trim_start_matches('.').to_lowercase()).filter(|e| !e.is_empty()).collect();
+  }
+  let value = serde_json::to_value(&list).map_err(|e| format!("Serialize watch folders failed: {e}"))?;
+  crate::config::save_settings(serde_json::json!({ "watch_folders": value })).await?;
+  Ok(list)
+}
+
+/// Enable or disable one watch. Returns false when the id is unknown.
+#[tauri::command]
+pub async fn set_watch_folder_enabled(id: String, enabled: bool) -> Result<bool, String> {
+  let mut list = load_watches();
+  let Some(w) = list.iter_mut().find(|w| w.id == id) else { return Ok(false) };
+  w.enabled = enabled;
+  let value = serde_json::to_value(&list).map_err(|e| format!("Serialize watch folders failed: {e}"))?;
+  crate::config::save_settings(serde_json::json!({ "watch_folders": value })).await?;
+  tracing::info!(watch = %id, enabled, "watch folder toggled");
+  Ok(true)
+}
+
+/// Recent watch events, newest last (optionally only for one watch).
+#[tauri::command]
+pub fn get_watch_log(watch_id: Option<String>, limit: Option<usize>) -> Result<Vec<WatchEvent>, String> {
+  let log = LOG.lock().map_err(|_| "lock poisoned".to_string())?;
+  let items: Vec<WatchEvent> = log.iter().filter(|e| watch_id.as_deref().map(|id| e.watch_id == id).unwrap_or(true)).cloned().collect();
+  let limit = limit.unwrap_or(MAX_LOG_ENTRIES).min(items.len());
+  Ok(items[items.len() - limit..].to_vec())
+}
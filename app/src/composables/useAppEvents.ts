@@ -1,7 +1,10 @@
 import { listen } from '@tauri-apps/api/event'
-import { convertFileSrc } from '@tauri-apps/api/core'
+import { convertFileSrc, invoke } from '@tauri-apps/api/core'
+import { ask } from '@tauri-apps/plugin-dialog'
 import { WebviewWindow } from '@tauri-apps/api/webviewWindow'
 import { nextTick } from 'vue'
+import { setPendingEdit, claimPendingEdit } from '../state/image_edit'
+import { addConversation, appendMessageTo, setCurrentConversation } from '../state/conversation'
 
 // Types for dependency injection
 export interface UseAppEventsDeps {
@@ -75,10 +78,55 @@ export function useAppEvents(deps: UseAppEventsDeps) {
           // Only show an error toast if adding the image fails
           try { showToast('Failed to attach captured image to prompt.', 'error') } catch {}
         }
+        // Overlay edit mode: prefill the edit command for the instruction
+        if (claimPendingEdit(p.path)) {
+          composerInput.value = '/edit '
+          requestAnimationFrame(() => { try { (composerRef.value as any)?.focus?.() } catch {} })
+        }
       } catch {}
     })
     unsubs.push(u3)
 
+    // Capture overlay edit mode: mask for the capture that follows
+    const uEdit = await listen<{ mask?: any[] }>('image:edit-request', (e) => {
+      const p = (e?.payload as any) || {}
+      setPendingEdit(Array.isArray(p.mask) ? p.mask : [])
+    })
+    unsubs.push(uEdit)
+
+    // Generated/edited image -> assistant image message in the current conversation
+    const uImg = await listen<{ path: string; prompt?: string }>('image:generated', (e) => {
+      const p = (e?.payload as any) || {}
+      if (!p.path) return
+      appendMessage({ role: 'assistant', type: 'image', images: [{ path: p.path, src: convertFileSrc(p.path) }] })
+    })
+    unsubs.push(uImg)
+
+    // Dropped file ingested by the backend -> image attachment, or extracted text into the composer
+    const uAtt = await listen<any>('chat:attachment-ready', async (e) => {
+      const p = (e?.payload as any) || {}
+      if (p.error && !p.text) {
+        showToast(`${p.name || 'File'}: ${p.error}`, 'error', 3500)
+        return
+      }
+      ui.activeSection = 'Prompt'
+      ui.promptSubview = 'Chat'
+      await nextTick()
+      if (p.kind === 'image' && p.path) {
+        try { (composerRef.value as any)?.addImage?.(p.path, convertFileSrc(p.path)) } catch {
+          try { showToast('Failed to attach dropped image to prompt.', 'error') } catch {}
+        }
+        return
+      }
+      if (!p.text) { showToast(`${p.name || 'File'}: no text found`, 'error', 2500); return }
+      const label = p.kind === 'audio' ? `Transcript of ${p.name}` : p.name
+      const block = `${label}${p.truncated ? ' (truncated)' : ''}:\n\`\`\`\n${p.text}\n\`\`\`\n`
+      const cur = composerInput.value || ''
+      composerInput.value = cur ? `${cur.replace(/\s*$/, '')}\n\n${block}` : block
+      requestAnimationFrame(() => { try { (composerRef.value as any)?.focus?.() } catch {} })
+    })
+    unsubs.push(uAtt)
+
     // Direct insert into Prompt composer
     const u4 = await listen<{ text: string }>('prompt:insert', (e) => {
       const p = (e?.payload as any) || {}
@@ -98,6 +146,30 @@ export function useAppEvents(deps: UseAppEventsDeps) {
     })
     unsubs.push(u5)
 
+    // Active window summary (summarize hotkey) -> new conversation with the summary as the answer
+    const uSum = await listen<any>('window:summary', (e) => {
+      const p = (e?.payload as any) || {}
+      if (typeof p.summary !== 'string') return
+      prompt.visible = false
+      newConversation()
+      setSection('Prompt')
+      const where = p.process ? ` (${p.process})` : ''
+      const note = p.truncated ? ' — content was truncated' : ''
+      appendMessage({ role: 'user', type: 'text', text: `Summarize window: ${p.title || 'Untitled'}${where}${note}` })
+      appendMessage({ role: 'assistant', type: 'text', text: p.summary })
+    })
+    unsubs.push(uSum)
+
+    // Recent conversation picked in the tray menu (the backend shows the window)
+    const uConv = await listen<{ id: string }>('conversation:open', (e) => {
+      const id = (e?.payload as any)?.id
+      if (typeof id !== 'string' || !setCurrentConversation(id)) { showToast('That conversation no longer exists.', 'error'); return }
+      prompt.visible = false
+      setSection('Prompt')
+      ui.promptSubview = 'Chat'
+    })
+    unsubs.push(uConv)
+
     // TTS open with optional autoplay
     const u6 = await listen<{ text: string; autoplay?: boolean }>('tts:open', (e) => {
       const p = (e?.payload as any) || {}
@@ -128,6 +200,62 @@ export function useAppEvents(deps: UseAppEventsDeps) {
     })
     unsubs.push(uCommandErr)
 
+    const uHotstringErr = await listen<{ trigger: string; message: string }>('hotstring:error', (e) => {
+      const p = (e?.payload as any) || {}
+      showToast(`Hotstring ${p.trigger || ''} failed: ${p.message || 'Unknown error'}`, 'error')
+    })
+    unsubs.push(uHotstringErr)
+
+    const uGuard = await listen<{ source: string; mode: string; rules: string[] }>('guard:detected', (e) => {
+      const p = (e?.payload as any) || {}
+      const action = p.mode === 'strip' ? 'removed from' : 'flagged in'
+      showToast(`Possible prompt injection ${action} ${p.source || 'tool output'}`, 'error', 4000)
+    })
+    unsubs.push(uGuard)
+
+    // Code runner: the assistant wants to run a snippet; nothing runs until the user approves
+    const uCode = await listen<{ id: string; language: string; code: string; network: boolean; network_isolated: boolean; timeout_secs: number; memory_mb: number }>('code:approval', async (e) => {
+      const p = (e?.payload as any) || {}
+      if (!p.id) return
+      const net = p.network ? 'Network allowed' : (p.network_isolated ? 'No network' : 'Network not blocked by the OS')
+      const limits = `${net}, ${p.timeout_secs}s, ${p.memory_mb} MB`
+      let approved = false
+      try {
+        approved = await ask(`The assistant wants to run this ${p.language} code (${limits}):\n\n${p.code}`, { title: 'Run code?', kind: 'warning', okLabel: 'Run', cancelLabel: 'Deny' })
+      } catch {}
+      try { await invoke('code_exec_respond', { id: p.id, approved }) } catch {}
+    })
+    unsubs.push(uCode)
+
+    // Scheduled prompt results: saved as a background conversation or shown as a notification
+    const uSched = await listen<any>('schedule:result', (e) => {
+      const p = (e?.payload as any) || {}
+      if (typeof p.text !== 'string') return
+      const name = p.name || 'Scheduled prompt'
+      if (p.deliver === 'conversation') {
+        const c = addConversation()
+        appendMessageTo(c.id, { role: 'user', type: 'text', text: `[${name}] ${p.prompt || ''}`.trim() })
+        appendMessageTo(c.id, { role: 'assistant', type: 'text', text: p.text })
+        showToast(`${name}: result saved to History`, 'success', 3000)
+        return
+      }
+      try {
+        if (typeof Notification !== 'undefined' && Notification.permission === 'granted') {
+          new Notification(name, { body: p.text.slice(0, 300) })
+          return
+        }
+        if (typeof Notification !== 'undefined' && Notification.permission !== 'denied') Notification.requestPermission().catch(() => {})
+      } catch {}
+      showToast(`${name}: ${p.text.slice(0, 300)}`, 'success', 8000)
+    })
+    unsubs.push(uSched)
+
+    const uSchedErr = await listen<{ name: string; message: string }>('schedule:error', (e) => {
+      const p = (e?.payload as any) || {}
+      showToast(`${p.name || 'Scheduled prompt'} failed: ${p.message || 'Unknown error'}`, 'error')
+    })
+    unsubs.push(uSchedErr)
+
     // MCP connection lifecycle
     const u7 = await listen<{ serverId: string }>('mcp:connected', (e) => {
       const id = (e?.payload as any)?.serverId
@@ -148,6 +276,17 @@ export function useAppEvents(deps: UseAppEventsDeps) {
       showToast(`MCP error: ${p.message || 'Unknown error'}`, 'error')
     })
     unsubs.push(u9)
+    // Supervisor pings and automatic restarts (mcp.rs)
+    const uHealth = await listen<{ serverId: string; status: string; error?: string; restarts?: number; latencyMs?: number }>('mcp:health', (e) => {
+      const p: any = e?.payload || {}
+      const s = p.serverId ? findServerById(p.serverId) : null
+      if (!s) return
+      s.restarts = p.restarts || 0
+      if (p.status === 'healthy' || p.status === 'reconnected') { s.status = 'connected'; s.error = null; s.latencyMs = p.latencyMs ?? s.latencyMs }
+      else if (p.status === 'reconnecting') { s.status = 'reconnecting'; s.error = p.error || null }
+      else if (p.status === 'unhealthy') { s.error = p.error || 'Not responding' }
+    })
+    unsubs.push(uHealth)
 
     // Chat tool call lifecycle events
     const u10 = await listen<any>('chat:tool-call', (e) => {
@@ -211,6 +350,32 @@ export function useAppEvents(deps: UseAppEventsDeps) {
     })
     unsubs.push(u11)
 
+    // Provider rate limiter: tell the user when a request is waiting in the queue (first notice only)
+    const announcedTickets = new Set<number>()
+    const u12 = await listen<{ provider: string; ticket: number; label: string; position: number; queued: number }>('ratelimit:queue', (e) => {
+      const p = (e?.payload as any) || {}
+      if (!p.ticket || p.position <= 0 || announcedTickets.has(p.ticket)) return
+      announcedTickets.add(p.ticket)
+      if (announcedTickets.size > 200) announcedTickets.clear()
+      showToast(`${p.provider} busy: ${p.label} queued (position ${p.position})`, 'success', 2500)
+    })
+    unsubs.push(u12)
+
+    // Multi-key rotation: a rate-limited or exhausted API key was swapped for the next one
+    const uFailover = await listen<{ provider: string; label: string; from_key: string; to_key: string; key_index: number; key_count: number }>('provider:failover', (e) => {
+      const p = (e?.payload as any) || {}
+      showToast(`${p.provider} key ${p.from_key} hit its limit; switched to key ${p.key_index} of ${p.key_count} (${p.to_key})`, 'success', 4000)
+    })
+    unsubs.push(uFailover)
+
+    // Connectivity monitor: offline / back online notices
+    const u13 = await listen<{ online: boolean }>('connectivity:changed', (e) => {
+      const online = !!(e?.payload as any)?.online
+      if (online) showToast('Back online.', 'success', 2500)
+      else showToast('You appear to be offline. Network features will fail until the connection returns.', 'error', 5000)
+    })
+    unsubs.push(u13)
+
     return () => { for (const u of unsubs) { try { u() } catch {} } }
   }
 
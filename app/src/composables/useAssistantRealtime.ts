@@ -114,6 +114,36 @@ export function useAssistantRealtime(opts: AssistantRealtimeOptions) {
   let eventsDc: RTCDataChannel | null = null
   // Removed WebAudio routing to avoid double playback. We rely on <audio> element only.
   const handledUserItems = new Set<string>()
+  // MCP function calls: outputs are sent as they finish; response.create waits for all of them
+  // and for the calling response to end
+  let responseActive = false
+  let pendingToolCalls = 0
+  let toolFollowUp = false
+
+  function maybeRequestToolFollowUp() {
+    if (!toolFollowUp || pendingToolCalls > 0 || responseActive) return
+    toolFollowUp = false
+    try { eventsDc?.send(JSON.stringify({ type: 'response.create' })) } catch {}
+  }
+
+  async function runToolCall(ev: any) {
+    pendingToolCalls++
+    const callId = String(ev?.call_id || '')
+    const name = String(ev?.name || '')
+    try { opts.onLog?.(`[tool] ${name}`) } catch {}
+    let output = ''
+    try {
+      output = await invoke<string>('realtime_call_tool', { name, arguments: String(ev?.arguments || '{}'), callId })
+    } catch (e: any) {
+      output = JSON.stringify({ error: typeof e === 'string' ? e : (e?.message || 'tool call failed') })
+    }
+    pendingToolCalls = Math.max(0, pendingToolCalls - 1)
+    try {
+      eventsDc?.send(JSON.stringify({ type: 'conversation.item.create', item: { type: 'function_call_output', call_id: callId, output } }))
+    } catch {}
+    toolFollowUp = true
+    maybeRequestToolFollowUp()
+  }
 
   async function connect(params: ConnectParams = {}) {
     try {
@@ -176,6 +206,11 @@ export function useAssistantRealtime(opts: AssistantRealtimeOptions) {
             if (parsed?.type === 'rate_limits.updated' && Array.isArray(parsed?.rate_limits)) {
               opts.onRateLimits?.(parsed.rate_limits)
             }
+            if (parsed?.type === 'response.created') responseActive = true
+            if (parsed?.type === 'response.done') { responseActive = false; maybeRequestToolFollowUp() }
+            if (parsed?.type === 'response.function_call_arguments.done') {
+              runToolCall(parsed).catch(() => {})
+            }
             if (parsed?.type === 'session.updated') {
               try { opts.onLog?.('[session.updated] ' + JSON.stringify(parsed?.session || {})) } catch {}
             }
@@ -0,0 +1,36 @@
+import { reactive } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
+
+// Mirrors capabilities::BuildCapabilities. Defaults assume a full build until loaded.
+export type BuildCapabilities = {
+  version: string
+  os: string
+  cloud_only: boolean
+  local_stt: boolean
+  local_embeddings: boolean
+  local_tts: boolean
+  streaming_server: boolean
+  audio_formats: string[]
+}
+
+const capabilities = reactive<BuildCapabilities>({
+  version: '',
+  os: '',
+  cloud_only: false,
+  local_stt: true,
+  local_embeddings: true,
+  local_tts: true,
+  streaming_server: true,
+  audio_formats: [],
+})
+
+let loading: Promise<void> | null = null
+
+export function useCapabilities() {
+  if (!loading) {
+    loading = invoke<BuildCapabilities>('get_build_capabilities')
+      .then((c) => { Object.assign(capabilities, c) })
+      .catch(() => {})
+  }
+  return { capabilities, ready: loading }
+}
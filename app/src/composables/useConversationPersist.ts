@@ -1,7 +1,8 @@
 import { watch } from 'vue'
 import type { Ref } from 'vue'
-import conversation, { getPersistState, setPersistState } from '../state/conversation'
+import conversation, { getPersistState, setPersistState, deleteConversation, renameConversation, insertConversation } from '../state/conversation'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 
 export function useConversationPersist(settingsPersistConversations: Ref<boolean>, showToast: (msg: string, kind?: 'error'|'success', ms?: number) => void) {
   async function loadPersistedConversation() {
@@ -55,6 +56,18 @@ export function useConversationPersist(settingsPersistConversations: Ref<boolean
     stopFns.push(watch(() => conversation.currentConversation.id, () => schedulePersistSave()))
     // Persist when conversations are added/removed
     stopFns.push(watch(() => conversation.conversations.length, () => schedulePersistSave()))
+    // Threads created, renamed or deleted through the conversation_* commands (other windows, CLI)
+    const unlisten = listen<any>('conversations:changed', (e) => {
+      const p = e.payload || {}
+      if (typeof p.id !== 'string') return
+      if (p.action === 'deleted') deleteConversation(p.id)
+      else if (p.action === 'renamed') renameConversation(p.id, typeof p.conversation?.title === 'string' ? p.conversation.title : '')
+      else if (p.action === 'created' && p.conversation && typeof p.conversation === 'object') {
+        const c = p.conversation
+        insertConversation({ id: p.id, title: typeof c.title === 'string' ? c.title : undefined, messages: [], createdAt: c.createdAt, updatedAt: c.updatedAt })
+      }
+    })
+    stopFns.push(() => { unlisten.then((f) => f()).catch(() => {}) })
     return () => { try { stopFns.forEach(s => s()) } catch {} }
   }
 
@@ -0,0 +1,39 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+// Mirrors jobs::JobStatus (payload of the `job:update` event)
+export type JobState = 'running' | 'waiting' | 'done' | 'failed' | 'cancelled'
+
+export type JobStatus = {
+  id: string
+  kind: string
+  label: string
+  state: JobState
+  current: number
+  total: number | null
+  detail: string | null
+  result: unknown
+  error: string | null
+  started_at_ms: number
+  finished_at_ms: number | null
+}
+
+export function newJobId(): string {
+  return crypto.randomUUID()
+}
+
+// Subscribe to updates for a single job id; resolves to an unlisten function
+export async function listenJob(id: string, cb: (status: JobStatus) => void): Promise<() => void> {
+  return listen<JobStatus>('job:update', (e) => {
+    const p = e?.payload
+    if (p && p.id === id) cb(p)
+  })
+}
+
+export async function cancelJob(id: string): Promise<boolean> {
+  try { return await invoke<boolean>('cancel_job', { id }) } catch { return false }
+}
+
+export async function listJobs(): Promise<JobStatus[]> {
+  return invoke<JobStatus[]>('list_jobs')
+}
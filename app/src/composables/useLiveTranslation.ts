@@ -0,0 +1,76 @@
+import { ref } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+// Live speech translation run by the backend (mic → STT → translate → TTS queue).
+
+export type LiveTranslationState = 'idle' | 'listening' | 'stopped' | 'error'
+
+export interface TranslationSegment {
+  index: number
+  source: string
+  translated: string
+}
+
+export interface LiveTranslationParams {
+  targetLanguage?: string
+  sourceLanguage?: string
+  speak?: boolean
+}
+
+export function useLiveTranslation(opts: { onError?: (msg: string) => void } = {}) {
+  const sessionId = ref<string | null>(null)
+  const state = ref<LiveTranslationState>('idle')
+  const segments = ref<TranslationSegment[]>([])
+  let unsubs: Array<() => void> = []
+
+  const mine = (p: any) => !!p && p.session_id === sessionId.value
+
+  function cleanup() {
+    for (const u of unsubs) { try { u() } catch {} }
+    unsubs = []
+    sessionId.value = null
+  }
+
+  async function start(params: LiveTranslationParams = {}) {
+    if (sessionId.value) return
+    segments.value = []
+    unsubs.push(await listen<any>('translation:state', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      state.value = p.state
+      if (p.state === 'stopped') cleanup()
+      else if (p.state === 'error') { cleanup(); opts.onError?.(String(p.message || 'Live translation failed')) }
+    }))
+    unsubs.push(await listen<any>('translation:segment', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      segments.value.push({ index: Number(p.index) || 0, source: String(p.source_text || ''), translated: String(p.translated_text || '') })
+      if (segments.value.length > 100) segments.value.shift()
+    }))
+    unsubs.push(await listen<any>('translation:error', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      opts.onError?.(String(p.message || 'Translation error'))
+    }))
+    try {
+      sessionId.value = await invoke<string>('translation_session_start', {
+        targetLanguage: params.targetLanguage,
+        sourceLanguage: params.sourceLanguage,
+        speak: params.speak,
+      })
+    } catch (e: any) {
+      cleanup()
+      state.value = 'error'
+      opts.onError?.(typeof e === 'string' ? e : (e?.message || 'Live translation failed to start'))
+    }
+  }
+
+  async function stop() {
+    try { await invoke('translation_session_stop') } catch {}
+    cleanup()
+    state.value = 'idle'
+  }
+
+  return { sessionId, state, segments, start, stop }
+}
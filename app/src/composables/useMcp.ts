@@ -1,6 +1,6 @@
 import { invoke } from '@tauri-apps/api/core'
 import type { UIStyle } from './useSettings'
-import { parseArgs, normalizeEnvInput, parseJsonObject } from './utils'
+import { parseArgs, normalizeEnvInput, normalizeHeadersInput, parseJsonObject } from './utils'
 
 export function useMcp(settings: any, showToast: (msg: string, kind?: 'error' | 'success', ms?: number) => void) {
   function findServerById(id: string) {
@@ -23,6 +23,7 @@ export function useMcp(settings: any, showToast: (msg: string, kind?: 'error' |
         args,
         cwd: s.transport === 'stdio' ? (s.cwd || null) : null,
         env,
+        headers: s.transport === 'stdio' ? null : normalizeHeadersInput(typeof s.headersText === 'string' ? s.headersText : s.headers),
         transport: s.transport
       })
       // If backend says it's already connected, we won't get another mcp:connected event.
@@ -0,0 +1,100 @@
+import { ref } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+// Realtime voice session run by the backend (WebSocket + native mic/speaker audio).
+// Mirrors the callbacks of useAssistantRealtime so Assistant Mode can switch between the two.
+
+export interface NativeRealtimeOptions {
+  onConnected?: () => void
+  onDisconnected?: () => void
+  onError?: (msg: string) => void
+  onLog?: (msg: string) => void
+  onTranscript?: (t: RealtimeTranscript) => void
+}
+
+export interface RealtimeTranscript {
+  session_id: string
+  conversation_id?: string | null
+  role: 'user' | 'assistant'
+  item_id?: string | null
+  text: string
+  delta?: string | null
+  final: boolean
+}
+
+export interface NativeConnectParams {
+  model?: string
+  voice?: string
+  instructions?: string
+  enableTools?: boolean
+  /** Continue this stored conversation (history + system prompt); transcripts carry its id */
+  conversationId?: string
+}
+
+export function useNativeRealtime(opts: NativeRealtimeOptions) {
+  const sessionId = ref<string | null>(null)
+  let unsubs: Array<() => void> = []
+
+  const mine = (p: any) => !!p && p.session_id === sessionId.value
+
+  function cleanup() {
+    for (const u of unsubs) { try { u() } catch {} }
+    unsubs = []
+    sessionId.value = null
+  }
+
+  async function connect(params: NativeConnectParams) {
+    if (sessionId.value) return
+    unsubs.push(await listen<any>('realtime:state', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      if (p.state === 'closed') { cleanup(); opts.onDisconnected?.() }
+      else if (p.state === 'error') { cleanup(); opts.onError?.(String(p.message || 'Realtime session failed')) }
+      opts.onLog?.(`[state] ${p.state}${p.message ? `: ${p.message}` : ''}`)
+    }))
+    unsubs.push(await listen<RealtimeTranscript>('realtime:transcript', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      opts.onTranscript?.(p)
+      if (p.final) opts.onLog?.(`[${p.role}] ${p.text}`)
+    }))
+    unsubs.push(await listen<any>('realtime:response', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      opts.onLog?.(`[response] ${p.response_id || ''} ${p.status}`)
+    }))
+    unsubs.push(await listen<any>('realtime:barge_in', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      opts.onLog?.(`[barge-in] ${p.source} after ${p.played_ms} ms`)
+    }))
+    unsubs.push(await listen<any>('realtime:error', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      opts.onLog?.(`[error] ${p.code ? p.code + ': ' : ''}${p.message}`)
+    }))
+    try {
+      sessionId.value = await invoke<string>('realtime_start', {
+        model: params.model,
+        voice: params.voice,
+        instructions: params.instructions,
+        enableTools: params.enableTools,
+        conversationId: params.conversationId,
+      })
+      opts.onConnected?.()
+    } catch (e: any) {
+      cleanup()
+      opts.onError?.(typeof e === 'string' ? e : (e?.message || 'Realtime session failed'))
+    }
+  }
+
+  async function disconnect() {
+    const had = !!sessionId.value
+    try { await invoke('realtime_stop') } catch {}
+    cleanup()
+    if (had) opts.onDisconnected?.()
+  }
+
+  return { sessionId, connect, disconnect }
+}
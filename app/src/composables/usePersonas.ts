@@ -0,0 +1,36 @@
+import { ref } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+// Mirrors personas::Persona
+export type Persona = {
+  id: string
+  name: string
+  system_prompt: string | null
+  voice_profile: string | null
+  model: string | null
+  tool_profile: 'all' | 'builtin' | 'selected' | 'none'
+  tool_servers: string[]
+}
+
+// Shared across components; kept in sync with `persona:changed` from any window or the backend
+const activePersona = ref<Persona | null>(null)
+
+let loading: Promise<void> | null = null
+
+async function refreshActivePersona(): Promise<void> {
+  try { activePersona.value = await invoke<Persona | null>('get_active_persona') } catch {}
+}
+
+async function setActivePersona(id: string | null): Promise<void> {
+  activePersona.value = await invoke<Persona | null>('set_active_persona', { id })
+}
+
+export function usePersonas() {
+  if (!loading) {
+    loading = refreshActivePersona().then(async () => {
+      try { await listen('persona:changed', () => { refreshActivePersona() }) } catch {}
+    })
+  }
+  return { activePersona, refreshActivePersona, setActivePersona, ready: loading }
+}
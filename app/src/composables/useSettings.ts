@@ -1,8 +1,10 @@
 import { reactive } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
-import { normalizeEnvInput } from './utils'
+import { normalizeEnvInput, normalizeHeadersInput, formatHeadersText } from './utils'
 
 export type UIStyle = 'sidebar-dark' | 'sidebar-light'
+// Sent by get_settings in place of stored API keys while OS re-auth is required
+export const SECRET_PLACEHOLDER = '__stored_secret__'
 
 // Module-singleton state to ensure all components share the same settings instance
 const DEFAULT_SYSTEM_PROMPT = (
@@ -20,14 +22,54 @@ const DEFAULT_STT_POST_PROCESS_PROMPT =
 
 const settings = reactive({
   openai_api_key: '',
+  openai_api_keys_extra: '',
   openai_chat_model: 'gpt-4o-mini',
+  // OpenAI-compatible server for chat and quick prompts (empty = api.openai.com)
+  openai_base_url: '' as string,
+  // Key sent to openai_base_url only; the OpenAI keys never leave api.openai.com
+  openai_base_url_api_key: '' as string,
+  chat_provider: 'openai' as 'openai' | 'anthropic' | 'gemini',
+  anthropic_api_key: '' as string,
+  anthropic_chat_model: 'claude-sonnet-4-5' as string,
+  gemini_api_key: '' as string,
+  gemini_chat_model: 'gemini-2.5-flash' as string,
+  elevenlabs_api_key: '' as string,
   quick_prompt_model: '' as string,
   temperature: 1.0 as number,
   persist_conversations: false as boolean,
   start_in_tray: false as boolean,
+  log_level: 'info' as 'off' | 'error' | 'warn' | 'info' | 'debug' | 'trace',
+  response_cache_enabled: false as boolean,
+  response_cache_ttl_minutes: 60 as number,
+  offline_queue_enabled: false as boolean,
   hide_tool_calls_in_chat: false as boolean,
+  injection_guard: 'flag' as 'off' | 'flag' | 'strip',
+  embedding_engine: 'local' as 'local' | 'cloud',
+  require_os_auth: false as boolean,
+  memory_enabled: false as boolean,
+  draft_tools_enabled: true as boolean,
+  code_exec_enabled: false as boolean,
+  code_exec_allow_network: false as boolean,
+  code_exec_timeout_secs: 20 as number,
+  code_exec_memory_mb: 512 as number,
+  pii_filter: false as boolean,
+  browser_context_enabled: false as boolean,
+  // Per quick prompt ("1".. "9") override of pii_filter
+  pii_filter_prompts: {} as Record<string, boolean>,
+  timeouts: {} as Record<string, number>,
+  // Per selection feature: never send Ctrl+C, use the clipboard as is
+  safe_mode_defaults: {} as Record<string, boolean>,
   ui_style: 'sidebar-dark' as UIStyle,
   global_hotkey: '' as string,
+  summarize_hotkey: '' as string,
+  paste_history_hotkey: '' as string,
+  dictation_hotkey: '' as string,
+  // Shortcuts of the other backend hotkey actions (hotkeys.rs), by action
+  hotkeys: {} as Record<string, string>,
+  recent_outputs_limit: 25 as number,
+  tts_playback_hotkeys: false as boolean,
+  tts_read_from_cursor: false as boolean,
+  hotstrings_enabled: false as boolean,
   mcp_servers: [] as Array<any>,
   system_prompt: '' as string,
   quick_prompt_system_prompt: 'Give the direct response to the task.' as string,
@@ -40,12 +82,15 @@ const settings = reactive({
   stt_cloud_model: 'whisper-1' as string,
   stt_cloud_api_key: '' as string,
   stt_input_device_id: '' as string,
+  // Native (cpal) microphone name for dictation and the voice assistant; '' = system default
+  stt_input_device: '' as string,
   stt_post_process_enabled: false as boolean,
   stt_post_process_model: 'gpt-4o-mini' as string,
   stt_post_process_prompt: DEFAULT_STT_POST_PROCESS_PROMPT as string,
   // Local Whisper (STT) model config
   stt_whisper_model_preset: 'base' as string,
   stt_whisper_model_url: '' as string,
+  stt_idle_unload_minutes: 15 as number,
   command_enabled: false as boolean,
   command_active_script: '' as string,
   command_hook_timeout_secs: 120 as number,
@@ -58,7 +103,16 @@ export function useSettings() {
     const v = await invoke<any>('get_settings')
     if (v && typeof v === 'object') {
       if (typeof v.openai_api_key === 'string') settings.openai_api_key = v.openai_api_key
+      if (typeof v.openai_api_keys_extra === 'string') settings.openai_api_keys_extra = v.openai_api_keys_extra
       if (typeof v.openai_chat_model === 'string' && v.openai_chat_model.trim()) settings.openai_chat_model = v.openai_chat_model
+      if (typeof v.openai_base_url === 'string') settings.openai_base_url = v.openai_base_url
+      if (typeof v.openai_base_url_api_key === 'string') settings.openai_base_url_api_key = v.openai_base_url_api_key
+      if (v.chat_provider === 'openai' || v.chat_provider === 'anthropic' || v.chat_provider === 'gemini') settings.chat_provider = v.chat_provider
+      if (typeof v.anthropic_api_key === 'string') settings.anthropic_api_key = v.anthropic_api_key
+      if (typeof v.anthropic_chat_model === 'string' && v.anthropic_chat_model.trim()) settings.anthropic_chat_model = v.anthropic_chat_model
+      if (typeof v.gemini_api_key === 'string') settings.gemini_api_key = v.gemini_api_key
+      if (typeof v.gemini_chat_model === 'string' && v.gemini_chat_model.trim()) settings.gemini_chat_model = v.gemini_chat_model
+      if (typeof v.elevenlabs_api_key === 'string') settings.elevenlabs_api_key = v.elevenlabs_api_key
       // Optional dedicated model for quick prompts via Quick Actions; empty means fallback to global
       if (typeof (v as any).quick_prompt_model === 'string') {
         settings.quick_prompt_model = (v as any).quick_prompt_model
@@ -68,8 +122,37 @@ export function useSettings() {
       if (typeof v.temperature === 'number') settings.temperature = v.temperature
       if (typeof v.persist_conversations === 'boolean') settings.persist_conversations = v.persist_conversations
       if (typeof (v as any).start_in_tray === 'boolean') settings.start_in_tray = (v as any).start_in_tray
+      if (typeof (v as any).log_level === 'string') {
+        const ll = String((v as any).log_level).toLowerCase()
+        if (['off', 'error', 'warn', 'info', 'debug', 'trace'].includes(ll)) settings.log_level = ll as any
+      }
+      if (typeof (v as any).response_cache_enabled === 'boolean') settings.response_cache_enabled = (v as any).response_cache_enabled
+      if (typeof (v as any).response_cache_ttl_minutes === 'number') settings.response_cache_ttl_minutes = (v as any).response_cache_ttl_minutes
+      if (typeof (v as any).offline_queue_enabled === 'boolean') settings.offline_queue_enabled = (v as any).offline_queue_enabled
       if (typeof (v as any).hide_tool_calls_in_chat === 'boolean') settings.hide_tool_calls_in_chat = (v as any).hide_tool_calls_in_chat
+      if (['off', 'flag', 'strip'].includes((v as any).injection_guard)) settings.injection_guard = (v as any).injection_guard
+      if ((v as any).embedding_engine === 'local' || (v as any).embedding_engine === 'cloud') settings.embedding_engine = (v as any).embedding_engine
+      if (typeof (v as any).require_os_auth === 'boolean') settings.require_os_auth = (v as any).require_os_auth
+      if (typeof (v as any).memory_enabled === 'boolean') settings.memory_enabled = (v as any).memory_enabled
+      if (typeof (v as any).draft_tools_enabled === 'boolean') settings.draft_tools_enabled = (v as any).draft_tools_enabled
+      if (typeof (v as any).code_exec_enabled === 'boolean') settings.code_exec_enabled = (v as any).code_exec_enabled
+      if (typeof (v as any).code_exec_allow_network === 'boolean') settings.code_exec_allow_network = (v as any).code_exec_allow_network
+      if (typeof (v as any).code_exec_timeout_secs === 'number') settings.code_exec_timeout_secs = (v as any).code_exec_timeout_secs
+      if (typeof (v as any).code_exec_memory_mb === 'number') settings.code_exec_memory_mb = (v as any).code_exec_memory_mb
+      if (typeof (v as any).pii_filter === 'boolean') settings.pii_filter = (v as any).pii_filter
+      if (typeof (v as any).browser_context_enabled === 'boolean') settings.browser_context_enabled = (v as any).browser_context_enabled
+      if ((v as any).pii_filter_prompts && typeof (v as any).pii_filter_prompts === 'object') settings.pii_filter_prompts = (v as any).pii_filter_prompts
+      if ((v as any).timeouts && typeof (v as any).timeouts === 'object') settings.timeouts = (v as any).timeouts
+      if ((v as any).safe_mode_defaults && typeof (v as any).safe_mode_defaults === 'object') settings.safe_mode_defaults = (v as any).safe_mode_defaults
       if (typeof (v as any).global_hotkey === 'string') settings.global_hotkey = (v as any).global_hotkey
+      if (typeof (v as any).summarize_hotkey === 'string') settings.summarize_hotkey = (v as any).summarize_hotkey
+      if (typeof (v as any).paste_history_hotkey === 'string') settings.paste_history_hotkey = (v as any).paste_history_hotkey
+      if (typeof (v as any).dictation_hotkey === 'string') settings.dictation_hotkey = (v as any).dictation_hotkey
+      if ((v as any).hotkeys && typeof (v as any).hotkeys === 'object') settings.hotkeys = (v as any).hotkeys
+      if (typeof (v as any).recent_outputs_limit === 'number') settings.recent_outputs_limit = (v as any).recent_outputs_limit
+      if (typeof (v as any).tts_playback_hotkeys === 'boolean') settings.tts_playback_hotkeys = (v as any).tts_playback_hotkeys
+      if (typeof (v as any).tts_read_from_cursor === 'boolean') settings.tts_read_from_cursor = (v as any).tts_read_from_cursor
+      if (typeof (v as any).hotstrings_enabled === 'boolean') settings.hotstrings_enabled = (v as any).hotstrings_enabled
       {
         let ui: any = (v as any).ui_style
         if (ui === 'sidebar') ui = 'sidebar-dark'
@@ -113,13 +196,15 @@ export function useSettings() {
           const envJsonStr = Object.keys(envObj).length ? JSON.stringify(envObj, null, 0) : '{ "LOG_LEVEL": "info" }'
           const config = {
             id,
-            transport: (s.transport === 'http' || s.transport === 'sse') ? 'http' : 'stdio',
+            transport: (s.transport === 'http' || s.transport === 'sse') ? s.transport : 'stdio',
             command: String(s.command || ''),
             args: Array.isArray(s.args) ? s.args.filter((x: any) => typeof x === 'string') : [],
             argsText: Array.isArray(s.args) ? s.args.join(' ') : (typeof s.args === 'string' ? s.args : ''),
             cwd: typeof s.cwd === 'string' ? s.cwd : '',
             env: envObj,
             envJson: envJsonStr,
+            headers: normalizeHeadersInput(s?.headers),
+            headersText: formatHeadersText(normalizeHeadersInput(s?.headers)),
             auto_connect: s.auto_connect === true,
             disabled_tools: Array.isArray(s.disabled_tools) ? s.disabled_tools.filter((x: any) => typeof x === 'string') : [],
           }
@@ -133,6 +218,8 @@ export function useSettings() {
             prev.cwd = config.cwd
             prev.env = config.env
             prev.envJson = config.envJson
+            prev.headers = config.headers
+            prev.headersText = config.headersText
             prev.auto_connect = config.auto_connect
             prev.disabled_tools = config.disabled_tools
             // Ensure required runtime fields exist
@@ -191,6 +278,7 @@ export function useSettings() {
       } else {
         settings.stt_input_device_id = ''
       }
+      if (typeof (v as any).stt_input_device === 'string') settings.stt_input_device = (v as any).stt_input_device
       if (typeof (v as any).stt_post_process_enabled === 'boolean') {
         settings.stt_post_process_enabled = (v as any).stt_post_process_enabled === true
       }
@@ -209,6 +297,7 @@ export function useSettings() {
       if (typeof (v as any).stt_whisper_model_url === 'string') {
         settings.stt_whisper_model_url = (v as any).stt_whisper_model_url
       }
+      if (typeof (v as any).stt_idle_unload_minutes === 'number') settings.stt_idle_unload_minutes = (v as any).stt_idle_unload_minutes
       if (typeof (v as any).command_enabled === 'boolean') {
         settings.command_enabled = (v as any).command_enabled === true
       } else {
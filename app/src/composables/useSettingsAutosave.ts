@@ -1,13 +1,13 @@
 import { watch } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
-import { applyGlobalHotkey, checkShortcutAvailable } from '../hotkeys'
-import { parseArgs, normalizeEnvInput } from './utils'
+import { applySummarizeHotkey, applyPasteHistoryHotkey, applyTtsPlaybackHotkeys } from '../hotkeys'
+import { parseArgs, normalizeEnvInput, normalizeHeadersInput } from './utils'
 import { getPersistState } from '../state/conversation'
 
 // MCP runtime-only fields that should NOT trigger autosave
 const MCP_RUNTIME_KEYS = new Set([
   'status', 'connecting', 'error', 'tools', 'toolsOpen',
-  'selectedTool', 'toolArgsJson', 'toolArgsError', 'toolResults', 'envError',
+  'selectedTool', 'toolArgsJson', 'toolArgsError', 'toolResults', 'envError', 'restarts', 'latencyMs',
 ])
 
 /** Return a JSON-serializable snapshot of only the saveable MCP server config */
@@ -43,15 +43,6 @@ export function useSettingsAutosave(settings: any, showToast: (msg: string, kind
 
   async function save() {
     try {
-      // Validate hotkey before saving
-      if (settings.global_hotkey && settings.global_hotkey.trim()) {
-        const ok = await checkShortcutAvailable(settings.global_hotkey)
-        if (!ok) {
-          showToast('Global hotkey is unavailable or already in use. Please choose another.', 'error')
-          return
-        }
-      }
-
       // Clean MCP servers
       const cleanServers = (settings.mcp_servers || []).map((s: any) => {
         let args: string[] = []
@@ -63,11 +54,12 @@ export function useSettingsAutosave(settings: any, showToast: (msg: string, kind
         const env = normalizeEnvInput(typeof s.envJson === 'string' ? s.envJson : s.env)
         return {
           id: String(s.id || ''),
-          transport: (s.transport === 'http' || s.transport === 'sse') ? 'http' : 'stdio',
+          transport: (s.transport === 'http' || s.transport === 'sse') ? s.transport : 'stdio',
           command: String(s.command || ''),
           args,
           cwd: typeof s.cwd === 'string' ? s.cwd : '',
           env,
+          headers: normalizeHeadersInput(typeof s.headersText === 'string' ? s.headersText : s.headers),
           disabled_tools: Array.isArray(s.disabled_tools) ? s.disabled_tools.filter((x: any) => typeof x === 'string') : [],
           auto_connect: s.auto_connect === true,
         }
@@ -76,13 +68,15 @@ export function useSettingsAutosave(settings: any, showToast: (msg: string, kind
       const mapToSave: any = { ...settings, mcp_servers: cleanServers }
       // Remove UI-only helper fields if present
       if (Array.isArray(mapToSave.mcp_servers)) {
-        for (const srv of mapToSave.mcp_servers) { delete srv.argsText; delete srv.envJson }
+        for (const srv of mapToSave.mcp_servers) { delete srv.argsText; delete srv.envJson; delete srv.headersText }
       }
 
       await invoke<string>('save_settings', { map: mapToSave })
 
-      // Re-apply hotkey silently
-      try { await applyGlobalHotkey(settings.global_hotkey) } catch {}
+      // Re-apply the UI hotkeys silently (the backend re-registers its own on save)
+      try { await applySummarizeHotkey(settings.summarize_hotkey) } catch {}
+      try { await applyPasteHistoryHotkey(settings.paste_history_hotkey) } catch {}
+      try { await applyTtsPlaybackHotkeys(settings.tts_playback_hotkeys) } catch {}
 
       // Persist/clear conversation state based on toggle
       try {
@@ -1,21 +1,12 @@
 import { invoke } from '@tauri-apps/api/core'
-import { applyGlobalHotkey, checkShortcutAvailable } from '../hotkeys'
-import { parseArgs, normalizeEnvInput } from './utils'
+import { applySummarizeHotkey, applyPasteHistoryHotkey, applyTtsPlaybackHotkeys } from '../hotkeys'
+import { parseArgs, normalizeEnvInput, normalizeHeadersInput } from './utils'
 import { getPersistState } from '../state/conversation'
 
 // Manual Settings Save with success toast
 export function useSettingsSave(settings: any, showToast: (msg: string, kind?: 'error'|'success', ms?: number) => void) {
   async function saveSettingsNow() {
     try {
-      // Validate global hotkey before saving & applying
-      if (settings.global_hotkey && settings.global_hotkey.trim()) {
-        const ok = await checkShortcutAvailable(settings.global_hotkey)
-        if (!ok) {
-          showToast('Global hotkey is unavailable or already in use. Please choose another.', 'error')
-          return
-        }
-      }
-
       // Prepare clean MCP servers array for persistence (strip UI-only fields)
       const cleanServers = (settings.mcp_servers || []).map((s: any) => {
         let args: string[] = []
@@ -27,11 +18,12 @@ export function useSettingsSave(settings: any, showToast: (msg: string, kind?: '
         const env = normalizeEnvInput(typeof s.envJson === 'string' ? s.envJson : s.env)
         return {
           id: String(s.id || ''),
-          transport: (s.transport === 'http' || s.transport === 'sse') ? 'http' : 'stdio',
+          transport: (s.transport === 'http' || s.transport === 'sse') ? s.transport : 'stdio',
           command: String(s.command || ''),
           args,
           cwd: typeof s.cwd === 'string' ? s.cwd : '',
           env,
+          headers: normalizeHeadersInput(typeof s.headersText === 'string' ? s.headersText : s.headers),
           disabled_tools: Array.isArray(s.disabled_tools) ? s.disabled_tools.filter((x: any) => typeof x === 'string') : [],
           auto_connect: s.auto_connect === true,
         }
@@ -40,14 +32,26 @@ export function useSettingsSave(settings: any, showToast: (msg: string, kind?: '
       const mapToSave: any = { ...settings, mcp_servers: cleanServers }
       // Remove UI-only helper fields if present
       if (Array.isArray(mapToSave.mcp_servers)) {
-        for (const srv of mapToSave.mcp_servers) { delete srv.argsText; delete srv.envJson }
+        for (const srv of mapToSave.mcp_servers) { delete srv.argsText; delete srv.envJson; delete srv.headersText }
       }
 
       const path = await invoke<string>('save_settings', { map: mapToSave })
       showToast(`Settings saved:\n${path}`, 'success')
 
-      // Re-apply global hotkey immediately when changed
-      try { await applyGlobalHotkey(settings.global_hotkey) } catch {}
+      // Re-apply the UI hotkeys immediately when changed; the backend re-registers its own on save
+      try { await applySummarizeHotkey(settings.summarize_hotkey) } catch (e: any) {
+        showToast(`Summarize hotkey not registered: ${e?.message || e}`, 'error')
+      }
+      try { await applyPasteHistoryHotkey(settings.paste_history_hotkey) } catch (e: any) {
+        showToast(`Paste history hotkey not registered: ${e?.message || e}`, 'error')
+      }
+      try { await applyTtsPlaybackHotkeys(settings.tts_playback_hotkeys) } catch (e: any) {
+        showToast(`TTS playback hotkeys: ${e?.message || e}`, 'error')
+      }
+      try {
+        const failed = (await invoke<Array<{ label: string; error?: string }>>('hotkeys_get')).filter((b) => b.error)
+        if (failed.length) showToast(failed.map((b) => `${b.label}: ${b.error}`).join('\n'), 'error')
+      } catch {}
 
       // Persist/clear conversations immediately according to toggle for privacy
       try {
@@ -1,13 +1,16 @@
-import { reactive, ref, watch, nextTick } from 'vue'
+import { reactive, ref, watch, nextTick, onScopeDispose } from 'vue'
 import { invoke, convertFileSrc } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type { Ref } from 'vue'
+import { useCapabilities } from './useCapabilities'
 
 export interface NotifyFn { (msg: string, kind?: 'error' | 'success', ms?: number): void }
 
 export const OPENAI_TTS_MAX_INPUT_CHARS = 3500
 
 export function useTtsPlayback(notify?: NotifyFn) {
-  const engine = ref<'local' | 'openai'>('local')
+  const engine = ref<'local' | 'openai' | 'elevenlabs' | 'piper'>('local')
+  const { capabilities } = useCapabilities()
 
   const form = reactive({
     text: '' as string,
@@ -17,8 +20,12 @@ export function useTtsPlayback(notify?: NotifyFn) {
     openaiVoice: 'alloy' as string,
     openaiModel: 'gpt-4o-mini-tts' as string,
     openaiFormat: 'wav' as 'wav' | 'mp3' | 'opus',
+    localFormat: 'wav' as 'wav' | 'mp3' | 'ogg',
     openaiStreaming: false as boolean,
     openaiInstructions: '' as string,
+    elevenVoice: '' as string,
+    elevenModel: 'eleven_multilingual_v2' as string,
+    piperVoice: 'en_US-lessac-medium' as string,
   })
 
   const speaking = ref(false)
@@ -29,18 +36,112 @@ export function useTtsPlayback(notify?: NotifyFn) {
   const playerRef = ref<HTMLAudioElement | null>(null)
   let localPollHandle: ReturnType<typeof setInterval> | null = null
 
+  // Native playback (tts_playback_*): position and pause state from `tts:playback:*` events
+  const playbackPositionMs = ref(0)
+  const playbackDurationMs = ref(0)
+  const playbackPaused = ref(false)
+  let playbackId: number | null = null
+  const unlisteners: UnlistenFn[] = []
+
+  async function deleteLastTemp() {
+    const p = lastPlayTempPath.value
+    if (!p) return
+    try { await invoke<boolean>('tts_delete_temp_wav', { path: p }) } catch {}
+    if (wavPath.value === p) { wavPath.value = ''; wavSrc.value = '' }
+    lastPlayTempPath.value = ''
+  }
+
+  listen<{ id: number; duration_ms: number }>('tts:playback:start', (e) => {
+    playbackDurationMs.value = e.payload.duration_ms
+    playbackPositionMs.value = 0
+    playbackPaused.value = false
+  }).then(u => unlisteners.push(u)).catch(() => {})
+  listen<{ id: number; position_ms: number; duration_ms: number; paused: boolean }>('tts:playback:progress', (e) => {
+    if (e.payload.id !== playbackId) return
+    playbackPositionMs.value = e.payload.position_ms
+    playbackDurationMs.value = e.payload.duration_ms
+    playbackPaused.value = e.payload.paused
+  }).then(u => unlisteners.push(u)).catch(() => {})
+  listen<{ id: number; stopped: boolean; error?: string | null }>('tts:playback:end', async (e) => {
+    if (e.payload.id !== playbackId) return
+    playbackId = null
+    speaking.value = false
+    playbackPaused.value = false
+    if (e.payload.error) notify?.(`Playback failed: ${e.payload.error}`, 'error')
+    await deleteLastTemp()
+  }).then(u => unlisteners.push(u)).catch(() => {})
+  // Long texts read in parts (tts_queue.rs, e.g. read selection aloud); null when none is running
+  const queue = ref<{ id: number; state: string; index: number; total: number; text?: string } | null>(null)
+  listen<{ id: number; state: string; index: number; total: number; text?: string; error?: string }>('tts:progress', (e) => {
+    const p = e.payload
+    if (p.state === 'finished' || p.state === 'stopped' || p.state === 'error') {
+      if (!queue.value || queue.value.id === p.id) queue.value = null
+      if (p.error) notify?.(`Reading aloud failed: ${p.error}`, 'error')
+      return
+    }
+    queue.value = { id: p.id, state: p.state, index: p.index, total: p.total, text: p.text }
+  }).then(u => unlisteners.push(u)).catch(() => {})
+  invoke<typeof queue.value>('tts_queue_state').then((s) => { if (s && !queue.value) queue.value = s }).catch(() => {})
+  onScopeDispose(() => { unlisteners.forEach(u => u()) })
+
+  async function onQueueSkip(forward: boolean) {
+    try { await invoke<boolean>(forward ? 'tts_queue_next' : 'tts_queue_previous') } catch {}
+  }
+
+  async function onQueueStop() {
+    try { await invoke<boolean>('tts_queue_stop') } catch {}
+    queue.value = null
+  }
+
+  async function onPauseResume() {
+    if (playbackId === null) return
+    try { await invoke(playbackPaused.value ? 'tts_playback_resume' : 'tts_playback_pause') } catch {}
+  }
+
+  async function onSeek(positionMs: number) {
+    if (playbackId === null) return
+    try { await invoke('tts_playback_seek', { positionMs: Math.max(0, Math.round(positionMs)) }) } catch {}
+  }
+
   // Streaming state
   const streamSessionUrl = ref('')
   let streamSessionId: string | null = null
 
+  // File synthesis with the selected non-SAPI engine; ElevenLabs returns MP3 or WAV, Piper WAV
+  function synthesizeToFile(format: 'wav' | 'mp3' | 'opus'): Promise<string> {
+    if (engine.value === 'piper') {
+      return invoke<string>('tts_piper_synthesize_file', { text: form.text, voice: form.piperVoice || null, rate: form.rate, volume: form.volume })
+    }
+    if (engine.value === 'elevenlabs') {
+      return invoke<string>('tts_elevenlabs_synthesize_file', {
+        text: form.text,
+        voice: form.elevenVoice || null,
+        model: form.elevenModel || null,
+        format: format === 'mp3' ? 'mp3' : 'wav',
+        rate: form.rate,
+        volume: form.volume,
+      })
+    }
+    return invoke<string>('tts_openai_synthesize_file', {
+      text: form.text,
+      voice: form.openaiVoice || 'alloy',
+      model: form.openaiModel || 'gpt-4o-mini-tts',
+      format,
+      rate: form.rate,
+      volume: form.volume,
+      instructions: form.openaiInstructions || null,
+    })
+  }
+
   function validateTtsInput(): boolean {
     const text = form.text.trim()
     if (!text) {
       notify?.('Enter some text to speak', 'error')
       return false
     }
-    if (engine.value === 'openai' && text.length > OPENAI_TTS_MAX_INPUT_CHARS) {
-      notify?.(`OpenAI TTS input is limited to ${OPENAI_TTS_MAX_INPUT_CHARS} characters.`, 'error')
+    // File synthesis splits long texts itself; streaming sends a single request
+    if (engine.value === 'openai' && form.openaiStreaming && capabilities.streaming_server && text.length > OPENAI_TTS_MAX_INPUT_CHARS) {
+      notify?.(`OpenAI TTS streaming is limited to ${OPENAI_TTS_MAX_INPUT_CHARS} characters. Turn off streaming to synthesize longer texts.`, 'error')
       return false
     }
     return true
@@ -62,90 +163,20 @@ export function useTtsPlayback(notify?: NotifyFn) {
           } catch { speaking.value = false; if (localPollHandle) { clearInterval(localPollHandle); localPollHandle = null } }
         }, 500)
       } else {
-        if (form.openaiStreaming) {
+        if (engine.value === 'openai' && form.openaiStreaming && capabilities.streaming_server) {
           await startProxyStreaming()
         } else {
           busy.value = true
-          const fmt = form.openaiFormat || 'wav'
-          const path = await invoke<string>('tts_openai_synthesize_file', {
-            text: form.text,
-            voice: form.openaiVoice || 'alloy',
-            model: form.openaiModel || 'gpt-4o-mini-tts',
-            format: fmt,
-            rate: form.rate,
-            volume: form.volume,
-            instructions: form.openaiInstructions || null,
-          })
+          const fmt = engine.value === 'openai' ? (form.openaiFormat || 'wav') : engine.value === 'elevenlabs' ? 'mp3' : 'wav'
+          const path = await synthesizeToFile(fmt)
           busy.value = false
           wavPath.value = path
           wavSrc.value = convertFileSrc(path)
           lastPlayTempPath.value = path
-          requestAnimationFrame(() => {
-            const a = playerRef.value
-            if (a) {
-              let fallbackTried = false
-              const tryWavFallback = async (): Promise<boolean> => {
-                if (fallbackTried) return false
-                fallbackTried = true
-                try {
-                  const fallbackPath = await invoke<string>('tts_openai_synthesize_file', {
-                    text: form.text,
-                    voice: form.openaiVoice || 'alloy',
-                    model: form.openaiModel || 'gpt-4o-mini-tts',
-                    format: 'wav',
-                    rate: form.rate,
-                    volume: form.volume,
-                    instructions: form.openaiInstructions || null,
-                  })
-                  const oldPath = lastPlayTempPath.value
-                  wavPath.value = fallbackPath
-                  wavSrc.value = convertFileSrc(fallbackPath)
-                  lastPlayTempPath.value = fallbackPath
-                  if (oldPath && oldPath !== fallbackPath) {
-                    try { await invoke<boolean>('tts_delete_temp_wav', { path: oldPath }) } catch {}
-                  }
-                  a.src = wavSrc.value
-                  a.currentTime = 0
-                  await a.play()
-                  speaking.value = true
-                  notify?.('Selected TTS format was not playable. Retried with WAV.', 'error', 3200)
-                  return true
-                } catch {
-                  return false
-                }
-              }
-
-              const factor = Math.max(0.25, Math.min(4, Math.pow(2, (form.rate || 0) / 10)))
-              a.playbackRate = factor
-              a.volume = Math.max(0, Math.min(1, (form.volume || 100) / 100))
-              a.currentTime = 0
-              a.onerror = async () => {
-                const recovered = await tryWavFallback()
-                if (!recovered) {
-                  speaking.value = false
-                  notify?.('Failed to load synthesized audio in this format.', 'error')
-                }
-              }
-              a.play().then(() => {
-                speaking.value = true
-              }).catch(async () => {
-                const recovered = await tryWavFallback()
-                if (!recovered) {
-                  speaking.value = false
-                  notify?.('Failed to play synthesized audio in this format.', 'error')
-                }
-              })
-              a.onended = async () => {
-                speaking.value = false
-                const p = lastPlayTempPath.value
-                if (p) {
-                  try { await invoke<boolean>('tts_delete_temp_wav', { path: p }) } catch {}
-                  if (wavPath.value === p) { wavPath.value = ''; wavSrc.value = '' }
-                  lastPlayTempPath.value = ''
-                }
-              }
-            }
-          })
+          // WAV output has rate and volume applied at synthesis; compressed formats get them at playback
+          const baked = path.toLowerCase().endsWith('.wav')
+          playbackId = await invoke<number>('tts_playback_play', { path, rate: baked ? null : form.rate, volume: baked ? null : form.volume })
+          speaking.value = true
         }
       }
     } catch (e: any) {
@@ -165,12 +196,11 @@ export function useTtsPlayback(notify?: NotifyFn) {
         await stopProxyStreaming()
         const a = playerRef.value
         if (a) { a.pause(); a.currentTime = 0; a.onended = null; a.onerror = null }
-        const p = lastPlayTempPath.value
-        if (p) {
-          try { await invoke<boolean>('tts_delete_temp_wav', { path: p }) } catch {}
-          if (wavPath.value === p) { wavPath.value = ''; wavSrc.value = '' }
-          lastPlayTempPath.value = ''
+        if (playbackId !== null) {
+          playbackId = null
+          await invoke<boolean>('tts_playback_stop')
         }
+        await deleteLastTemp()
       }
     } catch {}
     finally { speaking.value = false }
@@ -286,8 +316,8 @@ export function useTtsPlayback(notify?: NotifyFn) {
     try {
       busy.value = true
       const path = engine.value === 'local'
-        ? await invoke<string>('tts_synthesize_wav', { text: form.text, voice: form.voice || null, rate: form.rate, volume: form.volume })
-        : await invoke<string>('tts_openai_synthesize_file', { text: form.text, voice: (form.openaiVoice || 'alloy'), model: (form.openaiModel || 'gpt-4o-mini-tts'), format: (form.openaiFormat || 'wav'), rate: form.rate, volume: form.volume, instructions: form.openaiInstructions || null })
+        ? await invoke<string>('tts_synthesize_file', { text: form.text, voice: form.voice || null, rate: form.rate, volume: form.volume, format: form.localFormat })
+        : await synthesizeToFile(engine.value === 'openai' ? (form.openaiFormat || 'wav') : 'wav')
       busy.value = false
       wavPath.value = path
       wavSrc.value = convertFileSrc(path)
@@ -312,6 +342,14 @@ export function useTtsPlayback(notify?: NotifyFn) {
     onPlay,
     onStop,
     onSynthesize,
+    onPauseResume,
+    onSeek,
+    queue,
+    onQueueSkip,
+    onQueueStop,
+    playbackPositionMs,
+    playbackDurationMs,
+    playbackPaused,
     startProxyStreaming,
     stopProxyStreaming,
   }
@@ -0,0 +1,68 @@
+import { ref } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+
+// Hands-free voice assistant loop run by the backend (wake word → STT → chat → TTS).
+
+export type VoiceAssistantState = 'idle' | 'waiting' | 'listening' | 'thinking' | 'speaking' | 'stopped' | 'error'
+
+export interface VoiceAssistantTurn {
+  role: 'user' | 'assistant'
+  text: string
+  conversationId?: string | null
+}
+
+export function useVoiceAssistant(opts: { onError?: (msg: string) => void; onTurn?: (t: VoiceAssistantTurn) => void } = {}) {
+  const sessionId = ref<string | null>(null)
+  const state = ref<VoiceAssistantState>('idle')
+  const turns = ref<VoiceAssistantTurn[]>([])
+  let unsubs: Array<() => void> = []
+
+  const mine = (p: any) => !!p && p.session_id === sessionId.value
+
+  function cleanup() {
+    for (const u of unsubs) { try { u() } catch {} }
+    unsubs = []
+    sessionId.value = null
+  }
+
+  async function start(wakeWord?: string, conversationId?: string) {
+    if (sessionId.value) return
+    turns.value = []
+    unsubs.push(await listen<any>('assistant:state', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      state.value = p.state
+      if (p.state === 'stopped') cleanup()
+      else if (p.state === 'error') { cleanup(); opts.onError?.(String(p.message || 'Voice assistant failed')) }
+    }))
+    unsubs.push(await listen<any>('assistant:transcript', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      const turn: VoiceAssistantTurn = { role: p.role, text: String(p.text || ''), conversationId: p.conversation_id }
+      turns.value.push(turn)
+      if (turns.value.length > 40) turns.value.shift()
+      opts.onTurn?.(turn)
+    }))
+    unsubs.push(await listen<any>('assistant:error', (e) => {
+      const p = e?.payload
+      if (!mine(p)) return
+      opts.onError?.(String(p.message || 'Voice assistant error'))
+    }))
+    try {
+      sessionId.value = await invoke<string>('assistant_start', { wakeWord, conversationId })
+    } catch (e: any) {
+      cleanup()
+      state.value = 'error'
+      opts.onError?.(typeof e === 'string' ? e : (e?.message || 'Voice assistant failed to start'))
+    }
+  }
+
+  async function stop() {
+    try { await invoke('assistant_stop') } catch {}
+    cleanup()
+    state.value = 'idle'
+  }
+
+  return { sessionId, state, turns, start, stop }
+}
@@ -106,3 +106,49 @@ export function normalizeEnvInput(input: any): Record<string, string> {
   } catch {}
   return {}
 }
+
+// HTTP headers for remote MCP servers: a JSON object or "Name: value" lines
+export function normalizeHeadersInput(input: any): Record<string, string> {
+  if (typeof input === 'string') {
+    const t = input.trim()
+    if (!t) return {}
+    if (t.startsWith('{')) return normalizeEnvInput(t)
+    const out: Record<string, string> = {}
+    for (const line of t.split(/\r?\n/)) {
+      const idx = line.indexOf(':')
+      if (idx <= 0) continue
+      const k = line.slice(0, idx).trim()
+      if (k) out[k] = line.slice(idx + 1).trim()
+    }
+    return out
+  }
+  return normalizeEnvInput(input)
+}
+
+export function formatHeadersText(headers: Record<string, string>): string {
+  return Object.entries(headers).map(([k, v]) => `${k}: ${v}`).join('\n')
+}
+
+// Structured command error (AidcError on the Rust side)
+export type AidcError = {
+  code: string
+  message: string
+  retryable: boolean
+  provider: string | null
+}
+
+export function isAidcError(e: unknown): e is AidcError {
+  return !!e && typeof e === 'object' && typeof (e as any).code === 'string' && typeof (e as any).message === 'string'
+}
+
+// User-facing text for a command error, with hints for the codes the user can act on
+export function describeError(e: unknown, fallback = 'Unknown error'): string {
+  if (typeof e === 'string') return e || fallback
+  if (isAidcError(e)) {
+    if (e.code === 'missing_api_key') return `${e.message}. Add it in Settings → General.`
+    if (e.code === 'auth') return `${e.message}. Check the API key in Settings.`
+    if (e.retryable) return `${e.message} (temporary, try again)`
+    return e.message
+  }
+  return (e as any)?.message || fallback
+}
@@ -1,11 +1,26 @@
 // Hotkeys initializer for global shortcuts
-// Registers cross-platform hotkeys and emits a DOM event when triggered.
+// Registers the summarize, paste history and TTS playback hotkeys. The Quick Actions, dictation,
+// read-aloud, quick prompt and capture hotkeys are registered by the backend (hotkeys.rs,
+// hotkeys_get / hotkeys_set); main.ts handles the `hotkey:action` event they emit.
 
-import { register, unregisterAll, unregister, isRegistered } from '@tauri-apps/plugin-global-shortcut'
+import { register, unregister, isRegistered } from '@tauri-apps/plugin-global-shortcut'
 import { invoke } from '@tauri-apps/api/core'
+import { emit } from '@tauri-apps/api/event'
 
 let initialized = false
-let currentShortcut: string | null = null
+let summarizeShortcut: string | null = null
+let pasteHistoryShortcut: string | null = null
+let ttsPlaybackShortcuts: string[] = []
+
+// Fixed shortcuts for live TTS playback changes (settings.tts_playback_hotkeys)
+const TTS_PLAYBACK_HOTKEYS: Array<[string, string]> = [
+  ['Ctrl+Alt+Right', 'tts_rate_up'],
+  ['Ctrl+Alt+Left', 'tts_rate_down'],
+  ['Ctrl+Alt+Up', 'tts_volume_up'],
+  ['Ctrl+Alt+Down', 'tts_volume_down'],
+  ['Ctrl+Alt+PageDown', 'tts_queue_next'],
+  ['Ctrl+Alt+PageUp', 'tts_queue_previous'],
+]
 
 // Normalize UI modifier tokens to plugin format (maps 'Win' -> 'Super')
 export function normalizeModifier(mod: string): string {
@@ -15,130 +30,112 @@ export function normalizeModifier(mod: string): string {
   return m
 }
 
-// Quick check: attempts to register a shortcut temporarily, verifies registration and immediately unregisters it again.
-// Returns true if the shortcut can be registered by this app; false otherwise.
-export async function checkShortcutAvailable(shortcut: string): Promise<boolean> {
-  const s = (shortcut || '').trim().replace(/\bWin\b/gi, 'Super')
-  if (!s) return false
-  try {
-    // If we already own this shortcut, it's available
-    if (currentShortcut === s) return true
-    // If someone else holds it, it's NOT available
-    const already = await isRegistered(s).catch(() => false)
-    if (already) return false
-    // Temporary registration check
-    await register(s, () => {})
-    const ok = await isRegistered(s).catch(() => false)
-    await unregister(s).catch(() => {})
-    return !!ok
-  } catch {
-    try { await unregister(s) } catch {}
-    return false
-  }
-}
-
 export async function initGlobalHotkeys(): Promise<void> {
   if (initialized) return
   initialized = true
 
   console.info('[hotkeys] Initializing global shortcuts…')
 
-  // Load user-configured hotkey from persisted settings (if any). If it fails to
-  // register (typically because another app already owns it), fall through to the
-  // default candidate list instead of leaving the app with NO global hotkey.
   try {
     const v: any = await invoke('get_settings')
-    const shortcut = (v && typeof v.global_hotkey === 'string' && v.global_hotkey.trim()) ? v.global_hotkey.trim() : ''
-    if (shortcut) {
-      try {
-        await applyGlobalHotkey(shortcut)
-        return
-      } catch (err) {
-        console.warn(`[hotkeys] configured shortcut "${shortcut}" failed to register; trying defaults`, err)
+    const summarize = (v && typeof v.summarize_hotkey === 'string') ? v.summarize_hotkey.trim() : ''
+    if (summarize) {
+      try { await applySummarizeHotkey(summarize) } catch (err) {
+        console.warn(`[hotkeys] summarize shortcut "${summarize}" failed to register`, err)
       }
     }
-  } catch (e) {
-    console.warn('[hotkeys] get_settings failed, falling back to defaults', e)
-  }
-
-  // Fallback legacy behavior: try multiple candidates to avoid conflicts
-  const shortcuts = [
-    'Alt+A',
-    'Alt+Shift+A',
-    'Ctrl+Alt+A',
-    'Command+Shift+A',
-    'Command+Shift+G'
-  ]
-
-  const successes: string[] = []
-  for (const s of shortcuts) {
-    try {
-      await register(s, (event) => {
-        if (event.state === 'Pressed') {
-          console.log(`[hotkeys] ${event.shortcut} pressed`)
-          window.dispatchEvent(new CustomEvent('ai-desktop:hotkey'))
-        }
-      })
-      const ok = await isRegistered(s).catch(() => false)
-      console.info(`[hotkeys] tried ${s} -> ${ok ? 'OK' : 'NO'}`)
-      if (ok) successes.push(s)
-    } catch (err) {
-      console.warn(`[hotkeys] failed to register ${s}`, err)
+    const pasteHistory = (v && typeof v.paste_history_hotkey === 'string') ? v.paste_history_hotkey.trim() : ''
+    if (pasteHistory) {
+      try { await applyPasteHistoryHotkey(pasteHistory) } catch (err) {
+        console.warn(`[hotkeys] paste history shortcut "${pasteHistory}" failed to register`, err)
+      }
     }
-  }
-  if (successes.length === 0) {
-    console.error('[hotkeys] No global hotkeys could be registered. Another app may be using them. Try running as admin or change the hotkey in settings.')
-  } else {
-    currentShortcut = successes[0] || null
-    // Unregister all but the first successful shortcut to avoid ghost handlers
-    for (let i = 1; i < successes.length; i++) {
-      try { await unregister(successes[i]) } catch {}
+    if (v && v.tts_playback_hotkeys === true) {
+      try { await applyTtsPlaybackHotkeys(true) } catch (err) {
+        console.warn('[hotkeys] TTS playback shortcuts failed to register', err)
+      }
     }
-    console.info('[hotkeys] Registered:', currentShortcut)
+  } catch (e) {
+    console.warn('[hotkeys] get_settings failed', e)
   }
 
-  // Clean up on hot reload / window unload during dev
+  // Clean up on hot reload / window unload during dev (backend hotkeys stay registered)
   window.addEventListener('beforeunload', () => {
-    unregisterAll().catch(() => {})
+    for (const s of [summarizeShortcut, pasteHistoryShortcut, ...ttsPlaybackShortcuts]) {
+      if (s) unregister(s).catch(() => {})
+    }
   })
 }
 
-// Re-register to a specific shortcut at runtime (called after saving settings)
-export async function applyGlobalHotkey(shortcut: string | null | undefined): Promise<void> {
-  const sRaw = (typeof shortcut === 'string') ? shortcut.trim() : ''
-  const s = sRaw.replace(/\bWin\b/gi, 'Super')
-  if (!s) {
-    // Clear all existing shortcuts
-    try { await unregisterAll() } catch {}
-    currentShortcut = null
-    console.info('[hotkeys] cleared (no global hotkey set)')
-    return
+// Summarize-active-window shortcut (settings.summarize_hotkey). Pressing it summarizes the
+// focused window through UI Automation; the backend opens the main window with the result.
+export async function applySummarizeHotkey(shortcut: string | null | undefined): Promise<void> {
+  const s = ((typeof shortcut === 'string') ? shortcut.trim() : '').replace(/\bWin\b/gi, 'Super')
+  if (summarizeShortcut === (s || null)) return
+  if (summarizeShortcut) { try { await unregister(summarizeShortcut) } catch {} }
+  summarizeShortcut = null
+  if (!s) return
+  await register(s, (event) => {
+    if (event.state !== 'Pressed') return
+    console.log(`[hotkeys] ${event.shortcut} pressed (summarize window)`)
+    invoke<string>('summarize_active_window').catch((err) => {
+      const msg = typeof err === 'string' ? err : (err?.message || 'Summarize failed')
+      emit('command:error', { message: `Summarize window: ${msg}` }).catch(() => {})
+    })
+  })
+  const ok = await isRegistered(s).catch(() => false)
+  if (!ok) {
+    try { await unregister(s) } catch {}
+    throw new Error('Shortcut not registered (possibly in use by another app)')
   }
-  // Fast path: no change
-  if (currentShortcut && currentShortcut === s) return
+  summarizeShortcut = s
+  console.info(`[hotkeys] summarize -> ${s}`)
+}
 
-  // Try to register the new shortcut FIRST; only switch over if successful
-  try {
-    await register(s, (event) => {
-      if (event.state === 'Pressed') {
-        console.log(`[hotkeys] ${event.shortcut} pressed`)
-        window.dispatchEvent(new CustomEvent('ai-desktop:hotkey'))
-      }
-    })
-    const ok = await isRegistered(s).catch(() => false)
-    if (!ok) {
-      // Clean up attempted registration
-      try { await unregister(s) } catch {}
-      throw new Error('Shortcut not registered (possibly in use by another app)')
-    }
-    // Success: remove previous shortcut (if any) and commit
-    if (currentShortcut && currentShortcut !== s) {
-      try { await unregister(currentShortcut) } catch {}
+// Paste-history shortcut (settings.paste_history_hotkey). Pressing it toggles the popup listing
+// recent AI results; main.ts handles the 'ai-desktop:paste-history' event.
+export async function applyPasteHistoryHotkey(shortcut: string | null | undefined): Promise<void> {
+  const s = ((typeof shortcut === 'string') ? shortcut.trim() : '').replace(/\bWin\b/gi, 'Super')
+  if (pasteHistoryShortcut === (s || null)) return
+  if (pasteHistoryShortcut) { try { await unregister(pasteHistoryShortcut) } catch {} }
+  pasteHistoryShortcut = null
+  if (!s) return
+  if (s === summarizeShortcut) throw new Error('Paste history hotkey is already used by another hotkey')
+  await register(s, (event) => {
+    if (event.state !== 'Pressed') return
+    console.log(`[hotkeys] ${event.shortcut} pressed (paste history)`)
+    window.dispatchEvent(new CustomEvent('ai-desktop:paste-history'))
+  })
+  const ok = await isRegistered(s).catch(() => false)
+  if (!ok) {
+    try { await unregister(s) } catch {}
+    throw new Error('Shortcut not registered (possibly in use by another app)')
+  }
+  pasteHistoryShortcut = s
+  console.info(`[hotkeys] paste history -> ${s}`)
+}
+
+// TTS playback shortcuts: Ctrl+Alt+Left/Right change the speed and Ctrl+Alt+Up/Down the volume of
+// speech that is playing, without synthesizing it again. Shortcuts held by other apps are skipped.
+export async function applyTtsPlaybackHotkeys(enabled: boolean): Promise<void> {
+  if (enabled === (ttsPlaybackShortcuts.length > 0)) return
+  for (const s of ttsPlaybackShortcuts) { try { await unregister(s) } catch {} }
+  ttsPlaybackShortcuts = []
+  if (!enabled) return
+  const failed: string[] = []
+  for (const [s, command] of TTS_PLAYBACK_HOTKEYS) {
+    if (s === summarizeShortcut || s === pasteHistoryShortcut) { failed.push(s); continue }
+    try {
+      await register(s, (event) => {
+        if (event.state !== 'Pressed') return
+        invoke(command).catch(() => {})
+      })
+      if (await isRegistered(s).catch(() => false)) ttsPlaybackShortcuts.push(s)
+      else failed.push(s)
+    } catch {
+      failed.push(s)
     }
-    currentShortcut = s
-    console.info(`[hotkeys] active -> ${s}`)
-  } catch (err) {
-    console.error(`[hotkeys] failed to register configured shortcut "${s}"`, err)
-    throw err
   }
+  console.info(`[hotkeys] TTS playback -> ${ttsPlaybackShortcuts.join(', ') || 'none'}`)
+  if (failed.length) throw new Error(`Not registered (possibly in use by another app): ${failed.join(', ')}`)
 }
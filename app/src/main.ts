@@ -1,8 +1,10 @@
 import { createApp } from 'vue'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import './style.css'
 import App from './App.vue'
 import { initGlobalHotkeys } from './hotkeys'
-import { toggleQuickActionsWindow } from './popup'
+import { toggleQuickActionsWindow, togglePasteHistoryWindow, openCaptureOverlay } from './popup'
 
 // Sub-window class detection (moved from inline scripts to comply with CSP)
 const winSearch = window.location.search
@@ -13,12 +15,22 @@ if (winSearch.includes('window=capture-overlay')) document.body.classList.add('o
 const app = createApp(App)
 app.mount('#app')
 
-// Only initialize hotkeys and popup toggle in the main window (not in QuickActions or CaptureOverlay)
+// Only initialize hotkeys and popup toggle in the main window (not in QuickActions, CaptureOverlay or PasteHistory)
 const winParam = new URLSearchParams(window.location.search).get('window')
 if (!winParam) {
+  // Startup timing: the frame after the first render
+  requestAnimationFrame(() => { invoke('startup_first_paint').catch(() => {}) })
   initGlobalHotkeys().catch((err) => console.error('[hotkeys] init failed', err))
 
   window.addEventListener('ai-desktop:hotkey', () => {
     toggleQuickActionsWindow().catch((err) => console.error('[popup] toggle failed', err))
   })
+  window.addEventListener('ai-desktop:paste-history', () => {
+    togglePasteHistoryWindow().catch((err) => console.error('[popup] paste history toggle failed', err))
+  })
+  // Backend-registered hotkeys (hotkeys.rs) for actions that need a window
+  listen<{ action: string }>('hotkey:action', (ev) => {
+    if (ev.payload.action === 'quick_actions') window.dispatchEvent(new CustomEvent('ai-desktop:hotkey'))
+    else if (ev.payload.action === 'capture_region') openCaptureOverlay().catch((err) => console.error('[popup] capture overlay failed', err))
+  }).catch((err) => console.error('[hotkeys] listen failed', err))
 }
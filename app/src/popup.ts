@@ -1,6 +1,6 @@
 // Popup window management for Quick Actions
 // The window is statically defined in tauri.conf.json and created at app startup.
-// We only toggle visibility — never create/destroy.
+// We only toggle visibility — never create/destroy. The capture overlay (last) is the exception.
 
 import { WebviewWindow } from '@tauri-apps/api/webviewWindow'
 import { invoke } from '@tauri-apps/api/core'
@@ -38,3 +38,66 @@ export async function toggleQuickActionsWindow(): Promise<void> {
     toggling = false
   }
 }
+
+// Paste-history popup (also statically defined in tauri.conf.json). prepare_quick_actions records
+// the focused app so the chosen result is pasted back into it.
+let togglingHistory = false
+
+export async function togglePasteHistoryWindow(): Promise<void> {
+  if (togglingHistory) return
+  togglingHistory = true
+  try {
+    const w = await WebviewWindow.getByLabel('paste-history').catch(() => null)
+    if (!w) { console.error('[popup] paste-history window not found'); return }
+    if (await w.isVisible()) {
+      await w.hide()
+      return
+    }
+    try { await invoke('prepare_quick_actions') } catch (e) { console.warn('[popup] prepare_quick_actions failed', e) }
+    try { await invoke('position_quick_actions', { label: 'paste-history' }) } catch (e) { console.warn('[popup] position_quick_actions failed', e) }
+    await w.show()
+    await w.setFocus()
+  } catch (err) {
+    console.error('[popup] paste history toggle failed', err)
+  } finally {
+    togglingHistory = false
+  }
+}
+
+// Region capture overlay (transparent, full-screen, always on top). Unlike the popups it is created
+// on demand; a stale handle is replaced by a new window.
+export async function openCaptureOverlay(): Promise<void> {
+  const label = 'capture-overlay'
+  const base = `${window.location.origin}${window.location.pathname}`
+  const url = `${base}?window=capture-overlay`
+  let win: WebviewWindow | null = await WebviewWindow.getByLabel(label)
+
+  const createOverlay = () => {
+    console.info('[popup] creating capture overlay', url)
+    const w = new WebviewWindow(label, {
+      url,
+      center: true,
+      decorations: false,
+      transparent: true,
+      focus: true,
+      alwaysOnTop: true,
+    })
+    // Do not await these events; just log if they fire
+    try { w.once('tauri://created', () => console.info('[popup] overlay created')) } catch {}
+    try { w.once('tauri://error', (e) => console.error('[popup] overlay window error', e)) } catch {}
+    return w
+  }
+
+  if (!win) {
+    win = createOverlay()
+  }
+
+  // If focusing/showing fails (stale handle), recreate
+  let ready = false
+  try { if (win) { await win.show(); ready = true } else { ready = false } } catch { ready = false }
+  if (!ready) {
+    try { win = createOverlay(); await win.show(); ready = true } catch { ready = false }
+  }
+  try { if (win) await win.setAlwaysOnTop(true) } catch {}
+  try { if (win) await win.setFocus() } catch {}
+}
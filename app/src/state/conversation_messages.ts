@@ -36,3 +36,25 @@ export function updateMessage(id: string, patch: Partial<Message>): Message | nu
   state.currentConversation.updatedAt = Math.max(state.currentConversation.updatedAt ?? 0, Date.now())
   return next
 }
+
+/** Append to a conversation by id (e.g. voice transcripts for the conversation a session attached to) */
+export function appendMessageTo(
+  conversationId: string,
+  msg: Omit<Message, 'id' | 'createdAt'> & Partial<Pick<Message, 'id' | 'createdAt'>>
+): Message | null {
+  if (state.currentConversation.id === conversationId) return appendMessage(msg)
+  const conv = state.conversations.find(c => c.id === conversationId)
+  if (!conv) return null
+  const m: Message = {
+    id: msg.id || uid('m'),
+    createdAt: msg.createdAt || Date.now(),
+    role: msg.role,
+    type: msg.type,
+    text: msg.text,
+    images: msg.images,
+    tool: msg.tool,
+  }
+  conv.messages.push(m)
+  conv.updatedAt = Math.max(conv.updatedAt ?? 0, m.createdAt)
+  return m
+}
@@ -6,8 +6,13 @@ export function getPersistState(): PersistedState {
   return {
     conversations: state.conversations.map((c) => ({
       id: c.id,
+      title: c.title || undefined,
       createdAt: c.createdAt ?? Date.now(),
       updatedAt: c.updatedAt ?? c.createdAt ?? Date.now(),
+      backend: c.backend,
+      responseId: c.responseId,
+      responseTurns: c.responseTurns,
+      vectorStoreIds: c.vectorStoreIds ? [...c.vectorStoreIds] : undefined,
       messages: c.messages.map((m) => ({
         id: m.id,
         createdAt: m.createdAt,
@@ -129,7 +134,13 @@ export function setPersistState(v: any): boolean {
     const times = safeMessages.map((m) => m.createdAt)
     const created = typeof (c as any).createdAt === 'number' ? (c as any).createdAt : (times.length ? Math.min(...times) : Date.now())
     const updated = typeof (c as any).updatedAt === 'number' ? (c as any).updatedAt : (times.length ? Math.max(...times) : created)
-    list.push({ id: c.id, messages: safeMessages, createdAt: created, updatedAt: updated })
+    const conv: Conversation = { id: c.id, messages: safeMessages, createdAt: created, updatedAt: updated }
+    if (typeof (c as any).title === 'string' && (c as any).title.trim()) conv.title = (c as any).title.trim()
+    if ((c as any).backend === 'chat' || (c as any).backend === 'responses') conv.backend = (c as any).backend
+    if (typeof (c as any).responseId === 'string') conv.responseId = (c as any).responseId
+    if (typeof (c as any).responseTurns === 'number') conv.responseTurns = (c as any).responseTurns
+    if (Array.isArray((c as any).vectorStoreIds)) conv.vectorStoreIds = (c as any).vectorStoreIds.filter((s: any) => typeof s === 'string')
+    list.push(conv)
   }
 
   if (list.length === 0) {
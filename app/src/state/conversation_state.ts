@@ -15,12 +15,36 @@ export function newConversation() {
   state.currentConversation = c
 }
 
+// Add a conversation without switching to it (background results such as scheduled prompts)
+export function addConversation(): Conversation {
+  const c: Conversation = { id: uid('c'), messages: [], createdAt: Date.now(), updatedAt: Date.now() }
+  state.conversations.unshift(c)
+  return c
+}
+
 export function setCurrentConversation(id: string): boolean {
   const found = state.conversations.find((c) => c.id === id)
   if (found) { state.currentConversation = found; return true }
   return false
 }
 
+// Set or clear (empty title) the name shown in the history list
+export function renameConversation(id: string, title: string): boolean {
+  const found = state.conversations.find((c) => c.id === id)
+  if (!found) return false
+  const t = title.trim()
+  if (t) found.title = t
+  else delete found.title
+  return true
+}
+
+// Insert a thread created outside this window (conversation_create) unless it is already known
+export function insertConversation(c: Conversation): boolean {
+  if (state.conversations.some((x) => x.id === c.id)) return false
+  state.conversations.unshift(c)
+  return true
+}
+
 export function getConversationsSorted(): Conversation[] {
   return [...state.conversations].sort((a, b) => (b.updatedAt ?? b.createdAt ?? 0) - (a.updatedAt ?? a.createdAt ?? 0))
 }
@@ -26,11 +26,21 @@ export interface Message {
   createdAt: number
 }
 
+export type ChatBackend = 'chat' | 'responses'
+
 export interface Conversation {
   id: string
   messages: Message[]
+  // User-set name; untitled threads show the start of their first message
+  title?: string
   createdAt?: number
   updatedAt?: number
+  // Responses API mode keeps the thread server-side; responseId is the last reply to continue from
+  backend?: ChatBackend
+  responseId?: string
+  // Messages (system prompt excluded) the server holds for responseId
+  responseTurns?: number
+  vectorStoreIds?: string[]
 }
 
 export function uid(prefix: string) {
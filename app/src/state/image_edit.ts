@@ -0,0 +1,19 @@
+// Masks for captures taken in the overlay's edit mode, keyed by image path.
+// Rectangles are in image pixels; an empty list means "edit the whole image".
+
+export interface MaskRect { x: number; y: number; width: number; height: number }
+
+export const editMasks = new Map<string, MaskRect[]>()
+
+// Set by `image:edit-request` just before the capture it belongs to arrives
+let pending: MaskRect[] | null = null
+
+export function setPendingEdit(mask: MaskRect[]) { pending = mask }
+
+/** Claim the pending edit for a freshly captured image; true when the capture was an edit */
+export function claimPendingEdit(path: string): boolean {
+  if (!pending) return false
+  editMasks.set(path, pending)
+  pending = null
+  return true
+}
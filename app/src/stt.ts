@@ -82,7 +82,7 @@ function cleanup() {
 }
 
 // Transcode arbitrary audio blob to WAV 16kHz mono using WebAudio.
-// This allows us to support WebM/Opus recordings on browsers that don't expose an Opus decoder on the Rust side.
+// Cloud endpoints other than OpenAI may not accept WebM; the backend decodes WebM/Opus itself as a fallback.
 export async function transcodeToWav16kMono(blob: Blob): Promise<Uint8Array> {
   const arrayBuf = await blob.arrayBuffer()
   // Decode using a regular AudioContext first to leverage platform decoders